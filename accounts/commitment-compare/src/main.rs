@@ -0,0 +1,198 @@
+use clap::Parser;
+use common::Result;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::units::format_sol;
+use futures::future::try_join_all;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::hash;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Query the node at `processed`, `confirmed` and `finalized` commitment at
+/// the same moment and show how far apart the answers are: the slot each
+/// level has reached and, for an account, the balance and data each one
+/// reports.
+///
+/// `processed` is the node's latest view and can still be rolled back,
+/// `confirmed` has been voted on by a supermajority and is what most reads
+/// should use, and `finalized` is rooted and typically trails by ~32 slots.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Account to read at each commitment; a busy one such as a DEX pool
+    /// shows diverging balances most often. Slots alone are compared without
+    /// it.
+    account: Option<Pubkey>,
+
+    /// Number of comparisons to take
+    #[arg(short = 'n', long, default_value_t = 1)]
+    rounds: u32,
+
+    /// Seconds to wait between rounds
+    #[arg(short, long, default_value_t = 2)]
+    interval: u64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+const LEVELS: [(&str, CommitmentConfig); 3] = [
+    ("processed", CommitmentConfig::processed()),
+    ("confirmed", CommitmentConfig::confirmed()),
+    ("finalized", CommitmentConfig::finalized()),
+];
+
+/// What one commitment level reported in one round.
+#[derive(Serialize)]
+struct LevelView {
+    round: u32,
+    commitment: &'static str,
+    slot: u64,
+    /// Slots behind the furthest-ahead level in the same round, normally
+    /// `processed`
+    slot_lag: u64,
+    block_height: u64,
+    /// Slot the account read was served at
+    account_slot: Option<u64>,
+    lamports: Option<u64>,
+    /// Hash of the account data, to spot data changes that leave the
+    /// balance alone
+    data_hash: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.nonblocking_client();
+    let mut views = Vec::new();
+    for round in 1..=args.rounds {
+        if round > 1 {
+            tokio::time::sleep(Duration::from_secs(args.interval)).await;
+        }
+        // All levels are requested together so they describe the same instant.
+        let mut level_views =
+            try_join_all(LEVELS.iter().map(|&(name, commitment)| {
+                view(&connection, args.account, round, name, commitment)
+            }))
+            .await?;
+        let newest = level_views.iter().map(|view| view.slot).max().unwrap_or(0);
+        for view in &mut level_views {
+            view.slot_lag = newest.saturating_sub(view.slot);
+        }
+        views.extend(level_views);
+    }
+
+    args.output
+        .print(&views, || print_views(&views, args.account))
+}
+
+async fn view(
+    connection: &RpcClient,
+    account: Option<Pubkey>,
+    round: u32,
+    commitment_name: &'static str,
+    commitment: CommitmentConfig,
+) -> Result<LevelView> {
+    let (slot, block_height, account) = tokio::try_join!(
+        connection.get_slot_with_commitment(commitment),
+        connection.get_block_height_with_commitment(commitment),
+        async {
+            match account {
+                Some(address) => connection
+                    .get_account_with_commitment(&address, commitment)
+                    .await
+                    .map(Some),
+                None => Ok(None),
+            }
+        },
+    )?;
+    let account_slot = account.as_ref().map(|response| response.context.slot);
+    let account = account.and_then(|response| response.value);
+    Ok(LevelView {
+        round,
+        commitment: commitment_name,
+        slot,
+        slot_lag: 0,
+        block_height,
+        account_slot,
+        lamports: account.as_ref().map(|account| account.lamports),
+        data_hash: account
+            .as_ref()
+            .map(|account| hash(&account.data).to_string()),
+    })
+}
+
+fn print_views(views: &[LevelView], account: Option<Pubkey>) {
+    for round in views.chunk_by(|a, b| a.round == b.round) {
+        println!("Round {}", round[0].round);
+        println!(
+            "  {:<10} {:>12} {:>6} {:>12}  {}",
+            "commitment",
+            "slot",
+            "lag",
+            "block height",
+            if account.is_some() {
+                "balance (SOL)"
+            } else {
+                ""
+            }
+        );
+        for view in round {
+            let balance = match (account, view.lamports) {
+                (None, _) => String::new(),
+                (Some(_), Some(lamports)) => format_sol(lamports),
+                (Some(_), None) => "not found".to_string(),
+            };
+            println!(
+                "  {:<10} {:>12} {:>6} {:>12}  {balance}",
+                view.commitment, view.slot, view.slot_lag, view.block_height
+            );
+        }
+        for note in differences(round) {
+            println!("  note: {note}");
+        }
+    }
+}
+
+/// Explains where the levels disagreed within one round.
+fn differences(round: &[LevelView]) -> Vec<String> {
+    let mut notes = Vec::new();
+    for view in round.iter().filter(|view| view.slot_lag > 0) {
+        // Slots are produced roughly every 400ms.
+        notes.push(format!(
+            "{} is {} slots (~{:.1}s) behind the newest view",
+            view.commitment,
+            view.slot_lag,
+            view.slot_lag as f64 * 0.4
+        ));
+    }
+    for pair in round.windows(2) {
+        let (newer, older) = (&pair[0], &pair[1]);
+        if newer.lamports != older.lamports {
+            let (from, to) = (
+                older.lamports.map_or("none".to_string(), format_sol),
+                newer.lamports.map_or("none".to_string(), format_sol),
+            );
+            notes.push(format!(
+                "balance is {from} SOL at {} but {to} SOL at {}: a change has not reached {} yet",
+                older.commitment, newer.commitment, older.commitment
+            ));
+        } else if newer.data_hash != older.data_hash {
+            notes.push(format!(
+                "account data differs between {} and {}",
+                newer.commitment, older.commitment
+            ));
+        }
+    }
+    notes
+}
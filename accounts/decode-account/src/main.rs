@@ -0,0 +1,129 @@
+mod schema;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::output::{OutputArgs, RecordWriter};
+use common::{ExampleError, Result, batch};
+use schema::{Layout, Schema};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tracing::warn;
+
+/// Decode any account's raw data into labelled JSON using a schema file
+/// listing its fields and their types, to inspect program state without
+/// writing the structs in Rust.
+///
+/// The schema is YAML or JSON and describes either a Borsh layout or a
+/// bytemuck (zero-copy) one; see `schema.rs` for the format. For Anchor
+/// programs with an IDL, anchor-accounts decodes without writing a schema.
+/// Prints one JSON object per account, or CSV rows with the fields embedded
+/// as JSON.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// YAML or JSON file describing the account's fields
+    #[arg(short, long)]
+    schema: PathBuf,
+
+    /// Decode this base64 data instead of fetching accounts
+    #[arg(long, conflicts_with = "addresses")]
+    data: Option<String>,
+
+    /// Pretty-print each JSON object
+    #[arg(long)]
+    pretty: bool,
+
+    /// Accounts to decode
+    #[arg(required_unless_present = "data")]
+    addresses: Vec<Pubkey>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let schema = Schema::from_file(&args.schema)?;
+    let mut records = args.output.records();
+
+    if let Some(data) = &args.data {
+        let data = BASE64
+            .decode(data.trim())
+            .map_err(|err| ExampleError::InvalidArgument(format!("--data: {err}")))?;
+        let record = decode(&schema, None, None, &data)?;
+        return print_record(&mut records, &record, args.pretty);
+    }
+
+    let connection = args.rpc.client();
+    let accounts = batch::get_multiple_accounts(&connection, &args.addresses)?;
+    let mut failed = 0;
+    for (address, account) in args.addresses.iter().zip(accounts) {
+        let result = account
+            .ok_or(ExampleError::AccountNotFound(*address))
+            .and_then(|account| decode(&schema, Some(address), Some(&account.owner), &account.data))
+            .and_then(|record| print_record(&mut records, &record, args.pretty));
+        if let Err(err) = result {
+            eprintln!("{address}: {err}");
+            failed += 1;
+        }
+    }
+    if failed > 0 {
+        return Err(ExampleError::Parse(format!(
+            "{failed} of {} accounts",
+            args.addresses.len()
+        )));
+    }
+    Ok(())
+}
+
+fn decode(
+    schema: &Schema,
+    address: Option<&Pubkey>,
+    owner: Option<&Pubkey>,
+    data: &[u8],
+) -> Result<serde_json::Value> {
+    let decoded = schema.decode(data)?;
+    // A zero-copy account is exactly its struct, so a size mismatch means
+    // the schema is describing something else.
+    if schema.layout == Layout::Bytemuck && decoded.trailing > 0 {
+        warn!(
+            "{}: {} bytes after the fields the schema describes",
+            address.map_or("data".to_string(), Pubkey::to_string),
+            decoded.trailing
+        );
+    }
+    Ok(json!({
+        "address": address.map(Pubkey::to_string),
+        "owner": owner.map(Pubkey::to_string),
+        "data_len": data.len(),
+        "trailing_bytes": decoded.trailing,
+        "data": decoded.fields,
+    }))
+}
+
+/// The table format is JSON here as well, since the decoded fields have
+/// no fixed columns; --pretty only applies to it.
+fn print_record(
+    records: &mut RecordWriter,
+    record: &serde_json::Value,
+    pretty: bool,
+) -> Result<()> {
+    records.write(record, || {
+        let line = if pretty {
+            serde_json::to_string_pretty(record)
+        } else {
+            serde_json::to_string(record)
+        };
+        println!("{}", line.unwrap_or_default());
+    })
+}
@@ -0,0 +1,294 @@
+//! The `--schema` file: the layout of an account's data as a list of named,
+//! typed fields, read in order.
+//!
+//! ```yaml
+//! layout: borsh      # or bytemuck
+//! offset: 8          # bytes to skip first, e.g. an Anchor discriminator
+//! fields:
+//!   - { name: authority, type: pubkey }
+//!   - { name: amount, type: u64 }
+//!   - { name: name, type: string }
+//!   - { name: scores, type: "[u16; 4]" }
+//!   - { name: delegate, type: { option: pubkey } }
+//!   - name: config
+//!     type:
+//!       struct:
+//!         - { name: fee_bps, type: u16 }
+//!         - { name: paused, type: bool }
+//! ```
+//!
+//! Types are `bool`, `u8` to `u128`, `i8` to `i128`, `f32`, `f64`, `pubkey`,
+//! `string` and `bytes` (both prefixed with a `u32` length), fixed arrays as
+//! `"[T; N]"` or `{ array: [T, N] }`, `{ string: N }` for UTF-8 in a fixed
+//! `N` bytes padded with zeros, `{ option: T }`, `{ vec: T }` and nested
+//! `{ struct: [fields] }`.
+//!
+//! `borsh` reads options with a one-byte tag and vecs with a `u32` length.
+//! `bytemuck` is for zero-copy accounts: every field has a fixed size and
+//! sits right after the previous one, since `Pod` types have no padding,
+//! so options, vecs and length-prefixed strings are rejected. JSON is YAML
+//! too, so the file can be either.
+
+use common::borsh::Reader;
+use common::{ExampleError, Result};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Layout {
+    #[default]
+    Borsh,
+    Bytemuck,
+}
+
+#[derive(Debug)]
+pub struct Schema {
+    pub layout: Layout,
+    pub offset: usize,
+    fields: Vec<Field>,
+}
+
+#[derive(Debug)]
+struct Field {
+    name: String,
+    ty: Type,
+}
+
+#[derive(Debug)]
+enum Type {
+    Bool,
+    Unsigned(usize),
+    Signed(usize),
+    Float(usize),
+    Pubkey,
+    String,
+    Bytes,
+    FixedString(usize),
+    Array(Box<Type>, usize),
+    Option(Box<Type>),
+    Vec(Box<Type>),
+    Struct(Vec<Field>),
+}
+
+/// The file as written, before its types are parsed.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SchemaFile {
+    #[serde(default)]
+    layout: Layout,
+    #[serde(default)]
+    offset: usize,
+    fields: Vec<FieldFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FieldFile {
+    name: String,
+    #[serde(rename = "type")]
+    ty: Value,
+}
+
+/// What [`Schema::decode`] read.
+pub struct Decoded {
+    pub fields: Value,
+    /// Bytes after the last field, e.g. space allocated for growth
+    pub trailing: usize,
+}
+
+impl Schema {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let invalid = |err: String| ExampleError::Config(format!("{}: {err}", path.display()));
+        let file: SchemaFile = serde_yaml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| invalid(err.to_string()))?;
+        let schema = Self {
+            layout: file.layout,
+            offset: file.offset,
+            fields: parse_fields(file.fields).map_err(invalid)?,
+        };
+        if schema.layout == Layout::Bytemuck {
+            for field in &schema.fields {
+                if field.ty.size().is_none() {
+                    return Err(invalid(format!(
+                        "field `{}` has no fixed size, which the bytemuck layout needs",
+                        field.name
+                    )));
+                }
+            }
+        }
+        Ok(schema)
+    }
+
+    /// Bytes the fields take up, if that does not depend on the data.
+    fn size(&self) -> Option<usize> {
+        self.fields.iter().map(|field| field.ty.size()).sum()
+    }
+
+    pub fn decode(&self, data: &[u8]) -> Result<Decoded> {
+        if let Some(size) = self.size().filter(|size| data.len() < self.offset + size) {
+            return Err(ExampleError::Parse(format!(
+                "account data: {} bytes, the schema needs {}",
+                data.len(),
+                self.offset + size
+            )));
+        }
+        let body = data.get(self.offset..).ok_or_else(|| {
+            ExampleError::Parse(format!(
+                "account data: {} bytes, shorter than the offset of {}",
+                data.len(),
+                self.offset
+            ))
+        })?;
+        let mut reader = Reader::new(body);
+        let fields = read_fields(&self.fields, &mut reader)?;
+        Ok(Decoded {
+            fields,
+            trailing: reader.remaining(),
+        })
+    }
+}
+
+fn parse_fields(fields: Vec<FieldFile>) -> std::result::Result<Vec<Field>, String> {
+    fields
+        .into_iter()
+        .map(|field| {
+            let ty =
+                parse_type(&field.ty).map_err(|err| format!("field `{}`: {err}", field.name))?;
+            Ok(Field {
+                name: field.name,
+                ty,
+            })
+        })
+        .collect()
+}
+
+fn parse_type(ty: &Value) -> std::result::Result<Type, String> {
+    let unsupported = || format!("unsupported type {ty}");
+    if let Some(name) = ty.as_str() {
+        return parse_type_name(name.trim()).ok_or_else(unsupported);
+    }
+    let object = ty.as_object().filter(|object| object.len() == 1);
+    let (kind, inner) = object
+        .and_then(|object| object.iter().next())
+        .ok_or_else(unsupported)?;
+    Ok(match kind.as_str() {
+        "array" => {
+            let (element, len) = inner
+                .as_array()
+                .filter(|pair| pair.len() == 2)
+                .and_then(|pair| Some((&pair[0], pair[1].as_u64()?)))
+                .ok_or_else(unsupported)?;
+            Type::Array(Box::new(parse_type(element)?), len as usize)
+        }
+        "string" => Type::FixedString(inner.as_u64().ok_or_else(unsupported)? as usize),
+        "option" => Type::Option(Box::new(parse_type(inner)?)),
+        "vec" => Type::Vec(Box::new(parse_type(inner)?)),
+        "struct" => {
+            let fields = serde_json::from_value(inner.clone()).map_err(|err| err.to_string())?;
+            Type::Struct(parse_fields(fields)?)
+        }
+        _ => return Err(unsupported()),
+    })
+}
+
+fn parse_type_name(name: &str) -> Option<Type> {
+    // "[T; N]"
+    if let Some(inner) = name
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+    {
+        let (element, len) = inner.rsplit_once(';')?;
+        return Some(Type::Array(
+            Box::new(parse_type_name(element.trim())?),
+            len.trim().parse().ok()?,
+        ));
+    }
+    let bits = |prefix: &str| -> Option<usize> {
+        let bits: usize = name.strip_prefix(prefix)?.parse().ok()?;
+        [8, 16, 32, 64, 128].contains(&bits).then_some(bits / 8)
+    };
+    Some(match name {
+        "bool" => Type::Bool,
+        "f32" => Type::Float(4),
+        "f64" => Type::Float(8),
+        "pubkey" | "publicKey" => Type::Pubkey,
+        "string" => Type::String,
+        "bytes" => Type::Bytes,
+        _ => match (bits("u"), bits("i")) {
+            (Some(size), _) => Type::Unsigned(size),
+            (_, Some(size)) => Type::Signed(size),
+            _ => return None,
+        },
+    })
+}
+
+impl Type {
+    fn size(&self) -> Option<usize> {
+        match self {
+            Self::Bool => Some(1),
+            Self::Unsigned(size) | Self::Signed(size) | Self::Float(size) => Some(*size),
+            Self::Pubkey => Some(32),
+            Self::FixedString(len) => Some(*len),
+            Self::Array(element, len) => Some(element.size()? * len),
+            Self::Struct(fields) => fields.iter().map(|field| field.ty.size()).sum(),
+            Self::String | Self::Bytes | Self::Option(_) | Self::Vec(_) => None,
+        }
+    }
+}
+
+fn read_fields(fields: &[Field], reader: &mut Reader) -> Result<Value> {
+    let mut object = Map::new();
+    for field in fields {
+        let value = read_type(&field.ty, reader)
+            .map_err(|err| ExampleError::Parse(format!("field `{}`: {err}", field.name)))?;
+        object.insert(field.name.clone(), value);
+    }
+    Ok(Value::Object(object))
+}
+
+/// Scalars follow [`common::borsh`]; byte arrays are base64 as well.
+fn read_type(ty: &Type, reader: &mut Reader) -> Result<Value> {
+    Ok(match ty {
+        Type::Bool => reader.read_bool()?,
+        Type::Unsigned(size) => reader.read_unsigned(*size)?,
+        Type::Signed(size) => reader.read_signed(*size)?,
+        Type::Float(4) => reader.read_f32()?,
+        Type::Float(_) => reader.read_f64()?,
+        Type::Pubkey => reader.read_pubkey()?,
+        Type::String => reader.read_string()?,
+        Type::Bytes => reader.read_bytes()?,
+        Type::FixedString(len) => {
+            let bytes = reader.take(*len)?;
+            let end = bytes
+                .iter()
+                .rposition(|&byte| byte != 0)
+                .map_or(0, |i| i + 1);
+            json!(String::from_utf8_lossy(&bytes[..end]))
+        }
+        Type::Array(element, len) if matches!(**element, Type::Unsigned(1)) => {
+            reader.read_base64(*len)?
+        }
+        Type::Array(element, len) => (0..*len)
+            .map(|_| read_type(element, reader))
+            .collect::<Result<_>>()
+            .map(Value::Array)?,
+        Type::Option(inner) => {
+            if reader.take(1)?[0] == 0 {
+                Value::Null
+            } else {
+                read_type(inner, reader)?
+            }
+        }
+        Type::Vec(element) => {
+            let len = reader.read_u32()?;
+            (0..len)
+                .map(|_| read_type(element, reader))
+                .collect::<Result<_>>()
+                .map(Value::Array)?
+        }
+        Type::Struct(fields) => read_fields(fields, reader)?,
+    })
+}
@@ -0,0 +1,281 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::token::{self, TokenAccountInfo};
+use common::units::format_token_amount;
+use common::{ExampleError, Result, RetryingRpcClient};
+use serde::Serialize;
+use serde_json::{Value, json};
+use solana_account_decoder_client_types::{UiAccountEncoding, UiDataSliceConfig};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::RpcFilterType;
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::RpcKeyedAccount;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// `amount` follows the 32-byte mint and 32-byte owner in every token
+/// account, Token-2022 ones included.
+const AMOUNT_OFFSET: usize = 64;
+const AMOUNT_LEN: usize = 8;
+
+/// Fetch every token account of a mint with getProgramAccounts twice, once
+/// whole and once with a `dataSlice` of just the 8-byte amount, and compare
+/// the response sizes and latencies.
+///
+/// An indexer that only needs balances has no use for the other 157 bytes
+/// of each account, yet without a slice the node reads, base64-encodes and
+/// sends all of them; the filters still run against the full data. Sizes
+/// are those of the JSON results; latencies are the medians over --runs
+/// rounds, which alternate which fetch goes first so neither always gets a
+/// warm cache.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Mint whose token accounts are fetched
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    /// Rounds of both fetches
+    #[arg(short = 'n', long, default_value_t = 3)]
+    runs: usize,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct Comparison {
+    mint: String,
+    program: &'static str,
+    runs: usize,
+    full: FetchStats,
+    sliced: FetchStats,
+    /// Share of the full response the slice saved, in percent
+    bytes_saved: f64,
+    /// Full median latency over the sliced one
+    speedup: f64,
+    /// Both fetches found the same accounts with the same total
+    totals_match: bool,
+}
+
+#[derive(Serialize)]
+struct FetchStats {
+    /// `offset..end` of the data returned, `None` for all of it
+    data_slice: Option<String>,
+    accounts: usize,
+    response_bytes: usize,
+    bytes_per_account: f64,
+    median_ms: f64,
+    best_ms: f64,
+    /// Sum of the accounts' balances, in tokens
+    total: String,
+}
+
+/// One getProgramAccounts round trip.
+struct Fetch {
+    accounts: Vec<RpcKeyedAccount>,
+    bytes: usize,
+    elapsed: Duration,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    if args.runs == 0 {
+        return Err(ExampleError::InvalidArgument(
+            "--runs must be at least 1".to_string(),
+        ));
+    }
+    let connection = args.rpc.retrying_client();
+    let output = &args.output;
+    let mint = connection.call(|client| token::fetch_mint(client, &args.mint))?;
+    let filters = token::mint_account_filters(&mint.program_id, &args.mint);
+    let slice = UiDataSliceConfig {
+        offset: AMOUNT_OFFSET,
+        length: AMOUNT_LEN,
+    };
+
+    let mut full = Vec::new();
+    let mut sliced = Vec::new();
+    for round in 0..args.runs {
+        for with_slice in [round % 2 == 1, round % 2 == 0] {
+            let fetch = fetch(
+                &connection,
+                &mint.program_id,
+                &filters,
+                with_slice.then_some(slice),
+            )?;
+            output.status(format_args!(
+                "Round {}, {:<6} {} accounts, {} bytes in {:.0} ms",
+                round + 1,
+                if with_slice { "slice:" } else { "full:" },
+                fetch.accounts.len(),
+                fetch.bytes,
+                fetch.elapsed.as_secs_f64() * 1000.0
+            ));
+            if with_slice {
+                sliced.push(fetch);
+            } else {
+                full.push(fetch);
+            }
+        }
+    }
+
+    // The last round's accounts stand for all of them.
+    let full_total = total(
+        full.last().expect("at least one run"),
+        |address, account| Ok(TokenAccountInfo::unpack(address, account)?.account.amount),
+    )?;
+    let sliced_total = total(
+        sliced.last().expect("at least one run"),
+        |address, account| {
+            let amount: [u8; AMOUNT_LEN] = account
+                .data
+                .as_slice()
+                .try_into()
+                .map_err(|_| ExampleError::Parse(format!("amount slice of {address}")))?;
+            Ok(u64::from_le_bytes(amount))
+        },
+    )?;
+    let full_stats = stats(&full, None, full_total, mint.decimals());
+    let sliced_stats = stats(
+        &sliced,
+        Some(format!("{AMOUNT_OFFSET}..{}", AMOUNT_OFFSET + AMOUNT_LEN)),
+        sliced_total,
+        mint.decimals(),
+    );
+    let comparison = Comparison {
+        mint: args.mint.to_string(),
+        program: token::program_name(&mint.program_id),
+        runs: args.runs,
+        bytes_saved: if full_stats.response_bytes == 0 {
+            0.0
+        } else {
+            100.0 * (1.0 - sliced_stats.response_bytes as f64 / full_stats.response_bytes as f64)
+        },
+        speedup: if sliced_stats.median_ms > 0.0 {
+            full_stats.median_ms / sliced_stats.median_ms
+        } else {
+            0.0
+        },
+        totals_match: full_stats.accounts == sliced_stats.accounts
+            && full_stats.total == sliced_stats.total,
+        full: full_stats,
+        sliced: sliced_stats,
+    };
+
+    output.print(&comparison, || {
+        println!(
+            "{:<8} {:>10} {:>14} {:>10} {:>12} {:>10}",
+            "Fetch", "Accounts", "Bytes", "B/account", "Median ms", "Best ms"
+        );
+        for (name, stats) in [("full", &comparison.full), ("slice", &comparison.sliced)] {
+            println!(
+                "{:<8} {:>10} {:>14} {:>10.1} {:>12.1} {:>10.1}",
+                name,
+                stats.accounts,
+                stats.response_bytes,
+                stats.bytes_per_account,
+                stats.median_ms,
+                stats.best_ms
+            );
+        }
+        println!();
+        println!(
+            "The slice saved {:.1}% of the bytes and was {:.2}x as fast",
+            comparison.bytes_saved, comparison.speedup
+        );
+        if comparison.totals_match {
+            println!("Both found {} tokens in total", comparison.full.total);
+        } else {
+            println!(
+                "The totals differ, {} against {}: accounts changed between the fetches",
+                comparison.full.total, comparison.sliced.total
+            );
+        }
+    })?;
+    if connection.retries() > 0 {
+        output.status(format_args!(
+            "Needed {} retries, which are not counted in the latencies",
+            connection.retries()
+        ));
+    }
+    Ok(())
+}
+
+/// Sends getProgramAccounts by hand to measure the response; the client's
+/// own method decodes it straight away. Only the successful attempt is
+/// timed.
+fn fetch(
+    connection: &RetryingRpcClient,
+    program_id: &Pubkey,
+    filters: &[RpcFilterType],
+    data_slice: Option<UiDataSliceConfig>,
+) -> Result<Fetch> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(filters.to_vec()),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            data_slice,
+            commitment: Some(connection.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let params = json!([program_id.to_string(), config]);
+    let (result, elapsed) = connection.call(|client| {
+        let started = Instant::now();
+        let result: Value = client.send(RpcRequest::GetProgramAccounts, params.clone())?;
+        Ok((result, started.elapsed()))
+    })?;
+    let bytes = serde_json::to_vec(&result).map_or(0, |json| json.len());
+    let accounts = serde_json::from_value(result)
+        .map_err(|err| ExampleError::Parse(format!("getProgramAccounts result: {err}")))?;
+    Ok(Fetch {
+        accounts,
+        bytes,
+        elapsed,
+    })
+}
+
+/// Sums the amounts `amount_of` reads from each account.
+fn total(fetch: &Fetch, amount_of: impl Fn(&Pubkey, &Account) -> Result<u64>) -> Result<u64> {
+    fetch.accounts.iter().try_fold(0u64, |sum, keyed| {
+        let address: Pubkey = keyed.pubkey.parse()?;
+        let account: Account = keyed
+            .account
+            .decode()
+            .ok_or_else(|| ExampleError::Parse(format!("account data for {address}")))?;
+        Ok(sum.saturating_add(amount_of(&address, &account)?))
+    })
+}
+
+fn stats(fetches: &[Fetch], data_slice: Option<String>, total: u64, decimals: u8) -> FetchStats {
+    let mut millis: Vec<f64> = fetches
+        .iter()
+        .map(|fetch| fetch.elapsed.as_secs_f64() * 1000.0)
+        .collect();
+    millis.sort_by(f64::total_cmp);
+    let last = fetches.last().expect("at least one run");
+    FetchStats {
+        data_slice,
+        accounts: last.accounts.len(),
+        response_bytes: last.bytes,
+        bytes_per_account: if last.accounts.is_empty() {
+            0.0
+        } else {
+            last.bytes as f64 / last.accounts.len() as f64
+        },
+        median_ms: millis[millis.len() / 2],
+        best_ms: millis[0],
+        total: format_token_amount(total, decimals),
+    }
+}
@@ -0,0 +1,183 @@
+use clap::Parser;
+use common::cli::{AddressArg, RpcArgs};
+use common::output::OutputArgs;
+use common::units::format_sol;
+use common::{ExampleError, Result, RetryingRpcClient, batch};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Report the rent-exempt minimum balance for data sizes, or for existing
+/// accounts together with their balance and whether they are rent-exempt.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Data size in bytes to price (repeatable)
+    #[arg(short, long)]
+    size: Vec<usize>,
+
+    /// File with one account address per line ('#' starts a comment)
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+
+    /// Accounts, in addition to --file: addresses (base58), `.sol` domains,
+    /// keypair files or `wallet:mint`
+    accounts: Vec<AddressArg>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// One row per --size and per account; the size rows have no account.
+#[derive(Serialize)]
+struct RentRow {
+    account: Option<String>,
+    /// None for an account that does not exist
+    bytes: Option<usize>,
+    balance: Option<u64>,
+    minimum: Option<u64>,
+    status: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.retrying_client();
+    let mut keys = args
+        .accounts
+        .iter()
+        .map(|account| connection.call(|client| account.resolve(client)))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(file) = &args.file {
+        keys.extend(batch::read_keys_file(file)?);
+    }
+    if keys.is_empty() && args.size.is_empty() {
+        return Err(ExampleError::InvalidArgument(
+            "pass --size, account addresses or --file".to_string(),
+        ));
+    }
+    let mut minimums = RentCache::default();
+
+    let mut rows = Vec::with_capacity(args.size.len() + keys.len());
+    for &size in &args.size {
+        rows.push(RentRow {
+            account: None,
+            bytes: Some(size),
+            balance: None,
+            minimum: Some(minimums.get(&connection, size)?),
+            status: String::new(),
+        });
+    }
+
+    let accounts = if keys.is_empty() {
+        Vec::new()
+    } else {
+        connection.call(|client| batch::get_multiple_accounts(client, &keys))?
+    };
+    let (mut exempt, mut short) = (0, 0);
+    for (key, account) in keys.iter().zip(&accounts) {
+        let Some(account) = account else {
+            rows.push(RentRow {
+                account: Some(key.to_string()),
+                bytes: None,
+                balance: None,
+                minimum: None,
+                status: "not found".to_string(),
+            });
+            continue;
+        };
+        let minimum = minimums.get(&connection, account.data.len())?;
+        let status = if account.lamports >= minimum {
+            exempt += 1;
+            "rent-exempt".to_string()
+        } else {
+            short += 1;
+            format!("short by {} SOL", format_sol(minimum - account.lamports))
+        };
+        rows.push(RentRow {
+            account: Some(key.to_string()),
+            bytes: Some(account.data.len()),
+            balance: Some(account.lamports),
+            minimum: Some(minimum),
+            status,
+        });
+    }
+
+    args.output.print(&rows, || print_tables(&rows))?;
+    if keys.is_empty() {
+        return Ok(());
+    }
+    args.output.status(format_args!(
+        "{} accounts: {exempt} rent-exempt, {short} below the minimum, {} missing",
+        keys.len(),
+        keys.len() - exempt - short
+    ));
+    args.output.status(format_args!(
+        "Needed {} rent lookups, one per distinct size",
+        minimums.by_size.len()
+    ));
+    Ok(())
+}
+
+fn print_tables(rows: &[RentRow]) {
+    let (sizes, accounts): (Vec<&RentRow>, Vec<&RentRow>) =
+        rows.iter().partition(|row| row.account.is_none());
+    let sol = |lamports: Option<u64>| lamports.map_or_else(|| "-".to_string(), format_sol);
+    if !sizes.is_empty() {
+        println!("{:>12}  {:>20}", "Bytes", "Rent-exempt (SOL)");
+        for row in &sizes {
+            println!(
+                "{:>12}  {:>20}",
+                row.bytes.unwrap_or_default(),
+                sol(row.minimum)
+            );
+        }
+    }
+    if accounts.is_empty() {
+        return;
+    }
+    if !sizes.is_empty() {
+        println!();
+    }
+    println!(
+        "{:<44}  {:>10}  {:>16}  {:>16}  Status",
+        "Account", "Bytes", "Balance (SOL)", "Minimum (SOL)"
+    );
+    for row in accounts {
+        println!(
+            "{:<44}  {:>10}  {:>16}  {:>16}  {}",
+            row.account.as_deref().unwrap_or_default(),
+            row.bytes
+                .map_or_else(|| "-".to_string(), |bytes| bytes.to_string()),
+            sol(row.balance),
+            sol(row.minimum),
+            row.status
+        );
+    }
+}
+
+/// `getMinimumBalanceForRentExemption` answers for one size at a time, so
+/// results are cached per size; batches of similar accounts (token accounts
+/// are all 165 bytes) then need a single lookup.
+#[derive(Default)]
+struct RentCache {
+    by_size: HashMap<usize, u64>,
+}
+
+impl RentCache {
+    fn get(&mut self, connection: &RetryingRpcClient, size: usize) -> Result<u64> {
+        if let Some(&minimum) = self.by_size.get(&size) {
+            return Ok(minimum);
+        }
+        let minimum =
+            connection.call(|client| Ok(client.get_minimum_balance_for_rent_exemption(size)?))?;
+        self.by_size.insert(size, minimum);
+        Ok(minimum)
+    }
+}
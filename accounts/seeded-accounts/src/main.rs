@@ -0,0 +1,355 @@
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use common::cli::{AddressArg, KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::{MAX_SEED_LEN, Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+use solana_system_interface::instruction as system_instruction;
+use solana_system_interface::program as system_program;
+use std::process::ExitCode;
+
+/// Create and use accounts whose addresses are derived from a base key, a
+/// seed string and an owner program with `Pubkey::create_with_seed`.
+///
+/// Unlike a PDA, a seeded address is an ordinary sha256 of the three, and
+/// the account is authorised by the base key's signature rather than by a
+/// program: the wallet can recreate every auxiliary account it ever made
+/// from its own key and the seeds, without storing their keypairs. Seeded
+/// accounts owned by the system program hold SOL that only the base key can
+/// move out again, with `transfer_with_seed`.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // The base key, which also pays
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Derive the addresses for seeds and show whether the accounts exist
+    Derive {
+        /// Base key to derive from instead of the keypair's, which is then
+        /// not needed: an address (base58), a `.sol` domain or a keypair file
+        #[arg(long)]
+        base: Option<AddressArg>,
+
+        /// Seeds to derive, e.g. savings-0 savings-1
+        #[arg(short, long, required = true, num_args = 1..)]
+        seed: Vec<String>,
+
+        /// Program the accounts are assigned to
+        #[arg(long, default_value_t = system_program::ID)]
+        owner: Pubkey,
+    },
+    /// Create the seeded account with create_account_with_seed
+    Create {
+        #[command(flatten)]
+        seed: SeedArgs,
+
+        /// Bytes of data to allocate
+        #[arg(long, default_value_t = 0)]
+        space: u64,
+
+        /// Lamports to fund it with, in SOL; defaults to the rent-exempt
+        /// minimum for --space
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: Option<u64>,
+    },
+    /// Send SOL from the keypair to the seeded account, a plain transfer
+    Fund {
+        #[command(flatten)]
+        seed: SeedArgs,
+
+        /// Amount to send, in SOL
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: u64,
+    },
+    /// Move SOL out of a system-owned seeded account with
+    /// transfer_with_seed, signed by the base key
+    Withdraw {
+        #[command(flatten)]
+        seed: SeedArgs,
+
+        /// Recipient: an address (base58), a `.sol` domain or a keypair file;
+        /// defaults to the base key
+        #[arg(short, long)]
+        to: Option<AddressArg>,
+
+        /// Amount to move, in SOL
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: u64,
+    },
+}
+
+#[derive(ClapArgs)]
+struct SeedArgs {
+    /// Seed string, at most 32 bytes
+    #[arg(short, long, value_parser = parse_seed)]
+    seed: String,
+
+    /// Program the account is assigned to; part of the derivation
+    #[arg(long, default_value_t = system_program::ID)]
+    owner: Pubkey,
+}
+
+#[derive(Serialize)]
+struct SeededAccount {
+    base: String,
+    seed: String,
+    owner: String,
+    address: String,
+    exists: bool,
+    lamports: u64,
+    /// Data length of the account, when it exists
+    space: Option<usize>,
+    /// Program the account is actually assigned to, when it exists; differs
+    /// from `owner` only if the account was reassigned after creation
+    current_owner: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SeededAction {
+    action: &'static str,
+    address: String,
+    seed: String,
+    /// The other side of the transfer: the recipient of a withdraw or the
+    /// funder otherwise
+    counterparty: String,
+    lamports: u64,
+    /// Balance of the seeded account afterwards
+    balance: u64,
+    signature: String,
+}
+
+/// A transaction touching the seeded account, ready to send.
+struct Planned<'a> {
+    action: &'static str,
+    base: Keypair,
+    seed: &'a SeedArgs,
+    address: Pubkey,
+    instruction: Instruction,
+    lamports: u64,
+    counterparty: Pubkey,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+
+    let planned = match &args.command {
+        Command::Derive { base, seed, owner } => {
+            let base = match base {
+                Some(base) => base.resolve(&connection)?,
+                None => args.keypair.load()?.pubkey(),
+            };
+            let accounts = seed
+                .iter()
+                .map(|seed| lookup(&connection, &base, seed, owner))
+                .collect::<Result<Vec<_>>>()?;
+            return output.print(&accounts, || {
+                for account in &accounts {
+                    let state = match account.space {
+                        Some(space) => {
+                            format!("{} SOL, {space} bytes", format_sol(account.lamports))
+                        }
+                        None => "not created".to_string(),
+                    };
+                    println!("{:<44}  {:<32}  {state}", account.address, account.seed);
+                }
+            });
+        }
+        Command::Create {
+            seed,
+            space,
+            amount,
+        } => {
+            let base = args.keypair.load()?;
+            let address = derive(&base.pubkey(), &seed.seed, &seed.owner)?;
+            if connection
+                .get_account_with_commitment(&address, connection.commitment())?
+                .value
+                .is_some()
+            {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "{address} (seed `{}`) already exists",
+                    seed.seed
+                )));
+            }
+            let minimum = connection.get_minimum_balance_for_rent_exemption(*space as usize)?;
+            let lamports = amount.unwrap_or(minimum);
+            if lamports < minimum {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "{} bytes need at least {} SOL to be rent-exempt",
+                    space,
+                    format_sol(minimum)
+                )));
+            }
+            output.status(format_args!(
+                "Creating {address} from seed `{}` with {} SOL and {space} bytes",
+                seed.seed,
+                format_sol(lamports)
+            ));
+            // The base key signs in place of the new account's keypair,
+            // which does not exist.
+            let instruction = system_instruction::create_account_with_seed(
+                &base.pubkey(),
+                &address,
+                &base.pubkey(),
+                &seed.seed,
+                lamports,
+                *space,
+                &seed.owner,
+            );
+            Planned {
+                action: "create",
+                counterparty: base.pubkey(),
+                base,
+                seed,
+                address,
+                instruction,
+                lamports,
+            }
+        }
+        Command::Fund { seed, amount } => {
+            let base = args.keypair.load()?;
+            let address = derive(&base.pubkey(), &seed.seed, &seed.owner)?;
+            output.status(format_args!(
+                "Sending {} SOL to {address} (seed `{}`)",
+                format_sol(*amount),
+                seed.seed
+            ));
+            let instruction = system_instruction::transfer(&base.pubkey(), &address, *amount);
+            Planned {
+                action: "fund",
+                counterparty: base.pubkey(),
+                base,
+                seed,
+                address,
+                instruction,
+                lamports: *amount,
+            }
+        }
+        Command::Withdraw { seed, to, amount } => {
+            let base = args.keypair.load()?;
+            if seed.owner != system_program::ID {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "only accounts owned by the system program can be withdrawn from, not {}",
+                    seed.owner
+                )));
+            }
+            let address = derive(&base.pubkey(), &seed.seed, &seed.owner)?;
+            let to = match to {
+                Some(to) => to.resolve(&connection)?,
+                None => base.pubkey(),
+            };
+            output.status(format_args!(
+                "Moving {} SOL from {address} (seed `{}`) to {to}",
+                format_sol(*amount),
+                seed.seed
+            ));
+            // The seeded account is not a signer: the system program checks
+            // that it derives from the base, seed and owner, and that the
+            // base signed.
+            let instruction = system_instruction::transfer_with_seed(
+                &address,
+                &base.pubkey(),
+                seed.seed.clone(),
+                &seed.owner,
+                &to,
+                *amount,
+            );
+            Planned {
+                action: "withdraw",
+                base,
+                seed,
+                address,
+                instruction,
+                lamports: *amount,
+                counterparty: to,
+            }
+        }
+    };
+
+    let address = planned.address;
+    let signature = TxBuilder::new(&planned.base)
+        .add_instruction(planned.instruction)
+        .sign_and_send(&connection)?;
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+    let balance = connection.get_balance(&address)?;
+    output.status(format_args!(
+        "{address} now holds {} SOL",
+        format_sol(balance)
+    ));
+    let action = SeededAction {
+        action: planned.action,
+        address: address.to_string(),
+        seed: planned.seed.seed.clone(),
+        counterparty: planned.counterparty.to_string(),
+        lamports: planned.lamports,
+        balance,
+        signature: signature.to_string(),
+    };
+    output.print_structured(&action)
+}
+
+fn parse_seed(seed: &str) -> std::result::Result<String, String> {
+    if seed.len() > MAX_SEED_LEN {
+        return Err(format!(
+            "`{seed}` is {} bytes; seeds are at most {MAX_SEED_LEN}",
+            seed.len()
+        ));
+    }
+    Ok(seed.to_string())
+}
+
+fn derive(base: &Pubkey, seed: &str, owner: &Pubkey) -> Result<Pubkey> {
+    // Fails for seeds over 32 bytes, and for owners ending in the PDA
+    // marker, which would let a seeded address collide with a PDA.
+    Pubkey::create_with_seed(base, seed, owner)
+        .map_err(|err| ExampleError::InvalidArgument(format!("seed `{seed}`: {err}")))
+}
+
+fn lookup(
+    connection: &RpcClient,
+    base: &Pubkey,
+    seed: &str,
+    owner: &Pubkey,
+) -> Result<SeededAccount> {
+    let address = derive(base, seed, owner)?;
+    let account = connection
+        .get_account_with_commitment(&address, connection.commitment())?
+        .value;
+    Ok(SeededAccount {
+        base: base.to_string(),
+        seed: seed.to_string(),
+        owner: owner.to_string(),
+        address: address.to_string(),
+        exists: account.is_some(),
+        lamports: account.as_ref().map_or(0, |account| account.lamports),
+        space: account.as_ref().map(|account| account.data.len()),
+        current_owner: account.map(|account| account.owner.to_string()),
+    })
+}
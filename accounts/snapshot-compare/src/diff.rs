@@ -0,0 +1,246 @@
+//! What changed about an account between two states.
+
+use crate::state::State;
+use common::units::{format_change, format_sol, format_token_amount};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Serialize)]
+pub struct AccountDiff {
+    pub account: String,
+    pub change: Change,
+    pub lamports_before: u64,
+    pub lamports_after: u64,
+    /// In SOL, e.g. `-0.000005`
+    pub lamports_change: Option<String>,
+    pub token: Option<TokenDiff>,
+    /// Known only for states read live
+    pub owner_before: Option<String>,
+    pub owner_after: Option<String>,
+    pub data_len_before: Option<usize>,
+    pub data_len_after: Option<usize>,
+    /// `None` when the data on either side is not known
+    pub data_changed: Option<bool>,
+    /// Contiguous runs of changed bytes, when both sides are known
+    pub ranges: Vec<ByteRange>,
+    /// Transactions that loaded the account between the two points, when
+    /// the states were reconstructed from them
+    pub transactions: Option<usize>,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Change {
+    Created,
+    Closed,
+    Changed,
+    /// Loaded by transactions, with the same balances on both sides, but the
+    /// data before is not known
+    Written,
+    Unchanged,
+}
+
+impl Change {
+    pub fn label(self) -> &'static str {
+        match self {
+            Change::Created => "created",
+            Change::Closed => "closed",
+            Change::Changed => "changed",
+            Change::Written => "written",
+            Change::Unchanged => "unchanged",
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TokenDiff {
+    pub mint: String,
+    /// Whole tokens; `None` when the account held none of them on that side
+    pub before: Option<String>,
+    pub after: Option<String>,
+    pub change: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct ByteRange {
+    pub offset: usize,
+    /// Hex; shorter than `new` when the account grew, and the other way
+    /// round when it shrank
+    pub old: String,
+    pub new: String,
+}
+
+pub fn diff(
+    key: &Pubkey,
+    before: &State,
+    after: &State,
+    transactions: Option<usize>,
+) -> AccountDiff {
+    let (ranges, data_changed) = match (&before.account, &after.account) {
+        (Some(old), Some(new)) => {
+            let ranges = byte_ranges(&old.data, &new.data);
+            let changed = !ranges.is_empty() || old.owner != new.owner;
+            (ranges, Some(changed))
+        }
+        // One side does not exist, so has no data to compare.
+        (None, Some(_)) if before.lamports == 0 => (Vec::new(), Some(true)),
+        (Some(_), None) if after.lamports == 0 => (Vec::new(), Some(true)),
+        _ if transactions == Some(0) => (Vec::new(), Some(false)),
+        _ => (Vec::new(), None),
+    };
+
+    let token = match (&before.token, &after.token) {
+        (None, None) => None,
+        (old, new) => {
+            let mint = old
+                .as_ref()
+                .or(new.as_ref())
+                .map(|token| token.mint.clone());
+            let decimals = old
+                .as_ref()
+                .or(new.as_ref())
+                .map_or(0, |token| token.decimals);
+            let amount = |token: &Option<crate::state::TokenState>| {
+                token.as_ref().map_or(0, |token| token.amount)
+            };
+            let (old_amount, new_amount) = (amount(old), amount(new));
+            Some(TokenDiff {
+                mint: mint.unwrap_or_default(),
+                before: old
+                    .as_ref()
+                    .map(|token| format_token_amount(token.amount, decimals)),
+                after: new
+                    .as_ref()
+                    .map(|token| format_token_amount(token.amount, decimals)),
+                change: (old_amount != new_amount)
+                    .then(|| format_change(old_amount, new_amount, decimals)),
+            })
+        }
+    };
+
+    let change = match (before.lamports, after.lamports) {
+        (0, 0) => Change::Unchanged,
+        (0, _) => Change::Created,
+        (_, 0) => Change::Closed,
+        _ if before.lamports != after.lamports
+            || token.as_ref().is_some_and(|token| token.change.is_some())
+            || data_changed == Some(true) =>
+        {
+            Change::Changed
+        }
+        _ if data_changed.is_none() => Change::Written,
+        _ => Change::Unchanged,
+    };
+
+    AccountDiff {
+        account: key.to_string(),
+        change,
+        lamports_before: before.lamports,
+        lamports_after: after.lamports,
+        lamports_change: (before.lamports != after.lamports)
+            .then(|| format_change(before.lamports, after.lamports, 9)),
+        token,
+        owner_before: before
+            .account
+            .as_ref()
+            .map(|account| account.owner.to_string()),
+        owner_after: after
+            .account
+            .as_ref()
+            .map(|account| account.owner.to_string()),
+        data_len_before: before.account.as_ref().map(|account| account.data.len()),
+        data_len_after: after.account.as_ref().map(|account| account.data.len()),
+        data_changed,
+        ranges,
+        transactions,
+    }
+}
+
+/// Runs of differing bytes. Bytes past the end of the shorter buffer count
+/// as one final run.
+fn byte_ranges(old: &[u8], new: &[u8]) -> Vec<ByteRange> {
+    let common_len = old.len().min(new.len());
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for offset in 0..=common_len {
+        let differs = offset < common_len && old[offset] != new[offset];
+        match (differs, start) {
+            (true, None) => start = Some(offset),
+            (false, Some(from)) => {
+                ranges.push(ByteRange {
+                    offset: from,
+                    old: hex::encode(&old[from..offset]),
+                    new: hex::encode(&new[from..offset]),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if old.len() != new.len() {
+        ranges.push(ByteRange {
+            offset: common_len,
+            old: hex::encode(&old[common_len..]),
+            new: hex::encode(&new[common_len..]),
+        });
+    }
+    ranges
+}
+
+pub fn print_diff(diff: &AccountDiff) {
+    println!("{} {}", diff.account, diff.change.label());
+    if let Some(change) = &diff.lamports_change {
+        println!(
+            "  lamports  {} -> {} SOL ({change})",
+            format_sol(diff.lamports_before),
+            format_sol(diff.lamports_after)
+        );
+    }
+    if let Some(token) = &diff.token
+        && let Some(change) = &token.change
+    {
+        println!(
+            "  token     {} -> {} of {} ({change})",
+            token.before.as_deref().unwrap_or("0"),
+            token.after.as_deref().unwrap_or("0"),
+            token.mint
+        );
+    }
+    if let (Some(old), Some(new)) = (&diff.owner_before, &diff.owner_after)
+        && old != new
+    {
+        println!("  owner     {old} -> {new}");
+    }
+    match diff.data_changed {
+        Some(true) if !diff.ranges.is_empty() => {
+            let changed: usize = diff
+                .ranges
+                .iter()
+                .map(|range| range.old.len().max(range.new.len()) / 2)
+                .sum();
+            println!(
+                "  data      {changed} of {} bytes changed in {} range{}",
+                diff.data_len_after.unwrap_or_default(),
+                diff.ranges.len(),
+                if diff.ranges.len() == 1 { "" } else { "s" }
+            );
+            for range in &diff.ranges {
+                println!(
+                    "    @{:<6} {} -> {}",
+                    range.offset,
+                    or_empty(&range.old),
+                    or_empty(&range.new)
+                );
+            }
+        }
+        None => println!(
+            "  data      not known before; loaded by {} transactions",
+            diff.transactions.unwrap_or_default()
+        ),
+        _ => {}
+    }
+}
+
+fn or_empty(hex: &str) -> &str {
+    if hex.is_empty() { "(none)" } else { hex }
+}
@@ -0,0 +1,296 @@
+mod diff;
+mod state;
+
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use common::cli::RpcArgs;
+use common::output::{OutputArgs, RecordWriter};
+use common::{ExampleError, Result, RetryingRpcClient, batch};
+use diff::{AccountDiff, Change};
+use solana_account_decoder_client_types::UiDataSliceConfig;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use state::State;
+use std::collections::{BTreeSet, HashSet};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+/// Compare accounts, or every account a program's transactions wrote, at
+/// two points in time, and print what changed: balances, token amounts,
+/// owners and the byte ranges of the data.
+///
+/// `history` looks back: nodes only serve current state, so the balances at
+/// a past slot are reconstructed from the transactions that loaded the
+/// account since, as recorded by an archival endpoint. Data is only known
+/// where it comes from the live state, i.e. when nothing wrote the account
+/// after that slot. `live` looks forward: it snapshots the accounts now and
+/// again after --wait, the second time with `minContextSlot` so that the
+/// node cannot answer from before the first, and diffs everything.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Compare the accounts at two past slots (lamports and token balances)
+    History {
+        #[command(flatten)]
+        targets: Targets,
+
+        /// Slot the comparison starts from
+        #[arg(long)]
+        from_slot: Slot,
+
+        /// Slot the comparison ends at; defaults to the latest
+        #[arg(long)]
+        to_slot: Option<Slot>,
+
+        /// Give up on an account with more transactions than this after
+        /// --from-slot
+        #[arg(long, default_value_t = 1000)]
+        max_transactions: usize,
+    },
+    /// Snapshot the accounts now and again after --wait, and compare
+    Live {
+        #[command(flatten)]
+        targets: Targets,
+
+        /// Seconds between the two snapshots
+        #[arg(short, long, default_value_t = 30)]
+        wait: u64,
+    },
+}
+
+#[derive(ClapArgs)]
+struct Targets {
+    /// Program whose accounts to compare, instead of a list of accounts:
+    /// the ones it owns for `live`, the ones its transactions wrote for
+    /// `history`
+    #[arg(long, conflicts_with_all = ["file", "accounts"])]
+    program: Option<Pubkey>,
+
+    /// File with one account address per line ('#' starts a comment)
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+
+    /// Account addresses (base58), in addition to --file
+    accounts: Vec<Pubkey>,
+}
+
+impl Targets {
+    fn accounts(&self) -> Result<Vec<Pubkey>> {
+        let mut accounts = self.accounts.clone();
+        if let Some(file) = &self.file {
+            accounts.extend(batch::read_keys_file(file)?);
+        }
+        let mut seen = HashSet::new();
+        accounts.retain(|account| seen.insert(*account));
+        if accounts.is_empty() {
+            return Err(ExampleError::InvalidArgument(
+                "pass accounts as arguments, with --file or with --program".to_string(),
+            ));
+        }
+        Ok(accounts)
+    }
+}
+
+/// How many accounts ended up with each kind of change.
+#[derive(Default)]
+struct Tally {
+    created: usize,
+    closed: usize,
+    changed: usize,
+    written: usize,
+    unchanged: usize,
+}
+
+impl Tally {
+    /// Counts the diff, and writes it unless nothing changed.
+    fn record(&mut self, records: &mut RecordWriter, diff: &AccountDiff) -> Result<()> {
+        match diff.change {
+            Change::Created => self.created += 1,
+            Change::Closed => self.closed += 1,
+            Change::Changed => self.changed += 1,
+            Change::Written => self.written += 1,
+            Change::Unchanged => {
+                self.unchanged += 1;
+                return Ok(());
+            }
+        }
+        records.write(diff, || diff::print_diff(diff))
+    }
+
+    fn summary(&self, from: Slot, to: Slot) -> String {
+        format!(
+            "Slots {from} to {to}: {} changed, {} created, {} closed, {} written, {} unchanged",
+            self.changed, self.created, self.closed, self.written, self.unchanged
+        )
+    }
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    // Archival queries are slow and get rate limited; retry them.
+    let connection = args.rpc.retrying_client();
+    match &args.command {
+        Command::History {
+            targets,
+            from_slot,
+            to_slot,
+            max_transactions,
+        } => history(
+            &args,
+            &connection,
+            targets,
+            *from_slot,
+            *to_slot,
+            *max_transactions,
+        ),
+        Command::Live { targets, wait } => {
+            live(&args, &connection, targets, Duration::from_secs(*wait))
+        }
+    }
+}
+
+fn history(
+    args: &Args,
+    connection: &RetryingRpcClient,
+    targets: &Targets,
+    from: Slot,
+    to: Option<Slot>,
+    max_transactions: usize,
+) -> Result<()> {
+    let latest = connection.call(|client| Ok(client.get_slot()?))?;
+    let to = to.unwrap_or(latest);
+    if from >= to || to > latest {
+        return Err(ExampleError::InvalidArgument(format!(
+            "--from-slot must come before --to-slot, and both before the latest slot {latest}"
+        )));
+    }
+
+    let accounts = match &targets.program {
+        Some(program) => {
+            let signatures = state::signatures_after(connection, program, from, max_transactions)?;
+            let in_range: Vec<_> = signatures.iter().filter(|(slot, _)| *slot <= to).collect();
+            args.output.status(format_args!(
+                "Finding the accounts written by {} transactions of {program}",
+                in_range.len()
+            ));
+            let mut accounts = BTreeSet::new();
+            for (_, signature) in in_range {
+                accounts.extend(state::writable_accounts(connection, signature)?);
+            }
+            accounts.remove(program);
+            accounts.into_iter().collect()
+        }
+        None => targets.accounts()?,
+    };
+    args.output.status(format_args!(
+        "Comparing {} accounts between slots {from} and {to}",
+        accounts.len()
+    ));
+
+    // Read first: a transaction landing after this is then seen by the
+    // signature lookups below, so whatever it changed is not mistaken for
+    // the state at `to`.
+    let (_, live) = state::fetch_live(connection, &accounts, Some(to))?;
+    let mut records = args.output.records();
+    let mut tally = Tally::default();
+    for (account, live) in accounts.iter().zip(live) {
+        // Newest first, so the first transaction after a slot is the last
+        // one past it.
+        let signatures = state::signatures_after(connection, account, from, max_transactions)?;
+        let state_at = |slot: Slot| -> Result<State> {
+            match signatures.iter().rev().find(|(after, _)| *after > slot) {
+                Some((_, signature)) => state::state_before(connection, signature, account),
+                None => Ok(live.clone()),
+            }
+        };
+        let before = state_at(from)?;
+        let after = state_at(to)?;
+        let transactions = signatures.iter().filter(|(slot, _)| *slot <= to).count();
+        let diff = diff::diff(account, &before, &after, Some(transactions));
+        tally.record(&mut records, &diff)?;
+    }
+    args.output.status(tally.summary(from, to));
+    Ok(())
+}
+
+fn live(
+    args: &Args,
+    connection: &RetryingRpcClient,
+    targets: &Targets,
+    wait: Duration,
+) -> Result<()> {
+    let mut accounts = match &targets.program {
+        Some(program) => program_accounts(connection, program, None)?,
+        None => targets.accounts()?,
+    };
+    let (from, mut before) = state::fetch_live(connection, &accounts, None)?;
+    args.output.status(format_args!(
+        "Snapshot of {} accounts at slot {from}; comparing again in {wait:?}",
+        accounts.len()
+    ));
+    thread::sleep(wait);
+
+    if let Some(program) = &targets.program {
+        // Accounts the program created in the meantime did not exist before.
+        let known: HashSet<Pubkey> = accounts.iter().copied().collect();
+        for account in program_accounts(connection, program, Some(from + 1))? {
+            if !known.contains(&account) {
+                accounts.push(account);
+                before.push(State::default());
+            }
+        }
+    }
+    let (to, after) = state::fetch_live(connection, &accounts, Some(from + 1))?;
+    let mut records = args.output.records();
+    let mut tally = Tally::default();
+    for ((account, before), after) in accounts.iter().zip(&before).zip(&after) {
+        let diff = diff::diff(account, before, after, None);
+        tally.record(&mut records, &diff)?;
+    }
+    args.output.status(tally.summary(from, to));
+    Ok(())
+}
+
+/// Every address the program owns, without any of the data.
+fn program_accounts(
+    connection: &RetryingRpcClient,
+    program: &Pubkey,
+    min_context_slot: Option<Slot>,
+) -> Result<Vec<Pubkey>> {
+    connection.call(|client| {
+        let config = RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: 0,
+                }),
+                commitment: Some(client.commitment()),
+                min_context_slot,
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        Ok(client
+            .get_program_accounts_with_config(program, config)?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect())
+    })
+}
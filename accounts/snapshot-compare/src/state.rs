@@ -0,0 +1,255 @@
+//! An account's state at one point in time, read either live from the node
+//! or from the balances a transaction recorded.
+//!
+//! Nodes only serve the current state of an account, but every transaction
+//! stores the balances of the accounts it loads as they were before it ran
+//! (`preBalances` and `preTokenBalances`). So the state at slot N is the
+//! "before" of the first transaction after N that loads the account, or the
+//! current state if there is none. Only lamports and token balances are
+//! recorded that way; account data is known only when it comes from the
+//! live state.
+
+use common::token::{MintInfo, TokenAccountInfo};
+use common::{ExampleError, Result, RetryingRpcClient, at_least_confirmed, batch};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcTransactionConfig};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default)]
+pub struct State {
+    /// Zero when the account does not exist
+    pub lamports: u64,
+    pub token: Option<TokenState>,
+    /// The whole account, when the state was read live
+    pub account: Option<Account>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TokenState {
+    pub mint: String,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+/// Reads `keys` live, in pages of getMultipleAccounts, from a node that has
+/// reached at least `min_context_slot`. Returns the slot the node read them
+/// at; token balances are filled in with the decimals of their mints.
+pub fn fetch_live(
+    connection: &RetryingRpcClient,
+    keys: &[Pubkey],
+    min_context_slot: Option<Slot>,
+) -> Result<(Slot, Vec<State>)> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(connection.commitment()),
+        min_context_slot,
+        ..RpcAccountInfoConfig::default()
+    };
+    let mut slot = 0;
+    let mut accounts = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(batch::MAX_MULTIPLE_ACCOUNTS) {
+        let response = connection
+            .call(|client| Ok(client.get_multiple_accounts_with_config(chunk, config.clone())?))?;
+        // Pages can be served at different slots; the last is the latest.
+        slot = slot.max(response.context.slot);
+        accounts.extend(response.value);
+    }
+
+    let tokens: Vec<Option<TokenAccountInfo>> = keys
+        .iter()
+        .zip(&accounts)
+        .map(|(key, account)| {
+            account
+                .as_ref()
+                .and_then(|account| TokenAccountInfo::unpack(key, account).ok())
+        })
+        .collect();
+    let mut mints: Vec<Pubkey> = tokens
+        .iter()
+        .flatten()
+        .map(|token| token.account.mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+    let decimals: HashMap<Pubkey, u8> = mints
+        .iter()
+        .zip(connection.call(|client| batch::get_multiple_accounts(client, &mints))?)
+        .filter_map(|(mint, account)| {
+            let info = MintInfo::unpack(mint, account.as_ref()?).ok()?;
+            Some((*mint, info.decimals()))
+        })
+        .collect();
+
+    let states = accounts
+        .into_iter()
+        .zip(tokens)
+        .map(|(account, token)| State {
+            lamports: account.as_ref().map_or(0, |account| account.lamports),
+            token: token.and_then(|token| {
+                Some(TokenState {
+                    mint: token.account.mint.to_string(),
+                    amount: token.account.amount,
+                    decimals: *decimals.get(&token.account.mint)?,
+                })
+            }),
+            account,
+        })
+        .collect();
+    Ok((slot, states))
+}
+
+/// The slots and signatures of the transactions that loaded `key` after
+/// slot `after`, newest first, failed ones included: they still charge the
+/// fee payer.
+pub fn signatures_after(
+    connection: &RetryingRpcClient,
+    key: &Pubkey,
+    after: Slot,
+    max_transactions: usize,
+) -> Result<Vec<(Slot, Signature)>> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+    loop {
+        let page = connection.call(|client| {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(batch::MAX_SIGNATURES_FOR_ADDRESS),
+                commitment: Some(at_least_confirmed(client.commitment())),
+            };
+            Ok(client.get_signatures_for_address_with_config(key, config)?)
+        })?;
+        let full = page.len() == batch::MAX_SIGNATURES_FOR_ADDRESS;
+        for status in page {
+            if status.slot <= after {
+                return Ok(signatures);
+            }
+            let signature = parse_signature(&status.signature)?;
+            before = Some(signature);
+            signatures.push((status.slot, signature));
+            if signatures.len() > max_transactions {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "{key} has more than {max_transactions} transactions after slot {after}; \
+                     raise --max-transactions or pick a later slot"
+                )));
+            }
+        }
+        if !full {
+            return Ok(signatures);
+        }
+    }
+}
+
+/// The balances of `key` just before `signature` ran.
+pub fn state_before(
+    connection: &RetryingRpcClient,
+    signature: &Signature,
+    key: &Pubkey,
+) -> Result<State> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(at_least_confirmed(connection.commitment())),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed =
+        connection.call(|client| Ok(client.get_transaction_with_config(signature, config)?))?;
+    let transaction = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| ExampleError::Parse(format!("transaction {signature} is not base64")))?;
+    let meta = confirmed
+        .transaction
+        .meta
+        .ok_or_else(|| ExampleError::Parse(format!("transaction {signature} has no meta")))?;
+
+    // Balances follow the static keys, then the addresses loaded from
+    // lookup tables, writable ones first.
+    let mut keys: Vec<Pubkey> = transaction.message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            keys.push(address.parse()?);
+        }
+    }
+    let index = keys
+        .iter()
+        .position(|loaded| loaded == key)
+        .ok_or_else(|| {
+            ExampleError::Parse(format!("transaction {signature} does not load {key}"))
+        })?;
+    let lamports = *meta.pre_balances.get(index).ok_or_else(|| {
+        ExampleError::Parse(format!("transaction {signature} has no balance for {key}"))
+    })?;
+    let token = match &meta.pre_token_balances {
+        OptionSerializer::Some(balances) => balances
+            .iter()
+            .find(|balance| usize::from(balance.account_index) == index)
+            .map(|balance| -> Result<TokenState> {
+                Ok(TokenState {
+                    mint: balance.mint.clone(),
+                    amount: balance.ui_token_amount.amount.parse().map_err(|_| {
+                        ExampleError::Parse(format!(
+                            "token amount `{}` in transaction {signature}",
+                            balance.ui_token_amount.amount
+                        ))
+                    })?,
+                    decimals: balance.ui_token_amount.decimals,
+                })
+            })
+            .transpose()?,
+        _ => None,
+    };
+    Ok(State {
+        lamports,
+        token,
+        account: None,
+    })
+}
+
+/// The accounts a transaction could write, lookup table entries included.
+pub fn writable_accounts(
+    connection: &RetryingRpcClient,
+    signature: &Signature,
+) -> Result<Vec<Pubkey>> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(at_least_confirmed(connection.commitment())),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed =
+        connection.call(|client| Ok(client.get_transaction_with_config(signature, config)?))?;
+    let message = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| ExampleError::Parse(format!("transaction {signature} is not base64")))?
+        .message;
+    let mut keys: Vec<Pubkey> = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| message.is_maybe_writable(index, None))
+        .map(|(_, key)| *key)
+        .collect();
+    if let Some(meta) = &confirmed.transaction.meta
+        && let OptionSerializer::Some(loaded) = &meta.loaded_addresses
+    {
+        for address in &loaded.writable {
+            keys.push(address.parse()?);
+        }
+    }
+    Ok(keys)
+}
+
+fn parse_signature(signature: &str) -> Result<Signature> {
+    signature
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("signature `{signature}`")))
+}
@@ -0,0 +1,88 @@
+use clap::Parser;
+use common::Result;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::sns;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+
+/// Resolve a `.sol` domain to the wallet that owns it, or list the domains
+/// a wallet owns.
+///
+/// The domain's name account is derived from the hash of its name and read
+/// for its owner; a tokenized domain is owned by whoever holds its NFT. The
+/// reverse direction finds the wallet's name accounts under the `.sol` root
+/// and reads each one's reverse lookup account for its name.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// A domain such as `bonfida.sol` or `dex.bonfida.sol`, or a wallet
+    /// address (base58) to list the domains of
+    query: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct Resolution {
+    domain: String,
+    name_account: String,
+    owner: String,
+}
+
+#[derive(Serialize)]
+struct Domain {
+    domain: String,
+    name_account: String,
+}
+
+#[derive(Serialize)]
+struct OwnedDomains {
+    wallet: String,
+    domains: Vec<Domain>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    if sns::is_domain(&args.query) {
+        let resolution = Resolution {
+            domain: args.query.clone(),
+            name_account: sns::domain_key(&args.query)?.to_string(),
+            owner: sns::resolve(&connection, &args.query)?.to_string(),
+        };
+        return args.output.print(&resolution, || {
+            println!("Domain:       {}", resolution.domain);
+            println!("Name account: {}", resolution.name_account);
+            println!("Owner:        {}", resolution.owner);
+        });
+    }
+
+    let wallet: Pubkey = args.query.parse()?;
+    let owned = OwnedDomains {
+        wallet: wallet.to_string(),
+        domains: sns::domains_owned(&connection, &wallet)?
+            .into_iter()
+            .map(|(key, domain)| Domain {
+                domain,
+                name_account: key.to_string(),
+            })
+            .collect(),
+    };
+    args.output.print(&owned, || {
+        if owned.domains.is_empty() {
+            println!("{} owns no .sol domains", owned.wallet);
+        }
+        for domain in &owned.domains {
+            println!("{:<32} {}", domain.domain, domain.name_account);
+        }
+    })
+}
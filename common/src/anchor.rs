@@ -0,0 +1,377 @@
+//! Decoding Anchor program accounts from the program's IDL alone.
+//!
+//! Anchor prefixes every account with an 8-byte discriminator, the first
+//! bytes of `sha256("account:<Name>")`, and Borsh-serializes the rest.
+//! Given the IDL JSON that `anchor build` writes (both the 0.30+ spec format
+//! and the older one are accepted), [`Idl::decode_account`] identifies an
+//! account's type and renders its contents as JSON without the program's
+//! crate.
+//!
+//! Events raised with `emit!` are written to the logs as `Program data:`
+//! lines, prefixed with `sha256("event:<Name>")` in the same way;
+//! [`Idl::decode_logs`] finds and decodes those.
+
+use crate::borsh::Reader;
+use crate::error::{ExampleError, Result};
+use crate::logs::{self, LogLine};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use solana_sdk::hash::hashv;
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+
+pub const DISCRIMINATOR_LEN: usize = 8;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Idl {
+    /// Program address; 0.30+ IDLs carry it at the top level, older ones in
+    /// `metadata.address` when the IDL was published on chain.
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub metadata: Option<IdlMetadata>,
+    #[serde(default)]
+    pub accounts: Vec<IdlAccount>,
+    #[serde(default)]
+    pub events: Vec<IdlEvent>,
+    #[serde(default)]
+    pub types: Vec<IdlTypeDef>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlMetadata {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlAccount {
+    pub name: String,
+    /// Present in 0.30+ IDLs; older ones leave it to be derived from the name.
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    /// Older IDLs define the account layout inline instead of in `types`.
+    #[serde(default, rename = "type")]
+    pub layout: Option<IdlTypeDefTy>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlEvent {
+    pub name: String,
+    /// Present in 0.30+ IDLs, which define the layout in `types`.
+    #[serde(default)]
+    pub discriminator: Option<Vec<u8>>,
+    /// Older IDLs list the fields inline.
+    #[serde(default)]
+    pub fields: Option<IdlFields>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlTypeDef {
+    pub name: String,
+    /// `borsh` unless the type is `zero_copy`, which this module does not read.
+    #[serde(default)]
+    pub serialization: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: IdlTypeDefTy,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum IdlTypeDefTy {
+    Struct {
+        #[serde(default)]
+        fields: Option<IdlFields>,
+    },
+    Enum {
+        variants: Vec<IdlEnumVariant>,
+    },
+    /// A type alias, e.g. `type Basis = u64;`.
+    Type {
+        alias: Value,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlEnumVariant {
+    pub name: String,
+    #[serde(default)]
+    pub fields: Option<IdlFields>,
+}
+
+/// Struct or enum variant fields: named (`{ name, type }`) or tuple (bare
+/// types).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum IdlFields {
+    Named(Vec<IdlField>),
+    Tuple(Vec<Value>),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlField {
+    pub name: String,
+    /// Kept as raw JSON: the type grammar differs between IDL versions
+    /// (`publicKey` vs `pubkey`, `{"defined": "X"}` vs
+    /// `{"defined": {"name": "X"}}`) and is interpreted while decoding.
+    #[serde(rename = "type")]
+    pub ty: Value,
+}
+
+/// The discriminator Anchor gives an account type named `name`.
+pub fn account_discriminator(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    discriminator("account", name)
+}
+
+/// The discriminator Anchor prefixes the data of the instruction handled by
+/// the function `name` (snake case, e.g. `mint_v2`) with.
+pub fn instruction_discriminator(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    discriminator("global", name)
+}
+
+/// The discriminator Anchor prefixes an event named `name` with.
+pub fn event_discriminator(name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    discriminator("event", name)
+}
+
+/// First bytes of `sha256("<namespace>:<name>")`.
+fn discriminator(namespace: &str, name: &str) -> [u8; DISCRIMINATOR_LEN] {
+    let hash = hashv(&[namespace.as_bytes(), b":", name.as_bytes()]);
+    let mut discriminator = [0; DISCRIMINATOR_LEN];
+    discriminator.copy_from_slice(&hash.as_ref()[..DISCRIMINATOR_LEN]);
+    discriminator
+}
+
+impl Idl {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)?;
+        serde_json::from_str(&json)
+            .map_err(|err| ExampleError::Parse(format!("IDL {}: {err}", path.display())))
+    }
+
+    /// The program address recorded in the IDL, if any.
+    pub fn program_id(&self) -> Option<Result<Pubkey>> {
+        let address = self
+            .address
+            .as_ref()
+            .or_else(|| self.metadata.as_ref()?.address.as_ref())?;
+        Some(address.parse().map_err(Into::into))
+    }
+
+    pub fn discriminator(&self, account: &IdlAccount) -> [u8; DISCRIMINATOR_LEN] {
+        explicit_discriminator(account.discriminator.as_deref())
+            .unwrap_or_else(|| account_discriminator(&account.name))
+    }
+
+    pub fn event_discriminator(&self, event: &IdlEvent) -> [u8; DISCRIMINATOR_LEN] {
+        explicit_discriminator(event.discriminator.as_deref())
+            .unwrap_or_else(|| event_discriminator(&event.name))
+    }
+
+    pub fn account(&self, name: &str) -> Option<&IdlAccount> {
+        self.accounts.iter().find(|account| account.name == name)
+    }
+
+    /// Identifies `data` by its discriminator and decodes it, returning the
+    /// account type name and its fields as JSON.
+    pub fn decode_account(&self, data: &[u8]) -> Result<(&str, Value)> {
+        let (discriminator, body) = data
+            .split_at_checked(DISCRIMINATOR_LEN)
+            .ok_or_else(|| ExampleError::Parse("account shorter than a discriminator".into()))?;
+        let account = self
+            .accounts
+            .iter()
+            .find(|account| self.discriminator(account) == discriminator)
+            .ok_or_else(|| {
+                ExampleError::Parse("discriminator matches no account in the IDL".into())
+            })?;
+        let layout = match &account.layout {
+            Some(layout) => layout,
+            None => {
+                let def = self.type_def(&account.name)?;
+                if let Some(serialization) = def.serialization.as_deref()
+                    && serialization != "borsh"
+                {
+                    return Err(ExampleError::Parse(format!(
+                        "{} uses {serialization} serialization, only borsh is supported",
+                        account.name
+                    )));
+                }
+                &def.ty
+            }
+        };
+        // Accounts are allocated at their maximum size, so trailing bytes
+        // after the decoded value are expected and ignored.
+        let mut reader = Reader::new(body);
+        let value = self.read_type_def(layout, &mut reader)?;
+        Ok((&account.name, value))
+    }
+
+    /// Identifies event data by its discriminator and decodes it, returning
+    /// the event name and its fields as JSON.
+    pub fn decode_event(&self, data: &[u8]) -> Result<(&str, Value)> {
+        let (discriminator, body) = data
+            .split_at_checked(DISCRIMINATOR_LEN)
+            .ok_or_else(|| ExampleError::Parse("event shorter than a discriminator".into()))?;
+        let event = self
+            .events
+            .iter()
+            .find(|event| self.event_discriminator(event) == discriminator)
+            .ok_or_else(|| {
+                ExampleError::Parse("discriminator matches no event in the IDL".into())
+            })?;
+        let mut reader = Reader::new(body);
+        let value = match &event.fields {
+            Some(fields) => self.read_fields(Some(fields), &mut reader)?,
+            None => self.read_type_def(&self.type_def(&event.name)?.ty, &mut reader)?,
+        };
+        Ok((&event.name, value))
+    }
+
+    /// Decodes every event `program` emitted in a transaction's logs, in
+    /// order. Lines written by other programs, including ones `program`
+    /// invoked, are skipped; a line that is not one of the IDL's events
+    /// yields an error in its place.
+    pub fn decode_logs(&self, logs: &[String], program: &Pubkey) -> Vec<Result<(&str, Value)>> {
+        logs::lines_for_program(logs, program)
+            .filter_map(|line| match line {
+                // sol_log_data writes each slice it is given as its own
+                // base64 word; emit! passes just one.
+                LogLine::Data(data) => data.split(' ').next(),
+                _ => None,
+            })
+            .map(|data| {
+                let bytes = BASE64
+                    .decode(data)
+                    .map_err(|err| ExampleError::Parse(format!("program data: {err}")))?;
+                self.decode_event(&bytes)
+            })
+            .collect()
+    }
+
+    fn type_def(&self, name: &str) -> Result<&IdlTypeDef> {
+        self.types
+            .iter()
+            .find(|def| def.name == name)
+            .ok_or_else(|| ExampleError::Parse(format!("type {name} is not defined in the IDL")))
+    }
+
+    fn read_type_def(&self, ty: &IdlTypeDefTy, reader: &mut Reader) -> Result<Value> {
+        match ty {
+            IdlTypeDefTy::Struct { fields } => self.read_fields(fields.as_ref(), reader),
+            IdlTypeDefTy::Enum { variants } => {
+                let index = reader.take(1)?[0];
+                let variant = variants.get(usize::from(index)).ok_or_else(|| {
+                    ExampleError::Parse(format!("enum variant {index} out of range"))
+                })?;
+                match &variant.fields {
+                    None => Ok(Value::String(variant.name.clone())),
+                    fields => {
+                        Ok(json!({ &variant.name: self.read_fields(fields.as_ref(), reader)? }))
+                    }
+                }
+            }
+            IdlTypeDefTy::Type { alias } => self.read_type(alias, reader),
+        }
+    }
+
+    fn read_fields(&self, fields: Option<&IdlFields>, reader: &mut Reader) -> Result<Value> {
+        match fields {
+            None => Ok(Value::Null),
+            Some(IdlFields::Named(fields)) => {
+                let mut object = Map::new();
+                for field in fields {
+                    object.insert(field.name.clone(), self.read_type(&field.ty, reader)?);
+                }
+                Ok(Value::Object(object))
+            }
+            Some(IdlFields::Tuple(types)) => types
+                .iter()
+                .map(|ty| self.read_type(ty, reader))
+                .collect::<Result<_>>()
+                .map(Value::Array),
+        }
+    }
+
+    fn read_type(&self, ty: &Value, reader: &mut Reader) -> Result<Value> {
+        if let Some(name) = ty.as_str() {
+            return read_primitive(reader, name);
+        }
+        let unsupported = || ExampleError::Parse(format!("unsupported IDL type {ty}"));
+        let object = ty.as_object().ok_or_else(unsupported)?;
+        let (kind, inner) = object.iter().next().ok_or_else(unsupported)?;
+        match kind.as_str() {
+            "option" | "coption" => {
+                // Borsh options are a one-byte tag; COption uses four.
+                let tag_len = if kind == "option" { 1 } else { 4 };
+                if reader.take(tag_len)?.iter().all(|&byte| byte == 0) {
+                    Ok(Value::Null)
+                } else {
+                    self.read_type(inner, reader)
+                }
+            }
+            "vec" => {
+                let len = reader.read_u32()?;
+                (0..len)
+                    .map(|_| self.read_type(inner, reader))
+                    .collect::<Result<_>>()
+                    .map(Value::Array)
+            }
+            "array" => {
+                let (element, len) = inner
+                    .as_array()
+                    .and_then(|pair| Some((pair.first()?, pair.get(1)?.as_u64()?)))
+                    .ok_or_else(unsupported)?;
+                if element == "u8" {
+                    return reader.read_base64(len as usize);
+                }
+                (0..len)
+                    .map(|_| self.read_type(element, reader))
+                    .collect::<Result<_>>()
+                    .map(Value::Array)
+            }
+            "defined" => {
+                let name = inner
+                    .as_str()
+                    .or_else(|| inner.get("name")?.as_str())
+                    .ok_or_else(unsupported)?;
+                let def = self.type_def(name)?;
+                self.read_type_def(&def.ty, reader)
+            }
+            _ => Err(unsupported()),
+        }
+    }
+}
+
+fn explicit_discriminator(bytes: Option<&[u8]>) -> Option<[u8; DISCRIMINATOR_LEN]> {
+    bytes?.try_into().ok()
+}
+
+fn read_primitive(reader: &mut Reader, name: &str) -> Result<Value> {
+    match name {
+        "bool" => reader.read_bool(),
+        "u8" => reader.read_unsigned(1),
+        "i8" => reader.read_signed(1),
+        "u16" => reader.read_unsigned(2),
+        "i16" => reader.read_signed(2),
+        "u32" => reader.read_unsigned(4),
+        "i32" => reader.read_signed(4),
+        "f32" => reader.read_f32(),
+        "u64" => reader.read_unsigned(8),
+        "i64" => reader.read_signed(8),
+        "f64" => reader.read_f64(),
+        "u128" => reader.read_unsigned(16),
+        "i128" => reader.read_signed(16),
+        "pubkey" | "publicKey" => reader.read_pubkey(),
+        "string" => reader.read_string(),
+        "bytes" => reader.read_bytes(),
+        other => Err(ExampleError::Parse(format!(
+            "unsupported IDL type \"{other}\""
+        ))),
+    }
+}
@@ -0,0 +1,57 @@
+//! Batched account lookups.
+//!
+//! `getMultipleAccounts` accepts at most [`MAX_MULTIPLE_ACCOUNTS`] keys per
+//! request, so large lookups are split into chunks. Results are always
+//! returned in the order of the input keys, with `None` for missing accounts.
+
+use crate::error::Result;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use solana_client::nonblocking;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::fs;
+use std::path::Path;
+
+pub const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+/// `getSignaturesForAddress` returns at most this many signatures per call,
+/// so longer histories are read a page at a time.
+pub const MAX_SIGNATURES_FOR_ADDRESS: usize = 1000;
+
+/// Fetches `keys` one chunk at a time with the blocking client.
+pub fn get_multiple_accounts(client: &RpcClient, keys: &[Pubkey]) -> Result<Vec<Option<Account>>> {
+    let mut accounts = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        accounts.extend(client.get_multiple_accounts(chunk)?);
+    }
+    Ok(accounts)
+}
+
+/// Fetches `keys` with up to `concurrency` chunk requests in flight at once.
+pub async fn get_multiple_accounts_concurrent(
+    client: &nonblocking::rpc_client::RpcClient,
+    keys: &[Pubkey],
+    concurrency: usize,
+) -> Result<Vec<Option<Account>>> {
+    let chunks: Vec<Vec<Option<Account>>> = stream::iter(keys.chunks(MAX_MULTIPLE_ACCOUNTS))
+        .map(|chunk| client.get_multiple_accounts(chunk))
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await?;
+    Ok(chunks.into_iter().flatten().collect())
+}
+
+/// Reads one base58 address per line; blank lines and anything after `#`
+/// are ignored.
+pub fn read_keys_file(path: impl AsRef<Path>) -> Result<Vec<Pubkey>> {
+    let contents = fs::read_to_string(path)?;
+    let mut keys = Vec::new();
+    for line in contents.lines() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if !line.is_empty() {
+            keys.push(line.parse()?);
+        }
+    }
+    Ok(keys)
+}
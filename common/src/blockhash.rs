@@ -0,0 +1,63 @@
+//! A shared, periodically refreshed recent blockhash.
+//!
+//! Fetching a blockhash for every transaction doubles the request count of a
+//! busy sender; reusing one for a few seconds is safe because a blockhash
+//! stays valid for roughly 150 blocks (about a minute).
+
+use crate::error::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A blockhash together with the last block height at which transactions
+/// signed with it can still land.
+#[derive(Debug, Clone, Copy)]
+pub struct RecentBlockhash {
+    pub blockhash: Hash,
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Debug)]
+pub struct BlockhashCache {
+    max_age: Duration,
+    cached: Mutex<Option<(RecentBlockhash, Instant)>>,
+}
+
+impl Default for BlockhashCache {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(15))
+    }
+}
+
+impl BlockhashCache {
+    /// A cache that refetches once its blockhash is older than `max_age`.
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            max_age,
+            cached: Mutex::new(None),
+        }
+    }
+
+    /// Returns the cached blockhash, refreshing it first if it is stale.
+    pub fn get(&self, client: &RpcClient) -> Result<RecentBlockhash> {
+        let cached = *self.cached.lock().unwrap();
+        match cached {
+            Some((recent, fetched_at)) if fetched_at.elapsed() < self.max_age => Ok(recent),
+            _ => self.refresh(client),
+        }
+    }
+
+    /// Unconditionally fetches a new blockhash, e.g. after a transaction
+    /// signed with the cached one expired.
+    pub fn refresh(&self, client: &RpcClient) -> Result<RecentBlockhash> {
+        let (blockhash, last_valid_block_height) =
+            client.get_latest_blockhash_with_commitment(client.commitment())?;
+        let recent = RecentBlockhash {
+            blockhash,
+            last_valid_block_height,
+        };
+        *self.cached.lock().unwrap() = Some((recent, Instant::now()));
+        Ok(recent)
+    }
+}
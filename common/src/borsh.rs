@@ -0,0 +1,105 @@
+//! Reading Borsh-encoded bytes into JSON when the layout is only known at
+//! run time, from an Anchor IDL or a schema file.
+//!
+//! Integers up to 64 bits become JSON numbers, which serde_json holds
+//! exactly; 128-bit ones always become strings, because most JSON consumers
+//! cannot hold them. Byte strings are base64, like the node's account data.
+
+use crate::error::{ExampleError, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde_json::{Value, json};
+use solana_sdk::pubkey::Pubkey;
+
+/// A cursor over little-endian bytes.
+pub struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Bytes not read yet.
+    pub fn remaining(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let (head, rest) = self
+            .data
+            .split_at_checked(len)
+            .ok_or_else(|| ExampleError::Parse("data ended early".into()))?;
+        self.data = rest;
+        Ok(head)
+    }
+
+    pub fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("took exactly N bytes"))
+    }
+
+    pub fn read_bool(&mut self) -> Result<Value> {
+        Ok(Value::Bool(self.take(1)?[0] != 0))
+    }
+
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take_array()?))
+    }
+
+    /// An unsigned integer of `size` bytes, at most 16.
+    pub fn read_unsigned(&mut self, size: usize) -> Result<Value> {
+        let mut bytes = [0; 16];
+        bytes[..size].copy_from_slice(self.take(size)?);
+        let value = u128::from_le_bytes(bytes);
+        Ok(match u64::try_from(value) {
+            Ok(value) if size <= 8 => json!(value),
+            _ => json!(value.to_string()),
+        })
+    }
+
+    /// A two's-complement integer of `size` bytes, at most 16.
+    pub fn read_signed(&mut self, size: usize) -> Result<Value> {
+        let bytes = self.take(size)?;
+        // Sign-extend from the top byte.
+        let fill = if bytes[size - 1] & 0x80 != 0 { 0xff } else { 0 };
+        let mut extended = [fill; 16];
+        extended[..size].copy_from_slice(bytes);
+        let value = i128::from_le_bytes(extended);
+        Ok(match i64::try_from(value) {
+            Ok(value) if size <= 8 => json!(value),
+            _ => json!(value.to_string()),
+        })
+    }
+
+    pub fn read_f32(&mut self) -> Result<Value> {
+        Ok(json!(f32::from_le_bytes(self.take_array()?)))
+    }
+
+    pub fn read_f64(&mut self) -> Result<Value> {
+        Ok(json!(f64::from_le_bytes(self.take_array()?)))
+    }
+
+    pub fn read_pubkey(&mut self) -> Result<Value> {
+        Ok(json!(
+            Pubkey::new_from_array(self.take_array()?).to_string()
+        ))
+    }
+
+    /// A `u32` length, then that many bytes of UTF-8.
+    pub fn read_string(&mut self) -> Result<Value> {
+        let len = self.read_u32()? as usize;
+        Ok(json!(String::from_utf8_lossy(self.take(len)?)))
+    }
+
+    /// A `u32` length, then that many bytes, as base64.
+    pub fn read_bytes(&mut self) -> Result<Value> {
+        let len = self.read_u32()? as usize;
+        self.read_base64(len)
+    }
+
+    /// `len` bytes as base64.
+    pub fn read_base64(&mut self, len: usize) -> Result<Value> {
+        Ok(json!(BASE64.encode(self.take(len)?)))
+    }
+}
@@ -0,0 +1,418 @@
+use crate::client::{DEFAULT_RPC_URL, build_client, build_nonblocking_client, websocket_url};
+use crate::config::PROFILE_ENV;
+use crate::credits::{self, CreditTable, Overflow, Scheduler, SchedulerConfig};
+use crate::error::{ExampleError, Result};
+use crate::failover::FailoverRpcClient;
+use crate::keys::{self, DEFAULT_DERIVATION_PATH, KeySource};
+use crate::metrics;
+use crate::output::OutputArgs;
+use crate::retry::{RetryPolicy, RetryingRpcClient};
+use crate::stream::{self, SourceConfig, SourceKind, StreamSource};
+use crate::{sns, token};
+use clap::{Args, ValueEnum};
+use solana_client::nonblocking;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Connection flags shared by every example; `#[command(flatten)]` them into
+/// the example's own arguments and parse with [`crate::config::parse`] so
+/// that `--profile` can fill them in.
+#[derive(Args, Debug, Clone)]
+pub struct RpcArgs {
+    /// Config file profile supplying the endpoint, commitment and keypair
+    /// defaults
+    #[arg(long, env = PROFILE_ENV)]
+    pub profile: Option<String>,
+
+    /// JSON-RPC endpoint to query, e.g. your QuickNode HTTP URL
+    #[arg(short, long, env = "SOLANA_RPC_URL", default_value = DEFAULT_RPC_URL)]
+    pub rpc_url: String,
+
+    /// Commitment level used for queries
+    #[arg(short, long, value_enum, default_value_t = Commitment::Confirmed)]
+    pub commitment: Commitment,
+
+    /// Per-request timeout in seconds
+    #[arg(long, default_value_t = 30)]
+    pub timeout: u64,
+
+    /// Retries for rate-limited or failed reads, in examples that retry them
+    #[arg(long, default_value_t = 3)]
+    pub max_retries: u32,
+}
+
+impl RpcArgs {
+    pub fn commitment_config(&self) -> CommitmentConfig {
+        self.commitment.into()
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout)
+    }
+
+    pub fn client(&self) -> RpcClient {
+        build_client(&self.rpc_url, self.commitment_config(), self.timeout())
+    }
+
+    pub fn nonblocking_client(&self) -> nonblocking::rpc_client::RpcClient {
+        build_nonblocking_client(&self.rpc_url, self.commitment_config(), self.timeout())
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_retries: self.max_retries,
+            ..RetryPolicy::default()
+        }
+    }
+
+    pub fn retrying_client(&self) -> RetryingRpcClient {
+        RetryingRpcClient::new(self.client(), self.retry_policy())
+    }
+}
+
+/// Websocket flag for the streaming examples, flattened next to [`RpcArgs`].
+#[derive(Args, Debug, Clone)]
+pub struct WsArgs {
+    /// Websocket endpoint; derived from --rpc-url when omitted
+    #[arg(long, env = "SOLANA_WS_URL")]
+    pub ws_url: Option<String>,
+}
+
+impl WsArgs {
+    pub fn url(&self, rpc: &RpcArgs) -> String {
+        self.ws_url
+            .clone()
+            .unwrap_or_else(|| websocket_url(&rpc.rpc_url))
+    }
+}
+
+/// Source flags for the examples built on [`crate::stream`], flattened next
+/// to [`RpcArgs`] and [`WsArgs`].
+#[derive(Args, Debug, Clone)]
+pub struct StreamArgs {
+    /// Where transactions come from; `auto` tries gRPC (when --grpc-url is
+    /// set), then the websocket, then polling
+    #[arg(long, value_enum, default_value_t = SourceKind::Auto)]
+    pub source: SourceKind,
+
+    /// Yellowstone gRPC endpoint, e.g. https://example.solana-mainnet.quiknode.pro:10000
+    #[arg(long, env = "GEYSER_GRPC_URL")]
+    pub grpc_url: Option<String>,
+
+    /// Authentication token sent to the gRPC endpoint as `x-token`
+    #[arg(long, env = "GEYSER_X_TOKEN", hide_env_values = true)]
+    pub x_token: Option<String>,
+
+    /// Seconds between getSignaturesForAddress calls when polling
+    #[arg(long, default_value_t = 2)]
+    pub poll_interval: u64,
+}
+
+impl StreamArgs {
+    /// Connects to transactions mentioning `address`; see [`stream::connect`].
+    pub async fn connect(
+        &self,
+        rpc: &RpcArgs,
+        ws: &WsArgs,
+        address: &Pubkey,
+    ) -> Result<Box<dyn StreamSource>> {
+        let config = SourceConfig {
+            kind: self.source,
+            rpc_url: &rpc.rpc_url,
+            ws_url: &ws.url(rpc),
+            grpc_url: self.grpc_url.as_deref(),
+            x_token: self.x_token.as_deref(),
+            commitment: rpc.commitment_config(),
+            timeout: rpc.timeout(),
+            poll_interval: Duration::from_secs(self.poll_interval.max(1)),
+        };
+        stream::connect(&config, address).await
+    }
+}
+
+/// Fallback endpoint flags for the long-running examples, flattened next to
+/// [`RpcArgs`]; see [`crate::failover`].
+#[derive(Args, Debug, Clone)]
+pub struct FailoverArgs {
+    /// Endpoint to switch to when --rpc-url fails, in order of preference;
+    /// repeatable or comma-separated
+    #[arg(long, env = "SOLANA_FALLBACK_URLS", value_delimiter = ',')]
+    pub fallback_url: Vec<String>,
+
+    /// Seconds between health checks of every endpoint
+    #[arg(long, default_value_t = 10)]
+    pub health_interval: u64,
+}
+
+impl FailoverArgs {
+    /// A client over --rpc-url and the fallbacks, retrying with the policy
+    /// of `rpc` once all of them have failed a call.
+    pub fn client(&self, rpc: &RpcArgs) -> Result<FailoverRpcClient> {
+        let urls: Vec<String> = std::iter::once(rpc.rpc_url.clone())
+            .chain(self.fallback_url.iter().cloned())
+            .collect();
+        FailoverRpcClient::new(
+            &urls,
+            rpc.commitment_config(),
+            rpc.timeout(),
+            Duration::from_secs(self.health_interval.max(1)),
+            rpc.retry_policy(),
+        )
+    }
+}
+
+/// Prometheus endpoint flag for the long-running examples; see
+/// [`crate::metrics`].
+#[derive(Args, Debug, Clone)]
+pub struct MetricsArgs {
+    /// Serve Prometheus metrics on this port, at /metrics
+    #[arg(long)]
+    pub metrics_port: Option<u16>,
+}
+
+impl MetricsArgs {
+    /// Starts the endpoint if a port was given, returning whether it runs.
+    pub fn serve(&self, output: &OutputArgs) -> Result<bool> {
+        let Some(port) = self.metrics_port else {
+            return Ok(false);
+        };
+        let address = metrics::spawn_server(port)?;
+        output.status(format_args!("Serving metrics on http://{address}/metrics"));
+        Ok(true)
+    }
+}
+
+/// Request budget flags for examples that make many calls; see
+/// [`crate::credits`]. Nothing is limited unless one of them is given.
+#[derive(Args, Debug, Clone)]
+pub struct CreditArgs {
+    /// Most requests per second to send; faster calls wait their turn, or
+    /// fail with --shed
+    #[arg(long, env = "QUICKNODE_MAX_RPS")]
+    pub max_rps: Option<f64>,
+
+    /// Requests that may go out back to back before --max-rps applies
+    #[arg(long, default_value_t = 1, requires = "max_rps")]
+    pub burst: u32,
+
+    /// Fail calls over --max-rps instead of queueing them
+    #[arg(long, requires = "max_rps")]
+    pub shed: bool,
+
+    /// API credits that may be spent per month; calls fail once they would
+    /// exceed it
+    #[arg(long, env = "QUICKNODE_MONTHLY_CREDITS")]
+    pub monthly_credits: Option<u64>,
+
+    /// Credits charged for a method, as METHOD=CREDITS; repeatable
+    #[arg(long, value_name = "METHOD=CREDITS", value_parser = parse_method_credits)]
+    pub method_credits: Vec<(String, u64)>,
+
+    /// Credits charged for any other method
+    #[arg(long, default_value_t = credits::DEFAULT_METHOD_CREDITS)]
+    pub default_credits: u64,
+
+    /// JSON file carrying the month's spending over from earlier runs
+    #[arg(long, env = "QUICKNODE_CREDIT_LEDGER")]
+    pub credit_ledger: Option<PathBuf>,
+}
+
+impl CreditArgs {
+    /// Installs the scheduler if any limit or ledger was given, returning
+    /// whether it is in place.
+    pub fn install(&self) -> Result<bool> {
+        if self.max_rps.is_none() && self.monthly_credits.is_none() && self.credit_ledger.is_none()
+        {
+            return Ok(false);
+        }
+        if self
+            .max_rps
+            .is_some_and(|rps| !rps.is_finite() || rps <= 0.0)
+        {
+            return Err(ExampleError::InvalidArgument(
+                "--max-rps must be above zero".to_string(),
+            ));
+        }
+        credits::install(Scheduler::new(SchedulerConfig {
+            max_rps: self.max_rps,
+            burst: self.burst.max(1),
+            overflow: if self.shed {
+                Overflow::Shed
+            } else {
+                Overflow::Queue
+            },
+            monthly_credits: self.monthly_credits,
+            credits: CreditTable {
+                default: self.default_credits,
+                methods: self.method_credits.iter().cloned().collect(),
+            },
+            ledger: self.credit_ledger.clone(),
+        })?);
+        Ok(true)
+    }
+}
+
+fn parse_method_credits(input: &str) -> std::result::Result<(String, u64), String> {
+    let (method, credits) = input
+        .split_once('=')
+        .ok_or_else(|| format!("`{input}` is not METHOD=CREDITS"))?;
+    let credits = credits
+        .parse()
+        .map_err(|_| format!("`{credits}` is not a number of credits"))?;
+    Ok((method.to_string(), credits))
+}
+
+/// Signing key flags for examples that send transactions. At most one source
+/// may be given; without any, the key is the profile's keypair or is read
+/// from `SOLANA_PRIVATE_KEY`.
+#[derive(Args, Debug, Clone)]
+pub struct KeypairArgs {
+    /// Signer keypair file, in solana-keygen JSON format; falls back to the
+    /// SOLANA_PRIVATE_KEY environment variable when no key flag is given
+    #[arg(short, long, conflicts_with_all = ["secret_key", "mnemonic"])]
+    pub keypair: Option<PathBuf>,
+
+    /// Base58-encoded secret key (note: visible in shell history)
+    #[arg(long, conflicts_with = "mnemonic")]
+    pub secret_key: Option<String>,
+
+    /// BIP39 seed phrase, quoted as a single argument
+    #[arg(long)]
+    pub mnemonic: Option<String>,
+
+    /// Optional BIP39 passphrase for --mnemonic
+    #[arg(long, requires = "mnemonic")]
+    pub passphrase: Option<String>,
+
+    /// Derivation path for --mnemonic [default: m/44'/501'/0'/0']
+    #[arg(long, requires = "mnemonic")]
+    pub derivation_path: Option<String>,
+}
+
+impl KeypairArgs {
+    pub fn source(&self) -> KeySource {
+        // --keypair may hold a profile default rather than a flag, so the
+        // explicit alternatives are checked first.
+        if let Some(secret) = &self.secret_key {
+            KeySource::Base58(secret.clone())
+        } else if let Some(phrase) = &self.mnemonic {
+            KeySource::Mnemonic {
+                phrase: phrase.clone(),
+                passphrase: self.passphrase.clone().unwrap_or_default(),
+                derivation_path: self
+                    .derivation_path
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_DERIVATION_PATH.to_string()),
+            }
+        } else if let Some(path) = &self.keypair {
+            KeySource::File(path.clone())
+        } else {
+            KeySource::Env
+        }
+    }
+
+    pub fn load(&self) -> Result<Keypair> {
+        self.source().load()
+    }
+}
+
+/// An address argument as typed: a base58 key, a `.sol` domain standing for
+/// the wallet that owns it, a `solana-keygen` `.json` file standing for its
+/// public key, or `wallet:mint` standing for the wallet's associated token
+/// account for that mint. Parsing needs no network or files;
+/// [`AddressArg::resolve`] does the lookups.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressArg {
+    Pubkey(Pubkey),
+    Domain(String),
+    Keypair(PathBuf),
+    AssociatedTokenAccount {
+        /// A key or a domain
+        wallet: Box<AddressArg>,
+        mint: Pubkey,
+    },
+}
+
+impl AddressArg {
+    pub fn resolve(&self, client: &RpcClient) -> Result<Pubkey> {
+        match self {
+            Self::Pubkey(key) => Ok(*key),
+            Self::Domain(domain) => sns::resolve(client, domain),
+            Self::Keypair(path) => Ok(keys::read_keypair_file(path)?.pubkey()),
+            Self::AssociatedTokenAccount { wallet, mint } => {
+                let wallet = wallet.resolve(client)?;
+                // Token-2022 mints have their accounts at a different address.
+                let program_id = token::fetch_mint(client, mint)?.program_id;
+                Ok(get_associated_token_address_with_program_id(
+                    &wallet,
+                    mint,
+                    &program_id,
+                ))
+            }
+        }
+    }
+}
+
+impl FromStr for AddressArg {
+    type Err = String;
+
+    fn from_str(input: &str) -> std::result::Result<Self, String> {
+        if let Some((wallet, mint)) = input.split_once(':') {
+            let wallet = match wallet.parse()? {
+                Self::AssociatedTokenAccount { .. } => unreachable!("split at the first ':'"),
+                wallet => Box::new(wallet),
+            };
+            let mint = mint
+                .parse()
+                .map_err(|_| format!("`{mint}` is not a base58 mint address"))?;
+            return Ok(Self::AssociatedTokenAccount { wallet, mint });
+        }
+        if sns::is_domain(input) {
+            sns::domain_key(input).map_err(|err| err.to_string())?;
+            return Ok(Self::Domain(input.to_string()));
+        }
+        if input.ends_with(".json") {
+            return Ok(Self::Keypair(input.into()));
+        }
+        input.parse().map(Self::Pubkey).map_err(|_| {
+            format!(
+                "`{input}` is not a base58 address, a .sol domain, a keypair file or wallet:mint"
+            )
+        })
+    }
+}
+
+impl fmt::Display for AddressArg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pubkey(key) => write!(f, "{key}"),
+            Self::Domain(domain) => f.write_str(domain),
+            Self::Keypair(path) => write!(f, "{}", path.display()),
+            Self::AssociatedTokenAccount { wallet, mint } => write!(f, "{wallet}:{mint}"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl From<Commitment> for CommitmentConfig {
+    fn from(commitment: Commitment) -> Self {
+        match commitment {
+            Commitment::Processed => CommitmentConfig::processed(),
+            Commitment::Confirmed => CommitmentConfig::confirmed(),
+            Commitment::Finalized => CommitmentConfig::finalized(),
+        }
+    }
+}
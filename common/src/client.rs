@@ -0,0 +1,57 @@
+use crate::logging::TracingSender;
+use solana_client::nonblocking;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::time::Duration;
+
+pub const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// Builds a blocking [`RpcClient`] for `url` with the given default
+/// commitment and per-request timeout. Its requests are traced by
+/// [`TracingSender`].
+pub fn build_client(
+    url: impl ToString,
+    commitment: CommitmentConfig,
+    timeout: Duration,
+) -> RpcClient {
+    RpcClient::new_sender(
+        TracingSender::new(url, timeout),
+        RpcClientConfig::with_commitment(commitment),
+    )
+}
+
+/// Async counterpart of [`build_client`] for use inside a tokio runtime.
+pub fn build_nonblocking_client(
+    url: impl ToString,
+    commitment: CommitmentConfig,
+    timeout: Duration,
+) -> nonblocking::rpc_client::RpcClient {
+    nonblocking::rpc_client::RpcClient::new_sender(
+        TracingSender::new(url, timeout),
+        RpcClientConfig::with_commitment(commitment),
+    )
+}
+
+/// Derives the websocket endpoint that belongs to an HTTP RPC URL.
+///
+/// QuickNode and the public clusters serve both on the same host, so only the
+/// scheme changes; a local test validator listens one port above its RPC port.
+pub fn websocket_url(rpc_url: &str) -> String {
+    let url = if let Some(rest) = rpc_url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = rpc_url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else {
+        rpc_url.to_string()
+    };
+    url.replace(":8899", ":8900")
+}
+
+/// `getTransaction` and `getBlock` reject `processed`; bump it to `confirmed`.
+pub fn at_least_confirmed(commitment: CommitmentConfig) -> CommitmentConfig {
+    if commitment == CommitmentConfig::processed() {
+        CommitmentConfig::confirmed()
+    } else {
+        commitment
+    }
+}
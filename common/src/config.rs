@@ -0,0 +1,199 @@
+//! Named endpoint profiles read from a config file, so the RPC URL and
+//! keypair don't have to be passed to every example.
+//!
+//! The file lives at `~/.config/quicknode-solana/config.toml` (or under
+//! `$XDG_CONFIG_HOME`, or wherever `QUICKNODE_SOLANA_CONFIG` points):
+//!
+//! ```toml
+//! default_profile = "devnet"
+//!
+//! [profiles.devnet]
+//! rpc_url = "https://api.devnet.solana.com"
+//! keypair = "~/.config/solana/devnet.json"
+//!
+//! [profiles.my-quicknode]
+//! rpc_url = "https://example.solana-mainnet.quiknode.pro/TOKEN/"
+//! ws_url = "wss://example.solana-mainnet.quiknode.pro/TOKEN/"
+//! commitment = "finalized"
+//! ```
+//!
+//! A profile only supplies defaults: explicit flags and their environment
+//! variables (`SOLANA_RPC_URL` and friends) still take precedence.
+
+use crate::error::{ExampleError, Result};
+use crate::logging;
+use clap::{Command, CommandFactory, FromArgMatches};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+/// Overrides the config file location.
+pub const CONFIG_ENV: &str = "QUICKNODE_SOLANA_CONFIG";
+
+/// Selects a profile like `--profile` does.
+pub const PROFILE_ENV: &str = "QUICKNODE_PROFILE";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Profile used when `--profile` is not given.
+    pub default_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub rpc_url: Option<String>,
+    pub ws_url: Option<String>,
+    /// `processed`, `confirmed` or `finalized`.
+    pub commitment: Option<String>,
+    /// Keypair file for examples that sign; `~/` is expanded.
+    pub keypair: Option<PathBuf>,
+}
+
+/// `$QUICKNODE_SOLANA_CONFIG`, else `quicknode-solana/config.toml` in the
+/// XDG config directory.
+pub fn config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os(CONFIG_ENV) {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| Some(PathBuf::from(env::var_os("HOME")?).join(".config")))?;
+    Some(config_dir.join("quicknode-solana").join("config.toml"))
+}
+
+impl Config {
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| ExampleError::Config(format!("{}: {err}", path.display())))?;
+        toml::from_str(&text)
+            .map_err(|err| ExampleError::Config(format!("{}: {err}", path.display())))
+    }
+
+    /// The config at [`config_path`], or an empty one if there is no file.
+    pub fn load() -> Result<Self> {
+        match config_path() {
+            Some(path) if path.exists() => Self::from_file(path),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    /// Looks up `name`, falling back to `default_profile`. Naming a profile
+    /// that does not exist is an error; having no profile at all is not.
+    pub fn profile(&self, name: Option<&str>) -> Result<Option<&Profile>> {
+        let Some(name) = name.or(self.default_profile.as_deref()) else {
+            return Ok(None);
+        };
+        self.profiles.get(name).map(Some).ok_or_else(|| {
+            let known: Vec<_> = self.profiles.keys().map(String::as_str).collect();
+            ExampleError::Config(format!(
+                "no profile named `{name}` (known profiles: {})",
+                if known.is_empty() {
+                    "none".to_string()
+                } else {
+                    known.join(", ")
+                }
+            ))
+        })
+    }
+}
+
+/// Parses an example's arguments like `Args::parse()`, with the selected
+/// profile's values installed as defaults first. Exits with a usage error if
+/// the config file or profile is invalid.
+///
+/// Logging is set up here too, before clap runs, so the `--log-json` flag is
+/// looked for by hand.
+pub fn parse<T: CommandFactory + FromArgMatches>() -> T {
+    let args: Vec<OsString> = env::args_os().collect();
+    logging::init(args.iter().any(|arg| arg == logging::LOG_JSON_FLAG));
+    let mut command = T::command();
+    let command = match selected_profile(&command, &args) {
+        Ok(Some(profile)) => with_profile(command, &profile),
+        Ok(None) => command,
+        Err(err) => command
+            .error(clap::error::ErrorKind::InvalidValue, err)
+            .exit(),
+    };
+    let mut matches = command.get_matches_from(args);
+    T::from_arg_matches_mut(&mut matches).unwrap_or_else(|err| err.exit())
+}
+
+/// The profile chosen by `--profile`, `QUICKNODE_PROFILE` or the config's
+/// default. Examples without the flag never read the config.
+fn selected_profile(command: &Command, args: &[OsString]) -> Result<Option<Profile>> {
+    if !command.get_arguments().any(|arg| arg.get_id() == "profile") {
+        return Ok(None);
+    }
+    // The flag has to be found before clap runs, since its value decides the
+    // defaults clap is given.
+    let mut name = None;
+    let mut args = args.iter().skip(1).map(|arg| arg.to_string_lossy());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        } else if arg == "--profile" {
+            name = args.next().map(|value| value.into_owned());
+        } else if let Some(value) = arg.strip_prefix("--profile=") {
+            name = Some(value.to_string());
+        }
+    }
+    let name = name.or_else(|| env::var(PROFILE_ENV).ok());
+    let config = match (&name, config_path()) {
+        (Some(_), Some(path)) => Config::from_file(path)?,
+        (Some(name), None) => {
+            return Err(ExampleError::Config(format!(
+                "--profile {name} given but no config file location is known; set {CONFIG_ENV}"
+            )));
+        }
+        (None, _) => Config::load()?,
+    };
+    Ok(config.profile(name.as_deref())?.cloned())
+}
+
+/// Installs the profile's values as argument defaults, on the command and
+/// every subcommand that declares them.
+fn with_profile(mut command: Command, profile: &Profile) -> Command {
+    let defaults = [
+        ("rpc_url", profile.rpc_url.clone()),
+        ("ws_url", profile.ws_url.clone()),
+        ("commitment", profile.commitment.clone()),
+        (
+            "keypair",
+            profile
+                .keypair
+                .as_deref()
+                .map(|path| expand_home(path).display().to_string()),
+        ),
+    ];
+    for (id, value) in defaults {
+        let Some(value) = value else { continue };
+        if command.get_arguments().any(|arg| arg.get_id() == id) {
+            // clap wants 'static defaults; these live for the whole process
+            // anyway.
+            let value: &'static str = value.leak();
+            command = command.mut_arg(id, |arg| arg.default_value(value));
+        }
+    }
+    let subcommands: Vec<String> = command
+        .get_subcommands()
+        .map(|sub| sub.get_name().to_string())
+        .collect();
+    for name in subcommands {
+        command = command.mut_subcommand(name, |sub| with_profile(sub, profile));
+    }
+    command
+}
+
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.to_path_buf(),
+    }
+}
@@ -0,0 +1,347 @@
+//! Request budgeting for metered endpoints.
+//!
+//! QuickNode plans cap the requests per second and meter usage in API
+//! credits, each method costing some number of them. Once a [`Scheduler`] is
+//! [`install`]ed, every client built by [`crate::client`] asks it before
+//! sending a request: calls beyond the rate either wait for their turn
+//! ([`Overflow::Queue`]) or fail at once ([`Overflow::Shed`]), and calls that
+//! would overspend the monthly budget fail in either mode. [`stats`] reports
+//! what has been consumed, and the credits show up in [`crate::metrics`].
+//!
+//! Credits are charged when a request is sent, whatever its outcome. With a
+//! ledger file the month's total carries over between runs (and starts from
+//! zero when the month changes); without one only this process is counted.
+//! The file is rewritten at most every [`LEDGER_FLUSH_INTERVAL`], off the
+//! path of most requests, and once more by [`flush`] as the example exits
+//! through [`crate::report`].
+//! [`CreditArgs`](crate::cli::CreditArgs) sets all of this up from flags.
+
+use crate::error::{ExampleError, Result};
+use crate::metrics;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Credits charged for a method missing from [`CreditTable::methods`].
+/// QuickNode bills most Solana methods alike; check your plan's pricing
+/// page and override the methods it prices differently.
+pub const DEFAULT_METHOD_CREDITS: u64 = 30;
+
+/// How long charges may sit in memory before the ledger file is rewritten.
+pub const LEDGER_FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+static SCHEDULER: OnceLock<Scheduler> = OnceLock::new();
+
+/// What happens to a call that arrives faster than the rate allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait until the call fits under the rate.
+    Queue,
+    /// Fail the call without sending it.
+    Shed,
+}
+
+/// Credits charged per JSON-RPC method.
+#[derive(Debug, Clone)]
+pub struct CreditTable {
+    pub default: u64,
+    /// Keyed by method name, e.g. `getProgramAccounts`
+    pub methods: HashMap<String, u64>,
+}
+
+impl Default for CreditTable {
+    fn default() -> Self {
+        Self {
+            default: DEFAULT_METHOD_CREDITS,
+            methods: HashMap::new(),
+        }
+    }
+}
+
+impl CreditTable {
+    pub fn cost(&self, method: &str) -> u64 {
+        self.methods.get(method).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SchedulerConfig {
+    /// Requests per second; `None` leaves the rate alone.
+    pub max_rps: Option<f64>,
+    /// Requests that may go out back to back before the rate applies.
+    pub burst: u32,
+    pub overflow: Overflow,
+    /// Credits that may be spent per calendar month (UTC); `None` only
+    /// counts them.
+    pub monthly_credits: Option<u64>,
+    pub credits: CreditTable,
+    /// File keeping the month's spending between runs.
+    pub ledger: Option<PathBuf>,
+}
+
+/// Consumption so far, as returned by [`stats`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreditStats {
+    /// Requests sent by this process
+    pub requests: u64,
+    /// Credits charged by this process
+    pub credits: u64,
+    /// Credits charged this month, including earlier runs recorded in the
+    /// ledger
+    pub month_credits: u64,
+    pub monthly_budget: Option<u64>,
+    /// Calls that had to wait for the rate
+    pub queued: u64,
+    /// Total time those calls waited, in milliseconds
+    pub queued_ms: u64,
+    /// Calls failed for exceeding the rate or the budget
+    pub shed: u64,
+    pub by_method: BTreeMap<String, MethodStats>,
+}
+
+impl fmt::Display for CreditStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} requests, {} credits; {} credits this month",
+            self.requests, self.credits, self.month_credits
+        )?;
+        if let Some(budget) = self.monthly_budget {
+            write!(f, " of {budget}")?;
+        }
+        if self.queued > 0 {
+            write!(f, "; {} queued for {} ms", self.queued, self.queued_ms)?;
+        }
+        if self.shed > 0 {
+            write!(f, "; {} shed", self.shed)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MethodStats {
+    pub requests: u64,
+    pub credits: u64,
+}
+
+/// The month's spending as kept in the ledger file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Ledger {
+    /// `YYYY-MM`, in UTC
+    month: String,
+    credits: u64,
+    requests: u64,
+}
+
+pub struct Scheduler {
+    config: SchedulerConfig,
+    state: Mutex<State>,
+}
+
+struct State {
+    /// When the next request would be on schedule, in the sense of the
+    /// generic cell rate algorithm: a request may go out once it is no more
+    /// than the burst allowance ahead of this.
+    next_slot: Instant,
+    ledger: Ledger,
+    stats: CreditStats,
+    /// Whether charges were made since the ledger file was last written.
+    ledger_dirty: bool,
+    ledger_written: Instant,
+    /// Whether a failed ledger write was already logged.
+    ledger_warned: bool,
+}
+
+impl Scheduler {
+    /// Reads the ledger, if one is configured and exists.
+    pub fn new(config: SchedulerConfig) -> Result<Self> {
+        let month = current_month();
+        let ledger = match &config.ledger {
+            Some(path) if path.exists() => {
+                let text = fs::read_to_string(path)?;
+                let ledger: Ledger = serde_json::from_str(&text).map_err(|err| {
+                    ExampleError::Parse(format!("credit ledger {}: {err}", path.display()))
+                })?;
+                if ledger.month == month {
+                    ledger
+                } else {
+                    Ledger::default()
+                }
+            }
+            _ => Ledger::default(),
+        };
+        let ledger = Ledger { month, ..ledger };
+        let stats = CreditStats {
+            month_credits: ledger.credits,
+            monthly_budget: config.monthly_credits,
+            ..CreditStats::default()
+        };
+        Ok(Self {
+            config,
+            state: Mutex::new(State {
+                next_slot: Instant::now(),
+                ledger,
+                stats,
+                ledger_dirty: false,
+                ledger_written: Instant::now(),
+                ledger_warned: false,
+            }),
+        })
+    }
+
+    /// Waits until `method` may be sent and charges it, or fails if it is
+    /// shed or the budget is spent. The error says why, and is returned to
+    /// the caller as a custom client error.
+    pub async fn acquire(&self, method: &str) -> std::result::Result<(), String> {
+        let cost = self.config.credits.cost(method);
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            if let Err(err) = self.check_budget(&mut state, method, cost) {
+                state.stats.shed += 1;
+                return Err(err);
+            }
+            let wait = match self.reserve(&mut state) {
+                Some(wait) => wait,
+                None => {
+                    state.stats.shed += 1;
+                    return Err(format!(
+                        "{method} shed: over the limit of {} requests per second",
+                        self.config.max_rps.unwrap_or_default()
+                    ));
+                }
+            };
+            if !wait.is_zero() {
+                state.stats.queued += 1;
+                state.stats.queued_ms += wait.as_millis() as u64;
+            }
+            self.charge(&mut state, method, cost);
+            wait
+        };
+        metrics::record_credits(method, cost);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> CreditStats {
+        self.state.lock().unwrap().stats.clone()
+    }
+
+    /// Writes charges not yet in the ledger file.
+    pub fn flush(&self) {
+        let mut state = self.state.lock().unwrap();
+        if state.ledger_dirty {
+            self.write_ledger(&mut state);
+        }
+    }
+
+    fn check_budget(
+        &self,
+        state: &mut State,
+        method: &str,
+        cost: u64,
+    ) -> std::result::Result<(), String> {
+        // A run spanning midnight at the end of the month starts afresh.
+        let month = current_month();
+        if state.ledger.month != month {
+            state.ledger = Ledger {
+                month,
+                ..Ledger::default()
+            };
+            state.stats.month_credits = 0;
+        }
+        match self.config.monthly_credits {
+            Some(budget) if state.ledger.credits + cost > budget => Err(format!(
+                "{method} not sent: it costs {cost} credits and {} of the monthly budget of \
+                 {budget} are left",
+                budget.saturating_sub(state.ledger.credits)
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Books the next slot under the rate, returning how long to wait for
+    /// it, or `None` when the call has to wait and is to be shed instead.
+    fn reserve(&self, state: &mut State) -> Option<Duration> {
+        let Some(rps) = self.config.max_rps else {
+            return Some(Duration::ZERO);
+        };
+        let interval = Duration::from_secs_f64(1.0 / rps);
+        let allowance = interval * self.config.burst.saturating_sub(1);
+        let now = Instant::now();
+        let slot = state.next_slot.max(now);
+        let wait = slot.saturating_duration_since(now + allowance);
+        if !wait.is_zero() && self.config.overflow == Overflow::Shed {
+            return None;
+        }
+        state.next_slot = slot + interval;
+        Some(wait)
+    }
+
+    fn charge(&self, state: &mut State, method: &str, cost: u64) {
+        state.ledger.credits += cost;
+        state.ledger.requests += 1;
+        let stats = &mut state.stats;
+        stats.requests += 1;
+        stats.credits += cost;
+        stats.month_credits = state.ledger.credits;
+        let by_method = stats.by_method.entry(method.to_string()).or_default();
+        by_method.requests += 1;
+        by_method.credits += cost;
+
+        state.ledger_dirty = true;
+        if state.ledger_written.elapsed() >= LEDGER_FLUSH_INTERVAL {
+            self.write_ledger(state);
+        }
+    }
+
+    fn write_ledger(&self, state: &mut State) {
+        let Some(path) = &self.config.ledger else {
+            return;
+        };
+        state.ledger_dirty = false;
+        state.ledger_written = Instant::now();
+        let written = serde_json::to_vec_pretty(&state.ledger)
+            .map_err(std::io::Error::other)
+            .and_then(|json| fs::write(path, json));
+        if let (Err(err), false) = (written, state.ledger_warned) {
+            state.ledger_warned = true;
+            warn!("could not update credit ledger {}: {err}", path.display());
+        }
+    }
+}
+
+/// Makes `scheduler` the one every client consults. Only the first call has
+/// an effect.
+pub fn install(scheduler: Scheduler) {
+    let _ = SCHEDULER.set(scheduler);
+}
+
+/// The installed scheduler, if any.
+pub fn scheduler() -> Option<&'static Scheduler> {
+    SCHEDULER.get()
+}
+
+/// Consumption so far, if a scheduler is installed.
+pub fn stats() -> Option<CreditStats> {
+    scheduler().map(Scheduler::stats)
+}
+
+/// Writes the installed scheduler's pending charges to its ledger file.
+pub fn flush() {
+    if let Some(scheduler) = scheduler() {
+        scheduler.flush();
+    }
+}
+
+fn current_month() -> String {
+    Utc::now().format("%Y-%m").to_string()
+}
@@ -0,0 +1,147 @@
+//! Typed access to the Digital Asset Standard (DAS) API.
+//!
+//! DAS is not part of the core Solana RPC, so the methods go through
+//! [`RpcClient::send`] with custom method names and named (object) params.
+//! Only the fields the examples use are modelled; addresses are kept as the
+//! base58 strings the API returns.
+
+use crate::error::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Asset {
+    pub id: String,
+    pub interface: String,
+    pub content: Option<Content>,
+    pub compression: Option<Compression>,
+    pub ownership: Ownership,
+    pub royalty: Option<Royalty>,
+    #[serde(default)]
+    pub creators: Vec<Creator>,
+    #[serde(default)]
+    pub grouping: Vec<Grouping>,
+    #[serde(default)]
+    pub mutable: bool,
+    #[serde(default)]
+    pub burnt: bool,
+}
+
+impl Asset {
+    pub fn name(&self) -> Option<&str> {
+        self.content.as_ref()?.metadata.name.as_deref()
+    }
+
+    pub fn is_compressed(&self) -> bool {
+        self.compression.as_ref().is_some_and(|c| c.compressed)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Content {
+    pub json_uri: Option<String>,
+    #[serde(default)]
+    pub metadata: ContentMetadata,
+    #[serde(default)]
+    pub links: Links,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct ContentMetadata {
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct Links {
+    pub image: Option<String>,
+    pub external_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Compression {
+    pub compressed: bool,
+    pub tree: String,
+    pub leaf_id: u64,
+    pub seq: u64,
+    pub data_hash: String,
+    pub creator_hash: String,
+    pub asset_hash: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Ownership {
+    pub owner: String,
+    pub delegate: Option<String>,
+    pub delegated: bool,
+    pub frozen: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Royalty {
+    pub basis_points: u16,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Creator {
+    pub address: String,
+    pub share: u8,
+    pub verified: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Grouping {
+    pub group_key: String,
+    pub group_value: Option<String>,
+}
+
+/// One page of a paginated asset listing.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AssetList {
+    pub total: u32,
+    pub limit: u32,
+    pub page: Option<u32>,
+    pub items: Vec<Asset>,
+}
+
+/// Merkle proof of a compressed asset's leaf, as needed by Bubblegum
+/// instructions.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AssetProof {
+    pub root: String,
+    pub proof: Vec<String>,
+    pub node_index: u64,
+    pub leaf: String,
+    pub tree_id: String,
+}
+
+fn call<T: DeserializeOwned>(client: &RpcClient, method: &'static str, params: Value) -> Result<T> {
+    Ok(client.send(RpcRequest::Custom { method }, params)?)
+}
+
+pub fn get_asset(client: &RpcClient, id: &Pubkey) -> Result<Asset> {
+    call(client, "getAsset", json!({ "id": id.to_string() }))
+}
+
+/// `page` is 1-based; DAS caps `limit` at 1000.
+pub fn get_assets_by_owner(
+    client: &RpcClient,
+    owner: &Pubkey,
+    page: u32,
+    limit: u32,
+) -> Result<AssetList> {
+    call(
+        client,
+        "getAssetsByOwner",
+        json!({ "ownerAddress": owner.to_string(), "page": page, "limit": limit }),
+    )
+}
+
+pub fn get_asset_proof(client: &RpcClient, id: &Pubkey) -> Result<AssetProof> {
+    call(client, "getAssetProof", json!({ "id": id.to_string() }))
+}
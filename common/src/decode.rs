@@ -0,0 +1,579 @@
+//! Turning raw instructions back into something a person can read.
+//!
+//! A [`Decoder`] holds one [`InstructionDecoder`] per program it understands
+//! and falls back to the bare program ID for everything else. The default
+//! set covers the System, Compute Budget, Token, Token-2022, Associated
+//! Token Account and Memo programs; implement [`InstructionDecoder`] and
+//! [`register`](Decoder::register) it to teach the decoder your own program.
+
+use crate::error::{ExampleError, Result};
+use crate::token;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_system_interface::instruction::SystemInstruction;
+use solana_system_interface::program as system_program;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{
+    UiInstruction, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+use spl_token_2022::instruction::TokenInstruction;
+use std::fmt;
+
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+pub const MEMO_V1_PROGRAM_ID: Pubkey = pubkey!("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo");
+const COMPUTE_BUDGET_PROGRAM_ID: Pubkey = pubkey!("ComputeBudget111111111111111111111111111111");
+
+/// An instruction's program, decoded type and labelled arguments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedInstruction {
+    /// Instruction type, e.g. `transfer` or `createAccount`.
+    pub kind: String,
+    /// Named arguments and accounts, in display order.
+    pub fields: Vec<(String, String)>,
+}
+
+impl DecodedInstruction {
+    pub fn new(kind: impl Into<String>) -> Self {
+        Self {
+            kind: kind.into(),
+            fields: Vec::new(),
+        }
+    }
+
+    pub fn field(mut self, name: impl Into<String>, value: impl fmt::Display) -> Self {
+        self.fields.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Adds the accounts under `names`, pairing them up positionally; extra
+    /// accounts (multisig signers, for instance) are left out.
+    pub fn accounts(mut self, names: &[&str], accounts: &[Pubkey]) -> Self {
+        for (name, account) in names.iter().zip(accounts) {
+            self.fields.push((name.to_string(), account.to_string()));
+        }
+        self
+    }
+}
+
+/// Decodes the instructions of one or more programs.
+pub trait InstructionDecoder {
+    /// Whether this decoder understands instructions for `program_id`.
+    fn handles(&self, program_id: &Pubkey) -> bool;
+
+    /// Short name of the program, e.g. `System`.
+    fn program_name(&self, program_id: &Pubkey) -> String;
+
+    /// Decodes `data`, with `accounts` in instruction order. Returns `None`
+    /// for data the decoder does not recognize.
+    fn decode(
+        &self,
+        program_id: &Pubkey,
+        data: &[u8],
+        accounts: &[Pubkey],
+    ) -> Option<DecodedInstruction>;
+}
+
+/// One instruction as the [`Decoder`] saw it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionSummary {
+    pub program_id: Pubkey,
+    /// Program name, or the program ID when no decoder handles it.
+    pub program: String,
+    pub decoded: Option<DecodedInstruction>,
+    /// Call depth: 1 for top-level instructions, 2+ for inner (CPI) ones.
+    pub depth: usize,
+}
+
+/// A set of [`InstructionDecoder`]s, consulted in registration order.
+pub struct Decoder {
+    decoders: Vec<Box<dyn InstructionDecoder>>,
+}
+
+impl Default for Decoder {
+    /// A decoder for the System, Compute Budget, token and Memo programs.
+    fn default() -> Self {
+        Self::empty()
+            .register(SystemDecoder)
+            .register(ComputeBudgetDecoder)
+            .register(TokenDecoder)
+            .register(AssociatedTokenDecoder)
+            .register(MemoDecoder)
+    }
+}
+
+impl Decoder {
+    /// A decoder that knows no programs.
+    pub fn empty() -> Self {
+        Self {
+            decoders: Vec::new(),
+        }
+    }
+
+    pub fn register(mut self, decoder: impl InstructionDecoder + 'static) -> Self {
+        self.decoders.push(Box::new(decoder));
+        self
+    }
+
+    pub fn decode(
+        &self,
+        program_id: &Pubkey,
+        data: &[u8],
+        accounts: &[Pubkey],
+        depth: usize,
+    ) -> InstructionSummary {
+        let decoder = self
+            .decoders
+            .iter()
+            .find(|decoder| decoder.handles(program_id));
+        InstructionSummary {
+            program_id: *program_id,
+            program: decoder.map_or_else(
+                || program_id.to_string(),
+                |decoder| decoder.program_name(program_id),
+            ),
+            decoded: decoder.and_then(|decoder| decoder.decode(program_id, data, accounts)),
+            depth,
+        }
+    }
+
+    /// Decodes every instruction of a transaction, each top-level instruction
+    /// followed by the inner instructions it invoked.
+    ///
+    /// `meta` must come from a request with a binary encoding (base64 or
+    /// base58), so that inner instructions arrive in compiled form.
+    pub fn decode_transaction(
+        &self,
+        message: &VersionedMessage,
+        meta: &UiTransactionStatusMeta,
+    ) -> Result<Vec<InstructionSummary>> {
+        let keys = account_keys(message, meta)?;
+        let key = |index: u8| {
+            keys.get(usize::from(index))
+                .copied()
+                .ok_or_else(|| ExampleError::Parse(format!("account index {index} out of range")))
+        };
+        let resolve = |indexes: &[u8]| -> Result<Vec<Pubkey>> {
+            indexes.iter().map(|&index| key(index)).collect()
+        };
+
+        let inner = match &meta.inner_instructions {
+            OptionSerializer::Some(inner) => inner.as_slice(),
+            _ => &[],
+        };
+        let mut summaries = Vec::new();
+        for (position, instruction) in message.instructions().iter().enumerate() {
+            let accounts = resolve(&instruction.accounts)?;
+            summaries.push(self.decode(
+                &key(instruction.program_id_index)?,
+                &instruction.data,
+                &accounts,
+                1,
+            ));
+            let invoked = inner
+                .iter()
+                .filter(|inner| usize::from(inner.index) == position)
+                .flat_map(|inner| &inner.instructions);
+            for instruction in invoked {
+                let UiInstruction::Compiled(compiled) = instruction else {
+                    return Err(ExampleError::Parse(
+                        "inner instructions are not in compiled form".to_string(),
+                    ));
+                };
+                let data = bs58::decode(&compiled.data)
+                    .into_vec()
+                    .map_err(|err| ExampleError::Parse(format!("inner instruction data: {err}")))?;
+                let accounts = resolve(&compiled.accounts)?;
+                summaries.push(self.decode(
+                    &key(compiled.program_id_index)?,
+                    &data,
+                    &accounts,
+                    compiled.stack_height.map_or(2, |height| height as usize),
+                ));
+            }
+        }
+        Ok(summaries)
+    }
+}
+
+/// The transaction's full account list: the message's static keys followed
+/// by the writable and then the read-only addresses loaded from lookup
+/// tables, which is the order instruction account indexes refer to.
+pub fn account_keys(
+    message: &VersionedMessage,
+    meta: &UiTransactionStatusMeta,
+) -> Result<Vec<Pubkey>> {
+    let mut keys = message.static_account_keys().to_vec();
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        for address in loaded.writable.iter().chain(&loaded.readonly) {
+            keys.push(address.parse()?);
+        }
+    }
+    Ok(keys)
+}
+
+struct SystemDecoder;
+
+impl InstructionDecoder for SystemDecoder {
+    fn handles(&self, program_id: &Pubkey) -> bool {
+        *program_id == system_program::ID
+    }
+
+    fn program_name(&self, _program_id: &Pubkey) -> String {
+        "System".to_string()
+    }
+
+    fn decode(
+        &self,
+        _program_id: &Pubkey,
+        data: &[u8],
+        accounts: &[Pubkey],
+    ) -> Option<DecodedInstruction> {
+        let decoded = match bincode::deserialize(data).ok()? {
+            SystemInstruction::CreateAccount {
+                lamports,
+                space,
+                owner,
+            } => DecodedInstruction::new("createAccount")
+                .accounts(&["source", "newAccount"], accounts)
+                .field("lamports", lamports)
+                .field("space", space)
+                .field("owner", owner),
+            SystemInstruction::CreateAccountWithSeed {
+                base,
+                seed,
+                lamports,
+                space,
+                owner,
+            } => DecodedInstruction::new("createAccountWithSeed")
+                .accounts(&["source", "newAccount"], accounts)
+                .field("base", base)
+                .field("seed", seed)
+                .field("lamports", lamports)
+                .field("space", space)
+                .field("owner", owner),
+            SystemInstruction::Assign { owner } => DecodedInstruction::new("assign")
+                .accounts(&["account"], accounts)
+                .field("owner", owner),
+            SystemInstruction::Transfer { lamports } => DecodedInstruction::new("transfer")
+                .accounts(&["source", "destination"], accounts)
+                .field("lamports", lamports),
+            SystemInstruction::TransferWithSeed {
+                lamports,
+                from_seed,
+                from_owner,
+            } => DecodedInstruction::new("transferWithSeed")
+                .accounts(&["source", "base", "destination"], accounts)
+                .field("lamports", lamports)
+                .field("seed", from_seed)
+                .field("owner", from_owner),
+            SystemInstruction::Allocate { space } => DecodedInstruction::new("allocate")
+                .accounts(&["account"], accounts)
+                .field("space", space),
+            SystemInstruction::AdvanceNonceAccount => {
+                DecodedInstruction::new("advanceNonce").accounts(&["nonceAccount"], accounts)
+            }
+            SystemInstruction::WithdrawNonceAccount(lamports) => {
+                DecodedInstruction::new("withdrawFromNonce")
+                    .accounts(&["nonceAccount", "destination"], accounts)
+                    .field("lamports", lamports)
+            }
+            SystemInstruction::InitializeNonceAccount(authority) => {
+                DecodedInstruction::new("initializeNonce")
+                    .accounts(&["nonceAccount"], accounts)
+                    .field("authority", authority)
+            }
+            SystemInstruction::AuthorizeNonceAccount(authority) => {
+                DecodedInstruction::new("authorizeNonce")
+                    .accounts(&["nonceAccount"], accounts)
+                    .field("newAuthority", authority)
+            }
+            other => DecodedInstruction::new(variant_name(&other)),
+        };
+        Some(decoded)
+    }
+}
+
+struct ComputeBudgetDecoder;
+
+impl InstructionDecoder for ComputeBudgetDecoder {
+    fn handles(&self, program_id: &Pubkey) -> bool {
+        *program_id == COMPUTE_BUDGET_PROGRAM_ID
+    }
+
+    fn program_name(&self, _program_id: &Pubkey) -> String {
+        "Compute Budget".to_string()
+    }
+
+    /// The instructions are a one-byte tag followed by a little-endian
+    /// integer, so they are read directly rather than through borsh.
+    fn decode(
+        &self,
+        _program_id: &Pubkey,
+        data: &[u8],
+        _accounts: &[Pubkey],
+    ) -> Option<DecodedInstruction> {
+        let (&tag, value) = data.split_first()?;
+        let u32_value = || Some(u32::from_le_bytes(value.get(..4)?.try_into().ok()?));
+        let decoded = match tag {
+            1 => DecodedInstruction::new("requestHeapFrame").field("bytes", u32_value()?),
+            2 => DecodedInstruction::new("setComputeUnitLimit").field("units", u32_value()?),
+            3 => DecodedInstruction::new("setComputeUnitPrice").field(
+                "microLamports",
+                u64::from_le_bytes(value.get(..8)?.try_into().ok()?),
+            ),
+            4 => DecodedInstruction::new("setLoadedAccountsDataSizeLimit")
+                .field("bytes", u32_value()?),
+            _ => return None,
+        };
+        Some(decoded)
+    }
+}
+
+/// Decodes both token programs; Token-2022's instruction set is a superset
+/// of the legacy one with the same encoding.
+struct TokenDecoder;
+
+impl InstructionDecoder for TokenDecoder {
+    fn handles(&self, program_id: &Pubkey) -> bool {
+        token::TOKEN_PROGRAMS.contains(program_id)
+    }
+
+    fn program_name(&self, program_id: &Pubkey) -> String {
+        token::program_name(program_id).to_string()
+    }
+
+    fn decode(
+        &self,
+        _program_id: &Pubkey,
+        data: &[u8],
+        accounts: &[Pubkey],
+    ) -> Option<DecodedInstruction> {
+        let decoded = match TokenInstruction::unpack(data).ok()? {
+            TokenInstruction::InitializeMint {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            }
+            | TokenInstruction::InitializeMint2 {
+                decimals,
+                mint_authority,
+                freeze_authority,
+            } => DecodedInstruction::new("initializeMint")
+                .accounts(&["mint"], accounts)
+                .field("decimals", decimals)
+                .field("mintAuthority", mint_authority)
+                .field(
+                    "freezeAuthority",
+                    Option::<Pubkey>::from(freeze_authority)
+                        .map_or_else(|| "none".to_string(), |key| key.to_string()),
+                ),
+            TokenInstruction::InitializeAccount => DecodedInstruction::new("initializeAccount")
+                .accounts(&["account", "mint", "owner"], accounts),
+            TokenInstruction::InitializeAccount2 { owner }
+            | TokenInstruction::InitializeAccount3 { owner } => {
+                DecodedInstruction::new("initializeAccount")
+                    .accounts(&["account", "mint"], accounts)
+                    .field("owner", owner)
+            }
+            // Deprecated in favour of TransferChecked, but still widely sent.
+            #[allow(deprecated)]
+            TokenInstruction::Transfer { amount } => DecodedInstruction::new("transfer")
+                .accounts(&["source", "destination", "authority"], accounts)
+                .field("amount", amount),
+            TokenInstruction::TransferChecked { amount, decimals } => {
+                DecodedInstruction::new("transferChecked")
+                    .accounts(&["source", "mint", "destination", "authority"], accounts)
+                    .field("amount", amount)
+                    .field("decimals", decimals)
+            }
+            TokenInstruction::MintTo { amount } => DecodedInstruction::new("mintTo")
+                .accounts(&["mint", "account", "mintAuthority"], accounts)
+                .field("amount", amount),
+            TokenInstruction::MintToChecked { amount, decimals } => {
+                DecodedInstruction::new("mintToChecked")
+                    .accounts(&["mint", "account", "mintAuthority"], accounts)
+                    .field("amount", amount)
+                    .field("decimals", decimals)
+            }
+            TokenInstruction::Burn { amount } => DecodedInstruction::new("burn")
+                .accounts(&["account", "mint", "authority"], accounts)
+                .field("amount", amount),
+            TokenInstruction::BurnChecked { amount, decimals } => {
+                DecodedInstruction::new("burnChecked")
+                    .accounts(&["account", "mint", "authority"], accounts)
+                    .field("amount", amount)
+                    .field("decimals", decimals)
+            }
+            TokenInstruction::Approve { amount } => DecodedInstruction::new("approve")
+                .accounts(&["source", "delegate", "owner"], accounts)
+                .field("amount", amount),
+            TokenInstruction::ApproveChecked { amount, decimals } => {
+                DecodedInstruction::new("approveChecked")
+                    .accounts(&["source", "mint", "delegate", "owner"], accounts)
+                    .field("amount", amount)
+                    .field("decimals", decimals)
+            }
+            TokenInstruction::Revoke => {
+                DecodedInstruction::new("revoke").accounts(&["source", "owner"], accounts)
+            }
+            TokenInstruction::SetAuthority {
+                authority_type,
+                new_authority,
+            } => DecodedInstruction::new("setAuthority")
+                .accounts(&["account", "authority"], accounts)
+                .field("authorityType", format!("{authority_type:?}"))
+                .field(
+                    "newAuthority",
+                    Option::<Pubkey>::from(new_authority)
+                        .map_or_else(|| "none".to_string(), |key| key.to_string()),
+                ),
+            TokenInstruction::CloseAccount => DecodedInstruction::new("closeAccount")
+                .accounts(&["account", "destination", "owner"], accounts),
+            TokenInstruction::FreezeAccount => DecodedInstruction::new("freezeAccount")
+                .accounts(&["account", "mint", "freezeAuthority"], accounts),
+            TokenInstruction::ThawAccount => DecodedInstruction::new("thawAccount")
+                .accounts(&["account", "mint", "freezeAuthority"], accounts),
+            TokenInstruction::SyncNative => {
+                DecodedInstruction::new("syncNative").accounts(&["account"], accounts)
+            }
+            // Extension instructions carry their own nested instruction
+            // enums; the variant name is enough to tell them apart here.
+            other => DecodedInstruction::new(variant_name(&other)),
+        };
+        Some(decoded)
+    }
+}
+
+struct AssociatedTokenDecoder;
+
+impl InstructionDecoder for AssociatedTokenDecoder {
+    fn handles(&self, program_id: &Pubkey) -> bool {
+        *program_id == spl_associated_token_account_client::program::ID
+    }
+
+    fn program_name(&self, _program_id: &Pubkey) -> String {
+        "Associated Token Account".to_string()
+    }
+
+    fn decode(
+        &self,
+        _program_id: &Pubkey,
+        data: &[u8],
+        accounts: &[Pubkey],
+    ) -> Option<DecodedInstruction> {
+        let kind = match data.first() {
+            None | Some(0) => "create",
+            Some(1) => "createIdempotent",
+            Some(2) => "recoverNested",
+            Some(_) => return None,
+        };
+        Some(DecodedInstruction::new(kind).accounts(
+            &[
+                "payer",
+                "account",
+                "wallet",
+                "mint",
+                "systemProgram",
+                "tokenProgram",
+            ],
+            accounts,
+        ))
+    }
+}
+
+struct MemoDecoder;
+
+impl InstructionDecoder for MemoDecoder {
+    fn handles(&self, program_id: &Pubkey) -> bool {
+        *program_id == MEMO_PROGRAM_ID || *program_id == MEMO_V1_PROGRAM_ID
+    }
+
+    fn program_name(&self, _program_id: &Pubkey) -> String {
+        "Memo".to_string()
+    }
+
+    fn decode(
+        &self,
+        _program_id: &Pubkey,
+        data: &[u8],
+        accounts: &[Pubkey],
+    ) -> Option<DecodedInstruction> {
+        let mut decoded =
+            DecodedInstruction::new("memo").field("text", String::from_utf8_lossy(data));
+        for signer in accounts {
+            decoded = decoded.field("signer", signer);
+        }
+        Some(decoded)
+    }
+}
+
+/// `FooBar { .. }` -> `fooBar`, for instructions without a dedicated arm.
+fn variant_name(value: &impl fmt::Debug) -> String {
+    let debug = format!("{value:?}");
+    let name = debug
+        .split(|c: char| !c.is_alphanumeric())
+        .next()
+        .unwrap_or_default();
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// A token account whose balance a transaction changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenBalanceChange {
+    /// Index into the transaction's [`account_keys`].
+    pub account_index: usize,
+    pub mint: String,
+    pub owner: Option<String>,
+    pub decimals: u8,
+    pub before: u64,
+    pub after: u64,
+}
+
+/// Pairs up the pre- and post-transaction token balances by account and
+/// keeps the ones that changed, ordered by account index. Accounts created
+/// or closed by the transaction appear on only one side and count as zero on
+/// the other.
+pub fn token_balance_changes(meta: &UiTransactionStatusMeta) -> Vec<TokenBalanceChange> {
+    let (OptionSerializer::Some(pre), OptionSerializer::Some(post)) =
+        (&meta.pre_token_balances, &meta.post_token_balances)
+    else {
+        return Vec::new();
+    };
+    let amount = |balance: &UiTransactionTokenBalance| {
+        balance.ui_token_amount.amount.parse::<u64>().unwrap_or(0)
+    };
+    let find = |balances: &[UiTransactionTokenBalance], index: u8| {
+        balances
+            .iter()
+            .find(|balance| balance.account_index == index)
+            .map_or(0, amount)
+    };
+    let closed = pre.iter().filter(|before| {
+        !post
+            .iter()
+            .any(|after| after.account_index == before.account_index)
+    });
+    let mut changes: Vec<_> = post
+        .iter()
+        .chain(closed)
+        .map(|balance| TokenBalanceChange {
+            account_index: usize::from(balance.account_index),
+            mint: balance.mint.clone(),
+            owner: match &balance.owner {
+                OptionSerializer::Some(owner) => Some(owner.clone()),
+                _ => None,
+            },
+            decimals: balance.ui_token_amount.decimals,
+            before: find(pre, balance.account_index),
+            after: find(post, balance.account_index),
+        })
+        .filter(|change| change.before != change.after)
+        .collect();
+    changes.sort_by_key(|change| change.account_index);
+    changes
+}
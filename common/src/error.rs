@@ -0,0 +1,129 @@
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::pubsub_client::PubsubClientError;
+use solana_client::rpc_request::RpcError;
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::pubkey::{ParsePubkeyError, Pubkey};
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::SignerError;
+use solana_sdk::transaction::TransactionError;
+use thiserror::Error;
+
+pub type Result<T, E = ExampleError> = std::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum ExampleError {
+    /// The RPC call itself failed (transport, HTTP or JSON-RPC error).
+    #[error("RPC request failed: {0}")]
+    Rpc(Box<ClientError>),
+
+    /// The websocket connection or subscription failed.
+    #[error("websocket subscription failed: {0}")]
+    Pubsub(Box<PubsubClientError>),
+
+    /// A gRPC (Yellowstone Geyser) connection or stream failed.
+    #[error("gRPC stream failed: {0}")]
+    Grpc(String),
+
+    #[error("invalid public key: {0}")]
+    Pubkey(#[from] ParsePubkeyError),
+
+    /// Data returned by the node could not be decoded.
+    #[error("failed to parse {0}")]
+    Parse(String),
+
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    /// `simulateTransaction` reported that the transaction would fail.
+    #[error("simulation failed: {0}")]
+    SimulationFailed(TransactionError),
+
+    /// The transaction landed but its execution failed.
+    #[error("transaction failed: {0}")]
+    TransactionFailed(TransactionError),
+
+    /// The blockhash expired on every attempt before the transaction landed.
+    #[error("transaction {0} expired before it was confirmed")]
+    TransactionExpired(Signature),
+
+    #[error("signing failed: {0}")]
+    Signer(#[from] SignerError),
+
+    #[error("keypair error: {0}")]
+    Keypair(String),
+
+    /// A plain HTTP request (off-chain metadata, third-party APIs) failed.
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// A local database used to persist results failed.
+    #[error("database error: {0}")]
+    Database(String),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The config file could not be read, or names a profile that is missing.
+    #[error("config error: {0}")]
+    Config(String),
+
+    /// A user-supplied value was rejected after argument parsing, e.g. an
+    /// amount with more decimals than the mint supports.
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+}
+
+impl ExampleError {
+    /// Whether the failure is worth retrying, i.e. it happened in transport,
+    /// was a server-side hiccup or a rate limit, rather than being a
+    /// definitive answer from the node.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Rpc(err) if is_retryable(err))
+    }
+
+    /// Whether the endpoint rejected the request for exceeding its rate
+    /// limit, either with HTTP 429 or a JSON-RPC "Too many requests" error.
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Self::Rpc(err) if is_rate_limited(err))
+    }
+}
+
+/// [`ExampleError::is_retryable`] for an error still in the client's hands,
+/// e.g. inside an [`RpcSender`](solana_client::rpc_sender::RpcSender).
+pub(crate) fn is_retryable(err: &ClientError) -> bool {
+    match &err.kind {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(err) => err
+            .status()
+            .is_none_or(|status| status.as_u16() == 429 || status.is_server_error()),
+        _ => is_rate_limited(err),
+    }
+}
+
+pub(crate) fn is_rate_limited(err: &ClientError) -> bool {
+    match &err.kind {
+        ClientErrorKind::Reqwest(err) => err.status().is_some_and(|status| status.as_u16() == 429),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. }) => {
+            *code == 429 || message.to_ascii_lowercase().contains("too many requests")
+        }
+        _ => false,
+    }
+}
+
+impl From<ClientError> for ExampleError {
+    fn from(err: ClientError) -> Self {
+        Self::Rpc(Box::new(err))
+    }
+}
+
+impl From<PubsubClientError> for ExampleError {
+    fn from(err: PubsubClientError) -> Self {
+        Self::Pubsub(Box::new(err))
+    }
+}
+
+impl From<ProgramError> for ExampleError {
+    fn from(err: ProgramError) -> Self {
+        Self::Parse(format!("account data: {err}"))
+    }
+}
@@ -0,0 +1,33 @@
+//! Links to the Solana Explorer for whichever cluster an RPC URL points at.
+
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+pub fn transaction_url(signature: &Signature, rpc_url: &str) -> String {
+    format!(
+        "https://explorer.solana.com/tx/{signature}{}",
+        cluster_query(rpc_url)
+    )
+}
+
+pub fn address_url(address: &Pubkey, rpc_url: &str) -> String {
+    format!(
+        "https://explorer.solana.com/address/{address}{}",
+        cluster_query(rpc_url)
+    )
+}
+
+/// Guesses the cluster from the endpoint URL. QuickNode endpoints carry the
+/// network in their hostname (`*.solana-devnet.quiknode.pro`), as do the
+/// public ones; anything on localhost is treated as a test validator.
+fn cluster_query(rpc_url: &str) -> String {
+    if rpc_url.contains("devnet") {
+        "?cluster=devnet".to_string()
+    } else if rpc_url.contains("testnet") {
+        "?cluster=testnet".to_string()
+    } else if rpc_url.contains("localhost") || rpc_url.contains("127.0.0.1") {
+        format!("?cluster=custom&customUrl={rpc_url}")
+    } else {
+        String::new()
+    }
+}
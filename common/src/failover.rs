@@ -0,0 +1,292 @@
+//! One client over several endpoints, so an example keeps running through
+//! an endpoint's outage.
+//!
+//! [`FailoverRpcClient`] takes the endpoints in order of preference and
+//! sends each request to the first one that is currently fit to serve it.
+//! When the request fails in a way [`ExampleError::is_retryable`] would
+//! retry (transport errors, server errors, rate limits), the endpoint is
+//! marked down and the request goes to the next one; answers such as a
+//! missing account come back from the first endpoint as they are. A
+//! background thread runs `getHealth` and `getSlot` against every endpoint
+//! each health interval, which brings endpoints back once they answer again
+//! and passes over any that trail the highest slot by more than
+//! [`MAX_SLOT_LAG`]. As the order is kept otherwise, traffic returns to the
+//! primary as soon as it recovers. The checks are ordinary requests: they
+//! are traced, counted and charged like any other.
+
+use crate::error::{self, ExampleError, Result};
+use crate::logging::{TracingSender, endpoint_label};
+use crate::retry::{RetryPolicy, RetryingRpcClient};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::{Value, json};
+use solana_client::client_error::{ClientError, ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// An endpoint this many slots (about 20 seconds) behind the most advanced
+/// one only serves requests when every endpoint is behind or down.
+pub const MAX_SLOT_LAG: Slot = 50;
+
+/// How one endpoint looked at its last health check or request.
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointStatus {
+    /// Scheme and host only, see [`endpoint_label`]
+    pub endpoint: String,
+    pub healthy: bool,
+    /// Whether it trails the most advanced endpoint by over [`MAX_SLOT_LAG`]
+    pub lagging: bool,
+    pub slot: Option<Slot>,
+    /// Round trip of the last successful health check
+    pub latency_ms: Option<f64>,
+    /// Why it is marked down
+    pub error: Option<String>,
+}
+
+struct Endpoint {
+    sender: TracingSender,
+    label: String,
+    health: Mutex<Health>,
+}
+
+/// Endpoints start out healthy, so requests follow the configured order
+/// until the first check says otherwise.
+#[derive(Default)]
+struct Health {
+    error: Option<String>,
+    slot: Option<Slot>,
+    latency: Option<Duration>,
+}
+
+impl Endpoint {
+    fn mark_down(&self, reason: String) {
+        let mut health = self.health.lock().unwrap();
+        if health.error.is_none() {
+            warn!(endpoint = %self.label, "endpoint down: {reason}");
+        }
+        health.error = Some(reason);
+    }
+
+    /// Checks the endpoint through `checker`, a sender of its own: the
+    /// request sender's connections belong to the client's runtime, which
+    /// only runs while a request is in flight.
+    async fn check(&self, checker: &TracingSender, commitment: CommitmentConfig) {
+        let started = Instant::now();
+        let result = async {
+            checker.send(RpcRequest::GetHealth, Value::Null).await?;
+            let slot = checker
+                .send(RpcRequest::GetSlot, json!([commitment]))
+                .await?;
+            serde_json::from_value::<Slot>(slot)
+                .map_err(|err| ClientError::from(ClientErrorKind::SerdeJson(err)))
+        }
+        .await;
+        match result {
+            Ok(slot) => {
+                let mut health = self.health.lock().unwrap();
+                if health.error.take().is_some() {
+                    info!(endpoint = %self.label, "endpoint back up");
+                }
+                health.slot = Some(slot);
+                health.latency = Some(started.elapsed());
+            }
+            Err(err) => self.mark_down(format!("health check failed: {err}")),
+        }
+    }
+}
+
+struct Pool {
+    endpoints: Vec<Endpoint>,
+    commitment: CommitmentConfig,
+    failovers: AtomicU32,
+}
+
+impl Pool {
+    /// Every endpoint's status, best first: healthy before down, current
+    /// before lagging, then in the configured order.
+    fn ranked(&self) -> Vec<(usize, EndpointStatus)> {
+        let mut statuses: Vec<(usize, EndpointStatus)> = self
+            .endpoints
+            .iter()
+            .enumerate()
+            .map(|(index, endpoint)| {
+                let health = endpoint.health.lock().unwrap();
+                let status = EndpointStatus {
+                    endpoint: endpoint.label.clone(),
+                    healthy: health.error.is_none(),
+                    lagging: false,
+                    slot: health.slot,
+                    latency_ms: health.latency.map(|latency| latency.as_secs_f64() * 1000.0),
+                    error: health.error.clone(),
+                };
+                (index, status)
+            })
+            .collect();
+        let best = statuses.iter().filter_map(|(_, status)| status.slot).max();
+        for (_, status) in &mut statuses {
+            status.lagging = best
+                .zip(status.slot)
+                .is_some_and(|(best, slot)| best - slot > MAX_SLOT_LAG);
+        }
+        // Stable, so ties keep the configured order.
+        statuses.sort_by_key(|(_, status)| (!status.healthy, status.lagging));
+        statuses
+    }
+
+    /// Checks every endpoint each `interval` until the pool is dropped.
+    fn spawn_health_checks(
+        pool: Weak<Self>,
+        checkers: Vec<TracingSender>,
+        interval: Duration,
+    ) -> Result<()> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        thread::Builder::new()
+            .name("health-check".to_string())
+            .spawn(move || {
+                while let Some(pool) = pool.upgrade() {
+                    let checks = pool
+                        .endpoints
+                        .iter()
+                        .zip(&checkers)
+                        .map(|(endpoint, checker)| endpoint.check(checker, pool.commitment));
+                    runtime.block_on(futures::future::join_all(checks));
+                    drop(pool);
+                    thread::sleep(interval);
+                }
+            })?;
+        Ok(())
+    }
+}
+
+/// Sends each request down the ranked endpoints until one answers.
+struct FailoverSender {
+    pool: Arc<Pool>,
+}
+
+#[async_trait]
+impl RpcSender for FailoverSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let ranked = self.pool.ranked();
+        let mut last_error = None;
+        for (attempt, (index, _)) in ranked.iter().enumerate() {
+            let endpoint = &self.pool.endpoints[*index];
+            match endpoint.sender.send(request, params.clone()).await {
+                Err(err) if error::is_retryable(&err) => {
+                    endpoint.mark_down(err.to_string());
+                    if let Some((_, next)) = ranked.get(attempt + 1) {
+                        warn!(
+                            endpoint = %endpoint.label,
+                            "{request} failed, trying {}: {err}",
+                            next.endpoint
+                        );
+                        self.pool.failovers.fetch_add(1, Ordering::Relaxed);
+                    }
+                    last_error = Some(err);
+                }
+                result => return result,
+            }
+        }
+        Err(last_error.expect("the pool has at least one endpoint"))
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        let mut total = RpcTransportStats::default();
+        for endpoint in &self.pool.endpoints {
+            let stats = endpoint.sender.get_transport_stats();
+            total.request_count += stats.request_count;
+            total.elapsed_time += stats.elapsed_time;
+            total.rate_limited_time += stats.rate_limited_time;
+        }
+        total
+    }
+
+    /// The endpoint the next request goes to.
+    fn url(&self) -> String {
+        let (index, _) = self.pool.ranked()[0];
+        self.pool.endpoints[index].sender.url()
+    }
+}
+
+/// A [`RetryingRpcClient`] whose requests fail over between endpoints.
+///
+/// Each attempt [`call`](RetryingRpcClient::call) makes tries every
+/// endpoint in turn, so backing off only starts once all of them have
+/// failed. It derefs to the retrying client.
+pub struct FailoverRpcClient {
+    client: RetryingRpcClient,
+    pool: Arc<Pool>,
+}
+
+impl FailoverRpcClient {
+    /// Builds a client over `urls`, most preferred first, checking their
+    /// health every `health_interval`; slots are compared at `commitment`.
+    pub fn new(
+        urls: &[String],
+        commitment: CommitmentConfig,
+        timeout: Duration,
+        health_interval: Duration,
+        policy: RetryPolicy,
+    ) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(ExampleError::InvalidArgument(
+                "a failover client needs at least one endpoint".to_string(),
+            ));
+        }
+        let pool = Arc::new(Pool {
+            endpoints: urls
+                .iter()
+                .map(|url| Endpoint {
+                    sender: TracingSender::new(url, timeout),
+                    label: endpoint_label(url),
+                    health: Mutex::default(),
+                })
+                .collect(),
+            commitment,
+            failovers: AtomicU32::new(0),
+        });
+        let checkers = urls
+            .iter()
+            .map(|url| TracingSender::new(url, timeout))
+            .collect();
+        Pool::spawn_health_checks(Arc::downgrade(&pool), checkers, health_interval)?;
+        let sender = FailoverSender { pool: pool.clone() };
+        let client = RpcClient::new_sender(sender, RpcClientConfig::with_commitment(commitment));
+        Ok(Self {
+            client: RetryingRpcClient::new(client, policy),
+            pool,
+        })
+    }
+
+    /// Every endpoint's status, the one requests currently go to first.
+    pub fn endpoints(&self) -> Vec<EndpointStatus> {
+        self.pool
+            .ranked()
+            .into_iter()
+            .map(|(_, status)| status)
+            .collect()
+    }
+
+    /// Requests moved on to another endpoint so far.
+    pub fn failovers(&self) -> u32 {
+        self.pool.failovers.load(Ordering::Relaxed)
+    }
+}
+
+impl Deref for FailoverRpcClient {
+    type Target = RetryingRpcClient;
+
+    fn deref(&self) -> &RetryingRpcClient {
+        &self.client
+    }
+}
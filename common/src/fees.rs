@@ -0,0 +1,129 @@
+//! Priority fee estimation from `getRecentPrioritizationFees`, the compute
+//! budget instructions that attach the fee to a transaction, and pricing a
+//! whole transaction with `getFeeForMessage` before it is signed.
+
+use crate::error::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Compute unit limit and price to request for a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriorityFee {
+    pub unit_limit: u32,
+    pub micro_lamports_per_unit: u64,
+}
+
+impl PriorityFee {
+    /// `set_compute_unit_limit` followed by `set_compute_unit_price`.
+    pub fn instructions(&self) -> [Instruction; 2] {
+        [
+            ComputeBudgetInstruction::set_compute_unit_limit(self.unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(self.micro_lamports_per_unit),
+        ]
+    }
+
+    /// Lamports paid on top of the base fee if the whole limit is requested.
+    pub fn max_lamports(&self) -> u64 {
+        let micro_lamports = u128::from(self.unit_limit) * u128::from(self.micro_lamports_per_unit);
+        micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64
+    }
+}
+
+/// Returns `instructions` with the compute budget instructions prepended.
+pub fn with_priority_fee(fee: &PriorityFee, instructions: &[Instruction]) -> Vec<Instruction> {
+    fee.instructions()
+        .into_iter()
+        .chain(instructions.iter().cloned())
+        .collect()
+}
+
+/// Recent per-slot prioritization fees (micro-lamports per compute unit) for
+/// transactions that lock `writable_accounts`, sorted ascending.
+pub fn recent_fees(client: &RpcClient, writable_accounts: &[Pubkey]) -> Result<Vec<u64>> {
+    let mut fees: Vec<u64> = client
+        .get_recent_prioritization_fees(writable_accounts)?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    fees.sort_unstable();
+    Ok(fees)
+}
+
+/// Nearest-rank percentile of already sorted `fees`; `0` when empty.
+pub fn percentile(sorted_fees: &[u64], percentile: u8) -> u64 {
+    if sorted_fees.is_empty() {
+        return 0;
+    }
+    let rank = (usize::from(percentile.min(100)) * sorted_fees.len()).div_ceil(100);
+    sorted_fees[rank.saturating_sub(1)]
+}
+
+/// Picks the given percentile of recent fees for `writable_accounts`.
+pub fn estimate_priority_fee(
+    client: &RpcClient,
+    writable_accounts: &[Pubkey],
+    fee_percentile: u8,
+) -> Result<u64> {
+    Ok(percentile(
+        &recent_fees(client, writable_accounts)?,
+        fee_percentile,
+    ))
+}
+
+/// What a transaction will cost its fee payer, as priced by the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// Signatures the transaction needs.
+    pub signatures: u8,
+    /// Lamports charged per signature, without any compute budget surcharge.
+    pub base_fee: u64,
+    /// Compute budget surcharge: unit limit times unit price.
+    pub priority_fee: u64,
+}
+
+impl FeeEstimate {
+    pub fn total(&self) -> u64 {
+        self.base_fee + self.priority_fee
+    }
+}
+
+/// Prices `instructions`, paid by `payer` and with `priority_fee`'s compute
+/// budget instructions prepended if given, via `getFeeForMessage`. Nothing is
+/// signed; the blockhash only has to be recent enough for the node to price
+/// the message.
+pub fn estimate_fee(
+    client: &RpcClient,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+    priority_fee: Option<&PriorityFee>,
+    blockhash: &Hash,
+) -> Result<FeeEstimate> {
+    let base_message = Message::new_with_blockhash(instructions, Some(payer), blockhash);
+    let base_fee = client.get_fee_for_message(&base_message)?;
+    let priority_fee = match priority_fee {
+        Some(fee) => {
+            let message = Message::new_with_blockhash(
+                &with_priority_fee(fee, instructions),
+                Some(payer),
+                blockhash,
+            );
+            // The node derives the surcharge from the compute budget
+            // instructions the same way it will when charging it.
+            client
+                .get_fee_for_message(&message)?
+                .saturating_sub(base_fee)
+        }
+        None => 0,
+    };
+    Ok(FeeEstimate {
+        signatures: base_message.header.num_required_signatures,
+        base_fee,
+        priority_fee,
+    })
+}
@@ -0,0 +1,98 @@
+//! Jito block-engine bundle methods.
+//!
+//! The block engine (and QuickNode's Lil' JIT add-on, which proxies it)
+//! speaks plain JSON-RPC, so an [`RpcClient`] pointed at the bundle endpoint
+//! can call it through [`RpcClient::send`], as the DAS module does.
+//! Transactions are passed base64-encoded.
+
+use crate::error::Result;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+
+/// Public mainnet block engine; regional hosts such as
+/// `ny.mainnet.block-engine.jito.wtf` serve the same path.
+pub const DEFAULT_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf/api/v1/bundles";
+
+/// Bundles hold at most this many transactions.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// The smallest tip the block engine accepts, in lamports.
+pub const MIN_TIP_LAMPORTS: u64 = 1_000;
+
+#[derive(Debug, Clone, Deserialize)]
+struct Response<T> {
+    value: T,
+}
+
+/// Status of a recently submitted bundle, from `getInflightBundleStatuses`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InflightBundleStatus {
+    pub bundle_id: String,
+    /// `Invalid`, `Pending`, `Failed` or `Landed`.
+    pub status: String,
+    pub landed_slot: Option<u64>,
+}
+
+/// Final status of a landed bundle, from `getBundleStatuses`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleStatus {
+    pub bundle_id: String,
+    pub transactions: Vec<String>,
+    pub slot: u64,
+    pub confirmation_status: Option<String>,
+    pub err: Value,
+}
+
+fn call<T: DeserializeOwned>(client: &RpcClient, method: &'static str, params: Value) -> Result<T> {
+    Ok(client.send(RpcRequest::Custom { method }, params)?)
+}
+
+/// Accounts a bundle can tip by transferring SOL to one of them.
+pub fn get_tip_accounts(client: &RpcClient) -> Result<Vec<Pubkey>> {
+    let accounts: Vec<String> = call(client, "getTipAccounts", json!([]))?;
+    accounts
+        .iter()
+        .map(|account| Ok(account.parse()?))
+        .collect()
+}
+
+/// Submits base64-encoded transactions as one bundle and returns its ID.
+pub fn send_bundle(client: &RpcClient, transactions: &[String]) -> Result<String> {
+    call(
+        client,
+        "sendBundle",
+        json!([transactions, { "encoding": "base64" }]),
+    )
+}
+
+/// Simulates a bundle against the current bank. Only Lil' JIT serves this;
+/// the result is returned as raw JSON.
+pub fn simulate_bundle(client: &RpcClient, transactions: &[String]) -> Result<Value> {
+    call(client, "simulateBundle", json!([transactions]))
+}
+
+/// Statuses of bundles submitted in roughly the last five minutes; unknown
+/// IDs are omitted.
+pub fn get_inflight_bundle_statuses(
+    client: &RpcClient,
+    bundle_ids: &[String],
+) -> Result<Vec<InflightBundleStatus>> {
+    let response: Response<Vec<InflightBundleStatus>> =
+        call(client, "getInflightBundleStatuses", json!([bundle_ids]))?;
+    Ok(response.value)
+}
+
+/// Statuses of landed bundles; `None` for IDs that have not landed.
+pub fn get_bundle_statuses(
+    client: &RpcClient,
+    bundle_ids: &[String],
+) -> Result<Vec<Option<BundleStatus>>> {
+    let response: Response<Vec<Option<BundleStatus>>> =
+        call(client, "getBundleStatuses", json!([bundle_ids]))?;
+    Ok(response.value)
+}
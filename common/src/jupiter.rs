@@ -0,0 +1,77 @@
+//! Quotes from the Jupiter swap API.
+//!
+//! Only the fields the examples read are modelled. `/swap` wants the quote
+//! back exactly as `/quote` returned it, so [`fetch_quote`] hands out the raw
+//! JSON and [`Quote::decode`] reads the typed view from it.
+
+use crate::error::{ExampleError, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+pub const DEFAULT_SWAP_API_URL: &str = "https://lite-api.jup.ag/swap/v1";
+
+/// Amounts are strings of base units, as the API sends them.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Quote {
+    pub in_amount: String,
+    pub out_amount: String,
+    /// The least output the swap accepts once slippage is applied
+    pub other_amount_threshold: String,
+    /// A fraction, despite the name: "0.01" is 1%
+    pub price_impact_pct: String,
+    pub route_plan: Vec<RoutePlanStep>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RoutePlanStep {
+    pub swap_info: SwapInfo,
+    /// Share of the input routed through this step
+    pub percent: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwapInfo {
+    /// The pool
+    pub amm_key: String,
+    /// The DEX, e.g. "Raydium"
+    pub label: Option<String>,
+}
+
+impl Quote {
+    pub fn decode(raw: &Value) -> Result<Self> {
+        Self::deserialize(raw).map_err(|err| ExampleError::Parse(format!("Jupiter quote: {err}")))
+    }
+
+    /// [`price_impact_pct`](Self::price_impact_pct) in percent.
+    pub fn price_impact_percent(&self) -> Result<f64> {
+        let fraction: f64 = self.price_impact_pct.parse().map_err(|_| {
+            ExampleError::Parse(format!("price impact `{}` in quote", self.price_impact_pct))
+        })?;
+        Ok(fraction * 100.0)
+    }
+}
+
+/// Parses one of a quote's amounts.
+pub fn parse_amount(value: &str) -> Result<u64> {
+    value
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("amount `{value}` in quote")))
+}
+
+/// `GET /quote` with `query` (`inputMint`, `outputMint`, `amount` and any
+/// of the optional parameters), returning the response untouched.
+pub fn fetch_quote(
+    http: &reqwest::blocking::Client,
+    api_url: &str,
+    query: &[(&str, String)],
+) -> Result<Value> {
+    Ok(http
+        .get(format!("{api_url}/quote"))
+        .query(query)
+        .send()?
+        .error_for_status()?
+        .json()?)
+}
@@ -0,0 +1,108 @@
+//! Loading signing keys from the places people usually keep them.
+
+use crate::error::{ExampleError, Result};
+use solana_derivation_path::DerivationPath;
+use solana_keypair::seed_derivable::keypair_from_seed_and_derivation_path;
+use solana_sdk::signature::Keypair;
+use solana_seed_phrase::generate_seed_from_seed_phrase_and_passphrase;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// Environment variable consulted when no other key source is given. It may
+/// hold a base58 secret key or a solana-keygen style JSON byte array.
+pub const PRIVATE_KEY_ENV: &str = "SOLANA_PRIVATE_KEY";
+
+/// Derivation path used by Phantom, Solflare and `solana-keygen` for the
+/// first account of a seed phrase.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/501'/0'/0'";
+
+/// Where to load a keypair from.
+#[derive(Debug, Clone)]
+pub enum KeySource {
+    /// A `solana-keygen` JSON file.
+    File(PathBuf),
+    /// A base58-encoded 64-byte secret key, as exported by most wallets.
+    Base58(String),
+    /// A BIP39 seed phrase, derived along `derivation_path`.
+    Mnemonic {
+        phrase: String,
+        passphrase: String,
+        derivation_path: String,
+    },
+    /// The [`PRIVATE_KEY_ENV`] environment variable.
+    Env,
+}
+
+impl KeySource {
+    pub fn load(&self) -> Result<Keypair> {
+        match self {
+            Self::File(path) => read_keypair_file(path),
+            Self::Base58(secret) => keypair_from_base58(secret),
+            Self::Mnemonic {
+                phrase,
+                passphrase,
+                derivation_path,
+            } => keypair_from_mnemonic(phrase, passphrase, derivation_path),
+            Self::Env => keypair_from_env(),
+        }
+    }
+}
+
+/// Loads a keypair written by `solana-keygen` (a JSON array of 64 bytes).
+pub fn read_keypair_file(path: impl AsRef<Path>) -> Result<Keypair> {
+    let path = path.as_ref();
+    solana_sdk::signature::read_keypair_file(path)
+        .map_err(|err| ExampleError::Keypair(format!("{}: {err}", path.display())))
+}
+
+/// Writes `keypair` in the same JSON format `solana-keygen` produces.
+pub fn write_keypair_file(keypair: &Keypair, path: impl AsRef<Path>) -> Result<()> {
+    let path = path.as_ref();
+    solana_sdk::signature::write_keypair_file(keypair, path)
+        .map(|_| ())
+        .map_err(|err| ExampleError::Keypair(format!("{}: {err}", path.display())))
+}
+
+pub fn keypair_from_base58(secret: &str) -> Result<Keypair> {
+    let bytes = bs58::decode(secret.trim())
+        .into_vec()
+        .map_err(|err| ExampleError::Keypair(format!("invalid base58 secret key: {err}")))?;
+    keypair_from_bytes(&bytes)
+}
+
+/// Derives a keypair from a seed phrase the way wallets do. The phrase's
+/// BIP39 checksum is not verified, so a typo yields a different valid key
+/// rather than an error; check the printed public key.
+pub fn keypair_from_mnemonic(
+    phrase: &str,
+    passphrase: &str,
+    derivation_path: &str,
+) -> Result<Keypair> {
+    let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+    let path = DerivationPath::from_absolute_path_str(derivation_path).map_err(|err| {
+        ExampleError::Keypair(format!("derivation path {derivation_path}: {err}"))
+    })?;
+    let seed = generate_seed_from_seed_phrase_and_passphrase(&phrase, passphrase);
+    keypair_from_seed_and_derivation_path(&seed, Some(path))
+        .map_err(|err| ExampleError::Keypair(format!("seed phrase: {err}")))
+}
+
+/// Reads [`PRIVATE_KEY_ENV`], accepting either base58 or a JSON byte array.
+pub fn keypair_from_env() -> Result<Keypair> {
+    let value = env::var(PRIVATE_KEY_ENV).map_err(|_| {
+        ExampleError::Keypair(format!("no keypair given and {PRIVATE_KEY_ENV} is not set"))
+    })?;
+    let value = value.trim();
+    if value.starts_with('[') {
+        let bytes: Vec<u8> = serde_json::from_str(value)
+            .map_err(|err| ExampleError::Keypair(format!("{PRIVATE_KEY_ENV}: {err}")))?;
+        keypair_from_bytes(&bytes)
+    } else {
+        keypair_from_base58(value)
+    }
+}
+
+fn keypair_from_bytes(bytes: &[u8]) -> Result<Keypair> {
+    Keypair::try_from(bytes)
+        .map_err(|err| ExampleError::Keypair(format!("invalid secret key bytes: {err}")))
+}
@@ -0,0 +1,63 @@
+//! Shared plumbing for the Rust examples in this repository: RPC client
+//! construction, CLI flags, a common error type and the small helpers most
+//! examples end up needing.
+
+pub mod anchor;
+pub mod batch;
+pub mod blockhash;
+pub mod borsh;
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod credits;
+pub mod das;
+pub mod decode;
+pub mod error;
+pub mod explorer;
+pub mod failover;
+pub mod fees;
+pub mod jito;
+pub mod jupiter;
+pub mod keys;
+pub mod logging;
+pub mod logs;
+pub mod memo;
+pub mod metaplex;
+pub mod metrics;
+pub mod output;
+pub mod price;
+pub mod quicknode_ext;
+pub mod raydium;
+pub mod reliable_send;
+pub mod retry;
+pub mod send;
+pub mod sns;
+pub mod stream;
+pub mod time;
+pub mod token;
+pub mod token_list;
+pub mod tx_builder;
+pub mod tx_size;
+pub mod units;
+
+pub use client::{at_least_confirmed, build_client, build_nonblocking_client, websocket_url};
+pub use error::{ExampleError, Result};
+pub use failover::FailoverRpcClient;
+pub use retry::{RetryPolicy, RetryingRpcClient, retry_with_backoff};
+
+use std::process::ExitCode;
+
+/// Prints `result`'s error, if any, and turns it into a process exit code,
+/// after writing out the credit ledger.
+///
+/// Intended to be the last expression of an example's `main`.
+pub fn report(result: Result<()>) -> ExitCode {
+    credits::flush();
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
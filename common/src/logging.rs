@@ -0,0 +1,118 @@
+//! Diagnostics through `tracing`.
+//!
+//! Every client built by [`crate::client`] sends its requests through
+//! [`TracingSender`], which opens an `rpc` span per JSON-RPC call carrying the
+//! method and endpoint and logs the call's duration when it completes. Log
+//! lines go to stderr, filtered by `RUST_LOG` (`warn` when unset, so only
+//! retries and re-signs show), e.g. `RUST_LOG=common=debug` to see every
+//! call. With `--log-json` they are written as JSON objects, one per line.
+//! The same sender feeds the request counters in [`crate::metrics`] and
+//! asks the [`crate::credits`] scheduler, when one is installed, before each
+//! request goes out.
+
+use crate::{credits, metrics};
+use async_trait::async_trait;
+use serde_json::Value;
+use solana_client::client_error::{ClientErrorKind, Result as ClientResult};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_rpc_client::http_sender::HttpSender;
+use std::io::{self, IsTerminal};
+use std::time::{Duration, Instant};
+use tracing::{Instrument, debug, info_span};
+use tracing_subscriber::EnvFilter;
+
+/// The flag switching log lines to JSON, declared on
+/// [`OutputArgs`](crate::output::OutputArgs).
+pub const LOG_JSON_FLAG: &str = "--log-json";
+
+const DEFAULT_FILTER: &str = "warn";
+
+/// Installs the global subscriber. Called by [`crate::config::parse`] before
+/// the arguments are parsed, so nothing an example logs is lost.
+pub fn init(json: bool) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal());
+    // Fails only if a subscriber is already installed, which is fine.
+    let _ = if json {
+        builder
+            .json()
+            .with_current_span(true)
+            .with_span_list(false)
+            .try_init()
+    } else {
+        builder.try_init()
+    };
+}
+
+/// An [`HttpSender`] that wraps every request in an `rpc` span.
+pub struct TracingSender {
+    inner: HttpSender,
+    endpoint: String,
+}
+
+impl TracingSender {
+    pub fn new(url: impl ToString, timeout: Duration) -> Self {
+        let url = url.to_string();
+        Self {
+            endpoint: endpoint_label(&url),
+            inner: HttpSender::new_with_timeout(url, timeout),
+        }
+    }
+}
+
+#[async_trait]
+impl RpcSender for TracingSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let method = request.to_string();
+        let span = info_span!("rpc", %method, endpoint = %self.endpoint);
+        if let Some(scheduler) = credits::scheduler() {
+            scheduler
+                .acquire(&method)
+                .instrument(span.clone())
+                .await
+                .map_err(|reason| {
+                    span.in_scope(|| debug!(%reason, "not sent"));
+                    ClientErrorKind::Custom(reason)
+                })?;
+        }
+        let started = Instant::now();
+        let result = self
+            .inner
+            .send(request, params)
+            .instrument(span.clone())
+            .await;
+        let elapsed = started.elapsed();
+        metrics::record_rpc(&method, result.is_ok(), elapsed);
+        let duration_ms = elapsed.as_secs_f64() * 1000.0;
+        span.in_scope(|| match &result {
+            Ok(_) => debug!(duration_ms, "ok"),
+            Err(err) => debug!(duration_ms, error = %err, "failed"),
+        });
+        result
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url()
+    }
+}
+
+/// Scheme and host of an endpoint URL. QuickNode puts the access token in
+/// the path, which must not end up in logs.
+pub fn endpoint_label(url: &str) -> String {
+    match url.split_once("://") {
+        Some((scheme, rest)) => {
+            let host = rest.split(['/', '?']).next().unwrap_or_default();
+            format!("{scheme}://{host}")
+        }
+        None => url.split(['/', '?']).next().unwrap_or_default().to_string(),
+    }
+}
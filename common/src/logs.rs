@@ -0,0 +1,89 @@
+//! Parsing of the `Program ...` lines the runtime writes to transaction logs.
+
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogLine<'a> {
+    /// `Program <id> invoke [<depth>]`
+    Invoke { program: Pubkey, depth: usize },
+    /// `Program log: <message>`
+    Log(&'a str),
+    /// `Program data: <base64>...`, as written by `sol_log_data` / Anchor `emit!`
+    Data(&'a str),
+    /// `Program return: <id> <base64>`
+    Return { program: Pubkey, data: &'a str },
+    /// `Program <id> consumed <units> of <budget> compute units`
+    Consumed { program: Pubkey, units: u64 },
+    /// `Program <id> success`
+    Success { program: Pubkey },
+    /// `Program <id> failed: <reason>`
+    Failed { program: Pubkey, reason: &'a str },
+    /// Anything else, e.g. truncated logs or runtime messages.
+    Other(&'a str),
+}
+
+pub fn parse_line(line: &str) -> LogLine<'_> {
+    parse_program_line(line).unwrap_or(LogLine::Other(line))
+}
+
+fn parse_program_line(line: &str) -> Option<LogLine<'_>> {
+    let rest = line.strip_prefix("Program ")?;
+    if let Some(message) = rest.strip_prefix("log: ") {
+        return Some(LogLine::Log(message));
+    }
+    if let Some(data) = rest.strip_prefix("data: ") {
+        return Some(LogLine::Data(data));
+    }
+    if let Some(rest) = rest.strip_prefix("return: ") {
+        let (program, data) = rest.split_once(' ')?;
+        return Some(LogLine::Return {
+            program: program.parse().ok()?,
+            data,
+        });
+    }
+
+    let (program, rest) = rest.split_once(' ')?;
+    let program = program.parse().ok()?;
+    if rest == "success" {
+        return Some(LogLine::Success { program });
+    }
+    if let Some(reason) = rest.strip_prefix("failed: ") {
+        return Some(LogLine::Failed { program, reason });
+    }
+    if let Some(depth) = rest.strip_prefix("invoke [") {
+        let depth = depth.strip_suffix(']')?.parse().ok()?;
+        return Some(LogLine::Invoke { program, depth });
+    }
+    if let Some(consumed) = rest.strip_prefix("consumed ") {
+        let (units, _) = consumed.split_once(' ')?;
+        return Some(LogLine::Consumed {
+            program,
+            units: units.parse().ok()?,
+        });
+    }
+    None
+}
+
+/// Walks the invoke stack and yields every line emitted while `program` was
+/// the currently executing program, including its CPI bookkeeping lines.
+pub fn lines_for_program<'a>(
+    logs: &'a [String],
+    program: &Pubkey,
+) -> impl Iterator<Item = LogLine<'a>> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    logs.iter().filter_map(move |line| {
+        let parsed = parse_line(line);
+        let current = match &parsed {
+            LogLine::Invoke { program, .. } => {
+                stack.push(*program);
+                Some(*program)
+            }
+            LogLine::Success { program } | LogLine::Failed { program, .. } => {
+                stack.pop();
+                Some(*program)
+            }
+            _ => stack.last().copied(),
+        };
+        (current.as_ref() == Some(program)).then_some(parsed)
+    })
+}
@@ -0,0 +1,68 @@
+//! Annotating transactions with an SPL Memo and reading the memo back.
+//!
+//! The Memo program only checks that its data is valid UTF-8 and that every
+//! listed account signed, then logs the text. That makes it the usual way to
+//! attach a reference (an invoice number, an order ID) to a payment.
+
+use crate::decode::{MEMO_PROGRAM_ID, MEMO_V1_PROGRAM_ID};
+use serde_json::Value;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status_client_types::{
+    EncodedTransaction, UiInstruction, UiMessage, UiParsedInstruction,
+};
+
+/// A memo instruction carrying `memo`. Each of `signers` must sign the
+/// transaction; with none, the memo is unsigned and anyone could have added
+/// it.
+pub fn memo_instruction(memo: &str, signers: &[&Pubkey]) -> Instruction {
+    Instruction::new_with_bytes(
+        MEMO_PROGRAM_ID,
+        memo.as_bytes(),
+        signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(**signer, true))
+            .collect(),
+    )
+}
+
+/// Returns `instructions` with a memo instruction appended.
+pub fn with_memo(
+    instructions: &[Instruction],
+    memo: &str,
+    signers: &[&Pubkey],
+) -> Vec<Instruction> {
+    instructions
+        .iter()
+        .cloned()
+        .chain([memo_instruction(memo, signers)])
+        .collect()
+}
+
+/// The memos among a transaction's top-level instructions. The transaction
+/// must have been fetched with `jsonParsed` encoding, in which the node
+/// decodes memo instructions to their text.
+pub fn memos(transaction: &EncodedTransaction) -> Vec<String> {
+    let EncodedTransaction::Json(transaction) = transaction else {
+        return Vec::new();
+    };
+    let UiMessage::Parsed(message) = &transaction.message else {
+        return Vec::new();
+    };
+    let memo_programs = [MEMO_PROGRAM_ID.to_string(), MEMO_V1_PROGRAM_ID.to_string()];
+    message
+        .instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed))
+                if memo_programs.contains(&parsed.program_id) =>
+            {
+                match &parsed.parsed {
+                    Value::String(memo) => Some(memo.clone()),
+                    other => Some(other.to_string()),
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
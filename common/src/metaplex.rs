@@ -0,0 +1,73 @@
+//! Decoding of Metaplex Token Metadata accounts.
+//!
+//! Only the leading, stable part of the account layout is decoded; the
+//! optional fields appended by later program versions are ignored.
+
+use crate::error::{ExampleError, Result};
+use borsh::BorshDeserialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+/// Metadata PDA: `["metadata", program_id, mint]`.
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Creator {
+    pub address: Pubkey,
+    pub verified: bool,
+    /// Percentage of royalties this creator receives.
+    pub share: u8,
+}
+
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct Metadata {
+    pub key: u8,
+    pub update_authority: Pubkey,
+    pub mint: Pubkey,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    /// Royalties in basis points (1/100th of a percent).
+    pub seller_fee_basis_points: u16,
+    pub creators: Option<Vec<Creator>>,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+}
+
+impl Metadata {
+    /// Decodes account data, stripping the NUL padding the program stores
+    /// fixed-size strings with.
+    pub fn from_account_data(data: &[u8]) -> Result<Self> {
+        let mut metadata = Self::deserialize(&mut &data[..])
+            .map_err(|err| ExampleError::Parse(format!("token metadata: {err}")))?;
+        for field in [&mut metadata.name, &mut metadata.symbol, &mut metadata.uri] {
+            let trimmed = field.trim_end_matches('\0').trim().to_string();
+            *field = trimmed;
+        }
+        Ok(metadata)
+    }
+}
+
+/// Fetches and decodes the metadata account belonging to `mint`.
+pub fn fetch_metadata(client: &RpcClient, mint: &Pubkey) -> Result<Metadata> {
+    let address = metadata_address(mint);
+    let account = client
+        .get_account_with_commitment(&address, client.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(address))?;
+    Metadata::from_account_data(&account.data)
+}
@@ -0,0 +1,167 @@
+//! Prometheus metrics for the long-running examples.
+//!
+//! Every request sent by a client from [`crate::client`] is counted and
+//! timed by method, along with the credits it was charged when a
+//! [`crate::credits`] scheduler is installed; subscriptions report each
+//! notification with [`notification`], and its lag behind the node's slot
+//! is derived from the slot [`track_node_slot`] keeps polling. [`MetricsArgs`](crate::cli::MetricsArgs)
+//! serves it all as the Prometheus text format on `/metrics`.
+
+use crate::error::Result;
+use axum::Router;
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::get;
+use prometheus::{
+    Encoder, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, TextEncoder,
+    register_histogram_vec, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::clock::Slot;
+use std::net::{SocketAddr, TcpListener};
+use std::sync::LazyLock;
+use std::thread;
+use std::time::Duration;
+use tracing::warn;
+
+/// How often [`track_node_slot`] asks for the node's slot; about five slots.
+const NODE_SLOT_INTERVAL: Duration = Duration::from_secs(2);
+
+static RPC_REQUESTS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "solana_rpc_requests_total",
+        "JSON-RPC requests by method and outcome",
+        &["method", "status"]
+    )
+    .expect("metric is registered once")
+});
+
+static RPC_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    register_histogram_vec!(
+        "solana_rpc_request_duration_seconds",
+        "JSON-RPC request latency by method",
+        &["method"]
+    )
+    .expect("metric is registered once")
+});
+
+static RPC_CREDITS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "solana_rpc_credits_total",
+        "API credits charged by the credit scheduler, by method",
+        &["method"]
+    )
+    .expect("metric is registered once")
+});
+
+static NOTIFICATIONS: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    register_int_counter_vec!(
+        "solana_notifications_total",
+        "Websocket notifications processed by subscription",
+        &["subscription"]
+    )
+    .expect("metric is registered once")
+});
+
+static NOTIFICATION_SLOT: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "solana_notification_slot",
+        "Slot of the latest notification by subscription",
+        &["subscription"]
+    )
+    .expect("metric is registered once")
+});
+
+static SUBSCRIPTION_LAG: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    register_int_gauge_vec!(
+        "solana_subscription_lag_slots",
+        "Slots between the node's current slot and the latest notification",
+        &["subscription"]
+    )
+    .expect("metric is registered once")
+});
+
+static NODE_SLOT: LazyLock<IntGauge> = LazyLock::new(|| {
+    register_int_gauge!("solana_node_slot", "The node's current slot")
+        .expect("metric is registered once")
+});
+
+/// Counts one finished RPC request.
+pub fn record_rpc(method: &str, ok: bool, duration: Duration) {
+    let status = if ok { "ok" } else { "error" };
+    RPC_REQUESTS.with_label_values(&[method, status]).inc();
+    RPC_DURATION
+        .with_label_values(&[method])
+        .observe(duration.as_secs_f64());
+}
+
+/// Counts the credits [`crate::credits`] charged for one request.
+pub fn record_credits(method: &str, credits: u64) {
+    RPC_CREDITS.with_label_values(&[method]).inc_by(credits);
+}
+
+/// Counts a notification of `subscription` (e.g. `accountSubscribe`) for
+/// `slot` and updates its lag, once the node's slot is known.
+pub fn notification(subscription: &str, slot: Slot) {
+    NOTIFICATIONS.with_label_values(&[subscription]).inc();
+    NOTIFICATION_SLOT
+        .with_label_values(&[subscription])
+        .set(slot as i64);
+    let node_slot = NODE_SLOT.get();
+    if node_slot > 0 {
+        SUBSCRIPTION_LAG
+            .with_label_values(&[subscription])
+            .set(node_slot - slot as i64);
+    }
+}
+
+/// Records the node's slot, for examples that poll it anyway.
+pub fn set_node_slot(slot: Slot) {
+    NODE_SLOT.set(slot as i64);
+}
+
+/// Polls `getSlot` for the lag metric, forever; spawn it as a task. Failed
+/// polls are only visible in the request counters.
+pub async fn track_node_slot(client: RpcClient) {
+    loop {
+        if let Ok(slot) = client.get_slot().await {
+            set_node_slot(slot);
+        }
+        tokio::time::sleep(NODE_SLOT_INTERVAL).await;
+    }
+}
+
+/// Serves `/metrics` on `port` from a background thread with its own
+/// runtime, so blocking examples can use it too. Binding happens before
+/// returning, so a port in use is reported to the caller.
+pub fn spawn_server(port: u16) -> Result<SocketAddr> {
+    let listener = TcpListener::bind(("0.0.0.0", port))?;
+    listener.set_nonblocking(true)?;
+    let address = listener.local_addr()?;
+    thread::Builder::new()
+        .name("metrics".to_string())
+        .spawn(move || {
+            let served = tokio::runtime::Builder::new_current_thread()
+                .enable_io()
+                .build()
+                .and_then(|runtime| {
+                    runtime.block_on(async {
+                        let listener = tokio::net::TcpListener::from_std(listener)?;
+                        let app = Router::new().route("/metrics", get(render));
+                        axum::serve(listener, app).await
+                    })
+                });
+            if let Err(err) = served {
+                warn!("metrics endpoint stopped: {err}");
+            }
+        })?;
+    Ok(address)
+}
+
+async fn render() -> ([(axum::http::HeaderName, String); 1], Vec<u8>) {
+    let encoder = TextEncoder::new();
+    let mut body = Vec::new();
+    if let Err(err) = encoder.encode(&prometheus::gather(), &mut body) {
+        warn!("encoding metrics: {err}");
+    }
+    ([(CONTENT_TYPE, encoder.format_type().to_string())], body)
+}
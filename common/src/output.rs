@@ -0,0 +1,164 @@
+//! Machine-readable output for the examples.
+//!
+//! Every example prints aligned text for people by default. With
+//! `--output json` or `--output csv` it serializes its result structs
+//! instead, so the output can be piped into `jq` or opened in a spreadsheet.
+//! Progress messages go through [`OutputArgs::status`], which moves them to
+//! stderr in those modes to keep stdout parseable.
+
+use crate::error::Result;
+use clap::{Args, ValueEnum};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text
+    #[default]
+    Table,
+    /// Pretty-printed JSON; streaming examples print one object per line
+    Json,
+    /// A header row followed by one row per record
+    Csv,
+}
+
+/// The `--output` flag; `#[command(flatten)]` it into an example's arguments.
+#[derive(Args, Debug, Clone)]
+pub struct OutputArgs {
+    /// Output format
+    #[arg(long = "output", value_enum, default_value_t, global = true)]
+    pub format: OutputFormat,
+
+    /// Write log lines (see RUST_LOG) to stderr as JSON
+    #[arg(long, global = true)]
+    pub log_json: bool,
+}
+
+impl OutputArgs {
+    pub fn is_table(&self) -> bool {
+        self.format == OutputFormat::Table
+    }
+
+    /// Prints a progress or informational line: on stdout as part of the
+    /// table output, on stderr otherwise.
+    pub fn status(&self, message: impl Display) {
+        if self.is_table() {
+            println!("{message}");
+        } else {
+            eprintln!("{message}");
+        }
+    }
+
+    /// Prints `value` in the selected format, calling `table` to render the
+    /// human-readable version. A value that serializes to a JSON array
+    /// becomes one CSV row per element; anything else becomes a single row.
+    pub fn print<T: Serialize + ?Sized>(&self, value: &T, table: impl FnOnce()) -> Result<()> {
+        match self.format {
+            OutputFormat::Table => table(),
+            OutputFormat::Json => {
+                let mut stdout = io::stdout().lock();
+                serde_json::to_writer_pretty(&mut stdout, value).map_err(io::Error::from)?;
+                writeln!(stdout)?;
+            }
+            OutputFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(io::stdout().lock());
+                let records = match to_value(value)? {
+                    Value::Array(records) => records,
+                    record => vec![record],
+                };
+                if let Some(first) = records.first() {
+                    writer
+                        .write_record(header(first))
+                        .map_err(io::Error::from)?;
+                }
+                for record in &records {
+                    writer.write_record(row(record)).map_err(io::Error::from)?;
+                }
+                writer.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints `value` as [`print`](Self::print) does for JSON and CSV, and
+    /// nothing for a table: for examples whose [`status`](Self::status)
+    /// lines already told the story step by step.
+    pub fn print_structured<T: Serialize + ?Sized>(&self, value: &T) -> Result<()> {
+        self.print(value, || {})
+    }
+
+    /// A writer for examples that produce records as they go, such as
+    /// subscriptions: JSON lines, or CSV with the header taken from the
+    /// first record.
+    pub fn records(&self) -> RecordWriter {
+        RecordWriter {
+            format: self.format,
+            header_written: false,
+        }
+    }
+}
+
+pub struct RecordWriter {
+    format: OutputFormat,
+    header_written: bool,
+}
+
+impl RecordWriter {
+    /// Writes one record, flushing it immediately so a downstream reader sees
+    /// it without waiting for the process to exit.
+    pub fn write<T: Serialize + ?Sized>(&mut self, record: &T, table: impl FnOnce()) -> Result<()> {
+        let mut stdout = io::stdout().lock();
+        match self.format {
+            OutputFormat::Table => table(),
+            OutputFormat::Json => {
+                serde_json::to_writer(&mut stdout, record).map_err(io::Error::from)?;
+                writeln!(stdout)?;
+            }
+            OutputFormat::Csv => {
+                let record = to_value(record)?;
+                let mut writer = csv::Writer::from_writer(&mut stdout);
+                if !self.header_written {
+                    writer
+                        .write_record(header(&record))
+                        .map_err(io::Error::from)?;
+                    self.header_written = true;
+                }
+                writer.write_record(row(&record)).map_err(io::Error::from)?;
+                writer.flush()?;
+            }
+        }
+        stdout.flush()?;
+        Ok(())
+    }
+}
+
+fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Value> {
+    Ok(serde_json::to_value(value).map_err(io::Error::from)?)
+}
+
+/// Column names: the record's fields in declaration order.
+fn header(record: &Value) -> Vec<String> {
+    match record {
+        Value::Object(fields) => fields.keys().cloned().collect(),
+        _ => vec!["value".to_string()],
+    }
+}
+
+fn row(record: &Value) -> Vec<String> {
+    match record {
+        Value::Object(fields) => fields.values().map(cell).collect(),
+        value => vec![cell(value)],
+    }
+}
+
+/// Scalars are written as-is; nested lists and objects, which have no CSV
+/// equivalent, are embedded as compact JSON.
+fn cell(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(text) => text.clone(),
+        _ => value.to_string(),
+    }
+}
@@ -0,0 +1,49 @@
+//! USD prices from the Jupiter price API.
+
+use crate::error::{ExampleError, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+
+pub const DEFAULT_PRICE_API_URL: &str = "https://lite-api.jup.ag/price/v3";
+
+/// The API prices at most this many mints per request.
+pub const MAX_PRICE_IDS: usize = 50;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PriceEntry {
+    usd_price: f64,
+}
+
+/// USD price per whole token for each of `mints`. Mints Jupiter has no
+/// reliable price for are left out of the map rather than reported as zero.
+pub fn fetch_usd_prices(
+    http: &reqwest::blocking::Client,
+    api_url: &str,
+    mints: &[Pubkey],
+) -> Result<HashMap<Pubkey, f64>> {
+    let mut prices = HashMap::new();
+    for chunk in mints.chunks(MAX_PRICE_IDS) {
+        let ids = chunk
+            .iter()
+            .map(Pubkey::to_string)
+            .collect::<Vec<_>>()
+            .join(",");
+        let response: HashMap<String, Option<PriceEntry>> = http
+            .get(api_url)
+            .query(&[("ids", ids)])
+            .send()?
+            .error_for_status()?
+            .json()?;
+        for (mint, entry) in response {
+            if let Some(entry) = entry {
+                let mint = mint
+                    .parse()
+                    .map_err(|_| ExampleError::Parse(format!("mint `{mint}` in price response")))?;
+                prices.insert(mint, entry.usd_price);
+            }
+        }
+    }
+    Ok(prices)
+}
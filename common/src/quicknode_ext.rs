@@ -0,0 +1,155 @@
+//! Typed access to QuickNode add-on RPC methods.
+//!
+//! Like DAS, the add-ons answer on the endpoint's own URL under custom
+//! method names, so they go through [`RpcClient::send`]; an endpoint without
+//! the add-on enabled fails the call with a JSON-RPC error. Only the fields
+//! the examples use are modelled, and amounts the API returns as strings are
+//! kept that way.
+//!
+//! - `qn_estimatePriorityFees` (Priority Fee API)
+//! - `qn_getWalletTokenBalance` (Token and NFT API v2)
+//! - `qn_fetchNFTs` (Token and NFT API v2)
+
+use crate::error::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeMap;
+
+/// The Token and NFT API serves at most this many items per page.
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Fee levels from `qn_estimatePriorityFees`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PriorityFeeEstimate {
+    pub context: FeeContext,
+    /// Micro-lamports per compute unit
+    pub per_compute_unit: FeeLevels,
+    /// Lamports per transaction
+    pub per_transaction: FeeLevels,
+    /// What QuickNode suggests paying per compute unit, taking the network's
+    /// current load into account
+    pub recommended: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FeeContext {
+    pub slot: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FeeLevels {
+    /// The 95th percentile
+    pub extreme: f64,
+    /// The 80th
+    pub high: f64,
+    /// The 50th
+    pub medium: f64,
+    /// The 25th
+    pub low: f64,
+    #[serde(default)]
+    pub percentiles: BTreeMap<u8, f64>,
+}
+
+/// One page of a wallet listing from the Token and NFT API.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub owner: String,
+    pub assets: Vec<T>,
+    pub total_items: u32,
+    pub total_pages: u32,
+    pub page_number: u32,
+}
+
+/// A fungible token held by a wallet, from `qn_getWalletTokenBalance`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TokenBalance {
+    /// The mint
+    pub address: String,
+    pub name: Option<String>,
+    pub symbol: Option<String>,
+    pub decimals: Option<u8>,
+    /// In whole tokens
+    pub amount: String,
+}
+
+/// An NFT held by a wallet, from `qn_fetchNFTs`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Nft {
+    pub name: Option<String>,
+    /// The mint
+    pub token_address: String,
+    pub collection_name: Option<String>,
+    pub collection_address: Option<String>,
+    pub image_url: Option<String>,
+    #[serde(default)]
+    pub traits: Vec<Trait>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Trait {
+    pub trait_type: String,
+    pub value: Value,
+}
+
+fn call<T: DeserializeOwned>(client: &RpcClient, method: &'static str, params: Value) -> Result<T> {
+    Ok(client.send(RpcRequest::Custom { method }, params)?)
+}
+
+/// Fee levels over the last `last_n_blocks` blocks (QuickNode's default is
+/// 100), restricted to transactions that write-lock `account` when given.
+pub fn estimate_priority_fees(
+    client: &RpcClient,
+    last_n_blocks: Option<u32>,
+    account: Option<&Pubkey>,
+) -> Result<PriorityFeeEstimate> {
+    let mut params = json!({ "api_version": 2 });
+    if let Some(last_n_blocks) = last_n_blocks {
+        params["last_n_blocks"] = json!(last_n_blocks);
+    }
+    if let Some(account) = account {
+        params["account"] = json!(account.to_string());
+    }
+    // RpcClient only sends positional params; the add-on takes its object
+    // wrapped in an array, as the Token and NFT API does.
+    call(client, "qn_estimatePriorityFees", json!([params]))
+}
+
+/// `page` is 1-based; `per_page` is capped at [`MAX_PAGE_SIZE`].
+pub fn get_wallet_token_balance(
+    client: &RpcClient,
+    wallet: &Pubkey,
+    page: u32,
+    per_page: u32,
+) -> Result<Page<TokenBalance>> {
+    call(
+        client,
+        "qn_getWalletTokenBalance",
+        json!([{ "wallet": wallet.to_string(), "page": page, "perPage": per_page }]),
+    )
+}
+
+/// `page` is 1-based; `per_page` is capped at [`MAX_PAGE_SIZE`].
+pub fn fetch_nfts(
+    client: &RpcClient,
+    wallet: &Pubkey,
+    page: u32,
+    per_page: u32,
+) -> Result<Page<Nft>> {
+    call(
+        client,
+        "qn_fetchNFTs",
+        json!([{
+            "wallet": wallet.to_string(),
+            "page": page,
+            "perPage": per_page,
+            // Not modelled, and the bulk of the response.
+            "omitFields": ["provenance"],
+        }]),
+    )
+}
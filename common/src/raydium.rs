@@ -0,0 +1,60 @@
+//! Raydium AMM v4 pool accounts.
+//!
+//! Raydium publishes no Rust crate for the v4 program's state, so the layout
+//! is decoded by hand, as it would be for any such program.
+
+use crate::error::{ExampleError, Result};
+use borsh::BorshDeserialize;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+pub const AMM_V4_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+
+/// The leading part of Raydium's `AmmInfo`, a `#[repr(C)]` struct of
+/// little-endian integers and keys without padding, so Borsh decodes it.
+#[derive(Debug, Clone, BorshDeserialize)]
+pub struct AmmInfo {
+    /// 1 to 7 while the pool is usable
+    pub status: u64,
+    _nonce_order_num_depth: [u64; 3],
+    _coin_decimals: u64,
+    _pc_decimals: u64,
+    _state_to_sys_decimal_value: [u64; 10],
+    _min_separate_fee: [u64; 2],
+    _trade_fee: [u64; 2],
+    _pnl_fee: [u64; 2],
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    /// Fees owed to the protocol, still sitting in the vaults
+    pub need_take_pnl_coin: u64,
+    pub need_take_pnl_pc: u64,
+    _state_data: [u8; 128],
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub coin_mint: Pubkey,
+    pub pc_mint: Pubkey,
+    // The rest (LP mint, OpenBook market and such) is not needed for the
+    // price.
+}
+
+impl AmmInfo {
+    /// Decodes the pool at `address`, which must be an initialized AMM v4
+    /// pool.
+    pub fn unpack(address: &Pubkey, account: &Account) -> Result<Self> {
+        if account.owner != AMM_V4_PROGRAM_ID {
+            return Err(ExampleError::InvalidArgument(format!(
+                "{address} is owned by {}, not Raydium AMM v4",
+                account.owner
+            )));
+        }
+        let amm = Self::deserialize(&mut &account.data[..])
+            .map_err(|err| ExampleError::Parse(format!("Raydium pool {address}: {err}")))?;
+        if amm.status == 0 {
+            return Err(ExampleError::InvalidArgument(format!(
+                "Raydium pool {address} is not initialized"
+            )));
+        }
+        Ok(amm)
+    }
+}
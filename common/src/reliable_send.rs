@@ -0,0 +1,166 @@
+//! Submitting a transaction the way production senders do, instead of
+//! `send_and_confirm_transaction`.
+//!
+//! The transaction is sent with preflight skipped and the node's own retry
+//! queue disabled, then two loops run side by side: one polls
+//! `getSignatureStatuses` until the transaction reaches the wanted
+//! commitment, the other rebroadcasts it every few slots. Leaders drop
+//! transactions under load without telling anyone, so resending the same
+//! signed bytes until the blockhash expires is what gets them landed; it is
+//! safe because the signature makes duplicates no-ops.
+
+use crate::blockhash::RecentBlockhash;
+use crate::error::{ExampleError, Result};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use tokio::time;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ReliableSendConfig {
+    /// Resend the transaction whenever this many slots pass without it
+    /// landing.
+    pub rebroadcast_every_slots: u64,
+    /// How often statuses and the current slot are polled.
+    pub poll_interval: Duration,
+    /// Commitment the transaction must reach.
+    pub commitment: CommitmentConfig,
+}
+
+impl Default for ReliableSendConfig {
+    fn default() -> Self {
+        Self {
+            rebroadcast_every_slots: 2,
+            poll_interval: Duration::from_millis(400),
+            commitment: CommitmentConfig::confirmed(),
+        }
+    }
+}
+
+/// How a reliably sent transaction landed.
+#[derive(Debug, Clone)]
+pub struct LandingReport {
+    pub signature: Signature,
+    /// Slot at the first send.
+    pub sent_slot: u64,
+    /// Slot the transaction was included in.
+    pub landed_slot: u64,
+    /// Times the transaction was sent, the first send included.
+    pub broadcasts: u32,
+    /// Time from the first send until the commitment was reached.
+    pub elapsed: Duration,
+}
+
+impl LandingReport {
+    pub fn slots_to_land(&self) -> u64 {
+        self.landed_slot.saturating_sub(self.sent_slot)
+    }
+}
+
+/// Sends `transaction`, signed with `recent`, and rebroadcasts it until it
+/// reaches `config.commitment` or its blockhash expires.
+///
+/// Fails with [`ExampleError::TransactionFailed`] if it lands with an error
+/// and [`ExampleError::TransactionExpired`] if the blockhash runs out first;
+/// in the latter case the caller should re-sign with a fresh blockhash.
+pub async fn send_reliably(
+    client: &RpcClient,
+    transaction: &Transaction,
+    recent: RecentBlockhash,
+    config: ReliableSendConfig,
+) -> Result<LandingReport> {
+    let send_config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        // Rebroadcasting is done here; the node's queue would only duplicate it.
+        max_retries: Some(0),
+        ..RpcSendTransactionConfig::default()
+    };
+    let sent_slot = client
+        .get_slot_with_commitment(CommitmentConfig::processed())
+        .await?;
+    let started = Instant::now();
+    let signature = client
+        .send_transaction_with_config(transaction, send_config)
+        .await?;
+    let broadcasts = AtomicU32::new(1);
+
+    let wait_for_status = async {
+        let mut ticker = time::interval(config.poll_interval);
+        loop {
+            ticker.tick().await;
+            let status = client
+                .get_signature_statuses(&[signature])
+                .await?
+                .value
+                .remove(0);
+            if let Some(status) = status {
+                if let Some(err) = status.err {
+                    return Err(ExampleError::TransactionFailed(err));
+                }
+                if status.satisfies_commitment(config.commitment) {
+                    return Ok(status.slot);
+                }
+                // Included but not yet at the wanted commitment: it can no
+                // longer expire, so only keep polling.
+                continue;
+            }
+            let block_height = client
+                .get_block_height_with_commitment(CommitmentConfig::confirmed())
+                .await?;
+            if block_height > recent.last_valid_block_height {
+                return Err(ExampleError::TransactionExpired(signature));
+            }
+        }
+    };
+
+    let landed_slot = tokio::select! {
+        landed = wait_for_status => landed?,
+        result = rebroadcast(client, transaction, send_config, sent_slot, config, &broadcasts) => {
+            let Err(err) = result;
+            return Err(err);
+        }
+    };
+    Ok(LandingReport {
+        signature,
+        sent_slot,
+        landed_slot,
+        broadcasts: broadcasts.load(Ordering::Relaxed),
+        elapsed: started.elapsed(),
+    })
+}
+
+/// Resends `transaction` every `config.rebroadcast_every_slots` slots; only
+/// returns if polling the slot fails.
+async fn rebroadcast(
+    client: &RpcClient,
+    transaction: &Transaction,
+    send_config: RpcSendTransactionConfig,
+    sent_slot: u64,
+    config: ReliableSendConfig,
+    broadcasts: &AtomicU32,
+) -> Result<Infallible> {
+    let mut ticker = time::interval(config.poll_interval);
+    let mut last_sent_slot = sent_slot;
+    loop {
+        ticker.tick().await;
+        let slot = client
+            .get_slot_with_commitment(CommitmentConfig::processed())
+            .await?;
+        if slot >= last_sent_slot + config.rebroadcast_every_slots {
+            // A failed resend is not fatal: the first send was accepted.
+            if client
+                .send_transaction_with_config(transaction, send_config)
+                .await
+                .is_ok()
+            {
+                broadcasts.fetch_add(1, Ordering::Relaxed);
+            }
+            last_sent_slot = slot;
+        }
+    }
+}
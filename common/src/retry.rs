@@ -0,0 +1,128 @@
+use crate::error::Result;
+use rand::Rng;
+use solana_client::rpc_client::RpcClient;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::thread;
+use std::time::Duration;
+use tracing::warn;
+
+/// Exponential backoff settings for [`retry_with_backoff`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; `0` disables retrying.
+    pub max_retries: u32,
+    pub initial_delay: Duration,
+    pub max_delay: Duration,
+    /// Randomize each delay between half and all of its nominal value, so
+    /// that many clients backing off at once do not retry in lockstep.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(8),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Nominal delay before retry number `attempt` (zero-based).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.initial_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_delay)
+    }
+
+    /// [`delay`](Self::delay) with jitter applied, if enabled.
+    pub fn jittered_delay(&self, attempt: u32) -> Duration {
+        let delay = self.delay(attempt);
+        if self.jitter {
+            delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+        } else {
+            delay
+        }
+    }
+}
+
+/// Runs `op`, retrying with exponential backoff while it fails with a
+/// [retryable](crate::ExampleError::is_retryable) error.
+pub fn retry_with_backoff<T>(policy: &RetryPolicy, mut op: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(err) if err.is_retryable() && attempt < policy.max_retries => {
+                let delay = policy.jittered_delay(attempt);
+                let reason = if err.is_rate_limited() {
+                    "rate limited"
+                } else {
+                    "error"
+                };
+                warn!(
+                    "retry {}/{} in {delay:?} after {reason}: {err}",
+                    attempt + 1,
+                    policy.max_retries
+                );
+                thread::sleep(delay);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// An [`RpcClient`] whose calls made through [`call`](Self::call) are retried
+/// according to a [`RetryPolicy`].
+///
+/// The client's HTTP transport already absorbs a few 429 responses on its
+/// own; this covers the ones that get through, JSON-RPC level rate-limit
+/// errors and transient transport failures. It derefs to the inner client
+/// for calls that should not be retried, such as sending transactions.
+pub struct RetryingRpcClient {
+    client: RpcClient,
+    policy: RetryPolicy,
+    retries: AtomicU32,
+}
+
+impl RetryingRpcClient {
+    pub fn new(client: RpcClient, policy: RetryPolicy) -> Self {
+        Self {
+            client,
+            policy,
+            retries: AtomicU32::new(0),
+        }
+    }
+
+    /// Runs `op` against the inner client, retrying retryable failures.
+    pub fn call<T>(&self, mut op: impl FnMut(&RpcClient) -> Result<T>) -> Result<T> {
+        let mut first = true;
+        retry_with_backoff(&self.policy, || {
+            if !first {
+                self.retries.fetch_add(1, Ordering::Relaxed);
+            }
+            first = false;
+            op(&self.client)
+        })
+    }
+
+    /// Total number of retries performed so far, across all calls.
+    pub fn retries(&self) -> u32 {
+        self.retries.load(Ordering::Relaxed)
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+}
+
+impl Deref for RetryingRpcClient {
+    type Target = RpcClient;
+
+    fn deref(&self) -> &RpcClient {
+        &self.client
+    }
+}
@@ -0,0 +1,155 @@
+//! Helpers around submitting transactions.
+
+use crate::blockhash::BlockhashCache;
+use crate::error::{ExampleError, Result};
+use crate::output::OutputArgs;
+use solana_client::rpc_client::{RpcClient, SerializableTransaction};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::signers::Signers;
+use solana_sdk::transaction::{Transaction, TransactionError};
+use std::thread;
+use std::time::Duration;
+use tracing::warn;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Rebroadcast the pending transaction every this many polls.
+const REBROADCAST_EVERY: u32 = 4;
+
+/// Prints the compute units, program logs and error of a simulation.
+pub fn print_simulation(simulation: &RpcSimulateTransactionResult) {
+    match simulation.units_consumed {
+        Some(units) => println!("Simulation consumed {units} compute units"),
+        None => println!("Simulation did not report compute units"),
+    }
+    for line in simulation.logs.iter().flatten() {
+        println!("  {line}");
+    }
+    if let Some(err) = &simulation.err {
+        println!("Simulation error: {err}");
+    }
+}
+
+/// Simulates `transaction`, printing the outcome with
+/// [`print_simulation`] when `output` is a table.
+pub fn simulate(
+    client: &RpcClient,
+    transaction: &impl SerializableTransaction,
+    output: &OutputArgs,
+) -> Result<RpcSimulateTransactionResult> {
+    let simulation = client.simulate_transaction(transaction)?.value;
+    if output.is_table() {
+        print_simulation(&simulation);
+    }
+    Ok(simulation)
+}
+
+/// The outcome of [`simulate_and_send`].
+pub struct SimulatedSend {
+    pub simulation: RpcSimulateTransactionResult,
+    /// Absent when the simulation failed and nothing was sent
+    pub signature: Option<Signature>,
+}
+
+/// Simulates `transaction` as [`simulate`] does and only sends it (waiting
+/// for confirmation) if the simulation succeeded. A failed simulation is
+/// returned rather than raised, so the caller can still report its logs.
+pub fn simulate_and_send(
+    client: &RpcClient,
+    transaction: &impl SerializableTransaction,
+    output: &OutputArgs,
+) -> Result<SimulatedSend> {
+    let simulation = simulate(client, transaction, output)?;
+    let signature = match simulation.err {
+        Some(_) => None,
+        None => Some(client.send_and_confirm_transaction(transaction)?),
+    };
+    Ok(SimulatedSend {
+        simulation,
+        signature,
+    })
+}
+
+/// Signs `instructions` with a blockhash from `cache`, sends them and waits
+/// for the client's commitment. If the blockhash is rejected as unknown or
+/// expires before the transaction lands, the transaction is re-signed with a
+/// fresh blockhash and sent again, up to `max_resigns` times.
+///
+/// While waiting, the same signed transaction is rebroadcast periodically,
+/// since the node may drop it under load without reporting an error.
+pub fn send_with_fresh_blockhash<T: Signers + ?Sized>(
+    client: &RpcClient,
+    cache: &BlockhashCache,
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    signers: &T,
+    max_resigns: u32,
+) -> Result<Signature> {
+    send_signed_with_fresh_blockhash(client, cache, max_resigns, |blockhash| {
+        Ok(Transaction::new_signed_with_payer(
+            instructions,
+            Some(payer),
+            signers,
+            blockhash,
+        ))
+    })
+}
+
+/// [`send_with_fresh_blockhash`] for a transaction `sign` builds and signs
+/// over the blockhash it is given, such as a v0 one.
+pub fn send_signed_with_fresh_blockhash<T: SerializableTransaction>(
+    client: &RpcClient,
+    cache: &BlockhashCache,
+    max_resigns: u32,
+    sign: impl Fn(Hash) -> Result<T>,
+) -> Result<Signature> {
+    let mut last_signature = None;
+    for attempt in 0..=max_resigns {
+        let recent = if attempt == 0 {
+            cache.get(client)?
+        } else {
+            cache.refresh(client)?
+        };
+        let transaction = sign(recent.blockhash)?;
+        let signature = match client.send_transaction(&transaction) {
+            Ok(signature) => signature,
+            Err(err)
+                if err.get_transaction_error() == Some(TransactionError::BlockhashNotFound) =>
+            {
+                warn!("blockhash {} not found; re-signing", recent.blockhash);
+                continue;
+            }
+            Err(err) => return Err(err.into()),
+        };
+        last_signature = Some(signature);
+
+        let mut polls = 0;
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let status = client.get_signature_statuses(&[signature])?.value.remove(0);
+            if let Some(status) = status {
+                if let Some(err) = status.err {
+                    return Err(ExampleError::TransactionFailed(err));
+                }
+                if status.satisfies_commitment(client.commitment()) {
+                    return Ok(signature);
+                }
+            } else if client.get_block_height()? > recent.last_valid_block_height {
+                warn!("transaction {signature} expired; re-signing with a fresh blockhash");
+                break;
+            }
+            polls += 1;
+            if polls % REBROADCAST_EVERY == 0 {
+                // Errors here are not fatal: the original send was accepted.
+                let _ = client.send_transaction(&transaction);
+            }
+        }
+    }
+    Err(match last_signature {
+        Some(signature) => ExampleError::TransactionExpired(signature),
+        None => ExampleError::TransactionFailed(TransactionError::BlockhashNotFound),
+    })
+}
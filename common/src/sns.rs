@@ -0,0 +1,175 @@
+//! Resolution of Solana Name Service (`.sol`) domains.
+//!
+//! A domain is a name service account whose address is derived from the
+//! hash of its name and its parent: the `.sol` root for `bonfida.sol`, the
+//! domain for a subdomain such as `dex.bonfida.sol`. The account starts
+//! with a header naming its owner. Going the other way, each domain has a
+//! reverse lookup account, derived from the domain's address, holding its
+//! name.
+
+use crate::error::{ExampleError, Result};
+use solana_account_decoder_client_types::UiDataSliceConfig;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::hash::hashv;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use spl_token::state::Account as TokenAccount;
+
+pub const NAME_PROGRAM_ID: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+
+/// Parent of every `.sol` domain.
+pub const ROOT_DOMAIN_ACCOUNT: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
+
+/// Class of the reverse lookup accounts.
+pub const REVERSE_LOOKUP_CLASS: Pubkey = pubkey!("33m47vH6Eav6jr3Z2Ycz5Uozu3N3MaKyHD3MU8W5jMw9");
+
+/// Holds domains wrapped as NFTs, in which case the NFT's holder owns the
+/// domain.
+pub const NAME_TOKENIZER_ID: Pubkey = pubkey!("nftD3vbNkNqfj2Sd3HZwbpw4BxxKWr4AjGb9X38JeZk");
+
+const HASH_PREFIX: &str = "SPL Name Service";
+
+/// Parent, owner and class keys precede the account's data.
+const HEADER_LEN: usize = 96;
+const OWNER_OFFSET: usize = 32;
+
+/// Whether `input` looks like a domain rather than an address.
+pub fn is_domain(input: &str) -> bool {
+    input.ends_with(".sol")
+}
+
+/// Address of the name account for `name` under `parent`.
+pub fn name_account_key(name: &str, class: Option<&Pubkey>, parent: Option<&Pubkey>) -> Pubkey {
+    let hashed = hashv(&[HASH_PREFIX.as_bytes(), name.as_bytes()]);
+    let none = Pubkey::default();
+    Pubkey::find_program_address(
+        &[
+            hashed.as_ref(),
+            class.unwrap_or(&none).as_ref(),
+            parent.unwrap_or(&none).as_ref(),
+        ],
+        &NAME_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Address of the name account of a domain such as `bonfida.sol` or a
+/// subdomain such as `dex.bonfida.sol`; the `.sol` suffix is optional.
+pub fn domain_key(domain: &str) -> Result<Pubkey> {
+    let name = domain.strip_suffix(".sol").unwrap_or(domain);
+    let invalid = || ExampleError::InvalidArgument(format!("`{domain}` is not a .sol domain"));
+    match name.split('.').collect::<Vec<_>>().as_slice() {
+        [domain] if !domain.is_empty() => {
+            Ok(name_account_key(domain, None, Some(&ROOT_DOMAIN_ACCOUNT)))
+        }
+        // Subdomain names are stored with a leading NUL.
+        [sub, domain] if !sub.is_empty() && !domain.is_empty() => {
+            let parent = name_account_key(domain, None, Some(&ROOT_DOMAIN_ACCOUNT));
+            Ok(name_account_key(&format!("\0{sub}"), None, Some(&parent)))
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// The wallet that owns `domain`: the name account's owner, or for a
+/// tokenized domain the holder of its NFT.
+pub fn resolve(client: &RpcClient, domain: &str) -> Result<Pubkey> {
+    let key = domain_key(domain)?;
+    let account = client
+        .get_account_with_commitment(&key, client.commitment())?
+        .value
+        .ok_or_else(|| ExampleError::InvalidArgument(format!("{domain} is not registered")))?;
+    let owner = header_key(&account.data, OWNER_OFFSET)
+        .ok_or_else(|| ExampleError::Parse(format!("name account {key}")))?;
+    if owner != NAME_TOKENIZER_ID {
+        return Ok(owner);
+    }
+    let mint =
+        Pubkey::find_program_address(&[b"tokenized_name", key.as_ref()], &NAME_TOKENIZER_ID).0;
+    let holder = client
+        .get_token_largest_accounts(&mint)?
+        .into_iter()
+        .find(|balance| balance.amount.amount == "1")
+        .ok_or_else(|| ExampleError::InvalidArgument(format!("the NFT of {domain} is not held")))?;
+    let holder: Pubkey = holder.address.parse()?;
+    let account = client.get_account(&holder)?;
+    Ok(TokenAccount::unpack(&account.data)?.owner)
+}
+
+/// The domain name of the name account `key`, with `.sol` appended, or
+/// nothing when it has no reverse lookup account.
+pub fn reverse_lookup(client: &RpcClient, key: &Pubkey) -> Result<Option<String>> {
+    Ok(reverse_lookup_many(client, &[*key])?.pop().flatten())
+}
+
+/// Reverse lookups of second-level domains, in one batch of
+/// getMultipleAccounts calls.
+fn reverse_lookup_many(client: &RpcClient, keys: &[Pubkey]) -> Result<Vec<Option<String>>> {
+    let reverse_keys: Vec<Pubkey> = keys
+        .iter()
+        .map(|key| name_account_key(&key.to_string(), Some(&REVERSE_LOOKUP_CLASS), None))
+        .collect();
+    let accounts = crate::batch::get_multiple_accounts(client, &reverse_keys)?;
+    accounts
+        .into_iter()
+        .map(|account| {
+            let Some(account) = account else {
+                return Ok(None);
+            };
+            let name = reverse_name(&account.data)
+                .ok_or_else(|| ExampleError::Parse("reverse lookup account".to_string()))?;
+            Ok(Some(format!("{name}.sol")))
+        })
+        .collect()
+}
+
+/// The `.sol` domains `wallet` owns directly, with their name accounts.
+/// Tokenized domains are owned by the tokenizer and not listed.
+pub fn domains_owned(client: &RpcClient, wallet: &Pubkey) -> Result<Vec<(Pubkey, String)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, ROOT_DOMAIN_ACCOUNT.as_ref())),
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(OWNER_OFFSET, wallet.as_ref())),
+        ]),
+        // Only the addresses are needed.
+        account_config: RpcAccountInfoConfig {
+            data_slice: Some(UiDataSliceConfig {
+                offset: 0,
+                length: 0,
+            }),
+            commitment: Some(client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let keys: Vec<Pubkey> = client
+        .get_program_accounts_with_config(&NAME_PROGRAM_ID, config)?
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    let names = reverse_lookup_many(client, &keys)?;
+    let mut domains: Vec<(Pubkey, String)> = keys
+        .into_iter()
+        .zip(names)
+        .filter_map(|(key, name)| Some((key, name?)))
+        .collect();
+    domains.sort_by(|a, b| a.1.cmp(&b.1));
+    Ok(domains)
+}
+
+fn header_key(data: &[u8], offset: usize) -> Option<Pubkey> {
+    Some(Pubkey::new_from_array(
+        data.get(offset..offset + 32)?.try_into().ok()?,
+    ))
+}
+
+/// A reverse lookup account holds the name as a length-prefixed string.
+fn reverse_name(data: &[u8]) -> Option<String> {
+    let data = data.get(HEADER_LEN..)?;
+    let len = u32::from_le_bytes(data.get(..4)?.try_into().ok()?) as usize;
+    let name = std::str::from_utf8(data.get(4..4 + len)?).ok()?;
+    Some(name.trim_start_matches('\0').to_string())
+}
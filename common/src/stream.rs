@@ -0,0 +1,400 @@
+//! Transactions mentioning an address, from whichever source an endpoint
+//! offers.
+//!
+//! Yellowstone gRPC is the fastest and most complete, but is a paid add-on;
+//! websocket `logsSubscribe` works on every endpoint that serves websockets;
+//! polling `getSignaturesForAddress` works everywhere, seconds behind. All
+//! three implement [`StreamSource`], and [`connect`] picks the first one that
+//! works, so an example written against the trait runs on any endpoint.
+
+use crate::batch::MAX_SIGNATURES_FOR_ADDRESS;
+use crate::error::{ExampleError, Result};
+use crate::{at_least_confirmed, client};
+use async_trait::async_trait;
+use futures::{SinkExt, StreamExt};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{RpcTransactionLogsConfig, RpcTransactionLogsFilter};
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+use std::pin::Pin;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time;
+use tracing::warn;
+use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient};
+use yellowstone_grpc_proto::prelude::subscribe_update::UpdateOneof;
+use yellowstone_grpc_proto::prelude::{
+    CommitmentLevel as GrpcCommitment, SubscribeRequest, SubscribeRequestFilterTransactions,
+    SubscribeRequestPing, SubscribeUpdate,
+};
+use yellowstone_grpc_proto::tonic::Status;
+
+/// Notifications the websocket task may buffer ahead of the consumer.
+const LOGS_BUFFER: usize = 1024;
+
+/// A transaction that mentions the watched address.
+#[derive(Clone, Debug)]
+pub struct TransactionEvent {
+    pub slot: Slot,
+    pub signature: Signature,
+    pub failed: bool,
+}
+
+/// A stream of [`TransactionEvent`]s. `next` returns `None` once the source
+/// has ended, e.g. the server closed the connection; connect again to carry
+/// on.
+#[async_trait]
+pub trait StreamSource: Send {
+    /// `grpc`, `websocket` or `polling`, for status lines.
+    fn name(&self) -> &'static str;
+
+    async fn next(&mut self) -> Result<Option<TransactionEvent>>;
+}
+
+/// Which sources [`connect`] may use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum SourceKind {
+    /// gRPC if an endpoint is configured, then websocket, then polling
+    Auto,
+    Grpc,
+    Websocket,
+    Polling,
+}
+
+/// Where and how [`connect`] reaches each source.
+pub struct SourceConfig<'a> {
+    pub kind: SourceKind,
+    pub rpc_url: &'a str,
+    pub ws_url: &'a str,
+    pub grpc_url: Option<&'a str>,
+    pub x_token: Option<&'a str>,
+    pub commitment: CommitmentConfig,
+    pub timeout: Duration,
+    pub poll_interval: Duration,
+}
+
+/// Connects the first source of `config.kind` that works, logging why the
+/// ones before it were skipped. A single kind is tried alone, so its error
+/// is returned as is.
+pub async fn connect(config: &SourceConfig<'_>, address: &Pubkey) -> Result<Box<dyn StreamSource>> {
+    let grpc = async {
+        let Some(grpc_url) = config.grpc_url else {
+            return Err(ExampleError::InvalidArgument(
+                "no gRPC endpoint configured".to_string(),
+            ));
+        };
+        let source = GrpcSource::connect(grpc_url, config.x_token, address, config.commitment);
+        Ok(Box::new(source.await?) as Box<dyn StreamSource>)
+    };
+    let websocket = async {
+        let source = LogsSource::connect(config.ws_url, address, config.commitment);
+        Ok(Box::new(source.await?) as Box<dyn StreamSource>)
+    };
+    let polling = || {
+        let client =
+            client::build_nonblocking_client(config.rpc_url, config.commitment, config.timeout);
+        Box::new(PollingSource::new(client, *address, config.poll_interval))
+            as Box<dyn StreamSource>
+    };
+    match config.kind {
+        SourceKind::Grpc => grpc.await,
+        SourceKind::Websocket => websocket.await,
+        SourceKind::Polling => Ok(polling()),
+        SourceKind::Auto => {
+            if config.grpc_url.is_some() {
+                match grpc.await {
+                    Ok(source) => return Ok(source),
+                    Err(err) => warn!("gRPC unavailable, falling back to websocket: {err}"),
+                }
+            }
+            match websocket.await {
+                Ok(source) => Ok(source),
+                Err(err) => {
+                    warn!("websocket unavailable, falling back to polling: {err}");
+                    Ok(polling())
+                }
+            }
+        }
+    }
+}
+
+type GrpcUpdates = Pin<Box<dyn futures::Stream<Item = Result<SubscribeUpdate, Status>> + Send>>;
+type GrpcRequests =
+    Pin<Box<dyn futures::Sink<SubscribeRequest, Error = futures::channel::mpsc::SendError> + Send>>;
+
+/// A Yellowstone gRPC transaction subscription.
+pub struct GrpcSource {
+    requests: GrpcRequests,
+    updates: GrpcUpdates,
+}
+
+impl GrpcSource {
+    pub async fn connect(
+        endpoint: &str,
+        x_token: Option<&str>,
+        address: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> Result<Self> {
+        let mut client = GeyserGrpcClient::build_from_shared(endpoint.to_string())
+            .map_err(grpc_error)?
+            .x_token(x_token.map(str::to_string))
+            .map_err(grpc_error)?
+            .tls_config(ClientTlsConfig::new().with_native_roots())
+            .map_err(grpc_error)?
+            .connect_timeout(Duration::from_secs(10))
+            .connect()
+            .await
+            .map_err(grpc_error)?;
+        let commitment = match commitment.commitment {
+            CommitmentLevel::Processed => GrpcCommitment::Processed,
+            CommitmentLevel::Confirmed => GrpcCommitment::Confirmed,
+            CommitmentLevel::Finalized => GrpcCommitment::Finalized,
+        };
+        let request = SubscribeRequest {
+            transactions: HashMap::from([(
+                "transactions".to_string(),
+                SubscribeRequestFilterTransactions {
+                    vote: Some(false),
+                    account_include: vec![address.to_string()],
+                    ..SubscribeRequestFilterTransactions::default()
+                },
+            )]),
+            commitment: Some(commitment as i32),
+            ..SubscribeRequest::default()
+        };
+        let (requests, updates) = client
+            .subscribe_with_request(Some(request))
+            .await
+            .map_err(grpc_error)?;
+        Ok(Self {
+            requests: Box::pin(requests),
+            updates: Box::pin(updates),
+        })
+    }
+}
+
+#[async_trait]
+impl StreamSource for GrpcSource {
+    fn name(&self) -> &'static str {
+        "grpc"
+    }
+
+    async fn next(&mut self) -> Result<Option<TransactionEvent>> {
+        while let Some(update) = self.updates.next().await {
+            match update.map_err(grpc_error)?.update_oneof {
+                Some(UpdateOneof::Transaction(update)) => {
+                    let Some(info) = update.transaction else {
+                        continue;
+                    };
+                    let signature = Signature::try_from(info.signature.as_slice())
+                        .map_err(|_| ExampleError::Parse("signature in gRPC update".into()))?;
+                    return Ok(Some(TransactionEvent {
+                        slot: update.slot,
+                        signature,
+                        failed: info.meta.is_some_and(|meta| meta.err.is_some()),
+                    }));
+                }
+                // Load balancers drop idle streams; answering the server's
+                // pings keeps this one open.
+                Some(UpdateOneof::Ping(_)) => {
+                    self.requests
+                        .send(SubscribeRequest {
+                            ping: Some(SubscribeRequestPing { id: 1 }),
+                            ..SubscribeRequest::default()
+                        })
+                        .await
+                        .map_err(grpc_error)?;
+                }
+                _ => {}
+            }
+        }
+        Ok(None)
+    }
+}
+
+fn grpc_error(err: impl Display) -> ExampleError {
+    ExampleError::Grpc(err.to_string())
+}
+
+/// A websocket `logsSubscribe` for transactions mentioning the address.
+///
+/// The subscription borrows its client, so both live in a task that
+/// forwards notifications over a channel.
+pub struct LogsSource {
+    events: mpsc::Receiver<Result<TransactionEvent>>,
+    task: JoinHandle<()>,
+}
+
+impl LogsSource {
+    pub async fn connect(
+        ws_url: &str,
+        address: &Pubkey,
+        commitment: CommitmentConfig,
+    ) -> Result<Self> {
+        let (ready, subscribed) = oneshot::channel();
+        let (sender, events) = mpsc::channel(LOGS_BUFFER);
+        let ws_url = ws_url.to_string();
+        let filter = RpcTransactionLogsFilter::Mentions(vec![address.to_string()]);
+        let task = tokio::spawn(async move {
+            let client = match PubsubClient::new(&ws_url).await {
+                Ok(client) => client,
+                Err(err) => {
+                    let _ = ready.send(Err(err.into()));
+                    return;
+                }
+            };
+            let config = RpcTransactionLogsConfig {
+                commitment: Some(commitment),
+            };
+            let (mut notifications, unsubscribe) = match client.logs_subscribe(filter, config).await
+            {
+                Ok(subscription) => subscription,
+                Err(err) => {
+                    let _ = ready.send(Err(err.into()));
+                    return;
+                }
+            };
+            let _ = ready.send(Ok(()));
+            while let Some(response) = notifications.next().await {
+                let event = response
+                    .value
+                    .signature
+                    .parse()
+                    .map(|signature| TransactionEvent {
+                        slot: response.context.slot,
+                        signature,
+                        failed: response.value.err.is_some(),
+                    })
+                    .map_err(|_| {
+                        ExampleError::Parse(format!("signature `{}`", response.value.signature))
+                    });
+                if sender.send(event).await.is_err() {
+                    break;
+                }
+            }
+            unsubscribe().await;
+        });
+        match subscribed.await {
+            Ok(Ok(())) => Ok(Self { events, task }),
+            Ok(Err(err)) => Err(err),
+            Err(_) => Err(ExampleError::InvalidArgument(
+                "websocket task ended before subscribing".to_string(),
+            )),
+        }
+    }
+}
+
+impl Drop for LogsSource {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[async_trait]
+impl StreamSource for LogsSource {
+    fn name(&self) -> &'static str {
+        "websocket"
+    }
+
+    async fn next(&mut self) -> Result<Option<TransactionEvent>> {
+        self.events.recv().await.transpose()
+    }
+}
+
+/// Polls `getSignaturesForAddress` every `interval`, yielding what landed
+/// since the previous poll oldest first. Only transactions after the first
+/// poll are reported, like a subscription.
+pub struct PollingSource {
+    client: RpcClient,
+    address: Pubkey,
+    interval: Duration,
+    /// The newest signature seen; `None` before the first poll
+    until: Option<Option<Signature>>,
+    pending: VecDeque<TransactionEvent>,
+}
+
+impl PollingSource {
+    pub fn new(client: RpcClient, address: Pubkey, interval: Duration) -> Self {
+        Self {
+            client,
+            address,
+            interval,
+            until: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Every signature after `until`, newest first; without `until`, the
+    /// `limit` newest.
+    async fn poll(&self, until: Option<Signature>, limit: usize) -> Result<Vec<TransactionEvent>> {
+        let mut events = Vec::new();
+        let mut before = None;
+        loop {
+            let page = self
+                .client
+                .get_signatures_for_address_with_config(
+                    &self.address,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until,
+                        limit: Some(limit),
+                        commitment: Some(at_least_confirmed(self.client.commitment())),
+                    },
+                )
+                .await?;
+            for status in &page {
+                events.push(TransactionEvent {
+                    slot: status.slot,
+                    signature: status.signature.parse().map_err(|_| {
+                        ExampleError::Parse(format!("signature `{}`", status.signature))
+                    })?,
+                    failed: status.err.is_some(),
+                });
+            }
+            if page.len() < limit || until.is_none() {
+                return Ok(events);
+            }
+            before = events.last().map(|event| event.signature);
+        }
+    }
+}
+
+#[async_trait]
+impl StreamSource for PollingSource {
+    fn name(&self) -> &'static str {
+        "polling"
+    }
+
+    async fn next(&mut self) -> Result<Option<TransactionEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            match self.until {
+                None => {
+                    let newest = self
+                        .poll(None, 1)
+                        .await?
+                        .first()
+                        .map(|event| event.signature);
+                    self.until = Some(newest);
+                }
+                Some(until) => {
+                    time::sleep(self.interval).await;
+                    let events = self.poll(until, MAX_SIGNATURES_FOR_ADDRESS).await?;
+                    if let Some(newest) = events.first() {
+                        self.until = Some(Some(newest.signature));
+                    }
+                    self.pending.extend(events.into_iter().rev());
+                }
+            }
+        }
+    }
+}
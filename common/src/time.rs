@@ -0,0 +1,257 @@
+//! Timestamps, durations, and converting between slots and wall-clock time.
+//!
+//! A slot's time is exact when it has a block: [`block_time`] returns the
+//! `blockTime` of the first block at or after it, and [`slot_at_time`]
+//! binary-searches those for the first block at or after a time. Slots the
+//! node has no blocks for, e.g. future ones, are estimated with a
+//! [`SlotClock`], a least-squares fit of slots against block times.
+
+use crate::client::at_least_confirmed;
+use crate::error::{ExampleError, Result};
+use chrono::{DateTime, Utc};
+use solana_client::client_error::ClientErrorKind;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcError;
+use solana_client::rpc_response::RpcPerfSample;
+use solana_sdk::clock::{Slot, UnixTimestamp};
+use std::time::Duration;
+
+/// Formats a Unix timestamp (as returned in `blockTime`) as RFC 3339 UTC.
+pub fn format_timestamp(unix_timestamp: i64) -> String {
+    DateTime::from_timestamp(unix_timestamp, 0)
+        .map(|time| time.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+        .unwrap_or_else(|| unix_timestamp.to_string())
+}
+
+/// Slot duration the cluster targets, used when there are no performance
+/// samples to measure it from.
+pub const TARGET_SLOT_TIME: Duration = Duration::from_millis(400);
+
+/// Average slot duration over `getRecentPerformanceSamples` results, which
+/// is usually a little above the 400ms target.
+pub fn average_slot_time(samples: &[RpcPerfSample]) -> Duration {
+    let seconds: u64 = samples
+        .iter()
+        .map(|sample| u64::from(sample.sample_period_secs))
+        .sum();
+    let slots: u64 = samples.iter().map(|sample| sample.num_slots).sum();
+    if slots == 0 {
+        TARGET_SLOT_TIME
+    } else {
+        Duration::from_secs_f64(seconds as f64 / slots as f64)
+    }
+}
+
+/// Estimated wall-clock time, formatted like [`format_timestamp`], at which
+/// a slot `slots_ahead` slots from now starts.
+pub fn estimate_slot_time(slots_ahead: u64, slot_time: Duration) -> String {
+    let eta = Utc::now() + slot_time.mul_f64(slots_ahead as f64);
+    format_timestamp(eta.timestamp())
+}
+
+/// Formats a duration as e.g. `1d 4h 12m` or `3m 20s`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes) = (seconds / 86_400, seconds / 3_600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{days}d {hours}h {minutes}m")
+    } else if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else {
+        format!("{minutes}m {}s", seconds % 60)
+    }
+}
+
+/// clap value parser accepting an RFC 3339 time such as
+/// `2024-06-01T12:00:00Z` or a Unix timestamp in seconds.
+pub fn parse_timestamp(time: &str) -> Result<UnixTimestamp, String> {
+    if let Ok(seconds) = time.parse() {
+        return Ok(seconds);
+    }
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.timestamp())
+        .map_err(|err| format!("`{time}` is not an RFC 3339 time or Unix timestamp: {err}"))
+}
+
+/// JSON-RPC error codes for slots the node has no block or block time for:
+/// cleaned up, not available, skipped, and skipped or missing in long-term
+/// storage.
+const NO_BLOCK_CODES: [i64; 4] = [-32001, -32004, -32007, -32009];
+
+/// The first block at or after `slot` and its `blockTime`, or `None` if the
+/// node has none up to its latest confirmed block or cannot say when the
+/// block was produced.
+///
+/// Takes two requests: `getBlocksWithLimit` to step over skipped slots,
+/// then `getBlockTime`.
+pub fn block_time(client: &RpcClient, slot: Slot) -> Result<Option<(Slot, UnixTimestamp)>> {
+    let commitment = at_least_confirmed(client.commitment());
+    let Some(&block) = client
+        .get_blocks_with_limit_and_commitment(slot, 1, commitment)
+        .map_err(ExampleError::from)
+        .or_else(|err| {
+            if has_no_block(&err) {
+                Ok(Vec::new())
+            } else {
+                Err(err)
+            }
+        })?
+        .first()
+    else {
+        return Ok(None);
+    };
+    match client.get_block_time(block) {
+        Ok(timestamp) => Ok(Some((block, timestamp))),
+        Err(err) => {
+            let err = ExampleError::from(err);
+            if has_no_block(&err) {
+                Ok(None)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+fn has_no_block(err: &ExampleError) -> bool {
+    let ExampleError::Rpc(err) = err else {
+        return false;
+    };
+    matches!(
+        &err.kind,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if NO_BLOCK_CODES.contains(code)
+    )
+}
+
+/// The first block whose time is `timestamp` or later, found by binary
+/// search between the node's first available block and its latest one, so
+/// about `2 * log2(slots)` requests. `None` if no block is that late yet,
+/// or the time is before the first available block.
+pub fn slot_at_time(
+    client: &RpcClient,
+    timestamp: UnixTimestamp,
+) -> Result<Option<(Slot, UnixTimestamp)>> {
+    let mut low = client.get_first_available_block()?;
+    let mut high = client.get_slot_with_commitment(at_least_confirmed(client.commitment()))?;
+    if block_time(client, low)?.is_some_and(|(_, first)| first > timestamp) {
+        return Ok(None);
+    }
+    // `found` is the earliest block seen so far that is late enough; an
+    // earlier one can only be before the slot that led to it.
+    let mut found = None;
+    while low <= high {
+        let middle = low + (high - low) / 2;
+        let Some((block, time)) = block_time(client, middle)? else {
+            // Nothing at or after `middle` with a time: too recent.
+            if middle == 0 {
+                break;
+            }
+            high = middle - 1;
+            continue;
+        };
+        if time >= timestamp {
+            found = Some((block, time));
+            if middle == 0 {
+                break;
+            }
+            high = middle - 1;
+        } else {
+            // Every slot up to `block` maps to this same, too early, block.
+            low = block + 1;
+        }
+    }
+    Ok(found)
+}
+
+/// A linear fit of slot against time, for estimating when slots the node
+/// has no block for were, or will be, produced.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlotClock {
+    /// A slot on the fitted line and its time, in Unix seconds.
+    pub slot: Slot,
+    pub timestamp: f64,
+    pub slots_per_second: f64,
+}
+
+impl SlotClock {
+    /// Least-squares fit through `(slot, block time)` points. With fewer than
+    /// two distinct slots, or times that do not increase, the line goes
+    /// through the latest point at the target slot rate.
+    pub fn fit(points: &[(Slot, UnixTimestamp)]) -> Option<Self> {
+        let &(last_slot, last_time) = points.iter().max_by_key(|(slot, _)| *slot)?;
+        let target = Self {
+            slot: last_slot,
+            timestamp: last_time as f64,
+            slots_per_second: 1.0 / TARGET_SLOT_TIME.as_secs_f64(),
+        };
+        let n = points.len() as f64;
+        // Centred on the latest point, so the sums stay small enough for
+        // f64 to hold slot numbers in the hundreds of millions exactly.
+        let centred = points
+            .iter()
+            .map(|&(slot, time)| (slot as f64 - last_slot as f64, (time - last_time) as f64));
+        let (mut sum_x, mut sum_y, mut sum_xx, mut sum_xy) = (0.0, 0.0, 0.0, 0.0);
+        for (x, y) in centred {
+            sum_x += x;
+            sum_y += y;
+            sum_xx += x * x;
+            sum_xy += x * y;
+        }
+        let variance = n * sum_xx - sum_x * sum_x;
+        if variance <= 0.0 {
+            return Some(target);
+        }
+        let seconds_per_slot = (n * sum_xy - sum_x * sum_y) / variance;
+        if seconds_per_slot <= 0.0 {
+            return Some(target);
+        }
+        let intercept = (sum_y - seconds_per_slot * sum_x) / n;
+        Some(Self {
+            slot: last_slot,
+            timestamp: last_time as f64 + intercept,
+            slots_per_second: 1.0 / seconds_per_slot,
+        })
+    }
+
+    /// Fits the block times of `samples` slots spread over the `span` slots
+    /// before the latest confirmed one.
+    pub fn recent(client: &RpcClient, span: u64, samples: usize) -> Result<Self> {
+        let tip = client.get_slot_with_commitment(at_least_confirmed(client.commitment()))?;
+        Self::sample(client, tip.saturating_sub(span), tip, samples)
+    }
+
+    /// Fits the block times of `samples` slots spread evenly from `first`
+    /// to `last`.
+    pub fn sample(client: &RpcClient, first: Slot, last: Slot, samples: usize) -> Result<Self> {
+        let samples = samples.max(2) as u64;
+        let step = (last.saturating_sub(first) / (samples - 1)).max(1);
+        let mut points = Vec::new();
+        for slot in (0..samples)
+            .map(|i| first + i * step)
+            .take_while(|&slot| slot <= last)
+        {
+            if let Some(point) = block_time(client, slot)? {
+                points.push(point);
+            }
+        }
+        points.dedup();
+        Self::fit(&points).ok_or_else(|| {
+            ExampleError::Parse(format!("no block times between slots {first} and {last}"))
+        })
+    }
+
+    /// Estimated time `slot` starts, in Unix seconds.
+    pub fn time_at(&self, slot: Slot) -> UnixTimestamp {
+        let slots = slot as f64 - self.slot as f64;
+        (self.timestamp + slots / self.slots_per_second).round() as UnixTimestamp
+    }
+
+    /// Estimated slot at `timestamp`.
+    pub fn slot_at(&self, timestamp: UnixTimestamp) -> Slot {
+        let seconds = timestamp as f64 - self.timestamp;
+        (self.slot as f64 + seconds * self.slots_per_second)
+            .round()
+            .max(0.0) as Slot
+    }
+}
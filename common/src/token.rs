@@ -0,0 +1,236 @@
+//! Token account and mint decoding that works for both the legacy Token
+//! program and Token-2022.
+//!
+//! Token-2022 accounts share the legacy layout and append extensions after
+//! it, so everything is unpacked with `StateWithExtensions`, which also
+//! accepts plain legacy accounts.
+
+use crate::error::{ExampleError, Result};
+use serde_json::json;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTokenAccountsFilter,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{Response, RpcKeyedAccount};
+use solana_sdk::account::Account;
+use solana_sdk::clock::Epoch;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use spl_token_2022::extension::interest_bearing_mint::InterestBearingConfig;
+use spl_token_2022::extension::transfer_fee::{TransferFeeAmount, TransferFeeConfig};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Both token programs, legacy first.
+pub const TOKEN_PROGRAMS: [Pubkey; 2] = [spl_token::ID, spl_token_2022::ID];
+
+/// Short human-readable name of a token program.
+pub fn program_name(program_id: &Pubkey) -> &'static str {
+    if *program_id == spl_token_2022::ID {
+        "Token-2022"
+    } else if *program_id == spl_token::ID {
+        "Token"
+    } else {
+        "unknown"
+    }
+}
+
+/// Returns the owning token program of `account`, failing for accounts owned
+/// by anything else.
+pub fn token_program_of(address: &Pubkey, account: &Account) -> Result<Pubkey> {
+    if TOKEN_PROGRAMS.contains(&account.owner) {
+        Ok(account.owner)
+    } else {
+        Err(ExampleError::InvalidArgument(format!(
+            "{address} is owned by {}, not a token program",
+            account.owner
+        )))
+    }
+}
+
+/// A decoded mint plus the extensions the examples care about.
+#[derive(Debug, Clone)]
+pub struct MintInfo {
+    pub program_id: Pubkey,
+    pub mint: Mint,
+    pub extensions: Vec<ExtensionType>,
+    pub transfer_fee: Option<TransferFeeConfig>,
+    pub interest: Option<InterestBearingConfig>,
+}
+
+impl MintInfo {
+    pub fn unpack(address: &Pubkey, account: &Account) -> Result<Self> {
+        let program_id = token_program_of(address, account)?;
+        let state = StateWithExtensions::<Mint>::unpack(&account.data)?;
+        Ok(Self {
+            program_id,
+            mint: state.base,
+            extensions: state.get_extension_types()?,
+            transfer_fee: state.get_extension::<TransferFeeConfig>().ok().copied(),
+            interest: state.get_extension::<InterestBearingConfig>().ok().copied(),
+        })
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.mint.decimals
+    }
+
+    /// Fee withheld when transferring `amount` base units during `epoch`;
+    /// zero for mints without a transfer fee.
+    pub fn transfer_fee(&self, epoch: Epoch, amount: u64) -> u64 {
+        self.transfer_fee
+            .and_then(|config| config.calculate_epoch_fee(epoch, amount))
+            .unwrap_or(0)
+    }
+
+    /// Formats `amount` for display, including accrued interest for
+    /// interest-bearing mints. Interest is computed against the local clock,
+    /// which is close enough to the cluster clock for display purposes.
+    pub fn ui_amount(&self, amount: u64) -> String {
+        let decimals = self.decimals();
+        self.interest
+            .and_then(|config| {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs() as i64);
+                config.amount_to_ui_amount(amount, decimals, now)
+            })
+            .unwrap_or_else(|| spl_token_2022::amount_to_ui_amount_string_trimmed(amount, decimals))
+    }
+}
+
+/// Fetches and decodes a mint owned by either token program.
+pub fn fetch_mint(client: &RpcClient, mint: &Pubkey) -> Result<MintInfo> {
+    let account = client
+        .get_account_with_commitment(mint, client.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(*mint))?;
+    MintInfo::unpack(mint, &account)
+}
+
+/// `getProgramAccounts` filters selecting the token accounts of `mint`.
+///
+/// The mint is the first field of a token account. Legacy accounts are always
+/// exactly `Account::LEN` bytes, which lets the node skip mints and multisigs
+/// cheaply; Token-2022 accounts grow with their extensions, so only the
+/// `memcmp` filter applies there.
+pub fn mint_account_filters(program_id: &Pubkey, mint: &Pubkey) -> Vec<RpcFilterType> {
+    let mut filters = vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+        0,
+        mint.as_ref(),
+    ))];
+    if *program_id == spl_token::ID {
+        filters.push(RpcFilterType::DataSize(TokenAccount::LEN as u64));
+    }
+    filters
+}
+
+/// A decoded token account plus its Token-2022 extension state.
+#[derive(Debug, Clone)]
+pub struct TokenAccountInfo {
+    pub program_id: Pubkey,
+    pub account: TokenAccount,
+    pub extensions: Vec<ExtensionType>,
+    /// Transfer fees withheld in this account, waiting to be harvested.
+    pub withheld: u64,
+}
+
+impl TokenAccountInfo {
+    pub fn unpack(address: &Pubkey, account: &Account) -> Result<Self> {
+        let program_id = token_program_of(address, account)?;
+        let state = StateWithExtensions::<TokenAccount>::unpack(&account.data)?;
+        Ok(Self {
+            program_id,
+            account: state.base,
+            extensions: state.get_extension_types()?,
+            withheld: state
+                .get_extension::<TransferFeeAmount>()
+                .map_or(0, |fee| fee.withheld_amount.into()),
+        })
+    }
+}
+
+/// A token account found by [`fetch_token_accounts`] or
+/// [`fetch_mint_accounts`].
+#[derive(Debug, Clone)]
+pub struct TokenHolding {
+    pub address: Pubkey,
+    /// The rent deposit, returned to whoever closes the account.
+    pub lamports: u64,
+    pub info: TokenAccountInfo,
+}
+
+/// Fetches and decodes every token account `owner` has under either token
+/// program.
+///
+/// `get_token_accounts_by_owner` always asks for `jsonParsed` data, so the
+/// request is sent by hand with base64 encoding to decode it locally. The
+/// filter takes a single program, so each token program is queried in turn.
+pub fn fetch_token_accounts(client: &RpcClient, owner: &Pubkey) -> Result<Vec<TokenHolding>> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(client.commitment()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let mut holdings = Vec::new();
+    for program_id in TOKEN_PROGRAMS {
+        let response: Response<Vec<RpcKeyedAccount>> = client.send(
+            RpcRequest::GetTokenAccountsByOwner,
+            json!([
+                owner.to_string(),
+                RpcTokenAccountsFilter::ProgramId(program_id.to_string()),
+                config
+            ]),
+        )?;
+        for keyed in response.value {
+            let address: Pubkey = keyed.pubkey.parse()?;
+            let account: Account = keyed
+                .account
+                .decode()
+                .ok_or_else(|| ExampleError::Parse(format!("account data for {address}")))?;
+            holdings.push(TokenHolding {
+                address,
+                lamports: account.lamports,
+                info: TokenAccountInfo::unpack(&address, &account)?,
+            });
+        }
+    }
+    Ok(holdings)
+}
+
+/// Fetches and decodes every token account of `mint`, whose decoded state
+/// says which program to ask.
+pub fn fetch_mint_accounts(
+    client: &RpcClient,
+    mint: &Pubkey,
+    info: &MintInfo,
+) -> Result<Vec<TokenHolding>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(mint_account_filters(&info.program_id, mint)),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(client.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = client.get_program_accounts_with_config(&info.program_id, config)?;
+    // Without a size filter a Token-2022 result could in principle be some
+    // other account type whose first 32 bytes happen to match, so anything
+    // that does not decode as a token account is skipped.
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| {
+            let info = TokenAccountInfo::unpack(&address, &account).ok()?;
+            Some(TokenHolding {
+                address,
+                lamports: account.lamports,
+                info,
+            })
+        })
+        .collect())
+}
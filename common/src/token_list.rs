@@ -0,0 +1,67 @@
+//! Token names and symbols from a token-list JSON file.
+//!
+//! Accepts the classic Solana token-list layout (`{"tokens": [...]}`) and a
+//! bare array of the same entries, which is what most current token APIs
+//! export.
+
+use crate::error::{ExampleError, Result};
+use serde::Deserialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenListEntry {
+    pub address: String,
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    #[serde(default, alias = "logoURI")]
+    pub logo_uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum TokenListFile {
+    Wrapped { tokens: Vec<TokenListEntry> },
+    Bare(Vec<TokenListEntry>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TokenList {
+    by_mint: HashMap<Pubkey, TokenListEntry>,
+}
+
+impl TokenList {
+    /// Loads a token list; entries whose address is not a valid public key
+    /// are skipped.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)?;
+        let file: TokenListFile = serde_json::from_str(&json)
+            .map_err(|err| ExampleError::Parse(format!("token list {}: {err}", path.display())))?;
+        let entries = match file {
+            TokenListFile::Wrapped { tokens } => tokens,
+            TokenListFile::Bare(tokens) => tokens,
+        };
+        let by_mint = entries
+            .into_iter()
+            .filter_map(|entry| Some((entry.address.parse().ok()?, entry)))
+            .collect();
+        Ok(Self { by_mint })
+    }
+
+    pub fn get(&self, mint: &Pubkey) -> Option<&TokenListEntry> {
+        self.by_mint.get(mint)
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_mint.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_mint.is_empty()
+    }
+}
@@ -0,0 +1,212 @@
+//! Composing a transaction from its parts: the instructions, an optional
+//! priority fee and memo, and the address lookup tables that make it a v0
+//! transaction, then signing and sending it.
+//!
+//! ```ignore
+//! let signature = TxBuilder::new(&payer)
+//!     .add_instruction(system_instruction::transfer(&payer.pubkey(), &to, lamports))
+//!     .with_priority_fee(fee)
+//!     .with_memo("invoice 42")
+//!     .sign_and_send(&connection)?;
+//! ```
+
+use crate::blockhash::BlockhashCache;
+use crate::error::{ExampleError, Result};
+use crate::fees::PriorityFee;
+use crate::memo::memo_instruction;
+use crate::send::send_signed_with_fresh_blockhash;
+use crate::tx_size::{MAX_TRANSACTION_SIZE, TransactionBudget};
+use solana_address_lookup_table_interface::state::AddressLookupTable;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{AddressLookupTableAccount, Message, VersionedMessage, v0};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use solana_sdk::transaction::VersionedTransaction;
+
+/// How many times an expired transaction is re-signed before giving up,
+/// unless [`TxBuilder::with_max_resigns`] says otherwise.
+pub const DEFAULT_MAX_RESIGNS: u32 = 3;
+
+/// A transaction being put together. The fee payer signs first; signers
+/// added with [`TxBuilder::add_signer`] follow it.
+///
+/// The compute budget instructions of a priority fee go first, where
+/// wallets and explorers look for them, and the memo last. With lookup tables
+/// the transaction is compiled as v0, otherwise as a legacy one.
+pub struct TxBuilder<'a> {
+    payer: &'a dyn Signer,
+    signers: Vec<&'a dyn Signer>,
+    instructions: Vec<Instruction>,
+    priority_fee: Option<PriorityFee>,
+    memo: Option<String>,
+    lookup_tables: Vec<AddressLookupTableAccount>,
+    blockhash_cache: Option<&'a BlockhashCache>,
+    max_resigns: u32,
+}
+
+impl<'a> TxBuilder<'a> {
+    /// An empty transaction paid for and signed by `payer`.
+    pub fn new(payer: &'a dyn Signer) -> Self {
+        Self {
+            payer,
+            signers: Vec::new(),
+            instructions: Vec::new(),
+            priority_fee: None,
+            memo: None,
+            lookup_tables: Vec::new(),
+            blockhash_cache: None,
+            max_resigns: DEFAULT_MAX_RESIGNS,
+        }
+    }
+
+    pub fn add_instruction(mut self, instruction: Instruction) -> Self {
+        self.instructions.push(instruction);
+        self
+    }
+
+    pub fn add_instructions(mut self, instructions: impl IntoIterator<Item = Instruction>) -> Self {
+        self.instructions.extend(instructions);
+        self
+    }
+
+    /// Another signer the instructions need, e.g. a new account's keypair.
+    /// Signing twice for the payer is not needed.
+    pub fn add_signer(mut self, signer: &'a dyn Signer) -> Self {
+        self.signers.push(signer);
+        self
+    }
+
+    pub fn with_priority_fee(mut self, fee: PriorityFee) -> Self {
+        self.priority_fee = Some(fee);
+        self
+    }
+
+    /// Attaches `memo` with an SPL Memo instruction signed by the payer.
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Tables to look accounts up in, as fetched by [`fetch_lookup_tables`].
+    pub fn with_lookup_tables(mut self, tables: Vec<AddressLookupTableAccount>) -> Self {
+        self.lookup_tables = tables;
+        self
+    }
+
+    /// Shares a blockhash cache with other transactions being sent, instead
+    /// of fetching a blockhash for this one.
+    pub fn with_blockhash_cache(mut self, cache: &'a BlockhashCache) -> Self {
+        self.blockhash_cache = Some(cache);
+        self
+    }
+
+    pub fn with_max_resigns(mut self, max_resigns: u32) -> Self {
+        self.max_resigns = max_resigns;
+        self
+    }
+
+    pub fn payer(&self) -> Pubkey {
+        self.payer.pubkey()
+    }
+
+    /// Every instruction the transaction will carry, in order.
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let payer = self.payer();
+        self.priority_fee
+            .iter()
+            .flat_map(PriorityFee::instructions)
+            .chain(self.instructions.iter().cloned())
+            .chain(
+                self.memo
+                    .iter()
+                    .map(|memo| memo_instruction(memo, &[&payer])),
+            )
+            .collect()
+    }
+
+    /// How much of the legacy transaction limits the instructions use.
+    pub fn budget(&self) -> TransactionBudget {
+        TransactionBudget::measure(&self.instructions(), &self.payer())
+    }
+
+    /// Compiles and signs the transaction over `blockhash`.
+    pub fn build(&self, blockhash: Hash) -> Result<VersionedTransaction> {
+        let instructions = self.instructions();
+        let payer = self.payer();
+        let message = if self.lookup_tables.is_empty() {
+            VersionedMessage::Legacy(Message::new_with_blockhash(
+                &instructions,
+                Some(&payer),
+                &blockhash,
+            ))
+        } else {
+            VersionedMessage::V0(
+                v0::Message::try_compile(&payer, &instructions, &self.lookup_tables, blockhash)
+                    .map_err(|err| {
+                        ExampleError::InvalidArgument(format!("cannot compile message: {err}"))
+                    })?,
+            )
+        };
+        let signers: Vec<&dyn Signer> = [self.payer]
+            .into_iter()
+            .chain(
+                self.signers
+                    .iter()
+                    .copied()
+                    .filter(|signer| signer.pubkey() != payer),
+            )
+            .collect();
+        let transaction = VersionedTransaction::try_new(message, &signers)?;
+        let size = bincode::serialized_size(&transaction).map_or(usize::MAX, |size| size as usize);
+        if size > MAX_TRANSACTION_SIZE {
+            let legacy_suggestion = self
+                .lookup_tables
+                .is_empty()
+                .then(|| self.budget().suggestion())
+                .flatten();
+            let message = legacy_suggestion.unwrap_or_else(|| {
+                format!("the transaction is {size} bytes, over the limit of {MAX_TRANSACTION_SIZE}")
+            });
+            return Err(ExampleError::InvalidArgument(message));
+        }
+        Ok(transaction)
+    }
+
+    /// Signs and sends the transaction and waits for the client's
+    /// commitment, re-signing it with a fresh blockhash if it expires; see
+    /// [`crate::send::send_with_fresh_blockhash`].
+    pub fn sign_and_send(&self, client: &RpcClient) -> Result<Signature> {
+        let owned_cache;
+        let cache = match self.blockhash_cache {
+            Some(cache) => cache,
+            None => {
+                owned_cache = BlockhashCache::default();
+                &owned_cache
+            }
+        };
+        send_signed_with_fresh_blockhash(client, cache, self.max_resigns, |blockhash| {
+            self.build(blockhash)
+        })
+    }
+}
+
+/// Fetches address lookup tables for [`TxBuilder::with_lookup_tables`].
+pub fn fetch_lookup_tables(
+    client: &RpcClient,
+    tables: &[Pubkey],
+) -> Result<Vec<AddressLookupTableAccount>> {
+    tables
+        .iter()
+        .map(|table| {
+            let account = client.get_account(table)?;
+            let state = AddressLookupTable::deserialize(&account.data)
+                .map_err(|err| ExampleError::Parse(format!("lookup table {table}: {err}")))?;
+            Ok(AddressLookupTableAccount {
+                key: *table,
+                addresses: state.addresses.to_vec(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+//! Whether a set of instructions fits in one transaction: the serialized
+//! size against the 1232-byte packet limit, and the number of accounts it
+//! locks against the runtime's limit.
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Largest serialized transaction, signatures included.
+pub const MAX_TRANSACTION_SIZE: usize = PACKET_DATA_SIZE;
+
+/// Most accounts one transaction may lock.
+pub const MAX_ACCOUNT_LOCKS: usize = 64;
+
+/// A table entry is a one-byte index instead of a 32-byte key.
+const LOOKUP_SAVING_PER_KEY: usize = 31;
+
+/// A v0 message's version byte plus one table's key and two length
+/// prefixes.
+const LOOKUP_TABLE_OVERHEAD: usize = 1 + 32 + 2;
+
+/// How much of the transaction limits a set of instructions uses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionBudget {
+    /// Serialized size of the legacy transaction, signatures included
+    pub size: usize,
+    /// Unique accounts, programs included
+    pub accounts: usize,
+    pub signatures: usize,
+    /// Accounts that could move to an address lookup table: those that
+    /// neither sign nor are invoked as a program
+    pub lookup_candidates: usize,
+}
+
+impl TransactionBudget {
+    /// Measures a legacy transaction carrying `instructions`, paid for by
+    /// `payer`.
+    pub fn measure(instructions: &[Instruction], payer: &Pubkey) -> Self {
+        let message = Message::new(instructions, Some(payer));
+        let signatures = usize::from(message.header.num_required_signatures);
+        let programs = message
+            .instructions
+            .iter()
+            .map(|instruction| instruction.program_id_index)
+            .collect::<HashSet<_>>()
+            .len();
+        let accounts = message.account_keys.len();
+        let transaction = Transaction::new_unsigned(message);
+        Self {
+            size: bincode::serialized_size(&transaction).map_or(usize::MAX, |size| size as usize),
+            accounts,
+            signatures,
+            lookup_candidates: accounts.saturating_sub(signatures + programs),
+        }
+    }
+
+    pub fn fits(&self) -> bool {
+        self.size <= MAX_TRANSACTION_SIZE && self.accounts <= MAX_ACCOUNT_LOCKS
+    }
+
+    /// Estimated size as a v0 transaction with every candidate account in
+    /// one address lookup table.
+    pub fn size_with_lookup_table(&self) -> usize {
+        (self.size + LOOKUP_TABLE_OVERHEAD)
+            .saturating_sub(self.lookup_candidates * LOOKUP_SAVING_PER_KEY)
+    }
+
+    /// What to do about a transaction over budget, or nothing when it fits.
+    /// A lookup table shrinks the transaction but does not lift the account
+    /// limit, so too many accounts always means splitting it.
+    pub fn suggestion(&self) -> Option<String> {
+        if self.fits() {
+            return None;
+        }
+        if self.accounts > MAX_ACCOUNT_LOCKS {
+            return Some(format!(
+                "{} accounts is over the limit of {MAX_ACCOUNT_LOCKS}; split the instructions \
+                 across transactions",
+                self.accounts
+            ));
+        }
+        let with_table = self.size_with_lookup_table();
+        Some(if with_table <= MAX_TRANSACTION_SIZE {
+            format!(
+                "{} bytes is over the limit of {MAX_TRANSACTION_SIZE}; an address lookup table \
+                 holding its {} non-signer accounts would bring it to about {with_table} bytes",
+                self.size, self.lookup_candidates
+            )
+        } else {
+            format!(
+                "{} bytes is over the limit of {MAX_TRANSACTION_SIZE}, and about {with_table} \
+                 even with an address lookup table; split the instructions across transactions",
+                self.size
+            )
+        })
+    }
+}
+
+impl fmt::Display for TransactionBudget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{MAX_TRANSACTION_SIZE} bytes, {}/{MAX_ACCOUNT_LOCKS} accounts, {} signatures",
+            self.size, self.accounts, self.signatures
+        )
+    }
+}
@@ -0,0 +1,59 @@
+//! Conversions between base units (lamports, raw token amounts) and
+//! human-readable decimal amounts.
+
+use crate::error::{ExampleError, Result};
+use solana_sdk::native_token::sol_str_to_lamports;
+
+/// clap value parser turning a decimal SOL amount such as `0.25` into lamports.
+pub fn parse_sol(amount: &str) -> Result<u64, String> {
+    sol_str_to_lamports(amount).ok_or_else(|| format!("`{amount}` is not a valid SOL amount"))
+}
+
+/// Renders lamports as SOL with up to nine decimals and no trailing zeros.
+pub fn format_sol(lamports: u64) -> String {
+    format_token_amount(lamports, 9)
+}
+
+/// Renders a raw token amount with `decimals` decimals and no trailing zeros.
+pub fn format_token_amount(amount: u64, decimals: u8) -> String {
+    let decimals = usize::from(decimals);
+    let digits = format!("{amount:0>width$}", width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+/// Signed difference between two raw amounts, such as `+1.5` or `-0.000005`.
+pub fn format_change(before: u64, after: u64, decimals: u8) -> String {
+    if after >= before {
+        format!("+{}", format_token_amount(after - before, decimals))
+    } else {
+        format!("-{}", format_token_amount(before - after, decimals))
+    }
+}
+
+/// Turns a decimal token amount such as `12.5` into base units for a mint
+/// with `decimals` decimals, without going through floating point.
+pub fn parse_token_amount(amount: &str, decimals: u8) -> Result<u64> {
+    let invalid = || {
+        ExampleError::InvalidArgument(format!(
+            "`{amount}` is not a valid amount for a mint with {decimals} decimals"
+        ))
+    };
+    let (whole, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+    let is_digits = |part: &str| part.bytes().all(|byte| byte.is_ascii_digit());
+    if (whole.is_empty() && fraction.is_empty())
+        || !is_digits(whole)
+        || !is_digits(fraction)
+        || fraction.len() > usize::from(decimals)
+    {
+        return Err(invalid());
+    }
+    format!("{whole}{fraction:0<width$}", width = usize::from(decimals))
+        .parse()
+        .map_err(|_| invalid())
+}
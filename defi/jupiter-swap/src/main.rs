@@ -0,0 +1,224 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::jupiter::{self, DEFAULT_SWAP_API_URL, Quote};
+use common::output::OutputArgs;
+use common::units::{format_token_amount, parse_token_amount};
+use common::{ExampleError, Result, explorer, fees, token};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::process::ExitCode;
+
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// Swap tokens through Jupiter: fetch a quote, have the swap API build the
+/// transaction, sign it locally and send it through your own RPC endpoint
+/// with a priority fee estimated from the route's accounts. Defaults to
+/// SOL -> USDC on mainnet.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Wallet that pays and receives the swap
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Amount of the input token to swap, in whole tokens (e.g. 0.1)
+    #[arg(short, long)]
+    amount: String,
+
+    #[arg(long, default_value = SOL_MINT)]
+    input_mint: Pubkey,
+
+    #[arg(long, default_value = USDC_MINT)]
+    output_mint: Pubkey,
+
+    /// Maximum slippage in basis points
+    #[arg(short, long, default_value_t = 50)]
+    slippage_bps: u16,
+
+    /// Percentile of recent fees on the route's accounts to pay, 0-100
+    #[arg(short, long, default_value_t = 75, value_parser = clap::value_parser!(u8).range(0..=100))]
+    percentile: u8,
+
+    /// Jupiter swap API base URL
+    #[arg(
+        long,
+        env = "JUPITER_API_URL",
+        default_value = DEFAULT_SWAP_API_URL
+    )]
+    jupiter_url: String,
+
+    /// Print the quote and stop without swapping
+    #[arg(long)]
+    quote_only: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SwapResponse {
+    swap_transaction: String,
+    last_valid_block_height: u64,
+}
+
+#[derive(Serialize)]
+struct SwapSummary {
+    input_mint: String,
+    output_mint: String,
+    in_amount: String,
+    out_amount: String,
+    minimum_out: String,
+    /// In percent
+    price_impact_pct: f64,
+    route: Vec<RouteStep>,
+    /// Unset with --quote-only
+    priority_fee_micro_lamports: Option<u64>,
+    signature: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RouteStep {
+    percent: u8,
+    label: Option<String>,
+    amm_key: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let input_mint = token::fetch_mint(&connection, &args.input_mint)?;
+    let output_mint = token::fetch_mint(&connection, &args.output_mint)?;
+    let amount = parse_token_amount(&args.amount, input_mint.decimals())?;
+    let http = reqwest::blocking::Client::new();
+
+    // Kept raw for the swap request, which wants the quote back unchanged.
+    let raw_quote = jupiter::fetch_quote(
+        &http,
+        &args.jupiter_url,
+        &[
+            ("inputMint", args.input_mint.to_string()),
+            ("outputMint", args.output_mint.to_string()),
+            ("amount", amount.to_string()),
+            ("slippageBps", args.slippage_bps.to_string()),
+        ],
+    )?;
+    let quote = Quote::decode(&raw_quote)?;
+    let mut summary = summarize(&args, &quote, input_mint.decimals(), output_mint.decimals())?;
+    if output.is_table() {
+        print_quote(&summary);
+    }
+    if args.quote_only {
+        return output.print_structured(&summary);
+    }
+
+    // Priority fees are local to the accounts a transaction write-locks, so
+    // the estimate looks at the pools on the route rather than the whole
+    // cluster.
+    let mut writable_accounts = vec![payer.pubkey()];
+    for step in &quote.route_plan {
+        writable_accounts.push(step.swap_info.amm_key.parse()?);
+    }
+    let micro_lamports =
+        fees::estimate_priority_fee(&connection, &writable_accounts, args.percentile)?;
+    output.status(format_args!(
+        "Priority fee: {micro_lamports} micro-lamports/CU (p{})",
+        args.percentile
+    ));
+
+    let swap: SwapResponse = http
+        .post(format!("{}/swap", args.jupiter_url))
+        .json(&json!({
+            "quoteResponse": raw_quote,
+            "userPublicKey": payer.pubkey().to_string(),
+            "wrapAndUnwrapSol": true,
+            "dynamicComputeUnitLimit": true,
+            "computeUnitPriceMicroLamports": micro_lamports,
+        }))
+        .send()?
+        .error_for_status()?
+        .json()?;
+
+    let bytes = BASE64
+        .decode(&swap.swap_transaction)
+        .map_err(|err| ExampleError::Parse(format!("swap transaction: {err}")))?;
+    let unsigned: VersionedTransaction = bincode::deserialize(&bytes)
+        .map_err(|err| ExampleError::Parse(format!("swap transaction: {err}")))?;
+    // The API returns the message with an empty signature slot for the user;
+    // signing the message again fills it in.
+    let transaction = VersionedTransaction::try_new(unsigned.message, &[&payer])?;
+
+    output.status(format_args!(
+        "Sending swap (valid until block height {})",
+        swap.last_valid_block_height
+    ));
+    let signature = connection.send_and_confirm_transaction(&transaction)?;
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+    summary.priority_fee_micro_lamports = Some(micro_lamports);
+    summary.signature = Some(signature.to_string());
+    output.print_structured(&summary)
+}
+
+fn summarize(
+    args: &Args,
+    quote: &Quote,
+    input_decimals: u8,
+    output_decimals: u8,
+) -> Result<SwapSummary> {
+    let amount = |value: &str, decimals| -> Result<String> {
+        Ok(format_token_amount(jupiter::parse_amount(value)?, decimals))
+    };
+    Ok(SwapSummary {
+        input_mint: args.input_mint.to_string(),
+        output_mint: args.output_mint.to_string(),
+        in_amount: amount(&quote.in_amount, input_decimals)?,
+        out_amount: amount(&quote.out_amount, output_decimals)?,
+        minimum_out: amount(&quote.other_amount_threshold, output_decimals)?,
+        price_impact_pct: quote.price_impact_percent()?,
+        route: quote
+            .route_plan
+            .iter()
+            .map(|step| RouteStep {
+                percent: step.percent,
+                label: step.swap_info.label.clone(),
+                amm_key: step.swap_info.amm_key.clone(),
+            })
+            .collect(),
+        priority_fee_micro_lamports: None,
+        signature: None,
+    })
+}
+
+fn print_quote(summary: &SwapSummary) {
+    println!("In:            {}", summary.in_amount);
+    println!("Out:           {}", summary.out_amount);
+    println!("Minimum out:   {}", summary.minimum_out);
+    println!("Price impact:  {:.4}%", summary.price_impact_pct);
+    println!("Route:");
+    for step in &summary.route {
+        println!(
+            "  {:>3}%  {:<20}  {}",
+            step.percent,
+            step.label.as_deref().unwrap_or("?"),
+            step.amm_key
+        );
+    }
+}
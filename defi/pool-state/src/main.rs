@@ -0,0 +1,271 @@
+use borsh::BorshDeserialize;
+use clap::Parser;
+use common::anchor::{self, DISCRIMINATOR_LEN};
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::price::{self, DEFAULT_PRICE_API_URL};
+use common::raydium::{self, AmmInfo};
+use common::token::{MintInfo, TokenAccountInfo};
+use common::{ExampleError, Result, batch};
+use serde::Serialize;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+
+const WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+/// Read a Raydium AMM v4 or Orca Whirlpool pool straight from its account
+/// and print the current price, the reserves and the pool's TVL.
+///
+/// The account layouts are decoded here by hand, as they would be for any
+/// program without a published Rust crate: Raydium's price comes from the
+/// reserves in its two vaults, a Whirlpool's from the square-root price it
+/// stores, which is what its concentrated liquidity trades at.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Pool account address (base58); the owning program tells which kind
+    /// it is
+    pool: Pubkey,
+
+    /// Also value the TVL in USD with the Jupiter price API
+    #[arg(long)]
+    usd: bool,
+
+    /// Jupiter price API endpoint
+    #[arg(long, env = "JUPITER_PRICE_API_URL", default_value = DEFAULT_PRICE_API_URL)]
+    price_api: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// The leading part of a Whirlpool account, after its Anchor discriminator.
+#[derive(BorshDeserialize)]
+struct Whirlpool {
+    _whirlpools_config: Pubkey,
+    _bump: u8,
+    tick_spacing: u16,
+    _fee_tier_index_seed: [u8; 2],
+    /// In hundredths of a basis point
+    fee_rate: u16,
+    _protocol_fee_rate: u16,
+    liquidity: u128,
+    /// Square root of the price of A in B, as a Q64.64 fixed-point number
+    sqrt_price: u128,
+    tick_current_index: i32,
+    /// Protocol fees still sitting in the vaults
+    protocol_fees_owed: [u64; 2],
+    token_mint_a: Pubkey,
+    token_vault_a: Pubkey,
+    _fee_growth_global_a: u128,
+    token_mint_b: Pubkey,
+    token_vault_b: Pubkey,
+    // Fee growth and reward emissions follow.
+}
+
+#[derive(Serialize)]
+struct PoolState {
+    pool: String,
+    kind: &'static str,
+    base_mint: String,
+    quote_mint: String,
+    /// Reserves in whole tokens
+    base_reserve: String,
+    quote_reserve: String,
+    /// Quote tokens per base token
+    price: f64,
+    /// Base tokens per quote token
+    inverse_price: f64,
+    /// Swap fee, in percent
+    fee: f64,
+    /// Both reserves valued in the quote token
+    tvl_quote: f64,
+    tvl_usd: Option<f64>,
+    /// Whirlpools only: liquidity active at the current tick
+    liquidity: Option<String>,
+    tick_current_index: Option<i32>,
+    tick_spacing: Option<u16>,
+}
+
+/// What the two decoders have in common: a pair of mints, their vaults and
+/// how to price one in the other.
+struct Pool {
+    kind: &'static str,
+    mints: [Pubkey; 2],
+    vaults: [Pubkey; 2],
+    /// Amounts to subtract from the vault balances to get the reserves
+    owed: [u64; 2],
+    fee: f64,
+    /// Set for Whirlpools, whose price comes from here
+    whirlpool: Option<Whirlpool>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let account = connection
+        .get_account_with_commitment(&args.pool, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(args.pool))?;
+    let pool = match account.owner {
+        raydium::AMM_V4_PROGRAM_ID => raydium(&args.pool, &account)?,
+        WHIRLPOOL_PROGRAM_ID => whirlpool(&args.pool, &account)?,
+        owner => {
+            return Err(ExampleError::InvalidArgument(format!(
+                "{} is owned by {owner}, not Raydium AMM v4 or Orca Whirlpool",
+                args.pool
+            )));
+        }
+    };
+
+    let accounts = batch::get_multiple_accounts(
+        &connection,
+        &[pool.mints[0], pool.mints[1], pool.vaults[0], pool.vaults[1]],
+    )?;
+    let found = |index: usize, key: &Pubkey| -> Result<&Account> {
+        accounts[index]
+            .as_ref()
+            .ok_or(ExampleError::AccountNotFound(*key))
+    };
+    let mints = [
+        MintInfo::unpack(&pool.mints[0], found(0, &pool.mints[0])?)?,
+        MintInfo::unpack(&pool.mints[1], found(1, &pool.mints[1])?)?,
+    ];
+    let reserves = [
+        TokenAccountInfo::unpack(&pool.vaults[0], found(2, &pool.vaults[0])?)?
+            .account
+            .amount
+            .saturating_sub(pool.owed[0]),
+        TokenAccountInfo::unpack(&pool.vaults[1], found(3, &pool.vaults[1])?)?
+            .account
+            .amount
+            .saturating_sub(pool.owed[1]),
+    ];
+    let whole = [
+        whole_tokens(reserves[0], mints[0].decimals()),
+        whole_tokens(reserves[1], mints[1].decimals()),
+    ];
+
+    let price = match pool
+        .whirlpool
+        .as_ref()
+        .map(|whirlpool| whirlpool.sqrt_price)
+    {
+        Some(sqrt_price) => {
+            let sqrt = sqrt_price as f64 / 2f64.powi(64);
+            sqrt * sqrt
+                * 10f64.powi(i32::from(mints[0].decimals()) - i32::from(mints[1].decimals()))
+        }
+        // A constant-product pool trades at the ratio of its reserves.
+        None if whole[0] > 0.0 => whole[1] / whole[0],
+        None => 0.0,
+    };
+    let tvl_usd = if args.usd {
+        let http = reqwest::blocking::Client::new();
+        let prices = price::fetch_usd_prices(&http, &args.price_api, &pool.mints)?;
+        match (prices.get(&pool.mints[0]), prices.get(&pool.mints[1])) {
+            (Some(base), Some(quote)) => Some(whole[0] * base + whole[1] * quote),
+            // One price is enough: the pool prices the other side.
+            (None, Some(quote)) => Some((whole[0] * price + whole[1]) * quote),
+            (Some(base), None) if price > 0.0 => Some((whole[0] + whole[1] / price) * base),
+            _ => None,
+        }
+    } else {
+        None
+    };
+
+    let state = PoolState {
+        pool: args.pool.to_string(),
+        kind: pool.kind,
+        base_mint: pool.mints[0].to_string(),
+        quote_mint: pool.mints[1].to_string(),
+        base_reserve: mints[0].ui_amount(reserves[0]),
+        quote_reserve: mints[1].ui_amount(reserves[1]),
+        price,
+        inverse_price: if price > 0.0 { 1.0 / price } else { 0.0 },
+        fee: pool.fee,
+        tvl_quote: whole[0] * price + whole[1],
+        tvl_usd,
+        liquidity: pool
+            .whirlpool
+            .as_ref()
+            .map(|whirlpool| whirlpool.liquidity.to_string()),
+        tick_current_index: pool
+            .whirlpool
+            .as_ref()
+            .map(|whirlpool| whirlpool.tick_current_index),
+        tick_spacing: pool
+            .whirlpool
+            .as_ref()
+            .map(|whirlpool| whirlpool.tick_spacing),
+    };
+    args.output.print(&state, || {
+        println!("Pool:      {} ({})", state.pool, state.kind);
+        println!("Base:      {} ({})", state.base_mint, state.base_reserve);
+        println!("Quote:     {} ({})", state.quote_mint, state.quote_reserve);
+        println!("Price:     {} quote per base", state.price);
+        println!("           {} base per quote", state.inverse_price);
+        println!("Fee:       {}%", state.fee);
+        println!("TVL:       {:.2} in the quote token", state.tvl_quote);
+        if let Some(tvl_usd) = state.tvl_usd {
+            println!("           ${tvl_usd:.2}");
+        }
+        if let (Some(liquidity), Some(tick), Some(spacing)) = (
+            &state.liquidity,
+            state.tick_current_index,
+            state.tick_spacing,
+        ) {
+            println!("Liquidity: {liquidity} at tick {tick} (spacing {spacing})");
+        }
+    })?;
+    Ok(())
+}
+
+fn raydium(address: &Pubkey, account: &Account) -> Result<Pool> {
+    let amm = AmmInfo::unpack(address, account)?;
+    Ok(Pool {
+        kind: "raydium-amm-v4",
+        mints: [amm.coin_mint, amm.pc_mint],
+        vaults: [amm.coin_vault, amm.pc_vault],
+        owed: [amm.need_take_pnl_coin, amm.need_take_pnl_pc],
+        fee: if amm.swap_fee_denominator == 0 {
+            0.0
+        } else {
+            amm.swap_fee_numerator as f64 / amm.swap_fee_denominator as f64 * 100.0
+        },
+        whirlpool: None,
+    })
+}
+
+fn whirlpool(address: &Pubkey, account: &Account) -> Result<Pool> {
+    let invalid = || ExampleError::Parse(format!("{address} is not a Whirlpool"));
+    let (discriminator, mut data) = account
+        .data
+        .split_at_checked(DISCRIMINATOR_LEN)
+        .ok_or_else(invalid)?;
+    if discriminator != anchor::account_discriminator("Whirlpool") {
+        return Err(invalid());
+    }
+    let whirlpool = Whirlpool::deserialize(&mut data)
+        .map_err(|err| ExampleError::Parse(format!("Whirlpool {address}: {err}")))?;
+    Ok(Pool {
+        kind: "orca-whirlpool",
+        mints: [whirlpool.token_mint_a, whirlpool.token_mint_b],
+        vaults: [whirlpool.token_vault_a, whirlpool.token_vault_b],
+        owed: whirlpool.protocol_fees_owed,
+        fee: f64::from(whirlpool.fee_rate) / 10_000.0,
+        whirlpool: Some(whirlpool),
+    })
+}
+
+fn whole_tokens(amount: u64, decimals: u8) -> f64 {
+    amount as f64 / 10f64.powi(i32::from(decimals))
+}
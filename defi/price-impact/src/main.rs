@@ -0,0 +1,314 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::jupiter::{self, DEFAULT_SWAP_API_URL, Quote};
+use common::output::OutputArgs;
+use common::raydium::AmmInfo;
+use common::token::{MintInfo, TokenAccountInfo};
+use common::units::{format_token_amount, parse_token_amount};
+use common::{ExampleError, Result, batch};
+use serde::Serialize;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+
+/// Work out what a swap through a Raydium AMM v4 pool would return, and how
+/// far it would move the price, from the pool's reserves alone, then ask
+/// Jupiter for a quote of the same trade to compare.
+///
+/// A constant-product pool keeps `reserve_in * reserve_out` constant: the
+/// pool takes its fee from the input, and the rest buys
+/// `reserve_out * in / (reserve_in + in)` of the other token. The price a
+/// trade gets is therefore worse than the pool's current price, by more the
+/// larger the trade is relative to the reserves; that gap is the price
+/// impact. Nothing is signed or sent.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Raydium AMM v4 pool account address (base58)
+    pool: Pubkey,
+
+    /// Amount to sell, in whole tokens of the input mint (e.g. 10.5)
+    #[arg(short, long)]
+    amount: String,
+
+    /// Mint to sell, one of the pool's two; defaults to its base mint
+    #[arg(long)]
+    input_mint: Option<Pubkey>,
+
+    /// Jupiter swap API base URL
+    #[arg(
+        long,
+        env = "JUPITER_API_URL",
+        default_value = DEFAULT_SWAP_API_URL
+    )]
+    jupiter_url: String,
+
+    /// Only let Jupiter quote direct Raydium routes, which makes it likely
+    /// to quote this very pool
+    #[arg(long)]
+    raydium_only: bool,
+
+    /// Skip the Jupiter quote, e.g. for a pool on devnet
+    #[arg(long)]
+    no_jupiter: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// The outcome of a swap on the constant-product curve, in base units.
+struct Swap {
+    fee: u64,
+    amount_out: u64,
+}
+
+#[derive(Serialize)]
+struct Impact {
+    pool: String,
+    input_mint: String,
+    output_mint: String,
+    /// Reserves in whole tokens
+    reserve_in: String,
+    reserve_out: String,
+    amount_in: String,
+    /// The part of the input the pool keeps, in whole input tokens
+    fee: String,
+    fee_pct: f64,
+    amount_out: String,
+    /// Output tokens per input token before the trade
+    spot_price: f64,
+    /// Output tokens per input token the trade gets
+    execution_price: f64,
+    /// How much worse the execution price is than the spot price, in
+    /// percent: the curve's impact and the fee together
+    price_impact_pct: f64,
+    /// The curve's part alone
+    curve_impact_pct: f64,
+    /// Spot price once the trade has gone through
+    price_after: f64,
+    jupiter: Option<JupiterComparison>,
+}
+
+#[derive(Serialize)]
+struct JupiterComparison {
+    amount_out: String,
+    /// Jupiter's own figure, in percent
+    price_impact_pct: f64,
+    /// How much more (or, when negative, less) Jupiter returns than the
+    /// pool alone, in percent
+    difference_pct: f64,
+    /// Whether the route is a single hop through this pool
+    same_pool: bool,
+    route: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let account = connection
+        .get_account_with_commitment(&args.pool, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(args.pool))?;
+    let amm = AmmInfo::unpack(&args.pool, &account)?;
+    if amm.swap_fee_denominator == 0 || amm.swap_fee_numerator >= amm.swap_fee_denominator {
+        return Err(ExampleError::Parse(format!(
+            "Raydium pool {}: swap fee {}/{}",
+            args.pool, amm.swap_fee_numerator, amm.swap_fee_denominator
+        )));
+    }
+
+    // Index 0 is the side being sold.
+    let mut mints = [amm.coin_mint, amm.pc_mint];
+    let mut vaults = [amm.coin_vault, amm.pc_vault];
+    let mut owed = [amm.need_take_pnl_coin, amm.need_take_pnl_pc];
+    match args.input_mint {
+        None => {}
+        Some(mint) if mint == amm.coin_mint => {}
+        Some(mint) if mint == amm.pc_mint => {
+            mints.reverse();
+            vaults.reverse();
+            owed.reverse();
+        }
+        Some(mint) => {
+            return Err(ExampleError::InvalidArgument(format!(
+                "{mint} is not one of the pool's mints ({} and {})",
+                amm.coin_mint, amm.pc_mint
+            )));
+        }
+    }
+
+    let accounts =
+        batch::get_multiple_accounts(&connection, &[mints[0], mints[1], vaults[0], vaults[1]])?;
+    let found = |index: usize, key: &Pubkey| -> Result<&Account> {
+        accounts[index]
+            .as_ref()
+            .ok_or(ExampleError::AccountNotFound(*key))
+    };
+    let mint_infos = [
+        MintInfo::unpack(&mints[0], found(0, &mints[0])?)?,
+        MintInfo::unpack(&mints[1], found(1, &mints[1])?)?,
+    ];
+    let decimals = [mint_infos[0].decimals(), mint_infos[1].decimals()];
+    let reserves = [
+        TokenAccountInfo::unpack(&vaults[0], found(2, &vaults[0])?)?
+            .account
+            .amount
+            .saturating_sub(owed[0]),
+        TokenAccountInfo::unpack(&vaults[1], found(3, &vaults[1])?)?
+            .account
+            .amount
+            .saturating_sub(owed[1]),
+    ];
+    if reserves.contains(&0) {
+        return Err(ExampleError::InvalidArgument(format!(
+            "Raydium pool {} has no liquidity",
+            args.pool
+        )));
+    }
+
+    let amount_in = parse_token_amount(&args.amount, decimals[0])?;
+    let swap = swap_base_in(
+        amount_in,
+        reserves,
+        amm.swap_fee_numerator,
+        amm.swap_fee_denominator,
+    );
+    let whole = |amount: u64, side: usize| amount as f64 / 10f64.powi(i32::from(decimals[side]));
+    let spot_price = whole(reserves[1], 1) / whole(reserves[0], 0);
+    let execution_price = whole(swap.amount_out, 1) / whole(amount_in, 0);
+    let in_after_fee = amount_in - swap.fee;
+    let impact = Impact {
+        pool: args.pool.to_string(),
+        input_mint: mints[0].to_string(),
+        output_mint: mints[1].to_string(),
+        reserve_in: mint_infos[0].ui_amount(reserves[0]),
+        reserve_out: mint_infos[1].ui_amount(reserves[1]),
+        amount_in: format_token_amount(amount_in, decimals[0]),
+        fee: format_token_amount(swap.fee, decimals[0]),
+        fee_pct: amm.swap_fee_numerator as f64 / amm.swap_fee_denominator as f64 * 100.0,
+        amount_out: format_token_amount(swap.amount_out, decimals[1]),
+        spot_price,
+        execution_price,
+        price_impact_pct: (1.0 - execution_price / spot_price) * 100.0,
+        // What the input left after the fee gets, against the spot price:
+        // in / (reserve_in + in) of it is lost to the curve.
+        curve_impact_pct: in_after_fee as f64 / (reserves[0] as f64 + in_after_fee as f64) * 100.0,
+        price_after: whole(reserves[1] - swap.amount_out, 1) / whole(reserves[0] + amount_in, 0),
+        jupiter: None,
+    };
+
+    let impact = if args.no_jupiter {
+        impact
+    } else {
+        let quote = jupiter_quote(&args, &mints, amount_in)?;
+        let amount_out = jupiter::parse_amount(&quote.out_amount)?;
+        let pool = args.pool.to_string();
+        Impact {
+            jupiter: Some(JupiterComparison {
+                amount_out: format_token_amount(amount_out, decimals[1]),
+                price_impact_pct: quote.price_impact_percent()?,
+                difference_pct: if swap.amount_out == 0 {
+                    0.0
+                } else {
+                    (amount_out as f64 / swap.amount_out as f64 - 1.0) * 100.0
+                },
+                same_pool: matches!(&quote.route_plan[..], [step] if step.swap_info.amm_key == pool),
+                route: quote
+                    .route_plan
+                    .iter()
+                    .map(|step| {
+                        format!(
+                            "{} {}",
+                            step.swap_info.label.as_deref().unwrap_or("?"),
+                            step.swap_info.amm_key
+                        )
+                    })
+                    .collect(),
+            }),
+            ..impact
+        }
+    };
+
+    args.output.print(&impact, || {
+        println!("Pool:              {}", impact.pool);
+        println!(
+            "Reserves:          {} in, {} out",
+            impact.reserve_in, impact.reserve_out
+        );
+        println!(
+            "Sell:              {} of {}",
+            impact.amount_in, impact.input_mint
+        );
+        println!("Fee:               {} ({}%)", impact.fee, impact.fee_pct);
+        println!(
+            "Receive:           {} of {}",
+            impact.amount_out, impact.output_mint
+        );
+        println!("Spot price:        {}", impact.spot_price);
+        println!("Execution price:   {}", impact.execution_price);
+        println!("Price after:       {}", impact.price_after);
+        println!(
+            "Price impact:      {:.4}% ({:.4}% from the curve, the rest is the fee)",
+            impact.price_impact_pct, impact.curve_impact_pct
+        );
+        if let Some(jupiter) = &impact.jupiter {
+            println!();
+            println!("Jupiter quote:     {}", jupiter.amount_out);
+            println!("  price impact:    {:.4}%", jupiter.price_impact_pct);
+            println!(
+                "  vs this pool:    {:+.4}%{}",
+                jupiter.difference_pct,
+                if jupiter.same_pool {
+                    " (same pool)"
+                } else {
+                    ""
+                }
+            );
+            println!("  route:");
+            for step in &jupiter.route {
+                println!("    {step}");
+            }
+        }
+    })
+}
+
+/// Raydium's `swap_base_in`: the fee, rounded up, comes off the input, and
+/// the output is rounded down, so the product of the reserves never
+/// decreases.
+fn swap_base_in(
+    amount_in: u64,
+    reserves: [u64; 2],
+    fee_numerator: u64,
+    fee_denominator: u64,
+) -> Swap {
+    let fee = (u128::from(amount_in) * u128::from(fee_numerator))
+        .div_ceil(u128::from(fee_denominator)) as u64;
+    let in_after_fee = u128::from(amount_in - fee);
+    let amount_out =
+        u128::from(reserves[1]) * in_after_fee / (u128::from(reserves[0]) + in_after_fee);
+    Swap {
+        fee,
+        amount_out: amount_out as u64,
+    }
+}
+
+fn jupiter_quote(args: &Args, mints: &[Pubkey; 2], amount_in: u64) -> Result<Quote> {
+    let mut query = vec![
+        ("inputMint", mints[0].to_string()),
+        ("outputMint", mints[1].to_string()),
+        ("amount", amount_in.to_string()),
+        ("swapMode", "ExactIn".to_string()),
+    ];
+    if args.raydium_only {
+        query.push(("dexes", "Raydium".to_string()));
+        query.push(("onlyDirectRoutes", "true".to_string()));
+    }
+    let raw = jupiter::fetch_quote(&reqwest::blocking::Client::new(), &args.jupiter_url, &query)?;
+    Quote::decode(&raw)
+}
@@ -0,0 +1,336 @@
+use borsh::BorshDeserialize;
+use clap::Parser;
+use common::anchor::{self, DISCRIMINATOR_LEN};
+use common::cli::{RpcArgs, WsArgs};
+use common::output::{OutputArgs, RecordWriter};
+use common::time::format_timestamp;
+use common::units::format_token_amount;
+use common::{ExampleError, Result};
+use futures::StreamExt;
+use serde::Serialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::account::Account;
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+/// Owner of the price update accounts posted by pull-oracle integrators.
+const RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Owner of the price feed accounts Pyth keeps updated itself.
+const PUSH_ORACLE_PROGRAM_ID: Pubkey = pubkey!("pythWSnswVUd12oZpeFP8e9CVaEqJg25g1Vtc2biRsT");
+
+/// Exit code of a one-off read whose price failed validation; errors exit
+/// with 1 as usual.
+const INVALID_EXIT_CODE: u8 = 2;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Read a Pyth price and check it is fit to use: recent enough, with a
+/// confidence interval narrow enough, and fully verified.
+///
+/// Both pull-oracle price update accounts and push-oracle price feed
+/// accounts hold a `PriceUpdateV2`; a push feed can also be found from its
+/// feed id with `--feed-id`. A single read exits with code 2 when the price
+/// fails a check; `--watch` subscribes to the account and checks every
+/// update instead.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    /// Price update or price feed account (base58)
+    #[arg(required_unless_present = "feed_id", conflicts_with = "feed_id")]
+    account: Option<Pubkey>,
+
+    /// Feed id (hex) of a push-oracle feed, e.g. from pyth.network's list of
+    /// price feed ids
+    #[arg(long, value_parser = parse_feed_id)]
+    feed_id: Option<[u8; 32]>,
+
+    /// Push-oracle shard the feed lives in
+    #[arg(long, default_value_t = 0, requires = "feed_id")]
+    shard: u16,
+
+    /// Seconds since publication after which a price counts as stale
+    #[arg(long, default_value_t = 60)]
+    max_age: u64,
+
+    /// Widest confidence interval accepted, in percent of the price
+    #[arg(long, default_value_t = 1.0)]
+    max_confidence: f64,
+
+    /// Subscribe to the account and check every update
+    #[arg(short, long)]
+    watch: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(BorshDeserialize)]
+enum VerificationLevel {
+    /// Checked against fewer Wormhole guardian signatures than a quorum
+    Partial {
+        num_signatures: u8,
+    },
+    Full,
+}
+
+#[derive(BorshDeserialize)]
+struct PriceFeedMessage {
+    feed_id: [u8; 32],
+    price: i64,
+    conf: u64,
+    exponent: i32,
+    publish_time: i64,
+    _prev_publish_time: i64,
+    ema_price: i64,
+    _ema_conf: u64,
+}
+
+/// The account after its Anchor discriminator.
+#[derive(BorshDeserialize)]
+struct PriceUpdateV2 {
+    _write_authority: Pubkey,
+    verification_level: VerificationLevel,
+    price_message: PriceFeedMessage,
+    posted_slot: Slot,
+}
+
+#[derive(Serialize)]
+struct PriceReading {
+    account: String,
+    feed_id: String,
+    price: String,
+    /// Half-width of the confidence interval around the price
+    confidence: String,
+    ema_price: String,
+    publish_time: String,
+    /// Seconds since publication, by the local clock
+    age: i64,
+    posted_slot: Slot,
+    verification: String,
+    /// Confidence interval in percent of the price
+    confidence_percent: f64,
+    /// Checks the price failed; empty when it is fit to use
+    problems: Vec<String>,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(common::config::parse()).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(INVALID_EXIT_CODE),
+        Err(err) => common::report(Err(err)),
+    }
+}
+
+/// Returns whether the last price read passed every check.
+async fn run(args: Args) -> Result<bool> {
+    let account = match (args.account, args.feed_id) {
+        (Some(account), _) => account,
+        (None, Some(feed_id)) => feed_address(args.shard, &feed_id),
+        (None, None) => unreachable!("clap requires one of them"),
+    };
+    let mut records = args.output.records();
+    let connection = args.rpc.nonblocking_client();
+    let data = connection
+        .get_account_with_commitment(&account, connection.commitment())
+        .await?
+        .value
+        .ok_or(ExampleError::AccountNotFound(account))?;
+    let reading = read(&args, &account, &data)?;
+    report(&reading, &mut records)?;
+    if !args.watch {
+        return Ok(reading.problems.is_empty());
+    }
+
+    let ws_url = args.ws.url(&args.rpc);
+    loop {
+        match watch(&ws_url, &args, &account, &mut records).await {
+            Ok(Stop::Shutdown) => return Ok(true),
+            Ok(Stop::Disconnected) => warn!("websocket closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        warn!("resubscribing in {RECONNECT_DELAY:?}");
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(true),
+        }
+    }
+}
+
+/// Runs one subscription until the socket drops or Ctrl-C is pressed.
+async fn watch(
+    ws_url: &str,
+    args: &Args,
+    account: &Pubkey,
+    records: &mut RecordWriter,
+) -> Result<Stop> {
+    let client = PubsubClient::new(ws_url).await?;
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(args.rpc.commitment_config()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let (mut notifications, unsubscribe) = client.account_subscribe(account, Some(config)).await?;
+    args.output
+        .status(format_args!("Watching {account} (Ctrl-C to stop)"));
+
+    loop {
+        tokio::select! {
+            notification = notifications.next() => {
+                let Some(response) = notification else {
+                    return Ok(Stop::Disconnected);
+                };
+                let data: Account = response
+                    .value
+                    .decode()
+                    .ok_or_else(|| ExampleError::Parse(format!("account data for {account}")))?;
+                report(&read(args, account, &data)?, records)?;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                unsubscribe().await;
+                drop(notifications);
+                client.shutdown().await?;
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}
+
+/// Push-oracle feeds live at `[shard, feed_id]` under the push oracle.
+fn feed_address(shard: u16, feed_id: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(
+        &[&shard.to_le_bytes(), feed_id.as_ref()],
+        &PUSH_ORACLE_PROGRAM_ID,
+    )
+    .0
+}
+
+fn parse_feed_id(value: &str) -> std::result::Result<[u8; 32], String> {
+    let bytes = hex::decode(value.trim_start_matches("0x")).map_err(|err| err.to_string())?;
+    bytes
+        .try_into()
+        .map_err(|_| "a feed id is 32 bytes of hex".to_string())
+}
+
+/// Decodes the account and runs the checks against it.
+fn read(args: &Args, address: &Pubkey, account: &Account) -> Result<PriceReading> {
+    if account.owner != RECEIVER_PROGRAM_ID && account.owner != PUSH_ORACLE_PROGRAM_ID {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{address} is owned by {}, not the Pyth receiver or push oracle",
+            account.owner
+        )));
+    }
+    let invalid = || ExampleError::Parse(format!("{address} is not a Pyth price update"));
+    let (discriminator, mut data) = account
+        .data
+        .split_at_checked(DISCRIMINATOR_LEN)
+        .ok_or_else(invalid)?;
+    if discriminator != anchor::account_discriminator("PriceUpdateV2") {
+        return Err(invalid());
+    }
+    let update = PriceUpdateV2::deserialize(&mut data)
+        .map_err(|err| ExampleError::Parse(format!("price update {address}: {err}")))?;
+    let message = &update.price_message;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    let age = now - message.publish_time;
+    let confidence_percent = if message.price == 0 {
+        f64::INFINITY
+    } else {
+        message.conf as f64 / message.price.unsigned_abs() as f64 * 100.0
+    };
+    let mut problems = Vec::new();
+    if age > args.max_age as i64 {
+        problems.push(format!("stale: published {age}s ago"));
+    }
+    if confidence_percent > args.max_confidence {
+        problems.push(format!(
+            "confidence interval {confidence_percent:.4}% is wider than {}%",
+            args.max_confidence
+        ));
+    }
+    let verification = match update.verification_level {
+        VerificationLevel::Full => "full".to_string(),
+        VerificationLevel::Partial { num_signatures } => {
+            problems.push(format!(
+                "only verified by {num_signatures} guardian signatures"
+            ));
+            format!("partial, {num_signatures} signatures")
+        }
+    };
+    Ok(PriceReading {
+        account: address.to_string(),
+        feed_id: hex::encode(message.feed_id),
+        price: format_scaled(message.price, message.exponent),
+        confidence: format_scaled(message.conf as i64, message.exponent),
+        ema_price: format_scaled(message.ema_price, message.exponent),
+        publish_time: format_timestamp(message.publish_time),
+        age,
+        posted_slot: update.posted_slot,
+        verification,
+        confidence_percent,
+        problems,
+    })
+}
+
+/// Pyth stores prices as an integer and a power-of-ten exponent, usually
+/// negative.
+fn format_scaled(value: i64, exponent: i32) -> String {
+    let sign = if value < 0 { "-" } else { "" };
+    let magnitude = value.unsigned_abs();
+    match u8::try_from(-exponent) {
+        Ok(decimals) => format!("{sign}{}", format_token_amount(magnitude, decimals)),
+        Err(_) if exponent >= 0 => format!("{sign}{magnitude}{}", "0".repeat(exponent as usize)),
+        Err(_) => format!("{sign}{magnitude}e{exponent}"),
+    }
+}
+
+fn report(reading: &PriceReading, records: &mut RecordWriter) -> Result<()> {
+    if !reading.problems.is_empty() {
+        warn!(
+            "price of feed {} failed validation: {}",
+            reading.feed_id,
+            reading.problems.join("; ")
+        );
+    }
+    records.write(reading, || {
+        println!(
+            "{}  {} ± {} ({:.4}%)  published {} ({}s ago), slot {}  {}",
+            reading.feed_id,
+            reading.price,
+            reading.confidence,
+            reading.confidence_percent,
+            reading.publish_time,
+            reading.age,
+            reading.posted_slot,
+            if reading.problems.is_empty() {
+                "ok"
+            } else {
+                "INVALID"
+            }
+        );
+    })
+}
@@ -0,0 +1,101 @@
+//! The `--config` file: which balances to watch, the thresholds they alert
+//! at, and where alerts are sent.
+//!
+//! ```toml
+//! [[notifiers]]
+//! kind = "stdout"
+//!
+//! [[notifiers]]
+//! kind = "webhook"
+//! url = "https://example.com/hooks/solana"
+//! headers = { Authorization = "Bearer TOKEN" }
+//!
+//! [[notifiers]]
+//! kind = "telegram"
+//! chat_id = "-1001234567890"
+//! # bot_token = "123456:ABC..."  (or set TELEGRAM_BOT_TOKEN)
+//!
+//! [[watch]]
+//! address = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"
+//! label = "hot wallet"
+//! sol_below = 5
+//!
+//! [[watch.tokens]]
+//! mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+//! below = 1000
+//! above = 250000
+//! ```
+//!
+//! Thresholds are in whole SOL or tokens. Without any `[[notifiers]]`,
+//! alerts are printed to stdout.
+
+use common::{ExampleError, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AlertsConfig {
+    #[serde(default)]
+    pub notifiers: Vec<NotifierConfig>,
+    #[serde(default)]
+    pub watch: Vec<Watch>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase", deny_unknown_fields)]
+pub enum NotifierConfig {
+    /// One line per alert, or one record with `--output json` or `csv`
+    Stdout,
+    /// POSTs each alert as JSON
+    Webhook {
+        url: String,
+        /// Extra request headers, e.g. for authentication
+        #[serde(default)]
+        headers: BTreeMap<String, String>,
+    },
+    /// Sends each alert as a message from a Telegram bot
+    Telegram {
+        chat_id: String,
+        /// Falls back to `$TELEGRAM_BOT_TOKEN`, to keep it out of the file
+        bot_token: Option<String>,
+        /// Bot API base URL, for a self-hosted Bot API server
+        api_url: Option<String>,
+    },
+}
+
+/// An address and the balances of it to watch.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Watch {
+    pub address: String,
+    /// Name used in alerts instead of the bare address
+    pub label: Option<String>,
+    pub sol_below: Option<f64>,
+    pub sol_above: Option<f64>,
+    /// Balances of the address's token accounts, totalled per mint
+    #[serde(default)]
+    pub tokens: Vec<TokenWatch>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenWatch {
+    pub mint: String,
+    pub below: Option<f64>,
+    pub above: Option<f64>,
+}
+
+impl AlertsConfig {
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let error = |err: String| ExampleError::Config(format!("{}: {err}", path.display()));
+        let text = fs::read_to_string(path).map_err(|err| error(err.to_string()))?;
+        let config: Self = toml::from_str(&text).map_err(|err| error(err.to_string()))?;
+        if config.watch.is_empty() {
+            return Err(error("no [[watch]] entries".to_string()));
+        }
+        Ok(config)
+    }
+}
@@ -0,0 +1,343 @@
+mod config;
+mod notify;
+
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::token::{self, TokenAccountInfo};
+use common::units::{format_token_amount, parse_token_amount};
+use common::{ExampleError, Result, batch};
+use config::AlertsConfig;
+use serde::Serialize;
+use serde_json::json;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcTokenAccountsFilter};
+use solana_client::rpc_request::RpcRequest;
+use solana_client::rpc_response::{Response, RpcKeyedAccount};
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::warn;
+
+/// Exit code of `--once` when a balance is outside its thresholds.
+const FAILED_EXIT_CODE: u8 = 2;
+
+const SOL_DECIMALS: u8 = 9;
+
+/// Watch the SOL and token balances of a set of addresses and send an alert
+/// whenever one crosses a threshold set for it, and again when it is back
+/// within range.
+///
+/// Addresses, thresholds and notifiers (stdout, a webhook POST, a Telegram
+/// bot) are read from the `--config` TOML file. Balances are polled every
+/// `--interval` seconds; a token balance is the total over all of the
+/// address's accounts for the mint. A balance already outside its range
+/// when watching starts is alerted on the first poll. A notifier that fails
+/// to send is reported and does not stop the others.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// TOML file listing the balances to watch and the notifiers to use
+    #[arg(long, default_value = "alerts.toml")]
+    config: PathBuf,
+
+    /// Seconds between polls
+    #[arg(short, long, default_value_t = 30)]
+    interval: u64,
+
+    /// Poll once, alert on balances outside their range and exit, with
+    /// status 2 if there were any
+    #[arg(long)]
+    once: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Where a balance is relative to its thresholds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Level {
+    Below,
+    Within,
+    Above,
+}
+
+enum Asset {
+    Sol,
+    Token { mint: Pubkey, decimals: u8 },
+}
+
+impl Asset {
+    fn decimals(&self) -> u8 {
+        match self {
+            Self::Sol => SOL_DECIMALS,
+            Self::Token { decimals, .. } => *decimals,
+        }
+    }
+}
+
+impl fmt::Display for Asset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sol => f.pad("SOL"),
+            Self::Token { mint, .. } => f.pad(&mint.to_string()),
+        }
+    }
+}
+
+/// One watched balance and its thresholds, in base units.
+struct Rule {
+    address: Pubkey,
+    label: Option<String>,
+    asset: Asset,
+    below: Option<u64>,
+    above: Option<u64>,
+    /// The level and balance at the last poll
+    last: Option<(Level, u64)>,
+}
+
+/// What notifiers are sent. Webhooks receive it as JSON; the other
+/// backends send `message`.
+#[derive(Serialize)]
+pub struct Alert {
+    /// Unix time of the poll that saw the crossing
+    timestamp: i64,
+    address: String,
+    label: Option<String>,
+    /// `SOL`, or the token's mint
+    asset: String,
+    level: Level,
+    balance: String,
+    /// Balance at the previous poll; absent on the first one
+    previous: Option<String>,
+    below: Option<String>,
+    above: Option<String>,
+    message: String,
+}
+
+fn main() -> ExitCode {
+    match run(common::config::parse()) {
+        Ok(false) => ExitCode::from(FAILED_EXIT_CODE),
+        result => common::report(result.map(|_| ())),
+    }
+}
+
+fn run(args: Args) -> Result<bool> {
+    let config = AlertsConfig::from_file(&args.config)?;
+    let connection = args.rpc.client();
+    let mut rules = load_rules(&connection, &config)?;
+    let mut notifiers = notify::from_config(&config.notifiers, &args.output, args.rpc.timeout())?;
+    args.output.status(format_args!(
+        "Watching {} balances of {} addresses every {} s",
+        rules.len(),
+        config.watch.len(),
+        args.interval
+    ));
+
+    let interval = Duration::from_secs(args.interval.max(1));
+    loop {
+        let started = Instant::now();
+        match poll(&connection, &mut rules) {
+            Ok(alerts) => {
+                for alert in &alerts {
+                    for notifier in &mut notifiers {
+                        if let Err(err) = notifier.notify(alert) {
+                            warn!("{} failed: {err}", notifier.name());
+                        }
+                    }
+                }
+            }
+            Err(err) if !args.once => warn!("poll failed: {err}"),
+            Err(err) => return Err(err),
+        }
+        if args.once {
+            return Ok(rules
+                .iter()
+                .all(|rule| matches!(rule.last, Some((Level::Within, _)))));
+        }
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+fn load_rules(connection: &RpcClient, config: &AlertsConfig) -> Result<Vec<Rule>> {
+    let mut rules = Vec::new();
+    for watch in &config.watch {
+        let address: Pubkey = watch
+            .address
+            .parse()
+            .map_err(|_| ExampleError::Config(format!("{} is not an address", watch.address)))?;
+        if watch.sol_below.is_some() || watch.sol_above.is_some() {
+            rules.push(rule(
+                address,
+                &watch.label,
+                Asset::Sol,
+                (watch.sol_below, watch.sol_above),
+            )?);
+        }
+        for token in &watch.tokens {
+            let mint: Pubkey = token
+                .mint
+                .parse()
+                .map_err(|_| ExampleError::Config(format!("{} is not a mint", token.mint)))?;
+            let decimals = token::fetch_mint(connection, &mint)?.decimals();
+            rules.push(rule(
+                address,
+                &watch.label,
+                Asset::Token { mint, decimals },
+                (token.below, token.above),
+            )?);
+        }
+    }
+    if rules.is_empty() {
+        return Err(ExampleError::Config(
+            "no thresholds to watch: set sol_below, sol_above or [[watch.tokens]]".to_string(),
+        ));
+    }
+    Ok(rules)
+}
+
+fn rule(
+    address: Pubkey,
+    label: &Option<String>,
+    asset: Asset,
+    (below, above): (Option<f64>, Option<f64>),
+) -> Result<Rule> {
+    let name = label.clone().unwrap_or_else(|| address.to_string());
+    let threshold = |value: Option<f64>| {
+        value
+            .map(|value| parse_token_amount(&value.to_string(), asset.decimals()))
+            .transpose()
+            .map_err(|err| ExampleError::Config(format!("{name} {asset}: {err}")))
+    };
+    let (below, above) = (threshold(below)?, threshold(above)?);
+    if matches!((below, above), (Some(below), Some(above)) if below > above) {
+        return Err(ExampleError::Config(format!(
+            "{name} {asset}: the `below` threshold is over the `above` one"
+        )));
+    }
+    if below.is_none() && above.is_none() {
+        return Err(ExampleError::Config(format!(
+            "{name} {asset}: set `below`, `above` or both"
+        )));
+    }
+    Ok(Rule {
+        address,
+        label: label.clone(),
+        asset,
+        below,
+        above,
+        last: None,
+    })
+}
+
+/// Fetches every watched balance and returns an alert for each that changed
+/// level since the last poll.
+fn poll(connection: &RpcClient, rules: &mut [Rule]) -> Result<Vec<Alert>> {
+    let sol_addresses: Vec<Pubkey> = rules
+        .iter()
+        .filter(|rule| matches!(rule.asset, Asset::Sol))
+        .map(|rule| rule.address)
+        .collect();
+    let mut sol_balances = batch::get_multiple_accounts(connection, &sol_addresses)?
+        .into_iter()
+        .map(|account| account.map_or(0, |account| account.lamports));
+    let mut balances = Vec::with_capacity(rules.len());
+    for rule in rules.iter() {
+        balances.push(match rule.asset {
+            Asset::Sol => sol_balances.next().unwrap_or(0),
+            Asset::Token { mint, .. } => token_balance(connection, &rule.address, &mint)?,
+        });
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    let mut alerts = Vec::new();
+    for (rule, balance) in rules.iter_mut().zip(balances) {
+        let level = match (rule.below, rule.above) {
+            (Some(below), _) if balance < below => Level::Below,
+            (_, Some(above)) if balance > above => Level::Above,
+            _ => Level::Within,
+        };
+        let previous = rule.last.replace((level, balance));
+        let changed = match previous {
+            Some((previous, _)) => previous != level,
+            None => level != Level::Within,
+        };
+        if changed {
+            alerts.push(alert(rule, timestamp, previous.map(|(_, balance)| balance)));
+        }
+    }
+    Ok(alerts)
+}
+
+fn alert(rule: &Rule, timestamp: i64, previous: Option<u64>) -> Alert {
+    let (level, balance) = rule.last.expect("set by the poll");
+    let decimals = rule.asset.decimals();
+    let amount = |value: u64| format_token_amount(value, decimals);
+    let name = rule
+        .label
+        .clone()
+        .unwrap_or_else(|| rule.address.to_string());
+    let message = match (level, previous) {
+        (Level::Below, Some(_)) => format!("fell below {}", amount(rule.below.unwrap_or_default())),
+        (Level::Below, None) => format!("is below {}", amount(rule.below.unwrap_or_default())),
+        (Level::Above, Some(_)) => format!("rose above {}", amount(rule.above.unwrap_or_default())),
+        (Level::Above, None) => format!("is above {}", amount(rule.above.unwrap_or_default())),
+        (Level::Within, _) => "is back within range".to_string(),
+    };
+    let message = format!(
+        "{name}: {} balance {} {message}",
+        rule.asset,
+        amount(balance)
+    );
+    Alert {
+        timestamp,
+        address: rule.address.to_string(),
+        label: rule.label.clone(),
+        asset: rule.asset.to_string(),
+        level,
+        balance: amount(balance),
+        previous: previous.map(amount),
+        below: rule.below.map(amount),
+        above: rule.above.map(amount),
+        message,
+    }
+}
+
+/// The raw amount `owner` holds of `mint`, over all its token accounts.
+fn token_balance(connection: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> Result<u64> {
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(connection.commitment()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let response: Response<Vec<RpcKeyedAccount>> = connection.send(
+        RpcRequest::GetTokenAccountsByOwner,
+        json!([
+            owner.to_string(),
+            RpcTokenAccountsFilter::Mint(mint.to_string()),
+            config
+        ]),
+    )?;
+    let mut total: u64 = 0;
+    for keyed in response.value {
+        let address: Pubkey = keyed.pubkey.parse()?;
+        let account: Account = keyed
+            .account
+            .decode()
+            .ok_or_else(|| ExampleError::Parse(format!("account data for {address}")))?;
+        let info = TokenAccountInfo::unpack(&address, &account)?;
+        total = total.saturating_add(info.account.amount);
+    }
+    Ok(total)
+}
@@ -0,0 +1,134 @@
+//! Where alerts go. Each backend implements [`Notifier`]; add one there and
+//! to [`NotifierConfig`] to send alerts somewhere else.
+
+use crate::Alert;
+use crate::config::NotifierConfig;
+use common::output::{OutputArgs, RecordWriter};
+use common::time::format_timestamp;
+use common::{ExampleError, Result};
+use reqwest::blocking::Client;
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::env;
+use std::time::Duration;
+
+/// Read when a Telegram notifier has no `bot_token` in the config file.
+const TELEGRAM_TOKEN_ENV: &str = "TELEGRAM_BOT_TOKEN";
+
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+pub trait Notifier {
+    /// Names the backend in warnings about alerts it failed to send.
+    fn name(&self) -> String;
+
+    fn notify(&mut self, alert: &Alert) -> Result<()>;
+}
+
+/// The notifiers a config file lists, or stdout alone if it lists none.
+pub fn from_config(
+    configs: &[NotifierConfig],
+    output: &OutputArgs,
+    timeout: Duration,
+) -> Result<Vec<Box<dyn Notifier>>> {
+    if configs.is_empty() {
+        return Ok(vec![Box::new(Stdout(output.records()))]);
+    }
+    let http = Client::builder().timeout(timeout).build()?;
+    configs
+        .iter()
+        .map(|config| -> Result<Box<dyn Notifier>> {
+            Ok(match config {
+                NotifierConfig::Stdout => Box::new(Stdout(output.records())),
+                NotifierConfig::Webhook { url, headers } => Box::new(Webhook {
+                    http: http.clone(),
+                    url: url.clone(),
+                    headers: headers.clone(),
+                }),
+                NotifierConfig::Telegram {
+                    chat_id,
+                    bot_token,
+                    api_url,
+                } => {
+                    let bot_token = match bot_token {
+                        Some(token) => token.clone(),
+                        None => env::var(TELEGRAM_TOKEN_ENV).map_err(|_| {
+                            ExampleError::Config(format!(
+                                "the telegram notifier needs a bot_token or ${TELEGRAM_TOKEN_ENV}"
+                            ))
+                        })?,
+                    };
+                    Box::new(Telegram {
+                        http: http.clone(),
+                        api_url: api_url.as_deref().unwrap_or(TELEGRAM_API_URL).to_string(),
+                        bot_token,
+                        chat_id: chat_id.clone(),
+                    })
+                }
+            })
+        })
+        .collect()
+}
+
+struct Stdout(RecordWriter);
+
+impl Notifier for Stdout {
+    fn name(&self) -> String {
+        "stdout".to_string()
+    }
+
+    fn notify(&mut self, alert: &Alert) -> Result<()> {
+        self.0.write(alert, || {
+            println!("{}  {}", format_timestamp(alert.timestamp), alert.message);
+        })
+    }
+}
+
+struct Webhook {
+    http: Client,
+    url: String,
+    headers: BTreeMap<String, String>,
+}
+
+impl Notifier for Webhook {
+    fn name(&self) -> String {
+        format!("webhook {}", self.url)
+    }
+
+    fn notify(&mut self, alert: &Alert) -> Result<()> {
+        let mut request = self.http.post(&self.url).json(alert);
+        for (name, value) in &self.headers {
+            request = request.header(name, value);
+        }
+        request.send()?.error_for_status()?;
+        Ok(())
+    }
+}
+
+struct Telegram {
+    http: Client,
+    api_url: String,
+    bot_token: String,
+    chat_id: String,
+}
+
+impl Notifier for Telegram {
+    fn name(&self) -> String {
+        format!("telegram chat {}", self.chat_id)
+    }
+
+    fn notify(&mut self, alert: &Alert) -> Result<()> {
+        // The token is part of the URL, so a failed request's error (which
+        // includes the URL) is reported without it.
+        self.http
+            .post(format!(
+                "{}/bot{}/sendMessage",
+                self.api_url.trim_end_matches('/'),
+                self.bot_token
+            ))
+            .json(&json!({ "chat_id": self.chat_id, "text": alert.message }))
+            .send()
+            .and_then(|response| response.error_for_status())
+            .map_err(|err| ExampleError::Http(err.without_url()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,306 @@
+use clap::{Parser, ValueEnum};
+use common::cli::{AddressArg, RpcArgs};
+use common::client::build_nonblocking_client;
+use common::output::OutputArgs;
+use common::{Result, logging};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// Fire a mix of RPC calls at one or more endpoints and compare their
+/// latency percentiles and error rates per method, e.g. to weigh a QuickNode
+/// plan against the public endpoint.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Endpoints to compare; only --rpc-url is benchmarked when none are
+    /// given
+    endpoints: Vec<String>,
+
+    /// Methods to call, comma separated
+    #[arg(
+        short,
+        long,
+        value_enum,
+        value_delimiter = ',',
+        default_value = "slot,account-info,block"
+    )]
+    methods: Vec<Method>,
+
+    /// Requests per method and endpoint
+    #[arg(short = 'n', long, default_value_t = 50)]
+    requests: usize,
+
+    /// Requests kept in flight at once
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// Account read by getAccountInfo: an address (base58), a `.sol` domain,
+    /// a keypair file or `wallet:mint`; wrapped SOL's mint by default
+    #[arg(long, default_value = "So11111111111111111111111111111111111111112")]
+    account: AddressArg,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Method {
+    Slot,
+    AccountInfo,
+    Block,
+}
+
+impl Method {
+    fn rpc_name(self) -> &'static str {
+        match self {
+            Method::Slot => "getSlot",
+            Method::AccountInfo => "getAccountInfo",
+            Method::Block => "getBlock",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct MethodStats {
+    endpoint: String,
+    method: &'static str,
+    requests: usize,
+    errors: usize,
+    /// Percent of requests that failed or timed out
+    error_rate: f64,
+    /// Latency percentiles of the successful requests, in milliseconds
+    p50_ms: Option<f64>,
+    p95_ms: Option<f64>,
+    p99_ms: Option<f64>,
+    max_ms: Option<f64>,
+    last_error: Option<String>,
+}
+
+/// How far behind the finalized tip the benchmarked blocks start, so every
+/// endpoint is sure to have them.
+const BLOCK_OFFSET: u64 = 100;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let endpoints = if args.endpoints.is_empty() {
+        vec![args.rpc.rpc_url.clone()]
+    } else {
+        args.endpoints.clone()
+    };
+    let concurrency = args.concurrency.max(1);
+    // Resolved once against --rpc-url, so every endpoint reads the same
+    // account; `AddressArg` resolves with the blocking client.
+    let account = args.account.resolve(&args.rpc.client())?;
+
+    let mut rows = Vec::new();
+    // Endpoints run one after another so they don't compete for the local
+    // network.
+    for endpoint in &endpoints {
+        let connection =
+            build_nonblocking_client(endpoint, args.rpc.commitment_config(), args.rpc.timeout());
+        let label = logging::endpoint_label(endpoint);
+        let slots = if args.methods.contains(&Method::Block) {
+            Some(recent_blocks(&connection, args.requests).await)
+        } else {
+            None
+        };
+        for &method in &args.methods {
+            args.output.status(format_args!(
+                "{label}: {} x{} ({concurrency} in flight)",
+                method.rpc_name(),
+                args.requests
+            ));
+            let results = match (method, &slots) {
+                // Without a block to ask for, every getBlock call counts as
+                // failed.
+                (Method::Block, Some(Err(err))) => vec![Err(err.clone()); args.requests],
+                _ => {
+                    stream::iter(0..args.requests)
+                        .map(|index| {
+                            let slot = match &slots {
+                                Some(Ok(slots)) => Some(slots[index % slots.len()]),
+                                _ => None,
+                            };
+                            call(&connection, method, &account, slot)
+                        })
+                        .buffer_unordered(concurrency)
+                        .collect()
+                        .await
+                }
+            };
+            rows.push(stats(label.clone(), method, results));
+        }
+    }
+
+    args.output.print(&rows, || print_stats(&rows))
+}
+
+/// Up to `count` recent confirmed block slots, cycled through by the getBlock
+/// requests so the endpoint can't answer all of them from one cache entry.
+async fn recent_blocks(
+    connection: &RpcClient,
+    count: usize,
+) -> std::result::Result<Vec<u64>, String> {
+    let tip = connection
+        .get_slot_with_commitment(CommitmentConfig::finalized())
+        .await
+        .map_err(|err| err.to_string())?;
+    let start = tip.saturating_sub(BLOCK_OFFSET);
+    let slots = connection
+        .get_blocks_with_limit(start, count.max(1))
+        .await
+        .map_err(|err| err.to_string())?;
+    if slots.is_empty() {
+        return Err(format!("no blocks found after slot {start}"));
+    }
+    Ok(slots)
+}
+
+/// Times one request; the error is kept as text since only the last one is
+/// reported.
+async fn call(
+    connection: &RpcClient,
+    method: Method,
+    account: &Pubkey,
+    slot: Option<u64>,
+) -> std::result::Result<Duration, String> {
+    let started = Instant::now();
+    let result = match method {
+        Method::Slot => connection.get_slot().await.map(drop),
+        Method::AccountInfo => connection
+            .get_account_with_commitment(account, connection.commitment())
+            .await
+            .map(drop),
+        Method::Block => {
+            let Some(slot) = slot else {
+                return Err("no block to request".to_string());
+            };
+            // Signatures only: the full transactions would mostly measure
+            // bandwidth rather than the endpoint.
+            connection
+                .get_block_with_config(
+                    slot,
+                    RpcBlockConfig {
+                        encoding: Some(UiTransactionEncoding::Base64),
+                        transaction_details: Some(TransactionDetails::Signatures),
+                        rewards: Some(false),
+                        commitment: Some(CommitmentConfig::confirmed()),
+                        max_supported_transaction_version: Some(0),
+                    },
+                )
+                .await
+                .map(drop)
+        }
+    };
+    result
+        .map(|()| started.elapsed())
+        .map_err(|err| err.to_string())
+}
+
+fn stats(
+    endpoint: String,
+    method: Method,
+    results: Vec<std::result::Result<Duration, String>>,
+) -> MethodStats {
+    let requests = results.len();
+    let mut latencies = Vec::with_capacity(requests);
+    let mut errors = 0;
+    let mut last_error = None;
+    for result in results {
+        match result {
+            Ok(latency) => latencies.push(latency.as_secs_f64() * 1000.0),
+            Err(err) => {
+                errors += 1;
+                last_error = Some(err);
+            }
+        }
+    }
+    latencies.sort_by(f64::total_cmp);
+    MethodStats {
+        endpoint,
+        method: method.rpc_name(),
+        requests,
+        errors,
+        error_rate: if requests == 0 {
+            0.0
+        } else {
+            errors as f64 * 100.0 / requests as f64
+        },
+        p50_ms: percentile(&latencies, 50.0),
+        p95_ms: percentile(&latencies, 95.0),
+        p99_ms: percentile(&latencies, 99.0),
+        max_ms: latencies.last().copied(),
+        last_error,
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], percent: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn print_stats(rows: &[MethodStats]) {
+    let mut methods: Vec<&str> = rows.iter().map(|row| row.method).collect();
+    methods.dedup();
+    let width = rows
+        .iter()
+        .map(|row| row.endpoint.len())
+        .max()
+        .unwrap_or(0)
+        .max("Endpoint".len());
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{value:.1}"));
+
+    for method in methods {
+        let method_rows: Vec<&MethodStats> =
+            rows.iter().filter(|row| row.method == method).collect();
+        // Marks the endpoint with the lowest median for this method.
+        let fastest = method_rows
+            .iter()
+            .filter_map(|row| row.p50_ms)
+            .min_by(f64::total_cmp);
+        let endpoints = method_rows.len();
+        println!();
+        println!("{method}");
+        println!(
+            "  {:<width$}  {:>9}  {:>9}  {:>9}  {:>9}  {:>7}",
+            "Endpoint", "p50 ms", "p95 ms", "p99 ms", "max ms", "errors"
+        );
+        for row in method_rows {
+            let marker = if endpoints > 1 && row.p50_ms.is_some() && row.p50_ms == fastest {
+                "  fastest"
+            } else {
+                ""
+            };
+            println!(
+                "  {:<width$}  {:>9}  {:>9}  {:>9}  {:>9}  {:>6.1}%{marker}",
+                row.endpoint,
+                ms(row.p50_ms),
+                ms(row.p95_ms),
+                ms(row.p99_ms),
+                ms(row.max_ms),
+                row.error_rate
+            );
+            if let Some(err) = &row.last_error {
+                println!("  {:<width$}  last error: {err}", "");
+            }
+        }
+    }
+}
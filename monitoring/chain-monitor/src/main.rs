@@ -0,0 +1,388 @@
+use clap::Parser;
+use common::cli::{FailoverArgs, MetricsArgs, RpcArgs};
+use common::failover::EndpointStatus;
+use common::output::OutputArgs;
+use common::{Result, RetryingRpcClient, fees, metrics};
+use serde::Serialize;
+use solana_client::rpc_response::RpcPerfSample;
+use solana_sdk::clock::Slot;
+use std::cmp::Reverse;
+use std::io::{self, Write};
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Poll the cluster and print a refreshing dashboard of slot progress,
+/// rolling TPS, recent priority fees and the epoch's skipped-slot rate.
+/// With --output json or csv, each refresh is printed as one record instead.
+/// Give --fallback-url to keep the dashboard going through an outage of the
+/// main endpoint: requests move to the next healthy endpoint, and back once
+/// the main one recovers.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Seconds between refreshes
+    #[arg(short, long, default_value_t = 5)]
+    interval: u64,
+
+    /// Performance samples (one per minute) averaged into the rolling TPS
+    #[arg(short, long, default_value_t = 10)]
+    window: usize,
+
+    /// Leaders with the most skipped slots to list
+    #[arg(short, long, default_value_t = 5)]
+    leaders: usize,
+
+    #[command(flatten)]
+    failover: FailoverArgs,
+
+    #[command(flatten)]
+    metrics: MetricsArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Clears the terminal and moves the cursor to the top-left corner.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Leaders with fewer slots than this in the epoch are left out of the
+/// worst-skippers list, where a single miss would dominate their rate.
+const MIN_LEADER_SLOTS: usize = 8;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let client = args.failover.client(&args.rpc)?;
+    args.metrics.serve(&args.output)?;
+    let interval = Duration::from_secs(args.interval.max(1));
+    let mut previous: Option<(Slot, Instant)> = None;
+    let mut records = args.output.records();
+    loop {
+        let started = Instant::now();
+        let snapshot = Snapshot::fetch(&client, args.window)?;
+        metrics::set_node_slot(snapshot.slot);
+        let slots_per_sec = previous.map(|(slot, at)| {
+            snapshot.slot.saturating_sub(slot) as f64 / at.elapsed().as_secs_f64()
+        });
+        previous = Some((snapshot.slot, started));
+
+        if args.output.is_table() {
+            let mut out = io::stdout().lock();
+            write!(out, "{CLEAR_SCREEN}")?;
+            snapshot.render(&mut out, &args, &client.endpoints(), slots_per_sec)?;
+            out.flush()?;
+        } else {
+            let summary = snapshot.summary(
+                slots_per_sec,
+                args.leaders,
+                client.endpoints(),
+                client.failovers(),
+            );
+            records.write(&summary, || {})?;
+        }
+
+        thread::sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+/// One refresh in machine-readable form; fees are in micro-lamports per CU.
+#[derive(Serialize)]
+struct Summary {
+    slot: Slot,
+    slots_per_sec: Option<f64>,
+    tps_last_min: Option<f64>,
+    non_vote_tps_last_min: Option<f64>,
+    tps_window: Option<f64>,
+    non_vote_tps_window: Option<f64>,
+    slot_time_ms: Option<f64>,
+    fee_slots: usize,
+    fee_mean: Option<f64>,
+    fee_median: Option<u64>,
+    fee_p75: Option<u64>,
+    fee_p90: Option<u64>,
+    fee_max: Option<u64>,
+    first_slot: Slot,
+    last_slot: Slot,
+    leader_slots: usize,
+    produced: usize,
+    skip_rate: f64,
+    worst_skippers: Vec<Skipper>,
+    /// The one requests go to first
+    endpoints: Vec<EndpointStatus>,
+    failovers: u32,
+}
+
+#[derive(Serialize)]
+struct Skipper {
+    identity: String,
+    leader_slots: usize,
+    skipped: usize,
+}
+
+/// Everything shown on one refresh of the dashboard.
+struct Snapshot {
+    slot: Slot,
+    samples: Vec<RpcPerfSample>,
+    /// Recent per-slot prioritization fees, sorted ascending.
+    priority_fees: Vec<u64>,
+    first_slot: Slot,
+    last_slot: Slot,
+    /// `(identity, leader slots, blocks produced)` for the current epoch.
+    production: Vec<(String, usize, usize)>,
+}
+
+impl Snapshot {
+    fn fetch(client: &RetryingRpcClient, window: usize) -> Result<Self> {
+        let slot = client.call(|client| Ok(client.get_slot()?))?;
+        let samples =
+            client.call(|client| Ok(client.get_recent_performance_samples(Some(window))?))?;
+        let priority_fees = client.call(|client| fees::recent_fees(client, &[]))?;
+        let production = client
+            .call(|client| Ok(client.get_block_production()?))?
+            .value;
+        Ok(Self {
+            slot,
+            samples,
+            priority_fees,
+            first_slot: production.range.first_slot,
+            last_slot: production.range.last_slot,
+            production: production
+                .by_identity
+                .into_iter()
+                .map(|(identity, (leader_slots, produced))| (identity, leader_slots, produced))
+                .collect(),
+        })
+    }
+
+    /// Average seconds per slot over the samples.
+    fn slot_time(&self) -> f64 {
+        self.samples
+            .iter()
+            .map(|sample| f64::from(sample.sample_period_secs))
+            .sum::<f64>()
+            / self
+                .samples
+                .iter()
+                .map(|sample| sample.num_slots as f64)
+                .sum::<f64>()
+    }
+
+    fn fee_mean(&self) -> f64 {
+        let fees = &self.priority_fees;
+        fees.iter().map(|&fee| fee as f64).sum::<f64>() / fees.len() as f64
+    }
+
+    /// `(leader slots, blocks produced)` summed over every leader.
+    fn production_totals(&self) -> (usize, usize) {
+        self.production
+            .iter()
+            .fold((0, 0), |(slots, produced), (_, leader_slots, blocks)| {
+                (slots + leader_slots, produced + blocks)
+            })
+    }
+
+    /// Leaders with the most skipped slots, worst first.
+    fn skippers(&self, count: usize) -> Vec<&(String, usize, usize)> {
+        let mut skippers: Vec<_> = self
+            .production
+            .iter()
+            .filter(|(_, slots, produced)| *slots >= MIN_LEADER_SLOTS && produced < slots)
+            .collect();
+        skippers.sort_by_key(|(_, slots, produced)| Reverse(slots - produced));
+        skippers.truncate(count);
+        skippers
+    }
+
+    fn summary(
+        &self,
+        slots_per_sec: Option<f64>,
+        leaders: usize,
+        endpoints: Vec<EndpointStatus>,
+        failovers: u32,
+    ) -> Summary {
+        let latest = self
+            .samples
+            .first()
+            .map(|latest| average_tps(std::slice::from_ref(latest)));
+        let window = (!self.samples.is_empty()).then(|| average_tps(&self.samples));
+        let fees = &self.priority_fees;
+        let fee = |percentile| (!fees.is_empty()).then(|| fees::percentile(fees, percentile));
+        let (leader_slots, produced) = self.production_totals();
+        Summary {
+            slot: self.slot,
+            slots_per_sec,
+            tps_last_min: latest.map(|(tps, _)| tps),
+            non_vote_tps_last_min: latest.map(|(_, non_vote)| non_vote),
+            tps_window: window.map(|(tps, _)| tps),
+            non_vote_tps_window: window.map(|(_, non_vote)| non_vote),
+            slot_time_ms: (!self.samples.is_empty()).then(|| self.slot_time() * 1_000.0),
+            fee_slots: fees.len(),
+            fee_mean: (!fees.is_empty()).then(|| self.fee_mean()),
+            fee_median: fee(50),
+            fee_p75: fee(75),
+            fee_p90: fee(90),
+            fee_max: fees.last().copied(),
+            first_slot: self.first_slot,
+            last_slot: self.last_slot,
+            leader_slots,
+            produced,
+            skip_rate: skip_rate(leader_slots, produced),
+            worst_skippers: self
+                .skippers(leaders)
+                .into_iter()
+                .map(|(identity, slots, produced)| Skipper {
+                    identity: identity.clone(),
+                    leader_slots: *slots,
+                    skipped: slots - produced,
+                })
+                .collect(),
+            endpoints,
+            failovers,
+        }
+    }
+
+    fn render(
+        &self,
+        out: &mut impl Write,
+        args: &Args,
+        endpoints: &[EndpointStatus],
+        slots_per_sec: Option<f64>,
+    ) -> Result<()> {
+        writeln!(out, "Chain monitor  {}", endpoints[0].endpoint)?;
+        writeln!(out, "Refreshing every {}s, Ctrl-C to quit", args.interval)?;
+        writeln!(out)?;
+        if endpoints.len() > 1 {
+            render_endpoints(out, endpoints)?;
+        }
+
+        match slots_per_sec {
+            Some(rate) => writeln!(out, "Slot            {}  ({rate:.2} slots/s)", self.slot)?,
+            None => writeln!(out, "Slot            {}", self.slot)?,
+        }
+
+        // Samples arrive newest first, each covering roughly a minute.
+        if let Some(latest) = self.samples.first() {
+            let (tps, non_vote) = average_tps(std::slice::from_ref(latest));
+            writeln!(out, "TPS (last min)  {tps:>8.0}  non-vote {non_vote:>6.0}")?;
+            let (tps, non_vote) = average_tps(&self.samples);
+            writeln!(
+                out,
+                "TPS ({} min)    {tps:>8.0}  non-vote {non_vote:>6.0}",
+                self.samples.len()
+            )?;
+            writeln!(
+                out,
+                "Slot time       {:>8.0} ms",
+                self.slot_time() * 1_000.0
+            )?;
+        }
+        writeln!(out)?;
+
+        let fees = &self.priority_fees;
+        if fees.is_empty() {
+            writeln!(out, "Priority fees   no recent data")?;
+        } else {
+            let mean = self.fee_mean();
+            writeln!(
+                out,
+                "Priority fees over {} slots (micro-lamports/CU)",
+                fees.len()
+            )?;
+            writeln!(
+                out,
+                "  mean {mean:.0}  median {}  p75 {}  p90 {}  max {}",
+                fees::percentile(fees, 50),
+                fees::percentile(fees, 75),
+                fees::percentile(fees, 90),
+                fees.last().copied().unwrap_or_default()
+            )?;
+            let paying = fees.iter().filter(|&&fee| fee > 0).count();
+            writeln!(
+                out,
+                "  {:.0}% of slots had a non-zero minimum fee",
+                100.0 * paying as f64 / fees.len() as f64
+            )?;
+        }
+        writeln!(out)?;
+
+        let (leader_slots, produced) = self.production_totals();
+        writeln!(
+            out,
+            "Block production, slots {}..={}",
+            self.first_slot, self.last_slot
+        )?;
+        writeln!(
+            out,
+            "  {produced} of {leader_slots} leader slots produced, {:.2}% skipped",
+            skip_rate(leader_slots, produced)
+        )?;
+
+        let skippers = self.skippers(args.leaders);
+        if !skippers.is_empty() {
+            writeln!(out, "  Most skipped slots:")?;
+        }
+        for (identity, slots, produced) in skippers {
+            writeln!(
+                out,
+                "    {identity:<44}  {:>5} of {slots:>5} skipped ({:.1}%)",
+                slots - produced,
+                skip_rate(*slots, *produced)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// One line per endpoint, the active one first.
+fn render_endpoints(out: &mut impl Write, endpoints: &[EndpointStatus]) -> Result<()> {
+    writeln!(out, "Endpoints")?;
+    for status in endpoints {
+        let state = match (&status.error, status.lagging) {
+            (Some(error), _) => format!("down: {error}"),
+            (None, true) => "lagging".to_string(),
+            (None, false) => "ok".to_string(),
+        };
+        let slot = status.slot.map(|slot| slot.to_string()).unwrap_or_default();
+        let latency = status
+            .latency_ms
+            .map(|ms| format!("{ms:.0} ms"))
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "  {:<40}  {slot:>10}  {latency:>7}  {state}",
+            status.endpoint
+        )?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Average total and non-vote transactions per second over `samples`.
+fn average_tps(samples: &[RpcPerfSample]) -> (f64, f64) {
+    let seconds: f64 = samples
+        .iter()
+        .map(|sample| f64::from(sample.sample_period_secs))
+        .sum();
+    if seconds == 0.0 {
+        return (0.0, 0.0);
+    }
+    let total: u64 = samples.iter().map(|sample| sample.num_transactions).sum();
+    let non_vote: u64 = samples
+        .iter()
+        .filter_map(|sample| sample.num_non_vote_transactions)
+        .sum();
+    (total as f64 / seconds, non_vote as f64 / seconds)
+}
+
+fn skip_rate(leader_slots: usize, produced: usize) -> f64 {
+    if leader_slots == 0 {
+        return 0.0;
+    }
+    100.0 * leader_slots.saturating_sub(produced) as f64 / leader_slots as f64
+}
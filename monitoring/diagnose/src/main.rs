@@ -0,0 +1,256 @@
+use clap::Parser;
+use common::Result;
+use common::cli::{RpcArgs, WsArgs};
+use common::client::build_nonblocking_client;
+use common::output::OutputArgs;
+use futures::StreamExt;
+use serde::Serialize;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status_client_types::{TransactionDetails, UiTransactionEncoding};
+use std::future::Future;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use tokio::time;
+
+/// Exit code when at least one check failed; errors exit with 1 as usual.
+const FAILED_EXIT_CODE: u8 = 2;
+
+/// How long the websocket check waits for its first slot notification.
+const WEBSOCKET_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Roughly 46 days of slots; far enough back that only an archival endpoint
+/// still serves the block.
+const ARCHIVAL_DEPTH: Slot = 10_000_000;
+
+/// Run a battery of checks against an RPC endpoint and print a pass/fail
+/// report: getHealth, getVersion, whether the commitment levels are
+/// ordered as they should be, websocket connectivity, getLatestBlockhash
+/// latency, and whether old blocks are still served. Exits with code 2 when
+/// any check fails.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    /// getLatestBlockhash calls timed by the latency check
+    #[arg(short = 'n', long, default_value_t = 5)]
+    samples: usize,
+
+    /// Median getLatestBlockhash latency, in milliseconds, above which the
+    /// latency check fails
+    #[arg(long, default_value_t = 500)]
+    max_latency: u64,
+
+    /// Slot the archival check starts looking for a block at; defaults to
+    /// about 46 days ago
+    #[arg(long)]
+    archival_slot: Option<Slot>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct Check {
+    name: &'static str,
+    passed: bool,
+    detail: String,
+    /// Time the check took, in milliseconds
+    duration_ms: u64,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(common::config::parse()).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(FAILED_EXIT_CODE),
+        Err(err) => common::report(Err(err)),
+    }
+}
+
+/// Returns whether every check passed.
+async fn run(args: Args) -> Result<bool> {
+    let connection = args.rpc.nonblocking_client();
+    let checks = vec![
+        check("health", health(&connection)).await,
+        check("version", version(&connection)).await,
+        check("commitment", commitment(&args)).await,
+        check("websocket", websocket(&args.ws.url(&args.rpc))).await,
+        check("latency", latency(&connection, &args)).await,
+        check("archival", archival(&connection, args.archival_slot)).await,
+    ];
+    let passed = checks.iter().all(|check| check.passed);
+
+    args.output.print(&checks, || {
+        for check in &checks {
+            println!(
+                "{}  {:<10}  {:>6} ms  {}",
+                if check.passed { "PASS" } else { "FAIL" },
+                check.name,
+                check.duration_ms,
+                check.detail
+            );
+        }
+        println!();
+        println!(
+            "{} of {} checks passed",
+            checks.iter().filter(|check| check.passed).count(),
+            checks.len()
+        );
+    })?;
+    Ok(passed)
+}
+
+/// Runs and times one check.
+async fn check(name: &'static str, run: impl Future<Output = Result<Verdict>>) -> Check {
+    let started = Instant::now();
+    let result = run.await;
+    let duration_ms = started.elapsed().as_millis() as u64;
+    let (passed, detail) = match result {
+        Ok(Verdict::Pass(detail)) => (true, detail),
+        Ok(Verdict::Fail(detail)) => (false, detail),
+        Err(err) => (false, err.to_string()),
+    };
+    Check {
+        name,
+        passed,
+        detail,
+        duration_ms,
+    }
+}
+
+/// What a check concluded. A check that cannot run to a conclusion, e.g.
+/// because the request failed, returns an error instead, which fails it too.
+enum Verdict {
+    Pass(String),
+    Fail(String),
+}
+
+async fn health(connection: &RpcClient) -> Result<Verdict> {
+    connection.get_health().await?;
+    Ok(Verdict::Pass("node reports healthy".to_string()))
+}
+
+async fn version(connection: &RpcClient) -> Result<Verdict> {
+    let version = connection.get_version().await?;
+    Ok(Verdict::Pass(format!(
+        "solana-core {}, feature set {}",
+        version.solana_core,
+        version
+            .feature_set
+            .map_or_else(|| "unknown".to_string(), |set| set.to_string())
+    )))
+}
+
+/// Each level's slot must be at least the next stricter one's, and
+/// finalized should trail confirmed by about 32 slots.
+async fn commitment(args: &Args) -> Result<Verdict> {
+    let slot_at = |commitment: CommitmentConfig| async move {
+        build_nonblocking_client(&args.rpc.rpc_url, commitment, args.rpc.timeout())
+            .get_slot()
+            .await
+    };
+    let (processed, confirmed, finalized) = tokio::join!(
+        slot_at(CommitmentConfig::processed()),
+        slot_at(CommitmentConfig::confirmed()),
+        slot_at(CommitmentConfig::finalized())
+    );
+    let (processed, confirmed, finalized) = (processed?, confirmed?, finalized?);
+    let detail = format!("processed {processed}, confirmed {confirmed}, finalized {finalized}");
+    // The three calls race each other by a few milliseconds, so a slot of
+    // slack is allowed.
+    if processed + 1 < confirmed || confirmed + 1 < finalized {
+        return Ok(Verdict::Fail(format!("levels out of order: {detail}")));
+    }
+    Ok(Verdict::Pass(detail))
+}
+
+async fn websocket(ws_url: &str) -> Result<Verdict> {
+    let client = PubsubClient::new(ws_url).await?;
+    let (mut notifications, unsubscribe) = client.slot_subscribe().await?;
+    let first = time::timeout(WEBSOCKET_TIMEOUT, notifications.next()).await;
+    drop(notifications);
+    unsubscribe().await;
+    match first {
+        Ok(Some(slot)) => Ok(Verdict::Pass(format!(
+            "slotSubscribe delivered slot {}",
+            slot.slot
+        ))),
+        Ok(None) => Ok(Verdict::Fail(
+            "subscription closed without a notification".to_string(),
+        )),
+        Err(_) => Ok(Verdict::Fail(format!(
+            "no slot notification within {WEBSOCKET_TIMEOUT:?}"
+        ))),
+    }
+}
+
+async fn latency(connection: &RpcClient, args: &Args) -> Result<Verdict> {
+    let mut samples = Vec::with_capacity(args.samples.max(1));
+    for _ in 0..args.samples.max(1) {
+        let started = Instant::now();
+        connection.get_latest_blockhash().await?;
+        samples.push(started.elapsed());
+    }
+    samples.sort();
+    let median = samples[samples.len() / 2];
+    let detail = format!(
+        "getLatestBlockhash median {} ms, max {} ms over {} calls",
+        median.as_millis(),
+        samples[samples.len() - 1].as_millis(),
+        samples.len()
+    );
+    if median > Duration::from_millis(args.max_latency) {
+        return Ok(Verdict::Fail(format!(
+            "{detail}, above {} ms",
+            args.max_latency
+        )));
+    }
+    Ok(Verdict::Pass(detail))
+}
+
+/// Fetches the first block produced at or after `slot`: slots are often
+/// skipped, so getBlocksWithLimit finds one that exists.
+async fn archival(connection: &RpcClient, slot: Option<Slot>) -> Result<Verdict> {
+    let slot = match slot {
+        Some(slot) => slot,
+        None => connection
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .await?
+            .saturating_sub(ARCHIVAL_DEPTH),
+    };
+    let first_available = connection.get_first_available_block().await?;
+    let blocks = connection.get_blocks_with_limit(slot, 10).await?;
+    let Some(&block_slot) = blocks.first() else {
+        return Ok(Verdict::Fail(format!(
+            "no blocks listed from slot {slot}; first available block is {first_available}"
+        )));
+    };
+    let block = connection
+        .get_block_with_config(
+            block_slot,
+            RpcBlockConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                transaction_details: Some(TransactionDetails::None),
+                rewards: Some(false),
+                commitment: Some(CommitmentConfig::finalized()),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    Ok(Verdict::Pass(format!(
+        "block {block_slot} ({}) served; first available block is {first_available}",
+        block.block_time.map_or_else(
+            || "no block time".to_string(),
+            common::time::format_timestamp
+        )
+    )))
+}
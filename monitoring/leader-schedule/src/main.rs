@@ -0,0 +1,206 @@
+use clap::Parser;
+use common::Result;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::time::{average_slot_time, estimate_slot_time, format_duration};
+use serde::Serialize;
+use solana_client::rpc_config::RpcLeaderScheduleConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Show how far the current epoch has progressed and roughly when it ends,
+/// then either the upcoming leader slots of one validator (from
+/// `getLeaderSchedule`) or the next leaders of the whole cluster (from
+/// `getSlotLeaders`), with wall-clock estimates based on the measured slot
+/// time.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Validator identity whose leader slots to list
+    #[arg(short, long)]
+    identity: Option<Pubkey>,
+
+    /// Number of upcoming slots (or, with --identity, leader windows) to
+    /// list
+    #[arg(short = 'n', long, default_value_t = 10)]
+    count: usize,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct EpochSchedule {
+    epoch: u64,
+    slot: u64,
+    slot_index: u64,
+    slots_in_epoch: u64,
+    /// Percent of the epoch's slots already passed
+    progress: f64,
+    slot_time_ms: u128,
+    /// Estimated seconds until the next epoch starts
+    seconds_remaining: u64,
+    epoch_ends_at: String,
+    identity: Option<String>,
+    /// Leader slots of `identity` left in this epoch
+    leader_slots_remaining: Option<usize>,
+    upcoming: Vec<UpcomingSlot>,
+}
+
+#[derive(Serialize)]
+struct UpcomingSlot {
+    /// First slot; with --identity, the start of a run of consecutive
+    /// leader slots
+    slot: u64,
+    /// Consecutive slots led, normally 4
+    slots: u64,
+    leader: String,
+    slots_from_now: u64,
+    estimated_at: String,
+}
+
+/// getRecentPerformanceSamples entries (one per minute) used to measure the
+/// slot time.
+const PERFORMANCE_SAMPLES: usize = 30;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let epoch = connection.get_epoch_info()?;
+    let samples = connection.get_recent_performance_samples(Some(PERFORMANCE_SAMPLES))?;
+    let slot_time = average_slot_time(&samples);
+    let slots_left = epoch.slots_in_epoch - epoch.slot_index;
+    let epoch_start = epoch.absolute_slot - epoch.slot_index;
+
+    let mut leader_slots_remaining = None;
+    let upcoming = match &args.identity {
+        Some(identity) => {
+            // Indexes are relative to the first slot of the epoch.
+            let schedule = connection
+                .get_leader_schedule_with_config(
+                    None,
+                    RpcLeaderScheduleConfig {
+                        identity: Some(identity.to_string()),
+                        commitment: Some(connection.commitment()),
+                    },
+                )?
+                .unwrap_or_default();
+            let mut slots: Vec<u64> = schedule
+                .into_values()
+                .flatten()
+                .map(|index| epoch_start + index as u64)
+                .filter(|&slot| slot >= epoch.absolute_slot)
+                .collect();
+            slots.sort_unstable();
+            leader_slots_remaining = Some(slots.len());
+            leader_windows(&slots)
+                .into_iter()
+                .take(args.count)
+                .map(|(slot, count)| {
+                    upcoming(
+                        slot,
+                        count,
+                        identity.to_string(),
+                        epoch.absolute_slot,
+                        slot_time,
+                    )
+                })
+                .collect()
+        }
+        None => connection
+            .get_slot_leaders(epoch.absolute_slot, args.count as u64)?
+            .into_iter()
+            .enumerate()
+            .map(|(offset, leader)| {
+                let slot = epoch.absolute_slot + offset as u64;
+                upcoming(slot, 1, leader.to_string(), epoch.absolute_slot, slot_time)
+            })
+            .collect(),
+    };
+
+    let remaining = slot_time.mul_f64(slots_left as f64);
+    let schedule = EpochSchedule {
+        epoch: epoch.epoch,
+        slot: epoch.absolute_slot,
+        slot_index: epoch.slot_index,
+        slots_in_epoch: epoch.slots_in_epoch,
+        progress: epoch.slot_index as f64 * 100.0 / epoch.slots_in_epoch as f64,
+        slot_time_ms: slot_time.as_millis(),
+        seconds_remaining: remaining.as_secs(),
+        epoch_ends_at: estimate_slot_time(slots_left, slot_time),
+        identity: args.identity.map(|identity| identity.to_string()),
+        leader_slots_remaining,
+        upcoming,
+    };
+    args.output.print(&schedule, || print_schedule(&schedule))
+}
+
+/// Groups sorted slots into runs of consecutive slots.
+fn leader_windows(slots: &[u64]) -> Vec<(u64, u64)> {
+    let mut windows: Vec<(u64, u64)> = Vec::new();
+    for &slot in slots {
+        match windows.last_mut() {
+            Some((start, count)) if *start + *count == slot => *count += 1,
+            _ => windows.push((slot, 1)),
+        }
+    }
+    windows
+}
+
+fn upcoming(
+    slot: u64,
+    slots: u64,
+    leader: String,
+    current_slot: u64,
+    slot_time: Duration,
+) -> UpcomingSlot {
+    let slots_from_now = slot - current_slot;
+    UpcomingSlot {
+        slot,
+        slots,
+        leader,
+        slots_from_now,
+        estimated_at: estimate_slot_time(slots_from_now, slot_time),
+    }
+}
+
+fn print_schedule(schedule: &EpochSchedule) {
+    println!(
+        "Epoch {}: slot {} of {} ({:.2}%)",
+        schedule.epoch, schedule.slot_index, schedule.slots_in_epoch, schedule.progress
+    );
+    println!(
+        "Slot time {}ms; epoch ends in ~{} (around {})",
+        schedule.slot_time_ms,
+        format_duration(Duration::from_secs(schedule.seconds_remaining)),
+        schedule.epoch_ends_at
+    );
+    match (&schedule.identity, schedule.leader_slots_remaining) {
+        (Some(identity), Some(0)) => {
+            println!("{identity} has no leader slots left in this epoch");
+            return;
+        }
+        (Some(identity), Some(remaining)) => {
+            println!("{identity} leads {remaining} more slots this epoch")
+        }
+        _ => {}
+    }
+    println!();
+    println!(
+        "{:>12}  {:>5}  {:<44}  {:>10}  Estimated time",
+        "Slot", "Slots", "Leader", "In slots"
+    );
+    for slot in &schedule.upcoming {
+        println!(
+            "{:>12}  {:>5}  {:<44}  {:>10}  {}",
+            slot.slot, slot.slots, slot.leader, slot.slots_from_now, slot.estimated_at
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use clap::{Parser, Subcommand};
+use common::cli::{AddressArg, Commitment, RpcArgs};
+use common::decode::Decoder;
+use common::output::OutputArgs;
+use common::time::format_timestamp;
+use common::units::format_sol;
+use common::{ExampleError, Result, at_least_confirmed, token};
+use rustyline::DefaultEditor;
+use rustyline::error::ReadlineError;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::io;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tracing::error;
+
+/// Account data shown by `account` before it is cut short.
+const DATA_PREVIEW_LEN: usize = 64;
+
+/// Explore an RPC endpoint interactively: type `slot`, `balance <address>`,
+/// `account <address>` or `tx <signature>` and see the answer, decoded with
+/// the same helpers the other examples use. `help` lists every command.
+///
+/// Addresses may be base58 keys, `.sol` domains or `wallet:mint` for an
+/// associated token account. With `--output json` every answer is printed
+/// as JSON instead.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// File to load the command history from and save it to
+    #[arg(long)]
+    history: Option<PathBuf>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Commands; `help <command>` describes one in detail
+#[derive(Parser)]
+#[command(multicall = true)]
+struct Line {
+    #[command(subcommand)]
+    command: ReplCommand,
+}
+
+#[derive(Subcommand)]
+enum ReplCommand {
+    /// Current slot
+    Slot,
+    /// Current epoch and how far into it the cluster is
+    Epoch,
+    /// Latest blockhash and the block height it is valid until
+    Blockhash,
+    /// SOL balance of an address
+    Balance { address: AddressArg },
+    /// Owner, balance and data of an account, decoded for token accounts
+    /// and mints
+    Account { address: AddressArg },
+    /// A transaction's status, fee and decoded instructions
+    Tx { signature: Signature },
+    /// Switch the commitment level used from now on
+    Commitment {
+        #[arg(value_enum)]
+        level: Commitment,
+    },
+    /// Leave the REPL (Ctrl-D works too)
+    #[command(alias = "exit")]
+    Quit,
+}
+
+#[derive(Serialize)]
+struct SlotAnswer {
+    slot: u64,
+}
+
+#[derive(Serialize)]
+struct EpochAnswer {
+    epoch: u64,
+    slot_index: u64,
+    slots_in_epoch: u64,
+    block_height: u64,
+}
+
+#[derive(Serialize)]
+struct BlockhashAnswer {
+    blockhash: String,
+    last_valid_block_height: u64,
+}
+
+#[derive(Serialize)]
+struct BalanceAnswer {
+    address: String,
+    lamports: u64,
+}
+
+#[derive(Serialize)]
+struct AccountAnswer {
+    address: String,
+    owner: String,
+    lamports: u64,
+    executable: bool,
+    data_len: usize,
+    /// Hex of the first bytes of the data
+    data_preview: String,
+    /// What the data decodes as, when a decoder recognises it
+    decoded: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TxAnswer {
+    signature: String,
+    slot: u64,
+    time: Option<String>,
+    error: Option<String>,
+    fee: u64,
+    /// One line per instruction, inner ones indented
+    instructions: Vec<String>,
+}
+
+/// The connection and settings that outlive a single command.
+struct Session {
+    rpc: RpcArgs,
+    connection: RpcClient,
+    output: OutputArgs,
+    decoder: Decoder,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let mut editor = DefaultEditor::new().map_err(readline_error)?;
+    if let Some(history) = &args.history {
+        // A missing file just means no history yet.
+        let _ = editor.load_history(history);
+    }
+    let mut session = Session {
+        connection: args.rpc.client(),
+        rpc: args.rpc,
+        output: args.output,
+        decoder: Decoder::default(),
+    };
+    session.output.status(format_args!(
+        "Connected to {}; type `help` for the commands",
+        session.rpc.rpc_url
+    ));
+
+    loop {
+        let line = match editor.readline("solana> ") {
+            Ok(line) => line,
+            // Ctrl-C drops the line being typed, as in a shell.
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(err) => return Err(readline_error(err)),
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        if words.is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line.as_str());
+        let command = match Line::try_parse_from(&words) {
+            Ok(line) => line.command,
+            // Covers `help` and `<command> --help` as well as mistakes.
+            Err(err) => {
+                let _ = err.print();
+                continue;
+            }
+        };
+        if matches!(command, ReplCommand::Quit) {
+            break;
+        }
+        if let Err(err) = session.execute(command) {
+            error!("{err}");
+        }
+    }
+
+    if let Some(history) = &args.history {
+        editor.save_history(history).map_err(readline_error)?;
+    }
+    Ok(())
+}
+
+fn readline_error(err: ReadlineError) -> ExampleError {
+    match err {
+        ReadlineError::Io(err) => ExampleError::Io(err),
+        err => ExampleError::Io(io::Error::other(err)),
+    }
+}
+
+impl Session {
+    fn execute(&mut self, command: ReplCommand) -> Result<()> {
+        match command {
+            ReplCommand::Slot => {
+                let answer = SlotAnswer {
+                    slot: self.connection.get_slot()?,
+                };
+                self.output.print(&answer, || println!("{}", answer.slot))
+            }
+            ReplCommand::Epoch => {
+                let info = self.connection.get_epoch_info()?;
+                let answer = EpochAnswer {
+                    epoch: info.epoch,
+                    slot_index: info.slot_index,
+                    slots_in_epoch: info.slots_in_epoch,
+                    block_height: info.block_height,
+                };
+                self.output.print(&answer, || {
+                    println!(
+                        "Epoch {}, slot {}/{} ({:.1}%), block height {}",
+                        answer.epoch,
+                        answer.slot_index,
+                        answer.slots_in_epoch,
+                        answer.slot_index as f64 / answer.slots_in_epoch.max(1) as f64 * 100.0,
+                        answer.block_height
+                    )
+                })
+            }
+            ReplCommand::Blockhash => {
+                let (blockhash, last_valid_block_height) = self
+                    .connection
+                    .get_latest_blockhash_with_commitment(self.connection.commitment())?;
+                let answer = BlockhashAnswer {
+                    blockhash: blockhash.to_string(),
+                    last_valid_block_height,
+                };
+                self.output.print(&answer, || {
+                    println!(
+                        "{} (valid until block height {})",
+                        answer.blockhash, answer.last_valid_block_height
+                    )
+                })
+            }
+            ReplCommand::Balance { address } => {
+                let address = address.resolve(&self.connection)?;
+                let answer = BalanceAnswer {
+                    address: address.to_string(),
+                    lamports: self.connection.get_balance(&address)?,
+                };
+                self.output
+                    .print(&answer, || println!("{} SOL", format_sol(answer.lamports)))
+            }
+            ReplCommand::Account { address } => self.account(&address),
+            ReplCommand::Tx { signature } => self.transaction(&signature),
+            ReplCommand::Commitment { level } => {
+                self.rpc.commitment = level;
+                self.connection = self.rpc.client();
+                self.output
+                    .status(format_args!("Commitment set to {level:?}"));
+                Ok(())
+            }
+            ReplCommand::Quit => Ok(()),
+        }
+    }
+
+    fn account(&self, address: &AddressArg) -> Result<()> {
+        let address = address.resolve(&self.connection)?;
+        let account = self
+            .connection
+            .get_account_with_commitment(&address, self.connection.commitment())?
+            .value
+            .ok_or(ExampleError::AccountNotFound(address))?;
+        // The same decoders the token examples use; anything else is shown
+        // as raw bytes.
+        let decoded = if let Ok(info) = token::TokenAccountInfo::unpack(&address, &account) {
+            Some(format!(
+                "{} token account: mint {}, owner {}, {} base units",
+                token::program_name(&info.program_id),
+                info.account.mint,
+                info.account.owner,
+                info.account.amount
+            ))
+        } else if let Ok(mint) = token::MintInfo::unpack(&address, &account) {
+            Some(format!(
+                "{} mint: supply {}, {} decimals",
+                token::program_name(&mint.program_id),
+                mint.ui_amount(mint.mint.supply),
+                mint.decimals()
+            ))
+        } else {
+            None
+        };
+        let answer = AccountAnswer {
+            address: address.to_string(),
+            owner: account.owner.to_string(),
+            lamports: account.lamports,
+            executable: account.executable,
+            data_len: account.data.len(),
+            data_preview: hex::encode(&account.data[..account.data.len().min(DATA_PREVIEW_LEN)]),
+            decoded,
+        };
+        self.output.print(&answer, || {
+            println!("Address:    {}", answer.address);
+            println!("Owner:      {}", answer.owner);
+            println!("Balance:    {} SOL", format_sol(answer.lamports));
+            println!("Executable: {}", answer.executable);
+            println!("Data:       {} bytes", answer.data_len);
+            if let Some(decoded) = &answer.decoded {
+                println!("Decoded:    {decoded}");
+            } else if !answer.data_preview.is_empty() {
+                let more = if answer.data_len > DATA_PREVIEW_LEN {
+                    "…"
+                } else {
+                    ""
+                };
+                println!("            {}{more}", answer.data_preview);
+            }
+        })
+    }
+
+    fn transaction(&self, signature: &Signature) -> Result<()> {
+        let confirmed = self.connection.get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(at_least_confirmed(self.connection.commitment())),
+                max_supported_transaction_version: Some(0),
+            },
+        )?;
+        let transaction =
+            confirmed.transaction.transaction.decode().ok_or_else(|| {
+                ExampleError::Parse("transaction is not base64 encoded".to_string())
+            })?;
+        let meta = confirmed.transaction.meta.ok_or_else(|| {
+            ExampleError::Parse("the node returned no status metadata".to_string())
+        })?;
+        let instructions = self
+            .decoder
+            .decode_transaction(&transaction.message, &meta)?
+            .into_iter()
+            .map(|summary| {
+                let indent = "  ".repeat(summary.depth - 1);
+                match summary.decoded {
+                    Some(decoded) => {
+                        let fields: Vec<String> = decoded
+                            .fields
+                            .iter()
+                            .map(|(name, value)| format!("{name}={value}"))
+                            .collect();
+                        format!(
+                            "{indent}{}: {} {}",
+                            summary.program,
+                            decoded.kind,
+                            fields.join(" ")
+                        )
+                    }
+                    None => format!("{indent}{}", summary.program),
+                }
+            })
+            .collect();
+        let answer = TxAnswer {
+            signature: signature.to_string(),
+            slot: confirmed.slot,
+            time: confirmed.block_time.map(format_timestamp),
+            error: meta.err.as_ref().map(ToString::to_string),
+            fee: meta.fee,
+            instructions,
+        };
+        self.output.print(&answer, || {
+            println!(
+                "Slot {}{}: {}, fee {} SOL",
+                answer.slot,
+                answer
+                    .time
+                    .as_ref()
+                    .map(|time| format!(" ({time})"))
+                    .unwrap_or_default(),
+                answer
+                    .error
+                    .as_ref()
+                    .map_or_else(|| "success".to_string(), |err| format!("failed: {err}")),
+                format_sol(answer.fee)
+            );
+            for instruction in &answer.instructions {
+                println!("  {instruction}");
+            }
+        })
+    }
+}
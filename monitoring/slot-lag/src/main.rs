@@ -0,0 +1,171 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::client::build_nonblocking_client;
+use common::output::{OutputArgs, RecordWriter};
+use common::time::format_timestamp;
+use common::{Result, logging};
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::clock::Slot;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+/// Exit code of a one-off check that found the endpoint lagging; errors
+/// exit with 1 as usual.
+const LAGGING_EXIT_CODE: u8 = 2;
+
+/// Check whether an RPC endpoint keeps up with the cluster.
+///
+/// Two gaps are measured: how far the endpoint's `getSlot` trails a
+/// reference endpoint, and how far it trails the endpoint's own
+/// `getMaxShredInsertSlot`, i.e. blocks the node has received but not yet
+/// replayed. Either one above `--threshold` counts as lagging. A single
+/// check exits with code 2 when lagging, for cron jobs and container health
+/// checks; `--watch` keeps checking and logs a warning each time instead.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Endpoint to compare against, e.g. another provider or the public
+    /// cluster endpoint; without it only the shred gap is checked
+    #[arg(long, env = "SOLANA_REFERENCE_URL")]
+    reference: Option<String>,
+
+    /// Slots of lag tolerated before the endpoint counts as lagging
+    #[arg(short, long, default_value_t = 20)]
+    threshold: u64,
+
+    /// Keep checking instead of exiting after one check
+    #[arg(short, long)]
+    watch: bool,
+
+    /// Seconds between checks with --watch
+    #[arg(short, long, default_value_t = 10)]
+    interval: u64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct LagCheck {
+    timestamp: String,
+    endpoint: String,
+    slot: Slot,
+    max_shred_insert_slot: Slot,
+    /// Slots received but not yet replayed
+    shred_lag: u64,
+    reference: Option<String>,
+    reference_slot: Option<Slot>,
+    /// Slots behind the reference; negative when ahead of it
+    reference_lag: Option<i64>,
+    lagging: bool,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    match run(common::config::parse()).await {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(LAGGING_EXIT_CODE),
+        Err(err) => common::report(Err(err)),
+    }
+}
+
+/// Returns whether the endpoint was healthy at the last check.
+async fn run(args: Args) -> Result<bool> {
+    let endpoint = args.rpc.nonblocking_client();
+    let reference = args
+        .reference
+        .as_ref()
+        .map(|url| build_nonblocking_client(url, args.rpc.commitment_config(), args.rpc.timeout()));
+    let mut records = args.output.records();
+
+    if !args.watch {
+        let check = check(&args, &endpoint, reference.as_ref()).await?;
+        report(&args, &check, &mut records)?;
+        return Ok(!check.lagging);
+    }
+    let interval = Duration::from_secs(args.interval.max(1));
+    loop {
+        // The probe outlives failed checks; an unreachable endpoint is
+        // reported and checked again next time.
+        match check(&args, &endpoint, reference.as_ref()).await {
+            Ok(check) => report(&args, &check, &mut records)?,
+            Err(err) => error!("{err}"),
+        }
+        tokio::select! {
+            _ = time::sleep(interval) => {}
+            _ = signal::ctrl_c() => return Ok(true),
+        }
+    }
+}
+
+/// Queries all three slots at once, so the request latency does not show
+/// up as lag.
+async fn check(
+    args: &Args,
+    endpoint: &RpcClient,
+    reference: Option<&RpcClient>,
+) -> Result<LagCheck> {
+    let reference_slot = async {
+        match reference {
+            Some(reference) => reference.get_slot().await.map(Some),
+            None => Ok(None),
+        }
+    };
+    let (slot, max_shred_insert_slot, reference_slot) = tokio::join!(
+        endpoint.get_slot(),
+        endpoint.get_max_shred_insert_slot(),
+        reference_slot
+    );
+    let (slot, max_shred_insert_slot, reference_slot) =
+        (slot?, max_shred_insert_slot?, reference_slot?);
+
+    let shred_lag = max_shred_insert_slot.saturating_sub(slot);
+    let reference_lag = reference_slot.map(|reference| reference as i64 - slot as i64);
+    let lagging =
+        shred_lag > args.threshold || reference_lag.is_some_and(|lag| lag > args.threshold as i64);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    Ok(LagCheck {
+        timestamp: format_timestamp(now),
+        endpoint: logging::endpoint_label(&args.rpc.rpc_url),
+        slot,
+        max_shred_insert_slot,
+        shred_lag,
+        reference: args.reference.as_deref().map(logging::endpoint_label),
+        reference_slot,
+        reference_lag,
+        lagging,
+    })
+}
+
+fn report(args: &Args, check: &LagCheck, records: &mut RecordWriter) -> Result<()> {
+    if check.lagging {
+        warn!(
+            "{} is lagging by more than {} slots",
+            check.endpoint, args.threshold
+        );
+    }
+    records.write(check, || {
+        let reference = match (check.reference_slot, check.reference_lag) {
+            (Some(slot), Some(lag)) => format!(", reference {slot} ({lag} behind)"),
+            _ => String::new(),
+        };
+        println!(
+            "{}  {}  slot {}, shreds up to {} ({} behind){reference}  {}",
+            check.timestamp,
+            check.endpoint,
+            check.slot,
+            check.max_shred_insert_slot,
+            check.shred_lag,
+            if check.lagging { "LAGGING" } else { "ok" }
+        );
+    })
+}
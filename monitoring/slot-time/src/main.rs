@@ -0,0 +1,222 @@
+use clap::{Parser, Subcommand};
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::time::{self, SlotClock, format_timestamp, parse_timestamp};
+use common::{Result, at_least_confirmed};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::clock::{Slot, UnixTimestamp};
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Convert between slots and wall-clock time.
+///
+/// A slot with a block is given its block's `blockTime`, and a skipped slot
+/// the time of the next block. "What slot was it at time T" is answered
+/// exactly by binary-searching block times, a few dozen requests. Slots
+/// without blocks to go by, future ones or ones older than the node's
+/// history, are estimated from a least-squares fit of slots against block
+/// times sampled over `--span` slots, which `clock` prints on its own.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Slots sampled for estimates: the latest ones, or the node's oldest for
+    /// slots before its history
+    #[arg(long, default_value_t = 50_000)]
+    span: u64,
+
+    /// Block times fitted for estimates
+    #[arg(long, default_value_t = 8)]
+    samples: usize,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// When a slot was, or will be, produced
+    Time { slot: Slot },
+    /// The first slot at or after a time (RFC 3339 or Unix seconds)
+    Slot {
+        #[arg(value_parser = parse_timestamp)]
+        time: UnixTimestamp,
+        /// Estimate from the fit instead of searching block times
+        #[arg(long)]
+        estimate: bool,
+    },
+    /// The fitted slot rate and where it is anchored
+    Clock,
+}
+
+#[derive(Serialize)]
+struct Conversion {
+    slot: Slot,
+    timestamp: UnixTimestamp,
+    time: String,
+    source: Source,
+    /// The fit used when `source` is `estimated`
+    clock: Option<Clock>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum Source {
+    /// The slot's own block time
+    Block,
+    /// The slot was skipped; the time of the next block
+    NextBlock,
+    Estimated,
+}
+
+impl Source {
+    fn label(self) -> &'static str {
+        match self {
+            Source::Block => "block time",
+            Source::NextBlock => "next block's time; the slot was skipped",
+            Source::Estimated => "estimated",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Clock {
+    slot: Slot,
+    timestamp: UnixTimestamp,
+    time: String,
+    slots_per_second: f64,
+    ms_per_slot: f64,
+}
+
+impl From<SlotClock> for Clock {
+    fn from(clock: SlotClock) -> Self {
+        let timestamp = clock.time_at(clock.slot);
+        Self {
+            slot: clock.slot,
+            timestamp,
+            time: format_timestamp(timestamp),
+            slots_per_second: clock.slots_per_second,
+            ms_per_slot: 1000.0 / clock.slots_per_second,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    match args.command {
+        Command::Time { slot } => {
+            let first = connection.get_first_available_block()?;
+            let tip = connection
+                .get_slot_with_commitment(at_least_confirmed(args.rpc.commitment_config()))?;
+            let exact = if (first..=tip).contains(&slot) {
+                time::block_time(&connection, slot)?
+            } else {
+                None
+            };
+            let conversion = match exact {
+                Some((block, timestamp)) => Conversion {
+                    slot,
+                    timestamp,
+                    time: format_timestamp(timestamp),
+                    source: if block == slot {
+                        Source::Block
+                    } else {
+                        Source::NextBlock
+                    },
+                    clock: None,
+                },
+                None => {
+                    let clock = clock_near(&connection, &args, first, slot)?;
+                    estimated(slot, clock.time_at(slot), clock)
+                }
+            };
+            print_conversion(output, &conversion)
+        }
+        Command::Slot { time, estimate } => {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs() as i64);
+            let exact = if estimate || time > now {
+                None
+            } else {
+                time::slot_at_time(&connection, time)?
+            };
+            let conversion = match exact {
+                Some((slot, timestamp)) => Conversion {
+                    slot,
+                    timestamp,
+                    time: format_timestamp(timestamp),
+                    source: Source::Block,
+                    clock: None,
+                },
+                None => {
+                    let mut clock = SlotClock::recent(&connection, args.span, args.samples)?;
+                    let first = connection.get_first_available_block()?;
+                    if clock.slot_at(time) < first {
+                        clock =
+                            SlotClock::sample(&connection, first, first + args.span, args.samples)?;
+                    }
+                    estimated(clock.slot_at(time), time, clock)
+                }
+            };
+            print_conversion(output, &conversion)
+        }
+        Command::Clock => {
+            let clock = Clock::from(SlotClock::recent(&connection, args.span, args.samples)?);
+            output.print(&clock, || {
+                println!("Slot:        {}", clock.slot);
+                println!("Time:        {}", clock.time);
+                println!("Slots/s:     {:.3}", clock.slots_per_second);
+                println!("Slot time:   {:.1} ms", clock.ms_per_slot);
+            })
+        }
+    }
+}
+
+/// A fit near `slot`: over the node's oldest blocks, from `first`, for a
+/// slot older than them, else over the latest ones.
+fn clock_near(connection: &RpcClient, args: &Args, first: Slot, slot: Slot) -> Result<SlotClock> {
+    if slot < first {
+        SlotClock::sample(connection, first, first + args.span, args.samples)
+    } else {
+        SlotClock::recent(connection, args.span, args.samples)
+    }
+}
+
+fn estimated(slot: Slot, timestamp: UnixTimestamp, clock: SlotClock) -> Conversion {
+    Conversion {
+        slot,
+        timestamp,
+        time: format_timestamp(timestamp),
+        source: Source::Estimated,
+        clock: Some(clock.into()),
+    }
+}
+
+fn print_conversion(output: &OutputArgs, conversion: &Conversion) -> Result<()> {
+    output.print(conversion, || {
+        println!("Slot:    {}", conversion.slot);
+        println!(
+            "Time:    {} (Unix {})",
+            conversion.time, conversion.timestamp
+        );
+        println!("Source:  {}", conversion.source.label());
+        if let Some(clock) = &conversion.clock {
+            println!(
+                "Fit:     {:.3} slots/s through slot {} at {}",
+                clock.slots_per_second, clock.slot, clock.time
+            );
+        }
+    })
+}
@@ -0,0 +1,643 @@
+use borsh::BorshDeserialize;
+use clap::Parser;
+use common::anchor::{self, DISCRIMINATOR_LEN};
+use common::cli::{KeypairArgs, RpcArgs};
+use common::metaplex::{self, TOKEN_METADATA_PROGRAM_ID};
+use common::output::OutputArgs;
+use common::send::print_simulation;
+use common::time::format_timestamp;
+use common::tx_builder::TxBuilder;
+use common::units::format_sol;
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::sysvar;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Read a Candy Machine v3 and the Candy Guard wrapping it, show what a mint
+/// costs, how many items are left and whether minting is open, then build
+/// the guard's `mint_v2` transaction with every account it needs: the PDAs of
+/// the candy machine, the new NFT and the collection, plus the extra accounts
+/// each active guard reads, in guard order. Sends it, or only simulates it
+/// with --dry-run.
+///
+/// Guards that need off-chain input (allow list proofs, gatekeeper tokens, a
+/// third-party signature, NFTs to pay or burn with) are reported but not
+/// resolved.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer and minter of the NFT
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Candy machine address
+    #[arg(short = 'm', long)]
+    candy_machine: Pubkey,
+
+    /// Guard group to mint with, for candy guards that define groups
+    #[arg(short, long)]
+    group: Option<String>,
+
+    /// Simulate the mint instead of sending it
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+const CANDY_MACHINE_PROGRAM_ID: Pubkey = pubkey!("CndyV3LdqHUfDLmE5naZjVN8rBZz4tqhdefbAnjHG3JR");
+const CANDY_GUARD_PROGRAM_ID: Pubkey = pubkey!("Guard1JwRhJkVH6XZhzoYxeBVQe872VH6QggF4BWmS9g");
+
+/// `mint_v2` needs far more than the default 200k compute units.
+const MINT_UNIT_LIMIT: u32 = 400_000;
+
+/// `token_standard` of programmable NFTs, which need a token record.
+const PROGRAMMABLE_NON_FUNGIBLE: u8 = 4;
+
+/// Group labels are stored as fixed 6-byte, NUL-padded strings.
+const LABEL_LEN: usize = 6;
+
+#[derive(BorshDeserialize)]
+struct CandyMachine {
+    _version: u8,
+    token_standard: u8,
+    _features: [u8; 6],
+    authority: Pubkey,
+    /// The candy guard when the machine is wrapped by one
+    mint_authority: Pubkey,
+    collection_mint: Pubkey,
+    items_redeemed: u64,
+    items_available: u64,
+    symbol: String,
+    // The rest of the data (royalties, creators, config lines) is not needed
+    // to mint.
+}
+
+/// The guards this example knows the layout of, in the order the candy guard
+/// stores and evaluates them; bit `i` of a guard set's feature flags says
+/// whether guard `i` is present.
+#[derive(Clone, Default)]
+struct GuardSet {
+    bot_tax: Option<(u64, bool)>,
+    sol_payment: Option<(u64, Pubkey)>,
+    token_payment: Option<(u64, Pubkey, Pubkey)>,
+    start_date: Option<i64>,
+    third_party_signer: Option<Pubkey>,
+    token_gate: Option<(u64, Pubkey)>,
+    gatekeeper: Option<(Pubkey, bool)>,
+    end_date: Option<i64>,
+    allow_list: Option<[u8; 32]>,
+    mint_limit: Option<(u8, u16)>,
+    nft_payment: Option<(Pubkey, Pubkey)>,
+    redeemed_amount: Option<u64>,
+    address_gate: Option<Pubkey>,
+    nft_gate: Option<Pubkey>,
+    nft_burn: Option<Pubkey>,
+    token_burn: Option<(u64, Pubkey)>,
+    freeze_sol_payment: Option<(u64, Pubkey)>,
+    freeze_token_payment: Option<(u64, Pubkey, Pubkey)>,
+    program_gate: Option<Vec<Pubkey>>,
+    allocation: Option<(u8, u32)>,
+    token2022_payment: Option<(u64, Pubkey, Pubkey)>,
+}
+
+/// `program_gate` reserves room for this many programs whatever it holds.
+const PROGRAM_GATE_MAX: usize = 5;
+
+#[derive(Serialize)]
+struct CandyMachineMint {
+    candy_machine: String,
+    candy_guard: String,
+    authority: String,
+    collection_mint: String,
+    symbol: String,
+    group: Option<String>,
+    items_available: u64,
+    items_redeemed: u64,
+    items_remaining: u64,
+    /// Lamports charged by `solPayment`
+    sol_price: Option<u64>,
+    /// `tokenPayment` / `token2022Payment`: base units and mint
+    token_price: Option<(u64, String)>,
+    start_date: Option<String>,
+    end_date: Option<String>,
+    /// Reasons the mint would be rejected, as far as can be told up front
+    blockers: Vec<String>,
+    nft_mint: Option<String>,
+    dry_run: bool,
+    signature: Option<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    let minter = args.keypair.load()?;
+
+    let machine_account = fetch(&connection, &args.candy_machine)?;
+    if machine_account.owner != CANDY_MACHINE_PROGRAM_ID {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{} is not a Candy Machine v3 account",
+            args.candy_machine
+        )));
+    }
+    let machine = CandyMachine::deserialize(&mut discriminated(&machine_account.data)?)
+        .map_err(|err| ExampleError::Parse(format!("candy machine: {err}")))?;
+
+    let candy_guard = machine.mint_authority;
+    let guard_account = fetch(&connection, &candy_guard)?;
+    if guard_account.owner != CANDY_GUARD_PROGRAM_ID {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{} is not wrapped by a candy guard; only its authority {candy_guard} can mint",
+            args.candy_machine
+        )));
+    }
+    let guards = guard_set(&guard_account.data, args.group.as_deref())?;
+
+    let remaining = machine
+        .items_available
+        .saturating_sub(machine.items_redeemed);
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() as i64);
+    let mut blockers = Vec::new();
+    if remaining == 0 {
+        blockers.push("sold out".to_string());
+    }
+    if let Some(start) = guards.start_date
+        && now < start
+    {
+        blockers.push(format!("minting opens {}", format_timestamp(start)));
+    }
+    if let Some(end) = guards.end_date
+        && now > end
+    {
+        blockers.push(format!("minting closed {}", format_timestamp(end)));
+    }
+    if let Some(maximum) = guards.redeemed_amount
+        && machine.items_redeemed >= maximum
+    {
+        blockers.push(format!("redeemedAmount limit of {maximum} reached"));
+    }
+    if let Some(address) = guards.address_gate
+        && address != minter.pubkey()
+    {
+        blockers.push(format!("addressGate only lets {address} mint"));
+    }
+    blockers.extend(unsupported_guards(&guards));
+
+    let token_price = guards
+        .token_payment
+        .or(guards.token2022_payment)
+        .map(|(amount, mint, _)| (amount, mint.to_string()));
+    let mut result = CandyMachineMint {
+        candy_machine: args.candy_machine.to_string(),
+        candy_guard: candy_guard.to_string(),
+        authority: machine.authority.to_string(),
+        collection_mint: machine.collection_mint.to_string(),
+        symbol: machine.symbol.clone(),
+        group: args.group.clone(),
+        items_available: machine.items_available,
+        items_redeemed: machine.items_redeemed,
+        items_remaining: remaining,
+        sol_price: guards.sol_payment.map(|(lamports, _)| lamports),
+        token_price,
+        start_date: guards.start_date.map(format_timestamp),
+        end_date: guards.end_date.map(format_timestamp),
+        blockers,
+        nft_mint: None,
+        dry_run: args.dry_run,
+        signature: None,
+    };
+    print_machine(output, &result);
+    if !result.blockers.is_empty() {
+        return output.print(&result, || {
+            for blocker in &result.blockers {
+                println!("Cannot mint: {blocker}");
+            }
+        });
+    }
+
+    let nft_mint = Keypair::new();
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(MINT_UNIT_LIMIT),
+        mint_instruction(
+            &connection,
+            &args,
+            &machine,
+            &candy_guard,
+            &guards,
+            &minter.pubkey(),
+            &nft_mint.pubkey(),
+        )?,
+    ];
+    result.nft_mint = Some(nft_mint.pubkey().to_string());
+    output.status(format_args!("Minting {}", nft_mint.pubkey()));
+
+    let transaction = TxBuilder::new(&minter)
+        .add_instructions(instructions)
+        .add_signer(&nft_mint);
+    if args.dry_run {
+        let transaction = transaction.build(connection.get_latest_blockhash()?)?;
+        let simulation = connection
+            .simulate_transaction_with_config(
+                &transaction,
+                RpcSimulateTransactionConfig {
+                    commitment: Some(connection.commitment()),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )?
+            .value;
+        if output.is_table() {
+            print_simulation(&simulation);
+        }
+        if let Some(err) = simulation.err {
+            return Err(ExampleError::SimulationFailed(err));
+        }
+    } else {
+        let signature = transaction.sign_and_send(&connection)?;
+        output.status(format_args!("Signature: {signature}"));
+        output.status(format_args!(
+            "Explorer:  {}",
+            explorer::transaction_url(&signature, &args.rpc.rpc_url)
+        ));
+        result.signature = Some(signature.to_string());
+    }
+    output.print_structured(&result)
+}
+
+fn fetch(connection: &RpcClient, address: &Pubkey) -> Result<solana_sdk::account::Account> {
+    connection
+        .get_account_with_commitment(address, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(*address))
+}
+
+/// Account data after the Anchor discriminator.
+fn discriminated(data: &[u8]) -> Result<&[u8]> {
+    data.get(DISCRIMINATOR_LEN..)
+        .ok_or_else(|| ExampleError::Parse("account shorter than a discriminator".into()))
+}
+
+/// The guards that apply to a mint: the default set, overridden guard by
+/// guard by the chosen group.
+fn guard_set(data: &[u8], group: Option<&str>) -> Result<GuardSet> {
+    // base, bump and authority precede the guard data.
+    let mut data = discriminated(data)?
+        .get(32 + 1 + 32..)
+        .ok_or_else(|| ExampleError::Parse("candy guard account too short".into()))?;
+    let default = read_guard_set(&mut data)?;
+    let group_count = u32::deserialize(&mut data).unwrap_or(0);
+    let mut labels = Vec::new();
+    for _ in 0..group_count {
+        let label = take(&mut data, LABEL_LEN)?;
+        let label = String::from_utf8_lossy(label)
+            .trim_end_matches('\0')
+            .to_string();
+        let guards = read_guard_set(&mut data)?;
+        if group == Some(label.as_str()) {
+            return Ok(merge(default, guards));
+        }
+        labels.push(label);
+    }
+    match group {
+        None if labels.is_empty() => Ok(default),
+        None => Err(ExampleError::InvalidArgument(format!(
+            "this candy guard mints through groups; pass --group with one of {}",
+            labels.join(", ")
+        ))),
+        Some(group) => Err(ExampleError::InvalidArgument(format!(
+            "no guard group `{group}` (groups: {})",
+            if labels.is_empty() {
+                "none".to_string()
+            } else {
+                labels.join(", ")
+            }
+        ))),
+    }
+}
+
+fn read_guard_set(data: &mut &[u8]) -> Result<GuardSet> {
+    let features =
+        u64::deserialize(data).map_err(|err| ExampleError::Parse(format!("guard flags: {err}")))?;
+    let mut bit = 0;
+    let mut present = || {
+        let set = features & (1 << bit) != 0;
+        bit += 1;
+        set
+    };
+    let mut guards = GuardSet::default();
+    macro_rules! guard {
+        ($field:ident) => {
+            if present() {
+                guards.$field = Some(BorshDeserialize::deserialize(data).map_err(|err| {
+                    ExampleError::Parse(format!("{} guard: {err}", stringify!($field)))
+                })?);
+            }
+        };
+    }
+    guard!(bot_tax);
+    guard!(sol_payment);
+    guard!(token_payment);
+    guard!(start_date);
+    guard!(third_party_signer);
+    guard!(token_gate);
+    guard!(gatekeeper);
+    guard!(end_date);
+    guard!(allow_list);
+    guard!(mint_limit);
+    guard!(nft_payment);
+    guard!(redeemed_amount);
+    guard!(address_gate);
+    guard!(nft_gate);
+    guard!(nft_burn);
+    guard!(token_burn);
+    guard!(freeze_sol_payment);
+    guard!(freeze_token_payment);
+    if present() {
+        // Stored at its maximum size, unlike the other guards.
+        let mut slot = take(data, 4 + PROGRAM_GATE_MAX * 32)?;
+        guards.program_gate = Some(
+            Vec::<Pubkey>::deserialize(&mut slot)
+                .map_err(|err| ExampleError::Parse(format!("program_gate guard: {err}")))?,
+        );
+    }
+    guard!(allocation);
+    guard!(token2022_payment);
+    Ok(guards)
+}
+
+fn take<'a>(data: &mut &'a [u8], len: usize) -> Result<&'a [u8]> {
+    let (head, rest) = data
+        .split_at_checked(len)
+        .ok_or_else(|| ExampleError::Parse("candy guard data ends early".into()))?;
+    *data = rest;
+    Ok(head)
+}
+
+fn merge(default: GuardSet, group: GuardSet) -> GuardSet {
+    GuardSet {
+        bot_tax: group.bot_tax.or(default.bot_tax),
+        sol_payment: group.sol_payment.or(default.sol_payment),
+        token_payment: group.token_payment.or(default.token_payment),
+        start_date: group.start_date.or(default.start_date),
+        third_party_signer: group.third_party_signer.or(default.third_party_signer),
+        token_gate: group.token_gate.or(default.token_gate),
+        gatekeeper: group.gatekeeper.or(default.gatekeeper),
+        end_date: group.end_date.or(default.end_date),
+        allow_list: group.allow_list.or(default.allow_list),
+        mint_limit: group.mint_limit.or(default.mint_limit),
+        nft_payment: group.nft_payment.or(default.nft_payment),
+        redeemed_amount: group.redeemed_amount.or(default.redeemed_amount),
+        address_gate: group.address_gate.or(default.address_gate),
+        nft_gate: group.nft_gate.or(default.nft_gate),
+        nft_burn: group.nft_burn.or(default.nft_burn),
+        token_burn: group.token_burn.or(default.token_burn),
+        freeze_sol_payment: group.freeze_sol_payment.or(default.freeze_sol_payment),
+        freeze_token_payment: group.freeze_token_payment.or(default.freeze_token_payment),
+        program_gate: group.program_gate.or(default.program_gate),
+        allocation: group.allocation.or(default.allocation),
+        token2022_payment: group.token2022_payment.or(default.token2022_payment),
+    }
+}
+
+/// Active guards whose accounts or mint arguments depend on something this
+/// example can't produce.
+fn unsupported_guards(guards: &GuardSet) -> Vec<String> {
+    [
+        (guards.third_party_signer.is_some(), "thirdPartySigner"),
+        (guards.gatekeeper.is_some(), "gatekeeper"),
+        (guards.allow_list.is_some(), "allowList"),
+        (guards.nft_payment.is_some(), "nftPayment"),
+        (guards.nft_gate.is_some(), "nftGate"),
+        (guards.nft_burn.is_some(), "nftBurn"),
+        (guards.freeze_sol_payment.is_some(), "freezeSolPayment"),
+        (guards.freeze_token_payment.is_some(), "freezeTokenPayment"),
+    ]
+    .into_iter()
+    .filter(|(active, _)| *active)
+    .map(|(_, name)| format!("the {name} guard is not supported by this example"))
+    .collect()
+}
+
+fn mint_instruction(
+    connection: &RpcClient,
+    args: &Args,
+    machine: &CandyMachine,
+    candy_guard: &Pubkey,
+    guards: &GuardSet,
+    minter: &Pubkey,
+    nft_mint: &Pubkey,
+) -> Result<Instruction> {
+    let (authority_pda, _) = Pubkey::find_program_address(
+        &[b"candy_machine", args.candy_machine.as_ref()],
+        &CANDY_MACHINE_PROGRAM_ID,
+    );
+    let collection = &machine.collection_mint;
+    let collection_update_authority =
+        metaplex::fetch_metadata(connection, collection)?.update_authority;
+    // The candy machine's authority PDA is the collection's delegate, which
+    // is what lets it verify every minted NFT into the collection.
+    let (collection_delegate_record, _) = Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            collection.as_ref(),
+            b"collection_delegate",
+            collection_update_authority.as_ref(),
+            authority_pda.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    );
+    let token = get_associated_token_address_with_program_id(minter, nft_mint, &spl_token::id());
+    // Anchor encodes an absent optional account as the called program's ID.
+    let none = CANDY_GUARD_PROGRAM_ID;
+    let token_record = if machine.token_standard == PROGRAMMABLE_NON_FUNGIBLE {
+        Pubkey::find_program_address(
+            &[
+                b"metadata",
+                TOKEN_METADATA_PROGRAM_ID.as_ref(),
+                nft_mint.as_ref(),
+                b"token_record",
+                token.as_ref(),
+            ],
+            &TOKEN_METADATA_PROGRAM_ID,
+        )
+        .0
+    } else {
+        none
+    };
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*candy_guard, false),
+        AccountMeta::new_readonly(CANDY_MACHINE_PROGRAM_ID, false),
+        AccountMeta::new(args.candy_machine, false),
+        AccountMeta::new(authority_pda, false),
+        AccountMeta::new(*minter, true),
+        AccountMeta::new(*minter, true),
+        AccountMeta::new(*nft_mint, true),
+        AccountMeta::new_readonly(*minter, true),
+        AccountMeta::new(metaplex::metadata_address(nft_mint), false),
+        AccountMeta::new(edition_address(nft_mint), false),
+        AccountMeta::new(token, false),
+        AccountMeta::new(token_record, false),
+        AccountMeta::new_readonly(collection_delegate_record, false),
+        AccountMeta::new_readonly(*collection, false),
+        AccountMeta::new(metaplex::metadata_address(collection), false),
+        AccountMeta::new_readonly(edition_address(collection), false),
+        AccountMeta::new_readonly(collection_update_authority, false),
+        AccountMeta::new_readonly(TOKEN_METADATA_PROGRAM_ID, false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(spl_associated_token_account_client::program::id(), false),
+        AccountMeta::new_readonly(solana_sdk::system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        AccountMeta::new_readonly(sysvar::slot_hashes::id(), false),
+        AccountMeta::new_readonly(none, false),
+        AccountMeta::new_readonly(none, false),
+    ];
+    accounts.extend(guard_accounts(
+        guards,
+        candy_guard,
+        &args.candy_machine,
+        minter,
+    ));
+
+    // mint_args (no guard used here takes any) and the group label.
+    let mut data = anchor::instruction_discriminator("mint_v2").to_vec();
+    data.extend(0u32.to_le_bytes());
+    match &args.group {
+        Some(group) => {
+            data.push(1);
+            data.extend((group.len() as u32).to_le_bytes());
+            data.extend(group.as_bytes());
+        }
+        None => data.push(0),
+    }
+    Ok(Instruction {
+        program_id: CANDY_GUARD_PROGRAM_ID,
+        accounts,
+        data,
+    })
+}
+
+/// The remaining accounts the active guards read, in guard order, which is
+/// also the order they consume them in.
+fn guard_accounts(
+    guards: &GuardSet,
+    candy_guard: &Pubkey,
+    candy_machine: &Pubkey,
+    minter: &Pubkey,
+) -> Vec<AccountMeta> {
+    let token_2022 = spl_token_2022::id();
+    let ata = |mint: &Pubkey, program: &Pubkey| {
+        get_associated_token_address_with_program_id(minter, mint, program)
+    };
+    let mut accounts = Vec::new();
+    if let Some((_, destination)) = guards.sol_payment {
+        accounts.push(AccountMeta::new(destination, false));
+    }
+    if let Some((_, mint, destination)) = guards.token_payment {
+        accounts.push(AccountMeta::new(ata(&mint, &spl_token::id()), false));
+        accounts.push(AccountMeta::new(destination, false));
+    }
+    if let Some((_, mint)) = guards.token_gate {
+        accounts.push(AccountMeta::new_readonly(
+            ata(&mint, &spl_token::id()),
+            false,
+        ));
+    }
+    if let Some((id, _)) = guards.mint_limit {
+        let (counter, _) = Pubkey::find_program_address(
+            &[
+                b"mint_limit",
+                &[id],
+                minter.as_ref(),
+                candy_guard.as_ref(),
+                candy_machine.as_ref(),
+            ],
+            &CANDY_GUARD_PROGRAM_ID,
+        );
+        accounts.push(AccountMeta::new(counter, false));
+    }
+    if let Some((_, mint)) = guards.token_burn {
+        accounts.push(AccountMeta::new(ata(&mint, &spl_token::id()), false));
+        accounts.push(AccountMeta::new(mint, false));
+    }
+    if let Some((id, _)) = guards.allocation {
+        let (tracker, _) = Pubkey::find_program_address(
+            &[
+                b"allocation",
+                &[id],
+                candy_guard.as_ref(),
+                candy_machine.as_ref(),
+            ],
+            &CANDY_GUARD_PROGRAM_ID,
+        );
+        accounts.push(AccountMeta::new(tracker, false));
+    }
+    if let Some((_, mint, destination)) = guards.token2022_payment {
+        accounts.push(AccountMeta::new(ata(&mint, &token_2022), false));
+        accounts.push(AccountMeta::new_readonly(mint, false));
+        accounts.push(AccountMeta::new(destination, false));
+        accounts.push(AccountMeta::new_readonly(token_2022, false));
+    }
+    accounts
+}
+
+/// Master edition PDA: `["metadata", program_id, mint, "edition"]`.
+fn edition_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+fn print_machine(output: &OutputArgs, machine: &CandyMachineMint) {
+    output.status(format_args!(
+        "Candy machine {} ({}), guarded by {}",
+        machine.candy_machine, machine.symbol, machine.candy_guard
+    ));
+    output.status(format_args!(
+        "Items: {} of {} minted, {} left",
+        machine.items_redeemed, machine.items_available, machine.items_remaining
+    ));
+    match (&machine.sol_price, &machine.token_price) {
+        (None, None) => output.status("Price: free (plus rent and fees)"),
+        (sol, token) => {
+            if let Some(lamports) = sol {
+                output.status(format_args!("Price: {} SOL", format_sol(*lamports)));
+            }
+            if let Some((amount, mint)) = token {
+                output.status(format_args!("Price: {amount} base units of {mint}"));
+            }
+        }
+    }
+    if let Some(start) = &machine.start_date {
+        output.status(format_args!("Opens:  {start}"));
+    }
+    if let Some(end) = &machine.end_date {
+        output.status(format_args!("Closes: {end}"));
+    }
+}
@@ -0,0 +1,339 @@
+use clap::Parser;
+use common::blockhash::BlockhashCache;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::das;
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::format_sol;
+use common::{ExampleError, Result, explorer};
+use mpl_bubblegum::accounts::TreeConfig;
+use mpl_bubblegum::hash::{hash_creators, hash_metadata};
+use mpl_bubblegum::instructions::{CreateTreeConfigBuilder, MintV1Builder};
+use mpl_bubblegum::types::{Creator, LeafSchema, MetadataArgs, TokenProgramVersion};
+use mpl_bubblegum::utils::get_asset_id;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::keccak;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+/// Mint compressed NFTs with Bubblegum: allocate a concurrent Merkle tree
+/// account and register it with `create_tree_config` (unless --tree names an
+/// existing one), mint leaves into it with `mint_v1`, then fetch each new
+/// asset's proof with the DAS `getAssetProof` method and check it locally: the
+/// leaf must hash to what was minted and the proof must hash up to the root.
+///
+/// DAS is served by a QuickNode add-on; without it pass --skip-verify.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer, which also becomes the tree creator
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Existing tree to mint into; a new one is created when omitted
+    #[arg(short, long)]
+    tree: Option<Pubkey>,
+
+    /// Depth of a new tree; it holds 2^depth leaves
+    #[arg(long, default_value_t = 14)]
+    max_depth: u32,
+
+    /// Changes a new tree can take concurrently within one slot
+    #[arg(long, default_value_t = 64)]
+    max_buffer_size: u32,
+
+    /// Upper tree levels cached on chain, shortening the proofs later
+    /// instructions have to pass
+    #[arg(long, default_value_t = 0)]
+    canopy_depth: u32,
+
+    /// Number of cNFTs to mint
+    #[arg(short = 'n', long, default_value_t = 1)]
+    count: u32,
+
+    /// Name of the minted cNFTs, at most 32 bytes
+    #[arg(long)]
+    name: String,
+
+    /// Symbol of the minted cNFTs
+    #[arg(long, default_value = "")]
+    symbol: String,
+
+    /// URI of the off-chain metadata JSON
+    #[arg(short, long)]
+    uri: String,
+
+    /// Wallet that receives the cNFTs; defaults to the payer
+    #[arg(long)]
+    owner: Option<Pubkey>,
+
+    /// Don't check the minted leaves against getAssetProof
+    #[arg(long)]
+    skip_verify: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct CompressedMint {
+    tree: String,
+    /// Set when the tree was created by this run
+    tree_signature: Option<String>,
+    tree_rent: Option<u64>,
+    leaves: Vec<MintedLeaf>,
+}
+
+#[derive(Serialize)]
+struct MintedLeaf {
+    asset_id: String,
+    leaf_index: u64,
+    signature: String,
+    /// Root the DAS proof leads to, once verified
+    root: Option<String>,
+    verified: Option<bool>,
+}
+
+const ACCOUNT_COMPRESSION_PROGRAM_ID: Pubkey =
+    pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// getAssetProof attempts while waiting for DAS to index a new leaf.
+const PROOF_ATTEMPTS: u32 = 15;
+const PROOF_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let owner = args.owner.unwrap_or(payer.pubkey());
+    let cache = BlockhashCache::default();
+
+    let mut result = CompressedMint {
+        tree: String::new(),
+        tree_signature: None,
+        tree_rent: None,
+        leaves: Vec::new(),
+    };
+    let tree = match args.tree {
+        Some(tree) => tree,
+        None => {
+            let tree = Keypair::new();
+            let size = tree_account_size(args.max_depth, args.max_buffer_size, args.canopy_depth);
+            let rent = connection.get_minimum_balance_for_rent_exemption(size)?;
+            output.status(format_args!(
+                "Creating tree {} (depth {}, buffer {}, canopy {}): {size} bytes, {} SOL rent",
+                tree.pubkey(),
+                args.max_depth,
+                args.max_buffer_size,
+                args.canopy_depth,
+                format_sol(rent)
+            ));
+            let instructions = [
+                system_instruction::create_account(
+                    &payer.pubkey(),
+                    &tree.pubkey(),
+                    rent,
+                    size as u64,
+                    &ACCOUNT_COMPRESSION_PROGRAM_ID,
+                ),
+                CreateTreeConfigBuilder::new()
+                    .tree_config(TreeConfig::find_pda(&tree.pubkey()).0)
+                    .merkle_tree(tree.pubkey())
+                    .payer(payer.pubkey())
+                    .tree_creator(payer.pubkey())
+                    .max_depth(args.max_depth)
+                    .max_buffer_size(args.max_buffer_size)
+                    .public(false)
+                    .instruction(),
+            ];
+            let signature = TxBuilder::new(&payer)
+                .add_instructions(instructions)
+                .add_signer(&tree)
+                .with_blockhash_cache(&cache)
+                .sign_and_send(&connection)?;
+            print_signature(output, &signature, &args.rpc.rpc_url);
+            result.tree_signature = Some(signature.to_string());
+            result.tree_rent = Some(rent);
+            tree.pubkey()
+        }
+    };
+    result.tree = tree.to_string();
+
+    let tree_config = TreeConfig::find_pda(&tree).0;
+    let metadata = MetadataArgs {
+        name: args.name.clone(),
+        symbol: args.symbol.clone(),
+        uri: args.uri.clone(),
+        seller_fee_basis_points: 0,
+        primary_sale_happened: false,
+        is_mutable: true,
+        edition_nonce: None,
+        token_standard: None,
+        collection: None,
+        uses: None,
+        token_program_version: TokenProgramVersion::Original,
+        // Unverified: marking the payer verified would make mint_v1 require
+        // it as an extra signer account.
+        creators: vec![Creator {
+            address: payer.pubkey(),
+            verified: false,
+            share: 100,
+        }],
+    };
+
+    for _ in 0..args.count {
+        // The next leaf's index is the tree's mint count; nobody else can
+        // mint into a private tree, so it can be read up front.
+        let leaf_index = fetch_tree_config(&connection, &tree_config)?.num_minted;
+        let instruction = MintV1Builder::new()
+            .tree_config(tree_config)
+            .leaf_owner(owner)
+            .leaf_delegate(owner)
+            .merkle_tree(tree)
+            .payer(payer.pubkey())
+            .tree_creator_or_delegate(payer.pubkey())
+            .metadata(metadata.clone())
+            .instruction();
+        let signature = TxBuilder::new(&payer)
+            .add_instruction(instruction)
+            .with_blockhash_cache(&cache)
+            .sign_and_send(&connection)?;
+        let asset_id = get_asset_id(&tree, leaf_index);
+        output.status(format_args!("Minted leaf {leaf_index}: asset {asset_id}"));
+        print_signature(output, &signature, &args.rpc.rpc_url);
+        result.leaves.push(MintedLeaf {
+            asset_id: asset_id.to_string(),
+            leaf_index,
+            signature: signature.to_string(),
+            root: None,
+            verified: None,
+        });
+    }
+
+    if !args.skip_verify {
+        let expected_leaf = |leaf_index: u64| -> Result<[u8; 32]> {
+            let schema = LeafSchema::V1 {
+                id: get_asset_id(&tree, leaf_index),
+                owner,
+                delegate: owner,
+                nonce: leaf_index,
+                data_hash: hash_metadata(&metadata)
+                    .map_err(|err| ExampleError::Parse(format!("metadata hash: {err}")))?,
+                creator_hash: hash_creators(&metadata.creators),
+            };
+            Ok(schema.hash())
+        };
+        for leaf in &mut result.leaves {
+            let asset_id: Pubkey = leaf.asset_id.parse()?;
+            let proof = wait_for_proof(&connection, &asset_id)?;
+            let verified = verify_proof(&proof, leaf.leaf_index, &expected_leaf(leaf.leaf_index)?)?;
+            output.status(format_args!(
+                "Leaf {}: proof of {} nodes to root {} {}",
+                leaf.leaf_index,
+                proof.proof.len(),
+                proof.root,
+                if verified {
+                    "verified"
+                } else {
+                    "DOES NOT MATCH"
+                }
+            ));
+            leaf.root = Some(proof.root);
+            leaf.verified = Some(verified);
+        }
+    }
+
+    output.print_structured(&result)
+}
+
+fn fetch_tree_config(connection: &RpcClient, address: &Pubkey) -> Result<TreeConfig> {
+    let account = connection
+        .get_account_with_commitment(address, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(*address))?;
+    TreeConfig::from_bytes(&account.data)
+        .map_err(|err| ExampleError::Parse(format!("tree config {address}: {err}")))
+}
+
+/// Size of an SPL account compression tree account: header, the tree with
+/// its change log buffer and rightmost path, and the canopy.
+fn tree_account_size(max_depth: u32, max_buffer_size: u32, canopy_depth: u32) -> usize {
+    const HEADER: usize = 56;
+    let depth = max_depth as usize;
+    // Root, path and index of one change, padded to 8 bytes.
+    let change_log = 32 + 32 * depth + 8;
+    // Proof, leaf and index of the rightmost leaf, padded likewise.
+    let rightmost_path = 32 * depth + 32 + 8;
+    // Sequence number, active index and buffer size.
+    let tree = 24 + max_buffer_size as usize * change_log + rightmost_path;
+    let canopy = ((1usize << (canopy_depth + 1)) - 2) * 32;
+    HEADER + tree + canopy
+}
+
+/// DAS indexes new leaves a few seconds after they land.
+fn wait_for_proof(connection: &RpcClient, asset_id: &Pubkey) -> Result<das::AssetProof> {
+    let mut attempt = 1;
+    loop {
+        match das::get_asset_proof(connection, asset_id) {
+            Ok(proof) => return Ok(proof),
+            Err(_) if attempt < PROOF_ATTEMPTS => {
+                thread::sleep(PROOF_RETRY_DELAY);
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Checks that the proof's leaf is the one that was minted and that hashing
+/// it with the proof nodes, bottom up, reproduces the proof's root.
+fn verify_proof(
+    proof: &das::AssetProof,
+    leaf_index: u64,
+    expected_leaf: &[u8; 32],
+) -> Result<bool> {
+    let decode = |node: &str| -> Result<[u8; 32]> {
+        bs58::decode(node)
+            .into_vec()
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| ExampleError::Parse(format!("proof node {node}")))
+    };
+    let leaf = decode(&proof.leaf)?;
+    if leaf != *expected_leaf {
+        return Ok(false);
+    }
+    let mut node = leaf;
+    for (level, sibling) in proof.proof.iter().enumerate() {
+        let sibling = decode(sibling)?;
+        // The index bit at each level says whether the node is a left or a
+        // right child.
+        node = if (leaf_index >> level) & 1 == 0 {
+            keccak::hashv(&[&node, &sibling]).to_bytes()
+        } else {
+            keccak::hashv(&[&sibling, &node]).to_bytes()
+        };
+    }
+    Ok(node == decode(&proof.root)?)
+}
+
+fn print_signature(output: &OutputArgs, signature: &Signature, rpc_url: &str) {
+    output.status(format_args!("  Signature: {signature}"));
+    output.status(format_args!(
+        "  Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
@@ -0,0 +1,178 @@
+use clap::{Parser, Subcommand};
+use common::Result;
+use common::cli::{AddressArg, RpcArgs};
+use common::das::{self, Asset};
+use common::output::OutputArgs;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+
+/// Query the Digital Asset Standard API: look up single assets (compressed or
+/// not), list a wallet's assets and fetch Merkle proofs for compressed NFTs.
+/// Requires an endpoint with DAS enabled.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show a single asset (getAsset)
+    Asset {
+        /// Asset id: the mint, or the derived asset id for compressed NFTs
+        #[arg(short, long)]
+        id: Pubkey,
+    },
+    /// List the assets held by a wallet (getAssetsByOwner)
+    Owner {
+        /// Wallet: an address (base58), a `.sol` domain or a keypair file
+        #[arg(short, long)]
+        owner: AddressArg,
+
+        /// 1-based page number
+        #[arg(short, long, default_value_t = 1)]
+        page: u32,
+
+        /// Assets per page, at most 1000
+        #[arg(short, long, default_value_t = 50)]
+        limit: u32,
+    },
+    /// Fetch the Merkle proof of a compressed asset (getAssetProof)
+    Proof {
+        #[arg(short, long)]
+        id: Pubkey,
+    },
+}
+
+#[derive(Serialize)]
+struct AssetRow<'a> {
+    id: &'a str,
+    interface: &'a str,
+    compressed: bool,
+    name: &'a str,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    match args.command {
+        Command::Asset { id } => {
+            let asset = das::get_asset(&connection, &id)?;
+            output.print(&asset, || print_asset(&asset))?;
+        }
+        Command::Owner { owner, page, limit } => {
+            let owner = owner.resolve(&connection)?;
+            let list = das::get_assets_by_owner(&connection, &owner, page, limit)?;
+            output.status(format_args!(
+                "Page {} ({} of {} assets)",
+                list.page.unwrap_or(page),
+                list.items.len(),
+                list.total
+            ));
+            let rows: Vec<AssetRow> = list
+                .items
+                .iter()
+                .map(|asset| AssetRow {
+                    id: &asset.id,
+                    interface: &asset.interface,
+                    compressed: asset.is_compressed(),
+                    name: asset.name().unwrap_or(""),
+                })
+                .collect();
+            output.print(&rows, || {
+                println!("{:<44}  {:<10}  {:<5}  Name", "Id", "Interface", "cNFT");
+                for row in &rows {
+                    println!(
+                        "{:<44}  {:<10}  {:<5}  {}",
+                        row.id, row.interface, row.compressed, row.name
+                    );
+                }
+            })?;
+        }
+        Command::Proof { id } => {
+            let proof = das::get_asset_proof(&connection, &id)?;
+            output.print(&proof, || {
+                println!("Tree:       {}", proof.tree_id);
+                println!("Root:       {}", proof.root);
+                println!("Leaf:       {}", proof.leaf);
+                println!("Node index: {}", proof.node_index);
+                println!("Proof ({} nodes):", proof.proof.len());
+                for node in &proof.proof {
+                    println!("  {node}");
+                }
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn print_asset(asset: &Asset) {
+    println!("Id:         {}", asset.id);
+    println!("Interface:  {}", asset.interface);
+    println!("Name:       {}", asset.name().unwrap_or(""));
+    if let Some(content) = &asset.content {
+        if let Some(uri) = &content.json_uri {
+            println!("JSON URI:   {uri}");
+        }
+        if let Some(image) = &content.links.image {
+            println!("Image:      {image}");
+        }
+    }
+    println!("Owner:      {}", asset.ownership.owner);
+    if let Some(delegate) = &asset.ownership.delegate {
+        println!("Delegate:   {delegate}");
+    }
+    println!("Frozen:     {}", asset.ownership.frozen);
+    println!("Mutable:    {}", asset.mutable);
+    println!("Burnt:      {}", asset.burnt);
+    if let Some(royalty) = &asset.royalty {
+        println!(
+            "Royalties:  {}.{:02}%",
+            royalty.basis_points / 100,
+            royalty.basis_points % 100
+        );
+    }
+    for group in &asset.grouping {
+        println!(
+            "Group:      {} = {}",
+            group.group_key,
+            group.group_value.as_deref().unwrap_or("")
+        );
+    }
+    for creator in &asset.creators {
+        println!(
+            "Creator:    {} {:>3}% {}",
+            creator.address,
+            creator.share,
+            if creator.verified {
+                "verified"
+            } else {
+                "unverified"
+            }
+        );
+    }
+    match &asset.compression {
+        Some(compression) if compression.compressed => {
+            println!("Compressed: tree {}", compression.tree);
+            println!(
+                "            leaf {} (seq {})",
+                compression.leaf_id, compression.seq
+            );
+            println!("            data hash {}", compression.data_hash);
+            println!("            creator hash {}", compression.creator_hash);
+        }
+        _ => println!("Compressed: false"),
+    }
+}
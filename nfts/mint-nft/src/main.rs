@@ -0,0 +1,233 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::{ExampleError, Result, explorer};
+use mpl_token_metadata::accounts::{MasterEdition, Metadata};
+use mpl_token_metadata::instructions::{
+    CreateMasterEditionV3Builder, CreateMetadataAccountV3Builder,
+};
+use mpl_token_metadata::types::{Creator, DataV2};
+use serde::{Deserialize, Serialize};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_client::address::get_associated_token_address;
+use spl_associated_token_account_client::instruction::create_associated_token_account_idempotent;
+use spl_token::state::Mint;
+use std::process::ExitCode;
+
+/// Mint a 1/1 NFT in one transaction: a new 0-decimal mint, one token in the
+/// payer's associated token account, then the Metaplex metadata account and
+/// a master edition with a max supply of 0, which moves the mint and freeze
+/// authority to the edition so no second token can ever be minted.
+///
+/// The metadata only stores the URI; host the off-chain JSON (name, image,
+/// attributes) there first. It is downloaded before minting to check it is
+/// reachable and to fill in the name and symbol when they are not given.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer, which also becomes the update authority and sole creator
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// URI of the off-chain metadata JSON
+    #[arg(short, long)]
+    uri: String,
+
+    /// On-chain name, at most 32 bytes; defaults to the JSON's `name`
+    #[arg(short, long)]
+    name: Option<String>,
+
+    /// On-chain symbol, at most 10 bytes; defaults to the JSON's `symbol`
+    #[arg(short, long)]
+    symbol: Option<String>,
+
+    /// Royalties in basis points (500 = 5%)
+    #[arg(long, default_value_t = 0)]
+    seller_fee_basis_points: u16,
+
+    /// Make the metadata immutable; it can never be updated afterwards
+    #[arg(long)]
+    immutable: bool,
+
+    /// Mint without downloading the URI first
+    #[arg(long)]
+    skip_uri_check: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// The fields of the off-chain JSON standard the on-chain metadata repeats.
+#[derive(Deserialize)]
+struct OffChainMetadata {
+    name: Option<String>,
+    symbol: Option<String>,
+}
+
+#[derive(Serialize)]
+struct MintedNft {
+    mint: String,
+    token_account: String,
+    metadata_account: String,
+    master_edition: String,
+    name: String,
+    symbol: String,
+    uri: String,
+    signature: String,
+}
+
+/// Limits the Token Metadata program enforces on the on-chain strings.
+const MAX_NAME_LEN: usize = 32;
+const MAX_SYMBOL_LEN: usize = 10;
+const MAX_URI_LEN: usize = 200;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    let off_chain = if args.skip_uri_check {
+        None
+    } else {
+        output.status(format_args!("Checking {}", args.uri));
+        let off_chain: OffChainMetadata = reqwest::blocking::get(&args.uri)?
+            .error_for_status()?
+            .json()?;
+        Some(off_chain)
+    };
+    let from_json = |field: fn(&OffChainMetadata) -> &Option<String>| {
+        off_chain.as_ref().and_then(|json| field(json).clone())
+    };
+    let name = args
+        .name
+        .clone()
+        .or_else(|| from_json(|json| &json.name))
+        .ok_or_else(|| {
+            ExampleError::InvalidArgument("no --name given and the JSON has none".to_string())
+        })?;
+    let symbol = args
+        .symbol
+        .clone()
+        .or_else(|| from_json(|json| &json.symbol))
+        .unwrap_or_default();
+    for (field, value, max) in [
+        ("name", &name, MAX_NAME_LEN),
+        ("symbol", &symbol, MAX_SYMBOL_LEN),
+        ("uri", &args.uri, MAX_URI_LEN),
+    ] {
+        if value.len() > max {
+            return Err(ExampleError::InvalidArgument(format!(
+                "{field} is {} bytes, the limit is {max}",
+                value.len()
+            )));
+        }
+    }
+
+    let mint = Keypair::new();
+    let token_account = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let (metadata_account, _) = Metadata::find_pda(&mint.pubkey());
+    let (master_edition, _) = MasterEdition::find_pda(&mint.pubkey());
+    let rent = connection.get_minimum_balance_for_rent_exemption(Mint::LEN)?;
+    output.status(format_args!("Minting {name} as {}", mint.pubkey()));
+
+    let instructions = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        // The master edition requires both a mint and a freeze authority,
+        // and takes both over.
+        spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &payer.pubkey(),
+            Some(&payer.pubkey()),
+            0,
+        )?,
+        create_associated_token_account_idempotent(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &mint.pubkey(),
+            &spl_token::id(),
+        ),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &token_account,
+            &payer.pubkey(),
+            &[],
+            1,
+        )?,
+        CreateMetadataAccountV3Builder::new()
+            .metadata(metadata_account)
+            .mint(mint.pubkey())
+            .mint_authority(payer.pubkey())
+            .payer(payer.pubkey())
+            .update_authority(payer.pubkey(), true)
+            .data(DataV2 {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: args.uri.clone(),
+                seller_fee_basis_points: args.seller_fee_basis_points,
+                // Signing as the update authority lets the payer be listed
+                // as a verified creator right away.
+                creators: Some(vec![Creator {
+                    address: payer.pubkey(),
+                    verified: true,
+                    share: 100,
+                }]),
+                collection: None,
+                uses: None,
+            })
+            .is_mutable(!args.immutable)
+            .instruction(),
+        CreateMasterEditionV3Builder::new()
+            .edition(master_edition)
+            .mint(mint.pubkey())
+            .update_authority(payer.pubkey())
+            .mint_authority(payer.pubkey())
+            .payer(payer.pubkey())
+            .metadata(metadata_account)
+            .max_supply(0)
+            .instruction(),
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(instructions)
+        .add_signer(&mint)
+        .sign_and_send(&connection)?;
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+
+    let minted = MintedNft {
+        mint: mint.pubkey().to_string(),
+        token_account: token_account.to_string(),
+        metadata_account: metadata_account.to_string(),
+        master_edition: master_edition.to_string(),
+        name,
+        symbol,
+        uri: args.uri.clone(),
+        signature: signature.to_string(),
+    };
+    output.print(&minted, || {
+        println!("Mint:           {}", minted.mint);
+        println!("Token account:  {}", minted.token_account);
+        println!("Metadata:       {}", minted.metadata_account);
+        println!("Master edition: {}", minted.master_edition);
+    })
+}
@@ -0,0 +1,166 @@
+use clap::Parser;
+use common::Result;
+use common::cli::RpcArgs;
+use common::metaplex;
+use common::output::OutputArgs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+
+/// Fetch and decode the Metaplex metadata of an NFT (or any token with
+/// metadata), optionally resolving its off-chain JSON.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Mint address of the NFT
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    /// Also download the JSON document the metadata URI points to
+    #[arg(short, long)]
+    fetch_json: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// The commonly used subset of the Metaplex off-chain JSON standard.
+#[derive(Deserialize, Serialize)]
+struct OffChainMetadata {
+    description: Option<String>,
+    image: Option<String>,
+    #[serde(default)]
+    attributes: Vec<Attribute>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct Attribute {
+    trait_type: Option<String>,
+    value: Value,
+}
+
+#[derive(Serialize)]
+struct NftMetadata {
+    metadata_account: String,
+    name: String,
+    symbol: String,
+    uri: String,
+    update_authority: String,
+    seller_fee_basis_points: u16,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    creators: Vec<CreatorRow>,
+    /// Present with --fetch-json
+    off_chain: Option<OffChainMetadata>,
+}
+
+#[derive(Serialize)]
+struct CreatorRow {
+    address: String,
+    share: u8,
+    verified: bool,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let metadata_account = metaplex::metadata_address(&args.mint);
+    let metadata = metaplex::fetch_metadata(&connection, &args.mint)?;
+
+    let off_chain = if args.fetch_json && !metadata.uri.is_empty() {
+        let off_chain: OffChainMetadata = reqwest::blocking::get(&metadata.uri)?
+            .error_for_status()?
+            .json()?;
+        Some(off_chain)
+    } else {
+        None
+    };
+    let nft = NftMetadata {
+        metadata_account: metadata_account.to_string(),
+        creators: metadata
+            .creators
+            .iter()
+            .flatten()
+            .map(|creator| CreatorRow {
+                address: creator.address.to_string(),
+                share: creator.share,
+                verified: creator.verified,
+            })
+            .collect(),
+        name: metadata.name,
+        symbol: metadata.symbol,
+        uri: metadata.uri,
+        update_authority: metadata.update_authority.to_string(),
+        seller_fee_basis_points: metadata.seller_fee_basis_points,
+        primary_sale_happened: metadata.primary_sale_happened,
+        is_mutable: metadata.is_mutable,
+        off_chain,
+    };
+    args.output.print(&nft, || {
+        println!("Metadata account: {}", nft.metadata_account);
+        print_metadata(&nft);
+        if let Some(off_chain) = &nft.off_chain {
+            print_off_chain(off_chain);
+        }
+    })
+}
+
+fn print_metadata(metadata: &NftMetadata) {
+    println!("Name:             {}", metadata.name);
+    println!("Symbol:           {}", metadata.symbol);
+    println!("URI:              {}", metadata.uri);
+    println!("Update authority: {}", metadata.update_authority);
+    println!(
+        "Royalties:        {}.{:02}%",
+        metadata.seller_fee_basis_points / 100,
+        metadata.seller_fee_basis_points % 100
+    );
+    println!("Primary sale:     {}", metadata.primary_sale_happened);
+    println!("Mutable:          {}", metadata.is_mutable);
+    if metadata.creators.is_empty() {
+        println!("Creators:         none");
+        return;
+    }
+    println!("Creators:");
+    for creator in &metadata.creators {
+        println!(
+            "  {} {:>3}% {}",
+            creator.address,
+            creator.share,
+            if creator.verified {
+                "verified"
+            } else {
+                "unverified"
+            }
+        );
+    }
+}
+
+fn print_off_chain(off_chain: &OffChainMetadata) {
+    if let Some(description) = &off_chain.description {
+        println!("Description:      {description}");
+    }
+    if let Some(image) = &off_chain.image {
+        println!("Image:            {image}");
+    }
+    if !off_chain.attributes.is_empty() {
+        println!("Attributes:");
+        for attribute in &off_chain.attributes {
+            let value = match &attribute.value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            println!(
+                "  {}: {value}",
+                attribute.trait_type.as_deref().unwrap_or("?")
+            );
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use clap::Parser;
+use common::Result;
+use common::cli::{AddressArg, RpcArgs};
+use common::output::OutputArgs;
+use common::quicknode_ext::{self, MAX_PAGE_SIZE};
+use std::process::ExitCode;
+
+/// List the NFTs a wallet holds, with their collections and traits, through
+/// QuickNode's Token and NFT API (`qn_fetchNFTs`). Requires an endpoint with
+/// the add-on; for compressed NFTs use the DAS API (see `das-api`).
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet: an address (base58), a `.sol` domain or a keypair file
+    wallet: AddressArg,
+
+    /// 1-based page number
+    #[arg(short, long, default_value_t = 1)]
+    page: u32,
+
+    /// NFTs per page
+    #[arg(short, long, default_value_t = 20, value_parser = clap::value_parser!(u32).range(1..=i64::from(MAX_PAGE_SIZE)))]
+    limit: u32,
+
+    /// Also print each NFT's traits
+    #[arg(long)]
+    traits: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let wallet = args.wallet.resolve(&connection)?;
+    let page = quicknode_ext::fetch_nfts(&connection, &wallet, args.page, args.limit)?;
+    args.output.status(format_args!(
+        "Page {} of {} ({} NFTs in all)",
+        page.page_number, page.total_pages, page.total_items
+    ));
+    args.output.print(&page, || {
+        println!("{:<44}  {:<24}  Name", "Mint", "Collection");
+        for nft in &page.assets {
+            println!(
+                "{:<44}  {:<24}  {}",
+                nft.token_address,
+                nft.collection_name.as_deref().unwrap_or(""),
+                nft.name.as_deref().unwrap_or("")
+            );
+            if args.traits {
+                for attribute in &nft.traits {
+                    println!("    {}: {}", attribute.trait_type, attribute.value);
+                }
+            }
+        }
+    })
+}
@@ -0,0 +1,154 @@
+use clap::Parser;
+use common::anchor::{Idl, IdlAccount};
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::output::RecordWriter;
+use common::{ExampleError, Result, RetryingRpcClient};
+use serde_json::json;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::pubkey::Pubkey;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Decode an Anchor program's accounts into JSON using only its IDL: the
+/// account type is found by discriminator and the fields are read according
+/// to the IDL's type definitions. Prints one JSON object per line, or CSV
+/// rows with the fields embedded as JSON.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// IDL JSON file, as written to target/idl/ by `anchor build`
+    #[arg(short, long)]
+    idl: PathBuf,
+
+    /// Program ID; defaults to the address recorded in the IDL
+    #[arg(short, long)]
+    program: Option<Pubkey>,
+
+    /// Only fetch accounts of this type (the IDL account name)
+    #[arg(short, long)]
+    account: Option<String>,
+
+    /// Decode this single account instead of scanning the program
+    #[arg(long, conflicts_with = "account")]
+    address: Option<Pubkey>,
+
+    /// Stop after this many accounts
+    #[arg(short, long)]
+    limit: Option<usize>,
+
+    /// Pretty-print each JSON object
+    #[arg(long)]
+    pretty: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let idl = Idl::from_file(&args.idl)?;
+    let connection = args.rpc.retrying_client();
+
+    let mut records = args.output.records();
+
+    if let Some(address) = args.address {
+        let account = connection.call(|client| Ok(client.get_account(&address)?))?;
+        return print_account(&mut records, &idl, &address, &account.data, args.pretty);
+    }
+
+    let program = match (args.program, idl.program_id()) {
+        (Some(program), _) => program,
+        (None, Some(program)) => program?,
+        (None, None) => {
+            return Err(ExampleError::InvalidArgument(
+                "the IDL records no program address; pass --program".to_string(),
+            ));
+        }
+    };
+    let types: Vec<&IdlAccount> = match &args.account {
+        Some(name) => vec![idl.account(name).ok_or_else(|| {
+            ExampleError::InvalidArgument(format!("the IDL defines no account named {name}"))
+        })?],
+        None => idl.accounts.iter().collect(),
+    };
+
+    let limit = args.limit.unwrap_or(usize::MAX);
+    let mut printed = 0;
+    for account_type in types {
+        if printed >= limit {
+            break;
+        }
+        let accounts = fetch_accounts(&connection, &program, &idl, account_type)?;
+        eprintln!("{}: {} accounts", account_type.name, accounts.len());
+        for (address, data) in accounts.iter().take(limit - printed) {
+            if let Err(err) = print_account(&mut records, &idl, address, data, args.pretty) {
+                eprintln!("{address}: {err}");
+            }
+            printed += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Every account of `program` whose data starts with the discriminator of
+/// `account_type`.
+fn fetch_accounts(
+    connection: &RetryingRpcClient,
+    program: &Pubkey,
+    idl: &Idl,
+    account_type: &IdlAccount,
+) -> Result<Vec<(Pubkey, Vec<u8>)>> {
+    let discriminator = idl.discriminator(account_type);
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &discriminator,
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(connection.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = connection
+        .call(|client| Ok(client.get_program_accounts_with_config(program, config.clone())?))?;
+    Ok(accounts
+        .into_iter()
+        .map(|(address, account)| (address, account.data))
+        .collect())
+}
+
+/// The table format is JSON here as well, since the decoded fields have
+/// no fixed columns; --pretty only applies to it.
+fn print_account(
+    records: &mut RecordWriter,
+    idl: &Idl,
+    address: &Pubkey,
+    data: &[u8],
+    pretty: bool,
+) -> Result<()> {
+    let (account_type, fields) = idl.decode_account(data)?;
+    let record = json!({
+        "address": address.to_string(),
+        "type": account_type,
+        "data": fields,
+    });
+    records.write(&record, || {
+        let line = if pretty {
+            serde_json::to_string_pretty(&record)
+        } else {
+            serde_json::to_string(&record)
+        };
+        println!("{}", line.unwrap_or_default());
+    })
+}
@@ -0,0 +1,226 @@
+use clap::Parser;
+use common::anchor::Idl;
+use common::cli::{RpcArgs, WsArgs};
+use common::output::{OutputArgs, RecordWriter};
+use common::{ExampleError, Result, at_least_confirmed};
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Decode the events an Anchor program raises with `emit!`, using only its
+/// IDL: each `Program data:` line the program logs is matched to an event by
+/// its discriminator and its fields rendered as JSON.
+///
+/// Streams the program's events live over `logsSubscribe`, or decodes the
+/// events of one transaction with `--signature`. Events raised with
+/// `emit_cpi!` travel in instruction data instead of the logs and are not
+/// picked up here.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    /// IDL JSON file, as written to target/idl/ by `anchor build`
+    #[arg(short, long)]
+    idl: PathBuf,
+
+    /// Program ID; defaults to the address recorded in the IDL
+    #[arg(short, long)]
+    program: Option<Pubkey>,
+
+    /// Decode the events of this transaction instead of streaming
+    #[arg(short, long)]
+    signature: Option<Signature>,
+
+    /// Also decode events of failed transactions, which never took effect
+    #[arg(long)]
+    include_failed: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct Event {
+    signature: String,
+    slot: u64,
+    event: String,
+    data: Value,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let idl = Idl::from_file(&args.idl)?;
+    if idl.events.is_empty() {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{} defines no events",
+            args.idl.display()
+        )));
+    }
+    let program = match (args.program, idl.program_id()) {
+        (Some(program), _) => program,
+        (None, Some(program)) => program?,
+        (None, None) => {
+            return Err(ExampleError::InvalidArgument(
+                "the IDL records no program address; pass --program".to_string(),
+            ));
+        }
+    };
+    let mut records = args.output.records();
+
+    if let Some(signature) = &args.signature {
+        let connection = args.rpc.nonblocking_client();
+        let (slot, logs) = fetch_logs(&connection, signature).await?;
+        let found = write_events(&mut records, &idl, &program, signature, slot, &logs)?;
+        args.output
+            .status(format_args!("{found} events from {program}"));
+        return Ok(());
+    }
+
+    let ws_url = args.ws.url(&args.rpc);
+    loop {
+        match watch(&ws_url, &args, &idl, &program, &mut records).await {
+            Ok(Stop::Shutdown) => return Ok(()),
+            Ok(Stop::Disconnected) => warn!("websocket closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        warn!("resubscribing in {RECONNECT_DELAY:?}");
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Runs one subscription until the socket drops or Ctrl-C is pressed.
+async fn watch(
+    ws_url: &str,
+    args: &Args,
+    idl: &Idl,
+    program: &Pubkey,
+    records: &mut RecordWriter,
+) -> Result<Stop> {
+    let client = PubsubClient::new(ws_url).await?;
+    let (mut notifications, unsubscribe) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![program.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(args.rpc.commitment_config()),
+            },
+        )
+        .await?;
+    args.output.status(format_args!(
+        "Watching events of {program} (Ctrl-C to stop)"
+    ));
+
+    loop {
+        tokio::select! {
+            notification = notifications.next() => {
+                let Some(response) = notification else {
+                    return Ok(Stop::Disconnected);
+                };
+                let logs = response.value;
+                if logs.err.is_some() && !args.include_failed {
+                    continue;
+                }
+                let signature: Signature = logs
+                    .signature
+                    .parse()
+                    .map_err(|_| ExampleError::Parse(format!("signature `{}`", logs.signature)))?;
+                write_events(records, idl, program, &signature, response.context.slot, &logs.logs)?;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                unsubscribe().await;
+                drop(notifications);
+                client.shutdown().await?;
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}
+
+/// The slot a transaction landed in and its log messages.
+async fn fetch_logs(connection: &RpcClient, signature: &Signature) -> Result<(u64, Vec<String>)> {
+    let transaction = connection
+        .get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(at_least_confirmed(connection.commitment())),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    let logs: Option<Vec<String>> = transaction
+        .transaction
+        .meta
+        .and_then(|meta| meta.log_messages.into());
+    let logs = logs
+        .ok_or_else(|| ExampleError::Parse(format!("the node returned no logs for {signature}")))?;
+    Ok((transaction.slot, logs))
+}
+
+/// Writes each event in `logs` and returns how many there were. Lines that
+/// do not decode are reported and skipped.
+fn write_events(
+    records: &mut RecordWriter,
+    idl: &Idl,
+    program: &Pubkey,
+    signature: &Signature,
+    slot: u64,
+    logs: &[String],
+) -> Result<usize> {
+    let mut found = 0;
+    for decoded in idl.decode_logs(logs, program) {
+        let (name, data) = match decoded {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                warn!("{signature}: {err}");
+                continue;
+            }
+        };
+        let event = Event {
+            signature: signature.to_string(),
+            slot,
+            event: name.to_string(),
+            data,
+        };
+        records.write(&event, || {
+            println!(
+                "slot {} {} {}: {}",
+                event.slot, event.signature, event.event, event.data
+            );
+        })?;
+        found += 1;
+    }
+    Ok(found)
+}
@@ -0,0 +1,230 @@
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use common::output::OutputArgs;
+use common::{ExampleError, Result};
+use serde::Serialize;
+use solana_sdk::pubkey::{MAX_SEED_LEN, MAX_SEEDS, Pubkey};
+use std::process::ExitCode;
+use std::str::FromStr;
+
+/// Derive program addresses from typed seeds, or find which bump produced a
+/// given PDA. Every seed is echoed as hex so the bytes can be compared with
+/// what another language's client produces.
+///
+/// Seeds are written as `type:value`:
+///   string:vault       UTF-8 bytes
+///   pubkey:<base58>    the 32 key bytes
+///   u8:7, u16:7, u32:7, u64:7
+///                      little-endian integers, as `to_le_bytes()` and
+///                      Anchor's `.to_le_bytes().as_ref()` produce
+///   hex:deadbeef       raw bytes
+#[derive(Parser)]
+#[command(version, about, verbatim_doc_comment)]
+struct Args {
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run find_program_address and print the PDA and its canonical bump
+    Derive(SeedArgs),
+    /// Find the bump that turns the seeds into a given PDA
+    FindBump {
+        #[command(flatten)]
+        seeds: SeedArgs,
+
+        /// The PDA to match
+        #[arg(long)]
+        pda: Pubkey,
+    },
+}
+
+#[derive(ClapArgs)]
+struct SeedArgs {
+    /// Program that owns the PDA
+    #[arg(short, long)]
+    program: Pubkey,
+
+    /// Seeds in order, each as type:value
+    #[arg(short, long, required = true)]
+    seed: Vec<Seed>,
+}
+
+#[derive(Clone)]
+struct Seed {
+    label: String,
+    bytes: Vec<u8>,
+}
+
+impl FromStr for Seed {
+    type Err = String;
+
+    fn from_str(input: &str) -> std::result::Result<Self, String> {
+        let (kind, value) = input
+            .split_once(':')
+            .ok_or_else(|| format!("`{input}` is not type:value"))?;
+        let int_err = |_| format!("`{value}` is not a valid {kind}");
+        let bytes = match kind {
+            "string" | "str" => value.as_bytes().to_vec(),
+            "pubkey" => Pubkey::from_str(value)
+                .map_err(|err| format!("`{value}`: {err}"))?
+                .to_bytes()
+                .to_vec(),
+            "u8" => value.parse::<u8>().map_err(int_err)?.to_le_bytes().to_vec(),
+            "u16" => value
+                .parse::<u16>()
+                .map_err(int_err)?
+                .to_le_bytes()
+                .to_vec(),
+            "u32" => value
+                .parse::<u32>()
+                .map_err(int_err)?
+                .to_le_bytes()
+                .to_vec(),
+            "u64" => value
+                .parse::<u64>()
+                .map_err(int_err)?
+                .to_le_bytes()
+                .to_vec(),
+            "hex" => hex::decode(value).map_err(|err| format!("`{value}`: {err}"))?,
+            _ => {
+                return Err(format!(
+                    "unknown seed type `{kind}`; use string, pubkey, u8, u16, u32, u64 or hex"
+                ));
+            }
+        };
+        if bytes.len() > MAX_SEED_LEN {
+            return Err(format!(
+                "`{input}` is {} bytes, seeds are limited to {MAX_SEED_LEN}",
+                bytes.len()
+            ));
+        }
+        Ok(Self {
+            label: input.to_string(),
+            bytes,
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct Derivation {
+    program: String,
+    seeds: Vec<SeedRow>,
+    /// The derived PDA, or the one given to find-bump
+    pda: String,
+    /// None when no bump produces the given PDA
+    bump: Option<u8>,
+    canonical_pda: String,
+    canonical_bump: u8,
+}
+
+#[derive(Serialize)]
+struct SeedRow {
+    seed: String,
+    len: usize,
+    hex: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let output = &args.output;
+    match args.command {
+        Command::Derive(seeds) => {
+            let bytes = seed_bytes(&seeds)?;
+            let (pda, bump) = Pubkey::find_program_address(&bytes, &seeds.program);
+            let derivation = derivation(&seeds, pda, Some(bump), (pda, bump));
+            output.print(&derivation, || {
+                print_seeds(&derivation);
+                println!("PDA:  {pda}");
+                println!("Bump: {bump}");
+            })?;
+        }
+        Command::FindBump { seeds, pda } => {
+            let bytes = seed_bytes(&seeds)?;
+            let canonical = Pubkey::find_program_address(&bytes, &seeds.program);
+            let found = (0..=u8::MAX).rev().find(|bump| {
+                let mut with_bump = bytes.clone();
+                let bump = [*bump];
+                with_bump.push(&bump);
+                Pubkey::create_program_address(&with_bump, &seeds.program) == Ok(pda)
+            });
+            let derivation = derivation(&seeds, pda, found, canonical);
+            output.print(&derivation, || {
+                print_seeds(&derivation);
+                let (canonical, canonical_bump) = canonical;
+                match found {
+                    Some(bump) if bump == canonical_bump => {
+                        println!("{pda} uses bump {bump}, the canonical one");
+                    }
+                    Some(bump) => {
+                        println!(
+                            "{pda} uses bump {bump}; the canonical bump is {canonical_bump} ({canonical})"
+                        );
+                    }
+                    None => {
+                        println!("No bump turns these seeds into {pda} for {}", seeds.program);
+                        println!(
+                            "With these seeds the canonical PDA is {canonical} (bump {canonical_bump})"
+                        );
+                        println!(
+                            "Check seed order, integer widths and endianness against the program"
+                        );
+                    }
+                }
+            })?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the seeds as slices for the derivation calls.
+fn seed_bytes(args: &SeedArgs) -> Result<Vec<&[u8]>> {
+    // One slot is reserved for the bump.
+    if args.seed.len() > MAX_SEEDS - 1 {
+        return Err(ExampleError::InvalidArgument(format!(
+            "at most {} seeds fit alongside the bump",
+            MAX_SEEDS - 1
+        )));
+    }
+    Ok(args.seed.iter().map(|seed| seed.bytes.as_slice()).collect())
+}
+
+fn derivation(
+    args: &SeedArgs,
+    pda: Pubkey,
+    bump: Option<u8>,
+    (canonical_pda, canonical_bump): (Pubkey, u8),
+) -> Derivation {
+    Derivation {
+        program: args.program.to_string(),
+        seeds: args
+            .seed
+            .iter()
+            .map(|seed| SeedRow {
+                seed: seed.label.clone(),
+                len: seed.bytes.len(),
+                hex: hex::encode(&seed.bytes),
+            })
+            .collect(),
+        pda: pda.to_string(),
+        bump,
+        canonical_pda: canonical_pda.to_string(),
+        canonical_bump,
+    }
+}
+
+fn print_seeds(derivation: &Derivation) {
+    println!("Program: {}", derivation.program);
+    for (index, seed) in derivation.seeds.iter().enumerate() {
+        println!(
+            "Seed {index}: {:<40} {} bytes  {}",
+            seed.seed, seed.len, seed.hex
+        );
+    }
+}
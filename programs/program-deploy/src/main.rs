@@ -0,0 +1,573 @@
+use clap::{Parser, Subcommand};
+use common::blockhash::BlockhashCache;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::tx_size::{MAX_TRANSACTION_SIZE, TransactionBudget};
+use common::{ExampleError, Result, explorer, keys};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_loader_v3_interface::instruction as loader_instruction;
+use solana_loader_v3_interface::state::UpgradeableLoaderState;
+use solana_sdk::account::Account;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::thread;
+use tracing::error;
+
+/// Write passes over the buffer before giving up on chunks that still do
+/// not match the program.
+const MAX_WRITE_PASSES: usize = 3;
+
+const BUFFER_METADATA_LEN: usize = UpgradeableLoaderState::size_of_buffer_metadata();
+const PROGRAMDATA_METADATA_LEN: usize = UpgradeableLoaderState::size_of_programdata_metadata();
+
+/// Deploy or upgrade a compiled program with the upgradeable loader, the way
+/// `solana program deploy` does.
+///
+/// The program is written into a buffer account in chunks small enough for
+/// one transaction each, several in flight at once; the buffer then becomes
+/// the program's code in a single deploy or upgrade instruction. The buffer
+/// keypair is kept in a file, so a run that is interrupted can be started
+/// again with the same arguments: chunks already in the buffer are skipped.
+/// A buffer abandoned for good still holds its rent; close it with
+/// `solana program close`.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deploy a new program
+    Deploy {
+        #[command(flatten)]
+        buffer: BufferArgs,
+
+        /// Program keypair, whose address becomes the program id; generated
+        /// when the file does not exist [default: <name>-keypair.json next
+        /// to the .so, where `anchor build` puts it]
+        #[arg(long)]
+        program_keypair: Option<PathBuf>,
+
+        /// Largest size in bytes the program can be upgraded to without
+        /// extending it [default: the program's size]
+        #[arg(long)]
+        max_len: Option<usize>,
+    },
+    /// Replace the code of a deployed program, extending its program data
+    /// account first if the new code is larger
+    Upgrade {
+        #[command(flatten)]
+        buffer: BufferArgs,
+
+        #[arg(short, long)]
+        program_id: Pubkey,
+    },
+}
+
+#[derive(clap::Args)]
+struct BufferArgs {
+    /// Compiled program, e.g. target/deploy/my_program.so
+    program: PathBuf,
+
+    /// Buffer keypair; generated when the file does not exist, and reused to
+    /// resume an interrupted write [default: <name>-buffer-keypair.json next
+    /// to the .so]
+    #[arg(long)]
+    buffer: Option<PathBuf>,
+
+    /// Upgrade authority keypair file; defaults to the signer
+    #[arg(long)]
+    upgrade_authority: Option<PathBuf>,
+
+    /// Chunk writes in flight at once
+    #[arg(short = 'n', long, default_value_t = 8)]
+    concurrency: usize,
+}
+
+#[derive(Serialize)]
+struct DeployReport {
+    action: &'static str,
+    program_id: String,
+    buffer: String,
+    /// Size of the program in bytes
+    program_len: usize,
+    chunks: usize,
+    /// Chunks found already written by an earlier run
+    resumed_chunks: usize,
+    signature: String,
+    explorer: String,
+}
+
+/// The signer keys shared by both subcommands.
+struct Signers {
+    payer: Keypair,
+    /// Set when the upgrade authority is not the payer
+    authority: Option<Keypair>,
+    buffer: Keypair,
+}
+
+impl Signers {
+    fn authority(&self) -> &Keypair {
+        self.authority.as_ref().unwrap_or(&self.payer)
+    }
+
+    /// The payer, the authority unless it is the payer, and `extra`.
+    fn with<'a>(&'a self, extra: &[&'a Keypair]) -> Vec<&'a dyn Signer> {
+        let mut signers: Vec<&dyn Signer> = vec![&self.payer];
+        if let Some(authority) = &self.authority {
+            signers.push(authority);
+        }
+        signers.extend(extra.iter().map(|keypair| *keypair as &dyn Signer));
+        signers
+    }
+}
+
+struct Written {
+    chunks: usize,
+    resumed: usize,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let cache = BlockhashCache::default();
+    let payer = args.keypair.load()?;
+
+    let report = match &args.command {
+        Command::Deploy {
+            buffer,
+            program_keypair,
+            max_len,
+        } => {
+            let program = fs::read(&buffer.program)?;
+            let program_keypair = load_or_create(
+                &program_keypair
+                    .clone()
+                    .unwrap_or_else(|| sibling(&buffer.program, "-keypair.json")),
+                &args.output,
+            )?;
+            let program_id = program_keypair.pubkey();
+            if connection
+                .get_account_with_commitment(&program_id, connection.commitment())?
+                .value
+                .is_some()
+            {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "{program_id} already exists; use `upgrade` to replace its code"
+                )));
+            }
+            let max_len = max_len.unwrap_or(program.len());
+            if max_len < program.len() {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "--max-len {max_len} is smaller than the program's {} bytes",
+                    program.len()
+                )));
+            }
+            let signers = load_signers(buffer, payer, &args.output)?;
+            let written = write_buffer(
+                &connection,
+                &cache,
+                &signers,
+                &program,
+                buffer.concurrency,
+                &args.output,
+            )?;
+
+            let lamports = connection
+                .get_minimum_balance_for_rent_exemption(UpgradeableLoaderState::size_of_program())?;
+            // Loader-v3 deployments are deprecated in favour of loader-v4,
+            // which mainnet does not run yet.
+            #[allow(deprecated)]
+            let instructions = loader_instruction::deploy_with_max_program_len(
+                &signers.payer.pubkey(),
+                &program_id,
+                &signers.buffer.pubkey(),
+                &signers.authority().pubkey(),
+                lamports,
+                max_len,
+            )
+            .map_err(|err| ExampleError::InvalidArgument(err.to_string()))?;
+            let signature = send(
+                &connection,
+                &cache,
+                &instructions,
+                &signers,
+                &[&program_keypair],
+            )?;
+            report(
+                "deploy",
+                &program_id,
+                &signers,
+                &program,
+                written,
+                &signature,
+                &args.rpc.rpc_url,
+            )
+        }
+        Command::Upgrade { buffer, program_id } => {
+            let program = fs::read(&buffer.program)?;
+            let signers = load_signers(buffer, payer, &args.output)?;
+            let capacity =
+                check_upgradeable(&connection, program_id, &signers.authority().pubkey())?;
+            let written = write_buffer(
+                &connection,
+                &cache,
+                &signers,
+                &program,
+                buffer.concurrency,
+                &args.output,
+            )?;
+
+            if program.len() > capacity {
+                let additional = program.len() - capacity;
+                let extend = loader_instruction::extend_program_checked(
+                    program_id,
+                    &signers.authority().pubkey(),
+                    Some(&signers.payer.pubkey()),
+                    additional as u32,
+                );
+                send(&connection, &cache, &[extend], &signers, &[])?;
+                args.output.status(format_args!(
+                    "Extended the program data by {additional} bytes"
+                ));
+            }
+            let upgrade = loader_instruction::upgrade(
+                program_id,
+                &signers.buffer.pubkey(),
+                &signers.authority().pubkey(),
+                &signers.payer.pubkey(),
+            );
+            let signature = send(&connection, &cache, &[upgrade], &signers, &[])?;
+            report(
+                "upgrade",
+                program_id,
+                &signers,
+                &program,
+                written,
+                &signature,
+                &args.rpc.rpc_url,
+            )
+        }
+    };
+
+    args.output.status(format_args!(
+        "The {} consumed buffer {}; its keypair file is no longer needed",
+        report.action, report.buffer
+    ));
+    args.output.print(&report, || {
+        println!("Program id: {}", report.program_id);
+        println!(
+            "Code:       {} bytes in {} chunks ({} resumed)",
+            report.program_len, report.chunks, report.resumed_chunks
+        );
+        println!("Signature:  {}", report.signature);
+        println!("Explorer:   {}", report.explorer);
+    })
+}
+
+fn load_signers(args: &BufferArgs, payer: Keypair, output: &OutputArgs) -> Result<Signers> {
+    let authority = args
+        .upgrade_authority
+        .as_ref()
+        .map(keys::read_keypair_file)
+        .transpose()?
+        .filter(|authority| authority.pubkey() != payer.pubkey());
+    let buffer = load_or_create(
+        &args
+            .buffer
+            .clone()
+            .unwrap_or_else(|| sibling(&args.program, "-buffer-keypair.json")),
+        output,
+    )?;
+    Ok(Signers {
+        payer,
+        authority,
+        buffer,
+    })
+}
+
+/// `target/deploy/my_program.so` becomes `target/deploy/my_program<suffix>`.
+fn sibling(program: &Path, suffix: &str) -> PathBuf {
+    let stem = program
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    program.with_file_name(format!("{stem}{suffix}"))
+}
+
+fn load_or_create(path: &Path, output: &OutputArgs) -> Result<Keypair> {
+    if path.exists() {
+        return keys::read_keypair_file(path);
+    }
+    let keypair = Keypair::new();
+    keys::write_keypair_file(&keypair, path)?;
+    output.status(format_args!(
+        "Wrote new keypair {} to {}",
+        keypair.pubkey(),
+        path.display()
+    ));
+    Ok(keypair)
+}
+
+/// Checks `authority` may upgrade `program_id` and returns how many bytes of
+/// code its program data account has room for.
+fn check_upgradeable(client: &RpcClient, program_id: &Pubkey, authority: &Pubkey) -> Result<usize> {
+    let not_upgradeable =
+        || ExampleError::InvalidArgument(format!("{program_id} is not an upgradeable program"));
+    let program = fetch(client, program_id)?;
+    let Ok(UpgradeableLoaderState::Program {
+        programdata_address,
+    }) = bincode::deserialize(&program.data)
+    else {
+        return Err(not_upgradeable());
+    };
+    let programdata = fetch(client, &programdata_address)?;
+    let Ok(UpgradeableLoaderState::ProgramData {
+        upgrade_authority_address,
+        ..
+    }) = bincode::deserialize(&programdata.data)
+    else {
+        return Err(not_upgradeable());
+    };
+    match upgrade_authority_address {
+        None => Err(ExampleError::InvalidArgument(format!(
+            "{program_id} has been made immutable"
+        ))),
+        Some(current) if current != *authority => Err(ExampleError::InvalidArgument(format!(
+            "the upgrade authority of {program_id} is {current}, not {authority}"
+        ))),
+        Some(_) => Ok(programdata
+            .data
+            .len()
+            .saturating_sub(PROGRAMDATA_METADATA_LEN)),
+    }
+}
+
+fn fetch(client: &RpcClient, address: &Pubkey) -> Result<Account> {
+    client
+        .get_account_with_commitment(address, client.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(*address))
+}
+
+/// Creates the buffer if it does not exist yet, then writes every chunk the
+/// buffer does not already hold, rereading it after each pass until it
+/// matches the program.
+fn write_buffer(
+    client: &RpcClient,
+    cache: &BlockhashCache,
+    signers: &Signers,
+    program: &[u8],
+    concurrency: usize,
+    output: &OutputArgs,
+) -> Result<Written> {
+    let buffer = signers.buffer.pubkey();
+    let authority = signers.authority().pubkey();
+    let chunk_size = chunk_size(&buffer, &authority, &signers.payer.pubkey());
+    let chunks = program.len().div_ceil(chunk_size);
+
+    let mut resumed = None;
+    for _ in 0..MAX_WRITE_PASSES {
+        let current = match client
+            .get_account_with_commitment(&buffer, client.commitment())?
+            .value
+        {
+            Some(account) => buffer_contents(&buffer, &account, &authority, program.len())?,
+            None => {
+                create_buffer(client, cache, signers, program.len())?;
+                output.status(format_args!("Created buffer {buffer}"));
+                vec![0; program.len()]
+            }
+        };
+        let pending: Vec<(usize, &[u8])> = program
+            .chunks(chunk_size)
+            .enumerate()
+            .map(|(index, chunk)| (index * chunk_size, chunk))
+            .filter(|&(offset, chunk)| current[offset..offset + chunk.len()] != *chunk)
+            .collect();
+        let resumed = *resumed.get_or_insert(chunks - pending.len());
+        if pending.is_empty() {
+            return Ok(Written { chunks, resumed });
+        }
+        output.status(format_args!(
+            "Writing {} of {chunks} chunks of {chunk_size} bytes to {buffer}",
+            pending.len()
+        ));
+        write_chunks(client, cache, signers, &pending, concurrency, output)?;
+    }
+    Err(ExampleError::InvalidArgument(format!(
+        "buffer {buffer} still differs from the program after {MAX_WRITE_PASSES} passes"
+    )))
+}
+
+/// The program bytes held by an existing buffer, after checking it is one
+/// this run can keep writing to.
+fn buffer_contents(
+    address: &Pubkey,
+    account: &Account,
+    authority: &Pubkey,
+    program_len: usize,
+) -> Result<Vec<u8>> {
+    let Ok(UpgradeableLoaderState::Buffer { authority_address }) =
+        bincode::deserialize(&account.data)
+    else {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{address} is not a program buffer"
+        )));
+    };
+    if authority_address != Some(*authority) {
+        return Err(ExampleError::InvalidArgument(format!(
+            "buffer {address} does not belong to the upgrade authority {authority}"
+        )));
+    }
+    if account.data.len() != UpgradeableLoaderState::size_of_buffer(program_len) {
+        return Err(ExampleError::InvalidArgument(format!(
+            "buffer {address} was created for a program of {} bytes, not {program_len}; \
+             pass a new --buffer",
+            account.data.len().saturating_sub(BUFFER_METADATA_LEN)
+        )));
+    }
+    Ok(account.data[BUFFER_METADATA_LEN..].to_vec())
+}
+
+fn create_buffer(
+    client: &RpcClient,
+    cache: &BlockhashCache,
+    signers: &Signers,
+    program_len: usize,
+) -> Result<Signature> {
+    let lamports = client.get_minimum_balance_for_rent_exemption(
+        UpgradeableLoaderState::size_of_buffer(program_len),
+    )?;
+    let instructions = loader_instruction::create_buffer(
+        &signers.payer.pubkey(),
+        &signers.buffer.pubkey(),
+        &signers.authority().pubkey(),
+        lamports,
+        program_len,
+    )
+    .map_err(|err| ExampleError::InvalidArgument(err.to_string()))?;
+    send(client, cache, &instructions, signers, &[&signers.buffer])
+}
+
+/// Largest chunk whose write transaction still fits in a packet.
+fn chunk_size(buffer: &Pubkey, authority: &Pubkey, payer: &Pubkey) -> usize {
+    let empty = loader_instruction::write(buffer, authority, 0, Vec::new());
+    let budget = TransactionBudget::measure(&[empty], payer);
+    // The instruction data's length prefix grows to two bytes past 127.
+    MAX_TRANSACTION_SIZE - budget.size - 1
+}
+
+/// Sends the chunk writes, `concurrency` at a time. Every chunk is tried;
+/// the first failure is returned once all have finished.
+fn write_chunks(
+    client: &RpcClient,
+    cache: &BlockhashCache,
+    signers: &Signers,
+    chunks: &[(usize, &[u8])],
+    concurrency: usize,
+    output: &OutputArgs,
+) -> Result<()> {
+    let buffer = signers.buffer.pubkey();
+    let authority = signers.authority().pubkey();
+    let queue = Mutex::new(chunks.iter());
+    let progress = Mutex::new((0, None));
+    thread::scope(|scope| {
+        for _ in 0..concurrency.max(1) {
+            scope.spawn(|| {
+                loop {
+                    let Some(&(offset, chunk)) = queue.lock().unwrap().next() else {
+                        return;
+                    };
+                    let write = loader_instruction::write(
+                        &buffer,
+                        &authority,
+                        offset as u32,
+                        chunk.to_vec(),
+                    );
+                    let outcome = send(client, cache, &[write], signers, &[]);
+                    let mut progress = progress.lock().unwrap();
+                    match outcome {
+                        Ok(_) => {
+                            progress.0 += 1;
+                            output.status(format_args!(
+                                "Wrote {}/{} chunks",
+                                progress.0,
+                                chunks.len()
+                            ));
+                        }
+                        Err(err) => {
+                            error!("writing {} bytes at {offset}: {err}", chunk.len());
+                            progress.1.get_or_insert(err);
+                        }
+                    }
+                }
+            });
+        }
+    });
+    match progress.into_inner().unwrap() {
+        (_, Some(err)) => {
+            eprintln!("run the same command again to resume writing the buffer");
+            Err(err)
+        }
+        (_, None) => Ok(()),
+    }
+}
+
+fn send(
+    client: &RpcClient,
+    cache: &BlockhashCache,
+    instructions: &[Instruction],
+    signers: &Signers,
+    extra: &[&Keypair],
+) -> Result<Signature> {
+    signers
+        .with(extra)
+        .into_iter()
+        .fold(TxBuilder::new(&signers.payer), TxBuilder::add_signer)
+        .add_instructions(instructions.iter().cloned())
+        .with_blockhash_cache(cache)
+        .sign_and_send(client)
+}
+
+fn report(
+    action: &'static str,
+    program_id: &Pubkey,
+    signers: &Signers,
+    program: &[u8],
+    written: Written,
+    signature: &Signature,
+    rpc_url: &str,
+) -> DeployReport {
+    DeployReport {
+        action,
+        program_id: program_id.to_string(),
+        buffer: signers.buffer.pubkey().to_string(),
+        program_len: program.len(),
+        chunks: written.chunks,
+        resumed_chunks: written.resumed,
+        signature: signature.to_string(),
+        explorer: explorer::transaction_url(signature, rpc_url),
+    }
+}
@@ -0,0 +1,419 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use borsh::{BorshDeserialize, BorshSerialize};
+use clap::{Parser, ValueEnum};
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::{ExampleError, Result, RetryingRpcClient, at_least_confirmed, batch};
+use serde::Serialize;
+use solana_account_decoder_client_types::UiDataSliceConfig;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig,
+};
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Starts a binary snapshot.
+const MAGIC: [u8; 8] = *b"PGMSNAP1";
+
+/// Export every account a program owns, with its lamports and raw data, to
+/// newline-delimited JSON or a compact binary snapshot.
+///
+/// getProgramAccounts is first called with an empty data slice, so the node
+/// only returns addresses; the data then comes in pages of
+/// getMultipleAccounts, which keeps each response small however large the
+/// program's accounts are. The slot read before the export is stored in the
+/// snapshot as its watermark. `--since` takes an earlier snapshot and exports
+/// only the accounts written by the program's transactions after its
+/// watermark, with closed accounts as records of zero lamports and no data.
+///
+/// A JSON snapshot starts with a header line holding `program`, `slot` and
+/// `since`, followed by one `{pubkey, lamports, data}` line per account, the
+/// data in base64. A binary snapshot is the Borsh encoding of the same
+/// header, prefixed with `PGMSNAP1`, followed by the records until the end
+/// of the file.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Program whose accounts to export
+    program: Pubkey,
+
+    /// Snapshot file to write
+    #[arg(short = 'f', long)]
+    file: PathBuf,
+
+    /// How the snapshot file is encoded
+    #[arg(long, value_enum, default_value_t = SnapshotFormat::Ndjson)]
+    encoding: SnapshotFormat,
+
+    /// Earlier snapshot of the same program; only accounts changed since its
+    /// watermark are exported
+    #[arg(long)]
+    since: Option<PathBuf>,
+
+    /// Accounts per getMultipleAccounts call
+    #[arg(long, default_value_t = 100)]
+    page_size: usize,
+
+    /// Give up on an incremental export that would replay more transactions
+    /// than this; take a full snapshot instead
+    #[arg(long, default_value_t = 10_000)]
+    max_transactions: usize,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum SnapshotFormat {
+    /// One JSON object per line
+    Ndjson,
+    /// Borsh-encoded header and records
+    Binary,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct Header {
+    program: Pubkey,
+    /// Watermark: every change up to this slot is included
+    slot: Slot,
+    /// Watermark of the snapshot this one adds to, if incremental
+    since: Option<Slot>,
+}
+
+#[derive(Serialize)]
+struct JsonHeader {
+    program: String,
+    slot: Slot,
+    since: Option<Slot>,
+}
+
+#[derive(BorshSerialize)]
+struct Record {
+    pubkey: Pubkey,
+    lamports: u64,
+    data: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct JsonRecord {
+    pubkey: String,
+    lamports: u64,
+    /// Base64
+    data: String,
+}
+
+#[derive(Serialize)]
+struct ExportSummary {
+    program: String,
+    file: String,
+    slot: Slot,
+    since: Option<Slot>,
+    /// Transactions replayed to find the changed accounts, if incremental
+    transactions: Option<usize>,
+    accounts: usize,
+    /// Accounts closed since the watermark, if incremental
+    closed: usize,
+    /// Account data written, in bytes
+    bytes: usize,
+}
+
+/// Writes a snapshot in either format.
+struct SnapshotWriter {
+    file: BufWriter<File>,
+    format: SnapshotFormat,
+}
+
+impl SnapshotWriter {
+    fn create(path: &Path, format: SnapshotFormat, header: &Header) -> Result<Self> {
+        let mut file = BufWriter::new(File::create(path)?);
+        match format {
+            SnapshotFormat::Ndjson => {
+                let header = JsonHeader {
+                    program: header.program.to_string(),
+                    slot: header.slot,
+                    since: header.since,
+                };
+                serde_json::to_writer(&mut file, &header).map_err(std::io::Error::from)?;
+                writeln!(file)?;
+            }
+            SnapshotFormat::Binary => {
+                file.write_all(&MAGIC)?;
+                borsh::to_writer(&mut file, header)?;
+            }
+        }
+        Ok(Self { file, format })
+    }
+
+    fn write(&mut self, record: &Record) -> Result<()> {
+        match self.format {
+            SnapshotFormat::Ndjson => {
+                let record = JsonRecord {
+                    pubkey: record.pubkey.to_string(),
+                    lamports: record.lamports,
+                    data: BASE64.encode(&record.data),
+                };
+                serde_json::to_writer(&mut self.file, &record).map_err(std::io::Error::from)?;
+                writeln!(self.file)?;
+            }
+            SnapshotFormat::Binary => borsh::to_writer(&mut self.file, record)?,
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<()> {
+        Ok(self.file.flush()?)
+    }
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.retrying_client();
+    let since = args
+        .since
+        .as_ref()
+        .map(|path| read_watermark(path, &args.program))
+        .transpose()?;
+    // Read first, so that anything written while the export runs is
+    // picked up again by the next incremental one.
+    let slot = connection.call(|client| Ok(client.get_slot()?))?;
+
+    let (keys, transactions) = match since {
+        None => (program_account_keys(&connection, &args.program)?, None),
+        Some(since) => {
+            let (keys, transactions) = changed_accounts(&connection, &args, since)?;
+            (keys, Some(transactions))
+        }
+    };
+    args.output.status(format_args!(
+        "Exporting {} accounts of {} as of slot {slot}",
+        keys.len(),
+        args.program
+    ));
+
+    let header = Header {
+        program: args.program,
+        slot,
+        since,
+    };
+    let mut writer = SnapshotWriter::create(&args.file, args.encoding, &header)?;
+    let mut summary = ExportSummary {
+        program: args.program.to_string(),
+        file: args.file.display().to_string(),
+        slot,
+        since,
+        transactions,
+        accounts: 0,
+        closed: 0,
+        bytes: 0,
+    };
+    let page_size = args.page_size.clamp(1, 100);
+    let pages = keys.chunks(page_size).count();
+    for (number, page) in keys.chunks(page_size).enumerate() {
+        let accounts = connection.call(|client| batch::get_multiple_accounts(client, page))?;
+        for (pubkey, account) in page.iter().zip(accounts) {
+            let record = match account {
+                Some(account) if account.owner == args.program => Record {
+                    pubkey: *pubkey,
+                    lamports: account.lamports,
+                    data: account.data,
+                },
+                // Not the program's: a wallet or a token account written
+                // by the same transaction, or an account since reassigned.
+                Some(_) => continue,
+                // Closed after a full export listed the addresses.
+                None if since.is_none() => continue,
+                None => {
+                    summary.closed += 1;
+                    Record {
+                        pubkey: *pubkey,
+                        lamports: 0,
+                        data: Vec::new(),
+                    }
+                }
+            };
+            summary.accounts += 1;
+            summary.bytes += record.data.len();
+            writer.write(&record)?;
+        }
+        if (number + 1) % 10 == 0 || number + 1 == pages {
+            args.output
+                .status(format_args!("Fetched page {}/{pages}", number + 1));
+        }
+    }
+    writer.finish()?;
+
+    args.output.print(&summary, || {
+        println!("Program:   {}", summary.program);
+        println!("File:      {}", summary.file);
+        match summary.since {
+            Some(since) => println!("Slots:     {since} to {}", summary.slot),
+            None => println!("Slot:      {}", summary.slot),
+        }
+        if let Some(transactions) = summary.transactions {
+            println!("Replayed:  {transactions} transactions");
+        }
+        println!(
+            "Accounts:  {} ({} closed), {} bytes of data",
+            summary.accounts, summary.closed, summary.bytes
+        );
+    })
+}
+
+/// Every address the program owns, without any of the data.
+fn program_account_keys(connection: &RetryingRpcClient, program: &Pubkey) -> Result<Vec<Pubkey>> {
+    let mut keys: Vec<Pubkey> = connection.call(|client| {
+        let config = RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: 0,
+                }),
+                commitment: Some(client.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        Ok(client
+            .get_program_accounts_with_config(program, config)?
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect())
+    })?;
+    keys.sort();
+    Ok(keys)
+}
+
+/// The accounts written by successful transactions that mention the program
+/// after slot `since`, and how many transactions that was. Failed
+/// transactions change nothing but the fee payer's balance.
+fn changed_accounts(
+    connection: &RetryingRpcClient,
+    args: &Args,
+    since: Slot,
+) -> Result<(Vec<Pubkey>, usize)> {
+    let mut signatures = Vec::new();
+    let mut before = None;
+    'pages: loop {
+        let page = connection.call(|client| {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(batch::MAX_SIGNATURES_FOR_ADDRESS),
+                commitment: Some(at_least_confirmed(client.commitment())),
+            };
+            Ok(client.get_signatures_for_address_with_config(&args.program, config)?)
+        })?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(parse_signature(&last.signature)?);
+        let full = page.len() == batch::MAX_SIGNATURES_FOR_ADDRESS;
+        for status in page {
+            if status.slot <= since {
+                break 'pages;
+            }
+            if status.err.is_none() {
+                signatures.push(parse_signature(&status.signature)?);
+            }
+            if signatures.len() > args.max_transactions {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "more than {} transactions since slot {since}; take a full snapshot instead",
+                    args.max_transactions
+                )));
+            }
+        }
+        if !full {
+            break;
+        }
+    }
+    args.output.status(format_args!(
+        "Replaying {} transactions since slot {since}",
+        signatures.len()
+    ));
+
+    let mut keys = BTreeSet::new();
+    for signature in &signatures {
+        keys.extend(writable_accounts(connection, signature)?);
+    }
+    keys.remove(&args.program);
+    Ok((keys.into_iter().collect(), signatures.len()))
+}
+
+/// The accounts a transaction could write, lookup table entries included.
+fn writable_accounts(connection: &RetryingRpcClient, signature: &Signature) -> Result<Vec<Pubkey>> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(at_least_confirmed(connection.commitment())),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed =
+        connection.call(|client| Ok(client.get_transaction_with_config(signature, config)?))?;
+    let message = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| ExampleError::Parse("transaction is not base64 encoded".to_string()))?
+        .message;
+    let mut keys: Vec<Pubkey> = message
+        .static_account_keys()
+        .iter()
+        .enumerate()
+        .filter(|&(index, _)| message.is_maybe_writable(index, None))
+        .map(|(_, key)| *key)
+        .collect();
+    if let Some(meta) = &confirmed.transaction.meta
+        && let OptionSerializer::Some(loaded) = &meta.loaded_addresses
+    {
+        for address in &loaded.writable {
+            keys.push(address.parse()?);
+        }
+    }
+    Ok(keys)
+}
+
+fn parse_signature(signature: &str) -> Result<Signature> {
+    signature
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("signature `{signature}`")))
+}
+
+/// The watermark of an earlier snapshot of `program`, in either format.
+fn read_watermark(path: &Path, program: &Pubkey) -> Result<Slot> {
+    let mut file = BufReader::new(File::open(path)?);
+    let invalid = || ExampleError::Parse(format!("snapshot header of {}", path.display()));
+    let (snapshot_program, slot) = if file.fill_buf()?.starts_with(&MAGIC) {
+        let mut magic = [0; MAGIC.len()];
+        file.read_exact(&mut magic)?;
+        let header = Header::deserialize_reader(&mut file).map_err(|_| invalid())?;
+        (header.program, header.slot)
+    } else {
+        let mut line = String::new();
+        file.read_line(&mut line)?;
+        let header: serde_json::Value = serde_json::from_str(&line).map_err(|_| invalid())?;
+        let program: Pubkey = header["program"].as_str().ok_or_else(invalid)?.parse()?;
+        (program, header["slot"].as_u64().ok_or_else(invalid)?)
+    };
+    if snapshot_program != *program {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{} is a snapshot of {snapshot_program}, not {program}",
+            path.display()
+        )));
+    }
+    Ok(slot)
+}
@@ -0,0 +1,123 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::token::{MintInfo, TokenAccountInfo};
+use common::{ExampleError, Result, batch};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Instant;
+
+/// Look up thousands of token accounts with batched getMultipleAccounts
+/// calls, decode them locally and total the balances per mint.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// File with one token account address per line ('#' starts a comment)
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+
+    /// Number of 100-account chunks to request in parallel
+    #[arg(short = 'n', long, default_value_t = 4)]
+    concurrency: usize,
+
+    /// Token account addresses (base58), in addition to --file
+    accounts: Vec<Pubkey>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Default)]
+struct Total {
+    amount: u64,
+    accounts: usize,
+}
+
+#[derive(Serialize)]
+struct MintTotal {
+    mint: String,
+    accounts: usize,
+    total: String,
+    /// The total in base units
+    amount: u64,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let mut keys = args.accounts.clone();
+    if let Some(file) = &args.file {
+        keys.extend(batch::read_keys_file(file)?);
+    }
+    // An account listed twice would be counted twice in its mint's total.
+    let mut seen = HashSet::new();
+    keys.retain(|key| seen.insert(*key));
+    if keys.is_empty() {
+        return Err(ExampleError::InvalidArgument(
+            "pass token accounts as arguments or with --file".to_string(),
+        ));
+    }
+    let connection = args.rpc.nonblocking_client();
+
+    let started = Instant::now();
+    let accounts =
+        batch::get_multiple_accounts_concurrent(&connection, &keys, args.concurrency).await?;
+    let mut totals: BTreeMap<Pubkey, Total> = BTreeMap::new();
+    let (mut missing, mut skipped) = (0, 0);
+    for (key, account) in keys.iter().zip(&accounts) {
+        let Some(account) = account else {
+            missing += 1;
+            continue;
+        };
+        match TokenAccountInfo::unpack(key, account) {
+            Ok(info) => {
+                let total = totals.entry(info.account.mint).or_default();
+                total.amount += info.account.amount;
+                total.accounts += 1;
+            }
+            Err(err) => {
+                skipped += 1;
+                eprintln!("skipping {key}: {err}");
+            }
+        }
+    }
+
+    let mints: Vec<Pubkey> = totals.keys().copied().collect();
+    let mint_accounts =
+        batch::get_multiple_accounts_concurrent(&connection, &mints, args.concurrency).await?;
+    let elapsed = started.elapsed();
+
+    let mut rows = Vec::with_capacity(totals.len());
+    for ((mint, total), account) in totals.iter().zip(mint_accounts) {
+        let account = account.ok_or(ExampleError::AccountNotFound(*mint))?;
+        let info = MintInfo::unpack(mint, &account)?;
+        rows.push(MintTotal {
+            mint: mint.to_string(),
+            accounts: total.accounts,
+            total: info.ui_amount(total.amount),
+            amount: total.amount,
+        });
+    }
+    args.output.print(&rows, || {
+        println!("{:<44}  {:>8}  {:>24}", "Mint", "Accounts", "Total");
+        for row in &rows {
+            println!("{:<44}  {:>8}  {:>24}", row.mint, row.accounts, row.total);
+        }
+    })?;
+    args.output.status(format_args!(
+        "Looked up {} accounts ({missing} missing, {skipped} not token accounts) in {} requests, {elapsed:?}",
+        keys.len(),
+        keys.len().div_ceil(batch::MAX_MULTIPLE_ACCOUNTS)
+            + mints.len().div_ceil(batch::MAX_MULTIPLE_ACCOUNTS)
+    ));
+    Ok(())
+}
@@ -0,0 +1,233 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::send::print_simulation;
+use common::token::{self, TokenAccountInfo};
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_token_amount};
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_config::{
+    RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig,
+};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use std::process::ExitCode;
+
+/// Burn tokens from a token account with `burn_checked`, then close the
+/// emptied account with `close_account` to reclaim its rent. With --dry-run
+/// the transaction is only simulated and the expected refund printed.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Owner of the token account
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Mint of the tokens to burn
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    /// Amount to burn in whole tokens (e.g. 1.5), or `all` for the whole
+    /// balance
+    #[arg(short, long, default_value = "all")]
+    amount: String,
+
+    /// Token account to burn from; defaults to the owner's associated token
+    /// account
+    #[arg(long)]
+    account: Option<Pubkey>,
+
+    /// Wallet that receives the reclaimed rent; defaults to the owner
+    #[arg(long)]
+    rent_to: Option<Pubkey>,
+
+    /// Only burn, leaving the account open even if it ends up empty
+    #[arg(long)]
+    keep_open: bool,
+
+    /// Simulate the transaction instead of sending it
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct BurnAndClose {
+    account: String,
+    mint: String,
+    /// Amount burned, in base units
+    burned: u64,
+    /// Balance left in the account, in base units
+    remaining: u64,
+    closed: bool,
+    rent_to: String,
+    /// Lamports returned by closing the account
+    refund: u64,
+    dry_run: bool,
+    /// Net lamport change of `rent_to` in the simulation, after fees
+    simulated_change: Option<i64>,
+    signature: Option<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let owner = args.keypair.load()?;
+    let output = &args.output;
+
+    let mint = token::fetch_mint(&connection, &args.mint)?;
+    let program_id = mint.program_id;
+    let address = args.account.unwrap_or_else(|| {
+        get_associated_token_address_with_program_id(&owner.pubkey(), &args.mint, &program_id)
+    });
+    let account = connection
+        .get_account_with_commitment(&address, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(address))?;
+    let info = TokenAccountInfo::unpack(&address, &account)?;
+    if info.account.mint != args.mint {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{address} holds {}, not {}",
+            info.account.mint, args.mint
+        )));
+    }
+
+    let balance = info.account.amount;
+    let burned = if args.amount == "all" {
+        balance
+    } else {
+        parse_token_amount(&args.amount, mint.decimals())?
+    };
+    if burned > balance {
+        return Err(ExampleError::InvalidArgument(format!(
+            "cannot burn {} tokens, the account holds {}",
+            mint.ui_amount(burned),
+            mint.ui_amount(balance)
+        )));
+    }
+    let remaining = balance - burned;
+    // Token-2022 refuses to close accounts still holding withheld transfer
+    // fees; those have to be harvested to the mint first.
+    let close = !args.keep_open && remaining == 0 && info.withheld == 0;
+    if !args.keep_open && !close {
+        output.status(format_args!(
+            "{address} will not be empty afterwards ({} tokens, {} withheld), so it stays open",
+            mint.ui_amount(remaining),
+            mint.ui_amount(info.withheld)
+        ));
+    }
+    let rent_to = args.rent_to.unwrap_or(owner.pubkey());
+
+    let mut instructions = Vec::with_capacity(2);
+    if burned > 0 {
+        instructions.push(spl_token_2022::instruction::burn_checked(
+            &program_id,
+            &address,
+            &args.mint,
+            &owner.pubkey(),
+            &[],
+            burned,
+            mint.decimals(),
+        )?);
+    }
+    if close {
+        instructions.push(spl_token_2022::instruction::close_account(
+            &program_id,
+            &address,
+            &rent_to,
+            &owner.pubkey(),
+            &[],
+        )?);
+    }
+    if instructions.is_empty() {
+        return Err(ExampleError::InvalidArgument(
+            "nothing to burn and the account stays open".to_string(),
+        ));
+    }
+    let refund = if close { account.lamports } else { 0 };
+
+    output.status(format_args!(
+        "Burning {} of {} tokens in {address}{}",
+        mint.ui_amount(burned),
+        mint.ui_amount(balance),
+        if close {
+            format!(
+                ", then closing it ({} SOL to {rent_to})",
+                format_sol(refund)
+            )
+        } else {
+            String::new()
+        }
+    ));
+
+    let mut result = BurnAndClose {
+        account: address.to_string(),
+        mint: args.mint.to_string(),
+        burned,
+        remaining,
+        closed: close,
+        rent_to: rent_to.to_string(),
+        refund,
+        dry_run: args.dry_run,
+        simulated_change: None,
+        signature: None,
+    };
+
+    let transaction = TxBuilder::new(&owner).add_instructions(instructions);
+    if args.dry_run {
+        let transaction = transaction.build(connection.get_latest_blockhash()?)?;
+        let before = connection.get_balance(&rent_to)?;
+        let simulation = connection
+            .simulate_transaction_with_config(
+                &transaction,
+                RpcSimulateTransactionConfig {
+                    commitment: Some(connection.commitment()),
+                    accounts: Some(RpcSimulateTransactionAccountsConfig {
+                        encoding: Some(UiAccountEncoding::Base64),
+                        addresses: vec![rent_to.to_string()],
+                    }),
+                    ..RpcSimulateTransactionConfig::default()
+                },
+            )?
+            .value;
+        if output.is_table() {
+            print_simulation(&simulation);
+        }
+        if let Some(err) = simulation.err {
+            return Err(ExampleError::SimulationFailed(err));
+        }
+        let after = simulation
+            .accounts
+            .and_then(|accounts| accounts.into_iter().next().flatten())
+            .map(|account| account.lamports);
+        result.simulated_change = after.map(|after| after as i64 - before as i64);
+        if let Some(change) = result.simulated_change {
+            output.status(format_args!(
+                "Dry run: {rent_to} would change by {}{} SOL ({} SOL refund, net of any fee it pays)",
+                if change < 0 { "-" } else { "+" },
+                format_sol(change.unsigned_abs()),
+                format_sol(refund)
+            ));
+        }
+    } else {
+        let signature = transaction.sign_and_send(&connection)?;
+        output.status(format_args!("Signature: {signature}"));
+        output.status(format_args!(
+            "Explorer:  {}",
+            explorer::transaction_url(&signature, &args.rpc.rpc_url)
+        ));
+        result.signature = Some(signature.to_string());
+    }
+    output.print_structured(&result)
+}
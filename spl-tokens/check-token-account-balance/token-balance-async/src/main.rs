@@ -0,0 +1,103 @@
+use clap::Parser;
+use common::Result;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use futures::future::join_all;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+use std::time::Instant;
+
+/// Fetch the balances of many SPL token accounts concurrently with the
+/// nonblocking RPC client.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Token account addresses (base58) to query
+    #[arg(required = true)]
+    accounts: Vec<Pubkey>,
+
+    /// Await each request before sending the next one, to compare timings
+    #[arg(long)]
+    sequential: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct BalanceRow {
+    account: String,
+    balance: Option<String>,
+    error: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.nonblocking_client();
+
+    let started = Instant::now();
+    let results = if args.sequential {
+        let mut results = Vec::with_capacity(args.accounts.len());
+        for account in &args.accounts {
+            results.push(connection.get_token_account_balance(account).await);
+        }
+        results
+    } else {
+        join_all(
+            args.accounts
+                .iter()
+                .map(|account| connection.get_token_account_balance(account)),
+        )
+        .await
+    };
+    let elapsed = started.elapsed();
+
+    let rows: Vec<BalanceRow> = args
+        .accounts
+        .iter()
+        .zip(results)
+        .map(|(account, result)| {
+            let (balance, error) = match result {
+                Ok(balance) => (Some(balance.ui_amount_string), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            BalanceRow {
+                account: account.to_string(),
+                balance,
+                error,
+            }
+        })
+        .collect();
+    args.output.print(&rows, || {
+        for row in &rows {
+            match (&row.balance, &row.error) {
+                (Some(balance), _) => println!("{:<44}  {balance}", row.account),
+                (None, error) => {
+                    println!(
+                        "{:<44}  error: {}",
+                        row.account,
+                        error.as_deref().unwrap_or("")
+                    )
+                }
+            }
+        }
+    })?;
+    args.output.status(format_args!(
+        "Fetched {} balances {} in {elapsed:?}",
+        args.accounts.len(),
+        if args.sequential {
+            "sequentially"
+        } else {
+            "concurrently"
+        }
+    ));
+    Ok(())
+}
@@ -1,15 +1,152 @@
-use solana_client::rpc_client::RpcClient;
+use clap::Parser;
+use common::cli::{AddressArg, RpcArgs};
+use common::output::OutputArgs;
+use common::price::{self, DEFAULT_PRICE_API_URL};
+use common::token_list::TokenList;
+use common::{ExampleError, Result, RetryingRpcClient, metaplex, token};
+use serde::Serialize;
 use solana_sdk::pubkey::Pubkey;
-use std::str::FromStr;
-const TOKEN_ADDRESS: &str = "Token address";
-fn main() {
-    let associated_token_account = Pubkey::from_str(TOKEN_ADDRESS).unwrap();
-    let connection = RpcClient::new("https://api.mainnet-beta.solana.com".to_string());
-    let account_data = connection
-        .get_token_account_balance(&associated_token_address)
-        .unwrap();
-    println!(
-        "Token Balance (using Rust): {}",
-        account_data.ui_amount_string
-    );
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Print the balance of an SPL token account owned by either the Token or the
+/// Token-2022 program.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Token account address (base58), or `wallet:mint` for the wallet's
+    /// associated token account, where the wallet may be a `.sol` domain
+    #[arg(short, long)]
+    account: AddressArg,
+
+    /// Also resolve the mint's name and symbol and value the balance in USD
+    #[arg(short, long)]
+    enrich: bool,
+
+    /// Token-list JSON consulted before the mint's Metaplex metadata
+    #[arg(long, requires = "enrich")]
+    token_list: Option<PathBuf>,
+
+    /// Jupiter price API endpoint
+    #[arg(long, env = "JUPITER_PRICE_API_URL", default_value = DEFAULT_PRICE_API_URL)]
+    price_api: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct BalanceReport {
+    account: String,
+    mint: String,
+    /// UI amount as computed by the node
+    balance: String,
+    program: &'static str,
+    extensions: Vec<String>,
+    /// Transfer fees withheld in the account, in base units
+    withheld: u64,
+    name: Option<String>,
+    symbol: Option<String>,
+    usd_price: Option<f64>,
+    usd_value: Option<f64>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.retrying_client();
+    let address = connection.call(|client| args.account.resolve(client))?;
+    let account = connection
+        .call(|client| Ok(client.get_account_with_commitment(&address, client.commitment())?))?
+        .value
+        .ok_or(ExampleError::AccountNotFound(address))?;
+    let info = token::TokenAccountInfo::unpack(&address, &account)?;
+
+    // The RPC node applies extensions such as interest-bearing rates when
+    // computing the UI amount, so ask it rather than formatting locally.
+    let account_data = connection.call(|client| Ok(client.get_token_account_balance(&address)?))?;
+    let mut report = BalanceReport {
+        account: address.to_string(),
+        mint: info.account.mint.to_string(),
+        balance: account_data.ui_amount_string.clone(),
+        program: token::program_name(&info.program_id),
+        extensions: info
+            .extensions
+            .iter()
+            .map(|ext| format!("{ext:?}"))
+            .collect(),
+        withheld: info.withheld,
+        name: None,
+        symbol: None,
+        usd_price: None,
+        usd_value: None,
+    };
+    if args.enrich {
+        let mint = info.account.mint;
+        let token_list = args
+            .token_list
+            .as_ref()
+            .map(TokenList::from_file)
+            .transpose()?;
+        if let Some((name, symbol)) = token_name(&connection, token_list.as_ref(), &mint) {
+            report.name = Some(name);
+            report.symbol = Some(symbol);
+        }
+        let http = reqwest::blocking::Client::new();
+        let prices = price::fetch_usd_prices(&http, &args.price_api, &[mint])?;
+        if let Some(&usd_price) = prices.get(&mint) {
+            let amount: f64 = account_data.ui_amount_string.parse().map_err(|_| {
+                ExampleError::Parse(format!("UI amount {}", account_data.ui_amount_string))
+            })?;
+            report.usd_price = Some(usd_price);
+            report.usd_value = Some(amount * usd_price);
+        }
+    }
+
+    args.output.print(&report, || {
+        println!("Token Balance (using Rust): {}", report.balance);
+        println!("Token program: {}", report.program);
+        if !report.extensions.is_empty() {
+            println!("Extensions:    {}", report.extensions.join(", "));
+        }
+        if report.withheld > 0 {
+            println!("Withheld fees: {} base units", report.withheld);
+        }
+        if !args.enrich {
+            return;
+        }
+        match (&report.name, &report.symbol) {
+            (Some(name), Some(symbol)) => println!("Token:         {name} ({symbol})"),
+            _ => println!("Token:         unknown mint {}", report.mint),
+        }
+        match (report.usd_price, report.usd_value) {
+            (Some(usd_price), Some(usd_value)) => {
+                println!("Price:         ${usd_price}");
+                println!("Value:         ${usd_value:.2}");
+            }
+            _ => println!("Price:         Jupiter has no USD price for this mint"),
+        }
+    })?;
+    Ok(())
+}
+
+/// Name and symbol from the token list if it knows the mint, otherwise from
+/// the Metaplex metadata account; many mints have neither.
+fn token_name(
+    connection: &RetryingRpcClient,
+    token_list: Option<&TokenList>,
+    mint: &Pubkey,
+) -> Option<(String, String)> {
+    if let Some(entry) = token_list.and_then(|list| list.get(mint)) {
+        return Some((entry.name.clone(), entry.symbol.clone()));
+    }
+    let metadata = connection
+        .call(|client| metaplex::fetch_metadata(client, mint))
+        .ok()?;
+    Some((metadata.name, metadata.symbol))
 }
@@ -0,0 +1,290 @@
+use clap::Parser;
+use common::blockhash::BlockhashCache;
+use common::cli::{AddressArg, KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::token::{self, MintInfo};
+use common::tx_builder::TxBuilder;
+use common::tx_size::TransactionBudget;
+use common::units::format_sol;
+use common::{ExampleError, Result, batch, explorer, fees};
+use serde::Serialize;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_associated_token_account_client::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::ExtensionType;
+use spl_token_2022::state::Account as TokenAccount;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Create the associated token accounts a list of wallets is missing for a
+/// mint, the step before an airdrop can send them anything.
+///
+/// The wallets' ATAs are looked up first, and the rent and fees creating the
+/// missing ones will cost are worked out and checked against the payer's
+/// balance before anything is sent. The creates are packed into as few
+/// transactions as fit. They are idempotent, so a run that stopped part way
+/// can simply be repeated, and an account someone else creates in the
+/// meantime does not fail the transaction.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Pays the rent and fees; does not need to be any of the wallets
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Mint to create the token accounts for
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    /// File with one wallet address per line ('#' starts a comment)
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+
+    /// Only report what is missing and what creating it would cost
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Wallets, in addition to --file: addresses (base58), `.sol` domains or
+    /// keypair files
+    wallets: Vec<AddressArg>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct WalletAta {
+    wallet: String,
+    account: String,
+    status: AtaStatus,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum AtaStatus {
+    Exists,
+    Missing,
+    Created,
+}
+
+#[derive(Serialize)]
+struct AtaCreation {
+    mint: String,
+    program: &'static str,
+    wallets: usize,
+    existing: usize,
+    missing: usize,
+    /// Size of each new account, which Token-2022 mint extensions can grow
+    account_size: usize,
+    /// Lamports locked in each new account
+    rent_per_account: u64,
+    rent: u64,
+    /// Transaction fees for all batches, in lamports
+    fees: u64,
+    transactions: usize,
+    dry_run: bool,
+    signatures: Vec<String>,
+    accounts: Vec<WalletAta>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    let mut wallets = args
+        .wallets
+        .iter()
+        .map(|wallet| wallet.resolve(&connection))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(path) = &args.file {
+        wallets.extend(batch::read_keys_file(path)?);
+    }
+    let mut seen = HashSet::new();
+    wallets.retain(|wallet| seen.insert(*wallet));
+    if wallets.is_empty() {
+        return Err(ExampleError::InvalidArgument(
+            "no wallets given; pass addresses or --file".to_string(),
+        ));
+    }
+
+    let mint = token::fetch_mint(&connection, &args.mint)?;
+    let program_id = mint.program_id;
+    let addresses: Vec<Pubkey> = wallets
+        .iter()
+        .map(|wallet| get_associated_token_address_with_program_id(wallet, &args.mint, &program_id))
+        .collect();
+    let mut accounts: Vec<WalletAta> = wallets
+        .iter()
+        .zip(&addresses)
+        .zip(batch::get_multiple_accounts(&connection, &addresses)?)
+        .map(|((wallet, address), account)| WalletAta {
+            wallet: wallet.to_string(),
+            account: address.to_string(),
+            status: match account {
+                Some(_) => AtaStatus::Exists,
+                None => AtaStatus::Missing,
+            },
+        })
+        .collect();
+
+    let mut transactions: Vec<Vec<usize>> = Vec::new();
+    let instructions_of = |indices: &[usize]| -> Vec<Instruction> {
+        indices
+            .iter()
+            .map(|&index| {
+                create_associated_token_account_idempotent(
+                    &payer.pubkey(),
+                    &wallets[index],
+                    &args.mint,
+                    &program_id,
+                )
+            })
+            .collect()
+    };
+    for (index, ata) in accounts.iter().enumerate() {
+        if ata.status != AtaStatus::Missing {
+            continue;
+        }
+        let fits = transactions.last().is_some_and(|last| {
+            let mut combined = last.clone();
+            combined.push(index);
+            TransactionBudget::measure(&instructions_of(&combined), &payer.pubkey()).fits()
+        });
+        match transactions.last_mut() {
+            Some(last) if fits => last.push(index),
+            _ => transactions.push(vec![index]),
+        }
+    }
+    let batches: Vec<Vec<Instruction>> = transactions
+        .iter()
+        .map(|indices| instructions_of(indices))
+        .collect();
+
+    let missing = batches.iter().map(Vec::len).sum::<usize>();
+    let account_size = account_size(&mint)?;
+    let rent_per_account = connection.get_minimum_balance_for_rent_exemption(account_size)?;
+    let cache = BlockhashCache::default();
+    let mut fee_total = 0;
+    if !batches.is_empty() {
+        let blockhash = cache.get(&connection)?.blockhash;
+        for instructions in &batches {
+            fee_total +=
+                fees::estimate_fee(&connection, &payer.pubkey(), instructions, None, &blockhash)?
+                    .total();
+        }
+    }
+    let rent = rent_per_account * missing as u64;
+    output.status(format_args!(
+        "{} of {} wallets have no {} account for {}; creating them costs {} SOL rent \
+         plus {} SOL in fees over {} transactions",
+        missing,
+        wallets.len(),
+        token::program_name(&program_id),
+        args.mint,
+        format_sol(rent),
+        format_sol(fee_total),
+        batches.len()
+    ));
+
+    let mut signatures = Vec::new();
+    if !args.dry_run && !batches.is_empty() {
+        let balance = connection.get_balance(&payer.pubkey())?;
+        if balance < rent + fee_total {
+            return Err(ExampleError::InvalidArgument(format!(
+                "{} holds {} SOL but creating the accounts needs {}",
+                payer.pubkey(),
+                format_sol(balance),
+                format_sol(rent + fee_total)
+            )));
+        }
+        for (number, (instructions, indices)) in batches.iter().zip(&transactions).enumerate() {
+            let signature = TxBuilder::new(&payer)
+                .add_instructions(instructions.iter().cloned())
+                .with_blockhash_cache(&cache)
+                .sign_and_send(&connection)?;
+            output.status(format_args!(
+                "Batch {}/{}: {} accounts, {signature}",
+                number + 1,
+                batches.len(),
+                indices.len()
+            ));
+            output.status(format_args!(
+                "Explorer:  {}",
+                explorer::transaction_url(&signature, &args.rpc.rpc_url)
+            ));
+            for &index in indices {
+                accounts[index].status = AtaStatus::Created;
+            }
+            signatures.push(signature.to_string());
+        }
+    }
+
+    let creation = AtaCreation {
+        mint: args.mint.to_string(),
+        program: token::program_name(&program_id),
+        wallets: wallets.len(),
+        existing: wallets.len() - missing,
+        missing,
+        account_size,
+        rent_per_account,
+        rent,
+        fees: fee_total,
+        transactions: batches.len(),
+        dry_run: args.dry_run,
+        signatures,
+        accounts,
+    };
+    output.print(&creation, || {
+        println!("{:<44}  {:<44}  {:<8}", "Wallet", "Token account", "Status");
+        for ata in &creation.accounts {
+            let status = match ata.status {
+                AtaStatus::Exists => "exists",
+                AtaStatus::Missing => "missing",
+                AtaStatus::Created => "created",
+            };
+            println!("{:<44}  {:<44}  {:<8}", ata.wallet, ata.account, status);
+        }
+        println!(
+            "{} accounts {}, {} SOL rent ({} bytes, {} SOL each) and {} SOL in fees",
+            creation.missing,
+            if creation.dry_run {
+                "would be created"
+            } else {
+                "created"
+            },
+            format_sol(creation.rent),
+            creation.account_size,
+            format_sol(creation.rent_per_account),
+            format_sol(creation.fees)
+        );
+    })
+}
+
+/// The size the associated token account program allocates: a plain token
+/// account for Token, and for Token-2022 one with room for the immutable
+/// owner extension plus whatever the mint's extensions require of accounts.
+fn account_size(mint: &MintInfo) -> Result<usize> {
+    if mint.program_id == spl_token_2022::ID {
+        let mut extensions = ExtensionType::get_required_init_account_extensions(&mint.extensions);
+        if !extensions.contains(&ExtensionType::ImmutableOwner) {
+            extensions.push(ExtensionType::ImmutableOwner);
+        }
+        Ok(ExtensionType::try_calculate_account_len::<TokenAccount>(
+            &extensions,
+        )?)
+    } else {
+        Ok(TokenAccount::LEN)
+    }
+}
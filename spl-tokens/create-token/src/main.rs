@@ -0,0 +1,134 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::parse_token_amount;
+use common::{Result, explorer};
+use serde::Serialize;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_client::address::get_associated_token_address;
+use spl_associated_token_account_client::instruction::create_associated_token_account;
+use spl_token::state::Mint;
+use std::process::ExitCode;
+
+/// Create a new SPL token mint and mint an initial supply to the payer.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer, which also becomes the mint and freeze authority
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Number of decimals for the new mint
+    #[arg(short, long, default_value_t = 9)]
+    decimals: u8,
+
+    /// Initial supply to mint to the payer, in whole tokens
+    #[arg(short, long, default_value = "1000")]
+    supply: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct CreatedToken {
+    mint: String,
+    decimals: u8,
+    token_account: String,
+    supply: String,
+    create_signature: String,
+    mint_signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let supply = parse_token_amount(&args.supply, args.decimals)?;
+    let mint = Keypair::new();
+
+    let rent = connection.get_minimum_balance_for_rent_exemption(Mint::LEN)?;
+    let output = &args.output;
+    output.status(format_args!("Mint address: {}", mint.pubkey()));
+    output.status(format_args!(
+        "Rent-exempt minimum for {} bytes: {rent} lamports",
+        Mint::LEN
+    ));
+
+    let create_mint = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &payer.pubkey(),
+            Some(&payer.pubkey()),
+            args.decimals,
+        )?,
+    ];
+    let create_signature = TxBuilder::new(&payer)
+        .add_instructions(create_mint)
+        .add_signer(&mint)
+        .sign_and_send(&connection)?;
+    print_step(output, "Created mint", &create_signature, &args.rpc.rpc_url);
+
+    let token_account = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let mint_supply = [
+        create_associated_token_account(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &mint.pubkey(),
+            &spl_token::id(),
+        ),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &token_account,
+            &payer.pubkey(),
+            &[],
+            supply,
+        )?,
+    ];
+    let mint_signature = TxBuilder::new(&payer)
+        .add_instructions(mint_supply)
+        .sign_and_send(&connection)?;
+    print_step(
+        output,
+        &format!("Minted {} tokens to {token_account}", args.supply),
+        &mint_signature,
+        &args.rpc.rpc_url,
+    );
+
+    let created = CreatedToken {
+        mint: mint.pubkey().to_string(),
+        decimals: args.decimals,
+        token_account: token_account.to_string(),
+        supply: args.supply.clone(),
+        create_signature: create_signature.to_string(),
+        mint_signature: mint_signature.to_string(),
+    };
+    output.print_structured(&created)
+}
+
+fn print_step(output: &OutputArgs, step: &str, signature: &Signature, rpc_url: &str) {
+    output.status(step);
+    output.status(format_args!("  Signature: {signature}"));
+    output.status(format_args!(
+        "  Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
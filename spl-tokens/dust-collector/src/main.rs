@@ -0,0 +1,310 @@
+use clap::Parser;
+use common::blockhash::BlockhashCache;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::send::print_simulation;
+use common::token::{self, MintInfo, TokenHolding};
+use common::tx_builder::TxBuilder;
+use common::tx_size::TransactionBudget;
+use common::units::format_sol;
+use common::{ExampleError, Result, batch, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+/// Sweep a wallet's token accounts: every empty account is closed, and
+/// accounts holding less than --dust-below tokens have that dust burned
+/// first, returning the rent locked in each to the wallet. The burns and
+/// closes are packed into as few transactions as fit.
+///
+/// Frozen accounts, accounts another key can close and Token-2022 accounts
+/// holding withheld transfer fees cannot be closed by the owner and are
+/// skipped. Wrapped SOL is closed without burning; its balance is unwrapped
+/// along with the rent.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Owner of the token accounts, which also receives the rent
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Also burn and close accounts holding fewer than this many whole
+    /// tokens (e.g. 0.001); only empty accounts are closed when omitted
+    #[arg(long)]
+    dust_below: Option<f64>,
+
+    /// Mint whose accounts are left alone; repeat for several
+    #[arg(long = "keep")]
+    keep: Vec<Pubkey>,
+
+    /// Simulate the transactions instead of sending them
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct SweptAccount {
+    account: String,
+    mint: String,
+    program: &'static str,
+    /// Balance in base units
+    amount: u64,
+    ui_amount: String,
+    action: Action,
+    /// Lamports returned by closing the account
+    rent: u64,
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum Action {
+    Close,
+    BurnAndClose,
+    SkipFrozen,
+    SkipCloseAuthority,
+    SkipWithheldFees,
+}
+
+impl Action {
+    fn label(self) -> &'static str {
+        match self {
+            Action::Close => "close",
+            Action::BurnAndClose => "burn + close",
+            Action::SkipFrozen => "skip: frozen",
+            Action::SkipCloseAuthority => "skip: close authority",
+            Action::SkipWithheldFees => "skip: withheld fees",
+        }
+    }
+
+    fn closes(self) -> bool {
+        matches!(self, Action::Close | Action::BurnAndClose)
+    }
+}
+
+#[derive(Serialize)]
+struct DustSweep {
+    owner: String,
+    accounts: Vec<SweptAccount>,
+    closed: usize,
+    /// Rent returned to the owner, before fees
+    reclaimed: u64,
+    transactions: usize,
+    dry_run: bool,
+    signatures: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let owner = args.keypair.load()?;
+    let output = &args.output;
+
+    let holdings = token::fetch_token_accounts(&connection, &owner.pubkey())?;
+    let mints = fetch_mints(&connection, &holdings)?;
+    output.status(format_args!(
+        "{} token accounts owned by {}",
+        holdings.len(),
+        owner.pubkey()
+    ));
+
+    let mut accounts = Vec::new();
+    let mut transactions: Vec<Vec<Instruction>> = Vec::new();
+    for TokenHolding {
+        address,
+        lamports,
+        info,
+    } in &holdings
+    {
+        let state = &info.account;
+        let mint = &mints[&state.mint];
+        if args.keep.contains(&state.mint) || !is_dust(mint, state.amount, args.dust_below) {
+            continue;
+        }
+        let close_authority: Option<Pubkey> = state.close_authority.into();
+        let action = if state.is_frozen() {
+            Action::SkipFrozen
+        } else if close_authority.is_some_and(|authority| authority != owner.pubkey()) {
+            Action::SkipCloseAuthority
+        } else if info.withheld > 0 {
+            Action::SkipWithheldFees
+        } else if state.amount > 0 && !state.is_native() {
+            Action::BurnAndClose
+        } else {
+            Action::Close
+        };
+
+        if action.closes() {
+            let mut instructions = Vec::with_capacity(2);
+            if action == Action::BurnAndClose {
+                instructions.push(spl_token_2022::instruction::burn_checked(
+                    &info.program_id,
+                    address,
+                    &state.mint,
+                    &owner.pubkey(),
+                    &[],
+                    state.amount,
+                    mint.decimals(),
+                )?);
+            }
+            instructions.push(spl_token_2022::instruction::close_account(
+                &info.program_id,
+                address,
+                &owner.pubkey(),
+                &owner.pubkey(),
+                &[],
+            )?);
+            // A burn and its close always share a transaction, so no account
+            // is left burned but open.
+            let fits = transactions.last().is_some_and(|last| {
+                let combined: Vec<Instruction> =
+                    last.iter().chain(&instructions).cloned().collect();
+                TransactionBudget::measure(&combined, &owner.pubkey()).fits()
+            });
+            match transactions.last_mut() {
+                Some(last) if fits => last.extend(instructions),
+                _ => transactions.push(instructions),
+            }
+        }
+        accounts.push(SweptAccount {
+            account: address.to_string(),
+            mint: state.mint.to_string(),
+            program: token::program_name(&info.program_id),
+            amount: state.amount,
+            ui_amount: mint.ui_amount(state.amount),
+            action,
+            rent: if action.closes() { *lamports } else { 0 },
+        });
+    }
+
+    let closed = accounts
+        .iter()
+        .filter(|swept| swept.action.closes())
+        .count();
+    let reclaimed = accounts.iter().map(|swept| swept.rent).sum();
+    output.status(format_args!(
+        "Closing {closed} accounts in {} transactions to reclaim {} SOL",
+        transactions.len(),
+        format_sol(reclaimed)
+    ));
+    if let Some(largest) = transactions
+        .iter()
+        .map(|instructions| TransactionBudget::measure(instructions, &owner.pubkey()))
+        .max_by_key(|budget| budget.size)
+    {
+        output.status(format_args!("Largest transaction: {largest}"));
+    }
+
+    let mut signatures = Vec::new();
+    let cache = BlockhashCache::default();
+    for instructions in &transactions {
+        let transaction = TxBuilder::new(&owner)
+            .add_instructions(instructions.iter().cloned())
+            .with_blockhash_cache(&cache);
+        if args.dry_run {
+            let transaction = transaction.build(cache.get(&connection)?.blockhash)?;
+            let simulation = connection
+                .simulate_transaction_with_config(
+                    &transaction,
+                    RpcSimulateTransactionConfig {
+                        commitment: Some(connection.commitment()),
+                        ..RpcSimulateTransactionConfig::default()
+                    },
+                )?
+                .value;
+            if output.is_table() {
+                print_simulation(&simulation);
+            }
+            if let Some(err) = simulation.err {
+                return Err(ExampleError::SimulationFailed(err));
+            }
+        } else {
+            let signature = transaction.sign_and_send(&connection)?;
+            output.status(format_args!("Signature: {signature}"));
+            output.status(format_args!(
+                "Explorer:  {}",
+                explorer::transaction_url(&signature, &args.rpc.rpc_url)
+            ));
+            signatures.push(signature.to_string());
+        }
+    }
+
+    let sweep = DustSweep {
+        owner: owner.pubkey().to_string(),
+        closed,
+        reclaimed,
+        transactions: transactions.len(),
+        dry_run: args.dry_run,
+        signatures,
+        accounts,
+    };
+    output.print(&sweep, || {
+        if sweep.accounts.is_empty() {
+            println!("No empty or dust accounts found");
+            return;
+        }
+        println!(
+            "{:<44}  {:<44}  {:>20}  {:<22}  {:>12}",
+            "Account", "Mint", "UI Amount", "Action", "Rent (SOL)"
+        );
+        for swept in &sweep.accounts {
+            println!(
+                "{:<44}  {:<44}  {:>20}  {:<22}  {:>12}",
+                swept.account,
+                swept.mint,
+                swept.ui_amount,
+                swept.action.label(),
+                format_sol(swept.rent)
+            );
+        }
+        println!(
+            "{} accounts {}closed, {} SOL reclaimed",
+            sweep.closed,
+            if sweep.dry_run { "would be " } else { "" },
+            format_sol(sweep.reclaimed)
+        );
+    })
+}
+
+/// Whether a balance is small enough to sweep. Without a threshold only
+/// empty accounts qualify.
+fn is_dust(mint: &MintInfo, amount: u64, dust_below: Option<f64>) -> bool {
+    match dust_below {
+        _ if amount == 0 => true,
+        Some(threshold) => (amount as f64 / 10f64.powi(mint.decimals().into())) < threshold,
+        None => false,
+    }
+}
+
+fn fetch_mints(
+    connection: &RpcClient,
+    holdings: &[TokenHolding],
+) -> Result<HashMap<Pubkey, MintInfo>> {
+    let mut mints: Vec<Pubkey> = holdings
+        .iter()
+        .map(|holding| holding.info.account.mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+
+    let accounts = batch::get_multiple_accounts(connection, &mints)?;
+    let mut infos = HashMap::with_capacity(mints.len());
+    for (mint, account) in mints.iter().zip(accounts) {
+        let account = account.ok_or(ExampleError::AccountNotFound(*mint))?;
+        infos.insert(*mint, MintInfo::unpack(mint, &account)?);
+    }
+    Ok(infos)
+}
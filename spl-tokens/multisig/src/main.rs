@@ -0,0 +1,246 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::parse_token_amount;
+use common::{ExampleError, Result, explorer, keys};
+use serde::Serialize;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_client::address::get_associated_token_address;
+use spl_associated_token_account_client::instruction::create_associated_token_account;
+use spl_token::instruction::AuthorityType;
+use spl_token::state::{Mint, Multisig};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Create an M-of-N SPL token multisig, hand it a new mint's mint
+/// authority, then mint tokens with signatures from M of the N members.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Pays for every transaction and is the mint's initial authority
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Keypair files of the multisig members (repeatable); by default three
+    /// new keypairs are generated and saved as multisig-signer-<n>.json
+    #[arg(short, long)]
+    signer: Vec<PathBuf>,
+
+    /// Signatures required to act as the multisig
+    #[arg(short = 'm', long, default_value_t = 2)]
+    threshold: u8,
+
+    #[arg(short, long, default_value_t = 9)]
+    decimals: u8,
+
+    /// Tokens to mint through the multisig, in whole tokens
+    #[arg(short, long, default_value = "100")]
+    amount: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Members generated when no --signer files are given.
+const DEFAULT_MEMBERS: usize = 3;
+
+#[derive(Serialize)]
+struct MultisigMint {
+    multisig: String,
+    threshold: u8,
+    members: Vec<String>,
+    mint: String,
+    token_account: String,
+    amount: String,
+    signatures: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let members = load_members(output, &args.signer)?;
+    let threshold = usize::from(args.threshold);
+    if threshold == 0 || threshold > members.len() {
+        return Err(ExampleError::InvalidArgument(format!(
+            "the threshold must be between 1 and the {} members",
+            members.len()
+        )));
+    }
+    let amount = parse_token_amount(&args.amount, args.decimals)?;
+    let member_keys: Vec<Pubkey> = members.iter().map(Keypair::pubkey).collect();
+    let member_refs: Vec<&Pubkey> = member_keys.iter().collect();
+
+    // 1. The multisig is a token program account listing the member keys.
+    let multisig = Keypair::new();
+    let rent = connection.get_minimum_balance_for_rent_exemption(Multisig::LEN)?;
+    let create_multisig = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &multisig.pubkey(),
+            rent,
+            Multisig::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_multisig2(
+            &spl_token::id(),
+            &multisig.pubkey(),
+            &member_refs,
+            args.threshold,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(create_multisig)
+        .add_signer(&multisig)
+        .sign_and_send(&connection)?;
+    let mut signatures = vec![signature.to_string()];
+    print_step(
+        output,
+        &format!(
+            "Created {}-of-{} multisig {}",
+            args.threshold,
+            members.len(),
+            multisig.pubkey()
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    // 2. Create a mint with the payer as authority, then transfer the mint
+    //    authority to the multisig.
+    let mint = Keypair::new();
+    let rent = connection.get_minimum_balance_for_rent_exemption(Mint::LEN)?;
+    let create_mint = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint2(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            args.decimals,
+        )?,
+        spl_token::instruction::set_authority(
+            &spl_token::id(),
+            &mint.pubkey(),
+            Some(&multisig.pubkey()),
+            AuthorityType::MintTokens,
+            &payer.pubkey(),
+            &[],
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(create_mint)
+        .add_signer(&mint)
+        .sign_and_send(&connection)?;
+    signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!(
+            "Created mint {} with the multisig as mint authority",
+            mint.pubkey()
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    // 3. Mint through the multisig. The instruction names the multisig as
+    //    the authority and lists the members who sign; those members must
+    //    also sign the transaction itself, while the multisig account never
+    //    signs.
+    let cosigners = &members[..threshold];
+    let cosigner_keys: Vec<&Pubkey> = member_refs[..threshold].to_vec();
+    let token_account = get_associated_token_address(&payer.pubkey(), &mint.pubkey());
+    let mint_supply = [
+        create_associated_token_account(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &mint.pubkey(),
+            &spl_token::id(),
+        ),
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &token_account,
+            &multisig.pubkey(),
+            &cosigner_keys,
+            amount,
+        )?,
+    ];
+    let signature = cosigners
+        .iter()
+        .fold(TxBuilder::new(&payer), |transaction, cosigner| {
+            transaction.add_signer(cosigner)
+        })
+        .add_instructions(mint_supply)
+        .sign_and_send(&connection)?;
+    signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!(
+            "Minted {} tokens to {token_account}, signed by {}",
+            args.amount,
+            cosigners
+                .iter()
+                .map(|member| member.pubkey().to_string())
+                .collect::<Vec<_>>()
+                .join(" and ")
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let created = MultisigMint {
+        multisig: multisig.pubkey().to_string(),
+        threshold: args.threshold,
+        members: member_keys.iter().map(Pubkey::to_string).collect(),
+        mint: mint.pubkey().to_string(),
+        token_account: token_account.to_string(),
+        amount: args.amount.clone(),
+        signatures,
+    };
+    output.print_structured(&created)
+}
+
+fn load_members(output: &OutputArgs, paths: &[PathBuf]) -> Result<Vec<Keypair>> {
+    if !paths.is_empty() {
+        return paths.iter().map(keys::read_keypair_file).collect();
+    }
+    (1..=DEFAULT_MEMBERS)
+        .map(|n| {
+            let keypair = Keypair::new();
+            let path = format!("multisig-signer-{n}.json");
+            keys::write_keypair_file(&keypair, &path)?;
+            output.status(format_args!(
+                "Member {n}: {} (saved to {path})",
+                keypair.pubkey()
+            ));
+            Ok(keypair)
+        })
+        .collect()
+}
+
+fn print_step(output: &OutputArgs, step: &str, signature: &Signature, rpc_url: &str) {
+    output.status(step);
+    output.status(format_args!("  Signature: {signature}"));
+    output.status(format_args!(
+        "  Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
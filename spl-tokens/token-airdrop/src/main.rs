@@ -0,0 +1,589 @@
+use clap::Parser;
+use common::blockhash::BlockhashCache;
+use common::cli::{AddressArg, CreditArgs, KeypairArgs, RpcArgs};
+use common::credits::{self, CreditStats};
+use common::output::OutputArgs;
+use common::tx_size::TransactionBudget;
+use common::units::parse_token_amount;
+use common::{ExampleError, Result, batch, token};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_associated_token_account_client::instruction::create_associated_token_account_idempotent;
+use spl_token_2022::extension::transfer_fee::instruction::transfer_checked_with_fee;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::{fs, thread};
+use tracing::error;
+
+/// Airdrop SPL tokens to the (address, amount) rows of a CSV file. Transfers
+/// are packed into as few transactions as fit the packet size limit, the
+/// recipients' associated token accounts are created where missing, and a
+/// few transactions are sent at once.
+///
+/// Every row's outcome is appended to a results CSV as it happens, and each
+/// transaction's signature is written there before it is sent. Rerunning
+/// with the same files checks those signatures on chain and only sends the
+/// rows that did not land, so an interrupted airdrop never pays anyone twice.
+///
+/// `--max-rps` and `--monthly-credits` keep a large airdrop within a metered
+/// plan's limits; the summary reports the credits it used.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Sender of the tokens, which also pays fees and rent
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Mint address of the token to send
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    /// CSV with `address` (wallet, not token account: base58, a `.sol`
+    /// domain or a keypair file) and `amount` (whole tokens, e.g. 1.5)
+    /// columns
+    recipients: PathBuf,
+
+    /// Results CSV; defaults to the recipients file with a `.results.csv`
+    /// extension
+    #[arg(long)]
+    results: Option<PathBuf>,
+
+    /// Transactions in flight at once
+    #[arg(long, default_value_t = 4)]
+    concurrency: usize,
+
+    #[command(flatten)]
+    credits: CreditArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Deserialize)]
+struct Recipient {
+    address: String,
+    amount: String,
+}
+
+/// One line of the results file. A row can appear several times; the last
+/// line wins.
+#[derive(Serialize, Deserialize, Clone)]
+struct RowResult {
+    /// 1-based data row of the recipients file
+    row: usize,
+    address: String,
+    amount: String,
+    status: RowStatus,
+    signature: Option<String>,
+    /// Block height after which `signature` can no longer land
+    last_valid_block_height: Option<u64>,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum RowStatus {
+    /// Signed and about to be sent; the outcome is unknown
+    Pending,
+    Sent,
+    Failed,
+}
+
+#[derive(Serialize)]
+struct AirdropSummary {
+    mint: String,
+    program: &'static str,
+    rows: usize,
+    /// Rows a previous run already delivered
+    already_sent: usize,
+    sent: usize,
+    failed: usize,
+    transactions: usize,
+    /// Associated token accounts that did not exist before this run
+    created_accounts: usize,
+    results: String,
+    /// What the run spent, when a credit limit or ledger was given
+    credits: Option<CreditStats>,
+}
+
+struct Transfer {
+    row: usize,
+    wallet: Pubkey,
+    amount: String,
+    base_units: u64,
+}
+
+/// The instructions of one transaction and the transfers they carry.
+struct Batch {
+    transfers: Vec<usize>,
+    instructions: Vec<Instruction>,
+}
+
+/// `getSignatureStatuses` accepts at most this many signatures per request.
+const MAX_SIGNATURE_STATUSES: usize = 256;
+
+const EXPIRY_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    // Confirmations are polled too, so calls over the rate are queued
+    // rather than shed unless asked.
+    args.credits.install()?;
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let results_path = args
+        .results
+        .clone()
+        .unwrap_or_else(|| args.recipients.with_extension("results.csv"));
+
+    let mint = token::fetch_mint(&connection, &args.mint)?;
+    let program_id = mint.program_id;
+    let decimals = mint.decimals();
+    let transfers = read_recipients(&connection, &args.recipients, decimals)?;
+    output.status(format_args!(
+        "{} rows in {}; token program: {}",
+        transfers.len(),
+        args.recipients.display(),
+        token::program_name(&program_id)
+    ));
+
+    let previous = read_results(&results_path, &transfers)?;
+    let mut results = ResultsFile::open(&results_path)?;
+    let delivered = reconcile(&connection, &previous, &mut results, output)?;
+    let pending: Vec<&Transfer> = transfers
+        .iter()
+        .filter(|transfer| !delivered.contains(&transfer.row))
+        .collect();
+    if !delivered.is_empty() {
+        output.status(format_args!(
+            "{} rows already sent according to {}",
+            delivered.len(),
+            results_path.display()
+        ));
+    }
+
+    let source =
+        get_associated_token_address_with_program_id(&payer.pubkey(), &args.mint, &program_id);
+    let needed: u64 = pending.iter().map(|transfer| transfer.base_units).sum();
+    if needed > 0 {
+        let balance: u64 = connection
+            .get_token_account_balance(&source)?
+            .amount
+            .parse()
+            .map_err(|_| ExampleError::Parse(format!("balance of {source}")))?;
+        if balance < needed {
+            return Err(ExampleError::InvalidArgument(format!(
+                "{source} holds {} tokens but the remaining rows need {}",
+                mint.ui_amount(balance),
+                mint.ui_amount(needed)
+            )));
+        }
+    }
+
+    // Look each destination up once, however many rows share it.
+    let destinations: Vec<Pubkey> = pending
+        .iter()
+        .map(|transfer| {
+            get_associated_token_address_with_program_id(&transfer.wallet, &args.mint, &program_id)
+        })
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    let existing: HashSet<Pubkey> = destinations
+        .iter()
+        .zip(batch::get_multiple_accounts(&connection, &destinations)?)
+        .filter_map(|(address, account)| account.map(|_| *address))
+        .collect();
+    let epoch = match mint.transfer_fee {
+        Some(_) => Some(connection.get_epoch_info()?.epoch),
+        None => None,
+    };
+
+    let mut created = HashSet::new();
+    let mut batches: Vec<Batch> = Vec::new();
+    for (index, transfer) in pending.iter().enumerate() {
+        let destination =
+            get_associated_token_address_with_program_id(&transfer.wallet, &args.mint, &program_id);
+        let mut instructions = Vec::with_capacity(2);
+        // Every row to a missing account carries its own idempotent create:
+        // batches run concurrently, so one creating the account for a later
+        // batch may not have landed yet.
+        let creates = !existing.contains(&destination);
+        if creates {
+            created.insert(destination);
+            instructions.push(create_associated_token_account_idempotent(
+                &payer.pubkey(),
+                &transfer.wallet,
+                &args.mint,
+                &program_id,
+            ));
+        }
+        instructions.push(match epoch {
+            // The program rejects the transfer unless the expected fee
+            // matches the one it computes for the current epoch.
+            Some(epoch) => transfer_checked_with_fee(
+                &program_id,
+                &source,
+                &args.mint,
+                &destination,
+                &payer.pubkey(),
+                &[],
+                transfer.base_units,
+                decimals,
+                mint.transfer_fee(epoch, transfer.base_units),
+            )?,
+            None => spl_token_2022::instruction::transfer_checked(
+                &program_id,
+                &source,
+                &args.mint,
+                &destination,
+                &payer.pubkey(),
+                &[],
+                transfer.base_units,
+                decimals,
+            )?,
+        });
+        let fits = batches.last().is_some_and(|last| {
+            let combined: Vec<Instruction> = last
+                .instructions
+                .iter()
+                .chain(&instructions)
+                .cloned()
+                .collect();
+            TransactionBudget::measure(&combined, &payer.pubkey()).fits()
+        });
+        if !fits {
+            batches.push(Batch {
+                transfers: Vec::new(),
+                instructions: Vec::new(),
+            });
+        }
+        let last = batches.last_mut().expect("a batch was just pushed");
+        last.transfers.push(index);
+        last.instructions.extend(instructions);
+    }
+    output.status(format_args!(
+        "Sending {} rows in {} transactions, {} at a time; creating {} token accounts",
+        pending.len(),
+        batches.len(),
+        args.concurrency.max(1),
+        created.len()
+    ));
+    if let Some(largest) = batches
+        .iter()
+        .map(|batch| TransactionBudget::measure(&batch.instructions, &payer.pubkey()))
+        .max_by_key(|budget| budget.size)
+    {
+        output.status(format_args!("Largest transaction: {largest}"));
+    }
+
+    let cache = BlockhashCache::default();
+    let results = Mutex::new(results);
+    let queue = Mutex::new(batches.iter().enumerate());
+    let outcomes = Mutex::new((0, 0));
+    thread::scope(|scope| {
+        for _ in 0..args.concurrency.max(1) {
+            scope.spawn(|| {
+                loop {
+                    let Some((number, batch)) = queue.lock().unwrap().next() else {
+                        return;
+                    };
+                    let rows: Vec<&Transfer> = batch
+                        .transfers
+                        .iter()
+                        .map(|&index| pending[index])
+                        .collect();
+                    let (signature, error) =
+                        match send_batch(&connection, &cache, &payer, batch, &rows, &results) {
+                            Ok(signature) => (Some(signature), None),
+                            Err((signature, err)) => (signature, Some(err)),
+                        };
+                    let mut outcomes = outcomes.lock().unwrap();
+                    match &error {
+                        None => outcomes.0 += rows.len(),
+                        Some(_) => outcomes.1 += rows.len(),
+                    }
+                    output.status(format_args!(
+                        "Batch {}/{}: rows {}-{} {}",
+                        number + 1,
+                        batches.len(),
+                        rows[0].row,
+                        rows[rows.len() - 1].row,
+                        match (&signature, &error) {
+                            (_, Some(err)) => format!("failed: {err}"),
+                            (Some(signature), None) => format!("sent: {signature}"),
+                            (None, None) => "sent".to_string(),
+                        }
+                    ));
+                }
+            });
+        }
+    });
+    let (sent, failed) = outcomes.into_inner().unwrap();
+    if failed > 0 {
+        output.status(format_args!(
+            "warning: {failed} rows failed; run again with the same files to retry them"
+        ));
+    }
+
+    let summary = AirdropSummary {
+        mint: args.mint.to_string(),
+        program: token::program_name(&program_id),
+        rows: transfers.len(),
+        already_sent: delivered.len(),
+        sent,
+        failed,
+        transactions: batches.len(),
+        created_accounts: created.len(),
+        results: results_path.display().to_string(),
+        credits: credits::stats(),
+    };
+    output.print(&summary, || {
+        println!("Rows:          {}", summary.rows);
+        println!("Already sent:  {}", summary.already_sent);
+        println!("Sent:          {}", summary.sent);
+        println!("Failed:        {}", summary.failed);
+        println!("Transactions:  {}", summary.transactions);
+        println!("Results:       {}", summary.results);
+        if let Some(credits) = &summary.credits {
+            println!("Credits:       {credits}");
+        }
+    })
+}
+
+/// Signs the batch, records its rows as pending under the signature, then
+/// sends it, waits for confirmation and records the outcome. The error comes
+/// with the signature when the transaction had been signed.
+fn send_batch(
+    connection: &RpcClient,
+    cache: &BlockhashCache,
+    payer: &Keypair,
+    batch: &Batch,
+    rows: &[&Transfer],
+    results: &Mutex<ResultsFile>,
+) -> std::result::Result<Signature, (Option<Signature>, String)> {
+    let recent = cache
+        .get(connection)
+        .map_err(|err| (None, err.to_string()))?;
+    let transaction = Transaction::new_signed_with_payer(
+        &batch.instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent.blockhash,
+    );
+    let signature = transaction.signatures[0];
+    let record = |status, error: Option<&String>| {
+        results.lock().unwrap().write_rows(
+            rows,
+            status,
+            Some(&signature),
+            Some(recent.last_valid_block_height),
+            error,
+        )
+    };
+    // Written before sending, so a run killed while waiting for the
+    // confirmation leaves a signature the next run can look up.
+    record(RowStatus::Pending, None).map_err(|err| (None, err.to_string()))?;
+    let outcome = connection
+        .send_and_confirm_transaction(&transaction)
+        .map_err(|err| err.to_string());
+    // The block height stays on failed rows too: an error from the node does
+    // not prove the transaction cannot still land.
+    let recorded = match &outcome {
+        Ok(_) => record(RowStatus::Sent, None),
+        Err(err) => record(RowStatus::Failed, Some(err)),
+    };
+    if let Err(err) = recorded {
+        error!("could not record the outcome of {signature}: {err}");
+    }
+    outcome.map_err(|err| (Some(signature), err))
+}
+
+/// Settles the rows a previous run left pending or failed after signing:
+/// those whose transaction landed are recorded as sent, waiting out any
+/// blockhash that is still valid so an in-flight transaction cannot land
+/// after its rows are sent again. Returns the rows already delivered.
+fn reconcile(
+    connection: &RpcClient,
+    previous: &HashMap<usize, RowResult>,
+    results: &mut ResultsFile,
+    output: &OutputArgs,
+) -> Result<HashSet<usize>> {
+    let mut delivered: HashSet<usize> = previous
+        .values()
+        .filter(|result| result.status == RowStatus::Sent)
+        .map(|result| result.row)
+        .collect();
+    let mut unresolved: Vec<&RowResult> = previous
+        .values()
+        .filter(|result| result.status != RowStatus::Sent && result.signature.is_some())
+        .collect();
+    if !unresolved.is_empty() {
+        output.status(format_args!(
+            "Checking {} rows an earlier run did not finish",
+            unresolved.len()
+        ));
+    }
+
+    while !unresolved.is_empty() {
+        let signatures: Vec<Signature> = unresolved
+            .iter()
+            .map(|result| result.signature.as_deref().unwrap_or_default().parse())
+            .collect::<std::result::Result<_, _>>()
+            .map_err(|err| ExampleError::Parse(format!("signature in results file: {err}")))?;
+        let mut statuses = Vec::with_capacity(signatures.len());
+        for chunk in signatures.chunks(MAX_SIGNATURE_STATUSES) {
+            statuses.extend(connection.get_signature_statuses_with_history(chunk)?.value);
+        }
+        let block_height = connection.get_block_height()?;
+
+        let mut waiting = Vec::new();
+        for (result, status) in unresolved.into_iter().zip(statuses) {
+            match status {
+                Some(status) if status.err.is_none() => {
+                    let mut landed = result.clone();
+                    landed.status = RowStatus::Sent;
+                    landed.error = None;
+                    results.write(&landed)?;
+                    delivered.insert(result.row);
+                }
+                // Failed on chain, so nothing moved; the row is sent again.
+                Some(_) => {}
+                None if result
+                    .last_valid_block_height
+                    .is_some_and(|last_valid| last_valid >= block_height) =>
+                {
+                    waiting.push(result);
+                }
+                None => {}
+            }
+        }
+        if !waiting.is_empty() {
+            output.status(format_args!(
+                "Waiting for {} unconfirmed rows to land or expire",
+                waiting.len()
+            ));
+            thread::sleep(EXPIRY_POLL_INTERVAL);
+        }
+        unresolved = waiting;
+    }
+    Ok(delivered)
+}
+
+fn read_recipients(connection: &RpcClient, path: &Path, decimals: u8) -> Result<Vec<Transfer>> {
+    let mut reader = csv::Reader::from_path(path).map_err(io::Error::from)?;
+    let mut transfers = Vec::new();
+    for (index, recipient) in reader.deserialize::<Recipient>().enumerate() {
+        let row = index + 1;
+        let invalid = |what: String| ExampleError::InvalidArgument(format!("row {row}: {what}"));
+        let recipient = recipient.map_err(|err| invalid(err.to_string()))?;
+        let address = recipient.address.trim();
+        let amount = recipient.amount.trim();
+        let base_units = parse_token_amount(amount, decimals)
+            .map_err(|err| invalid(format!("amount {amount}: {err}")))?;
+        if base_units == 0 {
+            return Err(invalid("amount is zero".to_string()));
+        }
+        let wallet: AddressArg = address.parse().map_err(invalid)?;
+        transfers.push(Transfer {
+            row,
+            wallet: wallet.resolve(connection)?,
+            amount: amount.to_string(),
+            base_units,
+        });
+    }
+    Ok(transfers)
+}
+
+/// The latest line for each row of an existing results file, checked
+/// against the recipients so a results file from another airdrop is not
+/// mistaken for this one.
+fn read_results(path: &Path, transfers: &[Transfer]) -> Result<HashMap<usize, RowResult>> {
+    let mut latest = HashMap::new();
+    if !path.exists() {
+        return Ok(latest);
+    }
+    let mut reader = csv::Reader::from_path(path).map_err(io::Error::from)?;
+    for result in reader.deserialize::<RowResult>() {
+        let result =
+            result.map_err(|err| ExampleError::Parse(format!("{}: {err}", path.display())))?;
+        let matches = result
+            .row
+            .checked_sub(1)
+            .and_then(|index| transfers.get(index))
+            .is_some_and(|transfer| {
+                transfer.wallet.to_string() == result.address && transfer.amount == result.amount
+            });
+        if !matches {
+            return Err(ExampleError::InvalidArgument(format!(
+                "{} does not match the recipients at row {}; pass another --results file",
+                path.display(),
+                result.row
+            )));
+        }
+        latest.insert(result.row, result);
+    }
+    Ok(latest)
+}
+
+/// The results CSV, opened for appending; the header is only written to a
+/// new file.
+struct ResultsFile {
+    writer: csv::Writer<File>,
+}
+
+impl ResultsFile {
+    fn open(path: &Path) -> Result<Self> {
+        let is_new = fs::metadata(path).map_or(true, |metadata| metadata.len() == 0);
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let writer = csv::WriterBuilder::new()
+            .has_headers(is_new)
+            .from_writer(file);
+        Ok(Self { writer })
+    }
+
+    fn write(&mut self, result: &RowResult) -> Result<()> {
+        self.writer.serialize(result).map_err(io::Error::from)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+
+    fn write_rows(
+        &mut self,
+        rows: &[&Transfer],
+        status: RowStatus,
+        signature: Option<&Signature>,
+        last_valid_block_height: Option<u64>,
+        error: Option<&String>,
+    ) -> Result<()> {
+        for transfer in rows {
+            self.write(&RowResult {
+                row: transfer.row,
+                address: transfer.wallet.to_string(),
+                amount: transfer.amount.clone(),
+                status,
+                signature: signature.map(Signature::to_string),
+                last_valid_block_height,
+                error: error.cloned(),
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,158 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::token::{self, TokenAccountInfo};
+use common::{ExampleError, Result};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::process::ExitCode;
+
+/// Summarize how concentrated a token's supply is: `getTokenSupply` for the
+/// total, `getTokenLargestAccounts` for the 20 biggest token accounts, and
+/// the owners of those accounts resolved in one `getMultipleAccounts` call.
+///
+/// Unlike token-holders this never scans the whole program, so it is cheap
+/// enough to run against mints with millions of holders.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Mint to analyze
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct TokenAnalytics {
+    mint: String,
+    program: &'static str,
+    supply: String,
+    decimals: u8,
+    /// Percent of supply in the largest 1, 5, 10 and 20 accounts
+    top1_share: f64,
+    top5_share: f64,
+    top10_share: f64,
+    top20_share: f64,
+    /// Distinct owners among the largest accounts
+    distinct_owners: usize,
+    largest_accounts: Vec<LargestAccount>,
+}
+
+#[derive(Serialize)]
+struct LargestAccount {
+    rank: usize,
+    address: String,
+    owner: Option<String>,
+    /// Whether the owner is a program-derived address, i.e. a vault, pool
+    /// or escrow rather than a wallet with a private key
+    owner_is_pda: Option<bool>,
+    balance: String,
+    /// Share of the total supply, in percent
+    supply_share: f64,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.retrying_client();
+    let mint = connection.call(|client| token::fetch_mint(client, &args.mint))?;
+    let supply = connection.call(|client| Ok(client.get_token_supply(&args.mint)?))?;
+    let raw_supply: u64 = supply
+        .amount
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("token supply {}", supply.amount)))?;
+    let largest = connection.call(|client| Ok(client.get_token_largest_accounts(&args.mint)?))?;
+
+    let addresses: Vec<Pubkey> = largest
+        .iter()
+        .map(|balance| balance.address.parse())
+        .collect::<std::result::Result<_, _>>()?;
+    let accounts = connection.call(|client| Ok(client.get_multiple_accounts(&addresses)?))?;
+
+    let share = |amount: u64| {
+        if raw_supply == 0 {
+            0.0
+        } else {
+            amount as f64 * 100.0 / raw_supply as f64
+        }
+    };
+    let mut amounts = Vec::with_capacity(largest.len());
+    let mut owners = HashSet::new();
+    let mut rows = Vec::with_capacity(largest.len());
+    for (rank, ((address, balance), account)) in
+        addresses.iter().zip(&largest).zip(&accounts).enumerate()
+    {
+        let amount: u64 = balance.amount.amount.parse().unwrap_or(0);
+        amounts.push(amount);
+        let owner = account
+            .as_ref()
+            .and_then(|account| TokenAccountInfo::unpack(address, account).ok())
+            .map(|info| info.account.owner);
+        owners.extend(owner);
+        rows.push(LargestAccount {
+            rank: rank + 1,
+            address: address.to_string(),
+            owner: owner.map(|owner| owner.to_string()),
+            owner_is_pda: owner.map(|owner| !owner.is_on_curve()),
+            balance: mint.ui_amount(amount),
+            supply_share: share(amount),
+        });
+    }
+    let top = |count: usize| share(amounts.iter().take(count).sum());
+
+    let analytics = TokenAnalytics {
+        mint: args.mint.to_string(),
+        program: token::program_name(&mint.program_id),
+        supply: mint.ui_amount(raw_supply),
+        decimals: mint.decimals(),
+        top1_share: top(1),
+        top5_share: top(5),
+        top10_share: top(10),
+        top20_share: top(20),
+        distinct_owners: owners.len(),
+        largest_accounts: rows,
+    };
+    args.output
+        .print(&analytics, || print_analytics(&analytics))
+}
+
+fn print_analytics(analytics: &TokenAnalytics) {
+    println!("Mint:     {} ({})", analytics.mint, analytics.program);
+    println!(
+        "Supply:   {} ({} decimals)",
+        analytics.supply, analytics.decimals
+    );
+    println!(
+        "Top 1 / 5 / 10 / 20 accounts hold {:.2}% / {:.2}% / {:.2}% / {:.2}% of supply",
+        analytics.top1_share, analytics.top5_share, analytics.top10_share, analytics.top20_share
+    );
+    println!(
+        "The top {} accounts belong to {} distinct owners",
+        analytics.largest_accounts.len(),
+        analytics.distinct_owners
+    );
+    println!();
+    println!(
+        "{:>4}  {:<44}  {:<44}  {:>24}  {:>8}",
+        "Rank", "Token account", "Owner", "Balance", "Supply %"
+    );
+    for row in &analytics.largest_accounts {
+        let owner = match (&row.owner, row.owner_is_pda) {
+            (Some(owner), Some(true)) => format!("{owner} (PDA)"),
+            (Some(owner), _) => owner.clone(),
+            (None, _) => "?".to_string(),
+        };
+        println!(
+            "{:>4}  {:<44}  {:<44}  {:>24}  {:>7.3}%",
+            row.rank, row.address, owner, row.balance, row.supply_share
+        );
+    }
+}
@@ -0,0 +1,400 @@
+use clap::{Parser, ValueEnum};
+use common::cli::{AddressArg, RpcArgs};
+use common::output::OutputArgs;
+use common::token::{self, MintInfo, TokenHolding};
+use common::{ExampleError, Result, batch};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_token_2022::extension::default_account_state::DefaultAccountState;
+use spl_token_2022::extension::pausable::PausableConfig;
+use spl_token_2022::extension::permanent_delegate::PermanentDelegate;
+use spl_token_2022::extension::transfer_hook::TransferHook;
+use spl_token_2022::extension::{BaseStateWithExtensions, StateWithExtensions};
+use spl_token_2022::state::{AccountState, Mint};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::process::ExitCode;
+
+/// Audit a wallet's token accounts for settings that let someone other than
+/// the wallet move, freeze or close them, and print the findings as a
+/// security report.
+///
+/// Account checks: a close authority other than the owner, who can close the
+/// account once it is empty and send its rent wherever they like; a
+/// delegate, rated by how much of the balance its allowance covers; a frozen
+/// account; and token accounts that are not the associated one, which
+/// wallets may not show.
+///
+/// Mint checks, for every mint held: a Token-2022 permanent delegate, which
+/// can transfer or burn from every account of the mint; a transfer hook,
+/// whose program runs on every transfer; a pause authority; frozen default
+/// account state; a freeze authority; and a transfer fee.
+///
+/// Exits with code 2 when a finding is at least as severe as --fail-on.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet to audit: an address (base58), a `.sol` domain or a keypair
+    /// file
+    #[arg(short, long)]
+    wallet: AddressArg,
+
+    /// Lowest severity that makes the audit fail
+    #[arg(long, value_enum, default_value_t = Severity::High)]
+    fail_on: Severity,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize, ValueEnum, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+enum Severity {
+    Info,
+    Low,
+    Medium,
+    High,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            Self::Info => "INFO",
+            Self::Low => "LOW",
+            Self::Medium => "MEDIUM",
+            Self::High => "HIGH",
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct Finding {
+    severity: Severity,
+    check: &'static str,
+    /// The token account concerned; absent for findings about a mint, which
+    /// apply to every account of it
+    account: Option<String>,
+    mint: String,
+    detail: String,
+}
+
+#[derive(Serialize)]
+struct Report {
+    wallet: String,
+    accounts: usize,
+    mints: usize,
+    /// Findings by severity, most severe first
+    summary: Summary,
+    findings: Vec<Finding>,
+}
+
+#[derive(Serialize, Default)]
+struct Summary {
+    high: usize,
+    medium: usize,
+    low: usize,
+    info: usize,
+}
+
+/// Exit code when a finding reaches --fail-on.
+const FAILED_EXIT_CODE: u8 = 2;
+
+fn main() -> ExitCode {
+    match run(common::config::parse()) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(FAILED_EXIT_CODE),
+        Err(err) => common::report(Err(err)),
+    }
+}
+
+/// Returns whether no finding reached --fail-on.
+fn run(args: Args) -> Result<bool> {
+    let connection = args.rpc.client();
+    let wallet = args.wallet.resolve(&connection)?;
+    let output = &args.output;
+
+    let holdings = token::fetch_token_accounts(&connection, &wallet)?;
+    let mints = fetch_mints(&connection, &holdings)?;
+    output.status(format_args!(
+        "Auditing {} token accounts of {wallet} across {} mints",
+        holdings.len(),
+        mints.len()
+    ));
+
+    let mut findings = Vec::new();
+    for holding in &holdings {
+        let mint = &mints[&holding.info.account.mint].0;
+        audit_account(&wallet, holding, mint, &mut findings);
+    }
+    let mut audited: Vec<&Pubkey> = mints.keys().collect();
+    audited.sort();
+    for address in audited {
+        let (mint, account) = &mints[address];
+        audit_mint(address, mint, account, &mut findings)?;
+    }
+    // Most severe first; stable, so accounts stay in the order fetched.
+    findings.sort_by_key(|finding| Reverse(finding.severity));
+
+    let mut summary = Summary::default();
+    for finding in &findings {
+        *match finding.severity {
+            Severity::High => &mut summary.high,
+            Severity::Medium => &mut summary.medium,
+            Severity::Low => &mut summary.low,
+            Severity::Info => &mut summary.info,
+        } += 1;
+    }
+    let passed = findings
+        .iter()
+        .all(|finding| finding.severity < args.fail_on);
+    let report = Report {
+        wallet: wallet.to_string(),
+        accounts: holdings.len(),
+        mints: mints.len(),
+        summary,
+        findings,
+    };
+    output.print(&report, || {
+        for finding in &report.findings {
+            println!(
+                "{:<6}  {:<16}  {}",
+                finding.severity, finding.check, finding.detail
+            );
+            match &finding.account {
+                Some(account) => println!("{:<6}  {:<16}  account {account}", "", ""),
+                None => println!("{:<6}  {:<16}  mint {}", "", "", finding.mint),
+            }
+        }
+        if !report.findings.is_empty() {
+            println!();
+        }
+        println!(
+            "{} high, {} medium, {} low, {} info across {} accounts",
+            report.summary.high,
+            report.summary.medium,
+            report.summary.low,
+            report.summary.info,
+            report.accounts
+        );
+    })?;
+    Ok(passed)
+}
+
+fn audit_account(
+    wallet: &Pubkey,
+    holding: &TokenHolding,
+    mint: &MintInfo,
+    findings: &mut Vec<Finding>,
+) {
+    let account = &holding.info.account;
+    let mut finding = |severity, check, detail| {
+        findings.push(Finding {
+            severity,
+            check,
+            account: Some(holding.address.to_string()),
+            mint: account.mint.to_string(),
+            detail,
+        });
+    };
+
+    let close_authority: Option<Pubkey> = account.close_authority.into();
+    if let Some(authority) = close_authority.filter(|authority| authority != wallet) {
+        finding(
+            Severity::High,
+            "close-authority",
+            format!(
+                "{authority} can close the account once it is empty and choose where its rent goes"
+            ),
+        );
+    }
+
+    let delegate: Option<Pubkey> = account.delegate.into();
+    if let Some(delegate) = delegate {
+        let allowance = mint.ui_amount(account.delegated_amount);
+        let (severity, detail) = if account.delegated_amount == u64::MAX {
+            (
+                Severity::High,
+                format!("{delegate} may spend an unlimited amount"),
+            )
+        } else if account.delegated_amount >= account.amount && account.amount > 0 {
+            (
+                Severity::High,
+                format!(
+                    "{delegate} may spend {allowance} tokens, the whole balance of {}",
+                    mint.ui_amount(account.amount)
+                ),
+            )
+        } else if account.delegated_amount == 0 {
+            (
+                Severity::Low,
+                format!("{delegate} is still the delegate, with an allowance of zero"),
+            )
+        } else {
+            (
+                Severity::Medium,
+                format!(
+                    "{delegate} may spend {allowance} of {} tokens",
+                    mint.ui_amount(account.amount)
+                ),
+            )
+        };
+        finding(severity, "delegate", detail);
+    }
+
+    if account.state == AccountState::Frozen {
+        let freeze_authority: Option<Pubkey> = mint.mint.freeze_authority.into();
+        finding(
+            Severity::Medium,
+            "frozen",
+            match freeze_authority {
+                Some(authority) => format!("frozen; only {authority} can thaw it"),
+                None => "frozen, and the mint has no freeze authority to thaw it".to_string(),
+            },
+        );
+    }
+
+    let associated = get_associated_token_address_with_program_id(
+        wallet,
+        &account.mint,
+        &holding.info.program_id,
+    );
+    if holding.address != associated {
+        finding(
+            Severity::Info,
+            "not-associated",
+            format!("not the associated token account {associated}; wallets may not show it"),
+        );
+    }
+}
+
+fn audit_mint(
+    address: &Pubkey,
+    mint: &MintInfo,
+    account: &Account,
+    findings: &mut Vec<Finding>,
+) -> Result<()> {
+    let mut finding = |severity, check, detail| {
+        findings.push(Finding {
+            severity,
+            check,
+            account: None,
+            mint: address.to_string(),
+            detail,
+        });
+    };
+    // Legacy mints have no extensions; the lookups below just find nothing.
+    let state = StateWithExtensions::<Mint>::unpack(&account.data)?;
+
+    let permanent_delegate = state
+        .get_extension::<PermanentDelegate>()
+        .ok()
+        .and_then(|extension| Option::<Pubkey>::from(extension.delegate));
+    if let Some(delegate) = permanent_delegate {
+        finding(
+            Severity::High,
+            "permanent-delegate",
+            format!("{delegate} can transfer or burn tokens from every account of this mint"),
+        );
+    }
+
+    let hook = state.get_extension::<TransferHook>().ok();
+    let hook_program = hook.and_then(|hook| Option::<Pubkey>::from(hook.program_id));
+    if let (Some(hook), Some(program)) = (hook, hook_program) {
+        let authority = Option::<Pubkey>::from(hook.authority)
+            .map_or_else(|| "nobody".to_string(), |authority| authority.to_string());
+        finding(
+            Severity::Medium,
+            "transfer-hook",
+            format!("every transfer runs program {program}; {authority} can change it"),
+        );
+    }
+
+    if let Ok(pausable) = state.get_extension::<PausableConfig>() {
+        let authority = Option::<Pubkey>::from(pausable.authority)
+            .map_or_else(|| "nobody".to_string(), |authority| authority.to_string());
+        let paused = bool::from(pausable.paused);
+        finding(
+            if paused {
+                Severity::High
+            } else {
+                Severity::Medium
+            },
+            "pausable",
+            format!(
+                "{authority} can pause all transfers{}",
+                if paused {
+                    "; the mint is paused now"
+                } else {
+                    ""
+                }
+            ),
+        );
+    }
+
+    let default_frozen = state
+        .get_extension::<DefaultAccountState>()
+        .is_ok_and(|extension| extension.state == AccountState::Frozen as u8);
+    if default_frozen {
+        finding(
+            Severity::Low,
+            "default-frozen",
+            "new accounts of this mint start frozen until the freeze authority thaws them"
+                .to_string(),
+        );
+    }
+
+    if let Some(authority) = Option::<Pubkey>::from(mint.mint.freeze_authority) {
+        finding(
+            Severity::Low,
+            "freeze-authority",
+            format!("{authority} can freeze any account of this mint"),
+        );
+    }
+
+    if let Some(config) = &mint.transfer_fee {
+        let basis_points = u16::from(config.newer_transfer_fee.transfer_fee_basis_points);
+        let authority = Option::<Pubkey>::from(config.transfer_fee_config_authority);
+        if basis_points > 0 || authority.is_some() {
+            finding(
+                Severity::Info,
+                "transfer-fee",
+                format!(
+                    "transfers pay {basis_points} bps{}",
+                    authority.map_or_else(String::new, |authority| format!(
+                        "; {authority} can raise it"
+                    ))
+                ),
+            );
+        }
+    }
+    Ok(())
+}
+
+/// The mints of `holdings`, decoded and raw: the raw account is kept for the
+/// extensions `MintInfo` does not decode.
+fn fetch_mints(
+    connection: &RpcClient,
+    holdings: &[TokenHolding],
+) -> Result<HashMap<Pubkey, (MintInfo, Account)>> {
+    let mut mints: Vec<Pubkey> = holdings
+        .iter()
+        .map(|holding| holding.info.account.mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+
+    let accounts = batch::get_multiple_accounts(connection, &mints)?;
+    let mut infos = HashMap::with_capacity(mints.len());
+    for (mint, account) in mints.iter().zip(accounts) {
+        let account = account.ok_or(ExampleError::AccountNotFound(*mint))?;
+        infos.insert(*mint, (MintInfo::unpack(mint, &account)?, account));
+    }
+    Ok(infos)
+}
@@ -0,0 +1,374 @@
+use clap::{Parser, Subcommand, ValueEnum};
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::token::{self, MintInfo, TokenAccountInfo};
+use common::tx_builder::TxBuilder;
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+use solana_sdk::program_option::COption;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use spl_token_2022::instruction::AuthorityType;
+use spl_token_2022::state::AccountState;
+use std::process::ExitCode;
+
+/// Manage the authorities of a mint or token account: show them, hand them
+/// to another key or revoke them with `set_authority`, and freeze or thaw
+/// token accounts with the mint's freeze authority. Works for legacy and
+/// Token-2022 mints.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the authorities of a mint or token account
+    Show {
+        /// Mint or token account address
+        address: Pubkey,
+    },
+    /// Give an authority to another key, or revoke it with --none
+    SetAuthority {
+        // The current authority
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        /// Mint or token account whose authority changes
+        address: Pubkey,
+
+        /// Which authority to change
+        #[arg(short = 't', long = "type", value_enum)]
+        authority_type: Authority,
+
+        /// The new authority
+        #[arg(short, long, required_unless_present = "none", conflicts_with = "none")]
+        new: Option<Pubkey>,
+
+        /// Remove the authority for good; for the mint authority this fixes
+        /// the supply forever
+        #[arg(long)]
+        none: bool,
+
+        /// Confirm a revocation made with --none, which cannot be undone
+        #[arg(long, requires = "none")]
+        yes: bool,
+    },
+    /// Freeze a token account so its tokens cannot move
+    Freeze {
+        // The mint's freeze authority
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        /// Token account to freeze
+        account: Pubkey,
+    },
+    /// Thaw a frozen token account
+    Thaw {
+        // The mint's freeze authority
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        /// Token account to thaw
+        account: Pubkey,
+    },
+}
+
+/// The authority kinds `set_authority` accepts. The Token-2022 ones only
+/// exist on mints with the matching extension.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Authority {
+    /// Mint: may mint new tokens
+    Mint,
+    /// Mint: may freeze and thaw token accounts
+    Freeze,
+    /// Token account: its owner
+    Owner,
+    /// Token account: may close it (defaults to the owner)
+    Close,
+    /// Token-2022 mint: may close the mint once the supply is zero
+    CloseMint,
+    /// Token-2022 mint: may change the transfer fee
+    TransferFeeConfig,
+    /// Token-2022 mint: may withdraw withheld transfer fees
+    WithheldWithdraw,
+    /// Token-2022 mint: may transfer or burn from any account
+    PermanentDelegate,
+}
+
+impl From<Authority> for AuthorityType {
+    fn from(authority: Authority) -> Self {
+        match authority {
+            Authority::Mint => AuthorityType::MintTokens,
+            Authority::Freeze => AuthorityType::FreezeAccount,
+            Authority::Owner => AuthorityType::AccountOwner,
+            Authority::Close => AuthorityType::CloseAccount,
+            Authority::CloseMint => AuthorityType::CloseMint,
+            Authority::TransferFeeConfig => AuthorityType::TransferFeeConfig,
+            Authority::WithheldWithdraw => AuthorityType::WithheldWithdraw,
+            Authority::PermanentDelegate => AuthorityType::PermanentDelegate,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Authorities {
+    address: String,
+    /// `mint` or `token account`
+    kind: &'static str,
+    program: &'static str,
+    mint_authority: Option<String>,
+    freeze_authority: Option<String>,
+    mint: Option<String>,
+    owner: Option<String>,
+    close_authority: Option<String>,
+    /// Token accounts only: `initialized` or `frozen`
+    state: Option<&'static str>,
+}
+
+/// Printed by the subcommands that send a transaction.
+#[derive(Serialize)]
+struct AuthorityAction {
+    action: &'static str,
+    address: String,
+    authority_type: Option<String>,
+    /// The new authority; empty when it was revoked
+    new_authority: Option<String>,
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let rpc_url = &args.rpc.rpc_url;
+    let output = &args.output;
+    match args.command {
+        Command::Show { address } => {
+            let authorities = authorities(&address, &fetch(&connection, &address)?)?;
+            output.print(&authorities, || print_authorities(&authorities))?;
+        }
+        Command::SetAuthority {
+            keypair,
+            address,
+            authority_type,
+            new,
+            none,
+            yes,
+        } => {
+            if none && !yes {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "revoking the {} authority cannot be undone; pass --yes to confirm",
+                    authority_name(authority_type)
+                )));
+            }
+            let authority = keypair.load()?;
+            let program_id = token::token_program_of(&address, &fetch(&connection, &address)?)?;
+            let instruction = spl_token_2022::instruction::set_authority(
+                &program_id,
+                &address,
+                new.as_ref(),
+                authority_type.into(),
+                &authority.pubkey(),
+                &[],
+            )?;
+            match new {
+                Some(new) => output.status(format_args!(
+                    "Moving the {} authority of {address} to {new}",
+                    authority_name(authority_type)
+                )),
+                None => output.status(format_args!(
+                    "Revoking the {} authority of {address}",
+                    authority_name(authority_type)
+                )),
+            }
+            let signature = TxBuilder::new(&authority)
+                .add_instruction(instruction)
+                .sign_and_send(&connection)?;
+            print_signature(output, &signature, rpc_url);
+            output.print(
+                &AuthorityAction {
+                    action: "set-authority",
+                    address: address.to_string(),
+                    authority_type: Some(authority_name(authority_type)),
+                    new_authority: new.map(|new| new.to_string()),
+                    signature: signature.to_string(),
+                },
+                || {},
+            )?;
+        }
+        Command::Freeze { keypair, account } => {
+            let signature = freeze_or_thaw(&connection, &keypair.load()?, &account, true)?;
+            print_signature(output, &signature, rpc_url);
+            output.print_structured(&freeze_action("freeze", &account, &signature))?;
+        }
+        Command::Thaw { keypair, account } => {
+            let signature = freeze_or_thaw(&connection, &keypair.load()?, &account, false)?;
+            print_signature(output, &signature, rpc_url);
+            output.print_structured(&freeze_action("thaw", &account, &signature))?;
+        }
+    }
+    Ok(())
+}
+
+fn fetch(connection: &RpcClient, address: &Pubkey) -> Result<Account> {
+    connection
+        .get_account_with_commitment(address, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(*address))
+}
+
+fn authority_name(authority: Authority) -> String {
+    authority
+        .to_possible_value()
+        .map(|value| value.get_name().to_string())
+        .unwrap_or_default()
+}
+
+fn optional(key: COption<Pubkey>) -> Option<String> {
+    Option::from(key).map(|key: Pubkey| key.to_string())
+}
+
+/// Token accounts are tried first: a Token-2022 mint can be as long as a
+/// token account, but is tagged as a mint after the base layout.
+fn authorities(address: &Pubkey, account: &Account) -> Result<Authorities> {
+    if let Ok(info) = TokenAccountInfo::unpack(address, account) {
+        let state = match info.account.state {
+            AccountState::Frozen => "frozen",
+            _ => "initialized",
+        };
+        return Ok(Authorities {
+            address: address.to_string(),
+            kind: "token account",
+            program: token::program_name(&info.program_id),
+            mint_authority: None,
+            freeze_authority: None,
+            mint: Some(info.account.mint.to_string()),
+            owner: Some(info.account.owner.to_string()),
+            close_authority: optional(info.account.close_authority),
+            state: Some(state),
+        });
+    }
+    let info = MintInfo::unpack(address, account)?;
+    Ok(Authorities {
+        address: address.to_string(),
+        kind: "mint",
+        program: token::program_name(&info.program_id),
+        mint_authority: optional(info.mint.mint_authority),
+        freeze_authority: optional(info.mint.freeze_authority),
+        mint: None,
+        owner: None,
+        close_authority: None,
+        state: None,
+    })
+}
+
+fn print_authorities(authorities: &Authorities) {
+    let show = |key: &Option<String>| key.clone().unwrap_or_else(|| "none".to_string());
+    println!(
+        "{} ({} {})",
+        authorities.address, authorities.program, authorities.kind
+    );
+    if authorities.kind == "mint" {
+        println!("  mint authority:   {}", show(&authorities.mint_authority));
+        println!(
+            "  freeze authority: {}",
+            show(&authorities.freeze_authority)
+        );
+    } else {
+        println!("  mint:             {}", show(&authorities.mint));
+        println!("  owner:            {}", show(&authorities.owner));
+        println!("  close authority:  {}", show(&authorities.close_authority));
+        println!(
+            "  state:            {}",
+            authorities.state.unwrap_or_default()
+        );
+    }
+}
+
+/// Checks up front that the signer is the mint's freeze authority and that
+/// the account is not already in the requested state, since the program's
+/// own errors for both are bare codes.
+fn freeze_or_thaw(
+    connection: &RpcClient,
+    authority: &Keypair,
+    address: &Pubkey,
+    freeze: bool,
+) -> Result<Signature> {
+    let info = TokenAccountInfo::unpack(address, &fetch(connection, address)?)?;
+    let mint = token::fetch_mint(connection, &info.account.mint)?;
+    match Option::<Pubkey>::from(mint.mint.freeze_authority) {
+        None => {
+            return Err(ExampleError::InvalidArgument(format!(
+                "mint {} has no freeze authority",
+                info.account.mint
+            )));
+        }
+        Some(freeze_authority) if freeze_authority != authority.pubkey() => {
+            return Err(ExampleError::InvalidArgument(format!(
+                "the freeze authority of {} is {freeze_authority}, not {}",
+                info.account.mint,
+                authority.pubkey()
+            )));
+        }
+        Some(_) => {}
+    }
+    let frozen = info.account.state == AccountState::Frozen;
+    if frozen == freeze {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{address} is already {}",
+            if frozen { "frozen" } else { "thawed" }
+        )));
+    }
+
+    let instruction = if freeze {
+        spl_token_2022::instruction::freeze_account(
+            &info.program_id,
+            address,
+            &info.account.mint,
+            &authority.pubkey(),
+            &[],
+        )?
+    } else {
+        spl_token_2022::instruction::thaw_account(
+            &info.program_id,
+            address,
+            &info.account.mint,
+            &authority.pubkey(),
+            &[],
+        )?
+    };
+    TxBuilder::new(authority)
+        .add_instruction(instruction)
+        .sign_and_send(connection)
+}
+
+fn freeze_action(action: &'static str, account: &Pubkey, signature: &Signature) -> AuthorityAction {
+    AuthorityAction {
+        action,
+        address: account.to_string(),
+        authority_type: None,
+        new_authority: None,
+        signature: signature.to_string(),
+    }
+}
+
+fn print_signature(output: &OutputArgs, signature: &Signature, rpc_url: &str) {
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
@@ -0,0 +1,347 @@
+use clap::{Parser, Subcommand};
+use common::cli::{AddressArg, KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::token::{self, MintInfo, TokenAccountInfo, TokenHolding};
+use common::tx_builder::TxBuilder;
+use common::units::parse_token_amount;
+use common::{ExampleError, Result, batch, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_associated_token_account_client::instruction::create_associated_token_account_idempotent;
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+/// Grant, use and revoke a delegate's right to spend from a token account,
+/// and list the delegations standing on a wallet.
+///
+/// A token account has at most one delegate at a time, allowed to move up to
+/// `delegated_amount` base units; each transfer it signs counts down the
+/// allowance, and `revoke` or a new `approve` replaces it. Delegations made
+/// for a dApp that is no longer used are a common way wallets get drained,
+/// which is why `scan` exists.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // The account owner for approve and revoke, the delegate for
+    // transfer-from
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Let a delegate spend up to an amount from the keypair's token account
+    /// for a mint, with approve_checked
+    Approve {
+        /// Mint of the token account
+        #[arg(short, long)]
+        mint: Pubkey,
+
+        /// Address allowed to spend: an address (base58), a `.sol` domain or
+        /// a keypair file
+        #[arg(short, long)]
+        delegate: AddressArg,
+
+        /// Allowance in whole tokens (e.g. 1.5), using the mint's decimals
+        #[arg(short, long)]
+        amount: String,
+    },
+    /// Transfer from another wallet's token account as its delegate; the
+    /// keypair is the delegate and pays for the recipient's account
+    TransferFrom {
+        #[arg(short, long)]
+        mint: Pubkey,
+
+        /// Wallet whose associated token account is spent from: an address
+        /// (base58), a `.sol` domain or a keypair file
+        #[arg(long)]
+        owner: AddressArg,
+
+        /// Recipient wallet (not its token account), in the same forms
+        #[arg(short, long)]
+        to: AddressArg,
+
+        /// Amount in whole tokens
+        #[arg(short, long)]
+        amount: String,
+    },
+    /// Remove the delegate from the keypair's token account for a mint
+    Revoke {
+        #[arg(short, long)]
+        mint: Pubkey,
+    },
+    /// List a wallet's token accounts that have a delegate
+    Scan {
+        /// Wallet to scan: an address (base58), a `.sol` domain or a keypair
+        /// file; defaults to the keypair's
+        #[arg(short, long)]
+        wallet: Option<AddressArg>,
+    },
+}
+
+#[derive(Serialize)]
+struct DelegateAction {
+    action: &'static str,
+    mint: String,
+    program: &'static str,
+    /// The token account acted on
+    account: String,
+    /// Delegate afterwards, if any
+    delegate: Option<String>,
+    /// Remaining allowance afterwards, in base units
+    delegated_amount: u64,
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct Delegation {
+    account: String,
+    mint: String,
+    program: &'static str,
+    delegate: String,
+    /// Allowance left, in base units
+    delegated_amount: u64,
+    ui_delegated_amount: String,
+    /// The account's balance, which caps what the delegate can take no
+    /// matter the allowance
+    ui_balance: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+
+    let (action, signer, info, account, instructions) = match &args.command {
+        Command::Approve {
+            mint,
+            delegate,
+            amount,
+        } => {
+            let delegate = delegate.resolve(&connection)?;
+            let owner = args.keypair.load()?;
+            let info = token::fetch_mint(&connection, mint)?;
+            let amount = parse_token_amount(amount, info.decimals())?;
+            let account = get_associated_token_address_with_program_id(
+                &owner.pubkey(),
+                mint,
+                &info.program_id,
+            );
+            output.status(format_args!(
+                "Approving {delegate} to spend {} tokens from {account}",
+                info.ui_amount(amount)
+            ));
+            // The checked variant makes the program verify the mint and
+            // decimals, so an allowance is never granted off by a factor of
+            // ten because the client guessed the decimals wrong.
+            let instruction = spl_token_2022::instruction::approve_checked(
+                &info.program_id,
+                &account,
+                mint,
+                &delegate,
+                &owner.pubkey(),
+                &[],
+                amount,
+                info.decimals(),
+            )?;
+            ("approve", owner, info, account, vec![instruction])
+        }
+        Command::TransferFrom {
+            mint,
+            owner,
+            to,
+            amount,
+        } => {
+            let owner = owner.resolve(&connection)?;
+            let to = to.resolve(&connection)?;
+            let delegate = args.keypair.load()?;
+            let info = token::fetch_mint(&connection, mint)?;
+            let amount = parse_token_amount(amount, info.decimals())?;
+            let source =
+                get_associated_token_address_with_program_id(&owner, mint, &info.program_id);
+            let destination =
+                get_associated_token_address_with_program_id(&to, mint, &info.program_id);
+            let allowance = fetch_token_account(&connection, &source)?.account;
+            if allowance.delegate != Some(delegate.pubkey()).into() {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "{} is not the delegate of {source}",
+                    delegate.pubkey()
+                )));
+            }
+            if allowance.delegated_amount < amount {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "the allowance on {source} is {} tokens",
+                    info.ui_amount(allowance.delegated_amount)
+                )));
+            }
+            output.status(format_args!(
+                "Moving {} tokens from {source} to {destination} as delegate",
+                info.ui_amount(amount)
+            ));
+            // The delegate signs as the transfer's authority; the owner does
+            // not take part at all.
+            let instructions = vec![
+                create_associated_token_account_idempotent(
+                    &delegate.pubkey(),
+                    &to,
+                    mint,
+                    &info.program_id,
+                ),
+                spl_token_2022::instruction::transfer_checked(
+                    &info.program_id,
+                    &source,
+                    mint,
+                    &destination,
+                    &delegate.pubkey(),
+                    &[],
+                    amount,
+                    info.decimals(),
+                )?,
+            ];
+            ("transfer-from", delegate, info, source, instructions)
+        }
+        Command::Revoke { mint } => {
+            let owner = args.keypair.load()?;
+            let info = token::fetch_mint(&connection, mint)?;
+            let account = get_associated_token_address_with_program_id(
+                &owner.pubkey(),
+                mint,
+                &info.program_id,
+            );
+            output.status(format_args!("Revoking the delegate of {account}"));
+            let instruction = spl_token_2022::instruction::revoke(
+                &info.program_id,
+                &account,
+                &owner.pubkey(),
+                &[],
+            )?;
+            ("revoke", owner, info, account, vec![instruction])
+        }
+        Command::Scan { wallet } => {
+            let wallet = match wallet {
+                Some(wallet) => wallet.resolve(&connection)?,
+                None => args.keypair.load()?.pubkey(),
+            };
+            return scan(&connection, output, &wallet);
+        }
+    };
+
+    let signature = TxBuilder::new(&signer)
+        .add_instructions(instructions)
+        .sign_and_send(&connection)?;
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+    let after = fetch_token_account(&connection, &account)?.account;
+    let delegate: Option<Pubkey> = after.delegate.into();
+    let action = DelegateAction {
+        action,
+        mint: after.mint.to_string(),
+        program: token::program_name(&info.program_id),
+        account: account.to_string(),
+        delegate: delegate.map(|delegate| delegate.to_string()),
+        delegated_amount: after.delegated_amount,
+        signature: signature.to_string(),
+    };
+    output.status(format_args!(
+        "Allowance is now {} tokens",
+        info.ui_amount(action.delegated_amount)
+    ));
+    output.print_structured(&action)
+}
+
+fn scan(connection: &RpcClient, output: &OutputArgs, wallet: &Pubkey) -> Result<()> {
+    let holdings = token::fetch_token_accounts(connection, wallet)?;
+    let delegated: Vec<&TokenHolding> = holdings
+        .iter()
+        .filter(|holding| holding.info.account.delegate.is_some())
+        .collect();
+    let mints = fetch_mints(connection, &delegated)?;
+    let delegations: Vec<Delegation> = delegated
+        .iter()
+        .map(|holding| {
+            let account = &holding.info.account;
+            let mint = &mints[&account.mint];
+            let delegate: Option<Pubkey> = account.delegate.into();
+            Delegation {
+                account: holding.address.to_string(),
+                mint: account.mint.to_string(),
+                program: token::program_name(&holding.info.program_id),
+                delegate: delegate.map_or_else(String::new, |delegate| delegate.to_string()),
+                delegated_amount: account.delegated_amount,
+                ui_delegated_amount: mint.ui_amount(account.delegated_amount),
+                ui_balance: mint.ui_amount(account.amount),
+            }
+        })
+        .collect();
+
+    output.status(format_args!(
+        "{} of {} token accounts of {wallet} have a delegate",
+        delegations.len(),
+        holdings.len()
+    ));
+    output.print(&delegations, || {
+        if delegations.is_empty() {
+            return;
+        }
+        println!(
+            "{:<44}  {:<44}  {:<44}  {:>20}  {:>20}",
+            "ACCOUNT", "MINT", "DELEGATE", "ALLOWANCE", "BALANCE"
+        );
+        for delegation in &delegations {
+            println!(
+                "{:<44}  {:<44}  {:<44}  {:>20}  {:>20}",
+                delegation.account,
+                delegation.mint,
+                delegation.delegate,
+                delegation.ui_delegated_amount,
+                delegation.ui_balance
+            );
+        }
+    })
+}
+
+fn fetch_token_account(connection: &RpcClient, address: &Pubkey) -> Result<TokenAccountInfo> {
+    let account = connection
+        .get_account_with_commitment(address, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(*address))?;
+    TokenAccountInfo::unpack(address, &account)
+}
+
+fn fetch_mints(
+    connection: &RpcClient,
+    holdings: &[&TokenHolding],
+) -> Result<HashMap<Pubkey, MintInfo>> {
+    let mut mints: Vec<Pubkey> = holdings
+        .iter()
+        .map(|holding| holding.info.account.mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+
+    let accounts = batch::get_multiple_accounts(connection, &mints)?;
+    let mut infos = HashMap::with_capacity(mints.len());
+    for (mint, account) in mints.iter().zip(accounts) {
+        let account = account.ok_or(ExampleError::AccountNotFound(*mint))?;
+        infos.insert(*mint, MintInfo::unpack(mint, &account)?);
+    }
+    Ok(infos)
+}
@@ -0,0 +1,323 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_token_amount, parse_token_amount};
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_associated_token_account_client::instruction::create_associated_token_account;
+use spl_token_2022::extension::confidential_transfer::instruction::{
+    PubkeyValidityProofData, apply_pending_balance, approve_account, configure_account, deposit,
+    initialize_mint as initialize_confidential_mint,
+};
+use spl_token_2022::extension::confidential_transfer::{
+    ConfidentialTransferAccount, DecryptableBalance,
+};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::solana_zk_sdk::encryption::auth_encryption::{AeCiphertext, AeKey};
+use spl_token_2022::solana_zk_sdk::encryption::elgamal::ElGamalKeypair;
+use spl_token_2022::state::{Account as TokenAccount, Mint};
+use spl_token_confidential_transfer_proof_extraction::instruction::ProofLocation;
+use std::num::NonZeroI8;
+use std::process::ExitCode;
+
+/// Walk through the confidential-transfer setup of a Token-2022 mint: create
+/// the mint with the extension, give the payer's token account an ElGamal
+/// key, get it approved, and move part of its balance into the encrypted,
+/// confidential one.
+///
+/// Both the account's ElGamal keypair and the AES key for the balance it can
+/// decrypt itself are derived from the payer's signature over the token
+/// account address, so they can be derived again from the same keypair
+/// later. Configuring the account verifies a proof with the ZK ElGamal proof
+/// program, which must be enabled on the cluster. Confidential transfers and
+/// withdrawals need range and equality proofs split over several
+/// transactions and are not covered here.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer keypair; also the mint and confidential-transfer authority
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Number of decimals for the new mint
+    #[arg(short, long, default_value_t = 9)]
+    decimals: u8,
+
+    /// Initial supply to mint to the payer, in whole tokens
+    #[arg(short, long, default_value = "1000000")]
+    supply: String,
+
+    /// Amount to deposit into the confidential balance, in whole tokens
+    #[arg(short, long, default_value = "1000")]
+    amount: String,
+
+    /// Require the authority to approve accounts before they can be used
+    /// confidentially, instead of approving new ones automatically
+    #[arg(long)]
+    manual_approval: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct ConfidentialMint {
+    mint: String,
+    decimals: u8,
+    auto_approve: bool,
+    supply: String,
+    token_account: String,
+    /// The account's ElGamal public key, base64
+    elgamal_pubkey: String,
+    deposited: String,
+    /// The available confidential balance, as decrypted with the account's
+    /// AES key
+    confidential_balance: String,
+    public_balance: String,
+    signatures: Vec<String>,
+}
+
+/// Deposits and incoming transfers an account accepts before its pending
+/// balance must be applied; the default of the SPL token CLI.
+const MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER: u64 = 65536;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let program_id = spl_token_2022::id();
+    let supply = parse_token_amount(&args.supply, args.decimals)?;
+    let amount = parse_token_amount(&args.amount, args.decimals)?;
+    if amount > supply {
+        return Err(ExampleError::InvalidArgument(format!(
+            "--amount {} is more than the supply of {}",
+            args.amount, args.supply
+        )));
+    }
+    let mint = Keypair::new();
+
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[
+        ExtensionType::ConfidentialTransferMint,
+    ])?;
+    let rent = connection.get_minimum_balance_for_rent_exemption(space)?;
+    output.status(format_args!("Mint address: {}", mint.pubkey()));
+    output.status(format_args!(
+        "Rent-exempt minimum for {space} bytes: {rent} lamports"
+    ));
+
+    // No auditor: with an auditor ElGamal key every transfer amount is also
+    // encrypted for it.
+    let create_mint = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            space as u64,
+            &program_id,
+        ),
+        initialize_confidential_mint(
+            &program_id,
+            &mint.pubkey(),
+            Some(payer.pubkey()),
+            !args.manual_approval,
+            None,
+        )?,
+        spl_token_2022::instruction::initialize_mint2(
+            &program_id,
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            args.decimals,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(create_mint)
+        .add_signer(&mint)
+        .sign_and_send(&connection)?;
+    let mut signatures = vec![signature.to_string()];
+    print_step(
+        output,
+        &format!(
+            "Created mint with confidential transfers, {} approval of accounts",
+            if args.manual_approval {
+                "manual"
+            } else {
+                "automatic"
+            }
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let token_account =
+        get_associated_token_address_with_program_id(&payer.pubkey(), &mint.pubkey(), &program_id);
+    let mint_supply = [
+        create_associated_token_account(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &mint.pubkey(),
+            &program_id,
+        ),
+        spl_token_2022::instruction::mint_to(
+            &program_id,
+            &mint.pubkey(),
+            &token_account,
+            &payer.pubkey(),
+            &[],
+            supply,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(mint_supply)
+        .sign_and_send(&connection)?;
+    signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!("Minted {} tokens to {token_account}", args.supply),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let seed = token_account.to_bytes();
+    let elgamal = ElGamalKeypair::new_from_signer(&payer, &seed)
+        .map_err(|err| ExampleError::Keypair(format!("ElGamal key: {err}")))?;
+    let aes = AeKey::new_from_signer(&payer, &seed)
+        .map_err(|err| ExampleError::Keypair(format!("AES key: {err}")))?;
+    // The proof that the owner knows the ElGamal secret key has to follow
+    // the configure instruction directly, at offset 1.
+    let proof = PubkeyValidityProofData::new(&elgamal)
+        .map_err(|err| ExampleError::Keypair(format!("pubkey validity proof: {err}")))?;
+    let proof_offset = NonZeroI8::new(1).expect("1 is not zero");
+    // The associated token account was created with room for its immutable
+    // owner extension only.
+    let mut configure = vec![spl_token_2022::instruction::reallocate(
+        &program_id,
+        &token_account,
+        &payer.pubkey(),
+        &payer.pubkey(),
+        &[],
+        &[ExtensionType::ConfidentialTransferAccount],
+    )?];
+    configure.extend(configure_account(
+        &program_id,
+        &token_account,
+        &mint.pubkey(),
+        &aes.encrypt(0).into(),
+        MAXIMUM_PENDING_BALANCE_CREDIT_COUNTER,
+        &payer.pubkey(),
+        &[],
+        ProofLocation::InstructionOffset(proof_offset, &proof),
+    )?);
+    if args.manual_approval {
+        configure.push(approve_account(
+            &program_id,
+            &token_account,
+            &mint.pubkey(),
+            &payer.pubkey(),
+            &[],
+        )?);
+    }
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(configure)
+        .sign_and_send(&connection)?;
+    signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!(
+            "Configured {token_account} for confidential transfers with ElGamal key {}",
+            elgamal.pubkey()
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    // A deposit lands in the pending balance, encrypted under the account's
+    // key; applying it moves it to the available balance, along with a new
+    // copy of that balance the owner can decrypt with the AES key.
+    let deposit = [
+        deposit(
+            &program_id,
+            &token_account,
+            &mint.pubkey(),
+            amount,
+            args.decimals,
+            &payer.pubkey(),
+            &[],
+        )?,
+        apply_pending_balance(
+            &program_id,
+            &token_account,
+            1,
+            &aes.encrypt(amount).into(),
+            &payer.pubkey(),
+            &[],
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(deposit)
+        .sign_and_send(&connection)?;
+    signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!(
+            "Deposited {} tokens into the confidential balance",
+            args.amount
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let account = connection
+        .get_account_with_commitment(&token_account, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(token_account))?;
+    let state = StateWithExtensions::<TokenAccount>::unpack(&account.data)?;
+    let extension = state.get_extension::<ConfidentialTransferAccount>()?;
+    let confidential = decrypt(&aes, &extension.decryptable_available_balance)?;
+    output.status(format_args!(
+        "Balances: {} tokens public, {} tokens confidential",
+        format_token_amount(state.base.amount, args.decimals),
+        format_token_amount(confidential, args.decimals)
+    ));
+
+    let created = ConfidentialMint {
+        mint: mint.pubkey().to_string(),
+        decimals: args.decimals,
+        auto_approve: !args.manual_approval,
+        supply: args.supply.clone(),
+        token_account: token_account.to_string(),
+        elgamal_pubkey: elgamal.pubkey().to_string(),
+        deposited: args.amount.clone(),
+        confidential_balance: format_token_amount(confidential, args.decimals),
+        public_balance: format_token_amount(state.base.amount, args.decimals),
+        signatures,
+    };
+    output.print_structured(&created)
+}
+
+fn decrypt(aes: &AeKey, balance: &DecryptableBalance) -> Result<u64> {
+    AeCiphertext::try_from(*balance)
+        .ok()
+        .and_then(|ciphertext| aes.decrypt(&ciphertext))
+        .ok_or_else(|| ExampleError::Parse("decryptable available balance".to_string()))
+}
+
+fn print_step(output: &OutputArgs, step: &str, signature: &Signature, rpc_url: &str) {
+    output.status(step);
+    output.status(format_args!("  Signature: {signature}"));
+    output.status(format_args!(
+        "  Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
@@ -0,0 +1,247 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::token::{self, TokenAccountInfo};
+use common::tx_builder::TxBuilder;
+use common::units::parse_token_amount;
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_associated_token_account_client::instruction::create_associated_token_account;
+use spl_token_2022::extension::ExtensionType;
+use spl_token_2022::extension::transfer_fee::instruction::{
+    initialize_transfer_fee_config, transfer_checked_with_fee,
+};
+use spl_token_2022::state::Mint;
+use std::process::ExitCode;
+
+/// Create a Token-2022 mint with the transfer-fee extension, mint a supply to
+/// the payer and optionally send some of it to see the fee being withheld.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer keypair; also the mint, fee config and withdraw authority
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Number of decimals for the new mint
+    #[arg(short, long, default_value_t = 9)]
+    decimals: u8,
+
+    /// Transfer fee in basis points (100 = 1%)
+    #[arg(short, long, default_value_t = 100)]
+    fee_basis_points: u16,
+
+    /// Upper bound on the fee of a single transfer, in whole tokens
+    #[arg(short = 'x', long, default_value = "9")]
+    max_fee: String,
+
+    /// Initial supply to mint to the payer, in whole tokens
+    #[arg(short, long, default_value = "1000000")]
+    supply: String,
+
+    /// Wallet to send tokens to after minting
+    #[arg(short, long)]
+    to: Option<Pubkey>,
+
+    /// Amount to send to --to, in whole tokens
+    #[arg(short, long, default_value = "1000")]
+    amount: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct FeeMint {
+    mint: String,
+    decimals: u8,
+    fee_basis_points: u16,
+    max_fee: String,
+    supply: String,
+    token_account: String,
+    /// Set when --to was given
+    recipient_token_account: Option<String>,
+    sent: Option<String>,
+    fee: Option<String>,
+    recipient_balance: Option<String>,
+    withheld: Option<String>,
+    signatures: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let program_id = spl_token_2022::id();
+    let max_fee = parse_token_amount(&args.max_fee, args.decimals)?;
+    let supply = parse_token_amount(&args.supply, args.decimals)?;
+    let mint = Keypair::new();
+
+    // Extensions live after the base mint, so the account must be sized for
+    // them up front.
+    let space =
+        ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferFeeConfig])?;
+    let rent = connection.get_minimum_balance_for_rent_exemption(space)?;
+    output.status(format_args!("Mint address: {}", mint.pubkey()));
+    output.status(format_args!(
+        "Rent-exempt minimum for {space} bytes: {rent} lamports"
+    ));
+
+    // Extensions must be initialized before the mint itself.
+    let create_mint = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            space as u64,
+            &program_id,
+        ),
+        initialize_transfer_fee_config(
+            &program_id,
+            &mint.pubkey(),
+            Some(&payer.pubkey()),
+            Some(&payer.pubkey()),
+            args.fee_basis_points,
+            max_fee,
+        )?,
+        spl_token_2022::instruction::initialize_mint2(
+            &program_id,
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            args.decimals,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(create_mint)
+        .add_signer(&mint)
+        .sign_and_send(&connection)?;
+    let mut signatures = vec![signature.to_string()];
+    print_step(
+        output,
+        &format!(
+            "Created mint with a {} bps transfer fee capped at {} tokens",
+            args.fee_basis_points, args.max_fee
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let source =
+        get_associated_token_address_with_program_id(&payer.pubkey(), &mint.pubkey(), &program_id);
+    let mint_supply = [
+        create_associated_token_account(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &mint.pubkey(),
+            &program_id,
+        ),
+        spl_token_2022::instruction::mint_to(
+            &program_id,
+            &mint.pubkey(),
+            &source,
+            &payer.pubkey(),
+            &[],
+            supply,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(mint_supply)
+        .sign_and_send(&connection)?;
+    signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!("Minted {} tokens to {source}", args.supply),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let mut created = FeeMint {
+        mint: mint.pubkey().to_string(),
+        decimals: args.decimals,
+        fee_basis_points: args.fee_basis_points,
+        max_fee: args.max_fee.clone(),
+        supply: args.supply.clone(),
+        token_account: source.to_string(),
+        recipient_token_account: None,
+        sent: None,
+        fee: None,
+        recipient_balance: None,
+        withheld: None,
+        signatures,
+    };
+    let Some(to) = args.to else {
+        return output.print_structured(&created);
+    };
+    let amount = parse_token_amount(&args.amount, args.decimals)?;
+    let mint_info = token::fetch_mint(&connection, &mint.pubkey())?;
+    let epoch = connection.get_epoch_info()?.epoch;
+    let fee = mint_info.transfer_fee(epoch, amount);
+    let destination =
+        get_associated_token_address_with_program_id(&to, &mint.pubkey(), &program_id);
+    let transfer = [
+        create_associated_token_account(&payer.pubkey(), &to, &mint.pubkey(), &program_id),
+        transfer_checked_with_fee(
+            &program_id,
+            &source,
+            &mint.pubkey(),
+            &destination,
+            &payer.pubkey(),
+            &[],
+            amount,
+            args.decimals,
+            fee,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(transfer)
+        .sign_and_send(&connection)?;
+    created.signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!(
+            "Sent {} tokens to {destination} with a fee of {} tokens",
+            args.amount,
+            mint_info.ui_amount(fee)
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let account = connection
+        .get_account_with_commitment(&destination, connection.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(destination))?;
+    let received = TokenAccountInfo::unpack(&destination, &account)?;
+    output.status(format_args!(
+        "Recipient balance: {} tokens, {} tokens withheld for the withdraw authority",
+        mint_info.ui_amount(received.account.amount),
+        mint_info.ui_amount(received.withheld)
+    ));
+    created.recipient_token_account = Some(destination.to_string());
+    created.sent = Some(args.amount.clone());
+    created.fee = Some(mint_info.ui_amount(fee));
+    created.recipient_balance = Some(mint_info.ui_amount(received.account.amount));
+    created.withheld = Some(mint_info.ui_amount(received.withheld));
+    output.print_structured(&created)
+}
+
+fn print_step(output: &OutputArgs, step: &str, signature: &Signature, rpc_url: &str) {
+    output.status(step);
+    output.status(format_args!("  Signature: {signature}"));
+    output.status(format_args!(
+        "  Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
@@ -0,0 +1,279 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::parse_token_amount;
+use common::{ExampleError, Result, explorer};
+use futures::executor::block_on;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_associated_token_account_client::instruction::create_associated_token_account;
+use spl_tlv_account_resolution::state::ExtraAccountMetaList;
+use spl_token_2022::extension::ExtensionType;
+use spl_token_2022::extension::transfer_hook::instruction::initialize as initialize_transfer_hook;
+use spl_token_2022::offchain::create_transfer_checked_instruction_with_extra_metas;
+use spl_token_2022::state::Mint;
+use spl_transfer_hook_interface::get_extra_account_metas_address;
+use spl_transfer_hook_interface::instruction::initialize_extra_account_meta_list;
+use std::future;
+use std::process::ExitCode;
+
+/// Create a Token-2022 mint with the transfer-hook extension, so that every
+/// transfer of it also calls `--hook-program`, mint a supply to the payer
+/// and optionally send some of it through the hook.
+///
+/// The hook program must implement the SPL transfer-hook interface, like
+/// the example program in the SPL repository. Token-2022 passes it the
+/// accounts listed in its "extra-account-metas" PDA for the mint, which is
+/// initialized here with an empty list; `--no-extra-metas` leaves that to
+/// hook programs that create it their own way. Transfers resolve those
+/// accounts off-chain before sending.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer keypair; also the mint and transfer-hook authority
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Transfer-hook program to call on every transfer
+    #[arg(short = 'p', long)]
+    hook_program: Pubkey,
+
+    /// Do not initialize the hook's extra-account-metas account
+    #[arg(long)]
+    no_extra_metas: bool,
+
+    /// Number of decimals for the new mint
+    #[arg(short, long, default_value_t = 9)]
+    decimals: u8,
+
+    /// Initial supply to mint to the payer, in whole tokens
+    #[arg(short, long, default_value = "1000000")]
+    supply: String,
+
+    /// Wallet to send tokens to after minting
+    #[arg(short, long)]
+    to: Option<Pubkey>,
+
+    /// Amount to send to --to, in whole tokens
+    #[arg(short, long, default_value = "1000")]
+    amount: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct HookMint {
+    mint: String,
+    decimals: u8,
+    hook_program: String,
+    /// The PDA listing the extra accounts the hook is passed
+    extra_account_metas: String,
+    supply: String,
+    token_account: String,
+    /// Set when --to was given
+    recipient_token_account: Option<String>,
+    sent: Option<String>,
+    /// Accounts the transfer resolved for the hook beyond the base ones,
+    /// the hook program and its extra-account-metas account last
+    hook_accounts: Option<Vec<String>>,
+    signatures: Vec<String>,
+}
+
+/// Accounts of a plain `TransferChecked` with a single owner.
+const TRANSFER_ACCOUNTS: usize = 4;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let program_id = spl_token_2022::id();
+    let supply = parse_token_amount(&args.supply, args.decimals)?;
+    let mint = Keypair::new();
+    let extra_metas = get_extra_account_metas_address(&mint.pubkey(), &args.hook_program);
+
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&[ExtensionType::TransferHook])?;
+    let rent = connection.get_minimum_balance_for_rent_exemption(space)?;
+    output.status(format_args!("Mint address: {}", mint.pubkey()));
+    output.status(format_args!(
+        "Rent-exempt minimum for {space} bytes: {rent} lamports"
+    ));
+
+    // Like every extension, the hook must be initialized before the mint.
+    let create_mint = [
+        system_instruction::create_account(
+            &payer.pubkey(),
+            &mint.pubkey(),
+            rent,
+            space as u64,
+            &program_id,
+        ),
+        initialize_transfer_hook(
+            &program_id,
+            &mint.pubkey(),
+            Some(payer.pubkey()),
+            Some(args.hook_program),
+        )?,
+        spl_token_2022::instruction::initialize_mint2(
+            &program_id,
+            &mint.pubkey(),
+            &payer.pubkey(),
+            None,
+            args.decimals,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(create_mint)
+        .add_signer(&mint)
+        .sign_and_send(&connection)?;
+    let mut signatures = vec![signature.to_string()];
+    print_step(
+        output,
+        &format!("Created mint with transfer hook {}", args.hook_program),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    if !args.no_extra_metas {
+        // The SPL example hook allocates the PDA but does not fund it, so
+        // its rent is sent along with the instruction.
+        let metas_rent =
+            connection.get_minimum_balance_for_rent_exemption(ExtraAccountMetaList::size_of(0)?)?;
+        let initialize_metas = [
+            system_instruction::transfer(&payer.pubkey(), &extra_metas, metas_rent),
+            initialize_extra_account_meta_list(
+                &args.hook_program,
+                &extra_metas,
+                &mint.pubkey(),
+                &payer.pubkey(),
+                &[],
+            ),
+        ];
+        let signature = TxBuilder::new(&payer)
+            .add_instructions(initialize_metas)
+            .sign_and_send(&connection)?;
+        signatures.push(signature.to_string());
+        print_step(
+            output,
+            &format!("Initialized the hook's extra-account-metas at {extra_metas}"),
+            &signature,
+            &args.rpc.rpc_url,
+        );
+    }
+
+    let source =
+        get_associated_token_address_with_program_id(&payer.pubkey(), &mint.pubkey(), &program_id);
+    let mint_supply = [
+        create_associated_token_account(
+            &payer.pubkey(),
+            &payer.pubkey(),
+            &mint.pubkey(),
+            &program_id,
+        ),
+        spl_token_2022::instruction::mint_to(
+            &program_id,
+            &mint.pubkey(),
+            &source,
+            &payer.pubkey(),
+            &[],
+            supply,
+        )?,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(mint_supply)
+        .sign_and_send(&connection)?;
+    signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!("Minted {} tokens to {source}", args.supply),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+
+    let mut created = HookMint {
+        mint: mint.pubkey().to_string(),
+        decimals: args.decimals,
+        hook_program: args.hook_program.to_string(),
+        extra_account_metas: extra_metas.to_string(),
+        supply: args.supply.clone(),
+        token_account: source.to_string(),
+        recipient_token_account: None,
+        sent: None,
+        hook_accounts: None,
+        signatures,
+    };
+    let Some(to) = args.to else {
+        return output.print_structured(&created);
+    };
+    let amount = parse_token_amount(&args.amount, args.decimals)?;
+    let destination =
+        get_associated_token_address_with_program_id(&to, &mint.pubkey(), &program_id);
+    // Reads the mint's hook and the accounts its extra-account-metas list
+    // names, resolving any PDAs in it, and appends them to the transfer.
+    let fetch_account_data = |address: Pubkey| {
+        future::ready(
+            connection
+                .get_account_with_commitment(&address, connection.commitment())
+                .map(|response| response.value.map(|account| account.data))
+                .map_err(Into::into),
+        )
+    };
+    let transfer_checked = block_on(create_transfer_checked_instruction_with_extra_metas(
+        &program_id,
+        &source,
+        &mint.pubkey(),
+        &destination,
+        &payer.pubkey(),
+        &[],
+        amount,
+        args.decimals,
+        fetch_account_data,
+    ))
+    .map_err(|err| ExampleError::Parse(format!("extra-account-metas {extra_metas}: {err}")))?;
+    let hook_accounts: Vec<String> = transfer_checked.accounts[TRANSFER_ACCOUNTS..]
+        .iter()
+        .map(|meta| meta.pubkey.to_string())
+        .collect();
+    let transfer = [
+        create_associated_token_account(&payer.pubkey(), &to, &mint.pubkey(), &program_id),
+        transfer_checked,
+    ];
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(transfer)
+        .sign_and_send(&connection)?;
+    created.signatures.push(signature.to_string());
+    print_step(
+        output,
+        &format!(
+            "Sent {} tokens to {destination} through the hook, with {} extra accounts",
+            args.amount,
+            hook_accounts.len()
+        ),
+        &signature,
+        &args.rpc.rpc_url,
+    );
+    created.recipient_token_account = Some(destination.to_string());
+    created.sent = Some(args.amount.clone());
+    created.hook_accounts = Some(hook_accounts);
+    output.print_structured(&created)
+}
+
+fn print_step(output: &OutputArgs, step: &str, signature: &Signature, rpc_url: &str) {
+    output.status(step);
+    output.status(format_args!("  Signature: {signature}"));
+    output.status(format_args!(
+        "  Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
@@ -0,0 +1,118 @@
+use clap::Parser;
+use common::Result;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::token::{self, TokenHolding};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+/// Find every token account of a mint with getProgramAccounts and rank the
+/// owning wallets by balance.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Mint whose holders should be listed
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    /// Number of holders to print
+    #[arg(short, long, default_value_t = 20)]
+    top: usize,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+struct Holder {
+    owner: Pubkey,
+    amount: u64,
+    accounts: usize,
+}
+
+#[derive(Serialize)]
+struct HolderRow {
+    rank: usize,
+    owner: String,
+    balance: String,
+    /// Share of the total supply, in percent
+    supply_share: f64,
+    accounts: usize,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    // getProgramAccounts is one of the heaviest calls an endpoint serves and
+    // the first to hit rate limits, so every request here goes through retries.
+    let connection = args.rpc.retrying_client();
+    let mint = connection.call(|client| token::fetch_mint(client, &args.mint))?;
+    let accounts =
+        connection.call(|client| token::fetch_mint_accounts(client, &args.mint, &mint))?;
+    let holders = rank_holders(&accounts);
+    let funded = holders.iter().filter(|holder| holder.amount > 0).count();
+    args.output.status(format_args!(
+        "{} token accounts, {} distinct owners, {funded} with a non-zero balance",
+        accounts.len(),
+        holders.len()
+    ));
+
+    let supply = mint.mint.supply;
+    let rows: Vec<HolderRow> = holders
+        .iter()
+        .take(args.top)
+        .enumerate()
+        .map(|(rank, holder)| HolderRow {
+            rank: rank + 1,
+            owner: holder.owner.to_string(),
+            balance: mint.ui_amount(holder.amount),
+            supply_share: if supply == 0 {
+                0.0
+            } else {
+                holder.amount as f64 * 100.0 / supply as f64
+            },
+            accounts: holder.accounts,
+        })
+        .collect();
+    args.output.print(&rows, || {
+        println!(
+            "{:>4}  {:<44}  {:>24}  {:>8}  {:>8}",
+            "Rank", "Owner", "Balance", "Supply %", "Accounts"
+        );
+        for row in &rows {
+            println!(
+                "{:>4}  {:<44}  {:>24}  {:>7.3}%  {:>8}",
+                row.rank, row.owner, row.balance, row.supply_share, row.accounts
+            );
+        }
+    })?;
+    if connection.retries() > 0 {
+        args.output
+            .status(format_args!("Needed {} retries", connection.retries()));
+    }
+    Ok(())
+}
+
+/// Sums balances per owner, largest first; a wallet can hold several
+/// accounts of the same mint besides its associated one.
+fn rank_holders(accounts: &[TokenHolding]) -> Vec<Holder> {
+    let mut by_owner: HashMap<Pubkey, Holder> = HashMap::new();
+    for TokenHolding { info, .. } in accounts {
+        let holder = by_owner.entry(info.account.owner).or_insert(Holder {
+            owner: info.account.owner,
+            amount: 0,
+            accounts: 0,
+        });
+        holder.amount += info.account.amount;
+        holder.accounts += 1;
+    }
+    let mut holders: Vec<Holder> = by_owner.into_values().collect();
+    holders.sort_by(|a, b| b.amount.cmp(&a.amount).then(a.owner.cmp(&b.owner)));
+    holders
+}
@@ -0,0 +1,33 @@
+-- Every transfer, mint and burn of an indexed mint. Amounts are in the
+-- token's base units; NUMERIC because they can exceed a BIGINT.
+CREATE TABLE token_transfers (
+    signature         TEXT        NOT NULL,
+    -- Position of the instruction: `2`, or `2.1` for the first instruction
+    -- invoked by instruction 2
+    instruction       TEXT        NOT NULL,
+    slot              BIGINT      NOT NULL,
+    block_time        TIMESTAMPTZ,
+    mint              TEXT        NOT NULL,
+    -- transfer, mint or burn
+    kind              TEXT        NOT NULL,
+    -- Token accounts; no source for mints, no destination for burns
+    source            TEXT,
+    destination       TEXT,
+    -- Wallets owning those token accounts, when the node reported them
+    source_owner      TEXT,
+    destination_owner TEXT,
+    amount            NUMERIC(20, 0) NOT NULL,
+    PRIMARY KEY (signature, instruction)
+);
+
+CREATE INDEX token_transfers_mint_slot ON token_transfers (mint, slot);
+CREATE INDEX token_transfers_source_owner ON token_transfers (source_owner);
+CREATE INDEX token_transfers_destination_owner ON token_transfers (destination_owner);
+
+-- The newest transaction indexed per mint, where the next poll resumes.
+CREATE TABLE indexer_cursors (
+    mint             TEXT        PRIMARY KEY,
+    newest_signature TEXT        NOT NULL,
+    slot             BIGINT      NOT NULL,
+    updated_at       TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -0,0 +1,97 @@
+//! The Postgres side of the indexer. The schema lives in `migrations/`,
+//! which is embedded in the binary and applied on startup, so pointing the
+//! indexer at an empty database is all the setup there is.
+
+use crate::TokenTransfer;
+use common::{ExampleError, Result};
+use solana_sdk::clock::Slot;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use sqlx::PgPool;
+use sqlx::postgres::PgPoolOptions;
+
+/// One connection polls; a second lets ad-hoc queries through while a round
+/// is being written.
+const MAX_CONNECTIONS: u32 = 2;
+
+/// Connects and brings the schema up to date.
+pub async fn connect(url: &str) -> Result<PgPool> {
+    let pool = PgPoolOptions::new()
+        .max_connections(MAX_CONNECTIONS)
+        .connect(url)
+        .await
+        .map_err(database_error)?;
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .map_err(|err| ExampleError::Database(format!("migrations: {err}")))?;
+    Ok(pool)
+}
+
+/// The newest signature indexed for `mint`, where the next poll resumes.
+pub async fn cursor(pool: &PgPool, mint: &Pubkey) -> Result<Option<Signature>> {
+    let signature: Option<String> =
+        sqlx::query_scalar("SELECT newest_signature FROM indexer_cursors WHERE mint = $1")
+            .bind(mint.to_string())
+            .fetch_optional(pool)
+            .await
+            .map_err(database_error)?;
+    signature
+        .map(|signature| {
+            signature
+                .parse()
+                .map_err(|_| ExampleError::Database(format!("stored signature `{signature}`")))
+        })
+        .transpose()
+}
+
+/// Writes the transfers of one transaction and moves the cursor of `mint`
+/// to it, atomically. Rows already present are left alone, so re-indexing
+/// a transaction after a crash is harmless.
+pub async fn save(
+    pool: &PgPool,
+    mint: &Pubkey,
+    signature: &str,
+    slot: Slot,
+    transfers: &[TokenTransfer],
+) -> Result<()> {
+    let mut tx = pool.begin().await.map_err(database_error)?;
+    for transfer in transfers {
+        sqlx::query(
+            "INSERT INTO token_transfers (signature, instruction, slot, block_time, mint, kind,
+                 source, destination, source_owner, destination_owner, amount)
+             VALUES ($1, $2, $3, to_timestamp($4::float8), $5, $6, $7, $8, $9, $10, $11::numeric)
+             ON CONFLICT (signature, instruction) DO NOTHING",
+        )
+        .bind(&transfer.signature)
+        .bind(&transfer.instruction)
+        .bind(transfer.slot as i64)
+        .bind(transfer.block_time)
+        .bind(&transfer.mint)
+        .bind(transfer.kind)
+        .bind(&transfer.source)
+        .bind(&transfer.destination)
+        .bind(&transfer.source_owner)
+        .bind(&transfer.destination_owner)
+        .bind(transfer.amount.to_string())
+        .execute(&mut *tx)
+        .await
+        .map_err(database_error)?;
+    }
+    sqlx::query(
+        "INSERT INTO indexer_cursors (mint, newest_signature, slot) VALUES ($1, $2, $3)
+         ON CONFLICT (mint) DO UPDATE SET newest_signature = excluded.newest_signature,
+             slot = excluded.slot, updated_at = now()",
+    )
+    .bind(mint.to_string())
+    .bind(signature)
+    .bind(slot as i64)
+    .execute(&mut *tx)
+    .await
+    .map_err(database_error)?;
+    tx.commit().await.map_err(database_error)
+}
+
+fn database_error(err: sqlx::Error) -> ExampleError {
+    ExampleError::Database(err.to_string())
+}
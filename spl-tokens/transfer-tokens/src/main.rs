@@ -0,0 +1,156 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::parse_token_amount;
+use common::{Result, explorer, token};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_associated_token_account_client::instruction::create_associated_token_account;
+use spl_token_2022::extension::transfer_fee::instruction::transfer_checked_with_fee;
+use std::process::ExitCode;
+
+/// Send SPL tokens to a wallet, creating its associated token account first
+/// if it does not exist yet. Works for legacy and Token-2022 mints, including
+/// mints with a transfer fee.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Sender of the transfer
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Mint address of the token to send
+    #[arg(short, long)]
+    mint: Pubkey,
+
+    /// Recipient wallet address (not its token account)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send in whole tokens (e.g. 1.5), using the mint's decimals
+    #[arg(short, long)]
+    amount: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct TokenTransfer {
+    mint: String,
+    program: &'static str,
+    source: String,
+    destination: String,
+    /// Amount sent, in base units
+    amount: u64,
+    /// Transfer fee withheld by the mint, in base units
+    fee: u64,
+    created_destination: bool,
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    let mint = token::fetch_mint(&connection, &args.mint)?;
+    let program_id = mint.program_id;
+    let decimals = mint.decimals();
+    let amount = parse_token_amount(&args.amount, decimals)?;
+    output.status(format_args!(
+        "Token program: {}",
+        token::program_name(&program_id)
+    ));
+
+    let source =
+        get_associated_token_address_with_program_id(&payer.pubkey(), &args.mint, &program_id);
+    let destination =
+        get_associated_token_address_with_program_id(&args.to, &args.mint, &program_id);
+
+    let mut instructions = Vec::with_capacity(2);
+    let mut fee = 0;
+    let destination_exists = connection
+        .get_account_with_commitment(&destination, connection.commitment())?
+        .value
+        .is_some();
+    if !destination_exists {
+        output.status(format_args!(
+            "Creating associated token account {destination} for {}",
+            args.to
+        ));
+        instructions.push(create_associated_token_account(
+            &payer.pubkey(),
+            &args.to,
+            &args.mint,
+            &program_id,
+        ));
+    }
+    if mint.transfer_fee.is_some() {
+        // The program rejects the transfer unless the expected fee matches
+        // the one it computes for the current epoch.
+        let epoch = connection.get_epoch_info()?.epoch;
+        fee = mint.transfer_fee(epoch, amount);
+        output.status(format_args!(
+            "Mint charges a transfer fee: {} tokens will be withheld",
+            mint.ui_amount(fee)
+        ));
+        instructions.push(transfer_checked_with_fee(
+            &program_id,
+            &source,
+            &args.mint,
+            &destination,
+            &payer.pubkey(),
+            &[],
+            amount,
+            decimals,
+            fee,
+        )?);
+    } else {
+        instructions.push(spl_token_2022::instruction::transfer_checked(
+            &program_id,
+            &source,
+            &args.mint,
+            &destination,
+            &payer.pubkey(),
+            &[],
+            amount,
+            decimals,
+        )?);
+    }
+
+    output.status(format_args!(
+        "Sending {} tokens ({amount} base units) from {source} to {destination}",
+        args.amount
+    ));
+    let signature = TxBuilder::new(&payer)
+        .add_instructions(instructions)
+        .sign_and_send(&connection)?;
+
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+    let transfer = TokenTransfer {
+        mint: args.mint.to_string(),
+        program: token::program_name(&program_id),
+        source: source.to_string(),
+        destination: destination.to_string(),
+        amount,
+        fee,
+        created_destination: !destination_exists,
+        signature: signature.to_string(),
+    };
+    output.print_structured(&transfer)
+}
@@ -0,0 +1,103 @@
+use clap::Parser;
+use common::cli::{AddressArg, RpcArgs};
+use common::output::OutputArgs;
+use common::token::{self, MintInfo, TokenHolding};
+use common::{ExampleError, Result, batch};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+/// List every SPL token account owned by a wallet, across both the Token and
+/// Token-2022 programs.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet whose token accounts should be listed: an address (base58), a
+    /// `.sol` domain or a keypair file
+    #[arg(short, long)]
+    wallet: AddressArg,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct HoldingRow {
+    mint: String,
+    account: String,
+    program: &'static str,
+    /// Balance in base units
+    amount: u64,
+    ui_amount: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let wallet = args.wallet.resolve(&connection)?;
+
+    let holdings = token::fetch_token_accounts(&connection, &wallet)?;
+    if holdings.is_empty() {
+        args.output
+            .status(format_args!("No token accounts found for {wallet}"));
+    }
+
+    let mints = fetch_mints(&connection, &holdings)?;
+    let rows: Vec<HoldingRow> = holdings
+        .iter()
+        .map(|holding| {
+            let account = &holding.info.account;
+            HoldingRow {
+                mint: account.mint.to_string(),
+                account: holding.address.to_string(),
+                program: token::program_name(&holding.info.program_id),
+                amount: account.amount,
+                ui_amount: mints[&account.mint].ui_amount(account.amount),
+            }
+        })
+        .collect();
+    args.output.print(&rows, || {
+        if rows.is_empty() {
+            return;
+        }
+        println!(
+            "{:<44}  {:<44}  {:<10}  {:>20}  {:>24}",
+            "Mint", "ATA", "Program", "Amount", "UI Amount"
+        );
+        for row in &rows {
+            println!(
+                "{:<44}  {:<44}  {:<10}  {:>20}  {:>24}",
+                row.mint, row.account, row.program, row.amount, row.ui_amount
+            );
+        }
+    })?;
+    Ok(())
+}
+
+fn fetch_mints(
+    connection: &RpcClient,
+    holdings: &[TokenHolding],
+) -> Result<HashMap<Pubkey, MintInfo>> {
+    let mut mints: Vec<Pubkey> = holdings
+        .iter()
+        .map(|holding| holding.info.account.mint)
+        .collect();
+    mints.sort();
+    mints.dedup();
+
+    let accounts = batch::get_multiple_accounts(connection, &mints)?;
+    let mut infos = HashMap::with_capacity(mints.len());
+    for (mint, account) in mints.iter().zip(accounts) {
+        let account = account.ok_or(ExampleError::AccountNotFound(*mint))?;
+        infos.insert(*mint, MintInfo::unpack(mint, &account)?);
+    }
+    Ok(infos)
+}
@@ -0,0 +1,160 @@
+use clap::{Parser, Subcommand};
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use spl_associated_token_account_client::address::get_associated_token_address;
+use spl_associated_token_account_client::instruction::create_associated_token_account_idempotent;
+use spl_token::native_mint;
+use std::process::ExitCode;
+
+/// Wrap native SOL into wSOL, the SPL token DeFi programs trade instead of
+/// SOL, and unwrap it again.
+///
+/// Wrapping creates the wallet's associated token account for the native
+/// mint, transfers lamports into it and calls `sync_native` so the token
+/// balance catches up with the lamports. Unwrapping closes that account,
+/// which returns the wrapped SOL and the rent deposit in one go; there is
+/// no partial unwrap.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Move SOL into the wallet's wSOL account, creating it if needed
+    Wrap {
+        /// Amount to wrap, in SOL (e.g. 0.5)
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: u64,
+    },
+    /// Close the wSOL account, returning its whole balance and rent as SOL
+    Unwrap,
+}
+
+#[derive(Serialize)]
+struct WsolAction {
+    action: &'static str,
+    /// The wallet's wSOL associated token account
+    account: String,
+    /// Lamports wrapped, or returned to the wallet by closing the account
+    lamports: u64,
+    /// wSOL balance afterwards, in lamports
+    wsol_balance: u64,
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let owner = args.keypair.load()?;
+    let output = &args.output;
+    let account = get_associated_token_address(&owner.pubkey(), &native_mint::ID);
+
+    let action = match args.command {
+        Command::Wrap { amount } => {
+            output.status(format_args!(
+                "Wrapping {} SOL into {account}",
+                format_sol(amount)
+            ));
+            // Created idempotently so wrapping twice does not fail; the rent
+            // for a new account comes out of the wallet on top of `amount`.
+            let instructions = [
+                create_associated_token_account_idempotent(
+                    &owner.pubkey(),
+                    &owner.pubkey(),
+                    &native_mint::ID,
+                    &spl_token::ID,
+                ),
+                system_instruction::transfer(&owner.pubkey(), &account, amount),
+                // Lamports sent to a native account are not counted as
+                // tokens until this runs.
+                spl_token::instruction::sync_native(&spl_token::ID, &account)?,
+            ];
+            let signature = TxBuilder::new(&owner)
+                .add_instructions(instructions)
+                .sign_and_send(&connection)?;
+            print_signature(output, &signature, &args.rpc.rpc_url);
+            WsolAction {
+                action: "wrap",
+                account: account.to_string(),
+                lamports: amount,
+                wsol_balance: wsol_balance(&connection, &account)?,
+                signature: signature.to_string(),
+            }
+        }
+        Command::Unwrap => {
+            let lamports = connection
+                .get_account_with_commitment(&account, connection.commitment())?
+                .value
+                .ok_or(ExampleError::AccountNotFound(account))?
+                .lamports;
+            output.status(format_args!(
+                "Unwrapping {} wSOL by closing {account}",
+                format_sol(wsol_balance(&connection, &account)?)
+            ));
+            let instruction = spl_token::instruction::close_account(
+                &spl_token::ID,
+                &account,
+                &owner.pubkey(),
+                &owner.pubkey(),
+                &[],
+            )?;
+            let signature = TxBuilder::new(&owner)
+                .add_instruction(instruction)
+                .sign_and_send(&connection)?;
+            print_signature(output, &signature, &args.rpc.rpc_url);
+            WsolAction {
+                action: "unwrap",
+                account: account.to_string(),
+                lamports,
+                wsol_balance: 0,
+                signature: signature.to_string(),
+            }
+        }
+    };
+
+    output.status(format_args!(
+        "wSOL balance is now {} (wallet holds {} SOL)",
+        format_sol(action.wsol_balance),
+        format_sol(connection.get_balance(&owner.pubkey())?)
+    ));
+    output.print_structured(&action)
+}
+
+fn wsol_balance(connection: &RpcClient, account: &Pubkey) -> Result<u64> {
+    let balance = connection.get_token_account_balance(account)?;
+    balance
+        .amount
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("token amount {}", balance.amount)))
+}
+
+fn print_signature(output: &OutputArgs, signature: &Signature, rpc_url: &str) {
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
@@ -0,0 +1,452 @@
+use clap::{Parser, Subcommand};
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer, keys};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_response::RpcVoteAccountInfo;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_stake_interface::instruction as stake_instruction;
+use solana_stake_interface::stake_history::{self, StakeHistory, StakeHistoryEntry};
+use solana_stake_interface::state::{Authorized, Lockup, StakeStateV2};
+use std::cmp::Reverse;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Walk a stake account through its lifecycle, one stage per subcommand:
+/// create, delegate, check activation, deactivate and withdraw.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create and fund a stake account; the signer becomes staker and withdrawer
+    Create {
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        /// SOL to stake, on top of the rent-exempt reserve
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: u64,
+
+        /// Where to write the new stake account keypair
+        #[arg(long, default_value = "stake-account.json")]
+        stake_keypair: PathBuf,
+    },
+    /// List the largest healthy validators
+    Validators {
+        #[arg(short, long, default_value_t = 20)]
+        top: usize,
+    },
+    /// Delegate the stake account to a vote account
+    Delegate {
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        #[arg(short, long)]
+        stake_account: Pubkey,
+
+        /// Vote account to delegate to; by default the largest validator whose
+        /// commission does not exceed --max-commission
+        #[arg(long)]
+        vote_account: Option<Pubkey>,
+
+        /// Highest commission, in percent, accepted when picking a validator
+        #[arg(long, default_value_t = 10)]
+        max_commission: u8,
+    },
+    /// Show the delegation and its activation progress
+    Status {
+        #[arg(short, long)]
+        stake_account: Pubkey,
+    },
+    /// Start cooling down the stake; it becomes withdrawable after the epoch
+    /// boundary
+    Deactivate {
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        #[arg(short, long)]
+        stake_account: Pubkey,
+    },
+    /// Withdraw lamports from an inactive stake account
+    Withdraw {
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        #[arg(short, long)]
+        stake_account: Pubkey,
+
+        /// Recipient; defaults to the withdraw authority
+        #[arg(short, long)]
+        to: Option<Pubkey>,
+
+        /// Amount in SOL; defaults to the whole balance, closing the account
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: Option<u64>,
+    },
+}
+
+/// Printed by the subcommands that send a transaction.
+#[derive(Serialize)]
+struct StakeAction {
+    action: &'static str,
+    stake_account: String,
+    vote_account: Option<String>,
+    /// Lamports moved into or out of the stake account
+    lamports: Option<u64>,
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct ValidatorRow {
+    vote_account: String,
+    activated_stake: u64,
+    commission: u8,
+    last_vote: u64,
+}
+
+#[derive(Serialize)]
+struct StakeStatus {
+    stake_account: String,
+    balance: u64,
+    staker: Option<String>,
+    withdrawer: Option<String>,
+    rent_exempt_reserve: Option<u64>,
+    state: &'static str,
+    vote_account: Option<String>,
+    delegated: Option<u64>,
+    effective: Option<u64>,
+    activating: Option<u64>,
+    deactivating: Option<u64>,
+    activation_epoch: Option<u64>,
+    deactivation_epoch: Option<u64>,
+    epoch: Option<u64>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let rpc_url = &args.rpc.rpc_url;
+    let output = &args.output;
+    match args.command {
+        Command::Create {
+            keypair,
+            amount,
+            stake_keypair,
+        } => {
+            let payer = keypair.load()?;
+            let stake_account = Keypair::new();
+            let rent = connection.get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())?;
+            let minimum = connection.get_stake_minimum_delegation()?;
+            if amount < minimum {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "the minimum delegation is {} SOL",
+                    format_sol(minimum)
+                )));
+            }
+            let authorized = Authorized::auto(&payer.pubkey());
+            let instructions = stake_instruction::create_account(
+                &payer.pubkey(),
+                &stake_account.pubkey(),
+                &authorized,
+                &Lockup::default(),
+                rent + amount,
+            );
+            let signature = TxBuilder::new(&payer)
+                .add_instructions(instructions)
+                .add_signer(&stake_account)
+                .sign_and_send(&connection)?;
+            keys::write_keypair_file(&stake_account, &stake_keypair)?;
+            output.status(format_args!("Stake account: {}", stake_account.pubkey()));
+            output.status(format_args!("Keypair saved: {}", stake_keypair.display()));
+            output.status(format_args!(
+                "Funded with {} SOL ({} SOL rent reserve)",
+                format_sol(rent + amount),
+                format_sol(rent)
+            ));
+            print_signature(output, &signature, rpc_url);
+            output.print(
+                &StakeAction {
+                    action: "create",
+                    stake_account: stake_account.pubkey().to_string(),
+                    vote_account: None,
+                    lamports: Some(rent + amount),
+                    signature: signature.to_string(),
+                },
+                || {},
+            )?;
+        }
+        Command::Validators { top } => {
+            let rows: Vec<ValidatorRow> = healthy_validators(&connection)?
+                .into_iter()
+                .take(top)
+                .map(|validator| ValidatorRow {
+                    vote_account: validator.vote_pubkey,
+                    activated_stake: validator.activated_stake,
+                    commission: validator.commission,
+                    last_vote: validator.last_vote,
+                })
+                .collect();
+            output.print(&rows, || {
+                println!(
+                    "{:<44}  {:>16}  {:>10}  {:>12}",
+                    "Vote account", "Active stake", "Commission", "Last vote"
+                );
+                for row in &rows {
+                    println!(
+                        "{:<44}  {:>16}  {:>9}%  {:>12}",
+                        row.vote_account,
+                        format_sol(row.activated_stake),
+                        row.commission,
+                        row.last_vote
+                    );
+                }
+            })?;
+        }
+        Command::Delegate {
+            keypair,
+            stake_account,
+            vote_account,
+            max_commission,
+        } => {
+            let staker = keypair.load()?;
+            let vote_account = match vote_account {
+                Some(vote_account) => vote_account,
+                None => pick_validator(output, &connection, max_commission)?,
+            };
+            output.status(format_args!("Delegating {stake_account} to {vote_account}"));
+            let instruction =
+                stake_instruction::delegate_stake(&stake_account, &staker.pubkey(), &vote_account);
+            let signature = TxBuilder::new(&staker)
+                .add_instruction(instruction)
+                .sign_and_send(&connection)?;
+            print_signature(output, &signature, rpc_url);
+            output.status("The stake activates at the next epoch boundary; check with `status`");
+            output.print(
+                &StakeAction {
+                    action: "delegate",
+                    stake_account: stake_account.to_string(),
+                    vote_account: Some(vote_account.to_string()),
+                    lamports: None,
+                    signature: signature.to_string(),
+                },
+                || {},
+            )?;
+        }
+        Command::Status { stake_account } => {
+            let status = fetch_status(&connection, &stake_account)?;
+            output.print(&status, || print_status(&status))?;
+        }
+        Command::Deactivate {
+            keypair,
+            stake_account,
+        } => {
+            let staker = keypair.load()?;
+            let instruction = stake_instruction::deactivate_stake(&stake_account, &staker.pubkey());
+            let signature = TxBuilder::new(&staker)
+                .add_instruction(instruction)
+                .sign_and_send(&connection)?;
+            print_signature(output, &signature, rpc_url);
+            output.status(
+                "The stake cools down until the next epoch boundary, then `withdraw` works",
+            );
+            output.print(
+                &StakeAction {
+                    action: "deactivate",
+                    stake_account: stake_account.to_string(),
+                    vote_account: None,
+                    lamports: None,
+                    signature: signature.to_string(),
+                },
+                || {},
+            )?;
+        }
+        Command::Withdraw {
+            keypair,
+            stake_account,
+            to,
+            amount,
+        } => {
+            let withdrawer = keypair.load()?;
+            let to = to.unwrap_or_else(|| withdrawer.pubkey());
+            let lamports = match amount {
+                Some(amount) => amount,
+                None => connection.get_balance(&stake_account)?,
+            };
+            let instruction = stake_instruction::withdraw(
+                &stake_account,
+                &withdrawer.pubkey(),
+                &to,
+                lamports,
+                None,
+            );
+            let signature = TxBuilder::new(&withdrawer)
+                .add_instruction(instruction)
+                .sign_and_send(&connection)?;
+            output.status(format_args!(
+                "Withdrew {} SOL to {to}",
+                format_sol(lamports)
+            ));
+            print_signature(output, &signature, rpc_url);
+            output.print(
+                &StakeAction {
+                    action: "withdraw",
+                    stake_account: stake_account.to_string(),
+                    vote_account: None,
+                    lamports: Some(lamports),
+                    signature: signature.to_string(),
+                },
+                || {},
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Current, non-delinquent vote accounts, largest stake first.
+fn healthy_validators(connection: &RpcClient) -> Result<Vec<RpcVoteAccountInfo>> {
+    let mut validators = connection.get_vote_accounts()?.current;
+    validators.sort_by_key(|validator| Reverse(validator.activated_stake));
+    Ok(validators)
+}
+
+fn pick_validator(
+    output: &OutputArgs,
+    connection: &RpcClient,
+    max_commission: u8,
+) -> Result<Pubkey> {
+    let validator = healthy_validators(connection)?
+        .into_iter()
+        .find(|validator| validator.epoch_vote_account && validator.commission <= max_commission)
+        .ok_or_else(|| {
+            ExampleError::InvalidArgument(format!(
+                "no current validator charges at most {max_commission}% commission"
+            ))
+        })?;
+    output.status(format_args!(
+        "Picked validator {} ({}% commission, {} SOL active stake)",
+        validator.vote_pubkey,
+        validator.commission,
+        format_sol(validator.activated_stake)
+    ));
+    Ok(validator.vote_pubkey.parse()?)
+}
+
+/// The `getStakeActivation` RPC method was removed, so activation is computed
+/// locally from the delegation and the StakeHistory sysvar, as the runtime
+/// does.
+fn fetch_status(connection: &RpcClient, stake_account: &Pubkey) -> Result<StakeStatus> {
+    let account = connection.get_account(stake_account)?;
+    let state: StakeStateV2 = bincode::deserialize(&account.data)
+        .map_err(|err| ExampleError::Parse(format!("stake account {stake_account}: {err}")))?;
+    let meta = state.meta();
+    let mut status = StakeStatus {
+        stake_account: stake_account.to_string(),
+        balance: account.lamports,
+        staker: meta.map(|meta| meta.authorized.staker.to_string()),
+        withdrawer: meta.map(|meta| meta.authorized.withdrawer.to_string()),
+        rent_exempt_reserve: meta.map(|meta| meta.rent_exempt_reserve),
+        state: if meta.is_some() {
+            "initialized, not delegated"
+        } else {
+            "uninitialized"
+        },
+        vote_account: None,
+        delegated: None,
+        effective: None,
+        activating: None,
+        deactivating: None,
+        activation_epoch: None,
+        deactivation_epoch: None,
+        epoch: None,
+    };
+    let Some(delegation) = state.delegation() else {
+        return Ok(status);
+    };
+
+    let history_account = connection.get_account(&stake_history::id())?;
+    let history: StakeHistory = bincode::deserialize(&history_account.data)
+        .map_err(|err| ExampleError::Parse(format!("stake history: {err}")))?;
+    let epoch = connection.get_epoch_info()?.epoch;
+    // The reduced warmup/cooldown rate is active on every public cluster.
+    let StakeHistoryEntry {
+        effective,
+        activating,
+        deactivating,
+    } = delegation.stake_activating_and_deactivating(epoch, &history, Some(0));
+    status.state = if deactivating > 0 {
+        "deactivating"
+    } else if activating > 0 {
+        "activating"
+    } else if effective > 0 {
+        "active"
+    } else {
+        "inactive"
+    };
+    status.vote_account = Some(delegation.voter_pubkey.to_string());
+    status.delegated = Some(delegation.stake);
+    status.effective = Some(effective);
+    status.activating = Some(activating);
+    status.deactivating = Some(deactivating);
+    status.activation_epoch = Some(delegation.activation_epoch);
+    status.deactivation_epoch =
+        (delegation.deactivation_epoch != u64::MAX).then_some(delegation.deactivation_epoch);
+    status.epoch = Some(epoch);
+    Ok(status)
+}
+
+fn print_status(status: &StakeStatus) {
+    println!("Balance: {} SOL", format_sol(status.balance));
+    if let (Some(staker), Some(withdrawer), Some(reserve)) = (
+        &status.staker,
+        &status.withdrawer,
+        status.rent_exempt_reserve,
+    ) {
+        println!("Staker:     {staker}");
+        println!("Withdrawer: {withdrawer}");
+        println!("Rent reserve: {} SOL", format_sol(reserve));
+    }
+    let (Some(vote_account), Some(epoch)) = (&status.vote_account, status.epoch) else {
+        println!("State: {}", status.state);
+        return;
+    };
+    let sol = |lamports: Option<u64>| format_sol(lamports.unwrap_or_default());
+    println!("Vote account: {vote_account}");
+    println!("Delegated:    {} SOL", sol(status.delegated));
+    println!("State:        {} (epoch {epoch})", status.state);
+    println!("  effective    {} SOL", sol(status.effective));
+    println!("  activating   {} SOL", sol(status.activating));
+    println!("  deactivating {} SOL", sol(status.deactivating));
+    println!(
+        "Activation epoch: {}",
+        status.activation_epoch.unwrap_or_default()
+    );
+    if let Some(deactivation_epoch) = status.deactivation_epoch {
+        println!("Deactivation epoch: {deactivation_epoch}");
+    }
+}
+
+fn print_signature(output: &OutputArgs, signature: &Signature, rpc_url: &str) {
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
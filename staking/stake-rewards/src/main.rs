@@ -0,0 +1,199 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::time::average_slot_time;
+use common::units::format_sol;
+use common::{ExampleError, Result};
+use serde::Serialize;
+use solana_sdk::clock::Epoch;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+
+const PERFORMANCE_SAMPLES: usize = 30;
+
+const SECONDS_PER_YEAR: f64 = 365.25 * 24.0 * 60.0 * 60.0;
+
+/// Pull the inflation rewards paid to stake accounts over a range of epochs
+/// with `getInflationReward`: the lamports credited each epoch, an APY
+/// estimate from that epoch's return, and the running total.
+///
+/// Rewards for an epoch are paid at the start of the next one, so the most
+/// recent epoch with rewards is the one before the current epoch.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Stake accounts (base58) to look up
+    #[arg(required = true)]
+    stake_accounts: Vec<Pubkey>,
+
+    /// Number of epochs to look back over, ending at --to-epoch
+    #[arg(short = 'n', long, default_value_t = 10, conflicts_with = "from_epoch")]
+    epochs: u64,
+
+    /// First epoch to look up, instead of counting back --epochs
+    #[arg(long)]
+    from_epoch: Option<Epoch>,
+
+    /// Last epoch to look up; defaults to the last completed epoch
+    #[arg(long)]
+    to_epoch: Option<Epoch>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct AccountRewards {
+    stake_account: String,
+    epochs: Vec<EpochReward>,
+    /// Lamports earned over all the epochs
+    total: u64,
+    /// APY from the whole range's return, compounded as rewards are
+    /// restaked
+    apy: Option<f64>,
+}
+
+#[derive(Serialize)]
+struct EpochReward {
+    epoch: Epoch,
+    /// Absent when the account earned nothing, e.g. before it was
+    /// delegated or while activating
+    amount: Option<u64>,
+    post_balance: Option<u64>,
+    /// Commission the validator charged, in percent
+    commission: Option<u8>,
+    /// The epoch's return annualised, in percent
+    apy: Option<f64>,
+    /// Lamports earned up to and including this epoch
+    cumulative: u64,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let current = connection.get_epoch_info()?;
+    let last_completed = current
+        .epoch
+        .checked_sub(1)
+        .ok_or_else(|| ExampleError::InvalidArgument("no epoch has completed yet".to_string()))?;
+    let to = args.to_epoch.unwrap_or(last_completed).min(last_completed);
+    let from = args
+        .from_epoch
+        .unwrap_or_else(|| to.saturating_sub(args.epochs.max(1) - 1));
+    if from > to {
+        return Err(ExampleError::InvalidArgument(format!(
+            "--from-epoch {from} is after the last epoch {to}"
+        )));
+    }
+
+    // An epoch lasts slots_in_epoch slots at the currently measured slot
+    // time; past epochs ran at much the same pace.
+    let slot_time =
+        average_slot_time(&connection.get_recent_performance_samples(Some(PERFORMANCE_SAMPLES))?);
+    let epochs_per_year =
+        SECONDS_PER_YEAR / (current.slots_in_epoch as f64 * slot_time.as_secs_f64());
+
+    let mut accounts: Vec<AccountRewards> = args
+        .stake_accounts
+        .iter()
+        .map(|address| AccountRewards {
+            stake_account: address.to_string(),
+            epochs: Vec::new(),
+            total: 0,
+            apy: None,
+        })
+        .collect();
+    for epoch in from..=to {
+        args.output
+            .status(format_args!("Fetching rewards for epoch {epoch}"));
+        let rewards = connection.get_inflation_reward(&args.stake_accounts, Some(epoch))?;
+        for (account, reward) in accounts.iter_mut().zip(rewards) {
+            account.total += reward.as_ref().map_or(0, |reward| reward.amount);
+            account.epochs.push(EpochReward {
+                epoch,
+                amount: reward.as_ref().map(|reward| reward.amount),
+                post_balance: reward.as_ref().map(|reward| reward.post_balance),
+                commission: reward.as_ref().and_then(|reward| reward.commission),
+                apy: reward.as_ref().map(|reward| {
+                    apy(
+                        reward.amount,
+                        reward.post_balance - reward.amount,
+                        epochs_per_year,
+                    )
+                }),
+                cumulative: account.total,
+            });
+        }
+    }
+    for account in &mut accounts {
+        account.apy = range_apy(&account.epochs, epochs_per_year);
+    }
+
+    args.output.print(&accounts, || {
+        for account in &accounts {
+            println!("{}", account.stake_account);
+            println!(
+                "  {:>6}  {:>16}  {:>18}  {:>10}  {:>8}  {:>16}",
+                "Epoch", "Reward SOL", "Balance SOL", "Commission", "APY", "Cumulative SOL"
+            );
+            for epoch in &account.epochs {
+                let dash = || "-".to_string();
+                println!(
+                    "  {:>6}  {:>16}  {:>18}  {:>10}  {:>8}  {:>16}",
+                    epoch.epoch,
+                    epoch.amount.map_or_else(dash, format_sol),
+                    epoch.post_balance.map_or_else(dash, format_sol),
+                    epoch
+                        .commission
+                        .map_or_else(dash, |commission| format!("{commission}%")),
+                    epoch.apy.map_or_else(dash, |apy| format!("{apy:.2}%")),
+                    format_sol(epoch.cumulative)
+                );
+            }
+            println!(
+                "  Total {} SOL over {} epochs{}",
+                format_sol(account.total),
+                account.epochs.len(),
+                account
+                    .apy
+                    .map(|apy| format!(", {apy:.2}% APY"))
+                    .unwrap_or_default()
+            );
+            println!();
+        }
+    })?;
+    Ok(())
+}
+
+/// Annualises the return `reward / balance` of one epoch, compounding it
+/// every epoch as rewards are added to the stake. In percent.
+fn apy(reward: u64, balance: u64, epochs_per_year: f64) -> f64 {
+    if balance == 0 {
+        return 0.0;
+    }
+    ((1.0 + reward as f64 / balance as f64).powf(epochs_per_year) - 1.0) * 100.0
+}
+
+/// Annualises the return over the epochs that paid a reward: everything
+/// earned against the balance before the first of them. Only rewards count,
+/// so deposits and withdrawals in between do not skew it.
+fn range_apy(epochs: &[EpochReward], epochs_per_year: f64) -> Option<f64> {
+    let rewarded: Vec<(u64, u64)> = epochs
+        .iter()
+        .filter_map(|epoch| Some((epoch.amount?, epoch.post_balance?)))
+        .collect();
+    let &(first_amount, first_balance) = rewarded.first()?;
+    let start = first_balance - first_amount;
+    if start == 0 {
+        return None;
+    }
+    let earned: u64 = rewarded.iter().map(|(amount, _)| amount).sum();
+    let growth = 1.0 + earned as f64 / start as f64;
+    Some((growth.powf(epochs_per_year / rewarded.len() as f64) - 1.0) * 100.0)
+}
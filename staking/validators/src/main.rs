@@ -0,0 +1,167 @@
+use clap::{Parser, ValueEnum};
+use common::Result;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::units::format_sol;
+use serde::Serialize;
+use solana_client::rpc_response::{RpcContactInfo, RpcVoteAccountInfo};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::process::ExitCode;
+
+/// List validators from `getVoteAccounts`, joined with `getClusterNodes` for
+/// their software version and gossip address, to help choose where to
+/// stake: activated stake, commission, how far behind their last vote is
+/// and whether they are delinquent.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Number of validators to print
+    #[arg(short, long, default_value_t = 20)]
+    top: usize,
+
+    /// Order of the list
+    #[arg(short, long, value_enum, default_value_t = SortBy::Stake)]
+    sort: SortBy,
+
+    /// Skip validators charging more than this commission, in percent
+    #[arg(long)]
+    max_commission: Option<u8>,
+
+    /// Also list delinquent validators
+    #[arg(long)]
+    include_delinquent: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum SortBy {
+    /// Largest activated stake first
+    Stake,
+    /// Lowest commission first, then by stake
+    Commission,
+    /// Most recent vote first
+    LastVote,
+}
+
+#[derive(Serialize)]
+struct ValidatorRow {
+    rank: usize,
+    vote_account: String,
+    identity: String,
+    activated_stake: u64,
+    /// Share of all activated stake, in percent
+    stake_share: f64,
+    commission: u8,
+    last_vote: u64,
+    /// Slots between this validator's last vote and the newest vote seen
+    vote_distance: u64,
+    delinquent: bool,
+    version: Option<String>,
+    gossip: Option<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let vote_accounts = connection.get_vote_accounts()?;
+    let nodes: HashMap<String, RpcContactInfo> = connection
+        .get_cluster_nodes()?
+        .into_iter()
+        .map(|node| (node.pubkey.clone(), node))
+        .collect();
+
+    let total_stake: u64 = vote_accounts
+        .current
+        .iter()
+        .chain(&vote_accounts.delinquent)
+        .map(|validator| validator.activated_stake)
+        .sum();
+    let newest_vote = vote_accounts
+        .current
+        .iter()
+        .map(|validator| validator.last_vote)
+        .max()
+        .unwrap_or(0);
+    let delinquent = if args.include_delinquent {
+        vote_accounts.delinquent
+    } else {
+        Vec::new()
+    };
+    let mut validators: Vec<(RpcVoteAccountInfo, bool)> = vote_accounts
+        .current
+        .into_iter()
+        .map(|validator| (validator, false))
+        .chain(delinquent.into_iter().map(|validator| (validator, true)))
+        .filter(|(validator, _)| {
+            args.max_commission
+                .is_none_or(|max| validator.commission <= max)
+        })
+        .collect();
+    match args.sort {
+        SortBy::Stake => {
+            validators.sort_by_key(|(validator, _)| Reverse(validator.activated_stake))
+        }
+        SortBy::Commission => validators.sort_by_key(|(validator, _)| {
+            (validator.commission, Reverse(validator.activated_stake))
+        }),
+        SortBy::LastVote => validators.sort_by_key(|(validator, _)| Reverse(validator.last_vote)),
+    }
+
+    let rows: Vec<ValidatorRow> = validators
+        .into_iter()
+        .take(args.top)
+        .enumerate()
+        .map(|(rank, (validator, delinquent))| {
+            let node = nodes.get(&validator.node_pubkey);
+            ValidatorRow {
+                rank: rank + 1,
+                stake_share: if total_stake == 0 {
+                    0.0
+                } else {
+                    validator.activated_stake as f64 * 100.0 / total_stake as f64
+                },
+                vote_distance: newest_vote.saturating_sub(validator.last_vote),
+                version: node.and_then(|node| node.version.clone()),
+                gossip: node
+                    .and_then(|node| node.gossip)
+                    .map(|gossip| gossip.to_string()),
+                vote_account: validator.vote_pubkey,
+                identity: validator.node_pubkey,
+                activated_stake: validator.activated_stake,
+                commission: validator.commission,
+                last_vote: validator.last_vote,
+                delinquent,
+            }
+        })
+        .collect();
+
+    args.output.print(&rows, || {
+        println!(
+            "{:>4}  {:<44}  {:>14}  {:>7}  {:>4}  {:>6}  {:<10}  Gossip",
+            "Rank", "Vote account", "Stake (SOL)", "Stake %", "Fee", "Behind", "Version"
+        );
+        for row in &rows {
+            println!(
+                "{:>4}  {:<44}  {:>14}  {:>6.2}%  {:>3}%  {:>6}  {:<10}  {}{}",
+                row.rank,
+                row.vote_account,
+                format_sol(row.activated_stake),
+                row.stake_share,
+                row.commission,
+                row.vote_distance,
+                row.version.as_deref().unwrap_or("?"),
+                row.gossip.as_deref().unwrap_or("-"),
+                if row.delinquent { "  DELINQUENT" } else { "" }
+            );
+        }
+    })
+}
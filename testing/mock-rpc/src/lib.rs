@@ -0,0 +1,332 @@
+//! A JSON-RPC server that answers from fixture files, for running the
+//! examples without an endpoint: in the integration tests under `tests/`,
+//! and from the `mock-rpc` binary while developing.
+//!
+//! A fixture file is a JSON object from method name to an answer, or to a
+//! list of answers of which the first that matches is used. An answer gives
+//! the `result` verbatim, just the `value` of a `{context, value}` result,
+//! or an `error` object, and may be limited to requests whose leading
+//! `params` equal its own:
+//!
+//! ```json
+//! {
+//!   "getSlot": { "result": 250000000 },
+//!   "getBalance": [
+//!     { "params": ["Vote111111111111111111111111111111111111111"], "value": 0 },
+//!     { "value": 1000000000 }
+//!   ],
+//!   "sendTransaction": { "error": { "code": -32002, "message": "Blockhash not found" } }
+//! }
+//! ```
+//!
+//! A method without an answer gets JSON-RPC's "method not found" error, so
+//! an unexpected call fails loudly rather than returning something made up.
+//! Only HTTP is served; the websocket examples cannot run against it.
+
+use common::{ExampleError, Result};
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::CONTENT_TYPE;
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::fs;
+use std::net::{SocketAddr, TcpListener};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use tracing::{debug, warn};
+
+/// Slot reported in the context of `value` answers.
+pub const CONTEXT_SLOT: u64 = 1;
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const PARSE_ERROR: i64 = -32700;
+
+/// Canned answers by method.
+#[derive(Debug, Clone, Default)]
+pub struct Fixtures {
+    methods: HashMap<String, Vec<Answer>>,
+}
+
+/// One canned answer; exactly one of `result`, `value` and `error` is set.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Answer {
+    /// Leading parameters a request must have for this answer to apply
+    #[serde(default)]
+    pub params: Vec<Value>,
+    pub result: Option<Value>,
+    /// Shorthand for a `result` of `{"context": {"slot": 1}, "value": ...}`
+    pub value: Option<Value>,
+    pub error: Option<Value>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Answers {
+    One(Box<Answer>),
+    Many(Vec<Answer>),
+}
+
+impl Fixtures {
+    /// Reads a fixture file, or every `.json` file in a directory in name
+    /// order.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.is_dir() {
+            return Self::from_file(path);
+        }
+        let mut files: Vec<_> = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<std::io::Result<_>>()?;
+        files.retain(|file| {
+            file.extension()
+                .is_some_and(|extension| extension == "json")
+        });
+        files.sort();
+        let mut fixtures = Self::default();
+        for file in files {
+            fixtures.extend(Self::from_file(file)?);
+        }
+        Ok(fixtures)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        Self::from_json(&fs::read_to_string(path)?)
+            .map_err(|err| ExampleError::Parse(format!("fixtures {}: {err}", path.display())))
+    }
+
+    /// Parses the contents of a fixture file.
+    pub fn from_json(text: &str) -> std::result::Result<Self, String> {
+        let parsed: HashMap<String, Answers> =
+            serde_json::from_str(text).map_err(|err| err.to_string())?;
+        let mut fixtures = Self::default();
+        for (method, answers) in parsed {
+            let answers = match answers {
+                Answers::One(answer) => vec![*answer],
+                Answers::Many(answers) => answers,
+            };
+            for answer in answers {
+                let given = [&answer.result, &answer.value, &answer.error]
+                    .iter()
+                    .filter(|field| field.is_some())
+                    .count();
+                if given != 1 {
+                    return Err(format!(
+                        "an answer to {method} needs exactly one of result, value and error"
+                    ));
+                }
+                fixtures.push(&method, answer);
+            }
+        }
+        Ok(fixtures)
+    }
+
+    /// Answers `method` with `result` whatever its parameters.
+    pub fn with_result(mut self, method: &str, result: Value) -> Self {
+        self.push(
+            method,
+            Answer {
+                result: Some(result),
+                ..Answer::default()
+            },
+        );
+        self
+    }
+
+    /// Answers `method` with a `{context, value}` result.
+    pub fn with_value(mut self, method: &str, value: Value) -> Self {
+        self.push(
+            method,
+            Answer {
+                value: Some(value),
+                ..Answer::default()
+            },
+        );
+        self
+    }
+
+    /// Adds `other`'s answers after the ones already present.
+    pub fn extend(&mut self, other: Fixtures) {
+        for (method, answers) in other.methods {
+            self.methods.entry(method).or_default().extend(answers);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.methods.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.methods.is_empty()
+    }
+
+    fn push(&mut self, method: &str, answer: Answer) {
+        self.methods
+            .entry(method.to_string())
+            .or_default()
+            .push(answer);
+    }
+
+    /// The `result` or `error` member of the response to one call.
+    fn respond(&self, method: &str, params: &Value) -> (&'static str, Value) {
+        let given = params.as_array().map(Vec::as_slice).unwrap_or_default();
+        let answer = self.methods.get(method).and_then(|answers| {
+            answers.iter().find(|answer| {
+                answer.params.len() <= given.len()
+                    && answer
+                        .params
+                        .iter()
+                        .zip(given)
+                        .all(|(want, got)| want == got)
+            })
+        });
+        let Some(answer) = answer else {
+            warn!(method, %params, "no fixture");
+            return (
+                "error",
+                json!({"code": METHOD_NOT_FOUND, "message": format!("Method not found: no fixture for {method}")}),
+            );
+        };
+        debug!(method, %params, "answered");
+        match (&answer.result, &answer.value, &answer.error) {
+            (Some(result), _, _) => ("result", result.clone()),
+            (_, Some(value), _) => (
+                "result",
+                json!({"context": {"slot": CONTEXT_SLOT}, "value": value}),
+            ),
+            (_, _, Some(error)) => ("error", error.clone()),
+            (None, None, None) => unreachable!("checked when loaded"),
+        }
+    }
+}
+
+/// A request the server received.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub params: Value,
+}
+
+/// A running server; it lives as long as the process.
+pub struct MockServer {
+    address: SocketAddr,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl MockServer {
+    /// Serves `fixtures` on a free local port.
+    pub fn start(fixtures: Fixtures) -> Result<Self> {
+        Self::bind(fixtures, 0)
+    }
+
+    /// Serves `fixtures` on `port` of the loopback interface, from a
+    /// background thread with its own runtime. Binding happens before
+    /// returning, so a port in use is reported to the caller.
+    pub fn bind(fixtures: Fixtures, port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        listener.set_nonblocking(true)?;
+        let address = listener.local_addr()?;
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let state = Arc::new((fixtures, Arc::clone(&requests)));
+        thread::Builder::new()
+            .name("mock-rpc".to_string())
+            .spawn(move || {
+                let served = tokio::runtime::Builder::new_current_thread()
+                    .enable_io()
+                    .build()
+                    .and_then(|runtime| runtime.block_on(serve(listener, state)));
+                if let Err(err) = served {
+                    warn!("mock RPC server stopped: {err}");
+                }
+            })?;
+        Ok(Self { address, requests })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Every request received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// How many times `method` was called.
+    pub fn calls(&self, method: &str) -> usize {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|request| request.method == method)
+            .count()
+    }
+}
+
+type State = Arc<(Fixtures, Arc<Mutex<Vec<RecordedRequest>>>)>;
+
+async fn serve(listener: TcpListener, state: State) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::from_std(listener)?;
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let state = Arc::clone(&state);
+        tokio::spawn(async move {
+            let service = service_fn(move |request| handle(request, Arc::clone(&state)));
+            if let Err(err) = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await
+            {
+                debug!("connection closed: {err}");
+            }
+        });
+    }
+}
+
+async fn handle(
+    request: Request<Incoming>,
+    state: State,
+) -> std::result::Result<Response<Full<Bytes>>, Infallible> {
+    let body = match request.into_body().collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(err) => return Ok(json_response(parse_error(&err.to_string()))),
+    };
+    let response = match serde_json::from_slice::<Value>(&body) {
+        // Batches get an array of responses, in the same order.
+        Ok(Value::Array(calls)) => {
+            Value::Array(calls.iter().map(|call| respond_to(call, &state)).collect())
+        }
+        Ok(call) => respond_to(&call, &state),
+        Err(err) => parse_error(&err.to_string()),
+    };
+    Ok(json_response(response))
+}
+
+fn respond_to(call: &Value, state: &State) -> Value {
+    let (fixtures, requests) = &**state;
+    let method = call["method"].as_str().unwrap_or_default();
+    let params = call.get("params").cloned().unwrap_or(Value::Null);
+    let (key, value) = fixtures.respond(method, &params);
+    requests.lock().unwrap().push(RecordedRequest {
+        method: method.to_string(),
+        params,
+    });
+    json!({"jsonrpc": "2.0", "id": call["id"], key: value})
+}
+
+fn parse_error(message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": null, "error": {"code": PARSE_ERROR, "message": message}})
+}
+
+fn json_response(body: Value) -> Response<Full<Bytes>> {
+    Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(body.to_string())))
+        .expect("static headers are valid")
+}
@@ -0,0 +1,51 @@
+use clap::Parser;
+use common::Result;
+use common::output::OutputArgs;
+use mock_rpc::{Fixtures, MockServer};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use tokio::signal;
+
+/// Serve canned JSON-RPC responses from fixture files, to run and develop
+/// the examples offline without spending endpoint credits: point them at it
+/// with `-r http://127.0.0.1:8899`.
+///
+/// Fixtures map method names to answers; see the crate documentation for the
+/// format and `fixtures/` for the ones the tests use. Calls to methods
+/// without a fixture are answered with "method not found" and logged;
+/// `RUST_LOG=mock_rpc=debug` logs every call.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Fixture files, or directories of them; answers from earlier ones are
+    /// tried first
+    #[arg(required = true)]
+    fixtures: Vec<PathBuf>,
+
+    /// Port to listen on, on 127.0.0.1
+    #[arg(short, long, default_value_t = 8899)]
+    port: u16,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let mut fixtures = Fixtures::default();
+    for path in &args.fixtures {
+        fixtures.extend(Fixtures::load(path)?);
+    }
+    let methods = fixtures.len();
+    let server = MockServer::bind(fixtures, args.port)?;
+    args.output.status(format_args!(
+        "Serving fixtures for {methods} methods on {}; Ctrl-C to stop",
+        server.url()
+    ));
+    signal::ctrl_c().await?;
+    Ok(())
+}
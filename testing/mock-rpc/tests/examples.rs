@@ -0,0 +1,286 @@
+//! Runs example binaries against [`MockServer`] with the fixtures in
+//! `fixtures/`, and checks what they print with `--output json`.
+
+use mock_rpc::{Fixtures, MockServer};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::Mutex;
+
+const WALLET: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+const TOKEN_ACCOUNT: &str = "7UX2i7SucgLMQcfZ75s3VXmZZY4YRUyJN9X1RgfMoDUi";
+const MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+/// A second wallet, holding an account of [`MINT`] in `token-holders.json`.
+const OTHER_OWNER: &str = "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu";
+
+/// Serializes the `cargo build` calls, which would otherwise wait on each
+/// other's lock anyway.
+static BUILD: Mutex<()> = Mutex::new(());
+
+fn fixture_path(file: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(file)
+}
+
+fn fixtures(name: &str) -> Fixtures {
+    Fixtures::load(fixture_path(&format!("{name}.json"))).unwrap_or_else(|err| panic!("{err}"))
+}
+
+/// The path of `package`'s binary, built first. Cargo builds only this
+/// package's own binaries for its tests, so the examples are built here;
+/// when they are up to date that is a no-op.
+fn example(package: &str) -> PathBuf {
+    let _guard = BUILD
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "--package", package])
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "building {package} failed");
+    // Test binaries live in target/<profile>/deps.
+    let profile_dir = std::env::current_exe()
+        .expect("test binary path")
+        .parent()
+        .and_then(Path::parent)
+        .expect("target directory")
+        .to_path_buf();
+    profile_dir.join(format!("{package}{}", std::env::consts::EXE_SUFFIX))
+}
+
+/// Runs `package` against `server`, isolated from the caller's config file
+/// and environment.
+fn run(package: &str, server: &MockServer, args: &[&str]) -> Output {
+    Command::new(example(package))
+        .args(["--rpc-url", &server.url(), "--max-retries", "0"])
+        .args(["--output", "json"])
+        .args(args)
+        .env("QUICKNODE_SOLANA_CONFIG", "/nonexistent/config.toml")
+        .env_remove("QUICKNODE_PROFILE")
+        .env_remove("SOLANA_RPC_URL")
+        .env_remove("SOLANA_PRIVATE_KEY")
+        .output()
+        .expect("example runs")
+}
+
+/// Stdout of a run that must succeed, parsed as JSON.
+fn run_json(package: &str, server: &MockServer, args: &[&str]) -> Value {
+    let output = run(package, server, args);
+    assert!(
+        output.status.success(),
+        "{package} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap_or_else(|err| {
+        panic!(
+            "{package} printed invalid JSON ({err}): {}",
+            String::from_utf8_lossy(&output.stdout)
+        )
+    })
+}
+
+#[test]
+fn rent_calculator_reports_exempt_account() {
+    let server = MockServer::start(fixtures("rent-calculator")).unwrap();
+    let report = run_json("rent-calculator", &server, &[WALLET]);
+    assert_eq!(
+        report,
+        json!([{
+            "account": WALLET,
+            "bytes": 165,
+            "balance": 2039280,
+            "minimum": 2039280,
+            "status": "rent-exempt"
+        }])
+    );
+    // One rent lookup per distinct size.
+    assert_eq!(server.calls("getMinimumBalanceForRentExemption"), 1);
+}
+
+#[test]
+fn token_balance_reads_legacy_token_account() {
+    let server = MockServer::start(fixtures("token-balance")).unwrap();
+    let report = run_json("token-balance-rust", &server, &["--account", TOKEN_ACCOUNT]);
+    assert_eq!(report["mint"], MINT);
+    assert_eq!(report["balance"], "1.5");
+    assert_eq!(report["program"], "Token");
+    assert_eq!(report["extensions"], json!([]));
+}
+
+#[test]
+fn token_balance_fails_for_missing_account() {
+    let server =
+        MockServer::start(Fixtures::default().with_value("getAccountInfo", Value::Null)).unwrap();
+    let output = run("token-balance-rust", &server, &["--account", TOKEN_ACCOUNT]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not found"), "unexpected error: {stderr}");
+}
+
+#[test]
+fn wallet_token_accounts_lists_both_programs() {
+    let server = MockServer::start(fixtures("wallet-token-accounts")).unwrap();
+    let accounts = run_json("wallet-token-accounts", &server, &["--wallet", WALLET]);
+    assert_eq!(
+        accounts,
+        json!([{
+            "mint": MINT,
+            "account": TOKEN_ACCOUNT,
+            "program": "Token",
+            "amount": 1500000,
+            "ui_amount": "1.5"
+        }])
+    );
+    // Token and Token-2022 are each asked once.
+    assert_eq!(server.calls("getTokenAccountsByOwner"), 2);
+}
+
+#[test]
+fn validators_skips_delinquent_by_default() {
+    let server = MockServer::start(fixtures("validators")).unwrap();
+    let validators = run_json("validators", &server, &[]);
+    let validators = validators.as_array().expect("a list of validators");
+    assert_eq!(validators.len(), 1);
+    assert_eq!(
+        validators[0]["vote_account"],
+        "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu"
+    );
+}
+
+#[test]
+fn leader_schedule_lists_upcoming_leaders() {
+    let server = MockServer::start(fixtures("leader-schedule")).unwrap();
+    let schedule = run_json("leader-schedule", &server, &[]);
+    assert_eq!(schedule["epoch"], 700);
+    assert_eq!(schedule["slot_index"], 100);
+    let upcoming = schedule["upcoming"].as_array().expect("upcoming leaders");
+    assert_eq!(
+        upcoming[0]["leader"],
+        "DE1bawNcRJB9rVm3buyMVfr8mBEoyyu73NBovf2oXJsJ"
+    );
+    assert_eq!(upcoming[0]["slot"], 300000100);
+}
+
+#[test]
+fn commitment_compare_orders_commitment_levels() {
+    let server = MockServer::start(fixtures("commitment-compare")).unwrap();
+    let rows = run_json("commitment-compare", &server, &["--rounds", "1"]);
+    let lags: Vec<(&Value, &Value)> = rows
+        .as_array()
+        .expect("a row per commitment level")
+        .iter()
+        .map(|row| (&row["commitment"], &row["slot_lag"]))
+        .collect();
+    assert_eq!(
+        lags,
+        [
+            (&json!("processed"), &json!(0)),
+            (&json!("confirmed"), &json!(2)),
+            (&json!("finalized"), &json!(34)),
+        ]
+    );
+}
+
+#[test]
+fn batch_balances_totals_per_mint() {
+    let server = MockServer::start(fixtures("batch-balances")).unwrap();
+    let totals = run_json("batch-balances", &server, &[TOKEN_ACCOUNT]);
+    assert_eq!(
+        totals,
+        json!([{
+            "mint": MINT,
+            "accounts": 1,
+            "total": "1.5",
+            "amount": 1500000
+        }])
+    );
+    // One chunk of token accounts, then one of mints.
+    assert_eq!(server.calls("getMultipleAccounts"), 2);
+}
+
+#[test]
+fn batch_balances_counts_repeated_account_once() {
+    let server = MockServer::start(fixtures("batch-balances")).unwrap();
+    let totals = run_json("batch-balances", &server, &[TOKEN_ACCOUNT, TOKEN_ACCOUNT]);
+    assert_eq!(totals[0]["accounts"], 1);
+    assert_eq!(totals[0]["amount"], 1500000);
+}
+
+#[test]
+fn token_holders_ranks_owners_by_balance() {
+    let server = MockServer::start(fixtures("token-holders")).unwrap();
+    let holders = run_json("token-holders", &server, &["--mint", MINT]);
+    let ranked: Vec<(&Value, &Value, &Value)> = holders
+        .as_array()
+        .expect("a row per holder")
+        .iter()
+        .map(|row| (&row["owner"], &row["balance"], &row["accounts"]))
+        .collect();
+    // The wallet's two accounts are summed.
+    assert_eq!(
+        ranked,
+        [
+            (&json!(OTHER_OWNER), &json!("4"), &json!(1)),
+            (&json!(WALLET), &json!("2"), &json!(2)),
+        ]
+    );
+}
+
+#[test]
+fn slot_time_dates_skipped_slot_by_next_block() {
+    let server = MockServer::start(fixtures("slot-time")).unwrap();
+    let conversion = run_json("slot-time", &server, &["time", "300000005"]);
+    assert_eq!(
+        conversion,
+        json!({
+            "slot": 300000005,
+            "timestamp": 1700000000,
+            "time": "2023-11-14T22:13:20Z",
+            "source": "next-block",
+            "clock": null
+        })
+    );
+}
+
+#[test]
+fn fee_estimate_prices_simulated_transfer() {
+    let server = MockServer::start(fixtures("fee-estimate")).unwrap();
+    let estimate = run_json(
+        "fee-estimate",
+        &server,
+        &["--payer", WALLET, "--to", OTHER_OWNER, "--amount", "0.5"],
+    );
+    // 500 simulated units plus the 10% margin.
+    assert_eq!(estimate["unit_limit"], 550);
+    // p75 of [0, 100, 200, 1000].
+    assert_eq!(estimate["micro_lamports_per_unit"], 200);
+    assert_eq!(estimate["base_fee"], 5000);
+    assert_eq!(estimate["total_cost"], 500005000);
+    assert_eq!(estimate["shortfall"], 0);
+}
+
+#[test]
+fn decode_account_labels_fields_from_schema() {
+    let server = MockServer::start(fixtures("decode-account")).unwrap();
+    let schema = fixture_path("token-account.yaml");
+    let record = run_json(
+        "decode-account",
+        &server,
+        &["--schema", schema.to_str().unwrap(), TOKEN_ACCOUNT],
+    );
+    assert_eq!(
+        record,
+        json!({
+            "address": TOKEN_ACCOUNT,
+            "owner": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "data_len": 165,
+            "trailing_bytes": 93,
+            "data": {
+                "mint": MINT,
+                "owner": WALLET,
+                "amount": 1500000
+            }
+        })
+    );
+}
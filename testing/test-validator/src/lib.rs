@@ -0,0 +1,199 @@
+//! A local `solana-test-validator` for end-to-end tests of the examples that
+//! send transactions: [`TestValidator::start`] runs one on free ports with a
+//! throwaway ledger, [`TestValidator::fund`] airdrops to fresh keypairs
+//! written where `--keypair` can read them, and [`example`] runs an example
+//! binary against it. The validator is killed and its ledger removed when
+//! the value is dropped.
+//!
+//! The validator ships with the Solana CLI tools rather than as a crate, so
+//! [`TestValidator::start`] returns `None` when it is not installed and
+//! the tests skip themselves. `SOLANA_TEST_VALIDATOR` may point at a binary
+//! that is not on `PATH`.
+
+use common::keys;
+use common::{ExampleError, Result};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use std::env;
+use std::fs;
+use std::io::ErrorKind;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Points at the validator binary when it is not on `PATH`.
+pub const VALIDATOR_ENV: &str = "SOLANA_TEST_VALIDATOR";
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(90);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Validators bind more ports than the RPC pair, so only one runs at a time.
+static RUNNING: Mutex<()> = Mutex::new(());
+
+pub struct TestValidator {
+    process: Child,
+    ledger: PathBuf,
+    rpc_url: String,
+    _running: MutexGuard<'static, ()>,
+}
+
+/// A keypair holding lamports on the test validator, also saved as a
+/// solana-keygen file.
+pub struct FundedKeypair {
+    pub keypair: Keypair,
+    pub path: PathBuf,
+}
+
+impl FundedKeypair {
+    pub fn pubkey(&self) -> Pubkey {
+        self.keypair.pubkey()
+    }
+
+    /// The file path as an argument for `--keypair`.
+    pub fn arg(&self) -> &str {
+        self.path.to_str().expect("ledger paths are UTF-8")
+    }
+}
+
+impl TestValidator {
+    /// Starts a validator and waits until it answers `getHealth`; `None`
+    /// when the binary is not installed.
+    pub fn start() -> Result<Option<Self>> {
+        let binary = env::var_os(VALIDATOR_ENV).unwrap_or_else(|| "solana-test-validator".into());
+        let running = RUNNING
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let rpc_port = free_port_pair()?;
+        let faucet_port = free_port()?;
+        let ledger =
+            env::temp_dir().join(format!("test-validator-{}-{rpc_port}", std::process::id()));
+        let spawned = Command::new(&binary)
+            .arg("--ledger")
+            .arg(&ledger)
+            .args(["--reset", "--quiet", "--bind-address", "127.0.0.1"])
+            .args(["--rpc-port", &rpc_port.to_string()])
+            .args(["--faucet-port", &faucet_port.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        let process = match spawned {
+            Ok(process) => process,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        let mut validator = Self {
+            process,
+            ledger,
+            rpc_url: format!("http://127.0.0.1:{rpc_port}"),
+            _running: running,
+        };
+        validator.wait_until_healthy()?;
+        Ok(Some(validator))
+    }
+
+    pub fn rpc_url(&self) -> &str {
+        &self.rpc_url
+    }
+
+    pub fn client(&self) -> RpcClient {
+        common::build_client(
+            &self.rpc_url,
+            CommitmentConfig::confirmed(),
+            REQUEST_TIMEOUT,
+        )
+    }
+
+    /// A new keypair with `lamports` airdropped to it, once the airdrop is
+    /// confirmed.
+    pub fn fund(&self, lamports: u64) -> Result<FundedKeypair> {
+        let keypair = Keypair::new();
+        let client = self.client();
+        let signature = client.request_airdrop(&keypair.pubkey(), lamports)?;
+        client.poll_for_signature_with_commitment(&signature, CommitmentConfig::confirmed())?;
+        let path = self.ledger.join(format!("{}.json", keypair.pubkey()));
+        keys::write_keypair_file(&keypair, &path)?;
+        Ok(FundedKeypair { keypair, path })
+    }
+
+    fn wait_until_healthy(&mut self) -> Result<()> {
+        let client = self.client();
+        let started = Instant::now();
+        loop {
+            if client.get_health().is_ok() {
+                return Ok(());
+            }
+            if let Some(status) = self.process.try_wait()? {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "solana-test-validator exited with {status}; see {}",
+                    self.ledger.join("validator.log").display()
+                )));
+            }
+            if started.elapsed() > STARTUP_TIMEOUT {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "solana-test-validator did not become healthy within {STARTUP_TIMEOUT:?}"
+                )));
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+impl Drop for TestValidator {
+    fn drop(&mut self) {
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+        let _ = fs::remove_dir_all(&self.ledger);
+    }
+}
+
+/// A command running `package`'s binary against `validator`, isolated from
+/// the caller's config file and environment, with `--output json`. The
+/// binary is built first: cargo builds only this package's own binaries for
+/// its tests, and when it is up to date that is a no-op.
+pub fn example(package: &str, validator: &TestValidator) -> Command {
+    let status = Command::new(env!("CARGO"))
+        .args(["build", "--quiet", "--package", package])
+        .status()
+        .expect("cargo runs");
+    assert!(status.success(), "building {package} failed");
+    let mut command =
+        Command::new(profile_dir().join(format!("{package}{}", env::consts::EXE_SUFFIX)));
+    command
+        .args(["--rpc-url", validator.rpc_url(), "--output", "json"])
+        .env("QUICKNODE_SOLANA_CONFIG", "/nonexistent/config.toml")
+        .env_remove("QUICKNODE_PROFILE")
+        .env_remove("SOLANA_RPC_URL")
+        .env_remove("SOLANA_PRIVATE_KEY");
+    command
+}
+
+/// `target/<profile>`, two levels above the running test binary in `deps`.
+fn profile_dir() -> PathBuf {
+    env::current_exe()
+        .expect("test binary path")
+        .parent()
+        .and_then(Path::parent)
+        .expect("target directory")
+        .to_path_buf()
+}
+
+fn free_port() -> Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+/// A free port whose successor is free too: the validator serves websockets
+/// one above its RPC port.
+fn free_port_pair() -> Result<u16> {
+    loop {
+        let port = free_port()?;
+        if port < u16::MAX && TcpListener::bind(("127.0.0.1", port + 1)).is_ok() {
+            return Ok(port);
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Runs the examples that send transactions against a local test validator
+//! and checks the chain afterwards. Each test skips itself when
+//! `solana-test-validator` is not installed.
+
+use serde_json::Value;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use spl_associated_token_account_client::address::get_associated_token_address;
+use std::process::Command;
+use test_validator::{FundedKeypair, TestValidator, example};
+
+/// Starts a validator, or returns from the test when there is none.
+macro_rules! validator_or_skip {
+    () => {
+        match TestValidator::start().expect("test validator starts") {
+            Some(validator) => validator,
+            None => {
+                eprintln!("solana-test-validator is not installed; skipping");
+                return;
+            }
+        }
+    };
+}
+
+/// Stdout of a run that must succeed, parsed as JSON.
+fn run_json(mut command: Command) -> Value {
+    let output = command.output().expect("example runs");
+    assert!(
+        output.status.success(),
+        "{:?} failed: {}",
+        command.get_program(),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).expect("example prints JSON")
+}
+
+/// Creates a mint with `supply` whole tokens of 6 decimals in `payer`'s
+/// associated token account, returning the mint.
+fn create_token(validator: &TestValidator, payer: &FundedKeypair, supply: u64) -> Pubkey {
+    let mut command = example("create-token", validator);
+    command.args(["--keypair", payer.arg()]).args([
+        "--decimals",
+        "6",
+        "--supply",
+        &supply.to_string(),
+    ]);
+    let created = run_json(command);
+    created["mint"]
+        .as_str()
+        .expect("mint in the report")
+        .parse()
+        .expect("mint is a pubkey")
+}
+
+fn token_balance(validator: &TestValidator, account: &Pubkey) -> u64 {
+    validator
+        .client()
+        .get_token_account_balance(account)
+        .expect("token account exists")
+        .amount
+        .parse()
+        .expect("amount is a number")
+}
+
+#[test]
+fn transfer_sol_moves_lamports() {
+    let validator = validator_or_skip!();
+    let payer = validator.fund(2 * LAMPORTS_PER_SOL).unwrap();
+    let recipient = Keypair::new().pubkey();
+
+    let mut command = example("transfer-sol", &validator);
+    command.args(["--keypair", payer.arg()]).args([
+        "--to",
+        &recipient.to_string(),
+        "--amount",
+        "0.5",
+    ]);
+    let transfer = run_json(command);
+
+    assert_eq!(transfer["lamports"], LAMPORTS_PER_SOL / 2);
+    assert_eq!(
+        validator.client().get_balance(&recipient).unwrap(),
+        LAMPORTS_PER_SOL / 2
+    );
+}
+
+#[test]
+fn create_token_mints_supply_to_payer() {
+    let validator = validator_or_skip!();
+    let payer = validator.fund(2 * LAMPORTS_PER_SOL).unwrap();
+
+    let mint = create_token(&validator, &payer, 1000);
+
+    let account = get_associated_token_address(&payer.pubkey(), &mint);
+    assert_eq!(token_balance(&validator, &account), 1000 * 1_000_000);
+}
+
+#[test]
+fn transfer_tokens_creates_destination_account() {
+    let validator = validator_or_skip!();
+    let payer = validator.fund(2 * LAMPORTS_PER_SOL).unwrap();
+    let mint = create_token(&validator, &payer, 1000);
+    let recipient = Keypair::new().pubkey();
+
+    let mut command = example("transfer-tokens", &validator);
+    command
+        .args(["--keypair", payer.arg(), "--mint", &mint.to_string()])
+        .args(["--to", &recipient.to_string(), "--amount", "12.5"]);
+    let transfer = run_json(command);
+
+    assert_eq!(transfer["created_destination"], true);
+    let destination = get_associated_token_address(&recipient, &mint);
+    assert_eq!(token_balance(&validator, &destination), 12_500_000);
+    let source = get_associated_token_address(&payer.pubkey(), &mint);
+    assert_eq!(token_balance(&validator, &source), 987_500_000);
+}
+
+#[test]
+fn burn_and_close_burns_then_reclaims_rent() {
+    let validator = validator_or_skip!();
+    let payer = validator.fund(2 * LAMPORTS_PER_SOL).unwrap();
+    let mint = create_token(&validator, &payer, 1000);
+    let account = get_associated_token_address(&payer.pubkey(), &mint);
+
+    let mut command = example("burn-and-close", &validator);
+    command
+        .args(["--keypair", payer.arg(), "--mint", &mint.to_string()])
+        .args(["--amount", "250", "--keep-open"]);
+    let burned = run_json(command);
+    assert_eq!(burned["burned"], 250_000_000);
+    assert_eq!(burned["closed"], false);
+    assert_eq!(token_balance(&validator, &account), 750_000_000);
+
+    let mut command = example("burn-and-close", &validator);
+    command.args(["--keypair", payer.arg(), "--mint", &mint.to_string()]);
+    let closed = run_json(command);
+    assert_eq!(closed["remaining"], 0);
+    assert_eq!(closed["closed"], true);
+    assert!(closed["refund"].as_u64().unwrap() > 0);
+    assert!(
+        validator
+            .client()
+            .get_account_with_commitment(&account, validator.client().commitment())
+            .unwrap()
+            .value
+            .is_none()
+    );
+}
@@ -0,0 +1,351 @@
+mod store;
+
+use clap::Parser;
+use common::cli::{CreditArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::retry::RetryPolicy;
+use common::{ExampleError, Result, at_least_confirmed, credits};
+use futures::{StreamExt, stream};
+use serde::Serialize;
+use solana_client::client_error::ClientErrorKind;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_client::rpc_request::RpcError;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{
+    EncodedTransaction, TransactionDetails, UiConfirmedBlock, UiTransactionEncoding,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use store::{Missing, Store};
+use tokio::{signal, time};
+use tracing::warn;
+
+/// getBlocks accepts ranges of at most this many slots.
+const GET_BLOCKS_RANGE: u64 = 500_000;
+
+/// How often progress is reported.
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// JSON-RPC error codes getBlock answers with for slots it has no block for.
+const BLOCK_CLEANED_UP: i64 = -32001;
+const BLOCK_NOT_AVAILABLE: i64 = -32004;
+const SLOT_SKIPPED: i64 = -32007;
+const LONG_TERM_STORAGE_SLOT_SKIPPED: i64 = -32009;
+
+const VOTE_PROGRAM: &str = "Vote111111111111111111111111111111111111111";
+
+/// Fetch every block in a slot range into a SQLite file, several at a time,
+/// resuming after a crash or Ctrl-C where the last run stopped.
+///
+/// getBlocks lists the slots that have a block; the others were skipped by
+/// their leader and are recorded as such without a request. The listed
+/// blocks are fetched `--concurrency` at a time but stored in slot order,
+/// each together with the range's checkpoint, so the checkpoint never
+/// passes a block that is not stored. A block the node lists but cannot
+/// serve, e.g. one missing from long-term storage, is recorded as
+/// unavailable rather than failing the run; "not available yet" and
+/// transport errors are retried with backoff.
+///
+/// Blocks are fetched with `accounts` transaction details, enough for the
+/// per-block counts, fees and compute units stored without the instruction
+/// payloads.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// First slot of the range
+    #[arg(long)]
+    start_slot: Slot,
+
+    /// Last slot of the range, inclusive; defaults to where an unfinished
+    /// run from the same start slot was going, or else the current slot
+    #[arg(long)]
+    end_slot: Option<Slot>,
+
+    /// SQLite file to store the blocks and the checkpoint in
+    #[arg(long, default_value = "blocks.sqlite")]
+    db: PathBuf,
+
+    /// Blocks fetched in parallel
+    #[arg(long, default_value_t = 8)]
+    concurrency: usize,
+
+    #[command(flatten)]
+    credits: CreditArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// One row of `blocks`.
+struct BlockRow {
+    slot: Slot,
+    blockhash: String,
+    parent_slot: Slot,
+    block_time: Option<i64>,
+    block_height: Option<u64>,
+    transactions: u64,
+    vote_transactions: u64,
+    failed_transactions: u64,
+    /// Lamports
+    fees: u64,
+    compute_units: u64,
+}
+
+/// What getBlock gave for a listed slot.
+enum Fetched {
+    Block(BlockRow),
+    /// The node has no block for it after all; the error message
+    Unavailable(String),
+}
+
+#[derive(Serialize)]
+struct Backfill {
+    start_slot: Slot,
+    end_slot: Slot,
+    /// Checkpoint this run started from, when resuming
+    resumed_from: Option<Slot>,
+    /// First slot not processed; past `end_slot` once complete
+    next_slot: Slot,
+    complete: bool,
+    blocks: u64,
+    skipped: u64,
+    unavailable: u64,
+    transactions: u64,
+    elapsed_secs: f64,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    args.credits.install()?;
+    let connection = args.rpc.nonblocking_client();
+    let commitment = at_least_confirmed(args.rpc.commitment_config());
+    let output = &args.output;
+
+    let mut store = Store::open(&args.db)?;
+    let end = match (args.end_slot, store.unfinished(args.start_slot)?) {
+        (Some(end), _) | (None, Some(end)) => end,
+        (None, None) => connection.get_slot_with_commitment(commitment).await?,
+    };
+    if end < args.start_slot {
+        return Err(ExampleError::InvalidArgument(format!(
+            "the range ends at {end}, before it starts at {}",
+            args.start_slot
+        )));
+    }
+    let range = (args.start_slot, end);
+    let resumed_from = store.checkpoint(args.start_slot, end)?;
+    let mut next = resumed_from.unwrap_or(args.start_slot);
+    match resumed_from {
+        Some(checkpoint) => output.status(format_args!(
+            "Resuming slots {}..={end} at {checkpoint}",
+            args.start_slot
+        )),
+        None => output.status(format_args!(
+            "Backfilling slots {}..={end} into {}",
+            args.start_slot,
+            args.db.display()
+        )),
+    }
+
+    let started = Instant::now();
+    let mut summary = Backfill {
+        start_slot: args.start_slot,
+        end_slot: end,
+        resumed_from,
+        next_slot: next,
+        complete: false,
+        blocks: 0,
+        skipped: 0,
+        unavailable: 0,
+        transactions: 0,
+        elapsed_secs: 0.0,
+    };
+    let mut last_progress = Instant::now();
+    let policy = args.rpc.retry_policy();
+    'pages: while next <= end {
+        let page_end = end.min(next.saturating_add(GET_BLOCKS_RANGE - 1));
+        let listed = connection
+            .get_blocks_with_commitment(next, Some(page_end), commitment)
+            .await?;
+
+        // Fetched concurrently, but yielded in slot order for the
+        // checkpoint.
+        let mut blocks = stream::iter(listed.iter().copied())
+            .map(|slot| fetch_block(&connection, &policy, slot, commitment))
+            .buffered(args.concurrency.max(1));
+        let mut listed_slots = listed.iter();
+        loop {
+            let fetched = tokio::select! {
+                fetched = blocks.next() => fetched,
+                _ = signal::ctrl_c() => {
+                    output.status("Interrupted; the checkpoint is saved");
+                    break 'pages;
+                }
+            };
+            let Some(fetched) = fetched else {
+                break;
+            };
+            let slot = *listed_slots.next().expect("one result per listed slot");
+            let skipped: Vec<Slot> = (next..slot).collect();
+            if !skipped.is_empty() {
+                store.save_missing(range, &skipped, Missing::Skipped, slot)?;
+                summary.skipped += skipped.len() as u64;
+            }
+            match fetched? {
+                Fetched::Block(block) => {
+                    store.save_block(range, &block)?;
+                    summary.blocks += 1;
+                    summary.transactions += block.transactions;
+                }
+                Fetched::Unavailable(reason) => {
+                    warn!("no block for slot {slot}: {reason}");
+                    store.save_missing(range, &[slot], Missing::Unavailable, slot + 1)?;
+                    summary.unavailable += 1;
+                }
+            }
+            next = slot + 1;
+            summary.next_slot = next;
+            if last_progress.elapsed() >= PROGRESS_INTERVAL {
+                last_progress = Instant::now();
+                report_progress(output, &summary, started.elapsed());
+            }
+        }
+
+        let skipped: Vec<Slot> = (next..=page_end).collect();
+        store.save_missing(range, &skipped, Missing::Skipped, page_end + 1)?;
+        summary.skipped += skipped.len() as u64;
+        next = page_end + 1;
+        summary.next_slot = next;
+    }
+
+    summary.complete = next > end;
+    summary.elapsed_secs = started.elapsed().as_secs_f64();
+    if let Some(stats) = credits::stats() {
+        output.status(format_args!("Credits: {stats}"));
+    }
+    output.print(&summary, || {
+        println!(
+            "{} blocks, {} skipped and {} unavailable slots, {} transactions in {:.1} s",
+            summary.blocks,
+            summary.skipped,
+            summary.unavailable,
+            summary.transactions,
+            summary.elapsed_secs
+        );
+        if summary.complete {
+            println!(
+                "Slots {}..={} are complete",
+                summary.start_slot, summary.end_slot
+            );
+        } else {
+            println!("Stopped at slot {}; run again to resume", summary.next_slot);
+        }
+    })
+}
+
+fn report_progress(output: &OutputArgs, summary: &Backfill, elapsed: Duration) {
+    let done = summary.next_slot - summary.resumed_from.unwrap_or(summary.start_slot);
+    let total = summary.end_slot + 1 - summary.resumed_from.unwrap_or(summary.start_slot);
+    output.status(format_args!(
+        "Slot {} ({:.1}%), {} blocks at {:.1} blocks/s",
+        summary.next_slot,
+        done as f64 / total as f64 * 100.0,
+        summary.blocks,
+        summary.blocks as f64 / elapsed.as_secs_f64().max(f64::EPSILON)
+    ));
+}
+
+/// Fetches a listed block, retrying with backoff while the node says it is
+/// not available yet or the request failed in transport.
+async fn fetch_block(
+    connection: &RpcClient,
+    policy: &RetryPolicy,
+    slot: Slot,
+    commitment: CommitmentConfig,
+) -> Result<Fetched> {
+    let config = RpcBlockConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        transaction_details: Some(TransactionDetails::Accounts),
+        rewards: Some(false),
+        commitment: Some(commitment),
+        max_supported_transaction_version: Some(0),
+    };
+    let mut attempt = 0;
+    loop {
+        let err: ExampleError = match connection.get_block_with_config(slot, config).await {
+            Ok(block) => return block_row(slot, &block).map(Fetched::Block),
+            Err(err) => err.into(),
+        };
+        match rpc_error_code(&err) {
+            Some(SLOT_SKIPPED | LONG_TERM_STORAGE_SLOT_SKIPPED | BLOCK_CLEANED_UP) => {
+                return Ok(Fetched::Unavailable(err.to_string()));
+            }
+            Some(BLOCK_NOT_AVAILABLE) if attempt < policy.max_retries => {}
+            _ if err.is_retryable() && attempt < policy.max_retries => {}
+            _ => return Err(err),
+        }
+        time::sleep(policy.jittered_delay(attempt)).await;
+        attempt += 1;
+    }
+}
+
+fn rpc_error_code(err: &ExampleError) -> Option<i64> {
+    let ExampleError::Rpc(err) = err else {
+        return None;
+    };
+    match &err.kind {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => Some(*code),
+        _ => None,
+    }
+}
+
+fn block_row(slot: Slot, block: &UiConfirmedBlock) -> Result<BlockRow> {
+    let mut row = BlockRow {
+        slot,
+        blockhash: block.blockhash.clone(),
+        parent_slot: block.parent_slot,
+        block_time: block.block_time,
+        block_height: block.block_height,
+        transactions: 0,
+        vote_transactions: 0,
+        failed_transactions: 0,
+        fees: 0,
+        compute_units: 0,
+    };
+    for transaction in block.transactions.iter().flatten() {
+        let EncodedTransaction::Accounts(accounts) = &transaction.transaction else {
+            return Err(ExampleError::Parse(format!(
+                "block {slot}: expected transactions with `accounts` details"
+            )));
+        };
+        row.transactions += 1;
+        if accounts
+            .account_keys
+            .iter()
+            .any(|account| account.pubkey == VOTE_PROGRAM)
+        {
+            row.vote_transactions += 1;
+        }
+        if let Some(meta) = &transaction.meta {
+            row.fees += meta.fee;
+            if meta.err.is_some() {
+                row.failed_transactions += 1;
+            }
+            if let OptionSerializer::Some(units) = meta.compute_units_consumed {
+                row.compute_units += units;
+            }
+        }
+    }
+    Ok(row)
+}
@@ -0,0 +1,153 @@
+//! The SQLite file behind `--db`: one row per block fetched, one per slot in
+//! the range that has no block, and per backfilled range the first slot not
+//! yet processed, from which a restarted run carries on.
+
+use crate::BlockRow;
+use common::{ExampleError, Result};
+use rusqlite::{Connection, OptionalExtension, params};
+use solana_sdk::clock::Slot;
+use std::path::Path;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS blocks (
+        slot                INTEGER PRIMARY KEY,
+        blockhash           TEXT NOT NULL,
+        parent_slot         INTEGER NOT NULL,
+        block_time          INTEGER,
+        block_height        INTEGER,
+        transactions        INTEGER NOT NULL,
+        vote_transactions   INTEGER NOT NULL,
+        failed_transactions INTEGER NOT NULL,
+        fees                INTEGER NOT NULL,
+        compute_units       INTEGER NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS missing_slots (
+        slot   INTEGER PRIMARY KEY,
+        reason TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS checkpoints (
+        start_slot INTEGER NOT NULL,
+        end_slot   INTEGER NOT NULL,
+        next_slot  INTEGER NOT NULL,
+        PRIMARY KEY (start_slot, end_slot)
+    );
+";
+
+/// Why a slot in the range has no block.
+#[derive(Clone, Copy)]
+pub enum Missing {
+    /// Not listed by getBlocks: the leader produced nothing
+    Skipped,
+    /// Listed, but the node no longer has it, e.g. after long-term storage
+    /// lost it
+    Unavailable,
+}
+
+impl Missing {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Skipped => "skipped",
+            Self::Unavailable => "unavailable",
+        }
+    }
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path).map_err(database_error)?;
+        connection.execute_batch(SCHEMA).map_err(database_error)?;
+        Ok(Self { connection })
+    }
+
+    /// The first unprocessed slot of the range, if it was started before.
+    pub fn checkpoint(&self, start: Slot, end: Slot) -> Result<Option<Slot>> {
+        self.connection
+            .query_row(
+                "SELECT next_slot FROM checkpoints WHERE start_slot = ?1 AND end_slot = ?2",
+                [start, end],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(database_error)
+    }
+
+    /// The end slot of the latest range from `start` that is not finished.
+    pub fn unfinished(&self, start: Slot) -> Result<Option<Slot>> {
+        self.connection
+            .query_row(
+                "SELECT end_slot FROM checkpoints WHERE start_slot = ?1 AND next_slot <= end_slot
+                 ORDER BY end_slot DESC LIMIT 1",
+                [start],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(database_error)
+    }
+
+    /// Stores a block and moves the range's checkpoint past it, in one
+    /// database transaction. Storing the same block again replaces it.
+    pub fn save_block(&mut self, (start, end): (Slot, Slot), block: &BlockRow) -> Result<()> {
+        let tx = self.connection.transaction().map_err(database_error)?;
+        tx.execute(
+            "INSERT OR REPLACE INTO blocks
+                 (slot, blockhash, parent_slot, block_time, block_height, transactions,
+                  vote_transactions, failed_transactions, fees, compute_units)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                block.slot,
+                block.blockhash,
+                block.parent_slot,
+                block.block_time,
+                block.block_height,
+                block.transactions,
+                block.vote_transactions,
+                block.failed_transactions,
+                block.fees,
+                block.compute_units
+            ],
+        )
+        .map_err(database_error)?;
+        set_checkpoint(&tx, (start, end), block.slot + 1)?;
+        tx.commit().map_err(database_error)
+    }
+
+    /// Records slots without a block and moves the checkpoint to `next`.
+    pub fn save_missing(
+        &mut self,
+        (start, end): (Slot, Slot),
+        slots: &[Slot],
+        reason: Missing,
+        next: Slot,
+    ) -> Result<()> {
+        let tx = self.connection.transaction().map_err(database_error)?;
+        for slot in slots {
+            tx.execute(
+                "INSERT OR REPLACE INTO missing_slots (slot, reason) VALUES (?1, ?2)",
+                params![slot, reason.as_str()],
+            )
+            .map_err(database_error)?;
+        }
+        set_checkpoint(&tx, (start, end), next)?;
+        tx.commit().map_err(database_error)
+    }
+}
+
+fn set_checkpoint(connection: &Connection, (start, end): (Slot, Slot), next: Slot) -> Result<()> {
+    connection
+        .execute(
+            "INSERT INTO checkpoints (start_slot, end_slot, next_slot) VALUES (?1, ?2, ?3)
+             ON CONFLICT (start_slot, end_slot) DO UPDATE SET next_slot = excluded.next_slot",
+            [start, end, next],
+        )
+        .map_err(database_error)?;
+    Ok(())
+}
+
+fn database_error(err: rusqlite::Error) -> ExampleError {
+    ExampleError::Database(err.to_string())
+}
@@ -0,0 +1,253 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::client::at_least_confirmed;
+use common::output::OutputArgs;
+use common::{ExampleError, Result};
+use serde::Serialize;
+use serde_json::{Value, json};
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::UiConfirmedBlock;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+/// Fetch one block twice, with raw `base64` transactions and with
+/// `jsonParsed`, and compare response sizes and fetch times; the raw
+/// transactions are then deserialized locally into `VersionedTransaction`s,
+/// which is all an indexer needs to decode them itself.
+///
+/// `base64+zstd` would be smaller still, but the node only offers it for
+/// account data (`getAccountInfo`, `getProgramAccounts`); `getBlock` rejects
+/// it, so `base64` is the most compact encoding a block comes in.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Block to fetch; defaults to a recent confirmed one
+    slot: Option<u64>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct EncodingComparison {
+    slot: u64,
+    encodings: Vec<EncodingFetch>,
+    /// Percent of the jsonParsed response size the base64 response saves
+    base64_saving: f64,
+    decoded: DecodedBlock,
+}
+
+#[derive(Serialize)]
+struct EncodingFetch {
+    encoding: &'static str,
+    /// Size of the JSON-RPC response body
+    response_bytes: usize,
+    fetch_ms: u128,
+    /// Time to deserialize the response, plus for base64 to decode every
+    /// transaction into a `VersionedTransaction`
+    decode_ms: u128,
+    transactions: usize,
+}
+
+/// What the locally decoded base64 transactions contain.
+#[derive(Serialize)]
+struct DecodedBlock {
+    transactions: usize,
+    legacy: usize,
+    v0: usize,
+    signatures: usize,
+    instructions: usize,
+    /// Address lookup tables referenced by v0 transactions
+    lookup_tables: usize,
+    /// Total serialized transaction size, without meta
+    transaction_bytes: usize,
+}
+
+/// How far behind the confirmed tip the default block is picked, so it is
+/// sure to be available.
+const SLOT_OFFSET: u64 = 20;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let commitment = at_least_confirmed(args.rpc.commitment_config());
+    let slot = match args.slot {
+        Some(slot) => slot,
+        None => {
+            let tip = connection.get_slot_with_commitment(commitment)?;
+            let start = tip.saturating_sub(SLOT_OFFSET);
+            *connection
+                .get_blocks_with_limit(start, 1)?
+                .first()
+                .ok_or_else(|| ExampleError::Parse(format!("no block after slot {start}")))?
+        }
+    };
+    args.output.status(format_args!("Fetching block {slot}"));
+
+    // The raw request is made by hand so the response size can be measured;
+    // RpcClient only hands back the parsed value.
+    let http = reqwest::blocking::Client::builder()
+        .timeout(args.rpc.timeout())
+        .build()?;
+    let fetch = |encoding: &str| -> Result<(Vec<u8>, Duration)> {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getBlock",
+            "params": [slot, {
+                "encoding": encoding,
+                "transactionDetails": "full",
+                "rewards": false,
+                "commitment": commitment.commitment.to_string(),
+                "maxSupportedTransactionVersion": 0,
+            }],
+        });
+        let started = Instant::now();
+        let body = http
+            .post(&args.rpc.rpc_url)
+            .json(&request)
+            .send()?
+            .error_for_status()?
+            .bytes()?;
+        Ok((body.to_vec(), started.elapsed()))
+    };
+
+    let (parsed_body, parsed_fetch) = fetch("jsonParsed")?;
+    let started = Instant::now();
+    let parsed_block = block_from_response(&parsed_body)?;
+    let parsed_decode = started.elapsed();
+
+    let (raw_body, raw_fetch) = fetch("base64")?;
+    let started = Instant::now();
+    let raw_block = block_from_response(&raw_body)?;
+    let decoded = decode_transactions(&raw_block)?;
+    let raw_decode = started.elapsed();
+
+    let transactions = |block: &UiConfirmedBlock| block.transactions.as_ref().map_or(0, Vec::len);
+    let comparison = EncodingComparison {
+        slot,
+        base64_saving: if parsed_body.is_empty() {
+            0.0
+        } else {
+            100.0 - raw_body.len() as f64 * 100.0 / parsed_body.len() as f64
+        },
+        encodings: vec![
+            EncodingFetch {
+                encoding: "jsonParsed",
+                response_bytes: parsed_body.len(),
+                fetch_ms: parsed_fetch.as_millis(),
+                decode_ms: parsed_decode.as_millis(),
+                transactions: transactions(&parsed_block),
+            },
+            EncodingFetch {
+                encoding: "base64",
+                response_bytes: raw_body.len(),
+                fetch_ms: raw_fetch.as_millis(),
+                decode_ms: raw_decode.as_millis(),
+                transactions: transactions(&raw_block),
+            },
+        ],
+        decoded,
+    };
+    args.output
+        .print(&comparison, || print_comparison(&comparison))
+}
+
+/// Unwraps a JSON-RPC response into the block it carries.
+fn block_from_response(body: &[u8]) -> Result<UiConfirmedBlock> {
+    let mut response: Value = serde_json::from_slice(body)
+        .map_err(|err| ExampleError::Parse(format!("getBlock response: {err}")))?;
+    if let Some(error) = response.get("error") {
+        return Err(ExampleError::InvalidArgument(format!(
+            "getBlock failed: {error}"
+        )));
+    }
+    let result = response["result"].take();
+    if result.is_null() {
+        return Err(ExampleError::Parse(
+            "getBlock returned no block".to_string(),
+        ));
+    }
+    serde_json::from_value(result).map_err(|err| ExampleError::Parse(format!("block: {err}")))
+}
+
+fn decode_transactions(block: &UiConfirmedBlock) -> Result<DecodedBlock> {
+    let mut decoded = DecodedBlock {
+        transactions: 0,
+        legacy: 0,
+        v0: 0,
+        signatures: 0,
+        instructions: 0,
+        lookup_tables: 0,
+        transaction_bytes: 0,
+    };
+    for (index, entry) in block.transactions.iter().flatten().enumerate() {
+        let transaction = entry
+            .transaction
+            .decode()
+            .ok_or_else(|| ExampleError::Parse(format!("transaction {index} of the block")))?;
+        decoded.transactions += 1;
+        decoded.signatures += transaction.signatures.len();
+        decoded.instructions += transaction.message.instructions().len();
+        decoded.transaction_bytes += wire_size(&transaction);
+        match &transaction.message {
+            VersionedMessage::Legacy(_) => decoded.legacy += 1,
+            VersionedMessage::V0(message) => {
+                decoded.v0 += 1;
+                decoded.lookup_tables += message.address_table_lookups.len();
+            }
+        }
+    }
+    Ok(decoded)
+}
+
+/// Wire size of a transaction: signatures with their length prefix plus the
+/// serialized message.
+fn wire_size(transaction: &VersionedTransaction) -> usize {
+    let signatures = transaction.signatures.len();
+    // The count is a compact-u16: one byte below 128.
+    let prefix = if signatures < 0x80 { 1 } else { 2 };
+    prefix + signatures * 64 + transaction.message.serialize().len()
+}
+
+fn print_comparison(comparison: &EncodingComparison) {
+    println!("Block {}", comparison.slot);
+    println!();
+    println!(
+        "  {:<11} {:>14} {:>10} {:>11} {:>13}",
+        "Encoding", "Response", "Fetch ms", "Decode ms", "Transactions"
+    );
+    for fetch in &comparison.encodings {
+        println!(
+            "  {:<11} {:>12} B {:>10} {:>11} {:>13}",
+            fetch.encoding,
+            fetch.response_bytes,
+            fetch.fetch_ms,
+            fetch.decode_ms,
+            fetch.transactions
+        );
+    }
+    println!();
+    println!(
+        "base64 is {:.1}% smaller than jsonParsed",
+        comparison.base64_saving
+    );
+    let decoded = &comparison.decoded;
+    println!(
+        "Decoded {} transactions locally ({} legacy, {} v0): {} signatures, {} instructions, {} lookup tables, {} bytes on the wire",
+        decoded.transactions,
+        decoded.legacy,
+        decoded.v0,
+        decoded.signatures,
+        decoded.instructions,
+        decoded.lookup_tables,
+        decoded.transaction_bytes
+    );
+}
@@ -0,0 +1,234 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::decode::{self, Decoder};
+use common::output::OutputArgs;
+use common::time::format_timestamp;
+use common::units::{format_change, format_sol, format_token_amount};
+use common::{ExampleError, Result, at_least_confirmed};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use std::process::ExitCode;
+
+/// Fetch a transaction and break it down: each instruction (inner ones
+/// included) decoded by program, token balances before and after, and the
+/// net SOL change of every account.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Transaction signature (base58)
+    signature: Signature,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct DecodedTransaction {
+    signature: String,
+    slot: u64,
+    time: Option<String>,
+    error: Option<String>,
+    /// Fee in lamports
+    fee: u64,
+    compute_units: Option<u64>,
+    instructions: Vec<InstructionRow>,
+    token_balances: Vec<TokenBalanceRow>,
+    sol_changes: Vec<SolChange>,
+}
+
+#[derive(Serialize)]
+struct InstructionRow {
+    depth: usize,
+    program_id: String,
+    program: String,
+    /// Instruction type; absent when no decoder handles the program
+    kind: Option<String>,
+    fields: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct TokenBalanceRow {
+    account: String,
+    mint: String,
+    owner: Option<String>,
+    decimals: u8,
+    before: u64,
+    after: u64,
+}
+
+#[derive(Serialize)]
+struct SolChange {
+    account: String,
+    /// Balances in lamports
+    before: u64,
+    after: u64,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    // A binary encoding returns the message exactly as signed, and inner
+    // instructions in compiled form, so everything goes through the same
+    // decoder instead of the node's jsonParsed output.
+    let confirmed = connection.get_transaction_with_config(
+        &args.signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(at_least_confirmed(connection.commitment())),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let transaction = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| ExampleError::Parse("transaction is not base64 encoded".to_string()))?;
+    let meta = confirmed
+        .transaction
+        .meta
+        .ok_or_else(|| ExampleError::Parse("the node returned no status metadata".to_string()))?;
+    let decoder = Decoder::default();
+    let keys = decode::account_keys(&transaction.message, &meta)?;
+
+    let instructions = decoder
+        .decode_transaction(&transaction.message, &meta)?
+        .into_iter()
+        .map(|summary| {
+            let (kind, fields) = match summary.decoded {
+                Some(decoded) => (
+                    Some(decoded.kind),
+                    decoded
+                        .fields
+                        .into_iter()
+                        .map(|(name, value)| (name, Value::String(value)))
+                        .collect(),
+                ),
+                None => (None, Map::new()),
+            };
+            InstructionRow {
+                depth: summary.depth,
+                program_id: summary.program_id.to_string(),
+                program: summary.program,
+                kind,
+                fields,
+            }
+        })
+        .collect();
+    let token_balances = decode::token_balance_changes(&meta)
+        .into_iter()
+        .map(|change| TokenBalanceRow {
+            account: keys
+                .get(change.account_index)
+                .map_or_else(|| "?".to_string(), |key| key.to_string()),
+            mint: change.mint,
+            owner: change.owner,
+            decimals: change.decimals,
+            before: change.before,
+            after: change.after,
+        })
+        .collect();
+    let sol_changes = keys
+        .iter()
+        .zip(meta.pre_balances.iter().zip(&meta.post_balances))
+        .filter(|(_, (pre, post))| pre != post)
+        .map(|(key, (&before, &after))| SolChange {
+            account: key.to_string(),
+            before,
+            after,
+        })
+        .collect();
+    let decoded = DecodedTransaction {
+        signature: args.signature.to_string(),
+        slot: confirmed.slot,
+        time: confirmed.block_time.map(format_timestamp),
+        error: meta.err.as_ref().map(ToString::to_string),
+        fee: meta.fee,
+        compute_units: match meta.compute_units_consumed {
+            OptionSerializer::Some(units) => Some(units),
+            _ => None,
+        },
+        instructions,
+        token_balances,
+        sol_changes,
+    };
+    args.output.print(&decoded, || print_table(&decoded))
+}
+
+fn print_table(decoded: &DecodedTransaction) {
+    println!("Signature: {}", decoded.signature);
+    println!("Slot:      {}", decoded.slot);
+    if let Some(time) = &decoded.time {
+        println!("Time:      {time}");
+    }
+    match &decoded.error {
+        Some(err) => println!("Status:    failed: {err}"),
+        None => println!("Status:    success"),
+    }
+    println!("Fee:       {} SOL", format_sol(decoded.fee));
+    if let Some(units) = decoded.compute_units {
+        println!("Compute:   {units} units");
+    }
+
+    println!();
+    println!("Instructions");
+    let mut number = 0;
+    for instruction in &decoded.instructions {
+        let indent = "  ".repeat(instruction.depth);
+        let label = if instruction.depth == 1 {
+            number += 1;
+            format!("#{number}")
+        } else {
+            "->".to_string()
+        };
+        match &instruction.kind {
+            Some(kind) => {
+                println!("{indent}{label} {}: {kind}", instruction.program);
+                for (name, value) in &instruction.fields {
+                    println!(
+                        "{indent}     {name}: {}",
+                        value.as_str().unwrap_or_default()
+                    );
+                }
+            }
+            None => println!("{indent}{label} {}: <not decoded>", instruction.program),
+        }
+    }
+
+    if !decoded.token_balances.is_empty() {
+        println!();
+        println!("Token balances");
+        for change in &decoded.token_balances {
+            println!("  {}", change.account);
+            println!("    mint   {}", change.mint);
+            if let Some(owner) = &change.owner {
+                println!("    owner  {owner}");
+            }
+            println!(
+                "    {} -> {} ({})",
+                format_token_amount(change.before, change.decimals),
+                format_token_amount(change.after, change.decimals),
+                format_change(change.before, change.after, change.decimals)
+            );
+        }
+    }
+
+    println!();
+    println!("SOL changes");
+    for change in &decoded.sol_changes {
+        println!(
+            "  {:<44}  {:>20} SOL",
+            change.account,
+            format_change(change.before, change.after, 9)
+        );
+    }
+}
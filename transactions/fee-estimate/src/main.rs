@@ -0,0 +1,218 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::fees::{self, FeeEstimate, PriorityFee};
+use common::output::OutputArgs;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+
+/// Predict what a SOL transfer will cost before anything is signed: the
+/// base fee and compute budget surcharge as `getFeeForMessage` prices them,
+/// with the unit price taken from recent prioritization fees. Warns when the
+/// payer cannot cover the fee and the amount sent.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet that would pay for and send the transfer
+    #[arg(long)]
+    payer: Pubkey,
+
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send, in SOL (e.g. 0.01)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    /// Percentile of recent fees to pay, 0-100
+    #[arg(short, long, default_value_t = 75, value_parser = clap::value_parser!(u8).range(0..=100))]
+    percentile: u8,
+
+    /// Compute unit limit to request; measured by simulating the transfer
+    /// when omitted
+    #[arg(short, long)]
+    unit_limit: Option<u32>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct CostEstimate {
+    payer: String,
+    to: String,
+    lamports: u64,
+    signatures: u8,
+    base_fee: u64,
+    unit_limit: u32,
+    micro_lamports_per_unit: u64,
+    priority_fee: u64,
+    total_fee: u64,
+    /// Fee plus the amount sent
+    total_cost: u64,
+    balance: u64,
+    /// Lamports the payer is missing, counting the rent-exempt minimum it
+    /// has to keep unless it is emptied completely
+    shortfall: u64,
+}
+
+/// Largest compute unit limit a transaction can request.
+const MAX_UNIT_LIMIT: u32 = 1_400_000;
+
+/// Headroom added to the simulated compute units, since a transaction's
+/// consumption can vary slightly between simulation and execution.
+const UNIT_MARGIN: f64 = 1.1;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    let instructions = [system_instruction::transfer(
+        &args.payer,
+        &args.to,
+        args.amount,
+    )];
+
+    let recent = fees::recent_fees(&connection, &[args.payer, args.to])?;
+    let unit_limit = match args.unit_limit {
+        Some(limit) => limit,
+        None => {
+            let units = simulated_units(&connection, &args.payer, &instructions)?;
+            let limit = ((units as f64 * UNIT_MARGIN).ceil() as u32).min(MAX_UNIT_LIMIT);
+            output.status(format_args!(
+                "Simulation used {units} compute units; requesting {limit}"
+            ));
+            limit
+        }
+    };
+    let priority_fee = PriorityFee {
+        unit_limit,
+        micro_lamports_per_unit: fees::percentile(&recent, args.percentile),
+    };
+    output.status(format_args!(
+        "p{} of recent prioritization fees over {} slots: {} micro-lamports/CU",
+        args.percentile,
+        recent.len(),
+        priority_fee.micro_lamports_per_unit
+    ));
+
+    let blockhash = connection.get_latest_blockhash()?;
+    let estimate = fees::estimate_fee(
+        &connection,
+        &args.payer,
+        &instructions,
+        Some(&priority_fee),
+        &blockhash,
+    )?;
+    let balance = connection.get_balance(&args.payer)?;
+    let rent_minimum = connection.get_minimum_balance_for_rent_exemption(0)?;
+    let shortfall = shortfall(&estimate, args.amount, balance, rent_minimum);
+    if shortfall > 0 {
+        output.status(format_args!(
+            "warning: {} cannot afford this transaction; it is {} SOL short",
+            args.payer,
+            format_sol(shortfall)
+        ));
+    }
+
+    let cost = CostEstimate {
+        payer: args.payer.to_string(),
+        to: args.to.to_string(),
+        lamports: args.amount,
+        signatures: estimate.signatures,
+        base_fee: estimate.base_fee,
+        unit_limit,
+        micro_lamports_per_unit: priority_fee.micro_lamports_per_unit,
+        priority_fee: estimate.priority_fee,
+        total_fee: estimate.total(),
+        total_cost: estimate.total() + args.amount,
+        balance,
+        shortfall,
+    };
+    output.print(&cost, || {
+        println!(
+            "Base fee:      {} lamports ({} signature{})",
+            cost.base_fee,
+            cost.signatures,
+            if cost.signatures == 1 { "" } else { "s" }
+        );
+        println!(
+            "Priority fee:  {} lamports ({} CU x {} micro-lamports)",
+            cost.priority_fee, cost.unit_limit, cost.micro_lamports_per_unit
+        );
+        println!(
+            "Total fee:     {} lamports ({} SOL)",
+            cost.total_fee,
+            format_sol(cost.total_fee)
+        );
+        println!(
+            "Total cost:    {} SOL including the transfer",
+            format_sol(cost.total_cost)
+        );
+        println!("Balance:       {} SOL", format_sol(cost.balance));
+    })
+}
+
+/// Compute units the instructions consume, plus the two compute budget
+/// instructions the real transaction will carry. The transaction is left
+/// unsigned; the node is told not to check signatures.
+fn simulated_units(
+    connection: &RpcClient,
+    payer: &Pubkey,
+    instructions: &[Instruction],
+) -> Result<u64> {
+    let placeholder = PriorityFee {
+        unit_limit: MAX_UNIT_LIMIT,
+        micro_lamports_per_unit: 0,
+    };
+    let message = Message::new(
+        &fees::with_priority_fee(&placeholder, instructions),
+        Some(payer),
+    );
+    let simulation = connection
+        .simulate_transaction_with_config(
+            &Transaction::new_unsigned(message),
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(connection.commitment()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )?
+        .value;
+    match (simulation.err, simulation.units_consumed) {
+        (None, Some(units)) => Ok(units),
+        (Some(err), _) => Err(ExampleError::InvalidArgument(format!(
+            "simulation failed ({err}); pass --unit-limit to price the transaction anyway"
+        ))),
+        (None, None) => Err(ExampleError::Parse(
+            "simulation did not report compute units".to_string(),
+        )),
+    }
+}
+
+/// Lamports missing for the payer to send `amount` and pay the fee. A payer
+/// left with a balance below the rent-exempt minimum makes the transaction
+/// fail, so that minimum counts too, unless the transfer empties the account.
+fn shortfall(estimate: &FeeEstimate, amount: u64, balance: u64, rent_minimum: u64) -> u64 {
+    let cost = estimate.total() + amount;
+    if cost == balance {
+        return 0;
+    }
+    (cost + rent_minimum).saturating_sub(balance)
+}
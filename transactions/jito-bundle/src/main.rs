@@ -0,0 +1,204 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::jito::{self, DEFAULT_BLOCK_ENGINE_URL, MIN_TIP_LAMPORTS};
+use common::memo::memo_instruction;
+use common::output::OutputArgs;
+use common::units::format_sol;
+use common::{ExampleError, Result, explorer};
+use rand::seq::SliceRandom;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+use std::slice;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Build a two-transaction Jito bundle, a memo payload followed by a tip,
+/// submit it to the block engine with `sendBundle` and poll until it lands.
+/// The bundle executes atomically: both transactions land in order, or
+/// neither does.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Pays for and signs both transactions
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Bundle endpoint: the Jito block engine, or a QuickNode endpoint with
+    /// the Lil' JIT add-on
+    #[arg(long, env = "JITO_BLOCK_ENGINE_URL", default_value = DEFAULT_BLOCK_ENGINE_URL)]
+    block_engine_url: String,
+
+    /// Tip in lamports; the block engine rejects bundles below 1000
+    #[arg(long, default_value_t = MIN_TIP_LAMPORTS)]
+    tip: u64,
+
+    /// Memo written by the payload transaction
+    #[arg(short, long, default_value = "Hello from a Jito bundle")]
+    memo: String,
+
+    /// Simulate the bundle and stop (Lil' JIT endpoints only)
+    #[arg(long)]
+    simulate_only: bool,
+
+    /// Give up polling after this many seconds
+    #[arg(long, default_value_t = 60)]
+    poll_timeout: u64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct LandedBundle {
+    bundle_id: String,
+    tip_account: String,
+    tip: u64,
+    slot: Option<u64>,
+    confirmation_status: Option<String>,
+    signatures: Vec<String>,
+}
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    if args.tip < MIN_TIP_LAMPORTS {
+        return Err(ExampleError::InvalidArgument(format!(
+            "the minimum tip is {MIN_TIP_LAMPORTS} lamports"
+        )));
+    }
+    let connection = args.rpc.client();
+    let block_engine =
+        RpcClient::new_with_timeout(args.block_engine_url.clone(), args.rpc.timeout());
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    // Picking a random tip account spreads write locks across the eight
+    // accounts instead of contending on one.
+    let tip_accounts = jito::get_tip_accounts(&block_engine)?;
+    let tip_account = *tip_accounts
+        .choose(&mut rand::thread_rng())
+        .ok_or_else(|| ExampleError::Parse("the block engine returned no tip accounts".into()))?;
+    output.status(format_args!("Tip account: {tip_account}"));
+
+    let blockhash = connection.get_latest_blockhash()?;
+    let payload = Transaction::new_signed_with_payer(
+        &[memo_instruction(&args.memo, &[&payer.pubkey()])],
+        Some(&payer.pubkey()),
+        &[&payer],
+        blockhash,
+    );
+    // The tip goes last so that it is only paid if everything before it
+    // succeeded.
+    let tip = Transaction::new_signed_with_payer(
+        &[system_instruction::transfer(
+            &payer.pubkey(),
+            &tip_account,
+            args.tip,
+        )],
+        Some(&payer.pubkey()),
+        &[&payer],
+        blockhash,
+    );
+    let signatures = [payload.signatures[0], tip.signatures[0]];
+    let encoded = [&payload, &tip]
+        .iter()
+        .map(|transaction| {
+            bincode::serialize(transaction)
+                .map(|bytes| BASE64.encode(bytes))
+                .map_err(|err| ExampleError::Parse(format!("serializing transaction: {err}")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    if args.simulate_only {
+        // The simulation result has no fixed schema; the table view shows it
+        // as pretty-printed JSON too.
+        let simulation = jito::simulate_bundle(&block_engine, &encoded)?;
+        return output.print(&simulation, || {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&simulation).unwrap_or_default()
+            )
+        });
+    }
+
+    let bundle_id = jito::send_bundle(&block_engine, &encoded)?;
+    output.status(format_args!(
+        "Bundle {bundle_id} sent with a {} SOL tip",
+        format_sol(args.tip)
+    ));
+
+    let deadline = Instant::now() + Duration::from_secs(args.poll_timeout);
+    let mut last_status = String::new();
+    loop {
+        if Instant::now() > deadline {
+            return Err(ExampleError::InvalidArgument(format!(
+                "bundle {bundle_id} did not land within {}s (last status: {last_status})",
+                args.poll_timeout
+            )));
+        }
+        thread::sleep(POLL_INTERVAL);
+        let status =
+            jito::get_inflight_bundle_statuses(&block_engine, slice::from_ref(&bundle_id))?
+                .into_iter()
+                .next()
+                .map_or_else(|| "Unknown".to_string(), |status| status.status);
+        if status != last_status {
+            output.status(format_args!("  {status}"));
+            last_status = status;
+        }
+        match last_status.as_str() {
+            "Landed" => break,
+            "Failed" | "Invalid" => {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "bundle {bundle_id} {}",
+                    last_status.to_lowercase()
+                )));
+            }
+            _ => {}
+        }
+    }
+
+    let landed = jito::get_bundle_statuses(&block_engine, slice::from_ref(&bundle_id))?
+        .into_iter()
+        .next()
+        .flatten();
+    if let Some(landed) = &landed {
+        output.status(format_args!(
+            "Landed in slot {} ({})",
+            landed.slot,
+            landed.confirmation_status.as_deref().unwrap_or("processed")
+        ));
+    }
+    for signature in &signatures {
+        output.status(format_args!("Signature: {signature}"));
+        output.status(format_args!(
+            "Explorer:  {}",
+            explorer::transaction_url(signature, &args.rpc.rpc_url)
+        ));
+    }
+    output.status(format_args!(
+        "Bundle:    https://explorer.jito.wtf/bundle/{bundle_id}"
+    ));
+    let result = LandedBundle {
+        tip_account: tip_account.to_string(),
+        tip: args.tip,
+        slot: landed.as_ref().map(|landed| landed.slot),
+        confirmation_status: landed.and_then(|landed| landed.confirmation_status),
+        signatures: signatures.iter().map(ToString::to_string).collect(),
+        bundle_id,
+    };
+    output.print_structured(&result)
+}
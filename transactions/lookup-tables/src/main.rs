@@ -0,0 +1,203 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::{TxBuilder, fetch_lookup_tables};
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, at_least_confirmed, explorer};
+use serde::Serialize;
+use solana_address_lookup_table_interface::instruction::{
+    create_lookup_table, extend_lookup_table,
+};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction_status_client_types::{UiLoadedAddresses, UiTransactionEncoding};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+/// Create an address lookup table holding a set of recipients, send them SOL
+/// in a single v0 transaction that references the table, then decode the
+/// landed transaction back into its full account list.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer keypair, also used as the lookup table authority
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Reuse an existing lookup table instead of creating one
+    #[arg(long)]
+    table: Option<Pubkey>,
+
+    /// Amount to send to each recipient, in SOL
+    #[arg(short, long, value_parser = parse_sol, default_value = "0.001")]
+    amount: u64,
+
+    /// Recipient wallets; stored in a new table or expected in --table
+    #[arg(required = true)]
+    recipients: Vec<Pubkey>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct LookupTableTransfer {
+    table: String,
+    lamports_each: u64,
+    recipients: Vec<String>,
+    /// Serialized transaction size in bytes
+    size: u64,
+    signature: String,
+    #[serde(flatten)]
+    keys: AccountKeys,
+}
+
+/// The landed transaction's account list, split by where each key came from.
+#[derive(Serialize)]
+struct AccountKeys {
+    static_keys: Vec<String>,
+    loaded_writable: Vec<String>,
+    loaded_readonly: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    let table = match args.table {
+        Some(table) => table,
+        None => create_table(&connection, &args, &payer)?,
+    };
+    let tables = fetch_lookup_tables(&connection, &[table])?;
+    output.status(format_args!(
+        "Lookup table {table} holds {} addresses",
+        tables[0].addresses.len()
+    ));
+
+    let instructions: Vec<_> = args
+        .recipients
+        .iter()
+        .map(|recipient| system_instruction::transfer(&payer.pubkey(), recipient, args.amount))
+        .collect();
+    let builder = TxBuilder::new(&payer)
+        .add_instructions(instructions)
+        .with_lookup_tables(tables);
+    let legacy = builder.budget();
+    output.status(format_args!(
+        "Without the table: {legacy}{}",
+        legacy
+            .suggestion()
+            .map(|suggestion| format!(" ({suggestion})"))
+            .unwrap_or_default()
+    ));
+    let transaction = builder.build(connection.get_latest_blockhash()?)?;
+    let size = bincode::serialized_size(&transaction).unwrap_or_default();
+    output.status(format_args!(
+        "Sending {} SOL to each of {} recipients ({size} bytes serialized)",
+        format_sol(args.amount),
+        args.recipients.len(),
+    ));
+    let signature = builder.sign_and_send(&connection)?;
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+
+    let keys = fetch_account_keys(&connection, &signature)?;
+    let transfer = LookupTableTransfer {
+        table: table.to_string(),
+        lamports_each: args.amount,
+        recipients: args.recipients.iter().map(Pubkey::to_string).collect(),
+        size,
+        signature: signature.to_string(),
+        keys,
+    };
+    output.print(&transfer, || print_account_keys(&transfer.keys))
+}
+
+fn create_table(connection: &RpcClient, args: &Args, payer: &Keypair) -> Result<Pubkey> {
+    // The table address is derived from a slot that must still be in SlotHashes.
+    let recent_slot = connection.get_slot_with_commitment(CommitmentConfig::finalized())?;
+    let (create, table) = create_lookup_table(payer.pubkey(), payer.pubkey(), recent_slot);
+    let extend = extend_lookup_table(
+        table,
+        payer.pubkey(),
+        Some(payer.pubkey()),
+        args.recipients.clone(),
+    );
+    let signature = TxBuilder::new(payer)
+        .add_instructions([create, extend])
+        .sign_and_send(connection)?;
+    args.output
+        .status(format_args!("Created lookup table {table}: {signature}"));
+
+    // Extended addresses only become usable once the extending slot has passed.
+    let extended_at = connection.get_slot()?;
+    while connection.get_slot()? <= extended_at {
+        thread::sleep(Duration::from_millis(400));
+    }
+    Ok(table)
+}
+
+/// A v0 message only lists its static keys; the rest are resolved by the
+/// runtime and reported back in `meta.loadedAddresses`.
+fn fetch_account_keys(connection: &RpcClient, signature: &Signature) -> Result<AccountKeys> {
+    let fetched = connection.get_transaction_with_config(
+        signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(at_least_confirmed(connection.commitment())),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let transaction = fetched
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| ExampleError::Parse(format!("transaction {signature}")))?;
+    let loaded: Option<UiLoadedAddresses> = fetched
+        .transaction
+        .meta
+        .and_then(|meta| meta.loaded_addresses.into());
+    let loaded = loaded.unwrap_or_default();
+    Ok(AccountKeys {
+        static_keys: transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .map(Pubkey::to_string)
+            .collect(),
+        loaded_writable: loaded.writable,
+        loaded_readonly: loaded.readonly,
+    })
+}
+
+fn print_account_keys(keys: &AccountKeys) {
+    println!("Static account keys:");
+    for key in &keys.static_keys {
+        println!("  {key}");
+    }
+    if !keys.loaded_writable.is_empty() || !keys.loaded_readonly.is_empty() {
+        println!("Loaded from lookup tables:");
+        for key in &keys.loaded_writable {
+            println!("  {key} (writable)");
+        }
+        for key in &keys.loaded_readonly {
+            println!("  {key} (readonly)");
+        }
+    }
+}
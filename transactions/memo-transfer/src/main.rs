@@ -0,0 +1,115 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::memo;
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, at_least_confirmed, explorer};
+use serde::Serialize;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_system_interface::instruction as system_instruction;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::process::ExitCode;
+
+/// Send SOL with a UTF-8 memo attached through the SPL Memo program, then
+/// fetch the confirmed transaction in `jsonParsed` form and read the memo
+/// back out of it.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Sender of the transfer; also signs the memo
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send, in SOL (e.g. 0.01)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    /// Text to attach, e.g. an invoice number
+    #[arg(short, long)]
+    memo: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct MemoTransfer {
+    from: String,
+    to: String,
+    lamports: u64,
+    signature: String,
+    /// Memos found in the confirmed transaction
+    memos: Vec<String>,
+}
+
+/// Memo data shares the 1232-byte transaction with everything else; a
+/// transfer plus one signed memo leaves room for a little over 500 bytes.
+const MAX_MEMO_BYTES: usize = 500;
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    if args.memo.len() > MAX_MEMO_BYTES {
+        return Err(ExampleError::InvalidArgument(format!(
+            "the memo is {} bytes; keep it under {MAX_MEMO_BYTES}",
+            args.memo.len()
+        )));
+    }
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    output.status(format_args!(
+        "Sending {} SOL from {} to {} with memo {:?}",
+        format_sol(args.amount),
+        payer.pubkey(),
+        args.to,
+        args.memo
+    ));
+    let signature = TxBuilder::new(&payer)
+        .add_instruction(system_instruction::transfer(
+            &payer.pubkey(),
+            &args.to,
+            args.amount,
+        ))
+        .with_memo(args.memo.as_str())
+        .sign_and_send(&connection)?;
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+
+    let confirmed = connection.get_transaction_with_config(
+        &signature,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            commitment: Some(at_least_confirmed(connection.commitment())),
+            max_supported_transaction_version: Some(0),
+        },
+    )?;
+    let memos = memo::memos(&confirmed.transaction.transaction);
+    for memo in &memos {
+        output.status(format_args!("Memo read back: {memo:?}"));
+    }
+    let result = MemoTransfer {
+        from: payer.pubkey().to_string(),
+        to: args.to.to_string(),
+        lamports: args.amount,
+        signature: signature.to_string(),
+        memos,
+    };
+    output.print_structured(&result)
+}
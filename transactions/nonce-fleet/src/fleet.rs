@@ -0,0 +1,219 @@
+//! The fleet's nonce accounts and the ledger of the ones in flight.
+//!
+//! The accounts are derived with `create_with_seed` from the authority and
+//! `<prefix><index>`, so the same flags always name the same fleet and no
+//! keypair files need to be kept. The ledger is a small JSON file recording,
+//! per account, the transaction that is using its nonce; it is rewritten
+//! before a transaction is sent and after one is settled, so a sender that
+//! dies in between knows on restart which nonces may still be taken.
+
+use common::{ExampleError, Result, batch};
+use serde::{Deserialize, Serialize};
+use solana_client::nonce_utils;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_system_interface::program as system_program;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A transaction signed against a nonce and not yet settled.
+#[derive(Debug, Clone)]
+pub struct InFlight {
+    pub signature: Signature,
+    /// The nonce value the transaction was signed with
+    pub nonce: Hash,
+    /// Unix seconds
+    pub sent_at: u64,
+}
+
+impl InFlight {
+    pub fn new(signature: Signature, nonce: Hash) -> Self {
+        Self {
+            signature,
+            nonce,
+            sent_at: now(),
+        }
+    }
+
+    pub fn age(&self) -> u64 {
+        now().saturating_sub(self.sent_at)
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Ledger {
+    /// Keyed by nonce account
+    in_flight: BTreeMap<String, LedgerEntry>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LedgerEntry {
+    signature: String,
+    nonce: String,
+    sent_at: u64,
+}
+
+impl LedgerEntry {
+    fn parse(&self) -> Option<InFlight> {
+        Some(InFlight {
+            signature: self.signature.parse().ok()?,
+            nonce: self.nonce.parse().ok()?,
+            sent_at: self.sent_at,
+        })
+    }
+}
+
+impl From<&InFlight> for LedgerEntry {
+    fn from(in_flight: &InFlight) -> Self {
+        Self {
+            signature: in_flight.signature.to_string(),
+            nonce: in_flight.nonce.to_string(),
+            sent_at: in_flight.sent_at,
+        }
+    }
+}
+
+pub struct NonceSlot {
+    pub seed: String,
+    pub address: Pubkey,
+    /// The account's current nonce, `None` until fetched and again once a
+    /// transaction has used it
+    pub nonce: Option<Hash>,
+    pub in_flight: Option<InFlight>,
+}
+
+pub struct Fleet {
+    pub slots: Vec<NonceSlot>,
+    /// Ledger entries of accounts outside this fleet, e.g. after --size was
+    /// lowered; kept so they are not forgotten.
+    others: BTreeMap<Pubkey, InFlight>,
+    cursor: usize,
+}
+
+impl Fleet {
+    pub fn new(authority: &Pubkey, prefix: &str, size: usize) -> Result<Self> {
+        let slots = (0..size)
+            .map(|index| {
+                let seed = format!("{prefix}{index}");
+                let address = Pubkey::create_with_seed(authority, &seed, &system_program::ID)
+                    .map_err(|err| {
+                        ExampleError::InvalidArgument(format!("seed `{seed}`: {err}"))
+                    })?;
+                Ok(NonceSlot {
+                    seed,
+                    address,
+                    nonce: None,
+                    in_flight: None,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            slots,
+            others: BTreeMap::new(),
+            cursor: 0,
+        })
+    }
+
+    /// Restores the in-flight entries of a previous run; a missing ledger is
+    /// an empty one.
+    pub fn load_ledger(&mut self, path: &Path) -> Result<()> {
+        if !path.exists() {
+            return Ok(());
+        }
+        let ledger: Ledger = serde_json::from_slice(&fs::read(path)?)
+            .map_err(|err| ExampleError::Parse(format!("{}: {err}", path.display())))?;
+        for (address, entry) in &ledger.in_flight {
+            let (Ok(address), Some(in_flight)) = (address.parse::<Pubkey>(), entry.parse()) else {
+                return Err(ExampleError::Parse(format!(
+                    "{}: entry for {address}",
+                    path.display()
+                )));
+            };
+            match self.slots.iter_mut().find(|slot| slot.address == address) {
+                Some(slot) => slot.in_flight = Some(in_flight),
+                None => {
+                    self.others.insert(address, in_flight);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the ledger to a temporary file first, so a crash mid-write
+    /// leaves the previous one intact.
+    pub fn save_ledger(&self, path: &Path) -> Result<()> {
+        let in_flight = self
+            .others
+            .iter()
+            .chain(
+                self.slots
+                    .iter()
+                    .filter_map(|slot| Some((&slot.address, slot.in_flight.as_ref()?))),
+            )
+            .map(|(address, in_flight)| (address.to_string(), LedgerEntry::from(in_flight)))
+            .collect();
+        let json = serde_json::to_vec_pretty(&Ledger { in_flight })
+            .map_err(|err| ExampleError::Parse(format!("ledger: {err}")))?;
+        let temporary = path.with_extension("tmp");
+        fs::write(&temporary, json)?;
+        fs::rename(&temporary, path)?;
+        Ok(())
+    }
+
+    /// Accounts in the ledger that are not part of this fleet.
+    pub fn others(&self) -> usize {
+        self.others.len()
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.in_flight.is_some())
+            .count()
+    }
+
+    /// The next account without a transaction in flight, round-robin, so
+    /// that every account gets a turn.
+    pub fn next_free(&mut self) -> Option<usize> {
+        let size = self.slots.len();
+        let index = (0..size)
+            .map(|offset| (self.cursor + offset) % size)
+            .find(|&index| self.slots[index].in_flight.is_none())?;
+        self.cursor = (index + 1) % size;
+        Some(index)
+    }
+
+    /// Fetches the current nonce of every account in `indexes` in one
+    /// batch. Accounts that do not exist yet are left without one, which
+    /// `provision` fixes.
+    pub fn refresh(&mut self, client: &RpcClient, indexes: &[usize]) -> Result<()> {
+        let keys: Vec<Pubkey> = indexes
+            .iter()
+            .map(|&index| self.slots[index].address)
+            .collect();
+        let accounts = batch::get_multiple_accounts(client, &keys)?;
+        for (&index, account) in indexes.iter().zip(accounts) {
+            let slot = &mut self.slots[index];
+            slot.nonce = account
+                .map(|account| {
+                    nonce_utils::data_from_account(&account)
+                        .map(|data| data.blockhash())
+                        .map_err(|err| {
+                            ExampleError::Parse(format!("nonce account {}: {err}", slot.address))
+                        })
+                })
+                .transpose()?;
+        }
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -0,0 +1,560 @@
+mod fleet;
+
+use clap::{Args as ClapArgs, Parser, Subcommand};
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_sol};
+use common::{Result, batch, explorer};
+use fleet::{Fleet, InFlight};
+use serde::Serialize;
+use solana_client::client_error::ClientErrorKind;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcError;
+use solana_nonce::state::State as NonceState;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+
+/// Nonce accounts created per transaction when provisioning.
+const PROVISION_BATCH: usize = 4;
+
+/// getSignatureStatuses takes at most this many signatures.
+const MAX_STATUSES: usize = 256;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Keep a fleet of durable nonce accounts and send transactions through it,
+/// one nonce per transaction in flight, so a sender never waits on or races
+/// a recent blockhash's expiry.
+///
+/// A nonce can carry one transaction at a time: when that transaction lands,
+/// successful or not, the nonce advances and the next one must be signed
+/// against the new value. With N accounts assigned round-robin, up to N
+/// transactions are in flight at once.
+///
+/// Signed nonce transactions do not expire, which is also the catch: one
+/// that is sent and never seen again may still land any time. Every
+/// transaction in flight is recorded in --ledger before it is sent, and on
+/// start, or after --stuck-after seconds, its nonce is recovered: a
+/// transaction that landed or a nonce that moved on settles it, otherwise the
+/// nonce is advanced by hand, which makes the old transaction invalid for
+/// good.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Payer and authority of every nonce account
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Nonce accounts in the fleet
+    #[arg(short = 'n', long, default_value_t = 8)]
+    size: usize,
+
+    /// Seed prefix the accounts are derived from the authority with
+    #[arg(long, default_value = "nonce-")]
+    seed_prefix: String,
+
+    /// File recording the transactions in flight
+    #[arg(short, long, default_value = "nonce-fleet.json")]
+    ledger: PathBuf,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create the fleet's accounts that do not exist yet
+    Provision,
+    /// Show every account's nonce and the transaction using it
+    Status,
+    /// Settle every nonce recorded as in flight
+    Recover,
+    /// Send SOL transfers through the fleet
+    Send(SendArgs),
+}
+
+#[derive(ClapArgs)]
+struct SendArgs {
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount of each transfer, in SOL (e.g. 0.001)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    /// Number of transfers
+    #[arg(long, default_value_t = 20)]
+    count: usize,
+
+    /// Seconds without a status after which a transaction is taken as
+    /// dropped and its nonce recovered
+    #[arg(long, default_value_t = 60)]
+    stuck_after: u64,
+}
+
+#[derive(Serialize)]
+struct NonceRow {
+    seed: String,
+    address: String,
+    /// `None` when the account does not exist
+    nonce: Option<String>,
+    in_flight: Option<String>,
+    /// Seconds since the transaction in flight was sent
+    age: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct Provisioned {
+    created: Vec<String>,
+    existing: usize,
+    /// Lamports each new account holds to stay rent exempt
+    rent: u64,
+    signatures: Vec<String>,
+}
+
+/// How a nonce's transaction was settled.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+enum Settled {
+    /// Landed and succeeded
+    Landed,
+    /// Landed but failed; it still advanced the nonce
+    Failed,
+    /// Not found, but the nonce moved on without it, so it cannot land
+    Superseded,
+    /// Not found; the nonce was advanced here so it never will land
+    Invalidated,
+    /// Rejected by the node when sent; the nonce was not used
+    Rejected,
+}
+
+impl Settled {
+    fn label(self) -> &'static str {
+        match self {
+            Settled::Landed => "landed",
+            Settled::Failed => "failed",
+            Settled::Superseded => "superseded",
+            Settled::Invalidated => "invalidated",
+            Settled::Rejected => "rejected",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TransferRecord {
+    nonce_account: String,
+    signature: Option<String>,
+    status: Settled,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SendSummary {
+    sent: usize,
+    landed: usize,
+    failed: usize,
+    invalidated: usize,
+    rejected: usize,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+    let mut fleet = Fleet::new(&payer.pubkey(), &args.seed_prefix, args.size)?;
+    fleet.load_ledger(&args.ledger)?;
+    if fleet.others() > 0 {
+        output.status(format_args!(
+            "{} in-flight entries in {} belong to accounts outside this fleet and are left alone",
+            fleet.others(),
+            args.ledger.display()
+        ));
+    }
+    match args.command {
+        Command::Provision => provision(&args, &connection, &payer, &fleet),
+        Command::Status => status(&args, &connection, &mut fleet),
+        Command::Recover => {
+            let mut records = output.records();
+            let mut recovered = 0;
+            for index in 0..fleet.slots.len() {
+                let Some(in_flight) = fleet.slots[index].in_flight.clone() else {
+                    continue;
+                };
+                let settled = recover(
+                    &connection,
+                    &payer,
+                    &fleet.slots[index].address,
+                    &in_flight,
+                    true,
+                )?
+                .expect("forced recovery settles");
+                fleet.slots[index].in_flight = None;
+                fleet.save_ledger(&args.ledger)?;
+                let record = TransferRecord {
+                    nonce_account: fleet.slots[index].address.to_string(),
+                    signature: Some(in_flight.signature.to_string()),
+                    status: settled,
+                    error: None,
+                };
+                records.write(&record, || print_record(&record))?;
+                recovered += 1;
+            }
+            if recovered == 0 {
+                output.status("No nonces in flight");
+            } else {
+                output.status(format_args!("Recovered {recovered} nonces"));
+            }
+            Ok(())
+        }
+        Command::Send(ref transfers) => send(&args, &connection, &payer, &mut fleet, transfers),
+    }
+}
+
+fn provision(args: &Args, connection: &RpcClient, payer: &Keypair, fleet: &Fleet) -> Result<()> {
+    let output = &args.output;
+    let keys: Vec<Pubkey> = fleet.slots.iter().map(|slot| slot.address).collect();
+    let accounts = batch::get_multiple_accounts(connection, &keys)?;
+    let missing: Vec<_> = fleet
+        .slots
+        .iter()
+        .zip(&accounts)
+        .filter(|(_, account)| account.is_none())
+        .map(|(slot, _)| slot)
+        .collect();
+    let rent = connection.get_minimum_balance_for_rent_exemption(NonceState::size())?;
+    output.status(format_args!(
+        "{} of {} nonce accounts exist; creating {} at {} SOL each",
+        keys.len() - missing.len(),
+        keys.len(),
+        missing.len(),
+        format_sol(rent)
+    ));
+
+    let mut signatures = Vec::new();
+    for chunk in missing.chunks(PROVISION_BATCH) {
+        let instructions = chunk.iter().flat_map(|slot| {
+            system_instruction::create_nonce_account_with_seed(
+                &payer.pubkey(),
+                &slot.address,
+                &payer.pubkey(),
+                &slot.seed,
+                &payer.pubkey(),
+                rent,
+            )
+        });
+        let signature = TxBuilder::new(payer)
+            .add_instructions(instructions)
+            .sign_and_send(connection)?;
+        output.status(format_args!(
+            "Created {}: {}",
+            chunk
+                .iter()
+                .map(|slot| slot.seed.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            explorer::transaction_url(&signature, &args.rpc.rpc_url)
+        ));
+        signatures.push(signature.to_string());
+    }
+    let provisioned = Provisioned {
+        created: missing
+            .iter()
+            .map(|slot| slot.address.to_string())
+            .collect(),
+        existing: keys.len() - missing.len(),
+        rent,
+        signatures,
+    };
+    output.print_structured(&provisioned)
+}
+
+fn status(args: &Args, connection: &RpcClient, fleet: &mut Fleet) -> Result<()> {
+    let indexes: Vec<usize> = (0..fleet.slots.len()).collect();
+    fleet.refresh(connection, &indexes)?;
+    let rows: Vec<NonceRow> = fleet
+        .slots
+        .iter()
+        .map(|slot| NonceRow {
+            seed: slot.seed.clone(),
+            address: slot.address.to_string(),
+            nonce: slot.nonce.map(|nonce| nonce.to_string()),
+            in_flight: slot
+                .in_flight
+                .as_ref()
+                .map(|in_flight| in_flight.signature.to_string()),
+            age: slot.in_flight.as_ref().map(InFlight::age),
+        })
+        .collect();
+    args.output.print(&rows, || {
+        println!("{:<12} {:<44} {:<44} In flight", "Seed", "Account", "Nonce");
+        for row in &rows {
+            println!(
+                "{:<12} {:<44} {:<44} {}",
+                row.seed,
+                row.address,
+                row.nonce.as_deref().unwrap_or("(missing)"),
+                match (&row.in_flight, row.age) {
+                    (Some(signature), Some(age)) => format!("{signature} ({age}s)"),
+                    _ => "-".to_string(),
+                }
+            );
+        }
+    })
+}
+
+fn send(
+    args: &Args,
+    connection: &RpcClient,
+    payer: &Keypair,
+    fleet: &mut Fleet,
+    transfers: &SendArgs,
+) -> Result<()> {
+    let output = &args.output;
+    let mut records = output.records();
+    let mut summary = SendSummary {
+        sent: 0,
+        landed: 0,
+        failed: 0,
+        invalidated: 0,
+        rejected: 0,
+    };
+    let mut settle = |fleet: &mut Fleet, index: usize, settled: Settled, error: Option<String>| {
+        let slot = &mut fleet.slots[index];
+        let in_flight = slot.in_flight.take();
+        if settled != Settled::Rejected {
+            // Whatever happened, the nonce has moved on.
+            slot.nonce = None;
+        }
+        match settled {
+            Settled::Landed => summary.landed += 1,
+            Settled::Failed => summary.failed += 1,
+            Settled::Superseded | Settled::Invalidated => summary.invalidated += 1,
+            Settled::Rejected => summary.rejected += 1,
+        }
+        let record = TransferRecord {
+            nonce_account: slot.address.to_string(),
+            signature: in_flight.map(|in_flight| in_flight.signature.to_string()),
+            status: settled,
+            error,
+        };
+        fleet.save_ledger(&args.ledger)?;
+        records.write(&record, || print_record(&record))
+    };
+
+    // Nonces a previous run left in flight are settled first, without
+    // forcing: a transaction that still has a chance to land keeps its nonce
+    // until --stuck-after.
+    let recovered = fleet.in_flight();
+    if recovered > 0 {
+        output.status(format_args!(
+            "{recovered} nonces were left in flight by a previous run"
+        ));
+    }
+
+    let mut sent = 0;
+    loop {
+        // Settle what has landed, and recover what looks stuck.
+        let pending: Vec<(usize, InFlight)> = fleet
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(index, slot)| Some((index, slot.in_flight.clone()?)))
+            .collect();
+        for chunk in pending.chunks(MAX_STATUSES) {
+            let signatures: Vec<Signature> = chunk
+                .iter()
+                .map(|(_, in_flight)| in_flight.signature)
+                .collect();
+            let statuses = connection.get_signature_statuses(&signatures)?.value;
+            for ((index, in_flight), status) in chunk.iter().zip(statuses) {
+                match status {
+                    Some(status) if status.satisfies_commitment(connection.commitment()) => {
+                        let error = status.err.map(|err| err.to_string());
+                        let settled = if error.is_some() {
+                            Settled::Failed
+                        } else {
+                            Settled::Landed
+                        };
+                        settle(fleet, *index, settled, error)?;
+                    }
+                    Some(_) => {}
+                    None => {
+                        let address = fleet.slots[*index].address;
+                        let force = in_flight.age() >= transfers.stuck_after;
+                        if let Some(settled) =
+                            recover(connection, payer, &address, in_flight, force)?
+                        {
+                            settle(fleet, *index, settled, None)?;
+                        }
+                    }
+                }
+            }
+        }
+        if sent == transfers.count && fleet.in_flight() == 0 {
+            break;
+        }
+
+        // Hand every free nonce a transfer.
+        let mut free = Vec::new();
+        while free.len() < transfers.count - sent
+            && let Some(index) = fleet.next_free()
+        {
+            if free.contains(&index) {
+                break;
+            }
+            free.push(index);
+        }
+        let stale: Vec<usize> = free
+            .iter()
+            .copied()
+            .filter(|&index| fleet.slots[index].nonce.is_none())
+            .collect();
+        if !stale.is_empty() {
+            fleet.refresh(connection, &stale)?;
+        }
+        for index in free {
+            let slot = &fleet.slots[index];
+            let Some(nonce) = slot.nonce else {
+                return Err(common::ExampleError::AccountNotFound(slot.address));
+            };
+            let transaction =
+                sign_transfer(payer, &slot.address, nonce, &transfers.to, transfers.amount);
+            let signature = transaction.signatures[0];
+            // Recorded before sending, so a crash right after cannot lose it.
+            fleet.slots[index].in_flight = Some(InFlight::new(signature, nonce));
+            fleet.save_ledger(&args.ledger)?;
+            sent += 1;
+            summary.sent += 1;
+            match connection.send_transaction(&transaction) {
+                Ok(_) => {}
+                // The node answered, so the transaction went no further.
+                Err(err)
+                    if matches!(
+                        err.kind,
+                        ClientErrorKind::RpcError(RpcError::RpcResponseError { .. })
+                    ) =>
+                {
+                    settle(fleet, index, Settled::Rejected, Some(err.to_string()))?;
+                }
+                // A timeout or dropped connection may come after the leader
+                // got the transaction, so it stays in flight until its status
+                // or its nonce tells.
+                Err(err) => output.status(format_args!("sending {signature}: {err}")),
+            }
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+
+    output.print(&summary, || {
+        println!(
+            "Sent {} transfers of {} SOL through {} nonce accounts: {} landed, {} failed, {} invalidated, {} rejected",
+            summary.sent,
+            format_sol(transfers.amount),
+            fleet.slots.len(),
+            summary.landed,
+            summary.failed,
+            summary.invalidated,
+            summary.rejected
+        );
+    })
+}
+
+/// Settles the nonce of a transaction that has no status: if it landed
+/// after all, or the nonce no longer has the value it was signed with, it is
+/// done. Otherwise it might still land, unless `force` advances the nonce so
+/// that it cannot, which returns `None` without `force`.
+fn recover(
+    connection: &RpcClient,
+    payer: &Keypair,
+    address: &Pubkey,
+    in_flight: &InFlight,
+    force: bool,
+) -> Result<Option<Settled>> {
+    let landed = |connection: &RpcClient| -> Result<Option<Settled>> {
+        // A restart may come long after the transaction, so the node's
+        // whole history is searched.
+        let status = connection
+            .get_signature_statuses_with_history(&[in_flight.signature])?
+            .value
+            .remove(0);
+        Ok(status.map(|status| match status.err {
+            Some(_) => Settled::Failed,
+            None => Settled::Landed,
+        }))
+    };
+    if let Some(settled) = landed(connection)? {
+        return Ok(Some(settled));
+    }
+    let account = connection.get_account(address)?;
+    let current = solana_client::nonce_utils::data_from_account(&account)
+        .map_err(|err| common::ExampleError::Parse(format!("nonce account {address}: {err}")))?
+        .blockhash();
+    if current != in_flight.nonce {
+        return Ok(Some(Settled::Superseded));
+    }
+    if !force {
+        return Ok(None);
+    }
+    TxBuilder::new(payer)
+        .add_instruction(system_instruction::advance_nonce_account(
+            address,
+            &payer.pubkey(),
+        ))
+        .sign_and_send(connection)?;
+    // The transaction may have beaten the advance to it.
+    Ok(Some(landed(connection)?.unwrap_or(Settled::Invalidated)))
+}
+
+/// A transfer with `advance_nonce_account` as its first instruction and the
+/// nonce in place of a recent blockhash.
+fn sign_transfer(
+    payer: &Keypair,
+    nonce_account: &Pubkey,
+    nonce: Hash,
+    to: &Pubkey,
+    lamports: u64,
+) -> Transaction {
+    let message = Message::new_with_nonce(
+        vec![system_instruction::transfer(&payer.pubkey(), to, lamports)],
+        Some(&payer.pubkey()),
+        nonce_account,
+        &payer.pubkey(),
+    );
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.sign(&[payer], nonce);
+    transaction
+}
+
+fn print_record(record: &TransferRecord) {
+    println!(
+        "{:<12} {} via {}{}",
+        record.status.label(),
+        record.signature.as_deref().unwrap_or("-"),
+        record.nonce_account,
+        record
+            .error
+            .as_ref()
+            .map(|error| format!(" ({error})"))
+            .unwrap_or_default()
+    );
+}
@@ -0,0 +1,114 @@
+use clap::Parser;
+use common::cli::KeypairArgs;
+use common::output::OutputArgs;
+use common::{ExampleError, Result};
+use offline_signer::{missing_signers, print_instructions, read_transaction, write_transaction};
+use serde::Serialize;
+use solana_sdk::signature::Signer;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// The offline half of the offline signing example: shows what a
+/// transaction from `online-wallet build` does, then adds this machine's
+/// signature to it. Nothing here opens a network connection, so it can run
+/// on an air-gapped machine holding the cold key.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    // Cold wallet signing the transaction
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Partially signed transaction written by `online-wallet build`
+    #[arg(long, default_value = "unsigned-tx.b64")]
+    file: PathBuf,
+
+    /// File receiving the signed transaction
+    #[arg(long, default_value = "signed-tx.b64")]
+    out: PathBuf,
+
+    /// Sign without asking for confirmation
+    #[arg(short, long)]
+    yes: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct SignedTransaction {
+    signer: String,
+    file: String,
+    /// The fee payer's signature, which becomes the transaction ID
+    signature: String,
+    /// Signers still missing after this one; empty when it can be broadcast
+    missing_signers: Vec<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let keypair = args.keypair.load()?;
+    let output = &args.output;
+    let mut transaction = read_transaction(&args.file)?;
+    if !transaction
+        .message
+        .signer_keys()
+        .contains(&&keypair.pubkey())
+    {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{} is not a signer of this transaction",
+            keypair.pubkey()
+        )));
+    }
+
+    if output.is_table() {
+        println!("Fee payer: {}", transaction.message.account_keys[0]);
+        println!("Nonce:     {}", transaction.message.recent_blockhash);
+        println!("Instructions:");
+        print_instructions(&transaction);
+    }
+    if !args.yes && !confirm(&format!("Sign as {}?", keypair.pubkey()))? {
+        return Err(ExampleError::InvalidArgument(
+            "signing declined".to_string(),
+        ));
+    }
+
+    // Signing with the blockhash already in the message keeps the fee
+    // payer's signature; a different one would reset every signature.
+    let nonce = transaction.message.recent_blockhash;
+    transaction
+        .try_partial_sign(&[&keypair], nonce)
+        .map_err(|err| ExampleError::Keypair(err.to_string()))?;
+    write_transaction(&args.out, &transaction)?;
+    let missing = missing_signers(&transaction);
+    output.status(format_args!(
+        "Wrote {}; {}",
+        args.out.display(),
+        if missing.is_empty() {
+            "broadcast it with online-wallet broadcast"
+        } else {
+            "more signatures are needed before it can be broadcast"
+        }
+    ));
+
+    let signed = SignedTransaction {
+        signer: keypair.pubkey().to_string(),
+        file: args.out.display().to_string(),
+        signature: transaction.signatures[0].to_string(),
+        missing_signers: missing.iter().map(ToString::to_string).collect(),
+    };
+    output.print_structured(&signed)
+}
+
+/// Asks a yes/no question on stderr, so stdout stays parseable.
+fn confirm(question: &str) -> Result<bool> {
+    eprint!("{question} [y/N] ");
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -0,0 +1,200 @@
+use clap::{Parser, Subcommand};
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer};
+use offline_signer::{missing_signers, read_transaction, write_transaction};
+use serde::Serialize;
+use solana_client::nonce_utils;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// The connected half of the offline signing example. `build` prepares a
+/// SOL transfer out of a cold wallet against a durable nonce and signs it
+/// as fee payer; `airgapped-signer` adds the cold wallet's signature on a
+/// machine without network access; `broadcast` sends the result.
+///
+/// The nonce replaces the recent blockhash, so the transaction does not
+/// expire while it is carried between machines. Create a nonce account with
+/// the offline-tx example's `create` command first.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Build the transfer and sign it as fee payer
+    Build(Box<BuildArgs>),
+    /// Broadcast a transaction once every signer has signed it
+    Broadcast {
+        #[arg(long, default_value = "signed-tx.b64")]
+        file: PathBuf,
+    },
+}
+
+#[derive(clap::Args)]
+struct BuildArgs {
+    // Fee payer, which must also be the nonce authority
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    #[arg(short, long)]
+    nonce_account: Pubkey,
+
+    /// Cold wallet the SOL comes from; it signs on the air-gapped machine
+    #[arg(short, long)]
+    from: Pubkey,
+
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send, in SOL (e.g. 0.01)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    /// File receiving the partially signed transaction
+    #[arg(long, default_value = "unsigned-tx.b64")]
+    out: PathBuf,
+}
+
+#[derive(Serialize)]
+struct BuiltTransfer {
+    fee_payer: String,
+    from: String,
+    to: String,
+    lamports: u64,
+    nonce: String,
+    file: String,
+    /// Signers the air-gapped machine still has to provide
+    missing_signers: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Broadcast {
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    match args.command {
+        Command::Build(build) => {
+            let BuildArgs {
+                keypair,
+                nonce_account,
+                from,
+                to,
+                amount,
+                out,
+            } = *build;
+            let payer = keypair.load()?;
+            if from == payer.pubkey() {
+                return Err(ExampleError::InvalidArgument(
+                    "--from is the fee payer, leaving nothing for the offline signer".to_string(),
+                ));
+            }
+            let (authority, nonce) = fetch_nonce(&connection, &nonce_account)?;
+            if authority != payer.pubkey() {
+                return Err(ExampleError::InvalidArgument(format!(
+                    "the nonce authority of {nonce_account} is {authority}, not the fee payer"
+                )));
+            }
+
+            let message = Message::new_with_nonce(
+                vec![system_instruction::transfer(&from, &to, amount)],
+                Some(&payer.pubkey()),
+                &nonce_account,
+                &payer.pubkey(),
+            );
+            let mut transaction = Transaction::new_unsigned(message);
+            // Only the fee payer's slot is filled in; the others keep their
+            // placeholder until the air-gapped machine signs.
+            transaction
+                .try_partial_sign(&[&payer], nonce)
+                .map_err(|err| ExampleError::Keypair(err.to_string()))?;
+            write_transaction(&out, &transaction)?;
+            output.status(format_args!(
+                "Built a transfer of {} SOL from {from} to {to} using nonce {nonce}",
+                format_sol(amount)
+            ));
+            output.status(format_args!(
+                "Wrote {}; sign it with airgapped-signer",
+                out.display()
+            ));
+
+            let built = BuiltTransfer {
+                fee_payer: payer.pubkey().to_string(),
+                from: from.to_string(),
+                to: to.to_string(),
+                lamports: amount,
+                nonce: nonce.to_string(),
+                file: out.display().to_string(),
+                missing_signers: missing_signers(&transaction)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect(),
+            };
+            output.print_structured(&built)
+        }
+        Command::Broadcast { file } => {
+            let transaction = read_transaction(&file)?;
+            let missing = missing_signers(&transaction);
+            if !missing.is_empty() {
+                let missing: Vec<String> = missing.iter().map(ToString::to_string).collect();
+                return Err(ExampleError::InvalidArgument(format!(
+                    "{} is still missing signatures from {}",
+                    file.display(),
+                    missing.join(", ")
+                )));
+            }
+            transaction.verify().map_err(|err| {
+                ExampleError::InvalidArgument(format!("{}: {err}", file.display()))
+            })?;
+            let signature = connection.send_and_confirm_transaction(&transaction)?;
+            output.status(format_args!("Signature: {signature}"));
+            output.status(format_args!(
+                "Explorer:  {}",
+                explorer::transaction_url(&signature, &args.rpc.rpc_url)
+            ));
+            let broadcast = Broadcast {
+                signature: signature.to_string(),
+            };
+            output.print_structured(&broadcast)
+        }
+    }
+}
+
+fn fetch_nonce(connection: &RpcClient, nonce_account: &Pubkey) -> Result<(Pubkey, Hash)> {
+    let invalid = |err: nonce_utils::Error| {
+        ExampleError::Parse(format!("nonce account {nonce_account}: {err}"))
+    };
+    let account = nonce_utils::get_account_with_commitment(
+        connection,
+        nonce_account,
+        connection.commitment(),
+    )
+    .map_err(invalid)?;
+    let data = nonce_utils::data_from_account(&account).map_err(invalid)?;
+    Ok((data.authority, data.blockhash()))
+}
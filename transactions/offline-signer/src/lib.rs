@@ -0,0 +1,85 @@
+//! The file format and checks shared by the two halves of the offline
+//! signing example: `online-wallet`, which builds, pays for and broadcasts
+//! the transaction, and `airgapped-signer`, which adds the cold key's
+//! signature without ever touching the network.
+//!
+//! The transaction travels between them as base64 of its wire encoding,
+//! with an all-zero placeholder for every signature still missing.
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use common::decode::Decoder;
+use common::{ExampleError, Result};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use std::fs;
+use std::path::Path;
+
+pub fn write_transaction(path: &Path, transaction: &Transaction) -> Result<()> {
+    let encoded = bincode::serialize(transaction)
+        .map_err(|err| ExampleError::Parse(format!("transaction: {err}")))?;
+    fs::write(path, BASE64.encode(encoded))?;
+    Ok(())
+}
+
+pub fn read_transaction(path: &Path) -> Result<Transaction> {
+    let invalid = |err: String| ExampleError::Parse(format!("{}: {err}", path.display()));
+    let encoded = fs::read_to_string(path)?;
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|err| invalid(err.to_string()))?;
+    let transaction: Transaction =
+        bincode::deserialize(&bytes).map_err(|err| invalid(err.to_string()))?;
+    if transaction.signatures.len()
+        != usize::from(transaction.message.header.num_required_signatures)
+    {
+        return Err(invalid(
+            "signature count does not match the message".to_string(),
+        ));
+    }
+    Ok(transaction)
+}
+
+/// Required signers whose signature is still the placeholder.
+pub fn missing_signers(transaction: &Transaction) -> Vec<Pubkey> {
+    transaction
+        .message
+        .signer_keys()
+        .into_iter()
+        .zip(&transaction.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(key, _)| *key)
+        .collect()
+}
+
+/// Prints each instruction decoded, so the person signing can check what
+/// the transaction does before approving it.
+pub fn print_instructions(transaction: &Transaction) {
+    let decoder = Decoder::default();
+    let keys = &transaction.message.account_keys;
+    for (number, instruction) in transaction.message.instructions.iter().enumerate() {
+        let key = |index: &u8| keys.get(usize::from(*index)).copied().unwrap_or_default();
+        let accounts: Vec<Pubkey> = instruction.accounts.iter().map(key).collect();
+        let summary = decoder.decode(
+            &key(&instruction.program_id_index),
+            &instruction.data,
+            &accounts,
+            1,
+        );
+        match summary.decoded {
+            Some(decoded) => {
+                println!("  #{} {}: {}", number + 1, summary.program, decoded.kind);
+                for (name, value) in decoded.fields {
+                    println!("       {name}: {value}");
+                }
+            }
+            None => println!(
+                "  #{} {}: {} bytes of data",
+                number + 1,
+                summary.program,
+                instruction.data.len()
+            ),
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::{Parser, Subcommand};
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer, keys};
+use serde::Serialize;
+use solana_client::nonce_utils;
+use solana_client::rpc_client::RpcClient;
+use solana_nonce::state::State as NonceState;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Durable nonce workflow: create a nonce account, sign a transfer against it
+/// (optionally fully offline) and submit the signed transaction later.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Create and fund a new nonce account owned by the payer
+    Create {
+        // Payer, which also becomes the nonce authority
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        /// Where to write the new nonce account keypair
+        #[arg(long, default_value = "nonce-account.json")]
+        nonce_keypair: PathBuf,
+    },
+    /// Print the authority and current nonce of a nonce account
+    Show {
+        #[arg(short, long)]
+        nonce_account: Pubkey,
+    },
+    /// Sign a SOL transfer that uses the nonce instead of a recent blockhash
+    Sign {
+        // Payer, which must also be the nonce authority
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        #[arg(short, long)]
+        nonce_account: Pubkey,
+
+        /// Current nonce value; pass it to sign without any network access
+        #[arg(long)]
+        nonce: Option<Hash>,
+
+        /// Recipient wallet address (base58)
+        #[arg(short, long)]
+        to: Pubkey,
+
+        /// Amount to send, in SOL (e.g. 0.01)
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: u64,
+
+        /// File receiving the base64-encoded signed transaction
+        #[arg(short, long, default_value = "signed-tx.b64")]
+        out: PathBuf,
+    },
+    /// Broadcast a transaction previously written by `sign`
+    Submit {
+        #[arg(short, long, default_value = "signed-tx.b64")]
+        file: PathBuf,
+    },
+}
+
+#[derive(Serialize)]
+struct CreatedNonce {
+    nonce_account: String,
+    keypair_file: String,
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct NonceInfo {
+    nonce_account: String,
+    authority: String,
+    nonce: String,
+}
+
+#[derive(Serialize)]
+struct SignedTransfer {
+    to: String,
+    lamports: u64,
+    nonce: String,
+    file: String,
+    /// The fee payer's signature, which becomes the transaction ID
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct Submitted {
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    match args.command {
+        Command::Create {
+            keypair,
+            nonce_keypair,
+        } => create(
+            output,
+            &connection,
+            &args.rpc.rpc_url,
+            &keypair.load()?,
+            &nonce_keypair,
+        ),
+        Command::Show { nonce_account } => {
+            let (authority, nonce) = fetch_nonce(&connection, &nonce_account)?;
+            let info = NonceInfo {
+                nonce_account: nonce_account.to_string(),
+                authority: authority.to_string(),
+                nonce: nonce.to_string(),
+            };
+            output.print(&info, || {
+                println!("Authority: {}", info.authority);
+                println!("Nonce:     {}", info.nonce);
+            })
+        }
+        Command::Sign {
+            keypair,
+            nonce_account,
+            nonce,
+            to,
+            amount,
+            out,
+        } => {
+            let payer = keypair.load()?;
+            let nonce = match nonce {
+                Some(nonce) => nonce,
+                None => fetch_nonce(&connection, &nonce_account)?.1,
+            };
+            let transaction = sign(&payer, &nonce_account, nonce, &to, amount);
+            let encoded = bincode::serialize(&transaction)
+                .map_err(|err| ExampleError::Parse(format!("transaction: {err}")))?;
+            fs::write(&out, BASE64.encode(encoded))?;
+            output.status(format_args!(
+                "Signed transfer of {} SOL to {to} using nonce {nonce}",
+                format_sol(amount)
+            ));
+            output.status(format_args!("Wrote {}", out.display()));
+            let signed = SignedTransfer {
+                to: to.to_string(),
+                lamports: amount,
+                nonce: nonce.to_string(),
+                file: out.display().to_string(),
+                signature: transaction.signatures[0].to_string(),
+            };
+            output.print_structured(&signed)
+        }
+        Command::Submit { file } => {
+            let encoded = fs::read_to_string(&file)?;
+            let bytes = BASE64
+                .decode(encoded.trim())
+                .map_err(|err| ExampleError::Parse(format!("{}: {err}", file.display())))?;
+            let transaction: Transaction = bincode::deserialize(&bytes)
+                .map_err(|err| ExampleError::Parse(format!("{}: {err}", file.display())))?;
+            let signature = connection.send_and_confirm_transaction(&transaction)?;
+            output.status(format_args!("Signature: {signature}"));
+            output.status(format_args!(
+                "Explorer:  {}",
+                explorer::transaction_url(&signature, &args.rpc.rpc_url)
+            ));
+            let submitted = Submitted {
+                signature: signature.to_string(),
+            };
+            output.print_structured(&submitted)
+        }
+    }
+}
+
+fn create(
+    output: &OutputArgs,
+    connection: &RpcClient,
+    rpc_url: &str,
+    payer: &Keypair,
+    nonce_keypair_path: &Path,
+) -> Result<()> {
+    let nonce_account = Keypair::new();
+    let rent = connection.get_minimum_balance_for_rent_exemption(NonceState::size())?;
+    let instructions = system_instruction::create_nonce_account(
+        &payer.pubkey(),
+        &nonce_account.pubkey(),
+        &payer.pubkey(),
+        rent,
+    );
+    let recent_blockhash = connection.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &[payer, &nonce_account],
+        recent_blockhash,
+    );
+    let signature = connection.send_and_confirm_transaction(&transaction)?;
+    keys::write_keypair_file(&nonce_account, nonce_keypair_path)?;
+
+    output.status(format_args!("Nonce account: {}", nonce_account.pubkey()));
+    output.status(format_args!(
+        "Keypair saved: {}",
+        nonce_keypair_path.display()
+    ));
+    output.status(format_args!("Signature:     {signature}"));
+    output.status(format_args!(
+        "Explorer:      {}",
+        explorer::transaction_url(&signature, rpc_url)
+    ));
+    let created = CreatedNonce {
+        nonce_account: nonce_account.pubkey().to_string(),
+        keypair_file: nonce_keypair_path.display().to_string(),
+        signature: signature.to_string(),
+    };
+    output.print_structured(&created)
+}
+
+fn fetch_nonce(connection: &RpcClient, nonce_account: &Pubkey) -> Result<(Pubkey, Hash)> {
+    let invalid = |err: nonce_utils::Error| {
+        ExampleError::Parse(format!("nonce account {nonce_account}: {err}"))
+    };
+    let account = nonce_utils::get_account_with_commitment(
+        connection,
+        nonce_account,
+        connection.commitment(),
+    )
+    .map_err(invalid)?;
+    let data = nonce_utils::data_from_account(&account).map_err(invalid)?;
+    Ok((data.authority, data.blockhash()))
+}
+
+/// Builds the transfer with `advance_nonce_account` as its first instruction
+/// and the stored nonce in place of a recent blockhash. Nothing here touches
+/// the network, so it can run on an air-gapped machine.
+fn sign(
+    payer: &Keypair,
+    nonce_account: &Pubkey,
+    nonce: Hash,
+    to: &Pubkey,
+    lamports: u64,
+) -> Transaction {
+    let message = Message::new_with_nonce(
+        vec![system_instruction::transfer(&payer.pubkey(), to, lamports)],
+        Some(&payer.pubkey()),
+        nonce_account,
+        &payer.pubkey(),
+    );
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.sign(&[payer], nonce);
+    transaction
+}
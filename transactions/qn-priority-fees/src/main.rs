@@ -0,0 +1,68 @@
+use clap::Parser;
+use common::Result;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::quicknode_ext::{self, FeeLevels};
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+
+/// Estimate priority fees with QuickNode's Priority Fee API
+/// (`qn_estimatePriorityFees`): fee levels over recent blocks, per compute
+/// unit and per transaction, optionally for transactions that write-lock a
+/// given account, plus the fee QuickNode recommends. Requires an endpoint
+/// with the Priority Fee API add-on.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Only count transactions that write-lock this account, e.g. a busy
+    /// pool
+    #[arg(short, long)]
+    account: Option<Pubkey>,
+
+    /// Blocks to sample, at most 100
+    #[arg(short = 'n', long, value_parser = clap::value_parser!(u32).range(1..=100))]
+    last_n_blocks: Option<u32>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let estimate = quicknode_ext::estimate_priority_fees(
+        &connection,
+        args.last_n_blocks,
+        args.account.as_ref(),
+    )?;
+    args.output.print(&estimate, || {
+        println!("Slot:        {}", estimate.context.slot);
+        if let Some(account) = &args.account {
+            println!("Account:     {account}");
+        }
+        println!(
+            "{:<12} {:>20} {:>20}",
+            "", "micro-lamports/CU", "lamports/tx"
+        );
+        let row = |label: &str, level: fn(&FeeLevels) -> f64| {
+            println!(
+                "{label:<12} {:>20.0} {:>20.0}",
+                level(&estimate.per_compute_unit),
+                level(&estimate.per_transaction)
+            );
+        };
+        row("low", |levels| levels.low);
+        row("medium", |levels| levels.medium);
+        row("high", |levels| levels.high);
+        row("extreme", |levels| levels.extreme);
+        if let Some(recommended) = estimate.recommended {
+            println!("Recommended: {recommended:.0} micro-lamports/CU");
+        }
+    })
+}
@@ -0,0 +1,155 @@
+use clap::Parser;
+use common::blockhash::RecentBlockhash;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::fees::{PriorityFee, with_priority_fee};
+use common::output::OutputArgs;
+use common::reliable_send::{ReliableSendConfig, send_reliably};
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+use std::time::Duration;
+
+/// Send SOL with preflight skipped, rebroadcasting the signed transaction
+/// every few slots while polling its status, and report how many slots and
+/// broadcasts it took to land. Expired attempts are re-signed with a fresh
+/// blockhash.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Sender of the transfer
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send, in SOL (e.g. 0.01)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    /// Rebroadcast after this many slots without the transaction landing
+    #[arg(long, default_value_t = 2)]
+    rebroadcast_slots: u64,
+
+    /// Priority fee in micro-lamports per compute unit; 0 sends none
+    #[arg(long, default_value_t = 0)]
+    priority_fee: u64,
+
+    /// Times an expired transaction is re-signed before giving up
+    #[arg(long, default_value_t = 3)]
+    max_resigns: u32,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct Landed {
+    signature: String,
+    /// Blockhashes used, 1 unless earlier attempts expired
+    attempts: u32,
+    sent_slot: u64,
+    landed_slot: u64,
+    slots_to_land: u64,
+    broadcasts: u32,
+    milliseconds: u128,
+}
+
+/// A SOL transfer needs 150 compute units; the limit leaves headroom for
+/// the compute budget instructions themselves.
+const COMPUTE_UNIT_LIMIT: u32 = 1_000;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.nonblocking_client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    let transfer = system_instruction::transfer(&payer.pubkey(), &args.to, args.amount);
+    let instructions = if args.priority_fee > 0 {
+        let fee = PriorityFee {
+            unit_limit: COMPUTE_UNIT_LIMIT,
+            micro_lamports_per_unit: args.priority_fee,
+        };
+        with_priority_fee(&fee, &[transfer])
+    } else {
+        vec![transfer]
+    };
+    let config = ReliableSendConfig {
+        rebroadcast_every_slots: args.rebroadcast_slots,
+        poll_interval: Duration::from_millis(400),
+        commitment: args.rpc.commitment_config(),
+    };
+
+    output.status(format_args!(
+        "Sending {} SOL from {} to {}",
+        format_sol(args.amount),
+        payer.pubkey(),
+        args.to
+    ));
+    let mut attempt = 1;
+    let report = loop {
+        let (blockhash, last_valid_block_height) = connection
+            .get_latest_blockhash_with_commitment(connection.commitment())
+            .await?;
+        let transaction = Transaction::new_signed_with_payer(
+            &instructions,
+            Some(&payer.pubkey()),
+            &[&payer],
+            blockhash,
+        );
+        let recent = RecentBlockhash {
+            blockhash,
+            last_valid_block_height,
+        };
+        output.status(format_args!(
+            "Attempt {attempt}: {} (valid until block height {last_valid_block_height})",
+            transaction.signatures[0]
+        ));
+        match send_reliably(&connection, &transaction, recent, config).await {
+            Ok(report) => break report,
+            Err(ExampleError::TransactionExpired(signature)) if attempt <= args.max_resigns => {
+                output.status(format_args!(
+                    "{signature} expired without landing; re-signing"
+                ));
+                attempt += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    };
+
+    output.status(format_args!(
+        "Landed in slot {} after {} slots, {} broadcasts and {:.1?}",
+        report.landed_slot,
+        report.slots_to_land(),
+        report.broadcasts,
+        report.elapsed
+    ));
+    output.status(format_args!(
+        "Explorer: {}",
+        explorer::transaction_url(&report.signature, &args.rpc.rpc_url)
+    ));
+    let landed = Landed {
+        signature: report.signature.to_string(),
+        attempts: attempt,
+        sent_slot: report.sent_slot,
+        landed_slot: report.landed_slot,
+        slots_to_land: report.slots_to_land(),
+        broadcasts: report.broadcasts,
+        milliseconds: report.elapsed.as_millis(),
+    };
+    output.print_structured(&landed)
+}
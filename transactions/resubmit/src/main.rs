@@ -0,0 +1,239 @@
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::{Signature, Signer};
+use solana_sdk::signer::SignerError;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+use solana_system_interface::instruction::SystemInstruction;
+use solana_system_interface::program as system_program;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// Find out what became of a transaction, and send it again if it expired.
+///
+/// The signature is looked up with searchTransactionHistory, so a
+/// transaction that landed is found however old it is. One that is not
+/// found is either still pending or expired, which only its blockhash can
+/// tell: given the original with `--transaction`, a pending transaction is
+/// rebroadcast as it is, and an expired one is signed again with a fresh
+/// blockhash and sent. Expired means the blockhash is no longer valid
+/// anywhere, so the original can no longer land and paying twice is not
+/// possible.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Signature of the transaction to check
+    signature: Signature,
+
+    /// File holding the original transaction as base64 of its wire
+    /// encoding, needed to rebroadcast or re-sign it
+    #[arg(long)]
+    transaction: Option<PathBuf>,
+
+    // Signs the rebuilt message; must be its only signer
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Clone, Copy, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    /// Landed and succeeded
+    Landed,
+    /// Landed but its execution failed; resending would fail the same way
+    Failed,
+    /// Not landed, but its blockhash is still valid
+    Pending,
+    /// Not landed and can no longer land
+    Expired,
+    /// Not found, and no transaction to tell pending from expired
+    Unknown,
+}
+
+#[derive(Serialize)]
+struct Resubmission {
+    signature: String,
+    outcome: Outcome,
+    slot: Option<u64>,
+    confirmation_status: Option<String>,
+    error: Option<String>,
+    /// Signature of the re-signed transaction, when it was expired
+    new_signature: Option<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    let mut result = Resubmission {
+        signature: args.signature.to_string(),
+        outcome: Outcome::Unknown,
+        slot: None,
+        confirmation_status: None,
+        error: None,
+        new_signature: None,
+    };
+
+    if let Some(landed) = lookup(&connection, &args.signature, &mut result)? {
+        result.outcome = landed;
+        return print(output, &result);
+    }
+    let Some(path) = &args.transaction else {
+        output
+            .status("Not found; pass the original with --transaction to tell pending from expired");
+        return print(output, &result);
+    };
+    let original = read_transaction(path)?;
+    if original.signatures.first() != Some(&args.signature) {
+        return Err(ExampleError::InvalidArgument(format!(
+            "{} is not the transaction with signature {}",
+            path.display(),
+            args.signature
+        )));
+    }
+
+    // A durable nonce stands in for the blockhash and only stops working
+    // once the nonce advances, so such a transaction is always sent as is.
+    let blockhash = *original.message.recent_blockhash();
+    let still_valid = uses_durable_nonce(&original)
+        || connection.is_blockhash_valid(&blockhash, CommitmentConfig::processed())?;
+    if still_valid {
+        result.outcome = Outcome::Pending;
+        output.status("Not landed yet and still valid; rebroadcasting it unchanged");
+        let signature = connection.send_and_confirm_transaction(&original)?;
+        explorer_status(output, &signature, &args.rpc.rpc_url);
+        if let Some(landed) = lookup(&connection, &signature, &mut result)? {
+            result.outcome = landed;
+        }
+        return print(output, &result);
+    }
+
+    // It may have landed in the moment before the blockhash expired.
+    if let Some(landed) = lookup(&connection, &args.signature, &mut result)? {
+        result.outcome = landed;
+        return print(output, &result);
+    }
+    result.outcome = Outcome::Expired;
+    output.status(format_args!(
+        "Expired: blockhash {blockhash} is no longer valid; signing again with a fresh one"
+    ));
+    let payer = args.keypair.load()?;
+    let mut message = original.message;
+    message.set_recent_blockhash(connection.get_latest_blockhash()?);
+    let transaction =
+        VersionedTransaction::try_new(message, &[&payer]).map_err(|err| match err {
+            SignerError::NotEnoughSigners | SignerError::KeypairPubkeyMismatch => {
+                ExampleError::Keypair(format!(
+                    "the message must be signed by {} alone to be re-signed here",
+                    payer.pubkey()
+                ))
+            }
+            err => err.into(),
+        })?;
+    let signature = connection.send_and_confirm_transaction(&transaction)?;
+    explorer_status(output, &signature, &args.rpc.rpc_url);
+    result.new_signature = Some(signature.to_string());
+    print(output, &result)
+}
+
+/// Looks the signature up across the whole history, filling in where and
+/// how it landed. Returns nothing when it is not found.
+fn lookup(
+    connection: &RpcClient,
+    signature: &Signature,
+    result: &mut Resubmission,
+) -> Result<Option<Outcome>> {
+    let status = connection
+        .get_signature_statuses_with_history(&[*signature])?
+        .value
+        .into_iter()
+        .next()
+        .flatten();
+    let Some(status) = status else {
+        return Ok(None);
+    };
+    result.slot = Some(status.slot);
+    result.confirmation_status = status
+        .confirmation_status
+        .map(|status| format!("{status:?}").to_lowercase());
+    result.error = status.err.as_ref().map(TransactionError::to_string);
+    Ok(Some(if status.err.is_some() {
+        Outcome::Failed
+    } else {
+        Outcome::Landed
+    }))
+}
+
+fn read_transaction(path: &Path) -> Result<VersionedTransaction> {
+    let invalid = |err: String| ExampleError::Parse(format!("{}: {err}", path.display()));
+    let encoded = fs::read_to_string(path)?;
+    let bytes = BASE64
+        .decode(encoded.trim())
+        .map_err(|err| invalid(err.to_string()))?;
+    bincode::deserialize(&bytes).map_err(|err| invalid(err.to_string()))
+}
+
+/// Whether the first instruction advances a nonce account, which makes the
+/// blockhash field the nonce's value.
+fn uses_durable_nonce(transaction: &VersionedTransaction) -> bool {
+    let message = &transaction.message;
+    message.instructions().first().is_some_and(|instruction| {
+        message
+            .static_account_keys()
+            .get(usize::from(instruction.program_id_index))
+            == Some(&system_program::ID)
+            && matches!(
+                bincode::deserialize(&instruction.data),
+                Ok(SystemInstruction::AdvanceNonceAccount)
+            )
+    })
+}
+
+fn explorer_status(output: &OutputArgs, signature: &Signature, rpc_url: &str) {
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(signature, rpc_url)
+    ));
+}
+
+fn print(output: &OutputArgs, result: &Resubmission) -> Result<()> {
+    output.print(result, || {
+        let outcome = match result.outcome {
+            Outcome::Landed => "landed",
+            Outcome::Failed => "landed, but failed",
+            Outcome::Pending => "pending",
+            Outcome::Expired => "expired and re-signed",
+            Outcome::Unknown => "not found",
+        };
+        println!("{}: {outcome}", result.signature);
+        if let Some(slot) = result.slot {
+            println!(
+                "  slot {slot}, {}",
+                result.confirmation_status.as_deref().unwrap_or("processed")
+            );
+        }
+        if let Some(error) = &result.error {
+            println!("  error: {error}");
+        }
+        if let Some(signature) = &result.new_signature {
+            println!("  resubmitted as {signature}");
+        }
+    })
+}
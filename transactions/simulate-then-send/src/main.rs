@@ -0,0 +1,109 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::send::{self, SimulatedSend};
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+
+/// Simulate a SOL transfer, report compute units and logs, and only send it
+/// if the simulation succeeds.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Sender of the transfer
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send, in SOL (e.g. 0.01)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    /// Only simulate; never submit the transaction
+    #[arg(long)]
+    dry_run: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct SimulatedTransfer {
+    from: String,
+    to: String,
+    lamports: u64,
+    units_consumed: Option<u64>,
+    logs: Vec<String>,
+    error: Option<String>,
+    /// Absent for --dry-run or a failed simulation
+    signature: Option<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    let instruction = system_instruction::transfer(&payer.pubkey(), &args.to, args.amount);
+    let recent_blockhash = connection.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[&payer],
+        recent_blockhash,
+    );
+    output.status(format_args!(
+        "Transfer of {} SOL from {} to {}",
+        format_sol(args.amount),
+        payer.pubkey(),
+        args.to
+    ));
+
+    let sent = if args.dry_run {
+        SimulatedSend {
+            simulation: send::simulate(&connection, &transaction, output)?,
+            signature: None,
+        }
+    } else {
+        send::simulate_and_send(&connection, &transaction, output)?
+    };
+    let simulation = sent.simulation;
+    let mut result = SimulatedTransfer {
+        from: payer.pubkey().to_string(),
+        to: args.to.to_string(),
+        lamports: args.amount,
+        units_consumed: simulation.units_consumed,
+        logs: simulation.logs.unwrap_or_default(),
+        error: simulation.err.as_ref().map(ToString::to_string),
+        signature: None,
+    };
+    if let Some(err) = simulation.err {
+        output.print_structured(&result)?;
+        return Err(ExampleError::SimulationFailed(err));
+    }
+    if let Some(signature) = sent.signature {
+        output.status(format_args!("Signature: {signature}"));
+        output.status(format_args!(
+            "Explorer:  {}",
+            explorer::transaction_url(&signature, &args.rpc.rpc_url)
+        ));
+        result.signature = Some(signature.to_string());
+    }
+    output.print_structured(&result)
+}
@@ -0,0 +1,120 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::fees::{self, PriorityFee};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_sol};
+use common::{Result, explorer};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+
+/// Send SOL with a priority fee derived from recent prioritization fees.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Sender of the transfer
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send, in SOL (e.g. 0.01)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    /// Percentile of recent fees to pay, 0-100
+    #[arg(short, long, default_value_t = 75, value_parser = clap::value_parser!(u8).range(0..=100))]
+    percentile: u8,
+
+    /// Compute unit limit to request; a transfer plus the two compute budget
+    /// instructions needs about 450
+    #[arg(short, long, default_value_t = 1_000)]
+    unit_limit: u32,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct PriorityTransfer {
+    to: String,
+    lamports: u64,
+    /// Slots the recent fees were sampled from
+    sampled_slots: usize,
+    percentile: u8,
+    micro_lamports_per_unit: u64,
+    unit_limit: u32,
+    max_priority_fee: u64,
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    let writable_accounts = [payer.pubkey(), args.to];
+    let recent = fees::recent_fees(&connection, &writable_accounts)?;
+    output.status(format_args!(
+        "Recent prioritization fees over {} slots (micro-lamports/CU): min {} median {} max {}",
+        recent.len(),
+        fees::percentile(&recent, 0),
+        fees::percentile(&recent, 50),
+        fees::percentile(&recent, 100)
+    ));
+
+    let priority_fee = PriorityFee {
+        unit_limit: args.unit_limit,
+        micro_lamports_per_unit: fees::percentile(&recent, args.percentile),
+    };
+    output.status(format_args!(
+        "Using p{}: {} micro-lamports/CU x {} CU = at most {} lamports priority fee",
+        args.percentile,
+        priority_fee.micro_lamports_per_unit,
+        priority_fee.unit_limit,
+        priority_fee.max_lamports()
+    ));
+
+    output.status(format_args!(
+        "Sending {} SOL to {}",
+        format_sol(args.amount),
+        args.to
+    ));
+    let signature = TxBuilder::new(&payer)
+        .add_instruction(system_instruction::transfer(
+            &payer.pubkey(),
+            &args.to,
+            args.amount,
+        ))
+        .with_priority_fee(priority_fee)
+        .sign_and_send(&connection)?;
+
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+    let transfer = PriorityTransfer {
+        to: args.to.to_string(),
+        lamports: args.amount,
+        sampled_slots: recent.len(),
+        percentile: args.percentile,
+        micro_lamports_per_unit: priority_fee.micro_lamports_per_unit,
+        unit_limit: priority_fee.unit_limit,
+        max_priority_fee: priority_fee.max_lamports(),
+        signature: signature.to_string(),
+    };
+    output.print_structured(&transfer)
+}
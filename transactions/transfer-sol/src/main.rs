@@ -0,0 +1,81 @@
+use clap::Parser;
+use common::cli::{KeypairArgs, RpcArgs};
+use common::output::OutputArgs;
+use common::tx_builder::TxBuilder;
+use common::units::{format_sol, parse_sol};
+use common::{Result, explorer};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+
+/// Send SOL from a local keypair to another wallet and wait for confirmation,
+/// re-signing with a fresh blockhash if the transaction expires.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    // Sender of the transfer
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Recipient wallet address (base58)
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to send, in SOL (e.g. 0.01)
+    #[arg(short, long, value_parser = parse_sol)]
+    amount: u64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct SolTransfer {
+    from: String,
+    to: String,
+    lamports: u64,
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let payer = args.keypair.load()?;
+    let output = &args.output;
+
+    output.status(format_args!(
+        "Sending {} SOL from {} to {}",
+        format_sol(args.amount),
+        payer.pubkey(),
+        args.to
+    ));
+
+    let signature = TxBuilder::new(&payer)
+        .add_instruction(system_instruction::transfer(
+            &payer.pubkey(),
+            &args.to,
+            args.amount,
+        ))
+        .sign_and_send(&connection)?;
+
+    output.status(format_args!("Signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:  {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+    let transfer = SolTransfer {
+        from: payer.pubkey().to_string(),
+        to: args.to.to_string(),
+        lamports: args.amount,
+        signature: signature.to_string(),
+    };
+    output.print_structured(&transfer)
+}
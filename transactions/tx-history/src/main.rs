@@ -0,0 +1,345 @@
+mod store;
+
+use clap::Parser;
+use common::batch::MAX_SIGNATURES_FOR_ADDRESS;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::time::format_timestamp;
+use common::{ExampleError, Result, at_least_confirmed};
+use serde::Serialize;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiInstruction, UiMessage,
+    UiParsedInstruction, UiTransactionEncoding,
+};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use store::{Store, Transfer};
+
+/// Page through an address's transaction history, newest first.
+///
+/// With `--db`, the transactions are also stored in a SQLite file together
+/// with the SOL and token transfers in them, making a small personal
+/// indexer: the first run stores the `--limit` most recent transactions,
+/// and each later run adds the ones since, oldest first, in batches of at
+/// most `--limit`.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet or program address (base58)
+    #[arg(short, long)]
+    address: Pubkey,
+
+    /// Maximum number of transactions to print
+    #[arg(short, long, default_value_t = 20)]
+    limit: usize,
+
+    /// Start searching backwards from this signature
+    #[arg(long, conflicts_with = "db")]
+    before: Option<Signature>,
+
+    /// Stop once this signature is reached
+    #[arg(long, conflicts_with = "db")]
+    until: Option<Signature>,
+
+    /// SQLite file to store the transactions in, resuming from the newest
+    /// one stored for the address
+    #[arg(long)]
+    db: Option<PathBuf>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct HistoryRow {
+    signature: String,
+    slot: u64,
+    timestamp: Option<String>,
+    status: &'static str,
+    fee: u64,
+    summary: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    if let Some(path) = &args.db {
+        return index(&connection, &args, path);
+    }
+
+    // Rows are written as they are fetched, so a long history shows
+    // progress and can be cut short with ctrl-c.
+    let mut records = args.output.records();
+    let mut before = args.before;
+    let mut printed = 0;
+    while printed < args.limit {
+        let page = fetch_signatures(
+            &connection,
+            &args.address,
+            before,
+            args.until,
+            (args.limit - printed).min(MAX_SIGNATURES_FOR_ADDRESS),
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(parse_signature(&last.signature)?);
+
+        for status in &page {
+            let transaction = fetch_transaction(&connection, &status.signature)?;
+            let row = history_row(status, &transaction);
+            records.write(&row, || print_row(&row))?;
+            printed += 1;
+        }
+    }
+    args.output.status(format_args!("{printed} transactions"));
+    Ok(())
+}
+
+/// Stores the transactions newer than the address's cursor, oldest first,
+/// moving the cursor with each one so an interrupted run loses nothing.
+fn index(connection: &RpcClient, args: &Args, path: &Path) -> Result<()> {
+    let mut store = Store::open(path)?;
+    let cursor = store.cursor(&args.address)?;
+    // Listing signatures is cheap next to fetching transactions, so when
+    // resuming all of them back to the cursor are listed, to start from the
+    // oldest.
+    let wanted = match cursor {
+        Some(cursor) => {
+            args.output
+                .status(format_args!("Resuming {} after {cursor}", args.address));
+            usize::MAX
+        }
+        None => args.limit,
+    };
+    let mut pending = Vec::new();
+    let mut before = None;
+    while pending.len() < wanted {
+        let page = fetch_signatures(
+            connection,
+            &args.address,
+            before,
+            cursor,
+            (wanted - pending.len()).min(MAX_SIGNATURES_FOR_ADDRESS),
+        )?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(parse_signature(&last.signature)?);
+        pending.extend(page);
+    }
+
+    let mut records = args.output.records();
+    let mut stored = 0;
+    for status in pending.iter().rev().take(args.limit) {
+        let transaction = fetch_transaction(connection, &status.signature)?;
+        let row = history_row(status, &transaction);
+        store.save(
+            &args.address,
+            &row,
+            status.block_time,
+            &transfers(&transaction),
+        )?;
+        records.write(&row, || print_row(&row))?;
+        stored += 1;
+    }
+    let remaining = pending.len() - stored;
+    args.output.status(format_args!(
+        "Stored {stored} transactions in {}{}",
+        path.display(),
+        if remaining > 0 {
+            format!("; {remaining} newer ones are left for the next run")
+        } else {
+            String::new()
+        }
+    ));
+    Ok(())
+}
+
+fn print_row(row: &HistoryRow) {
+    println!(
+        "{:<20}  {:<6}  {:>8}  {:<88}  {}",
+        row.timestamp.as_deref().unwrap_or("-"),
+        row.status,
+        row.fee,
+        row.signature,
+        row.summary
+    );
+}
+
+fn fetch_signatures(
+    connection: &RpcClient,
+    address: &Pubkey,
+    before: Option<Signature>,
+    until: Option<Signature>,
+    limit: usize,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+    Ok(connection.get_signatures_for_address_with_config(
+        address,
+        GetConfirmedSignaturesForAddress2Config {
+            before,
+            until,
+            limit: Some(limit),
+            commitment: Some(at_least_confirmed(connection.commitment())),
+        },
+    )?)
+}
+
+fn parse_signature(signature: &str) -> Result<Signature> {
+    signature
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("signature `{signature}`")))
+}
+
+fn fetch_transaction(
+    connection: &RpcClient,
+    signature: &str,
+) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+    Ok(connection.get_transaction_with_config(
+        &parse_signature(signature)?,
+        RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::JsonParsed),
+            commitment: Some(at_least_confirmed(connection.commitment())),
+            max_supported_transaction_version: Some(0),
+        },
+    )?)
+}
+
+fn history_row(
+    status: &RpcConfirmedTransactionStatusWithSignature,
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> HistoryRow {
+    HistoryRow {
+        signature: status.signature.clone(),
+        slot: status.slot,
+        timestamp: status.block_time.map(format_timestamp),
+        status: if status.err.is_some() { "failed" } else { "ok" },
+        fee: transaction
+            .transaction
+            .meta
+            .as_ref()
+            .map(|meta| meta.fee)
+            .unwrap_or_default(),
+        summary: summarize(transaction),
+    }
+}
+
+/// One entry per top-level instruction: `program:type` when the node could
+/// parse it, otherwise the raw program ID.
+fn summarize(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> String {
+    let EncodedTransaction::Json(ui_transaction) = &transaction.transaction.transaction else {
+        return String::new();
+    };
+    let UiMessage::Parsed(message) = &ui_transaction.message else {
+        return String::new();
+    };
+    message
+        .instructions
+        .iter()
+        .map(|instruction| match instruction {
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => {
+                match parsed.parsed.get("type").and_then(|kind| kind.as_str()) {
+                    Some(kind) => format!("{}:{kind}", parsed.program),
+                    None => parsed.program.clone(),
+                }
+            }
+            UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) => {
+                decoded.program_id.clone()
+            }
+            UiInstruction::Compiled(compiled) => {
+                format!("program #{}", compiled.program_id_index)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// SOL and token transfers among the instructions the node parsed, inner
+/// instructions included. A plain token `transfer` does not name its mint,
+/// which is then looked up in the transaction's token balances.
+fn transfers(transaction: &EncodedConfirmedTransactionWithStatusMeta) -> Vec<Transfer> {
+    let EncodedTransaction::Json(ui_transaction) = &transaction.transaction.transaction else {
+        return Vec::new();
+    };
+    let UiMessage::Parsed(message) = &ui_transaction.message else {
+        return Vec::new();
+    };
+    let meta = transaction.transaction.meta.as_ref();
+    let mint_of = |account: &str| {
+        let index = message
+            .account_keys
+            .iter()
+            .position(|key| key.pubkey == account)?;
+        let meta = meta?;
+        [&meta.pre_token_balances, &meta.post_token_balances]
+            .into_iter()
+            .filter_map(|balances| match balances {
+                OptionSerializer::Some(balances) => Some(balances),
+                _ => None,
+            })
+            .flatten()
+            .find(|balance| usize::from(balance.account_index) == index)
+            .map(|balance| balance.mint.clone())
+    };
+
+    let mut instructions: Vec<(String, &UiInstruction)> = message
+        .instructions
+        .iter()
+        .enumerate()
+        .map(|(index, instruction)| (index.to_string(), instruction))
+        .collect();
+    if let Some(OptionSerializer::Some(inner)) = meta.map(|meta| &meta.inner_instructions) {
+        for group in inner {
+            for (position, instruction) in group.instructions.iter().enumerate() {
+                instructions.push((format!("{}.{}", group.index, position + 1), instruction));
+            }
+        }
+    }
+
+    instructions
+        .into_iter()
+        .filter_map(|(position, instruction)| {
+            let UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) = instruction else {
+                return None;
+            };
+            let kind = parsed.parsed.get("type")?.as_str()?;
+            let info = parsed.parsed.get("info")?;
+            let field = |name: &str| info.get(name).and_then(|value| value.as_str());
+            let (mint, amount) = match (parsed.program.as_str(), kind) {
+                ("system", "transfer") => (None, info.get("lamports")?.as_u64()?),
+                (program, "transfer") if program.starts_with("spl-token") => {
+                    (mint_of(field("source")?), field("amount")?.parse().ok()?)
+                }
+                (program, "transferChecked") if program.starts_with("spl-token") => (
+                    field("mint").map(str::to_string),
+                    info.get("tokenAmount")?
+                        .get("amount")?
+                        .as_str()?
+                        .parse()
+                        .ok()?,
+                ),
+                _ => return None,
+            };
+            Some(Transfer {
+                instruction: position,
+                program: parsed.program.clone(),
+                source: field("source")?.to_string(),
+                destination: field("destination")?.to_string(),
+                mint,
+                amount,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,181 @@
+//! The SQLite file behind `--db`: one row per transaction, the SOL and token
+//! transfers decoded from it, and per indexed address the newest signature
+//! stored so far, from which the next run carries on.
+//!
+//! Transfer amounts are stored as decimal text: a token's base units can
+//! exceed the signed 64-bit range of an SQLite integer.
+
+use crate::HistoryRow;
+use common::{ExampleError, Result};
+use rusqlite::{Connection, OptionalExtension, params};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::path::Path;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS transactions (
+        signature  TEXT PRIMARY KEY,
+        slot       INTEGER NOT NULL,
+        block_time INTEGER,
+        status     TEXT NOT NULL,
+        fee        INTEGER NOT NULL,
+        summary    TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS transfers (
+        signature   TEXT NOT NULL REFERENCES transactions (signature),
+        instruction TEXT NOT NULL,
+        program     TEXT NOT NULL,
+        source      TEXT NOT NULL,
+        destination TEXT NOT NULL,
+        mint        TEXT,
+        amount      TEXT NOT NULL,
+        PRIMARY KEY (signature, instruction)
+    );
+    CREATE TABLE IF NOT EXISTS address_transactions (
+        address   TEXT NOT NULL,
+        signature TEXT NOT NULL REFERENCES transactions (signature),
+        PRIMARY KEY (address, signature)
+    );
+    CREATE TABLE IF NOT EXISTS cursors (
+        address          TEXT PRIMARY KEY,
+        newest_signature TEXT NOT NULL
+    );
+";
+
+/// A SOL or token transfer found in a transaction's instructions.
+pub struct Transfer {
+    /// Position of the instruction, e.g. `2`, or `2.1` for the first
+    /// instruction invoked by instruction 2
+    pub instruction: String,
+    pub program: String,
+    pub source: String,
+    pub destination: String,
+    /// The token's mint, or none for SOL
+    pub mint: Option<String>,
+    /// In lamports or the token's base units
+    pub amount: u64,
+}
+
+pub struct Store {
+    connection: Connection,
+}
+
+impl Store {
+    /// Opens the database at `path`, creating it and its tables if needed.
+    pub fn open(path: &Path) -> Result<Self> {
+        let connection = Connection::open(path).map_err(database_error)?;
+        connection.execute_batch(SCHEMA).map_err(database_error)?;
+        // `CREATE TABLE IF NOT EXISTS` keeps the integer column of a file
+        // written before amounts were stored as text.
+        let amount_type: String = connection
+            .query_row(
+                "SELECT type FROM pragma_table_info('transfers') WHERE name = 'amount'",
+                [],
+                |row| row.get(0),
+            )
+            .map_err(database_error)?;
+        if amount_type != "TEXT" {
+            return Err(ExampleError::Database(format!(
+                "{} stores transfer amounts as {amount_type}; index into a new file",
+                path.display()
+            )));
+        }
+        Ok(Self { connection })
+    }
+
+    /// The newest signature stored for `address`, if it was indexed before.
+    pub fn cursor(&self, address: &Pubkey) -> Result<Option<Signature>> {
+        let signature: Option<String> = self
+            .connection
+            .query_row(
+                "SELECT newest_signature FROM cursors WHERE address = ?1",
+                [address.to_string()],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(database_error)?;
+        signature
+            .map(|signature| {
+                signature
+                    .parse()
+                    .map_err(|_| ExampleError::Database(format!("stored signature `{signature}`")))
+            })
+            .transpose()
+    }
+
+    /// Upserts one transaction of `address` with its transfers, and moves
+    /// the address's cursor to it, all in one database transaction. Saving
+    /// the same transaction again replaces what was stored.
+    pub fn save(
+        &mut self,
+        address: &Pubkey,
+        row: &HistoryRow,
+        block_time: Option<i64>,
+        transfers: &[Transfer],
+    ) -> Result<()> {
+        let slot = to_integer("slot", row.slot)?;
+        let fee = to_integer("fee", row.fee)?;
+        let tx = self.connection.transaction().map_err(database_error)?;
+        tx.execute(
+            "INSERT INTO transactions (signature, slot, block_time, status, fee, summary)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+             ON CONFLICT (signature) DO UPDATE SET
+                 slot = excluded.slot, block_time = excluded.block_time,
+                 status = excluded.status, fee = excluded.fee, summary = excluded.summary",
+            params![
+                row.signature,
+                slot,
+                block_time,
+                row.status,
+                fee,
+                row.summary
+            ],
+        )
+        .map_err(database_error)?;
+        tx.execute(
+            "DELETE FROM transfers WHERE signature = ?1",
+            [&row.signature],
+        )
+        .map_err(database_error)?;
+        for transfer in transfers {
+            tx.execute(
+                "INSERT INTO transfers
+                     (signature, instruction, program, source, destination, mint, amount)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![
+                    row.signature,
+                    transfer.instruction,
+                    transfer.program,
+                    transfer.source,
+                    transfer.destination,
+                    transfer.mint,
+                    transfer.amount.to_string()
+                ],
+            )
+            .map_err(database_error)?;
+        }
+        tx.execute(
+            "INSERT OR IGNORE INTO address_transactions (address, signature) VALUES (?1, ?2)",
+            [address.to_string(), row.signature.clone()],
+        )
+        .map_err(database_error)?;
+        tx.execute(
+            "INSERT INTO cursors (address, newest_signature) VALUES (?1, ?2)
+             ON CONFLICT (address) DO UPDATE SET newest_signature = excluded.newest_signature",
+            [address.to_string(), row.signature.clone()],
+        )
+        .map_err(database_error)?;
+        tx.commit().map_err(database_error)
+    }
+}
+
+/// `value` as an SQLite integer, which is signed.
+fn to_integer(column: &str, value: u64) -> Result<i64> {
+    i64::try_from(value).map_err(|_| {
+        ExampleError::Database(format!("{column} {value} does not fit an SQLite integer"))
+    })
+}
+
+fn database_error(err: rusqlite::Error) -> ExampleError {
+    ExampleError::Database(err.to_string())
+}
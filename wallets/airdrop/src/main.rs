@@ -0,0 +1,141 @@
+use clap::Parser;
+use common::cli::RpcArgs;
+use common::output::OutputArgs;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, RetryPolicy, explorer};
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::process::ExitCode;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Request a devnet/testnet airdrop, wait for it to finalize and print the
+/// new balance. Faucet rate limits are retried with a growing delay.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet to fund
+    #[arg(short, long)]
+    to: Pubkey,
+
+    /// Amount to request, in SOL; the public faucets cap this at 1-5 SOL
+    #[arg(short, long, value_parser = parse_sol, default_value = "1")]
+    amount: u64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct Airdrop {
+    to: String,
+    lamports: u64,
+    signature: String,
+    /// Finalized balance after the airdrop, in lamports
+    balance: u64,
+}
+
+/// How long to wait for an accepted airdrop to reach `finalized`.
+const FINALIZE_TIMEOUT: Duration = Duration::from_secs(90);
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    if args.rpc.rpc_url.contains("mainnet") {
+        return Err(ExampleError::InvalidArgument(
+            "airdrops are only available on devnet, testnet and local validators; pass --rpc-url"
+                .to_string(),
+        ));
+    }
+    let connection = args.rpc.client();
+    let output = &args.output;
+    let policy = RetryPolicy {
+        initial_delay: Duration::from_secs(2),
+        max_delay: Duration::from_secs(30),
+        ..args.rpc.retry_policy()
+    };
+
+    output.status(format_args!(
+        "Requesting {} SOL for {}",
+        format_sol(args.amount),
+        args.to
+    ));
+    let signature = request_airdrop(&connection, &policy, &args.to, args.amount)?;
+    output.status(format_args!("Airdrop signature: {signature}"));
+    output.status(format_args!(
+        "Explorer:          {}",
+        explorer::transaction_url(&signature, &args.rpc.rpc_url)
+    ));
+
+    wait_for_finalized(&connection, &signature)?;
+    let balance =
+        connection.get_balance_with_commitment(&args.to, CommitmentConfig::finalized())?;
+    output.status(format_args!(
+        "Finalized. Balance is now {} SOL",
+        format_sol(balance.value)
+    ));
+    let airdrop = Airdrop {
+        to: args.to.to_string(),
+        lamports: args.amount,
+        signature: signature.to_string(),
+        balance: balance.value,
+    };
+    output.print_structured(&airdrop)
+}
+
+/// The faucet answers over-limit requests with a JSON-RPC error (or a 429)
+/// rather than queueing them, so those are retried with backoff.
+fn request_airdrop(
+    connection: &RpcClient,
+    policy: &RetryPolicy,
+    to: &Pubkey,
+    lamports: u64,
+) -> Result<Signature> {
+    let mut attempt = 0;
+    loop {
+        let err = match connection.request_airdrop(to, lamports) {
+            Ok(signature) => return Ok(signature),
+            Err(err) => ExampleError::from(err),
+        };
+        if attempt >= policy.max_retries || !(err.is_retryable() || is_faucet_limit(&err)) {
+            return Err(err);
+        }
+        let delay = policy.jittered_delay(attempt);
+        eprintln!(
+            "airdrop attempt {} failed, retrying in {delay:.1?}: {err}",
+            attempt + 1
+        );
+        thread::sleep(delay);
+        attempt += 1;
+    }
+}
+
+fn is_faucet_limit(err: &ExampleError) -> bool {
+    let message = err.to_string().to_ascii_lowercase();
+    err.is_rate_limited()
+        || message.contains("airdrop request limit")
+        || message.contains("faucet has run dry")
+        || message.contains("rate limit")
+}
+
+fn wait_for_finalized(connection: &RpcClient, signature: &Signature) -> Result<()> {
+    let started = Instant::now();
+    while started.elapsed() < FINALIZE_TIMEOUT {
+        let confirmed = connection
+            .confirm_transaction_with_commitment(signature, CommitmentConfig::finalized())?
+            .value;
+        if confirmed {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_secs(1));
+    }
+    Err(ExampleError::TransactionExpired(*signature))
+}
@@ -0,0 +1,202 @@
+use clap::Parser;
+use common::batch::MAX_SIGNATURES_FOR_ADDRESS;
+use common::cli::RpcArgs;
+use common::decode::{self, TokenBalanceChange};
+use common::output::OutputArgs;
+use common::time::format_timestamp;
+use common::units::{format_change, format_token_amount};
+use common::{ExampleError, Result, RetryingRpcClient, at_least_confirmed};
+use serde::Serialize;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::process::ExitCode;
+
+/// Replay a wallet's recent transactions and rebuild its SOL and SPL token
+/// balances over time from each transaction's pre- and post-balances. Rows
+/// come out oldest first, one per balance a transaction changed; run with
+/// `--output csv` to chart them.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet address (base58)
+    #[arg(short, long)]
+    address: Pubkey,
+
+    /// Number of most recent transactions to replay
+    #[arg(short, long, default_value_t = 100)]
+    limit: usize,
+
+    /// Only track this token; pass `SOL` for native SOL only
+    #[arg(short, long)]
+    mint: Option<String>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// One point of the time series.
+#[derive(Serialize)]
+struct BalancePoint {
+    timestamp: Option<String>,
+    slot: u64,
+    signature: String,
+    /// `SOL` or the token's mint
+    asset: String,
+    /// The wallet itself for SOL, the token account for tokens
+    account: String,
+    change: String,
+    /// Balance right after the transaction
+    balance: String,
+}
+
+const SOL: &str = "SOL";
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.retrying_client();
+    let statuses = fetch_signatures(&connection, &args.address, args.limit)?;
+    args.output.status(format_args!(
+        "Replaying {} transactions of {}",
+        statuses.len(),
+        args.address
+    ));
+
+    let mut points = Vec::new();
+    // Signatures come newest first; the series is built oldest first.
+    for status in statuses.iter().rev() {
+        points.extend(balance_points(&connection, &args.address, status)?);
+    }
+    if let Some(mint) = &args.mint {
+        points.retain(|point| &point.asset == mint);
+    }
+
+    args.output.print(&points, || {
+        println!(
+            "{:<20}  {:<44}  {:>22}  {:>22}  Signature",
+            "Time", "Asset", "Change", "Balance"
+        );
+        for point in &points {
+            println!(
+                "{:<20}  {:<44}  {:>22}  {:>22}  {}",
+                point.timestamp.as_deref().unwrap_or("-"),
+                point.asset,
+                point.change,
+                point.balance,
+                point.signature
+            );
+        }
+    })
+}
+
+fn fetch_signatures(
+    connection: &RetryingRpcClient,
+    address: &Pubkey,
+    limit: usize,
+) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+    let mut statuses = Vec::new();
+    let mut before = None;
+    while statuses.len() < limit {
+        let page_limit = (limit - statuses.len()).min(MAX_SIGNATURES_FOR_ADDRESS);
+        let page = connection.call(|client| {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(page_limit),
+                commitment: Some(at_least_confirmed(client.commitment())),
+            };
+            Ok(client.get_signatures_for_address_with_config(address, config)?)
+        })?;
+        let Some(last) = page.last() else {
+            break;
+        };
+        before = Some(parse_signature(&last.signature)?);
+        statuses.extend(page);
+    }
+    Ok(statuses)
+}
+
+fn parse_signature(signature: &str) -> Result<Signature> {
+    signature
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("signature `{signature}`")))
+}
+
+/// The wallet's SOL balance and the balances of token accounts it owns, as
+/// they were after one transaction. Failed transactions still count: their
+/// fee was charged.
+fn balance_points(
+    connection: &RetryingRpcClient,
+    address: &Pubkey,
+    status: &RpcConfirmedTransactionStatusWithSignature,
+) -> Result<Vec<BalancePoint>> {
+    let signature = parse_signature(&status.signature)?;
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Base64),
+        commitment: Some(at_least_confirmed(connection.commitment())),
+        max_supported_transaction_version: Some(0),
+    };
+    let confirmed =
+        connection.call(|client| Ok(client.get_transaction_with_config(&signature, config)?))?;
+    let Some(meta) = &confirmed.transaction.meta else {
+        return Ok(Vec::new());
+    };
+    let transaction = confirmed
+        .transaction
+        .transaction
+        .decode()
+        .ok_or_else(|| ExampleError::Parse("transaction is not base64 encoded".to_string()))?;
+    let keys = decode::account_keys(&transaction.message, meta)?;
+
+    let point = |asset: String, account: String, change: String, balance: String| BalancePoint {
+        timestamp: confirmed.block_time.map(format_timestamp),
+        slot: confirmed.slot,
+        signature: status.signature.clone(),
+        asset,
+        account,
+        change,
+        balance,
+    };
+    let mut points = Vec::new();
+    if let Some(index) = keys.iter().position(|key| key == address) {
+        let (before, after) = (meta.pre_balances[index], meta.post_balances[index]);
+        if before != after {
+            points.push(point(
+                SOL.to_string(),
+                address.to_string(),
+                format_change(before, after, 9),
+                format_token_amount(after, 9),
+            ));
+        }
+    }
+    let owner = address.to_string();
+    for TokenBalanceChange {
+        account_index,
+        mint,
+        decimals,
+        before,
+        after,
+        ..
+    } in decode::token_balance_changes(meta)
+        .into_iter()
+        .filter(|change| change.owner.as_ref() == Some(&owner))
+    {
+        points.push(point(
+            mint,
+            keys.get(account_index)
+                .map_or_else(|| "?".to_string(), ToString::to_string),
+            format_change(before, after, decimals),
+            format_token_amount(after, decimals),
+        ));
+    }
+    Ok(points)
+}
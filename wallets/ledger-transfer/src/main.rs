@@ -0,0 +1,262 @@
+use clap::{Parser, Subcommand};
+use common::cli::RpcArgs;
+use common::keys::DEFAULT_DERIVATION_PATH;
+use common::output::OutputArgs;
+use common::units::{format_sol, parse_sol};
+use common::{ExampleError, Result, explorer};
+use serde::Serialize;
+use solana_derivation_path::DerivationPath;
+use solana_remote_wallet::locator::Locator;
+use solana_remote_wallet::remote_keypair::{RemoteKeypair, generate_remote_keypair};
+use solana_remote_wallet::remote_wallet::{
+    RemoteWalletError, RemoteWalletManager, initialize_wallet_manager,
+};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use solana_sdk::signer::SignerError;
+use solana_sdk::transaction::Transaction;
+use solana_system_interface::instruction as system_instruction;
+use std::process::ExitCode;
+use std::rc::Rc;
+
+/// Sign a SOL transfer on a Ledger hardware wallet with
+/// `solana-remote-wallet`. `list` finds connected devices and shows the
+/// addresses of the first few derivation paths, to pick the account to use;
+/// `transfer` builds the transaction and waits while it is reviewed and
+/// approved on the device.
+///
+/// Unlock the Ledger and open the Solana app first. USB access needs the
+/// `hidapi` feature, which on Linux links against libudev:
+/// `cargo run -p ledger-transfer --features hidapi -- list`.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Which device to use: `usb://ledger`, or `usb://ledger/<pubkey>` to
+    /// pick one of several by its base address
+    #[arg(long, default_value = "usb://ledger")]
+    locator: String,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// List connected Ledgers and the addresses of their first accounts
+    List {
+        /// Derivation paths m/44'/501'/<n>'/0' to show, starting at 0
+        #[arg(short = 'n', long, default_value_t = 5)]
+        accounts: u32,
+    },
+    /// Send SOL from a Ledger account
+    Transfer {
+        /// BIP44 path of the sending account
+        #[arg(long, default_value = DEFAULT_DERIVATION_PATH)]
+        derivation_path: String,
+
+        /// Recipient wallet address (base58)
+        #[arg(short, long)]
+        to: Pubkey,
+
+        /// Amount to send, in SOL (e.g. 0.01)
+        #[arg(short, long, value_parser = parse_sol)]
+        amount: u64,
+
+        /// Also show the sending address on the device and wait for it to be
+        /// confirmed there, guarding against a compromised host
+        #[arg(long)]
+        confirm_key: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct Device {
+    model: String,
+    serial: String,
+    /// Base address, at m/44'/501'
+    pubkey: String,
+    accounts: Vec<DerivedAccount>,
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DerivedAccount {
+    derivation_path: String,
+    address: String,
+    balance: u64,
+}
+
+#[derive(Serialize)]
+struct LedgerTransfer {
+    from: String,
+    derivation_path: String,
+    to: String,
+    lamports: u64,
+    signature: String,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let output = &args.output;
+    let locator = Locator::new_from_path(&args.locator)
+        .map_err(|err| ExampleError::InvalidArgument(format!("--locator: {err}")))?;
+    let manager = connect(output)?;
+
+    match args.command {
+        Command::List { accounts } => {
+            let mut devices = Vec::new();
+            for info in manager.list_devices() {
+                let mut device = Device {
+                    model: info.model.clone(),
+                    serial: info.serial.clone(),
+                    pubkey: info.pubkey.to_string(),
+                    accounts: Vec::new(),
+                    error: info.error.as_ref().map(ToString::to_string),
+                };
+                if info.error.is_none() {
+                    let device_locator = Locator::new_from_path(info.get_pretty_path())
+                        .map_err(|err| ExampleError::Parse(err.to_string()))?;
+                    for account in 0..accounts {
+                        let path = DerivationPath::new_bip44(Some(account), Some(0));
+                        let keypair = remote_keypair(&device_locator, &path, &manager, false)?;
+                        device.accounts.push(DerivedAccount {
+                            derivation_path: format!("{path:?}"),
+                            address: keypair.pubkey().to_string(),
+                            balance: connection.get_balance(&keypair.pubkey())?,
+                        });
+                    }
+                }
+                devices.push(device);
+            }
+            output.print(&devices, || {
+                for device in &devices {
+                    println!("{} {} ({})", device.model, device.serial, device.pubkey);
+                    if let Some(err) = &device.error {
+                        println!("  unavailable: {err}");
+                    }
+                    for account in &device.accounts {
+                        println!(
+                            "  {:<20}  {:<44}  {:>14} SOL",
+                            account.derivation_path,
+                            account.address,
+                            format_sol(account.balance)
+                        );
+                    }
+                }
+            })
+        }
+        Command::Transfer {
+            derivation_path,
+            to,
+            amount,
+            confirm_key,
+        } => {
+            let path = DerivationPath::from_absolute_path_str(&derivation_path).map_err(|err| {
+                ExampleError::InvalidArgument(format!("--derivation-path: {err}"))
+            })?;
+            if confirm_key {
+                output.status("Confirm the sending address on the device");
+            }
+            let keypair = remote_keypair(&locator, &path, &manager, confirm_key)?;
+            let from = keypair.pubkey();
+            output.status(format_args!("Sending from {from} ({})", keypair.path));
+
+            let message = Message::new(
+                &[system_instruction::transfer(&from, &to, amount)],
+                Some(&from),
+            );
+            // The device can take a while to approve, so the blockhash is
+            // fetched right before signing. Signing happens once: re-signing
+            // after an expiry would mean approving again.
+            let blockhash = connection.get_latest_blockhash()?;
+            output.status(format_args!(
+                "Review and approve the transfer of {} SOL to {to} on the device",
+                format_sol(amount)
+            ));
+            let mut transaction = Transaction::new_unsigned(message);
+            transaction
+                .try_sign(&[&keypair], blockhash)
+                .map_err(|err| match err {
+                    SignerError::UserCancel(_) => {
+                        ExampleError::Keypair("the transfer was rejected on the device".to_string())
+                    }
+                    err => ExampleError::Keypair(err.to_string()),
+                })?;
+            let signature = connection.send_and_confirm_transaction(&transaction)?;
+            output.status(format_args!("Signature: {signature}"));
+            output.status(format_args!(
+                "Explorer:  {}",
+                explorer::transaction_url(&signature, &args.rpc.rpc_url)
+            ));
+
+            let transfer = LedgerTransfer {
+                from: from.to_string(),
+                derivation_path: format!("{path:?}"),
+                to: to.to_string(),
+                lamports: amount,
+                signature: signature.to_string(),
+            };
+            output.print_structured(&transfer)
+        }
+    }
+}
+
+/// Opens USB access and scans for devices, failing with a hint when none
+/// answer.
+fn connect(output: &OutputArgs) -> Result<Rc<RemoteWalletManager>> {
+    if cfg!(not(feature = "hidapi")) {
+        return Err(ExampleError::InvalidArgument(
+            "built without USB support; rerun with `--features hidapi`".to_string(),
+        ));
+    }
+    let manager = initialize_wallet_manager().map_err(wallet_error)?;
+    let found = manager.update_devices().map_err(wallet_error)?;
+    if found == 0 {
+        return Err(ExampleError::Keypair(
+            "no Ledger found; connect and unlock it and open the Solana app".to_string(),
+        ));
+    }
+    output.status(format_args!(
+        "Found {found} device{}",
+        if found == 1 { "" } else { "s" }
+    ));
+    Ok(manager)
+}
+
+/// The key at `path` on the device `locator` selects. With several devices
+/// matching, the user is asked to pick one on the terminal.
+fn remote_keypair(
+    locator: &Locator,
+    path: &DerivationPath,
+    manager: &RemoteWalletManager,
+    confirm_key: bool,
+) -> Result<RemoteKeypair> {
+    generate_remote_keypair(
+        locator.clone(),
+        path.clone(),
+        manager,
+        confirm_key,
+        "ledger-transfer",
+    )
+    .map_err(wallet_error)
+}
+
+fn wallet_error(err: RemoteWalletError) -> ExampleError {
+    match err {
+        RemoteWalletError::UserCancel => {
+            ExampleError::Keypair("the request was rejected on the device".to_string())
+        }
+        err => ExampleError::Keypair(format!("ledger: {err}")),
+    }
+}
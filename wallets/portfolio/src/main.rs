@@ -0,0 +1,464 @@
+use clap::Parser;
+use common::cli::{AddressArg, RpcArgs};
+use common::output::OutputArgs;
+use common::price::{self, DEFAULT_PRICE_API_URL};
+use common::time::format_timestamp;
+use common::token::{self, MintInfo};
+use common::units::format_sol;
+use common::{ExampleError, Result, batch};
+use serde::Serialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::clock::Slot;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_stake_interface::state::StakeStateV2;
+use spl_token::native_mint;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Mutex;
+use std::thread;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Offset of the withdraw authority in a stake account: the state tag and
+/// the rent-exempt reserve come first, then the stake authority.
+const WITHDRAWER_OFFSET: usize = 4 + 8 + 32;
+
+/// Take a snapshot of what a set of wallets holds: SOL, stake accounts they
+/// can withdraw from, and token accounts under both token programs, totalled
+/// per mint across all of them and valued in USD where a price is known.
+///
+/// `--output json` emits the whole snapshot as a single document, with each
+/// wallet's holdings as well as the totals.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// File with one wallet address per line ('#' starts a comment)
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+
+    /// Wallets looked up at the same time
+    #[arg(short = 'n', long, default_value_t = 8)]
+    concurrency: usize,
+
+    /// Jupiter price API endpoint
+    #[arg(long, env = "JUPITER_PRICE_API_URL", default_value = DEFAULT_PRICE_API_URL)]
+    price_api: String,
+
+    /// JSON file mapping mint addresses to USD prices, used instead of the
+    /// price API; wrapped SOL's mint prices SOL
+    #[arg(long, conflicts_with = "no_prices")]
+    prices: Option<PathBuf>,
+
+    /// Leave the snapshot unvalued
+    #[arg(long)]
+    no_prices: bool,
+
+    /// Wallets, in addition to --file: addresses (base58), `.sol` domains or
+    /// keypair files
+    wallets: Vec<AddressArg>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Where USD prices come from. Implement it to value holdings with another
+/// feed; mints it has no price for are left unvalued.
+trait PriceSource {
+    /// USD price per whole token for each of `mints` that has one.
+    fn usd_prices(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, f64>>;
+}
+
+struct Jupiter {
+    http: reqwest::blocking::Client,
+    api_url: String,
+}
+
+impl PriceSource for Jupiter {
+    fn usd_prices(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, f64>> {
+        price::fetch_usd_prices(&self.http, &self.api_url, mints)
+    }
+}
+
+/// Prices read from a file, for valuing a snapshot offline or at prices of
+/// one's choosing.
+struct FixedPrices(HashMap<Pubkey, f64>);
+
+impl FixedPrices {
+    fn from_file(path: &Path) -> Result<Self> {
+        let invalid = |err: String| ExampleError::Parse(format!("{}: {err}", path.display()));
+        let prices: HashMap<String, f64> = serde_json::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| invalid(err.to_string()))?;
+        prices
+            .into_iter()
+            .map(|(mint, usd_price)| {
+                let mint = mint
+                    .parse()
+                    .map_err(|_| invalid(format!("{mint} is not a mint address")))?;
+                Ok((mint, usd_price))
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl PriceSource for FixedPrices {
+    fn usd_prices(&self, mints: &[Pubkey]) -> Result<HashMap<Pubkey, f64>> {
+        Ok(mints
+            .iter()
+            .filter_map(|mint| Some((*mint, *self.0.get(mint)?)))
+            .collect())
+    }
+}
+
+#[derive(Serialize)]
+struct Snapshot {
+    timestamp: String,
+    /// Slot the holdings were read at, give or take the time the lookups took
+    slot: Slot,
+    wallets: Vec<WalletHoldings>,
+    /// SOL first, then each mint, largest USD value first
+    totals: Vec<AssetTotal>,
+    /// Sum of the valued totals; absent with --no-prices
+    usd_value: Option<f64>,
+    /// Assets no price was found for, left out of `usd_value`
+    unpriced: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct WalletHoldings {
+    address: String,
+    lamports: u64,
+    stake_accounts: Vec<StakeHolding>,
+    token_accounts: Vec<TokenHolding>,
+}
+
+#[derive(Serialize)]
+struct StakeHolding {
+    address: String,
+    /// Balance including the rent-exempt reserve
+    lamports: u64,
+    /// Vote account the stake is delegated to, if any
+    vote_account: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TokenHolding {
+    account: String,
+    mint: String,
+    program: &'static str,
+    /// Balance in base units
+    amount: u64,
+    ui_amount: String,
+}
+
+/// A wallet as looked up, before its token accounts are decoded with
+/// their mints.
+struct Fetched {
+    wallet: WalletHoldings,
+    tokens: Vec<token::TokenHolding>,
+}
+
+#[derive(Serialize)]
+struct AssetTotal {
+    /// "SOL" for native and staked SOL, else the mint address
+    asset: String,
+    /// Total in base units: lamports for SOL
+    amount: u64,
+    ui_amount: String,
+    decimals: u8,
+    /// Wallets holding any of it
+    wallets: usize,
+    usd_price: Option<f64>,
+    usd_value: Option<f64>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let mut wallets = args
+        .wallets
+        .iter()
+        .map(|wallet| wallet.resolve(&connection))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(file) = &args.file {
+        wallets.extend(batch::read_keys_file(file)?);
+    }
+    // A wallet listed twice would be counted twice in the totals.
+    let mut seen = HashSet::new();
+    wallets.retain(|wallet| seen.insert(*wallet));
+    if wallets.is_empty() {
+        return Err(ExampleError::InvalidArgument(
+            "pass wallets as arguments or with --file".to_string(),
+        ));
+    }
+    let prices: Option<Box<dyn PriceSource>> = match &args.prices {
+        _ if args.no_prices => None,
+        Some(path) => Some(Box::new(FixedPrices::from_file(path)?)),
+        None => Some(Box::new(Jupiter {
+            http: reqwest::blocking::Client::new(),
+            api_url: args.price_api.clone(),
+        })),
+    };
+
+    let slot = connection.get_slot()?;
+    args.output.status(format_args!(
+        "Looking up {} wallets at slot {slot}",
+        wallets.len()
+    ));
+    let holdings = fetch_all(&connection, &wallets, args.concurrency)?;
+    let mints = fetch_mints(&connection, &holdings)?;
+    let mut snapshot = consolidate(slot, holdings, &mints);
+    if let Some(prices) = &prices {
+        value(&mut snapshot, prices.as_ref())?;
+    }
+
+    args.output.print(&snapshot, || print_totals(&snapshot))?;
+    Ok(())
+}
+
+/// Looks the wallets up from `concurrency` threads, keeping their order.
+fn fetch_all(
+    connection: &RpcClient,
+    wallets: &[Pubkey],
+    concurrency: usize,
+) -> Result<Vec<Fetched>> {
+    let queue = Mutex::new(wallets.iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(wallets.len()));
+    thread::scope(|scope| {
+        for _ in 0..concurrency.max(1) {
+            scope.spawn(|| {
+                loop {
+                    let Some((index, wallet)) = queue.lock().unwrap().next() else {
+                        return;
+                    };
+                    let result = fetch_wallet(connection, wallet);
+                    results.lock().unwrap().push((index, result));
+                }
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+fn fetch_wallet(connection: &RpcClient, wallet: &Pubkey) -> Result<Fetched> {
+    let tokens = token::fetch_token_accounts(connection, wallet)?;
+    let wallet = WalletHoldings {
+        address: wallet.to_string(),
+        lamports: connection.get_balance(wallet)?,
+        stake_accounts: fetch_stake_accounts(connection, wallet)?,
+        token_accounts: Vec::new(),
+    };
+    Ok(Fetched { wallet, tokens })
+}
+
+/// Stake accounts count towards the wallet that can withdraw them: the
+/// stake authority can only delegate and deactivate.
+fn fetch_stake_accounts(connection: &RpcClient, wallet: &Pubkey) -> Result<Vec<StakeHolding>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            WITHDRAWER_OFFSET,
+            wallet.as_ref(),
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(connection.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = connection
+        .get_program_accounts_with_config(&solana_stake_interface::program::ID, config)?;
+    accounts
+        .into_iter()
+        .map(|(address, account)| {
+            let state: StakeStateV2 = bincode::deserialize(&account.data)
+                .map_err(|err| ExampleError::Parse(format!("stake account {address}: {err}")))?;
+            Ok(StakeHolding {
+                address: address.to_string(),
+                lamports: account.lamports,
+                vote_account: state
+                    .delegation()
+                    .map(|delegation| delegation.voter_pubkey.to_string()),
+            })
+        })
+        .collect()
+}
+
+fn fetch_mints(connection: &RpcClient, holdings: &[Fetched]) -> Result<HashMap<Pubkey, MintInfo>> {
+    let mut mints: Vec<Pubkey> = holdings
+        .iter()
+        .flat_map(|fetched| {
+            fetched
+                .tokens
+                .iter()
+                .map(|holding| holding.info.account.mint)
+        })
+        .collect();
+    mints.sort();
+    mints.dedup();
+
+    let accounts = batch::get_multiple_accounts(connection, &mints)?;
+    let mut infos = HashMap::with_capacity(mints.len());
+    for (mint, account) in mints.iter().zip(accounts) {
+        let account = account.ok_or(ExampleError::AccountNotFound(*mint))?;
+        infos.insert(*mint, MintInfo::unpack(mint, &account)?);
+    }
+    Ok(infos)
+}
+
+/// Fills in each wallet's token accounts and sums every asset across the
+/// wallets. Staked SOL counts as SOL; wrapped SOL stays a token of its own.
+fn consolidate(slot: Slot, holdings: Vec<Fetched>, mints: &HashMap<Pubkey, MintInfo>) -> Snapshot {
+    let mut sol = AssetTotal {
+        asset: "SOL".to_string(),
+        amount: 0,
+        ui_amount: String::new(),
+        decimals: LAMPORTS_PER_SOL.ilog10() as u8,
+        wallets: 0,
+        usd_price: None,
+        usd_value: None,
+    };
+    let mut by_mint: BTreeMap<Pubkey, (u64, HashSet<usize>)> = BTreeMap::new();
+    let mut wallets = Vec::with_capacity(holdings.len());
+    for (index, Fetched { mut wallet, tokens }) in holdings.into_iter().enumerate() {
+        let staked: u64 = wallet
+            .stake_accounts
+            .iter()
+            .map(|stake| stake.lamports)
+            .sum();
+        if wallet.lamports + staked > 0 {
+            sol.amount += wallet.lamports + staked;
+            sol.wallets += 1;
+        }
+        for token::TokenHolding { address, info, .. } in tokens {
+            let account = &info.account;
+            wallet.token_accounts.push(TokenHolding {
+                account: address.to_string(),
+                mint: account.mint.to_string(),
+                program: token::program_name(&info.program_id),
+                amount: account.amount,
+                ui_amount: mints[&account.mint].ui_amount(account.amount),
+            });
+            // Emptied accounts stay open until closed; they hold nothing.
+            if account.amount > 0 {
+                let (amount, holders) = by_mint.entry(account.mint).or_default();
+                *amount += account.amount;
+                holders.insert(index);
+            }
+        }
+        wallets.push(wallet);
+    }
+    sol.ui_amount = format_sol(sol.amount);
+
+    let mut totals = vec![sol];
+    totals.extend(
+        by_mint
+            .into_iter()
+            .map(|(mint, (amount, holders))| AssetTotal {
+                asset: mint.to_string(),
+                amount,
+                ui_amount: mints[&mint].ui_amount(amount),
+                decimals: mints[&mint].decimals(),
+                wallets: holders.len(),
+                usd_price: None,
+                usd_value: None,
+            }),
+    );
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs() as i64;
+    Snapshot {
+        timestamp: format_timestamp(now),
+        slot,
+        wallets,
+        totals,
+        usd_value: None,
+        unpriced: Vec::new(),
+    }
+}
+
+/// Values each total at its price and orders the mints by value. SOL is
+/// priced as wrapped SOL. Values are computed in floating point, which is
+/// plenty for a valuation but not for accounting.
+fn value(snapshot: &mut Snapshot, prices: &dyn PriceSource) -> Result<()> {
+    let mint_of = |total: &AssetTotal| -> Result<Pubkey> {
+        if total.asset == "SOL" {
+            Ok(native_mint::ID)
+        } else {
+            Ok(total.asset.parse()?)
+        }
+    };
+    let mints = snapshot
+        .totals
+        .iter()
+        .map(mint_of)
+        .collect::<Result<Vec<_>>>()?;
+    let mut unique = mints.clone();
+    unique.sort();
+    unique.dedup();
+    let found = prices.usd_prices(&unique)?;
+    snapshot.usd_value = Some(0.0);
+
+    for (total, mint) in snapshot.totals.iter_mut().zip(&mints) {
+        let Some(&usd_price) = found.get(mint) else {
+            snapshot.unpriced.push(total.asset.clone());
+            continue;
+        };
+        let usd_value = total.amount as f64 / 10f64.powi(i32::from(total.decimals)) * usd_price;
+        total.usd_price = Some(usd_price);
+        total.usd_value = Some(usd_value);
+        snapshot.usd_value = snapshot.usd_value.map(|sum| sum + usd_value);
+    }
+    snapshot.totals[1..].sort_by(|a, b| {
+        b.usd_value
+            .unwrap_or(-1.0)
+            .total_cmp(&a.usd_value.unwrap_or(-1.0))
+    });
+    Ok(())
+}
+
+fn print_totals(snapshot: &Snapshot) {
+    println!(
+        "{} wallets at slot {} ({})",
+        snapshot.wallets.len(),
+        snapshot.slot,
+        snapshot.timestamp
+    );
+    println!();
+    println!(
+        "{:<44}  {:>28}  {:>7}  {:>14}",
+        "Asset", "Amount", "Wallets", "USD value"
+    );
+    for total in &snapshot.totals {
+        println!(
+            "{:<44}  {:>28}  {:>7}  {:>14}",
+            total.asset,
+            total.ui_amount,
+            total.wallets,
+            total
+                .usd_value
+                .map_or_else(|| "-".to_string(), |value| format!("{value:.2}"))
+        );
+    }
+    if let Some(usd_value) = snapshot.usd_value {
+        println!();
+        println!("Total: ${usd_value:.2}");
+    }
+    if !snapshot.unpriced.is_empty() {
+        println!("No price for {} of the assets", snapshot.unpriced.len());
+    }
+}
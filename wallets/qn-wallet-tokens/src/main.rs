@@ -0,0 +1,58 @@
+use clap::Parser;
+use common::Result;
+use common::cli::{AddressArg, RpcArgs};
+use common::output::OutputArgs;
+use common::quicknode_ext::{self, MAX_PAGE_SIZE};
+use std::process::ExitCode;
+
+/// List the fungible tokens a wallet holds, with their names and symbols,
+/// through QuickNode's Token and NFT API (`qn_getWalletTokenBalance`), in
+/// one call instead of a getTokenAccountsByOwner per token program plus a
+/// metadata lookup per mint. Requires an endpoint with the add-on.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    /// Wallet: an address (base58), a `.sol` domain or a keypair file
+    wallet: AddressArg,
+
+    /// 1-based page number
+    #[arg(short, long, default_value_t = 1)]
+    page: u32,
+
+    /// Tokens per page
+    #[arg(short, long, default_value_t = 50, value_parser = clap::value_parser!(u32).range(1..=i64::from(MAX_PAGE_SIZE)))]
+    limit: u32,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.client();
+    let wallet = args.wallet.resolve(&connection)?;
+    let page =
+        quicknode_ext::get_wallet_token_balance(&connection, &wallet, args.page, args.limit)?;
+    args.output.status(format_args!(
+        "Page {} of {} ({} tokens in all)",
+        page.page_number, page.total_pages, page.total_items
+    ));
+    args.output.print(&page, || {
+        println!("{:<44}  {:<10}  {:>24}  Name", "Mint", "Symbol", "Amount");
+        for token in &page.assets {
+            println!(
+                "{:<44}  {:<10}  {:>24}  {}",
+                token.address,
+                token.symbol.as_deref().unwrap_or(""),
+                token.amount,
+                token.name.as_deref().unwrap_or("")
+            );
+        }
+    })
+}
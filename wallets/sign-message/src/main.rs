@@ -0,0 +1,320 @@
+mod siws;
+
+use chrono::{Duration, Utc};
+use clap::{Parser, Subcommand};
+use common::cli::KeypairArgs;
+use common::output::OutputArgs;
+use common::{ExampleError, Result};
+use rand::Rng;
+use rand::distributions::Alphanumeric;
+use serde::Serialize;
+use siws::SignInMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use std::fs;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Length of the nonces `sign-in` generates; the format asks for at least 8
+/// alphanumeric characters.
+const NONCE_LEN: usize = 16;
+
+/// Sign off-chain messages with a keypair and verify such signatures, the way
+/// a backend checks that a user controls a wallet.
+///
+/// Wallets' `signMessage` signs the raw bytes of the message with the
+/// account's ed25519 key, which is what `Keypair::sign_message` does; anyone
+/// can check the result against the address with `Signature::verify`. No
+/// RPC endpoint is involved.
+///
+/// `sign-in` builds and signs a "Sign In With Solana" message. `verify
+/// --domain` additionally parses one and checks what a backend must: that it
+/// names its domain and the signer's address, carries the nonce it issued,
+/// and is within its validity window.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Sign a message with the keypair
+    Sign {
+        #[command(flatten)]
+        keypair: KeypairArgs,
+
+        #[command(flatten)]
+        message: MessageArgs,
+    },
+    /// Build a Sign In With Solana message for the keypair's address and
+    /// sign it, as a wallet would
+    SignIn(SignInArgs),
+    /// Check a signature over a message
+    Verify(VerifyArgs),
+}
+
+/// The message, given inline or read from a file.
+#[derive(clap::Args)]
+#[group(required = true, multiple = false)]
+struct MessageArgs {
+    /// Message text
+    #[arg(short, long)]
+    message: Option<String>,
+
+    /// File holding the message, byte for byte; for messages spanning
+    /// lines such as sign-in messages
+    #[arg(long)]
+    message_file: Option<PathBuf>,
+}
+
+impl MessageArgs {
+    fn read(&self) -> Result<String> {
+        match (&self.message, &self.message_file) {
+            (Some(message), _) => Ok(message.clone()),
+            (None, Some(path)) => Ok(fs::read_to_string(path)?),
+            (None, None) => unreachable!("clap requires one of them"),
+        }
+    }
+}
+
+#[derive(clap::Args)]
+struct SignInArgs {
+    #[command(flatten)]
+    keypair: KeypairArgs,
+
+    /// Domain of the site asking the user to sign in, e.g. example.com
+    #[arg(long)]
+    domain: String,
+
+    /// Human-readable line shown to the user
+    #[arg(long)]
+    statement: Option<String>,
+
+    /// URI of the resource being signed in to; defaults to https://<domain>
+    #[arg(long)]
+    uri: Option<String>,
+
+    #[arg(long, default_value = "mainnet")]
+    chain_id: String,
+
+    /// Nonce issued by the backend; a random one is generated when omitted
+    #[arg(long)]
+    nonce: Option<String>,
+
+    /// Minutes the message stays valid for
+    #[arg(long, default_value_t = 10)]
+    expires_in: u32,
+
+    /// Resource URIs the sign-in grants access to; repeatable
+    #[arg(long)]
+    resource: Vec<String>,
+
+    /// Also write the message to this file, for `verify --message-file`
+    #[arg(long)]
+    out: Option<PathBuf>,
+}
+
+#[derive(clap::Args)]
+struct VerifyArgs {
+    /// Address (base58) that supposedly signed the message
+    #[arg(long)]
+    signer: Pubkey,
+
+    /// Signature (base58)
+    #[arg(long)]
+    signature: Signature,
+
+    #[command(flatten)]
+    message: MessageArgs,
+
+    /// Treat the message as a sign-in message that must be for this domain
+    #[arg(long)]
+    domain: Option<String>,
+
+    /// Nonce the sign-in message must carry
+    #[arg(long, requires = "domain")]
+    nonce: Option<String>,
+}
+
+#[derive(Serialize)]
+struct SignedMessage {
+    signer: String,
+    message: String,
+    signature: String,
+}
+
+#[derive(Serialize)]
+struct Verification {
+    signer: String,
+    signature_valid: bool,
+    /// The sign-in fields, when --domain was given and the signature holds
+    sign_in: Option<SignInCheck>,
+}
+
+#[derive(Serialize)]
+struct SignInCheck {
+    domain: String,
+    nonce: Option<String>,
+    issued_at: Option<String>,
+    expiration_time: Option<String>,
+    /// Why the message is not acceptable, if it is not
+    error: Option<String>,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    match &args.command {
+        Command::Sign { keypair, message } => {
+            let keypair = keypair.load()?;
+            let message = message.read()?;
+            print_signed(&args.output, &keypair, message)
+        }
+        Command::SignIn(sign_in) => {
+            let keypair = sign_in.keypair.load()?;
+            let now = Utc::now();
+            let mut message = SignInMessage::new(&sign_in.domain, keypair.pubkey());
+            message.statement = sign_in.statement.clone();
+            message.uri = Some(
+                sign_in
+                    .uri
+                    .clone()
+                    .unwrap_or_else(|| format!("https://{}", sign_in.domain)),
+            );
+            message.version = Some("1".to_string());
+            message.chain_id = Some(sign_in.chain_id.clone());
+            message.nonce = Some(sign_in.nonce.clone().unwrap_or_else(|| {
+                rand::thread_rng()
+                    .sample_iter(&Alphanumeric)
+                    .take(NONCE_LEN)
+                    .map(char::from)
+                    .collect()
+            }));
+            message.issued_at = Some(now);
+            message.expiration_time = Some(now + Duration::minutes(i64::from(sign_in.expires_in)));
+            message.resources = sign_in.resource.clone();
+            let message = message.to_string();
+            if let Some(path) = &sign_in.out {
+                fs::write(path, &message)?;
+            }
+            print_signed(&args.output, &keypair, message)
+        }
+        Command::Verify(verify) => self::verify(&args.output, verify),
+    }
+}
+
+fn print_signed(output: &OutputArgs, keypair: &dyn Signer, message: String) -> Result<()> {
+    let signature = keypair.try_sign_message(message.as_bytes())?;
+    let signed = SignedMessage {
+        signer: keypair.try_pubkey()?.to_string(),
+        message,
+        signature: signature.to_string(),
+    };
+    output.print(&signed, || {
+        println!("Signer:    {}", signed.signer);
+        println!("Signature: {}", signed.signature);
+        println!();
+        println!("{}", signed.message);
+    })
+}
+
+fn verify(output: &OutputArgs, args: &VerifyArgs) -> Result<()> {
+    let text = args.message.read()?;
+    let signature_valid = args.signature.verify(args.signer.as_ref(), text.as_bytes());
+    // Fields of a message nobody signed mean nothing, so they are only
+    // looked at once the signature holds.
+    let sign_in = match (&args.domain, signature_valid) {
+        (Some(domain), true) => Some(check_sign_in(&text, domain, args)),
+        _ => None,
+    };
+    let verification = Verification {
+        signer: args.signer.to_string(),
+        signature_valid,
+        sign_in,
+    };
+    output.print(&verification, || {
+        println!(
+            "Signature: {}",
+            if verification.signature_valid {
+                "valid"
+            } else {
+                "INVALID"
+            }
+        );
+        if let Some(sign_in) = &verification.sign_in {
+            println!("Domain:    {}", sign_in.domain);
+            if let Some(nonce) = &sign_in.nonce {
+                println!("Nonce:     {nonce}");
+            }
+            if let Some(issued_at) = &sign_in.issued_at {
+                println!("Issued at: {issued_at}");
+            }
+            if let Some(expiration) = &sign_in.expiration_time {
+                println!("Expires:   {expiration}");
+            }
+            match &sign_in.error {
+                Some(error) => println!("Sign-in:   REJECTED: {error}"),
+                None => println!("Sign-in:   accepted"),
+            }
+        }
+    })?;
+
+    if !verification.signature_valid {
+        return Err(ExampleError::InvalidArgument(format!(
+            "the signature is not {}'s over this message",
+            args.signer
+        )));
+    }
+    if let Some(error) = verification.sign_in.and_then(|sign_in| sign_in.error) {
+        return Err(ExampleError::InvalidArgument(error));
+    }
+    Ok(())
+}
+
+fn check_sign_in(text: &str, domain: &str, args: &VerifyArgs) -> SignInCheck {
+    let message: SignInMessage = match text.parse() {
+        Ok(message) => message,
+        Err(err) => {
+            return SignInCheck {
+                domain: domain.to_string(),
+                nonce: None,
+                issued_at: None,
+                expiration_time: None,
+                error: Some(format!("not a sign-in message: {err}")),
+            };
+        }
+    };
+    let error = if message.domain != domain {
+        Some(format!(
+            "the message is for {}, not {domain}",
+            message.domain
+        ))
+    } else if message.address != args.signer {
+        Some(format!(
+            "the message names {}, not the signer",
+            message.address
+        ))
+    } else if args
+        .nonce
+        .as_ref()
+        .is_some_and(|nonce| message.nonce.as_ref() != Some(nonce))
+    {
+        Some("the nonce is not the one issued".to_string())
+    } else {
+        message.check_time(Utc::now()).err()
+    };
+    SignInCheck {
+        domain: message.domain,
+        nonce: message.nonce,
+        issued_at: message.issued_at.as_ref().map(siws::timestamp),
+        expiration_time: message.expiration_time.as_ref().map(siws::timestamp),
+        error,
+    }
+}
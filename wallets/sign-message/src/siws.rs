@@ -0,0 +1,200 @@
+//! The "Sign In With Solana" message: the Solana take on EIP-4361 that
+//! wallets show when a site asks the user to sign in. The text is what gets
+//! signed, so formatting and parsing have to agree on it line for line:
+//!
+//! ```text
+//! example.com wants you to sign in with your Solana account:
+//! 9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM
+//!
+//! Sign in to the dashboard
+//!
+//! URI: https://example.com/login
+//! Version: 1
+//! Chain ID: mainnet
+//! Nonce: 32891756
+//! Issued At: 2024-01-01T00:00:00.000Z
+//! Expiration Time: 2024-01-01T00:10:00.000Z
+//! ```
+//!
+//! Every field after the address is optional; those present appear in this
+//! order.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use solana_sdk::pubkey::Pubkey;
+use std::fmt;
+use std::str::FromStr;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+
+const FIELDS: [&str; 8] = [
+    "URI",
+    "Version",
+    "Chain ID",
+    "Nonce",
+    "Issued At",
+    "Expiration Time",
+    "Not Before",
+    "Request ID",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignInMessage {
+    /// The site asking, e.g. `example.com`; a backend must check it is its
+    /// own, or a signature collected by another site could be replayed
+    pub domain: String,
+    pub address: Pubkey,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    /// `mainnet`, `devnet`, `testnet` or `localnet`
+    pub chain_id: Option<String>,
+    /// Issued by the backend and accepted once, against replays
+    pub nonce: Option<String>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+impl SignInMessage {
+    pub fn new(domain: impl Into<String>, address: Pubkey) -> Self {
+        Self {
+            domain: domain.into(),
+            address,
+            statement: None,
+            uri: None,
+            version: None,
+            chain_id: None,
+            nonce: None,
+            issued_at: None,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        }
+    }
+
+    /// Why the message is not acceptable at `now`, if it is not.
+    pub fn check_time(&self, now: DateTime<Utc>) -> Result<(), String> {
+        if let Some(expiration) = self.expiration_time.filter(|&expiration| now >= expiration) {
+            return Err(format!("the message expired at {}", timestamp(&expiration)));
+        }
+        if let Some(not_before) = self.not_before.filter(|&not_before| now < not_before) {
+            return Err(format!(
+                "the message is not valid before {}",
+                timestamp(&not_before)
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Milliseconds and a `Z`, as browsers' `toISOString` writes it.
+pub fn timestamp(time: &DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+impl fmt::Display for SignInMessage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{HEADER_SUFFIX}\n{}", self.domain, self.address)?;
+        if let Some(statement) = &self.statement {
+            write!(f, "\n\n{statement}")?;
+        }
+        let fields = [
+            ("URI", self.uri.clone()),
+            ("Version", self.version.clone()),
+            ("Chain ID", self.chain_id.clone()),
+            ("Nonce", self.nonce.clone()),
+            ("Issued At", self.issued_at.as_ref().map(timestamp)),
+            (
+                "Expiration Time",
+                self.expiration_time.as_ref().map(timestamp),
+            ),
+            ("Not Before", self.not_before.as_ref().map(timestamp)),
+            ("Request ID", self.request_id.clone()),
+        ];
+        let mut first = true;
+        for (name, value) in fields
+            .iter()
+            .filter_map(|(name, value)| Some((name, value.as_ref()?)))
+        {
+            // A blank line separates the fields from what comes before.
+            write!(f, "{}{name}: {value}", if first { "\n\n" } else { "\n" })?;
+            first = false;
+        }
+        if !self.resources.is_empty() {
+            write!(f, "{}Resources:", if first { "\n\n" } else { "\n" })?;
+            for resource in &self.resources {
+                write!(f, "\n- {resource}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SignInMessage {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .ok_or(
+                "the first line is not `<domain> wants you to sign in with your Solana account:`",
+            )?;
+        let address = lines
+            .next()
+            .ok_or("the address line is missing")?
+            .parse()
+            .map_err(|_| "the second line is not a base58 address".to_string())?;
+        let mut message = Self::new(domain, address);
+
+        let mut fields_started = false;
+        let mut in_resources = false;
+        for line in lines.filter(|line| !line.is_empty()) {
+            if in_resources {
+                let resource = line
+                    .strip_prefix("- ")
+                    .ok_or_else(|| format!("`{line}` is not a `- ` resource line"))?;
+                message.resources.push(resource.to_string());
+                continue;
+            }
+            if line == "Resources:" {
+                in_resources = true;
+                continue;
+            }
+            let field = line
+                .split_once(": ")
+                .filter(|(name, _)| FIELDS.contains(name));
+            let Some((name, value)) = field else {
+                // The statement is the only free-form line, and comes first.
+                if fields_started || message.statement.is_some() {
+                    return Err(format!("unexpected line `{line}`"));
+                }
+                message.statement = Some(line.to_string());
+                continue;
+            };
+            fields_started = true;
+            let value = value.to_string();
+            match name {
+                "URI" => message.uri = Some(value),
+                "Version" => message.version = Some(value),
+                "Chain ID" => message.chain_id = Some(value),
+                "Nonce" => message.nonce = Some(value),
+                "Issued At" => message.issued_at = Some(parse_time(name, &value)?),
+                "Expiration Time" => message.expiration_time = Some(parse_time(name, &value)?),
+                "Not Before" => message.not_before = Some(parse_time(name, &value)?),
+                _ => message.request_id = Some(value),
+            }
+        }
+        Ok(message)
+    }
+}
+
+fn parse_time(name: &str, value: &str) -> Result<DateTime<Utc>, String> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|err| format!("{name} `{value}`: {err}"))
+}
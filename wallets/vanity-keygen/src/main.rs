@@ -0,0 +1,139 @@
+use clap::Parser;
+use common::output::OutputArgs;
+use common::{ExampleError, Result, keys};
+use serde::Serialize;
+use solana_sdk::signature::{Keypair, Signer};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Grind keypairs on every CPU core until one has an address starting with
+/// the given prefix, then save it in solana-keygen JSON format.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Base58 prefix the address must start with
+    prefix: String,
+
+    /// Match the prefix case-insensitively (much faster for long prefixes)
+    #[arg(short, long)]
+    ignore_case: bool,
+
+    /// Worker threads; defaults to the number of CPU cores
+    #[arg(short, long)]
+    threads: Option<usize>,
+
+    /// Output file; defaults to <ADDRESS>.json
+    #[arg(short, long)]
+    out: Option<PathBuf>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct VanityKeypair {
+    address: String,
+    file: String,
+    attempts: u64,
+    seconds: f64,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+fn run(args: Args) -> Result<()> {
+    if let Some(invalid) = args.prefix.chars().find(|c| !BASE58_ALPHABET.contains(*c)) {
+        return Err(ExampleError::InvalidArgument(format!(
+            "'{invalid}' never appears in a base58 address (0, O, I and l are excluded)"
+        )));
+    }
+    let prefix = if args.ignore_case {
+        args.prefix.to_lowercase()
+    } else {
+        args.prefix.clone()
+    };
+    let output = &args.output;
+    let threads = args
+        .threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()));
+    output.status(format_args!(
+        "Searching for an address starting with '{}' on {threads} threads",
+        args.prefix
+    ));
+
+    let found = AtomicBool::new(false);
+    let attempts = AtomicU64::new(0);
+    let (sender, receiver) = mpsc::channel();
+    let started = Instant::now();
+    let keypair = thread::scope(|scope| {
+        for _ in 0..threads {
+            let sender = sender.clone();
+            let (found, attempts, prefix) = (&found, &attempts, &prefix);
+            scope.spawn(move || {
+                let mut local = 0;
+                while !found.load(Ordering::Relaxed) {
+                    let keypair = Keypair::new();
+                    let address = keypair.pubkey().to_string();
+                    let matches = if args.ignore_case {
+                        address.to_lowercase().starts_with(prefix.as_str())
+                    } else {
+                        address.starts_with(prefix.as_str())
+                    };
+                    local += 1;
+                    // Batch counter updates to keep the shared cache line quiet.
+                    if local == 1_000 {
+                        attempts.fetch_add(local, Ordering::Relaxed);
+                        local = 0;
+                    }
+                    if matches && !found.swap(true, Ordering::Relaxed) {
+                        let _ = sender.send(keypair);
+                    }
+                }
+                attempts.fetch_add(local, Ordering::Relaxed);
+            });
+        }
+        drop(sender);
+
+        loop {
+            match receiver.recv_timeout(Duration::from_secs(5)) {
+                Ok(keypair) => return Some(keypair),
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    let tried = attempts.load(Ordering::Relaxed);
+                    output.status(format_args!(
+                        "  {tried} keys tried, {:.0} keys/sec",
+                        tried as f64 / started.elapsed().as_secs_f64()
+                    ));
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    })
+    .ok_or_else(|| ExampleError::Keypair("all search threads exited".to_string()))?;
+
+    let elapsed = started.elapsed();
+    let tried = attempts.load(Ordering::Relaxed);
+    output.status(format_args!(
+        "Found {} after {tried} keys in {elapsed:.1?} ({:.0} keys/sec)",
+        keypair.pubkey(),
+        tried as f64 / elapsed.as_secs_f64()
+    ));
+    let out = args
+        .out
+        .unwrap_or_else(|| PathBuf::from(format!("{}.json", keypair.pubkey())));
+    keys::write_keypair_file(&keypair, &out)?;
+    output.status(format_args!("Keypair saved to {}", out.display()));
+    let found = VanityKeypair {
+        address: keypair.pubkey().to_string(),
+        file: out.display().to_string(),
+        attempts: tried,
+        seconds: elapsed.as_secs_f64(),
+    };
+    output.print_structured(&found)
+}
@@ -0,0 +1,486 @@
+use clap::Parser;
+use common::cli::{AddressArg, RpcArgs, WsArgs};
+use common::{ExampleError, Result, token};
+use futures::StreamExt;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table};
+use ratatui::{DefaultTerminal, Frame};
+use solana_account_decoder_client_types::{UiAccount, UiAccountData, UiAccountEncoding};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionLogsConfig,
+    RpcTransactionLogsFilter,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, VecDeque};
+use std::process::ExitCode;
+use std::thread;
+use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio::time;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Performance samples are taken once a minute, so polling more often than
+/// this only repeats the same number.
+const TPS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Transactions kept in the recent-transactions pane.
+const RECENT_TRANSACTIONS: usize = 20;
+
+/// The owner is the second field of a token account, in both programs.
+const TOKEN_OWNER_OFFSET: usize = 32;
+
+/// Watch a wallet in a terminal dashboard: its SOL and token balances, its
+/// latest transactions, and the cluster's slot and TPS, each in its own
+/// pane. Press `q` to quit.
+///
+/// The panes are filled once over RPC and then kept current over one
+/// websocket: accountSubscribe for the SOL balance, a programSubscribe per
+/// token program filtered on the owner for the token balances,
+/// logsSubscribe for new transactions and slotSubscribe for the slot. Only
+/// TPS is polled, from getRecentPerformanceSamples.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    /// Wallet to watch: an address (base58), a `.sol` domain or a keypair
+    /// file
+    wallet: AddressArg,
+}
+
+/// Everything the panes show.
+struct Dashboard {
+    wallet: Pubkey,
+    endpoint: String,
+    lamports: Option<u64>,
+    /// Keyed by token account
+    tokens: BTreeMap<Pubkey, TokenRow>,
+    /// Newest first
+    transactions: VecDeque<TransactionRow>,
+    slot: Option<Slot>,
+    tps: Option<f64>,
+    status: String,
+}
+
+struct TokenRow {
+    mint: String,
+    ui_amount: String,
+    program: &'static str,
+}
+
+struct TransactionRow {
+    signature: String,
+    slot: Slot,
+    failed: bool,
+}
+
+/// What the background tasks and the keyboard send the UI.
+enum Update {
+    Lamports(u64),
+    Token(Pubkey, TokenRow),
+    Transaction(TransactionRow),
+    Slot(Slot),
+    Tps(f64),
+    Status(String),
+    Quit,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let connection = args.rpc.nonblocking_client();
+    // `AddressArg` resolves with the blocking client; it is one call.
+    let wallet = args.wallet.resolve(&args.rpc.client())?;
+    let mut dashboard = Dashboard {
+        wallet,
+        endpoint: args.rpc.rpc_url.clone(),
+        lamports: Some(connection.get_balance(&wallet).await?),
+        tokens: fetch_tokens(&connection, &wallet).await?,
+        transactions: fetch_transactions(&connection, &wallet).await?,
+        slot: Some(connection.get_slot().await?),
+        tps: None,
+        status: "Connecting".to_string(),
+    };
+
+    let (updates, mut received) = unbounded_channel();
+    spawn_keyboard(updates.clone());
+    tokio::spawn(poll_tps(connection, updates.clone()));
+    tokio::spawn(subscribe(
+        args.ws.url(&args.rpc),
+        wallet,
+        args.rpc.commitment_config(),
+        updates,
+    ));
+
+    let mut terminal = ratatui::init();
+    let result = draw_loop(&mut terminal, &mut dashboard, &mut received).await;
+    ratatui::restore();
+    result
+}
+
+async fn draw_loop(
+    terminal: &mut DefaultTerminal,
+    dashboard: &mut Dashboard,
+    updates: &mut tokio::sync::mpsc::UnboundedReceiver<Update>,
+) -> Result<()> {
+    loop {
+        terminal.draw(|frame| dashboard.render(frame))?;
+        match updates.recv().await {
+            None | Some(Update::Quit) => return Ok(()),
+            Some(update) => dashboard.apply(update),
+        }
+    }
+}
+
+/// Raw mode turns Ctrl-C into a key press, so it is handled here along with
+/// `q` and Esc. Reading blocks, hence the thread.
+fn spawn_keyboard(updates: UnboundedSender<Update>) {
+    thread::spawn(move || {
+        while let Ok(event) = event::read() {
+            let Event::Key(key) = event else {
+                // Resizes and the like only need a redraw.
+                if updates.send(Update::Status(String::new())).is_err() {
+                    return;
+                }
+                continue;
+            };
+            let quit = key.kind == KeyEventKind::Press
+                && (matches!(key.code, KeyCode::Char('q') | KeyCode::Esc)
+                    || (key.code == KeyCode::Char('c')
+                        && key.modifiers.contains(KeyModifiers::CONTROL)));
+            if quit {
+                let _ = updates.send(Update::Quit);
+                return;
+            }
+        }
+    });
+}
+
+async fn poll_tps(connection: RpcClient, updates: UnboundedSender<Update>) {
+    let mut ticks = time::interval(TPS_INTERVAL);
+    loop {
+        ticks.tick().await;
+        let update = match connection.get_recent_performance_samples(Some(1)).await {
+            Ok(samples) => match samples.first() {
+                Some(sample) if sample.sample_period_secs > 0 => Update::Tps(
+                    sample.num_transactions as f64 / f64::from(sample.sample_period_secs),
+                ),
+                _ => continue,
+            },
+            Err(err) => Update::Status(format!("TPS: {err}")),
+        };
+        if updates.send(update).is_err() {
+            return;
+        }
+    }
+}
+
+/// Keeps the subscriptions up, reconnecting whenever the socket drops.
+async fn subscribe(
+    ws_url: String,
+    wallet: Pubkey,
+    commitment: CommitmentConfig,
+    updates: UnboundedSender<Update>,
+) {
+    loop {
+        let status = match watch(&ws_url, &wallet, commitment, &updates).await {
+            Ok(Stop::Shutdown) => return,
+            Ok(Stop::Disconnected) => "websocket closed by the server".to_string(),
+            Err(err) => err.to_string(),
+        };
+        let status = format!("{status}; resubscribing in {RECONNECT_DELAY:?}");
+        if updates.send(Update::Status(status)).is_err() {
+            return;
+        }
+        time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+/// Runs one set of subscriptions until the socket drops or the UI is gone.
+async fn watch(
+    ws_url: &str,
+    wallet: &Pubkey,
+    commitment: CommitmentConfig,
+    updates: &UnboundedSender<Update>,
+) -> Result<Stop> {
+    let client = PubsubClient::new(ws_url).await?;
+    let account_config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::JsonParsed),
+        commitment: Some(commitment),
+        ..RpcAccountInfoConfig::default()
+    };
+    let (mut balances, _) = client
+        .account_subscribe(wallet, Some(account_config.clone()))
+        .await?;
+    let token_config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            TOKEN_OWNER_OFFSET,
+            wallet.as_ref(),
+        ))]),
+        account_config,
+        ..RpcProgramAccountsConfig::default()
+    };
+    let (mut legacy_tokens, _) = client
+        .program_subscribe(&token::TOKEN_PROGRAMS[0], Some(token_config.clone()))
+        .await?;
+    let (mut tokens_2022, _) = client
+        .program_subscribe(&token::TOKEN_PROGRAMS[1], Some(token_config))
+        .await?;
+    let (mut logs, _) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![wallet.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(commitment),
+            },
+        )
+        .await?;
+    let (mut slots, _) = client.slot_subscribe().await?;
+    if updates.send(Update::Status("Live".to_string())).is_err() {
+        return Ok(Stop::Shutdown);
+    }
+
+    // The subscriptions end with the client when this returns, so there is
+    // nothing to unsubscribe by hand.
+    loop {
+        let update = tokio::select! {
+            notification = balances.next() => match notification {
+                Some(response) => Update::Lamports(response.value.lamports),
+                None => return Ok(Stop::Disconnected),
+            },
+            notification = legacy_tokens.next() => match notification {
+                Some(response) => match token_update(&response.value.pubkey, &response.value.account) {
+                    Some(update) => update,
+                    None => continue,
+                },
+                None => return Ok(Stop::Disconnected),
+            },
+            notification = tokens_2022.next() => match notification {
+                Some(response) => match token_update(&response.value.pubkey, &response.value.account) {
+                    Some(update) => update,
+                    None => continue,
+                },
+                None => return Ok(Stop::Disconnected),
+            },
+            notification = logs.next() => match notification {
+                Some(response) => Update::Transaction(TransactionRow {
+                    signature: response.value.signature,
+                    slot: response.context.slot,
+                    failed: response.value.err.is_some(),
+                }),
+                None => return Ok(Stop::Disconnected),
+            },
+            notification = slots.next() => match notification {
+                Some(info) => Update::Slot(info.slot),
+                None => return Ok(Stop::Disconnected),
+            },
+        };
+        if updates.send(update).is_err() {
+            return Ok(Stop::Shutdown);
+        }
+    }
+}
+
+fn token_update(address: &str, account: &UiAccount) -> Option<Update> {
+    let address = address.parse().ok()?;
+    Some(Update::Token(address, token_row(account)?))
+}
+
+/// The mint and balance out of a `jsonParsed` token account, which carries
+/// the amount already scaled by the mint's decimals.
+fn token_row(account: &UiAccount) -> Option<TokenRow> {
+    let UiAccountData::Json(parsed) = &account.data else {
+        return None;
+    };
+    let info = &parsed.parsed["info"];
+    Some(TokenRow {
+        mint: info["mint"].as_str()?.to_string(),
+        ui_amount: info["tokenAmount"]["uiAmountString"]
+            .as_str()
+            .map(str::to_string)?,
+        program: token::program_name(&account.owner.parse().ok()?),
+    })
+}
+
+async fn fetch_tokens(
+    connection: &RpcClient,
+    wallet: &Pubkey,
+) -> Result<BTreeMap<Pubkey, TokenRow>> {
+    let mut tokens = BTreeMap::new();
+    for program_id in token::TOKEN_PROGRAMS {
+        let accounts = connection
+            .get_token_accounts_by_owner(wallet, TokenAccountsFilter::ProgramId(program_id))
+            .await?;
+        for keyed in accounts {
+            let address: Pubkey = keyed.pubkey.parse()?;
+            let row = token_row(&keyed.account)
+                .ok_or_else(|| ExampleError::Parse(format!("token account {address}")))?;
+            tokens.insert(address, row);
+        }
+    }
+    Ok(tokens)
+}
+
+async fn fetch_transactions(
+    connection: &RpcClient,
+    wallet: &Pubkey,
+) -> Result<VecDeque<TransactionRow>> {
+    let statuses = connection
+        .get_signatures_for_address_with_config(
+            wallet,
+            GetConfirmedSignaturesForAddress2Config {
+                limit: Some(RECENT_TRANSACTIONS),
+                ..GetConfirmedSignaturesForAddress2Config::default()
+            },
+        )
+        .await?;
+    Ok(statuses
+        .into_iter()
+        .map(|status| TransactionRow {
+            signature: status.signature,
+            slot: status.slot,
+            failed: status.err.is_some(),
+        })
+        .collect())
+}
+
+impl Dashboard {
+    fn apply(&mut self, update: Update) {
+        match update {
+            Update::Lamports(lamports) => self.lamports = Some(lamports),
+            Update::Token(address, row) => {
+                self.tokens.insert(address, row);
+            }
+            Update::Transaction(row) => {
+                // logsSubscribe can report a transaction once per commitment
+                // level it passes; keep the first.
+                if self
+                    .transactions
+                    .iter()
+                    .any(|seen| seen.signature == row.signature)
+                {
+                    return;
+                }
+                self.transactions.push_front(row);
+                self.transactions.truncate(RECENT_TRANSACTIONS);
+            }
+            Update::Slot(slot) => self.slot = Some(slot),
+            Update::Tps(tps) => self.tps = Some(tps),
+            Update::Status(status) if status.is_empty() => {}
+            Update::Status(status) => self.status = status,
+            Update::Quit => {}
+        }
+    }
+
+    fn render(&self, frame: &mut Frame) {
+        let [header, body, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+        let [balances, transactions] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(body);
+
+        let slot = self
+            .slot
+            .map_or_else(|| "-".to_string(), |slot| slot.to_string());
+        let tps = self
+            .tps
+            .map_or_else(|| "-".to_string(), |tps| format!("{tps:.0}"));
+        frame.render_widget(
+            Paragraph::new(Line::from(vec![
+                "Slot ".into(),
+                slot.bold(),
+                "   TPS ".into(),
+                tps.bold(),
+                format!("   {}", self.endpoint).dark_gray(),
+            ]))
+            .block(Block::bordered().title(format!(" {} ", self.wallet))),
+            header,
+        );
+
+        let sol = self
+            .lamports
+            .map_or_else(|| "-".to_string(), common::units::format_sol);
+        let rows = std::iter::once(Row::new(vec![
+            Cell::from("SOL"),
+            Cell::from(sol).bold(),
+            Cell::from(""),
+        ]))
+        .chain(self.tokens.values().map(|row| {
+            Row::new(vec![
+                Cell::from(row.mint.clone()),
+                Cell::from(row.ui_amount.clone()),
+                Cell::from(row.program).dark_gray(),
+            ])
+        }));
+        frame.render_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Min(44),
+                    Constraint::Length(20),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(Row::new(vec!["Asset", "Balance", "Program"]).bold())
+            .block(Block::bordered().title(" Balances ")),
+            balances,
+        );
+
+        let rows = self.transactions.iter().map(|row| {
+            let (status, style) = if row.failed {
+                ("failed", Style::new().fg(Color::Red))
+            } else {
+                ("ok", Style::new().fg(Color::Green))
+            };
+            Row::new(vec![
+                Cell::from(row.slot.to_string()),
+                Cell::from(status).style(style),
+                Cell::from(row.signature.clone()),
+            ])
+        });
+        frame.render_widget(
+            Table::new(
+                rows,
+                [
+                    Constraint::Length(10),
+                    Constraint::Length(6),
+                    Constraint::Min(20),
+                ],
+            )
+            .header(Row::new(vec!["Slot", "Status", "Signature"]).bold())
+            .block(Block::bordered().title(" Recent transactions ")),
+            transactions,
+        );
+
+        frame.render_widget(
+            Paragraph::new(format!("{}   q to quit", self.status)).dark_gray(),
+            footer,
+        );
+    }
+}
@@ -0,0 +1,90 @@
+//! The token balance checker of `check-token-account-balance`, compiled to
+//! WebAssembly and run in a browser page.
+//!
+//! Build it with [wasm-pack] and serve this directory:
+//!
+//! ```text
+//! wasm-pack build --target web
+//! python3 -m http.server 8080
+//! ```
+//!
+//! then open <http://localhost:8080> and enter an RPC endpoint and a token
+//! account, or `wallet:mint` for the wallet's associated token account. The
+//! page only reads, so no keypair is involved; everything goes through
+//! [`wasm_rpc`] and the browser's `fetch`.
+//!
+//! [wasm-pack]: https://rustwasm.github.io/wasm-pack/
+
+use serde::Serialize;
+use solana_pubkey::Pubkey;
+use wasm_bindgen::prelude::*;
+use wasm_rpc::pda::associated_token_address;
+use wasm_rpc::{Error, Result, RpcClient, token};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BalanceReport {
+    account: String,
+    owner: String,
+    mint: String,
+    /// UI amount as computed by the node
+    balance: String,
+    /// Base units
+    amount: String,
+    decimals: u8,
+    program: &'static str,
+    extensions: Vec<String>,
+    /// SOL held by the account itself, its rent
+    lamports: u64,
+}
+
+/// Looks up a token account and resolves to its balance report as a JSON
+/// string. `account` is a token account address or `wallet:mint`.
+#[wasm_bindgen(js_name = tokenBalance)]
+pub async fn token_balance(rpc_url: String, account: String) -> Result<String, JsError> {
+    let client = RpcClient::new(rpc_url);
+    let address = resolve(&client, account.trim()).await?;
+    let account = client
+        .get_account(&address)
+        .await?
+        .ok_or(Error::AccountNotFound(address))?;
+    let info = token::TokenAccountInfo::unpack(&address, &account)?;
+    // The node applies extensions such as interest-bearing rates when
+    // computing the UI amount, so ask it rather than formatting locally.
+    let balance = client.get_token_account_balance(&address).await?;
+    let report = BalanceReport {
+        account: address.to_string(),
+        owner: info.account.owner.to_string(),
+        mint: info.account.mint.to_string(),
+        balance: balance.ui_amount_string,
+        amount: balance.amount,
+        decimals: balance.decimals,
+        program: token::program_name(&info.program_id),
+        extensions: info
+            .extensions
+            .iter()
+            .map(|ext| format!("{ext:?}"))
+            .collect(),
+        lamports: account.lamports,
+    };
+    Ok(serde_json::to_string(&report)?)
+}
+
+/// The token account `input` names: an address, or `wallet:mint` for the
+/// associated token account under the mint's program.
+async fn resolve(client: &RpcClient, input: &str) -> Result<Pubkey> {
+    let parse = |key: &str| {
+        key.parse::<Pubkey>()
+            .map_err(|_| Error::InvalidArgument(format!("`{key}` is not a base58 address")))
+    };
+    let Some((wallet, mint)) = input.split_once(':') else {
+        return parse(input);
+    };
+    let (wallet, mint) = (parse(wallet)?, parse(mint)?);
+    let mint_account = client
+        .get_account(&mint)
+        .await?
+        .ok_or(Error::AccountNotFound(mint))?;
+    let program_id = token::token_program_of(&mint, &mint_account)?;
+    Ok(associated_token_address(&wallet, &mint, &program_id))
+}
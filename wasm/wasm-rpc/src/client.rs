@@ -0,0 +1,147 @@
+//! Just enough of the Solana JSON-RPC API for read-only lookups.
+//!
+//! In a browser the endpoint must accept requests from the page's origin;
+//! QuickNode endpoints do.
+
+use crate::error::{Error, Result};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use solana_pubkey::Pubkey;
+
+pub struct RpcClient {
+    http: reqwest::Client,
+    url: String,
+    commitment: String,
+}
+
+/// An account as `getAccountInfo` returns it, the data decoded.
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+    pub executable: bool,
+}
+
+/// A token amount as the node formats it, extensions such as interest
+/// applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAmount {
+    /// Base units
+    pub amount: String,
+    pub decimals: u8,
+    pub ui_amount_string: String,
+}
+
+#[derive(Deserialize)]
+struct Response<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct WithContext<T> {
+    value: T,
+}
+
+#[derive(Deserialize)]
+struct UiAccount {
+    lamports: u64,
+    owner: String,
+    /// `[data, "base64"]`
+    data: (String, String),
+    executable: bool,
+}
+
+impl RpcClient {
+    /// A client reading at `confirmed` commitment.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self::new_with_commitment(url, "confirmed")
+    }
+
+    /// `commitment` is `processed`, `confirmed` or `finalized`.
+    pub fn new_with_commitment(url: impl Into<String>, commitment: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url: url.into(),
+            commitment: commitment.to_string(),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Calls `method` and decodes its `result`.
+    pub async fn request<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let body = json!({"jsonrpc": "2.0", "id": 1, "method": method, "params": params});
+        let response: Response<T> = self
+            .http
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match (response.result, response.error) {
+            (_, Some(error)) => Err(Error::Rpc {
+                code: error.code,
+                message: error.message,
+            }),
+            (Some(result), None) => Ok(result),
+            (None, None) => Err(Error::Parse(format!("{method} response without a result"))),
+        }
+    }
+
+    pub async fn get_balance(&self, address: &Pubkey) -> Result<u64> {
+        let params = json!([address.to_string(), {"commitment": self.commitment}]);
+        let balance: WithContext<u64> = self.request("getBalance", params).await?;
+        Ok(balance.value)
+    }
+
+    /// The account, or `None` if it does not exist.
+    pub async fn get_account(&self, address: &Pubkey) -> Result<Option<Account>> {
+        let params = json!([
+            address.to_string(),
+            {"commitment": self.commitment, "encoding": "base64"}
+        ]);
+        let account: WithContext<Option<UiAccount>> =
+            self.request("getAccountInfo", params).await?;
+        account
+            .value
+            .map(|account| {
+                let owner = account
+                    .owner
+                    .parse()
+                    .map_err(|_| Error::Parse(format!("owner of {address}")))?;
+                let data = BASE64
+                    .decode(&account.data.0)
+                    .map_err(|err| Error::Parse(format!("data of {address}: {err}")))?;
+                Ok(Account {
+                    lamports: account.lamports,
+                    owner,
+                    data,
+                    executable: account.executable,
+                })
+            })
+            .transpose()
+    }
+
+    pub async fn get_token_account_balance(&self, address: &Pubkey) -> Result<TokenAmount> {
+        let params = json!([address.to_string(), {"commitment": self.commitment}]);
+        let balance: WithContext<TokenAmount> =
+            self.request("getTokenAccountBalance", params).await?;
+        Ok(balance.value)
+    }
+}
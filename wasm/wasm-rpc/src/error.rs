@@ -0,0 +1,25 @@
+use solana_pubkey::Pubkey;
+use thiserror::Error;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The request got no response: network, HTTP or, in a browser, CORS.
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The node answered with a JSON-RPC error.
+    #[error("RPC error {code}: {message}")]
+    Rpc { code: i64, message: String },
+
+    /// Data returned by the node could not be decoded.
+    #[error("failed to parse {0}")]
+    Parse(String),
+
+    #[error("account {0} not found")]
+    AccountNotFound(Pubkey),
+
+    #[error("invalid argument: {0}")]
+    InvalidArgument(String),
+}
@@ -0,0 +1,16 @@
+//! The read-only helpers of the examples, cut down to what builds for
+//! `wasm32-unknown-unknown`: JSON-RPC calls, token account decoding and PDA
+//! derivation.
+//!
+//! `common` talks to the node through `solana-client`, whose transports need
+//! threads and sockets a browser does not have. [`RpcClient`] here posts the
+//! JSON-RPC requests itself with `reqwest`, which on wasm32 sends them with
+//! the browser's `fetch`; natively it is an ordinary async client.
+
+pub mod client;
+pub mod error;
+pub mod pda;
+pub mod token;
+
+pub use client::RpcClient;
+pub use error::{Error, Result};
@@ -0,0 +1,61 @@
+//! Token account decoding for both the legacy Token program and Token-2022,
+//! as in `common::token`.
+
+use crate::client::Account;
+use crate::error::{Error, Result};
+use solana_pubkey::{Pubkey, pubkey};
+use spl_token_2022::extension::{BaseStateWithExtensions, ExtensionType, StateWithExtensions};
+use spl_token_2022::state::Account as TokenAccount;
+
+pub const TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+/// Both token programs, legacy first.
+pub const TOKEN_PROGRAMS: [Pubkey; 2] = [TOKEN_PROGRAM_ID, spl_token_2022::ID];
+
+/// Short human-readable name of a token program.
+pub fn program_name(program_id: &Pubkey) -> &'static str {
+    if *program_id == spl_token_2022::ID {
+        "Token-2022"
+    } else if *program_id == TOKEN_PROGRAM_ID {
+        "Token"
+    } else {
+        "unknown"
+    }
+}
+
+/// Returns the owning token program of `account`, failing for accounts owned
+/// by anything else.
+pub fn token_program_of(address: &Pubkey, account: &Account) -> Result<Pubkey> {
+    if TOKEN_PROGRAMS.contains(&account.owner) {
+        Ok(account.owner)
+    } else {
+        Err(Error::InvalidArgument(format!(
+            "{address} is owned by {}, not a token program",
+            account.owner
+        )))
+    }
+}
+
+/// A decoded token account and the extensions it carries.
+#[derive(Debug, Clone)]
+pub struct TokenAccountInfo {
+    pub program_id: Pubkey,
+    pub account: TokenAccount,
+    pub extensions: Vec<ExtensionType>,
+}
+
+impl TokenAccountInfo {
+    pub fn unpack(address: &Pubkey, account: &Account) -> Result<Self> {
+        let program_id = token_program_of(address, account)?;
+        let state = StateWithExtensions::<TokenAccount>::unpack(&account.data)
+            .map_err(|err| Error::Parse(format!("token account {address}: {err}")))?;
+        let extensions = state
+            .get_extension_types()
+            .map_err(|err| Error::Parse(format!("extensions of {address}: {err}")))?;
+        Ok(Self {
+            program_id,
+            account: state.base,
+            extensions,
+        })
+    }
+}
@@ -0,0 +1,290 @@
+use axum::Router;
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use clap::Parser;
+use common::output::{OutputArgs, RecordWriter};
+use common::units::format_token_amount;
+use common::{ExampleError, Result};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use solana_transaction_status_client_types::{
+    EncodedTransaction, UiConfirmedBlock, UiInstruction, UiMessage, UiParsedInstruction,
+};
+use std::net::SocketAddr;
+use std::process::ExitCode;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::net::TcpListener;
+use tokio::signal;
+use tracing::{error, warn};
+
+/// Receive QuickNode Streams (or webhook) deliveries of Solana blocks over
+/// HTTP, verify their HMAC signature and log the SOL and token transfers
+/// they contain.
+///
+/// Point a Streams destination at `http://<host>:<port>/webhook` with the
+/// Solana `block` dataset, payload compression off, and the stream's
+/// security token passed as --secret.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// Address to listen on
+    #[arg(short, long, default_value = "0.0.0.0:3000")]
+    listen: SocketAddr,
+
+    /// The destination's security token; without it signatures are not
+    /// checked, which is only sensible for local testing
+    #[arg(short, long, env = "QN_STREAMS_SECRET")]
+    secret: Option<String>,
+
+    /// Reject deliveries whose timestamp is further than this many seconds
+    /// from the local clock, so captured requests cannot be replayed
+    #[arg(long, default_value_t = 300)]
+    max_age: u64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+struct AppState {
+    secret: Option<String>,
+    max_age: u64,
+    output: OutputArgs,
+    /// Deliveries are handled concurrently; the lock keeps their records
+    /// from interleaving.
+    records: Mutex<RecordWriter>,
+}
+
+/// A delivery holds one block or, with batching, several; some destination
+/// types wrap them in a `data` field.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Payload {
+    Wrapped { data: Vec<UiConfirmedBlock> },
+    Batch(Vec<UiConfirmedBlock>),
+    Block(Box<UiConfirmedBlock>),
+}
+
+impl Payload {
+    fn into_blocks(self) -> Vec<UiConfirmedBlock> {
+        match self {
+            Self::Wrapped { data } | Self::Batch(data) => data,
+            Self::Block(block) => vec![*block],
+        }
+    }
+}
+
+/// A SOL or token transfer found in a parsed instruction.
+#[derive(Debug, Serialize)]
+struct Transfer {
+    signature: String,
+    program: String,
+    source: String,
+    destination: String,
+    /// Lamports for SOL transfers, base units for token transfers
+    amount: u64,
+    /// The mint's decimals, which only checked token transfers carry
+    decimals: Option<u8>,
+    mint: Option<String>,
+}
+
+/// A transfer together with the block it was delivered in.
+#[derive(Serialize)]
+struct TransferRecord<'a> {
+    blockhash: &'a str,
+    #[serde(flatten)]
+    transfer: &'a Transfer,
+}
+
+fn main() -> ExitCode {
+    common::report(run(common::config::parse()))
+}
+
+#[tokio::main]
+async fn run(args: Args) -> Result<()> {
+    let output = args.output.clone();
+    if args.secret.is_none() {
+        warn!("no --secret given, accepting unsigned deliveries");
+    }
+    let state = Arc::new(AppState {
+        secret: args.secret,
+        max_age: args.max_age,
+        records: Mutex::new(args.output.records()),
+        output: args.output,
+    });
+    let app = Router::new()
+        .route("/webhook", post(receive))
+        .with_state(state);
+
+    let listener = TcpListener::bind(args.listen).await?;
+    output.status(format_args!("Listening on http://{}/webhook", args.listen));
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            let _ = signal::ctrl_c().await;
+            output.status("Shutting down");
+        })
+        .await?;
+    Ok(())
+}
+
+async fn receive(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> StatusCode {
+    if let Some(secret) = &state.secret
+        && let Err(err) = verify_signature(secret, state.max_age, &headers, &body)
+    {
+        warn!("rejected delivery: {err}");
+        return StatusCode::UNAUTHORIZED;
+    }
+    let payload: Payload = match serde_json::from_slice(&body) {
+        Ok(payload) => payload,
+        Err(err) => {
+            warn!("could not parse delivery: {err}");
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+    let mut records = state.records.lock().unwrap_or_else(|err| err.into_inner());
+    for block in payload.into_blocks() {
+        let transfers = block_transfers(&block);
+        state.output.status(format_args!(
+            "block {} ({} transactions, {} transfers)",
+            block.blockhash,
+            block.transactions.as_ref().map_or(0, Vec::len),
+            transfers.len()
+        ));
+        for transfer in &transfers {
+            let record = TransferRecord {
+                blockhash: &block.blockhash,
+                transfer,
+            };
+            let written = records.write(&record, || print_transfer(transfer));
+            if let Err(err) = written {
+                error!("could not write transfer: {err}");
+            }
+        }
+    }
+    // Any 2xx tells Streams the delivery succeeded; errors are retried.
+    StatusCode::OK
+}
+
+fn print_transfer(transfer: &Transfer) {
+    println!(
+        "  {} {} {} -> {} amount {}{}",
+        transfer.signature,
+        transfer.program,
+        transfer.source,
+        transfer.destination,
+        match transfer.decimals {
+            Some(decimals) => format_token_amount(transfer.amount, decimals),
+            None => transfer.amount.to_string(),
+        },
+        transfer
+            .mint
+            .as_ref()
+            .map(|mint| format!(" of {mint}"))
+            .unwrap_or_default()
+    );
+}
+
+/// Streams signs `nonce + timestamp + body` with HMAC-SHA256 keyed by the
+/// security token and sends the hex digest in `x-qn-signature`.
+fn verify_signature(secret: &str, max_age: u64, headers: &HeaderMap, body: &[u8]) -> Result<()> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .ok_or_else(|| ExampleError::InvalidArgument(format!("missing {name} header")))
+    };
+    let nonce = header("x-qn-nonce")?;
+    let timestamp = header("x-qn-timestamp")?;
+    let signature = hex::decode(header("x-qn-signature")?)
+        .map_err(|_| ExampleError::InvalidArgument("signature is not hex".to_string()))?;
+
+    let sent_at: u64 = timestamp
+        .parse()
+        .map_err(|_| ExampleError::InvalidArgument(format!("bad timestamp {timestamp}")))?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    if now.abs_diff(sent_at) > max_age {
+        return Err(ExampleError::InvalidArgument(format!(
+            "timestamp {sent_at} is more than {max_age}s away from now"
+        )));
+    }
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .map_err(|err| ExampleError::InvalidArgument(err.to_string()))?;
+    mac.update(nonce.as_bytes());
+    mac.update(timestamp.as_bytes());
+    mac.update(body);
+    // verify_slice compares in constant time.
+    mac.verify_slice(&signature)
+        .map_err(|_| ExampleError::InvalidArgument("signature mismatch".to_string()))
+}
+
+/// Transfers among a block's top-level instructions. Blocks must be in
+/// `jsonParsed` form, which is what the Streams block dataset delivers.
+fn block_transfers(block: &UiConfirmedBlock) -> Vec<Transfer> {
+    let mut transfers = Vec::new();
+    for transaction in block.transactions.iter().flatten() {
+        if transaction
+            .meta
+            .as_ref()
+            .is_some_and(|meta| meta.err.is_some())
+        {
+            continue;
+        }
+        let EncodedTransaction::Json(ui_transaction) = &transaction.transaction else {
+            continue;
+        };
+        let UiMessage::Parsed(message) = &ui_transaction.message else {
+            continue;
+        };
+        let signature = ui_transaction
+            .signatures
+            .first()
+            .cloned()
+            .unwrap_or_default();
+        for instruction in &message.instructions {
+            let UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) = instruction else {
+                continue;
+            };
+            let kind = parsed.parsed.get("type").and_then(Value::as_str);
+            let info = &parsed.parsed["info"];
+            let field = |name: &str| info[name].as_str().unwrap_or_default().to_string();
+            let raw = |value: &Value| value.as_str().and_then(|amount| amount.parse().ok());
+            // The parser names the program `spl-token` or `spl-token-2022`.
+            let (amount, decimals, mint) = match (parsed.program.as_str(), kind) {
+                ("system", Some("transfer")) => (info["lamports"].as_u64(), None, None),
+                (program, Some("transfer")) if program.starts_with("spl-token") => {
+                    (raw(&info["amount"]), None, None)
+                }
+                (program, Some("transferChecked")) if program.starts_with("spl-token") => (
+                    raw(&info["tokenAmount"]["amount"]),
+                    info["tokenAmount"]["decimals"]
+                        .as_u64()
+                        .and_then(|decimals| u8::try_from(decimals).ok()),
+                    Some(field("mint")),
+                ),
+                _ => continue,
+            };
+            let Some(amount) = amount else { continue };
+            transfers.push(Transfer {
+                signature: signature.clone(),
+                program: parsed.program.clone(),
+                source: field("source"),
+                destination: field("destination"),
+                amount,
+                decimals,
+                mint,
+            });
+        }
+    }
+    transfers
+}
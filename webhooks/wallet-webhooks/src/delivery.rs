@@ -0,0 +1,196 @@
+//! Signed POSTs to the webhook, with a queue of deliveries to retry.
+//!
+//! Every request carries the event id in `x-webhook-id`, the Unix time it
+//! was sent in `x-webhook-timestamp` and, with a secret, the hex HMAC-SHA256
+//! of the id, the timestamp and the body, in that order, in
+//! `x-webhook-signature`: the scheme of QuickNode Streams, with the event id
+//! as the nonce.
+
+use crate::events::WalletEvent;
+use common::retry::RetryPolicy;
+use common::{ExampleError, Result};
+use hmac::{Hmac, Mac};
+use reqwest::StatusCode;
+use sha2::Sha256;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::time::Instant;
+
+pub struct Webhook {
+    client: reqwest::Client,
+    url: String,
+    secret: Option<String>,
+}
+
+/// How a POST went.
+pub enum Outcome {
+    Delivered(StatusCode),
+    /// A network error, a 5xx or a 429: worth trying again
+    Failed(String),
+    /// Any other status; the receiver will not take the event as it is
+    Rejected(StatusCode),
+}
+
+impl Webhook {
+    pub fn new(url: String, secret: Option<String>, timeout: Duration) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|err| ExampleError::Config(format!("HTTP client: {err}")))?;
+        Ok(Self {
+            client,
+            url,
+            secret,
+        })
+    }
+
+    pub async fn post(&self, id: &str, body: &[u8]) -> Outcome {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs())
+            .to_string();
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("content-type", "application/json")
+            .header("x-webhook-id", id)
+            .header("x-webhook-timestamp", &timestamp);
+        if let Some(secret) = &self.secret {
+            request = request.header("x-webhook-signature", sign(secret, id, &timestamp, body));
+        }
+        match request.body(body.to_vec()).send().await {
+            Ok(response) => {
+                let status = response.status();
+                if status.is_success() {
+                    Outcome::Delivered(status)
+                } else if status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS {
+                    Outcome::Failed(format!("HTTP {status}"))
+                } else {
+                    Outcome::Rejected(status)
+                }
+            }
+            Err(err) => Outcome::Failed(err.to_string()),
+        }
+    }
+}
+
+fn sign(secret: &str, id: &str, timestamp: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any length");
+    mac.update(id.as_bytes());
+    mac.update(timestamp.as_bytes());
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+pub struct Delivery {
+    pub event: WalletEvent,
+    pub body: Vec<u8>,
+    /// POSTs made so far
+    pub attempts: u32,
+    due: Instant,
+}
+
+/// Deliveries waiting for their turn, in the order they are due. Failed ones
+/// are put back with the policy's backoff; when the queue is full the
+/// oldest one is dropped to make room.
+pub struct Queue {
+    pending: VecDeque<Delivery>,
+    capacity: usize,
+    policy: RetryPolicy,
+}
+
+impl Queue {
+    pub fn new(capacity: usize, policy: RetryPolicy) -> Self {
+        Self {
+            pending: VecDeque::new(),
+            capacity: capacity.max(1),
+            policy,
+        }
+    }
+
+    /// Queues `event` for delivery now, returning the delivery it pushed out
+    /// of a full queue.
+    pub fn push(&mut self, event: WalletEvent) -> Result<Option<Delivery>> {
+        let body = serde_json::to_vec(&event)
+            .map_err(|err| ExampleError::Parse(format!("event {}: {err}", event.id)))?;
+        let dropped = self.make_room();
+        self.insert(Delivery {
+            event,
+            body,
+            attempts: 0,
+            due: Instant::now(),
+        });
+        Ok(dropped)
+    }
+
+    /// Whether a failed delivery has retries left.
+    pub fn can_retry(&self, delivery: &Delivery) -> bool {
+        delivery.attempts <= self.policy.max_retries
+    }
+
+    /// Puts a failed delivery back after its backoff, returning the delivery
+    /// it pushed out of a full queue.
+    pub fn retry(&mut self, mut delivery: Delivery) -> Option<Delivery> {
+        delivery.due = Instant::now() + self.policy.jittered_delay(delivery.attempts - 1);
+        let dropped = self.make_room();
+        self.insert(delivery);
+        dropped
+    }
+
+    /// When the first delivery is due.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.pending.front().map(|delivery| delivery.due)
+    }
+
+    pub fn pop_due(&mut self) -> Option<Delivery> {
+        if self.next_due()? <= Instant::now() {
+            self.pending.pop_front()
+        } else {
+            None
+        }
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = Delivery> + '_ {
+        self.pending.drain(..)
+    }
+
+    fn make_room(&mut self) -> Option<Delivery> {
+        if self.pending.len() < self.capacity {
+            return None;
+        }
+        let oldest = (0..self.pending.len())
+            .min_by_key(|&index| self.pending[index].event.slot)
+            .expect("a full queue is not empty");
+        self.pending.remove(oldest)
+    }
+
+    fn insert(&mut self, delivery: Delivery) {
+        let index = self
+            .pending
+            .partition_point(|queued| queued.due <= delivery.due);
+        self.pending.insert(index, delivery);
+    }
+}
+
+/// Appends undeliverable events to a JSON Lines file, one per line, to be
+/// replayed or inspected later.
+pub struct DeadLetters {
+    file: File,
+}
+
+impl DeadLetters {
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+
+    pub fn write(&mut self, delivery: &Delivery) -> Result<()> {
+        self.file.write_all(&delivery.body)?;
+        self.file.write_all(b"\n")?;
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+//! Turning a `jsonParsed` transaction into the events the watched wallets
+//! took part in.
+//!
+//! Transfers are read from the instructions the node parsed, inner ones
+//! included, so a transfer made by a program through CPI counts too. A
+//! transaction that calls one of the [`MARKETPLACES`] and moves an NFT (a
+//! zero-decimal token balance going from 1 to 0 on one side and 0 to 1 on
+//! the other) is reported as a single `nft_sale` instead of the transfers
+//! it is made of.
+
+use common::decode::token_balance_changes;
+use serde::Serialize;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiInstruction, UiMessage,
+    UiParsedInstruction, UiTransactionTokenBalance,
+};
+use std::collections::HashSet;
+
+/// NFT marketplace programs whose transactions are checked for sales.
+pub const MARKETPLACES: &[(Pubkey, &str)] = &[
+    (
+        pubkey!("M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K"),
+        "Magic Eden",
+    ),
+    (
+        pubkey!("TCMPhJdwDryooaGtiocG1u3xcYbRpiJzb283XfCZsDp"),
+        "Tensor",
+    ),
+    (
+        pubkey!("TSWAPaqyCSx2KABk68Shruf4rp7CxcNi8hAsbdwmHbN"),
+        "Tensor Swap",
+    ),
+    (
+        pubkey!("hausS13jsjafwWwGqZTUQRmWyvyxn9EQpqMwV1PBBmk"),
+        "Metaplex Auction House",
+    ),
+];
+
+/// What the webhook receives, one per POST.
+#[derive(Debug, Clone, Serialize)]
+pub struct WalletEvent {
+    /// `<signature>:<index>`, the same on every retry, for deduplication
+    pub id: String,
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    /// The watched wallets the event involves
+    pub wallets: Vec<String>,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    SolTransfer {
+        from: String,
+        to: String,
+        lamports: u64,
+    },
+    TokenTransfer {
+        mint: Option<String>,
+        /// Owners of the token accounts, where the transaction names them
+        from: Option<String>,
+        to: Option<String>,
+        source_account: String,
+        destination_account: String,
+        /// Base units
+        amount: u64,
+        decimals: Option<u8>,
+    },
+    NftSale {
+        marketplace: &'static str,
+        mint: String,
+        seller: Option<String>,
+        buyer: Option<String>,
+        /// Lamports the buyer spent, which includes marketplace fees,
+        /// royalties and the rent of a new token account; `None` when the
+        /// buyer is not in the transaction's account list
+        price: Option<u64>,
+    },
+}
+
+impl EventKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::SolTransfer { .. } => "sol_transfer",
+            Self::TokenTransfer { .. } => "token_transfer",
+            Self::NftSale { .. } => "nft_sale",
+        }
+    }
+}
+
+/// The events of `transaction` involving any of `wallets`. Failed
+/// transactions have none.
+pub fn wallet_events(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    wallets: &HashSet<String>,
+) -> Vec<WalletEvent> {
+    let EncodedTransaction::Json(ui_transaction) = &transaction.transaction.transaction else {
+        return Vec::new();
+    };
+    let UiMessage::Parsed(message) = &ui_transaction.message else {
+        return Vec::new();
+    };
+    let (Some(meta), Some(signature)) = (
+        &transaction.transaction.meta,
+        ui_transaction.signatures.first(),
+    ) else {
+        return Vec::new();
+    };
+    if meta.err.is_some() {
+        return Vec::new();
+    }
+    let keys: Vec<&str> = message
+        .account_keys
+        .iter()
+        .map(|key| key.pubkey.as_str())
+        .collect();
+    let watched =
+        |owner: &Option<String>| owner.as_ref().is_some_and(|owner| wallets.contains(owner));
+
+    let mut kinds = Vec::new();
+    let marketplace = MARKETPLACES.iter().find(|(program_id, _)| {
+        let program_id = program_id.to_string();
+        keys.contains(&program_id.as_str())
+    });
+    if let Some((_, marketplace)) = marketplace {
+        let nfts: Vec<_> = token_balance_changes(meta)
+            .into_iter()
+            .filter(|change| change.decimals == 0 && change.before.max(change.after) == 1)
+            .collect();
+        for sold in nfts.iter().filter(|change| change.before == 1) {
+            let bought = nfts
+                .iter()
+                .find(|change| change.after == 1 && change.mint == sold.mint);
+            let buyer = bought.and_then(|change| change.owner.clone());
+            kinds.push(EventKind::NftSale {
+                marketplace,
+                mint: sold.mint.clone(),
+                seller: sold.owner.clone(),
+                price: buyer
+                    .as_deref()
+                    .and_then(|buyer| keys.iter().position(|key| *key == buyer))
+                    .and_then(|index| {
+                        let spent = meta
+                            .pre_balances
+                            .get(index)?
+                            .checked_sub(*meta.post_balances.get(index)?)?;
+                        // The fee payer is the first account.
+                        Some(if index == 0 {
+                            spent.saturating_sub(meta.fee)
+                        } else {
+                            spent
+                        })
+                    }),
+                buyer,
+            });
+        }
+    }
+    // A sale's own transfers are summed up by the sale.
+    if kinds.is_empty() {
+        kinds = transfers(ui_transaction_instructions(message, meta), meta, &keys);
+    }
+
+    kinds
+        .into_iter()
+        .filter_map(|kind| {
+            let involved: Vec<String> = match &kind {
+                EventKind::SolTransfer { from, to, .. } => {
+                    vec![Some(from.clone()), Some(to.clone())]
+                }
+                EventKind::TokenTransfer { from, to, .. } => vec![from.clone(), to.clone()],
+                EventKind::NftSale { seller, buyer, .. } => vec![seller.clone(), buyer.clone()],
+            }
+            .into_iter()
+            .filter(|wallet| watched(wallet))
+            .flatten()
+            .collect();
+            (!involved.is_empty()).then_some((involved, kind))
+        })
+        .enumerate()
+        .map(|(index, (mut involved, kind))| {
+            involved.dedup();
+            WalletEvent {
+                id: format!("{signature}:{index}"),
+                signature: signature.clone(),
+                slot: transaction.slot,
+                block_time: transaction.block_time,
+                wallets: involved,
+                kind,
+            }
+        })
+        .collect()
+}
+
+/// Top-level instructions followed by the inner ones.
+fn ui_transaction_instructions<'a>(
+    message: &'a solana_transaction_status_client_types::UiParsedMessage,
+    meta: &'a solana_transaction_status_client_types::UiTransactionStatusMeta,
+) -> Vec<&'a UiInstruction> {
+    let mut instructions: Vec<&UiInstruction> = message.instructions.iter().collect();
+    if let OptionSerializer::Some(inner) = &meta.inner_instructions {
+        instructions.extend(inner.iter().flat_map(|group| &group.instructions));
+    }
+    instructions
+}
+
+/// SOL and token transfers among `instructions`. Token account owners and
+/// mints the instruction does not name are looked up in the transaction's
+/// token balances.
+fn transfers(
+    instructions: Vec<&UiInstruction>,
+    meta: &solana_transaction_status_client_types::UiTransactionStatusMeta,
+    keys: &[&str],
+) -> Vec<EventKind> {
+    let balances: Vec<&UiTransactionTokenBalance> =
+        [&meta.pre_token_balances, &meta.post_token_balances]
+            .into_iter()
+            .filter_map(|balances| match balances {
+                OptionSerializer::Some(balances) => Some(balances),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+    let balance_of = |account: &str| {
+        let index = keys.iter().position(|key| *key == account)?;
+        balances
+            .iter()
+            .find(|balance| usize::from(balance.account_index) == index)
+    };
+    let owner_of = |account: &str| match &balance_of(account)?.owner {
+        OptionSerializer::Some(owner) => Some(owner.clone()),
+        _ => None,
+    };
+
+    instructions
+        .into_iter()
+        .filter_map(|instruction| {
+            let UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) = instruction else {
+                return None;
+            };
+            let kind = parsed.parsed.get("type")?.as_str()?;
+            let info = parsed.parsed.get("info")?;
+            let field = |name: &str| info.get(name).and_then(|value| value.as_str());
+            match (parsed.program.as_str(), kind) {
+                ("system", "transfer" | "transferWithSeed") => Some(EventKind::SolTransfer {
+                    from: field("source")?.to_string(),
+                    to: field("destination")?.to_string(),
+                    lamports: info.get("lamports")?.as_u64()?,
+                }),
+                (program, "transfer" | "transferChecked") if program.starts_with("spl-token") => {
+                    let source = field("source")?;
+                    let destination = field("destination")?;
+                    let amount = if kind == "transferChecked" {
+                        info.get("tokenAmount")?.get("amount")?.as_str()?
+                    } else {
+                        field("amount")?
+                    };
+                    let balance = balance_of(source).or_else(|| balance_of(destination));
+                    Some(EventKind::TokenTransfer {
+                        mint: field("mint")
+                            .map(str::to_string)
+                            .or_else(|| balance.map(|balance| balance.mint.clone())),
+                        from: owner_of(source).or_else(|| field("authority").map(str::to_string)),
+                        to: owner_of(destination),
+                        source_account: source.to_string(),
+                        destination_account: destination.to_string(),
+                        amount: amount.parse().ok()?,
+                        decimals: balance.map(|balance| balance.ui_token_amount.decimals),
+                    })
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
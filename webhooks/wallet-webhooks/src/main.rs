@@ -0,0 +1,395 @@
+mod delivery;
+mod events;
+
+use clap::Parser;
+use common::cli::{AddressArg, RpcArgs, WsArgs};
+use common::output::{OutputArgs, RecordWriter};
+use common::retry::RetryPolicy;
+use common::stream::{LogsSource, StreamSource};
+use common::{ExampleError, Result, at_least_confirmed, batch};
+use delivery::{DeadLetters, Delivery, Outcome, Queue, Webhook};
+use events::WalletEvent;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::collections::{HashSet, VecDeque};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::signal;
+use tokio::sync::mpsc;
+use tokio::time::{self, Instant};
+use tracing::{error, warn};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// A notification can arrive before the node serves the transaction.
+const FETCH_ATTEMPTS: u32 = 5;
+const FETCH_DELAY: Duration = Duration::from_secs(1);
+
+/// Signatures remembered to skip a transaction that mentions several of the
+/// wallets, and so is notified once per wallet.
+const SEEN_SIGNATURES: usize = 10_000;
+
+/// Watch wallets over websocket subscriptions and POST what happens to them
+/// to a webhook, as normalized JSON events: `sol_transfer`, `token_transfer`
+/// and `nft_sale`, the latter for transactions of known NFT marketplaces
+/// (Magic Eden, Tensor, Metaplex Auction House).
+///
+/// Each wallet gets a `logsSubscribe` of its own, so keep the list within
+/// the endpoint's websocket limits. Deliveries that fail with a network
+/// error, a 5xx or a 429 are retried with exponential backoff; ones still
+/// failing after --delivery-retries, or rejected with another status, go to
+/// --dead-letter when given.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    /// File with one wallet address per line ('#' starts a comment)
+    #[arg(short, long)]
+    file: Option<PathBuf>,
+
+    /// URL the events are POSTed to
+    #[arg(short = 'w', long, env = "WALLET_WEBHOOK_URL")]
+    webhook_url: String,
+
+    /// Secret the deliveries are signed with, HMAC-SHA256; without it they
+    /// are sent unsigned
+    #[arg(short, long, env = "WALLET_WEBHOOK_SECRET", hide_env_values = true)]
+    secret: Option<String>,
+
+    /// Retries of a failed delivery before it is given up on
+    #[arg(long, default_value_t = 8)]
+    delivery_retries: u32,
+
+    /// Deliveries queued at most; when full the oldest is dropped
+    #[arg(long, default_value_t = 10_000)]
+    queue_size: usize,
+
+    /// JSON Lines file undeliverable events are appended to
+    #[arg(long)]
+    dead_letter: Option<PathBuf>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    /// Wallets, in addition to --file: addresses (base58), `.sol` domains or
+    /// keypair files
+    wallets: Vec<AddressArg>,
+}
+
+/// What became of one event.
+#[derive(Serialize)]
+struct DeliveryRecord {
+    id: String,
+    signature: String,
+    slot: u64,
+    #[serde(rename = "type")]
+    kind: &'static str,
+    wallets: Vec<String>,
+    status: Status,
+    attempts: u32,
+    /// The webhook's response status, if it answered
+    http_status: Option<u16>,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Clone, Copy)]
+#[serde(rename_all = "kebab-case")]
+enum Status {
+    Delivered,
+    Rejected,
+    /// Out of retries
+    GaveUp,
+    /// Pushed out of a full queue
+    Dropped,
+    /// Still queued at shutdown
+    Undelivered,
+}
+
+impl Status {
+    fn label(self) -> &'static str {
+        match self {
+            Status::Delivered => "delivered",
+            Status::Rejected => "rejected",
+            Status::GaveUp => "gave up",
+            Status::Dropped => "dropped",
+            Status::Undelivered => "undelivered",
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    // `AddressArg` resolves with the blocking client; it is one call each.
+    let resolver = args.rpc.client();
+    let mut wallets = args
+        .wallets
+        .iter()
+        .map(|wallet| wallet.resolve(&resolver))
+        .collect::<Result<Vec<_>>>()?;
+    if let Some(file) = &args.file {
+        wallets.extend(batch::read_keys_file(file)?);
+    }
+    let mut seen = HashSet::new();
+    wallets.retain(|wallet| seen.insert(*wallet));
+    if wallets.is_empty() {
+        return Err(ExampleError::InvalidArgument(
+            "pass wallets as arguments or with --file".to_string(),
+        ));
+    }
+    let watched: HashSet<String> = wallets.iter().map(Pubkey::to_string).collect();
+    // getTransaction serves confirmed transactions at the earliest.
+    let commitment = at_least_confirmed(args.rpc.commitment_config());
+    let connection = args.rpc.nonblocking_client();
+    let webhook = Webhook::new(
+        args.webhook_url.clone(),
+        args.secret.clone(),
+        args.rpc.timeout(),
+    )?;
+    let mut queue = Queue::new(
+        args.queue_size,
+        RetryPolicy {
+            max_retries: args.delivery_retries,
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(300),
+            jitter: true,
+        },
+    );
+    let mut dead_letters = args
+        .dead_letter
+        .as_deref()
+        .map(DeadLetters::open)
+        .transpose()?;
+    let mut records = args.output.records();
+
+    let (sender, mut signatures) = mpsc::channel(1024);
+    let ws_url = args.ws.url(&args.rpc);
+    for wallet in &wallets {
+        tokio::spawn(subscribe(
+            ws_url.clone(),
+            *wallet,
+            commitment,
+            sender.clone(),
+        ));
+    }
+    drop(sender);
+    args.output.status(format_args!(
+        "Watching {} wallets, posting to {} (Ctrl-C to stop)",
+        wallets.len(),
+        args.webhook_url
+    ));
+
+    let mut recent = RecentSignatures::default();
+    loop {
+        let next_due = queue.next_due();
+        tokio::select! {
+            signature = signatures.recv() => {
+                let Some(signature) = signature else {
+                    break;
+                };
+                if !recent.insert(signature) {
+                    continue;
+                }
+                let events = match fetch_events(&connection, &signature, commitment, &watched).await {
+                    Ok(events) => events,
+                    Err(err) => {
+                        warn!("{signature}: {err}");
+                        continue;
+                    }
+                };
+                for event in events {
+                    if let Some(dropped) = queue.push(event)? {
+                        let record = record(&dropped, Status::Dropped, None, Some("queue full".to_string()));
+                        write_record(&mut records, &mut dead_letters, &dropped, record)?;
+                    }
+                }
+            }
+            _ = time::sleep_until(next_due.unwrap_or_else(Instant::now)), if next_due.is_some() => {
+                let Some(mut delivery) = queue.pop_due() else {
+                    continue;
+                };
+                delivery.attempts += 1;
+                let (status, http_status, error) = match webhook.post(&delivery.event.id, &delivery.body).await {
+                    Outcome::Delivered(status) => (Status::Delivered, Some(status.as_u16()), None),
+                    Outcome::Rejected(status) => (Status::Rejected, Some(status.as_u16()), None),
+                    Outcome::Failed(error) if queue.can_retry(&delivery) => {
+                        warn!("{}: {error}, retry {}/{}", delivery.event.id, delivery.attempts, args.delivery_retries);
+                        if let Some(dropped) = queue.retry(delivery) {
+                            let record = record(&dropped, Status::Dropped, None, Some("queue full".to_string()));
+                            write_record(&mut records, &mut dead_letters, &dropped, record)?;
+                        }
+                        continue;
+                    }
+                    Outcome::Failed(error) => (Status::GaveUp, None, Some(error)),
+                };
+                let record = record(&delivery, status, http_status, error);
+                write_record(&mut records, &mut dead_letters, &delivery, record)?;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                break;
+            }
+        }
+    }
+
+    let undelivered: Vec<Delivery> = queue.drain().collect();
+    for delivery in &undelivered {
+        let record = record(delivery, Status::Undelivered, None, None);
+        write_record(&mut records, &mut dead_letters, delivery, record)?;
+    }
+    if !undelivered.is_empty() {
+        args.output.status(format_args!(
+            "{} events were still queued{}",
+            undelivered.len(),
+            if dead_letters.is_some() {
+                ", written to the dead-letter file"
+            } else {
+                " and are lost"
+            }
+        ));
+    }
+    Ok(())
+}
+
+/// Forwards the signatures of the wallet's successful transactions,
+/// reconnecting whenever the subscription ends.
+async fn subscribe(
+    ws_url: String,
+    wallet: Pubkey,
+    commitment: CommitmentConfig,
+    sender: mpsc::Sender<Signature>,
+) {
+    loop {
+        match LogsSource::connect(&ws_url, &wallet, commitment).await {
+            Ok(mut source) => loop {
+                match source.next().await {
+                    Ok(Some(event)) => {
+                        if !event.failed && sender.send(event.signature).await.is_err() {
+                            return;
+                        }
+                    }
+                    Ok(None) => {
+                        warn!("{wallet}: stream closed by the server");
+                        break;
+                    }
+                    Err(err) => {
+                        error!("{wallet}: {err}");
+                        break;
+                    }
+                }
+            },
+            Err(err) => error!("{wallet}: {err}"),
+        }
+        time::sleep(RECONNECT_DELAY).await;
+    }
+}
+
+async fn fetch_events(
+    connection: &RpcClient,
+    signature: &Signature,
+    commitment: CommitmentConfig,
+    wallets: &HashSet<String>,
+) -> Result<Vec<WalletEvent>> {
+    let config = RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::JsonParsed),
+        commitment: Some(commitment),
+        max_supported_transaction_version: Some(0),
+    };
+    let mut attempt = 1;
+    loop {
+        match connection
+            .get_transaction_with_config(signature, config)
+            .await
+        {
+            Ok(transaction) => return Ok(events::wallet_events(&transaction, wallets)),
+            Err(_) if attempt < FETCH_ATTEMPTS => {
+                attempt += 1;
+                time::sleep(FETCH_DELAY).await;
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+fn record(
+    delivery: &Delivery,
+    status: Status,
+    http_status: Option<u16>,
+    error: Option<String>,
+) -> DeliveryRecord {
+    DeliveryRecord {
+        id: delivery.event.id.clone(),
+        signature: delivery.event.signature.clone(),
+        slot: delivery.event.slot,
+        kind: delivery.event.kind.name(),
+        wallets: delivery.event.wallets.clone(),
+        status,
+        attempts: delivery.attempts,
+        http_status,
+        error,
+    }
+}
+
+/// Writes the record and, unless the event was delivered, its dead letter.
+fn write_record(
+    records: &mut RecordWriter,
+    dead_letters: &mut Option<DeadLetters>,
+    delivery: &Delivery,
+    record: DeliveryRecord,
+) -> Result<()> {
+    if let (Some(dead_letters), false) = (dead_letters, matches!(record.status, Status::Delivered))
+    {
+        dead_letters.write(delivery)?;
+    }
+    records.write(&record, || {
+        let detail = match (&record.http_status, &record.error) {
+            (_, Some(error)) => format!(" ({error})"),
+            (Some(status), None) => format!(" (HTTP {status})"),
+            (None, None) => String::new(),
+        };
+        println!(
+            "slot {:<10} {:<14} {:<11} {}{detail}",
+            record.slot,
+            record.kind,
+            record.status.label(),
+            record.id
+        );
+    })
+}
+
+/// The last [`SEEN_SIGNATURES`] signatures handled.
+#[derive(Default)]
+struct RecentSignatures {
+    set: HashSet<Signature>,
+    order: VecDeque<Signature>,
+}
+
+impl RecentSignatures {
+    /// Whether `signature` is new.
+    fn insert(&mut self, signature: Signature) -> bool {
+        if !self.set.insert(signature) {
+            return false;
+        }
+        self.order.push_back(signature);
+        if self.order.len() > SEEN_SIGNATURES
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.set.remove(&oldest);
+        }
+        true
+    }
+}
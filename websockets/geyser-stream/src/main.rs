@@ -0,0 +1,302 @@
+use clap::Parser;
+use common::cli::Commitment;
+use common::output::{OutputArgs, RecordWriter};
+use common::units::format_sol;
+use common::{ExampleError, Result};
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::{signal, time};
+use tracing::{error, warn};
+use yellowstone_grpc_client::{ClientTlsConfig, GeyserGrpcClient};
+use yellowstone_grpc_proto::prelude::subscribe_update::UpdateOneof;
+use yellowstone_grpc_proto::prelude::{
+    CommitmentLevel, SubscribeRequest, SubscribeRequestFilterAccounts,
+    SubscribeRequestFilterTransactions, SubscribeRequestPing, SubscribeUpdateAccount,
+    SubscribeUpdateTransaction,
+};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Stream account updates and transactions from a Yellowstone (Geyser) gRPC
+/// endpoint, such as QuickNode's Yellowstone add-on. Accounts are selected
+/// by owner program and transactions by the accounts they touch. After a
+/// disconnect the stream resumes from the last slot it saw, so nothing is
+/// missed as long as the server still holds that slot (updates from that
+/// slot itself are delivered again).
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    /// gRPC endpoint, e.g. https://example.solana-mainnet.quiknode.pro:10000
+    #[arg(short, long, env = "GEYSER_GRPC_URL")]
+    endpoint: String,
+
+    /// Authentication token sent as the `x-token` header
+    #[arg(short = 't', long, env = "GEYSER_X_TOKEN", hide_env_values = true)]
+    x_token: Option<String>,
+
+    /// Stream updates to accounts owned by this program (repeatable)
+    #[arg(short, long)]
+    owner: Vec<Pubkey>,
+
+    /// Stream transactions that mention this account or program (repeatable)
+    #[arg(short, long)]
+    mentions: Vec<Pubkey>,
+
+    /// Include failed transactions
+    #[arg(long)]
+    include_failed: bool,
+
+    #[arg(short, long, value_enum, default_value_t = Commitment::Confirmed)]
+    commitment: Commitment,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// One streamed update. Accounts and transactions share a flat record so
+/// that a CSV stream keeps the same columns throughout.
+#[derive(Serialize, Default)]
+struct StreamUpdate {
+    /// `account` or `transaction`
+    kind: &'static str,
+    slot: u64,
+    /// The transaction, or the one that caused the account update
+    signature: Option<String>,
+    account: Option<String>,
+    owner: Option<String>,
+    lamports: Option<u64>,
+    data_len: Option<usize>,
+    failed: Option<bool>,
+    fee: Option<u64>,
+    static_accounts: Option<usize>,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    if args.owner.is_empty() && args.mentions.is_empty() {
+        return Err(ExampleError::InvalidArgument(
+            "pass at least one --owner or --mentions".to_string(),
+        ));
+    }
+    let mut last_slot = None;
+    let mut records = args.output.records();
+    loop {
+        match watch(&args, &mut last_slot, &mut records).await {
+            Ok(Stop::Shutdown) => return Ok(()),
+            Ok(Stop::Disconnected) => warn!("stream closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        match last_slot {
+            Some(slot) => warn!("reconnecting in {RECONNECT_DELAY:?}, resuming at slot {slot}"),
+            None => warn!("reconnecting in {RECONNECT_DELAY:?}"),
+        }
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+fn grpc_error(err: impl Display) -> ExampleError {
+    ExampleError::Grpc(err.to_string())
+}
+
+fn subscribe_request(args: &Args, from_slot: Option<u64>) -> SubscribeRequest {
+    let commitment = match args.commitment {
+        Commitment::Processed => CommitmentLevel::Processed,
+        Commitment::Confirmed => CommitmentLevel::Confirmed,
+        Commitment::Finalized => CommitmentLevel::Finalized,
+    };
+    let mut accounts = HashMap::new();
+    if !args.owner.is_empty() {
+        accounts.insert(
+            "accounts".to_string(),
+            SubscribeRequestFilterAccounts {
+                owner: args.owner.iter().map(Pubkey::to_string).collect(),
+                ..SubscribeRequestFilterAccounts::default()
+            },
+        );
+    }
+    let mut transactions = HashMap::new();
+    if !args.mentions.is_empty() {
+        transactions.insert(
+            "transactions".to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: Some(false),
+                failed: (!args.include_failed).then_some(false),
+                account_include: args.mentions.iter().map(Pubkey::to_string).collect(),
+                ..SubscribeRequestFilterTransactions::default()
+            },
+        );
+    }
+    SubscribeRequest {
+        accounts,
+        transactions,
+        commitment: Some(commitment as i32),
+        from_slot,
+        ..SubscribeRequest::default()
+    }
+}
+
+/// Streams until the server disconnects or Ctrl-C, recording the highest
+/// slot seen in `last_slot`.
+async fn watch(
+    args: &Args,
+    last_slot: &mut Option<u64>,
+    records: &mut RecordWriter,
+) -> Result<Stop> {
+    let mut client = GeyserGrpcClient::build_from_shared(args.endpoint.clone())
+        .map_err(grpc_error)?
+        .x_token(args.x_token.clone())
+        .map_err(grpc_error)?
+        .tls_config(ClientTlsConfig::new().with_native_roots())
+        .map_err(grpc_error)?
+        .connect_timeout(Duration::from_secs(10))
+        .max_decoding_message_size(64 * 1024 * 1024)
+        .connect()
+        .await
+        .map_err(grpc_error)?;
+    let request = subscribe_request(args, *last_slot);
+    let (mut requests, mut updates) = client
+        .subscribe_with_request(Some(request))
+        .await
+        .map_err(grpc_error)?;
+    args.output.status(format_args!(
+        "Connected to {} (Ctrl-C to stop)",
+        args.endpoint
+    ));
+
+    loop {
+        tokio::select! {
+            update = updates.next() => {
+                let Some(update) = update else {
+                    return Ok(Stop::Disconnected);
+                };
+                match update.map_err(grpc_error)?.update_oneof {
+                    Some(UpdateOneof::Account(update)) => {
+                        *last_slot = (*last_slot).max(Some(update.slot));
+                        if let Some(entry) = account_update(&update)? {
+                            records.write(&entry, || print_account(&entry))?;
+                        }
+                    }
+                    Some(UpdateOneof::Transaction(update)) => {
+                        *last_slot = (*last_slot).max(Some(update.slot));
+                        if let Some(entry) = transaction_update(&update)? {
+                            records.write(&entry, || print_transaction(&entry))?;
+                        }
+                    }
+                    // Load balancers drop idle streams; answering the
+                    // server's pings keeps this one open.
+                    Some(UpdateOneof::Ping(_)) => {
+                        requests
+                            .send(SubscribeRequest {
+                                ping: Some(SubscribeRequestPing { id: 1 }),
+                                ..SubscribeRequest::default()
+                            })
+                            .await
+                            .map_err(grpc_error)?;
+                    }
+                    _ => {}
+                }
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}
+
+fn pubkey(bytes: &[u8]) -> Result<Pubkey> {
+    Pubkey::try_from(bytes).map_err(|_| ExampleError::Parse("pubkey in gRPC update".into()))
+}
+
+fn signature(bytes: &[u8]) -> Result<Signature> {
+    Signature::try_from(bytes).map_err(|_| ExampleError::Parse("signature in gRPC update".into()))
+}
+
+fn account_update(update: &SubscribeUpdateAccount) -> Result<Option<StreamUpdate>> {
+    let Some(account) = &update.account else {
+        return Ok(None);
+    };
+    Ok(Some(StreamUpdate {
+        kind: "account",
+        slot: update.slot,
+        signature: account
+            .txn_signature
+            .as_deref()
+            .map(signature)
+            .transpose()?
+            .map(|signature| signature.to_string()),
+        account: Some(pubkey(&account.pubkey)?.to_string()),
+        owner: Some(pubkey(&account.owner)?.to_string()),
+        lamports: Some(account.lamports),
+        data_len: Some(account.data.len()),
+        ..StreamUpdate::default()
+    }))
+}
+
+fn transaction_update(update: &SubscribeUpdateTransaction) -> Result<Option<StreamUpdate>> {
+    let Some(info) = &update.transaction else {
+        return Ok(None);
+    };
+    let accounts = info
+        .transaction
+        .as_ref()
+        .and_then(|transaction| transaction.message.as_ref())
+        .map_or(0, |message| message.account_keys.len());
+    Ok(Some(StreamUpdate {
+        kind: "transaction",
+        slot: update.slot,
+        signature: Some(signature(&info.signature)?.to_string()),
+        failed: Some(info.meta.as_ref().is_some_and(|meta| meta.err.is_some())),
+        fee: Some(info.meta.as_ref().map_or(0, |meta| meta.fee)),
+        static_accounts: Some(accounts),
+        ..StreamUpdate::default()
+    }))
+}
+
+fn print_account(entry: &StreamUpdate) {
+    let cause = match &entry.signature {
+        Some(signature) => format!("tx {signature}"),
+        None => "no transaction".to_string(),
+    };
+    println!(
+        "[account] slot {} {} owner {} {} SOL {} bytes ({cause})",
+        entry.slot,
+        entry.account.as_deref().unwrap_or_default(),
+        entry.owner.as_deref().unwrap_or_default(),
+        format_sol(entry.lamports.unwrap_or_default()),
+        entry.data_len.unwrap_or_default()
+    );
+}
+
+fn print_transaction(entry: &StreamUpdate) {
+    let status = if entry.failed == Some(true) {
+        "failed"
+    } else {
+        "ok"
+    };
+    println!(
+        "[tx] slot {} {} {status} fee {} SOL, {} static accounts",
+        entry.slot,
+        entry.signature.as_deref().unwrap_or_default(),
+        format_sol(entry.fee.unwrap_or_default()),
+        entry.static_accounts.unwrap_or_default()
+    );
+}
@@ -0,0 +1,303 @@
+use clap::Parser;
+use common::Result;
+use common::cli::{RpcArgs, WsArgs};
+use common::client::build_nonblocking_client;
+use common::output::OutputArgs;
+use futures::StreamExt;
+use serde::Serialize;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::clock::Slot;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::BTreeMap;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use tokio::signal;
+use tokio::sync::mpsc;
+use tokio::time::{self, MissedTickBehavior};
+use tracing::warn;
+
+/// Watch new slots with `slotSubscribe` and with a `getSlot` polling loop
+/// side by side, then compare how early and how completely each saw them.
+///
+/// Neither side knows when a slot was really produced, so delays are
+/// relative: for every slot both saw, the one that saw it later is charged
+/// the difference. A slot counts as missed by a side when the other saw it
+/// and it did not; polling misses every slot that starts and ends between
+/// two polls, a subscription only what the connection drops. Polling runs
+/// at processed commitment, which is what slot notifications report, and
+/// each poll is one request against the endpoint's rate limit and credits.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    /// Seconds to compare for
+    #[arg(short, long, default_value_t = 60)]
+    duration: u64,
+
+    /// Milliseconds between polls; slots last about 400
+    #[arg(short, long, default_value_t = 400)]
+    poll_interval: u64,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct Comparison {
+    duration_secs: f64,
+    /// Slots seen by either side between the first and last slot both saw
+    slots: usize,
+    first_slot: Option<Slot>,
+    last_slot: Option<Slot>,
+    push: SideStats,
+    poll: SideStats,
+}
+
+#[derive(Serialize)]
+struct SideStats {
+    method: &'static str,
+    /// Notifications received, or requests sent
+    messages: u64,
+    errors: u64,
+    slots_seen: usize,
+    /// Slots the other side saw and this one did not
+    missed: usize,
+    /// Slots this side saw strictly before the other
+    first: usize,
+    /// Delay behind the other side over the slots both saw, in milliseconds
+    /// (zero when this side was first)
+    median_delay_ms: Option<f64>,
+    p90_delay_ms: Option<f64>,
+    max_delay_ms: Option<f64>,
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Push,
+    Poll,
+}
+
+/// When each side first saw a slot.
+#[derive(Default)]
+struct Sightings {
+    push: Option<Instant>,
+    poll: Option<Instant>,
+}
+
+/// A slot and when the poller got it back, or a failed poll.
+type Polled = Result<(Slot, Instant)>;
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let output = &args.output;
+    let client = PubsubClient::new(&args.ws.url(&args.rpc)).await?;
+    let (mut notifications, unsubscribe) = client.slot_subscribe().await?;
+
+    // Polled on its own task, so a slow response never holds up a
+    // notification being timestamped, nor the other way round.
+    let (polled_tx, mut polled) = mpsc::unbounded_channel();
+    let poller = tokio::spawn(poll(
+        build_nonblocking_client(
+            &args.rpc.rpc_url,
+            CommitmentConfig::processed(),
+            args.rpc.timeout(),
+        ),
+        Duration::from_millis(args.poll_interval.max(1)),
+        polled_tx,
+    ));
+
+    output.status(format_args!(
+        "Comparing slotSubscribe with getSlot every {} ms for {} s (Ctrl-C to stop early)",
+        args.poll_interval, args.duration
+    ));
+    let mut slots: BTreeMap<Slot, Sightings> = BTreeMap::new();
+    let (mut notified, mut requests, mut poll_errors, mut push_errors) = (0, 0, 0, 0);
+    let started = Instant::now();
+    let deadline = time::sleep(Duration::from_secs(args.duration));
+    tokio::pin!(deadline);
+    loop {
+        tokio::select! {
+            notification = notifications.next() => {
+                let Some(info) = notification else {
+                    push_errors += 1;
+                    warn!("the slot subscription closed; comparing what was seen");
+                    break;
+                };
+                notified += 1;
+                record(&mut slots, info.slot, Side::Push, Instant::now());
+            }
+            Some(result) = polled.recv() => {
+                requests += 1;
+                match result {
+                    Ok((slot, at)) => record(&mut slots, slot, Side::Poll, at),
+                    Err(err) => {
+                        poll_errors += 1;
+                        warn!("getSlot failed: {err}");
+                    }
+                }
+            }
+            _ = &mut deadline => break,
+            _ = signal::ctrl_c() => break,
+        }
+    }
+    let elapsed = started.elapsed();
+    poller.abort();
+    unsubscribe().await;
+    drop(notifications);
+    client.shutdown().await?;
+
+    let comparison = compare(
+        &slots,
+        elapsed,
+        (notified, push_errors),
+        (requests, poll_errors),
+    );
+    output.print(&comparison, || print_comparison(&comparison))
+}
+
+async fn poll(client: RpcClient, interval: Duration, polled: mpsc::UnboundedSender<Polled>) {
+    let mut ticks = time::interval(interval);
+    // A slow response delays the next poll instead of firing a burst to
+    // catch up.
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        let result = client
+            .get_slot()
+            .await
+            .map(|slot| (slot, Instant::now()))
+            .map_err(Into::into);
+        if polled.send(result).is_err() {
+            return;
+        }
+    }
+}
+
+fn record(slots: &mut BTreeMap<Slot, Sightings>, slot: Slot, side: Side, at: Instant) {
+    let sightings = slots.entry(slot).or_default();
+    let seen = match side {
+        Side::Push => &mut sightings.push,
+        Side::Poll => &mut sightings.poll,
+    };
+    seen.get_or_insert(at);
+}
+
+fn compare(
+    slots: &BTreeMap<Slot, Sightings>,
+    elapsed: Duration,
+    (notified, push_errors): (u64, u64),
+    (requests, poll_errors): (u64, u64),
+) -> Comparison {
+    // Only the stretch both sides were running for is compared, so a
+    // subscription that starts a moment before the first poll is not
+    // counted as the poll missing slots.
+    let both = |sightings: &&Sightings| sightings.push.is_some() && sightings.poll.is_some();
+    let first_slot = slots.iter().find(|(_, s)| both(s)).map(|(slot, _)| *slot);
+    let last_slot = slots
+        .iter()
+        .rev()
+        .find(|(_, s)| both(s))
+        .map(|(slot, _)| *slot);
+    let window: Vec<&Sightings> = match (first_slot, last_slot) {
+        (Some(first), Some(last)) => slots.range(first..=last).map(|(_, s)| s).collect(),
+        _ => Vec::new(),
+    };
+    Comparison {
+        duration_secs: elapsed.as_secs_f64(),
+        slots: window.len(),
+        first_slot,
+        last_slot,
+        push: side_stats(&window, Side::Push, notified, push_errors),
+        poll: side_stats(&window, Side::Poll, requests, poll_errors),
+    }
+}
+
+fn side_stats(window: &[&Sightings], side: Side, messages: u64, errors: u64) -> SideStats {
+    let pick = |sightings: &Sightings| match side {
+        Side::Push => (sightings.push, sightings.poll),
+        Side::Poll => (sightings.poll, sightings.push),
+    };
+    let (mut seen, mut missed, mut first) = (0, 0, 0);
+    let mut delays = Vec::new();
+    for sightings in window {
+        match pick(sightings) {
+            (Some(ours), Some(theirs)) => {
+                seen += 1;
+                if ours < theirs {
+                    first += 1;
+                }
+                delays.push(ours.saturating_duration_since(theirs).as_secs_f64() * 1000.0);
+            }
+            (Some(_), None) => seen += 1,
+            (None, Some(_)) => missed += 1,
+            (None, None) => {}
+        }
+    }
+    delays.sort_by(f64::total_cmp);
+    SideStats {
+        method: match side {
+            Side::Push => "slotSubscribe",
+            Side::Poll => "getSlot",
+        },
+        messages,
+        errors,
+        slots_seen: seen,
+        missed,
+        first,
+        median_delay_ms: percentile(&delays, 50.0),
+        p90_delay_ms: percentile(&delays, 90.0),
+        max_delay_ms: delays.last().copied(),
+    }
+}
+
+/// Nearest-rank percentile of sorted values.
+fn percentile(sorted: &[f64], percent: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = (percent / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+fn print_comparison(comparison: &Comparison) {
+    match (comparison.first_slot, comparison.last_slot) {
+        (Some(first), Some(last)) => println!(
+            "{} slots from {first} to {last} in {:.1} s",
+            comparison.slots, comparison.duration_secs
+        ),
+        _ => {
+            println!("The two sides never saw the same slot; nothing to compare");
+            return;
+        }
+    }
+    println!();
+    println!(
+        "{:<14}  {:>8}  {:>6}  {:>6}  {:>7}  {:>6}  {:>10}  {:>10}  {:>10}",
+        "Method", "Messages", "Errors", "Seen", "Missed", "First", "Median ms", "p90 ms", "Max ms"
+    );
+    let ms = |value: Option<f64>| value.map_or("-".to_string(), |value| format!("{value:.1}"));
+    for side in [&comparison.push, &comparison.poll] {
+        println!(
+            "{:<14}  {:>8}  {:>6}  {:>6}  {:>7}  {:>6}  {:>10}  {:>10}  {:>10}",
+            side.method,
+            side.messages,
+            side.errors,
+            side.slots_seen,
+            side.missed,
+            side.first,
+            ms(side.median_delay_ms),
+            ms(side.p90_delay_ms),
+            ms(side.max_delay_ms)
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use clap::Parser;
+use common::anchor::Idl;
+use common::cli::{AddressArg, RpcArgs, WsArgs};
+use common::output::{OutputArgs, RecordWriter};
+use common::units::format_change;
+use common::{ExampleError, Result};
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::Value;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Snapshot an account's raw data, then print which byte ranges change on
+/// every `accountSubscribe` notification. With an Anchor IDL describing the
+/// account's Borsh layout, the changed fields are listed by name as well,
+/// which helps working out what a program instruction does to its state.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    /// Account to watch: an address (base58), a `.sol` domain, a keypair
+    /// file or `wallet:mint`
+    #[arg(short, long)]
+    account: AddressArg,
+
+    /// Anchor IDL JSON file used to decode the account into named fields
+    #[arg(long)]
+    idl: Option<PathBuf>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct AccountDiff {
+    slot: u64,
+    lamports: u64,
+    lamports_change: Option<String>,
+    data_len: usize,
+    /// Contiguous runs of changed bytes
+    ranges: Vec<ByteRange>,
+    /// Account type the IDL identified the data as
+    account_type: Option<String>,
+    fields: Vec<FieldChange>,
+}
+
+#[derive(Serialize)]
+struct ByteRange {
+    offset: usize,
+    /// Hex; shorter than `new` when the account grew, and the other way
+    /// round when it shrank
+    old: String,
+    new: String,
+}
+
+#[derive(Serialize)]
+struct FieldChange {
+    /// Dotted path into the decoded account, e.g. `config.fees[2]`
+    path: String,
+    old: Value,
+    new: Value,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let idl = args.idl.as_ref().map(Idl::from_file).transpose()?;
+    // `AddressArg` resolves with the blocking client; it is one call.
+    let account = args.account.resolve(&args.rpc.client())?;
+    let connection = args.rpc.nonblocking_client();
+    let snapshot = connection
+        .get_account_with_commitment(&account, args.rpc.commitment_config())
+        .await?
+        .value
+        .ok_or(ExampleError::AccountNotFound(account))?;
+    args.output.status(format_args!(
+        "Snapshot of {account}: {} bytes, owned by {}",
+        snapshot.data.len(),
+        snapshot.owner
+    ));
+    let mut previous = snapshot;
+    let ws_url = args.ws.url(&args.rpc);
+    let mut records = args.output.records();
+
+    loop {
+        match watch(
+            &ws_url,
+            &args,
+            &account,
+            idl.as_ref(),
+            &mut previous,
+            &mut records,
+        )
+        .await
+        {
+            Ok(Stop::Shutdown) => return Ok(()),
+            Ok(Stop::Disconnected) => warn!("websocket closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        warn!("resubscribing in {RECONNECT_DELAY:?}");
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Runs one subscription until the socket drops or Ctrl-C is pressed.
+async fn watch(
+    ws_url: &str,
+    args: &Args,
+    address: &Pubkey,
+    idl: Option<&Idl>,
+    previous: &mut Account,
+    records: &mut RecordWriter,
+) -> Result<Stop> {
+    let client = PubsubClient::new(ws_url).await?;
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(args.rpc.commitment_config()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let (mut notifications, unsubscribe) = client.account_subscribe(address, Some(config)).await?;
+    args.output
+        .status(format_args!("Watching {address} (Ctrl-C to stop)"));
+
+    loop {
+        tokio::select! {
+            notification = notifications.next() => {
+                let Some(response) = notification else {
+                    return Ok(Stop::Disconnected);
+                };
+                let account: Account = response.value.decode().ok_or_else(|| {
+                    ExampleError::Parse(format!("account data for {address}"))
+                })?;
+                let diff = diff(response.context.slot, previous, &account, idl);
+                records.write(&diff, || print_diff(&diff))?;
+                *previous = account;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                unsubscribe().await;
+                drop(notifications);
+                client.shutdown().await?;
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}
+
+fn diff(slot: u64, old: &Account, new: &Account, idl: Option<&Idl>) -> AccountDiff {
+    let mut diff = AccountDiff {
+        slot,
+        lamports: new.lamports,
+        lamports_change: (old.lamports != new.lamports)
+            .then(|| format_change(old.lamports, new.lamports, 9)),
+        data_len: new.data.len(),
+        ranges: byte_ranges(&old.data, &new.data),
+        account_type: None,
+        fields: Vec::new(),
+    };
+    if let Some(idl) = idl {
+        // Either side failing to decode (e.g. mid-migration) just leaves the
+        // byte ranges to go on.
+        if let (Ok((name, old_value)), Ok((_, new_value))) =
+            (idl.decode_account(&old.data), idl.decode_account(&new.data))
+        {
+            diff.account_type = Some(name.to_string());
+            field_changes(String::new(), &old_value, &new_value, &mut diff.fields);
+        }
+    }
+    diff
+}
+
+/// Runs of differing bytes. Bytes past the end of the shorter buffer count
+/// as one final run.
+fn byte_ranges(old: &[u8], new: &[u8]) -> Vec<ByteRange> {
+    let common_len = old.len().min(new.len());
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for offset in 0..=common_len {
+        let differs = offset < common_len && old[offset] != new[offset];
+        match (differs, start) {
+            (true, None) => start = Some(offset),
+            (false, Some(from)) => {
+                ranges.push(ByteRange {
+                    offset: from,
+                    old: hex::encode(&old[from..offset]),
+                    new: hex::encode(&new[from..offset]),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if old.len() != new.len() {
+        ranges.push(ByteRange {
+            offset: common_len,
+            old: hex::encode(&old[common_len..]),
+            new: hex::encode(&new[common_len..]),
+        });
+    }
+    ranges
+}
+
+/// Walks both decoded values side by side and records the leaves that
+/// differ; containers whose shape changed are reported whole.
+fn field_changes(path: String, old: &Value, new: &Value, changes: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old_fields), Value::Object(new_fields))
+            if old_fields.keys().eq(new_fields.keys()) =>
+        {
+            for (name, old_field) in old_fields {
+                let path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{path}.{name}")
+                };
+                field_changes(path, old_field, &new_fields[name], changes);
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items))
+            if old_items.len() == new_items.len() =>
+        {
+            for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                field_changes(format!("{path}[{index}]"), old_item, new_item, changes);
+            }
+        }
+        _ if old != new => changes.push(FieldChange {
+            path,
+            old: old.clone(),
+            new: new.clone(),
+        }),
+        _ => {}
+    }
+}
+
+fn print_diff(diff: &AccountDiff) {
+    let lamports = match &diff.lamports_change {
+        Some(change) => format!(", lamports {change} SOL"),
+        None => String::new(),
+    };
+    if diff.ranges.is_empty() {
+        println!("slot {}: data unchanged{lamports}", diff.slot);
+        return;
+    }
+    let changed: usize = diff
+        .ranges
+        .iter()
+        .map(|range| range.old.len().max(range.new.len()) / 2)
+        .sum();
+    println!(
+        "slot {}: {changed} of {} bytes changed in {} range{}{lamports}",
+        diff.slot,
+        diff.data_len,
+        diff.ranges.len(),
+        if diff.ranges.len() == 1 { "" } else { "s" }
+    );
+    for range in &diff.ranges {
+        println!(
+            "  @{:<6} {} -> {}",
+            range.offset,
+            or_empty(&range.old),
+            or_empty(&range.new)
+        );
+    }
+    if let Some(account_type) = &diff.account_type {
+        println!("  {account_type}:");
+        for field in &diff.fields {
+            println!("    {}: {} -> {}", field.path, field.old, field.new);
+        }
+    }
+}
+
+fn or_empty(hex: &str) -> &str {
+    if hex.is_empty() { "(none)" } else { hex }
+}
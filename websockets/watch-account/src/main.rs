@@ -0,0 +1,170 @@
+use clap::Parser;
+use common::cli::{AddressArg, MetricsArgs, RpcArgs, WsArgs};
+use common::output::{OutputArgs, RecordWriter};
+use common::token::{self, TokenAccountInfo};
+use common::units::{format_change, format_token_amount};
+use common::{ExampleError, Result, metrics};
+use futures::StreamExt;
+use serde::Serialize;
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::account::Account;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Stream balance changes of an SPL token account over a websocket
+/// `accountSubscribe` subscription.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    #[command(flatten)]
+    metrics: MetricsArgs,
+
+    /// Token account to watch: an address (base58), a `.sol` domain, a
+    /// keypair file or `wallet:mint`
+    #[arg(short, long)]
+    account: AddressArg,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct BalanceUpdate {
+    slot: u64,
+    /// Balance in base units
+    amount: u64,
+    balance: String,
+    /// Signed change since the previous notification, if any
+    change: Option<String>,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    if args.metrics.serve(&args.output)? {
+        tokio::spawn(metrics::track_node_slot(args.rpc.nonblocking_client()));
+    }
+    // The address and the mint are looked up once, with the blocking client.
+    let client = args.rpc.client();
+    let account = args.account.resolve(&client)?;
+    let decimals = fetch_decimals(&client, &account)?;
+    let ws_url = args.ws.url(&args.rpc);
+    let mut last_amount = None;
+    let mut records = args.output.records();
+
+    loop {
+        match watch(
+            &ws_url,
+            &args,
+            &account,
+            decimals,
+            &mut last_amount,
+            &mut records,
+        )
+        .await
+        {
+            Ok(Stop::Shutdown) => return Ok(()),
+            Ok(Stop::Disconnected) => warn!("websocket closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        warn!("resubscribing in {RECONNECT_DELAY:?}");
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Decimals of the account's mint, under either token program; Token-2022
+/// accounts with extensions are longer than the legacy layout.
+fn fetch_decimals(client: &RpcClient, address: &Pubkey) -> Result<u8> {
+    let account = client
+        .get_account_with_commitment(address, client.commitment())?
+        .value
+        .ok_or(ExampleError::AccountNotFound(*address))?;
+    let info = TokenAccountInfo::unpack(address, &account)?;
+    Ok(token::fetch_mint(client, &info.account.mint)?.decimals())
+}
+
+/// Runs one subscription until the socket drops or Ctrl-C is pressed.
+async fn watch(
+    ws_url: &str,
+    args: &Args,
+    address: &Pubkey,
+    decimals: u8,
+    last_amount: &mut Option<u64>,
+    records: &mut RecordWriter,
+) -> Result<Stop> {
+    let client = PubsubClient::new(ws_url).await?;
+    let config = RpcAccountInfoConfig {
+        encoding: Some(UiAccountEncoding::Base64),
+        commitment: Some(args.rpc.commitment_config()),
+        ..RpcAccountInfoConfig::default()
+    };
+    let (mut notifications, unsubscribe) = client.account_subscribe(address, Some(config)).await?;
+    args.output
+        .status(format_args!("Watching {address} (Ctrl-C to stop)"));
+
+    loop {
+        tokio::select! {
+            notification = notifications.next() => {
+                let Some(response) = notification else {
+                    return Ok(Stop::Disconnected);
+                };
+                metrics::notification("accountSubscribe", response.context.slot);
+                let account: Account = response.value.decode().ok_or_else(|| {
+                    ExampleError::Parse(format!("account data for {address}"))
+                })?;
+                let amount = TokenAccountInfo::unpack(address, &account)?.account.amount;
+                let slot = response.context.slot;
+                let previous = last_amount.replace(amount);
+                let update = BalanceUpdate {
+                    slot,
+                    amount,
+                    balance: format_token_amount(amount, decimals),
+                    change: previous.map(|previous| format_change(previous, amount, decimals)),
+                };
+                records.write(&update, || print_change(&update))?;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                unsubscribe().await;
+                drop(notifications);
+                client.shutdown().await?;
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}
+
+fn print_change(update: &BalanceUpdate) {
+    match &update.change {
+        Some(change) => println!(
+            "slot {}: balance {} ({change})",
+            update.slot, update.balance
+        ),
+        None => println!("slot {}: balance {}", update.slot, update.balance),
+    }
+}
@@ -0,0 +1,250 @@
+use clap::Parser;
+use common::cli::{MetricsArgs, RpcArgs, WsArgs};
+use common::output::{OutputArgs, RecordWriter};
+use common::units::{format_change, format_sol};
+use common::{ExampleError, Result, decode, metrics};
+use futures::StreamExt;
+use serde::Serialize;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::rpc_config::{RpcBlockSubscribeConfig, RpcBlockSubscribeFilter};
+use solana_client::rpc_response::RpcBlockUpdate;
+use solana_sdk::pubkey::Pubkey;
+use solana_transaction_status_client_types::{
+    EncodedTransaction, EncodedTransactionWithStatusMeta, TransactionDetails,
+};
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Stream confirmed blocks via `blockSubscribe`, keeping only transactions
+/// that mention an address or program, and print their balance changes.
+///
+/// `blockSubscribe` is not served by every node; on QuickNode it needs a
+/// full node with the method enabled for the endpoint.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    #[command(flatten)]
+    metrics: MetricsArgs,
+
+    /// Account or program the transactions must mention
+    #[arg(short, long)]
+    address: Pubkey,
+
+    /// Include failed transactions
+    #[arg(long)]
+    include_failed: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct BlockTransaction {
+    slot: u64,
+    signature: String,
+    error: Option<String>,
+    fee: u64,
+    sol_changes: Vec<SolChange>,
+    token_changes: Vec<TokenChange>,
+}
+
+#[derive(Serialize)]
+struct SolChange {
+    account: String,
+    /// Signed change in SOL
+    change: String,
+}
+
+#[derive(Serialize)]
+struct TokenChange {
+    account: String,
+    mint: String,
+    change: String,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    if args.metrics.serve(&args.output)? {
+        tokio::spawn(metrics::track_node_slot(args.rpc.nonblocking_client()));
+    }
+    let ws_url = args.ws.url(&args.rpc);
+    let mut records = args.output.records();
+    loop {
+        match watch(&ws_url, &args, &mut records).await {
+            Ok(Stop::Shutdown) => return Ok(()),
+            Ok(Stop::Disconnected) => warn!("websocket closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        warn!("resubscribing in {RECONNECT_DELAY:?}");
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+async fn watch(ws_url: &str, args: &Args, records: &mut RecordWriter) -> Result<Stop> {
+    let client = PubsubClient::new(ws_url).await?;
+    // `accounts` details carry the full account list (lookup table entries
+    // included) and the balance metadata, without the instruction payloads.
+    let (mut notifications, unsubscribe) = client
+        .block_subscribe(
+            RpcBlockSubscribeFilter::MentionsAccountOrProgram(args.address.to_string()),
+            Some(RpcBlockSubscribeConfig {
+                commitment: Some(args.rpc.commitment_config()),
+                transaction_details: Some(TransactionDetails::Accounts),
+                show_rewards: Some(false),
+                max_supported_transaction_version: Some(0),
+                ..RpcBlockSubscribeConfig::default()
+            }),
+        )
+        .await?;
+    args.output.status(format_args!(
+        "Watching blocks for transactions mentioning {} (Ctrl-C to stop)",
+        args.address
+    ));
+
+    loop {
+        tokio::select! {
+            notification = notifications.next() => {
+                let Some(response) = notification else {
+                    return Ok(Stop::Disconnected);
+                };
+                metrics::notification("blockSubscribe", response.value.slot);
+                write_block(&response.value, args, records)?;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                unsubscribe().await;
+                drop(notifications);
+                client.shutdown().await?;
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}
+
+fn write_block(update: &RpcBlockUpdate, args: &Args, records: &mut RecordWriter) -> Result<()> {
+    if let Some(err) = &update.err {
+        warn!("slot {}: {err}", update.slot);
+        return Ok(());
+    }
+    let Some(block) = &update.block else {
+        return Ok(());
+    };
+    for transaction in block.transactions.iter().flatten() {
+        let failed = transaction
+            .meta
+            .as_ref()
+            .is_some_and(|meta| meta.err.is_some());
+        if failed && !args.include_failed {
+            continue;
+        }
+        if let Some(entry) = block_transaction(update.slot, transaction)? {
+            records.write(&entry, || print_transaction(&entry, &args.address))?;
+        }
+    }
+    Ok(())
+}
+
+fn block_transaction(
+    slot: u64,
+    transaction: &EncodedTransactionWithStatusMeta,
+) -> Result<Option<BlockTransaction>> {
+    let EncodedTransaction::Accounts(accounts) = &transaction.transaction else {
+        return Err(ExampleError::Parse(
+            "expected a transaction with `accounts` details".to_string(),
+        ));
+    };
+    let Some(meta) = &transaction.meta else {
+        return Ok(None);
+    };
+    let key = |index: usize| {
+        accounts
+            .account_keys
+            .get(index)
+            .map_or("?", |account| account.pubkey.as_str())
+            .to_string()
+    };
+
+    let sol_changes = meta
+        .pre_balances
+        .iter()
+        .zip(&meta.post_balances)
+        .enumerate()
+        .filter(|(_, (pre, post))| pre != post)
+        .map(|(index, (pre, post))| SolChange {
+            account: key(index),
+            change: format_change(*pre, *post, 9),
+        })
+        .collect();
+    let token_changes = decode::token_balance_changes(meta)
+        .into_iter()
+        .map(|change| TokenChange {
+            account: key(change.account_index),
+            mint: change.mint.to_string(),
+            change: format_change(change.before, change.after, change.decimals),
+        })
+        .collect();
+
+    Ok(Some(BlockTransaction {
+        slot,
+        signature: accounts
+            .signatures
+            .first()
+            .map_or("?", String::as_str)
+            .to_string(),
+        error: meta.err.as_ref().map(ToString::to_string),
+        fee: meta.fee,
+        sol_changes,
+        token_changes,
+    }))
+}
+
+fn print_transaction(entry: &BlockTransaction, address: &Pubkey) {
+    let (slot, signature) = (entry.slot, &entry.signature);
+    match &entry.error {
+        Some(err) => println!("slot {slot}  {signature}  failed: {err}"),
+        None => println!("slot {slot}  {signature}"),
+    }
+    println!("  fee {} SOL", format_sol(entry.fee));
+
+    let address = address.to_string();
+    let marker = |pubkey: &str| if pubkey == address { "*" } else { " " };
+    for change in &entry.sol_changes {
+        println!(
+            " {}{:<44}  {:>20} SOL",
+            marker(&change.account),
+            change.account,
+            change.change
+        );
+    }
+    for change in &entry.token_changes {
+        println!(
+            " {}{:<44}  {:>20} of {}",
+            marker(&change.account),
+            change.account,
+            change.change,
+            change.mint
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use clap::Parser;
+use common::cli::{MetricsArgs, RpcArgs, WsArgs};
+use common::logs::{self, LogLine};
+use common::output::{OutputArgs, RecordWriter};
+use common::{ExampleError, Result, at_least_confirmed, metrics};
+use futures::StreamExt;
+use serde::Serialize;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_client::rpc_response::RpcLogsResponse;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Stream transactions that mention a program via `logsSubscribe` and print
+/// the log lines that program emitted.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    #[command(flatten)]
+    metrics: MetricsArgs,
+
+    /// Program to watch; defaults to the SPL Token program
+    #[arg(
+        short,
+        long,
+        default_value = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+    )]
+    program: Pubkey,
+
+    /// Also fetch each transaction over HTTP and print its slot and fee
+    #[arg(short, long)]
+    fetch: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct ProgramLogs {
+    signature: String,
+    /// Slot the notification was sent for
+    slot: u64,
+    error: Option<String>,
+    /// Lines the program emitted, e.g. `log: ...` or `consumed 1234 compute units`
+    logs: Vec<String>,
+    /// Only filled in with `--fetch`
+    fee: Option<u64>,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    if args.metrics.serve(&args.output)? {
+        tokio::spawn(metrics::track_node_slot(args.rpc.nonblocking_client()));
+    }
+    let ws_url = args.ws.url(&args.rpc);
+    let connection = args.rpc.nonblocking_client();
+    let mut records = args.output.records();
+
+    loop {
+        match watch(&ws_url, &args, &connection, &mut records).await {
+            Ok(Stop::Shutdown) => return Ok(()),
+            Ok(Stop::Disconnected) => warn!("websocket closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        warn!("resubscribing in {RECONNECT_DELAY:?}");
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+async fn watch(
+    ws_url: &str,
+    args: &Args,
+    connection: &RpcClient,
+    records: &mut RecordWriter,
+) -> Result<Stop> {
+    let client = PubsubClient::new(ws_url).await?;
+    let (mut notifications, unsubscribe) = client
+        .logs_subscribe(
+            RpcTransactionLogsFilter::Mentions(vec![args.program.to_string()]),
+            RpcTransactionLogsConfig {
+                commitment: Some(args.rpc.commitment_config()),
+            },
+        )
+        .await?;
+    args.output.status(format_args!(
+        "Watching logs of {} (Ctrl-C to stop)",
+        args.program
+    ));
+
+    loop {
+        tokio::select! {
+            notification = notifications.next() => {
+                let Some(response) = notification else {
+                    return Ok(Stop::Disconnected);
+                };
+                metrics::notification("logsSubscribe", response.context.slot);
+                let mut entry = program_logs(response.context.slot, &response.value, &args.program);
+                if args.fetch {
+                    match fetch_transaction(connection, &response.value.signature).await {
+                        Ok((slot, fee)) => (entry.slot, entry.fee) = (slot, fee),
+                        Err(err) => warn!("could not fetch {}: {err}", entry.signature),
+                    }
+                }
+                records.write(&entry, || print_logs(&entry))?;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                unsubscribe().await;
+                drop(notifications);
+                client.shutdown().await?;
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}
+
+fn program_logs(slot: u64, response: &RpcLogsResponse, program: &Pubkey) -> ProgramLogs {
+    let logs = logs::lines_for_program(&response.logs, program)
+        .filter_map(|line| match line {
+            LogLine::Log(message) => Some(format!("log: {message}")),
+            LogLine::Data(data) => Some(format!("data: {data}")),
+            LogLine::Consumed { units, .. } => Some(format!("consumed {units} compute units")),
+            LogLine::Failed { reason, .. } => Some(format!("failed: {reason}")),
+            _ => None,
+        })
+        .collect();
+    ProgramLogs {
+        signature: response.signature.clone(),
+        slot,
+        error: response.err.as_ref().map(ToString::to_string),
+        logs,
+        fee: None,
+    }
+}
+
+fn print_logs(entry: &ProgramLogs) {
+    match &entry.error {
+        Some(err) => println!("{} failed: {err}", entry.signature),
+        None => println!("{}", entry.signature),
+    }
+    for line in &entry.logs {
+        println!("  {line}");
+    }
+    if let Some(fee) = entry.fee {
+        println!("  slot {} fee {fee} lamports", entry.slot);
+    }
+}
+
+/// The slot the transaction landed in and its fee.
+async fn fetch_transaction(connection: &RpcClient, signature: &str) -> Result<(u64, Option<u64>)> {
+    let signature: Signature = signature
+        .parse()
+        .map_err(|_| ExampleError::Parse(format!("signature `{signature}`")))?;
+    let transaction = connection
+        .get_transaction_with_config(
+            &signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::JsonParsed),
+                commitment: Some(at_least_confirmed(connection.commitment())),
+                max_supported_transaction_version: Some(0),
+            },
+        )
+        .await?;
+    let fee = transaction.transaction.meta.as_ref().map(|meta| meta.fee);
+    Ok((transaction.slot, fee))
+}
@@ -0,0 +1,115 @@
+use clap::Parser;
+use common::cli::{RpcArgs, StreamArgs, WsArgs};
+use common::output::{OutputArgs, RecordWriter};
+use common::stream::StreamSource;
+use common::{Result, explorer};
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::{signal, time};
+use tracing::{error, warn};
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(3);
+
+/// Print transactions that mention an address as they land, from the best
+/// source the endpoint offers: Yellowstone gRPC when `--grpc-url` is given
+/// and the add-on is enabled, else a websocket `logsSubscribe`, else polling
+/// getSignaturesForAddress. `--source` pins one of them.
+#[derive(Parser)]
+#[command(version, about)]
+struct Args {
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    ws: WsArgs,
+
+    #[command(flatten)]
+    stream: StreamArgs,
+
+    /// Wallet, account or program the transactions must mention
+    #[arg(short, long)]
+    address: Pubkey,
+
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Serialize)]
+struct TransactionEntry {
+    slot: u64,
+    signature: String,
+    status: &'static str,
+    /// The source that delivered it
+    source: &'static str,
+}
+
+enum Stop {
+    Shutdown,
+    Disconnected,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    common::report(run(common::config::parse()).await)
+}
+
+async fn run(args: Args) -> Result<()> {
+    let mut records = args.output.records();
+    loop {
+        // Each reconnect goes through the fallback again, so a gRPC stream
+        // that comes back is picked up again.
+        match watch(&args, &mut records).await {
+            Ok(Stop::Shutdown) => return Ok(()),
+            Ok(Stop::Disconnected) => warn!("stream closed by the server"),
+            Err(err) => error!("{err}"),
+        }
+        warn!("reconnecting in {RECONNECT_DELAY:?}");
+        tokio::select! {
+            _ = time::sleep(RECONNECT_DELAY) => {}
+            _ = signal::ctrl_c() => return Ok(()),
+        }
+    }
+}
+
+/// Streams until the source ends or Ctrl-C is pressed.
+async fn watch(args: &Args, records: &mut RecordWriter) -> Result<Stop> {
+    let mut source: Box<dyn StreamSource> = args
+        .stream
+        .connect(&args.rpc, &args.ws, &args.address)
+        .await?;
+    args.output.status(format_args!(
+        "Watching {} over {} (Ctrl-C to stop)",
+        args.address,
+        source.name()
+    ));
+
+    loop {
+        tokio::select! {
+            event = source.next() => {
+                let Some(event) = event? else {
+                    return Ok(Stop::Disconnected);
+                };
+                let entry = TransactionEntry {
+                    slot: event.slot,
+                    signature: event.signature.to_string(),
+                    status: if event.failed { "failed" } else { "ok" },
+                    source: source.name(),
+                };
+                records.write(&entry, || {
+                    println!(
+                        "slot {:<10} {:<6} {}",
+                        entry.slot,
+                        entry.status,
+                        explorer::transaction_url(&event.signature, &args.rpc.rpc_url)
+                    );
+                })?;
+            }
+            _ = signal::ctrl_c() => {
+                args.output.status("Shutting down");
+                return Ok(Stop::Shutdown);
+            }
+        }
+    }
+}